@@ -1,7 +1,9 @@
 use std::collections::BTreeMap;
 
+use crate::decimal::Decimal;
 use crate::error::{ValidationError, ValidationField};
 use crate::json::JsonValue;
+use crate::time;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Value {
@@ -10,6 +12,14 @@ pub enum Value {
     Int(i64),
     Float(f64),
     String(String),
+    Decimal(Decimal),
+    Instant(i64),
+    Date(i64),
+    Duration(i64),
+    DateTime {
+        unix_ms: i64,
+        offset_minutes: i32,
+    },
     List(Vec<Value>),
     Map(BTreeMap<String, Value>),
     Struct {
@@ -30,6 +40,11 @@ pub enum Type {
     Int,
     Float,
     String,
+    Decimal,
+    Instant,
+    Date,
+    Duration,
+    DateTime,
     Option(Box<Type>),
     List(Box<Type>),
     Map(Box<Type>),
@@ -69,6 +84,14 @@ pub fn encode_value(value: &Value) -> JsonValue {
         Value::Int(v) => JsonValue::Number(*v as f64),
         Value::Float(v) => JsonValue::Number(*v),
         Value::String(v) => JsonValue::String(v.clone()),
+        Value::Decimal(value) => JsonValue::String(value.to_string()),
+        Value::Instant(ms) => JsonValue::String(time::format_instant(*ms)),
+        Value::Date(days) => JsonValue::String(time::format_date(*days)),
+        Value::Duration(ms) => JsonValue::String(time::format_duration(*ms)),
+        Value::DateTime {
+            unix_ms,
+            offset_minutes,
+        } => JsonValue::String(time::format_datetime(*unix_ms, *offset_minutes)),
         Value::List(items) => JsonValue::Array(items.iter().map(encode_value).collect()),
         Value::Map(map) => {
             let mut out = BTreeMap::new();
@@ -169,6 +192,52 @@ impl DecodeCtx {
                     None
                 }
             },
+            Type::Decimal => match value {
+                JsonValue::Number(v) => match Decimal::from_json_number(*v) {
+                    Ok(decimal) => Some(Value::Decimal(decimal)),
+                    Err(message) => {
+                        self.push_error(path, "invalid_value", &message);
+                        None
+                    }
+                },
+                JsonValue::String(_) => self
+                    .decode_text(value, path, "expected decimal", Decimal::parse)
+                    .map(Value::Decimal),
+                _ => {
+                    self.push_error(path, "invalid_type", "expected decimal");
+                    None
+                }
+            },
+            Type::Instant => self
+                .decode_text(
+                    value,
+                    path,
+                    "expected RFC 3339 date-time",
+                    time::parse_instant,
+                )
+                .map(Value::Instant),
+            Type::Date => self
+                .decode_text(value, path, "expected RFC 3339 full-date", time::parse_date)
+                .map(Value::Date),
+            Type::Duration => self
+                .decode_text(
+                    value,
+                    path,
+                    "expected ISO 8601 duration",
+                    time::parse_duration,
+                )
+                .map(Value::Duration),
+            Type::DateTime => self
+                .decode_text(
+                    value,
+                    path,
+                    "expected RFC 3339 date-time",
+                    time::parse_datetime,
+                )
+                .map(|(unix_ms, offset_minutes)| Value::DateTime {
+                    unix_ms,
+                    offset_minutes,
+                }),
             Type::Option(inner) => match value {
                 JsonValue::Null => Some(Value::Null),
                 other => self.decode(other, inner, path),
@@ -210,6 +279,26 @@ impl DecodeCtx {
         }
     }
 
+    fn decode_text<T>(
+        &mut self,
+        value: &JsonValue,
+        path: &Path,
+        expected: &str,
+        parse: fn(&str) -> Result<T, String>,
+    ) -> Option<T> {
+        let JsonValue::String(text) = value else {
+            self.push_error(path, "invalid_type", "expected string");
+            return None;
+        };
+        match parse(text) {
+            Ok(parsed) => Some(parsed),
+            Err(_) => {
+                self.push_error(path, "invalid_value", expected);
+                None
+            }
+        }
+    }
+
     fn decode_struct(&mut self, value: &JsonValue, ty: &StructType, path: &Path) -> Option<Value> {
         let obj = match value {
             JsonValue::Object(map) => map,
@@ -0,0 +1,346 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Largest number of fractional digits a `Decimal` keeps.
+pub const MAX_SCALE: u32 = 28;
+
+/// Minimum fractional digits kept by division before trailing zeros are trimmed.
+const DIV_SCALE: u32 = 18;
+
+/// Significant digits an `f64` always carries exactly (`f64::DIGITS`).
+const JSON_NUMBER_DIGITS: usize = f64::DIGITS as usize;
+
+/// Exact base-10 number: `units / 10^scale`.
+#[derive(Clone, Copy, Debug)]
+pub struct Decimal {
+    units: i128,
+    scale: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RoundingMode {
+    HalfEven,
+    HalfUp,
+    HalfDown,
+    Up,
+    Down,
+    Ceiling,
+    Floor,
+}
+
+impl RoundingMode {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "half_even" => Ok(Self::HalfEven),
+            "half_up" => Ok(Self::HalfUp),
+            "half_down" => Ok(Self::HalfDown),
+            "up" => Ok(Self::Up),
+            "down" => Ok(Self::Down),
+            "ceiling" => Ok(Self::Ceiling),
+            "floor" => Ok(Self::Floor),
+            _ => Err(format!(
+                "unknown rounding mode `{name}`; expected half_even, half_up, half_down, up, down, ceiling or floor"
+            )),
+        }
+    }
+}
+
+impl Decimal {
+    pub fn new(units: i128, scale: u32) -> Result<Self, String> {
+        if scale > MAX_SCALE {
+            return Err(format!("decimal scale {scale} exceeds {MAX_SCALE}"));
+        }
+        Ok(Self { units, scale })
+    }
+
+    pub fn from_i64(value: i64) -> Self {
+        Self {
+            units: i128::from(value),
+            scale: 0,
+        }
+    }
+
+    /// Converts through the shortest text that round-trips the float, so
+    /// `0.1` becomes exactly `0.1`.
+    pub fn from_f64(value: f64) -> Result<Self, String> {
+        if !value.is_finite() {
+            return Err("decimal cannot represent NaN or infinity".to_string());
+        }
+        Self::parse(&format!("{value}"))
+    }
+
+    /// Converts a number read from JSON. JSON numbers arrive as `f64`, so
+    /// ones with more than 15 significant digits may already have lost
+    /// digits; they are rejected and must be sent as strings instead.
+    pub fn from_json_number(value: f64) -> Result<Self, String> {
+        if !value.is_finite() {
+            return Err("decimal cannot represent NaN or infinity".to_string());
+        }
+        let text = format!("{value}");
+        let digits: String = text
+            .bytes()
+            .filter(u8::is_ascii_digit)
+            .map(char::from)
+            .collect();
+        let significant = digits.trim_start_matches('0').trim_end_matches('0').len();
+        if significant > JSON_NUMBER_DIGITS {
+            return Err(format!(
+                "JSON number has more than {JSON_NUMBER_DIGITS} significant digits; send the decimal as a string"
+            ));
+        }
+        Self::parse(&text)
+    }
+
+    /// Parses `[+-]digits[.digits][e[+-]digits]`, where either side of the
+    /// point may be empty but not both (`12.50`, `.5`, `1e2`, `-2.5E-3`).
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        let invalid = || {
+            format!(
+                "invalid decimal `{text}`; expected digits with an optional sign, fraction and exponent such as `-12.50`, `.5` or `1e2`"
+            )
+        };
+        let (negative, rest) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, text.strip_prefix('+').unwrap_or(text)),
+        };
+        let (mantissa, exponent) = match rest.split_once(['e', 'E']) {
+            Some((mantissa, exponent)) => {
+                let digits = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+                if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                    return Err(invalid());
+                }
+                let exponent: i64 = exponent
+                    .parse()
+                    .map_err(|_| format!("decimal `{text}` is out of range"))?;
+                (mantissa, exponent)
+            }
+            None => (rest, 0),
+        };
+        let (whole, frac) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        if (whole.is_empty() && frac.is_empty())
+            || !whole.bytes().all(|b| b.is_ascii_digit())
+            || !frac.bytes().all(|b| b.is_ascii_digit())
+            || (mantissa.contains('.') && frac.is_empty())
+        {
+            return Err(invalid());
+        }
+        let out_of_range = || format!("decimal `{text}` is out of range");
+        let mut units: i128 = 0;
+        for byte in whole.bytes().chain(frac.bytes()) {
+            units = units
+                .checked_mul(10)
+                .and_then(|value| value.checked_add(i128::from(byte - b'0')))
+                .ok_or_else(out_of_range)?;
+        }
+        let mut scale = (frac.len() as i64)
+            .checked_sub(exponent)
+            .ok_or_else(out_of_range)?;
+        if scale < 0 {
+            let factor = u32::try_from(-scale)
+                .ok()
+                .and_then(|power| 10i128.checked_pow(power))
+                .ok_or_else(out_of_range)?;
+            units = units.checked_mul(factor).ok_or_else(out_of_range)?;
+            scale = 0;
+        }
+        if scale > i64::from(MAX_SCALE) {
+            return Err(format!(
+                "decimal `{text}` has more than {MAX_SCALE} fractional digits"
+            ));
+        }
+        Ok(Self {
+            units: if negative { -units } else { units },
+            scale: scale as u32,
+        })
+    }
+
+    pub fn units(self) -> i128 {
+        self.units
+    }
+
+    pub fn scale(self) -> u32 {
+        self.scale
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.units == 0
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.to_string().parse().unwrap_or(f64::NAN)
+    }
+
+    pub fn checked_add(self, other: Self) -> Result<Self, String> {
+        let scale = self.scale.max(other.scale);
+        let left = self.rescaled_units(scale)?;
+        let right = other.rescaled_units(scale)?;
+        let units = left.checked_add(right).ok_or_else(overflow)?;
+        Ok(Self { units, scale })
+    }
+
+    pub fn checked_sub(self, other: Self) -> Result<Self, String> {
+        self.checked_add(-other)
+    }
+
+    pub fn checked_mul(self, other: Self) -> Result<Self, String> {
+        let units = self.units.checked_mul(other.units).ok_or_else(overflow)?;
+        let product = Self {
+            units,
+            scale: self.scale + other.scale,
+        };
+        if product.scale > MAX_SCALE {
+            return product.round(MAX_SCALE, RoundingMode::HalfEven);
+        }
+        Ok(product)
+    }
+
+    /// Divides with half-even rounding at up to 18 fractional digits (never
+    /// fewer than either operand), then trims trailing zeros back to the
+    /// larger operand scale.
+    pub fn checked_div(self, other: Self) -> Result<Self, String> {
+        if other.is_zero() {
+            return Err("division by zero".to_string());
+        }
+        let keep = self.scale.max(other.scale);
+        let scale = keep.clamp(DIV_SCALE, MAX_SCALE);
+        // units = self.units * 10^(scale - self.scale + other.scale) / other.units
+        let shift = scale + other.scale - self.scale;
+        let numerator = self.units.checked_mul(pow10(shift)?).ok_or_else(overflow)?;
+        let units = div_round(numerator, other.units, RoundingMode::HalfEven);
+        Ok(Self { units, scale }.trimmed(keep))
+    }
+
+    pub fn checked_rem(self, other: Self) -> Result<Self, String> {
+        if other.is_zero() {
+            return Err("division by zero".to_string());
+        }
+        let scale = self.scale.max(other.scale);
+        let left = self.rescaled_units(scale)?;
+        let right = other.rescaled_units(scale)?;
+        Ok(Self {
+            units: left % right,
+            scale,
+        })
+    }
+
+    /// Rounds (or pads) to exactly `scale` fractional digits.
+    pub fn round(self, scale: u32, mode: RoundingMode) -> Result<Self, String> {
+        if scale > MAX_SCALE {
+            return Err(format!("decimal scale {scale} exceeds {MAX_SCALE}"));
+        }
+        if scale >= self.scale {
+            return Ok(Self {
+                units: self.rescaled_units(scale)?,
+                scale,
+            });
+        }
+        let divisor = pow10(self.scale - scale)?;
+        Ok(Self {
+            units: div_round(self.units, divisor, mode),
+            scale,
+        })
+    }
+
+    fn rescaled_units(self, scale: u32) -> Result<i128, String> {
+        debug_assert!(scale >= self.scale);
+        self.units
+            .checked_mul(pow10(scale - self.scale)?)
+            .ok_or_else(overflow)
+    }
+
+    fn trimmed(mut self, min_scale: u32) -> Self {
+        while self.scale > min_scale && self.units % 10 == 0 {
+            self.units /= 10;
+            self.scale -= 1;
+        }
+        self
+    }
+}
+
+impl std::ops::Neg for Decimal {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            units: -self.units,
+            scale: self.scale,
+        }
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let scale = self.scale.max(other.scale);
+        match (self.rescaled_units(scale), other.rescaled_units(scale)) {
+            (Ok(left), Ok(right)) => left.cmp(&right),
+            // Rescaling overflowed, so the overflowing side has the larger magnitude.
+            (Err(_), _) => self.units.signum().cmp(&0),
+            (_, Err(_)) => 0.cmp(&other.units.signum()),
+        }
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = self.units.unsigned_abs().to_string();
+        let sign = if self.units < 0 { "-" } else { "" };
+        let scale = self.scale as usize;
+        if scale == 0 {
+            return write!(f, "{sign}{digits}");
+        }
+        let padded = format!("{digits:0>width$}", width = scale + 1);
+        let (whole, frac) = padded.split_at(padded.len() - scale);
+        write!(f, "{sign}{whole}.{frac}")
+    }
+}
+
+fn pow10(exp: u32) -> Result<i128, String> {
+    10_i128.checked_pow(exp).ok_or_else(overflow)
+}
+
+fn overflow() -> String {
+    "decimal overflow".to_string()
+}
+
+fn div_round(numerator: i128, denominator: i128, mode: RoundingMode) -> i128 {
+    let (numerator, denominator) = if denominator < 0 {
+        (-numerator, -denominator)
+    } else {
+        (numerator, denominator)
+    };
+    let quotient = numerator / denominator;
+    let remainder = numerator % denominator;
+    if remainder == 0 {
+        return quotient;
+    }
+    let away = if numerator < 0 { -1 } else { 1 };
+    let twice = remainder.unsigned_abs() * 2;
+    let half = twice.cmp(&denominator.unsigned_abs());
+    let bump = match mode {
+        RoundingMode::Down => false,
+        RoundingMode::Up => true,
+        RoundingMode::Ceiling => numerator > 0,
+        RoundingMode::Floor => numerator < 0,
+        RoundingMode::HalfUp => half != Ordering::Less,
+        RoundingMode::HalfDown => half == Ordering::Greater,
+        RoundingMode::HalfEven => {
+            half == Ordering::Greater || (half == Ordering::Equal && quotient % 2 != 0)
+        }
+    };
+    if bump { quotient + away } else { quotient }
+}
@@ -1,7 +1,9 @@
 pub mod bytes;
 pub mod codec;
 pub mod config;
+pub mod decimal;
 pub mod error;
 pub mod json;
 pub mod log;
+pub mod time;
 pub mod validate;
@@ -0,0 +1,316 @@
+pub const MILLIS_PER_DAY: i64 = 86_400_000;
+
+const MAX_OFFSET_MINUTES: i32 = 23 * 60 + 59;
+
+// Howard Hinnant's days_from_civil / civil_from_days (proleptic Gregorian).
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> Option<i64> {
+    if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+        return None;
+    }
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let m = month as i64;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    Some(era * 146_097 + doe - 719_468)
+}
+
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        _ => {
+            let leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+            if leap { 29 } else { 28 }
+        }
+    }
+}
+
+pub fn format_date(days: i64) -> String {
+    let (year, month, day) = civil_from_days(days);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+pub fn parse_date(text: &str) -> Result<i64, String> {
+    let mut cursor = Cursor::new(text.trim());
+    let days = cursor.date()?;
+    cursor.finish()?;
+    Ok(days)
+}
+
+pub fn format_instant(unix_ms: i64) -> String {
+    format_datetime(unix_ms, 0)
+}
+
+pub fn parse_instant(text: &str) -> Result<i64, String> {
+    parse_datetime(text).map(|(unix_ms, _)| unix_ms)
+}
+
+pub fn format_datetime(unix_ms: i64, offset_minutes: i32) -> String {
+    let local_ms = unix_ms + i64::from(offset_minutes) * 60_000;
+    let days = local_ms.div_euclid(MILLIS_PER_DAY);
+    let ms_of_day = local_ms.rem_euclid(MILLIS_PER_DAY);
+    let hour = ms_of_day / 3_600_000;
+    let minute = ms_of_day / 60_000 % 60;
+    let second = ms_of_day / 1000 % 60;
+    let millis = ms_of_day % 1000;
+    let mut out = format!("{}T{hour:02}:{minute:02}:{second:02}", format_date(days));
+    if millis != 0 {
+        out.push_str(&format!(".{millis:03}"));
+    }
+    out.push_str(&format_offset(offset_minutes));
+    out
+}
+
+pub fn parse_datetime(text: &str) -> Result<(i64, i32), String> {
+    let mut cursor = Cursor::new(text.trim());
+    let days = cursor.date()?;
+    match cursor.next() {
+        Some(b'T' | b't' | b' ') => {}
+        _ => return Err("expected `T` between date and time".to_string()),
+    }
+    let hour = cursor.number(2, "hour")?;
+    cursor.expect(b':')?;
+    let minute = cursor.number(2, "minute")?;
+    cursor.expect(b':')?;
+    let second = cursor.number(2, "second")?;
+    if hour > 23 || minute > 59 || second > 59 {
+        return Err("time of day out of range".to_string());
+    }
+    let mut millis = 0;
+    if cursor.peek() == Some(b'.') {
+        cursor.next();
+        let mut digits = 0;
+        while let Some(digit) = cursor.peek().filter(u8::is_ascii_digit) {
+            cursor.next();
+            if digits < 3 {
+                millis = millis * 10 + i64::from(digit - b'0');
+            }
+            digits += 1;
+        }
+        if digits == 0 {
+            return Err("expected fractional seconds after `.`".to_string());
+        }
+        for _ in digits..3 {
+            millis *= 10;
+        }
+    }
+    let offset = parse_offset(cursor.rest())?;
+    let local_ms = days * MILLIS_PER_DAY + hour * 3_600_000 + minute * 60_000 + second * 1000;
+    Ok((local_ms + millis - i64::from(offset) * 60_000, offset))
+}
+
+pub fn format_offset(minutes: i32) -> String {
+    if minutes == 0 {
+        return "Z".to_string();
+    }
+    let sign = if minutes < 0 { '-' } else { '+' };
+    let abs = minutes.abs();
+    format!("{sign}{:02}:{:02}", abs / 60, abs % 60)
+}
+
+pub fn parse_offset(text: &str) -> Result<i32, String> {
+    let text = text.trim();
+    if text.eq_ignore_ascii_case("z") || text == "UTC" {
+        return Ok(0);
+    }
+    let mut cursor = Cursor::new(text);
+    let sign = match cursor.next() {
+        Some(b'+') => 1,
+        Some(b'-') => -1,
+        _ => return Err(format!("invalid UTC offset `{text}`; expected Z or ±HH:MM")),
+    };
+    let hours = cursor.number(2, "offset hour")?;
+    cursor.expect(b':')?;
+    let minutes = cursor.number(2, "offset minute")?;
+    cursor.finish()?;
+    let total = (hours * 60 + minutes) as i32;
+    if minutes > 59 || total > MAX_OFFSET_MINUTES {
+        return Err(format!("UTC offset `{text}` out of range"));
+    }
+    Ok(sign * total)
+}
+
+pub fn format_duration(ms: i64) -> String {
+    if ms == 0 {
+        return "PT0S".to_string();
+    }
+    let mut out = String::new();
+    if ms < 0 {
+        out.push('-');
+    }
+    let abs = ms.unsigned_abs();
+    let days = abs / MILLIS_PER_DAY as u64;
+    let hours = abs / 3_600_000 % 24;
+    let minutes = abs / 60_000 % 60;
+    let seconds = abs / 1000 % 60;
+    let millis = abs % 1000;
+    out.push('P');
+    if days > 0 {
+        out.push_str(&format!("{days}D"));
+    }
+    if hours > 0 || minutes > 0 || seconds > 0 || millis > 0 {
+        out.push('T');
+        if hours > 0 {
+            out.push_str(&format!("{hours}H"));
+        }
+        if minutes > 0 {
+            out.push_str(&format!("{minutes}M"));
+        }
+        if millis > 0 {
+            let frac = format!("{millis:03}");
+            out.push_str(&format!("{seconds}.{}S", frac.trim_end_matches('0')));
+        } else if seconds > 0 {
+            out.push_str(&format!("{seconds}S"));
+        }
+    }
+    out
+}
+
+pub fn parse_duration(text: &str) -> Result<i64, String> {
+    let text = text.trim();
+    let (negative, body) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let Some(body) = body.strip_prefix(['P', 'p']) else {
+        return Err("ISO 8601 duration must start with `P`".to_string());
+    };
+    let mut total: i64 = 0;
+    let mut in_time = false;
+    let mut components = 0;
+    let mut number = String::new();
+    for ch in body.chars() {
+        match ch {
+            '0'..='9' | '.' => number.push(ch),
+            'T' | 't' if number.is_empty() && !in_time => in_time = true,
+            _ => {
+                if number.is_empty() {
+                    return Err(format!("missing number before `{ch}` in duration"));
+                }
+                let unit_ms: i64 = match (ch.to_ascii_uppercase(), in_time) {
+                    ('W', false) => 7 * MILLIS_PER_DAY,
+                    ('D', false) => MILLIS_PER_DAY,
+                    ('H', true) => 3_600_000,
+                    ('M', true) => 60_000,
+                    ('S', true) => 1000,
+                    ('Y' | 'M', false) => {
+                        return Err(
+                            "years and months are not fixed-length; use weeks or days".to_string()
+                        );
+                    }
+                    _ => return Err(format!("unexpected `{ch}` in duration")),
+                };
+                let part = duration_component(&number, unit_ms)?;
+                total = total
+                    .checked_add(part)
+                    .ok_or_else(|| "duration overflow".to_string())?;
+                components += 1;
+                number.clear();
+            }
+        }
+    }
+    if !number.is_empty() || components == 0 {
+        return Err("incomplete ISO 8601 duration".to_string());
+    }
+    Ok(if negative { -total } else { total })
+}
+
+fn duration_component(number: &str, unit_ms: i64) -> Result<i64, String> {
+    let (whole, frac) = number.split_once('.').unwrap_or((number, ""));
+    if whole.is_empty() || frac.contains('.') || (number.contains('.') && unit_ms != 1000) {
+        return Err(format!("invalid duration number `{number}`"));
+    }
+    let whole: i64 = whole
+        .parse()
+        .map_err(|_| format!("invalid duration number `{number}`"))?;
+    let mut millis = 0;
+    for (idx, digit) in frac.bytes().take(3).enumerate() {
+        millis += i64::from(digit - b'0') * 10_i64.pow(2 - idx as u32);
+    }
+    whole
+        .checked_mul(unit_ms)
+        .and_then(|ms| ms.checked_add(millis))
+        .ok_or_else(|| "duration overflow".to_string())
+}
+
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            bytes: text.as_bytes(),
+            pos: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let byte = self.peek()?;
+        self.pos += 1;
+        Some(byte)
+    }
+
+    fn rest(&self) -> &'a str {
+        std::str::from_utf8(&self.bytes[self.pos..]).unwrap_or("")
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), String> {
+        if self.next() == Some(byte) {
+            Ok(())
+        } else {
+            Err(format!("expected `{}`", byte as char))
+        }
+    }
+
+    fn finish(&self) -> Result<(), String> {
+        if self.pos == self.bytes.len() {
+            Ok(())
+        } else {
+            Err(format!("unexpected trailing text `{}`", self.rest()))
+        }
+    }
+
+    fn number(&mut self, digits: usize, what: &str) -> Result<i64, String> {
+        let mut value = 0;
+        for _ in 0..digits {
+            match self.next() {
+                Some(digit) if digit.is_ascii_digit() => {
+                    value = value * 10 + i64::from(digit - b'0')
+                }
+                _ => return Err(format!("expected {digits}-digit {what}")),
+            }
+        }
+        Ok(value)
+    }
+
+    fn date(&mut self) -> Result<i64, String> {
+        let year = self.number(4, "year")?;
+        self.expect(b'-')?;
+        let month = self.number(2, "month")?;
+        self.expect(b'-')?;
+        let day = self.number(2, "day")?;
+        days_from_civil(year, month as u32, day as u32)
+            .ok_or_else(|| format!("invalid calendar date {year:04}-{month:02}-{day:02}"))
+    }
+}
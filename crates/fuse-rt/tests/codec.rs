@@ -91,3 +91,141 @@ fn encodes_enum_value() {
     assert_eq!(obj.get("type"), Some(&JsonValue::String("Red".to_string())));
     assert!(!obj.contains_key("data"));
 }
+
+#[test]
+fn round_trips_time_values_as_rfc3339_text() {
+    let ty = Type::Struct(StructType {
+        name: "Booking".to_string(),
+        fields: vec![
+            StructField {
+                name: "at".to_string(),
+                ty: Type::Instant,
+                default: None,
+            },
+            StructField {
+                name: "day".to_string(),
+                ty: Type::Date,
+                default: None,
+            },
+            StructField {
+                name: "length".to_string(),
+                ty: Type::Duration,
+                default: None,
+            },
+            StructField {
+                name: "local".to_string(),
+                ty: Type::DateTime,
+                default: None,
+            },
+        ],
+    });
+    let json = JsonValue::Object(
+        [
+            (
+                "at".to_string(),
+                JsonValue::String("2024-03-10T09:30:00.250+01:00".to_string()),
+            ),
+            (
+                "day".to_string(),
+                JsonValue::String("2024-02-29".to_string()),
+            ),
+            (
+                "length".to_string(),
+                JsonValue::String("PT1H30M".to_string()),
+            ),
+            (
+                "local".to_string(),
+                JsonValue::String("2024-03-10T09:30:00-05:00".to_string()),
+            ),
+        ]
+        .into_iter()
+        .collect(),
+    );
+
+    let value = decode_value(&json, &ty).expect("decode time fields");
+    let Value::Struct { fields, .. } = &value else {
+        panic!("expected struct");
+    };
+    assert_eq!(fields.get("at"), Some(&Value::Instant(1_710_059_400_250)));
+    assert_eq!(fields.get("day"), Some(&Value::Date(19_782)));
+    assert_eq!(fields.get("length"), Some(&Value::Duration(5_400_000)));
+    assert_eq!(
+        fields.get("local"),
+        Some(&Value::DateTime {
+            unix_ms: 1_710_081_000_000,
+            offset_minutes: -300,
+        })
+    );
+
+    let JsonValue::Object(encoded) = encode_value(&value) else {
+        panic!("expected object");
+    };
+    let text = |key: &str| match encoded.get(key) {
+        Some(JsonValue::String(text)) => text.clone(),
+        other => panic!("expected string for {key}, got {other:?}"),
+    };
+    assert_eq!(text("at"), "2024-03-10T08:30:00.250Z");
+    assert_eq!(text("day"), "2024-02-29");
+    assert_eq!(text("length"), "PT1H30M");
+    assert_eq!(text("local"), "2024-03-10T09:30:00-05:00");
+}
+
+#[test]
+fn rejects_malformed_time_text_with_paths() {
+    let ty = Type::List(Box::new(Type::Date));
+    let json = JsonValue::Array(vec![
+        JsonValue::String("2023-02-29".to_string()),
+        JsonValue::Number(1.0),
+    ]);
+
+    let err = decode_value(&json, &ty).unwrap_err();
+    assert_eq!(err.fields.len(), 2);
+    assert_eq!(err.fields[0].path, "[0]");
+    assert_eq!(err.fields[0].code, "invalid_value");
+    assert_eq!(err.fields[1].path, "[1]");
+    assert_eq!(err.fields[1].code, "invalid_type");
+}
+
+#[test]
+fn decodes_decimals_from_strings_and_numbers() {
+    use fuse_rt::decimal::Decimal;
+
+    let ty = Type::List(Box::new(Type::Decimal));
+    let json = JsonValue::Array(vec![
+        JsonValue::String("19.990".to_string()),
+        JsonValue::Number(0.1),
+        JsonValue::String("12.3.4".to_string()),
+        JsonValue::Bool(true),
+        JsonValue::Number(12345678901234567.89),
+    ]);
+    let err = decode_value(&json, &ty).unwrap_err();
+    assert_eq!(err.fields.len(), 3);
+    assert_eq!(err.fields[0].path, "[2]");
+    assert_eq!(err.fields[0].code, "invalid_value");
+    assert_eq!(err.fields[1].path, "[3]");
+    assert_eq!(err.fields[1].code, "invalid_type");
+    assert_eq!(err.fields[2].path, "[4]");
+    assert_eq!(err.fields[2].code, "invalid_value");
+
+    let json = JsonValue::Array(vec![
+        JsonValue::String("19.990".to_string()),
+        JsonValue::Number(0.1),
+        JsonValue::String("12345678901234567.89".to_string()),
+    ]);
+    let value = decode_value(&json, &ty).expect("decode ok");
+    let Value::List(items) = &value else {
+        panic!("expected list");
+    };
+    assert_eq!(
+        items[1],
+        Value::Decimal(Decimal::parse("0.1").expect("decimal"))
+    );
+    assert_eq!(
+        encode_value(&value),
+        JsonValue::Array(vec![
+            JsonValue::String("19.990".to_string()),
+            JsonValue::String("0.1".to_string()),
+            JsonValue::String("12345678901234567.89".to_string()),
+        ])
+    );
+}
@@ -0,0 +1,112 @@
+use fuse_rt::decimal::{Decimal, RoundingMode};
+
+fn dec(text: &str) -> Decimal {
+    Decimal::parse(text).expect("valid decimal")
+}
+
+#[test]
+fn parses_and_formats_without_loss() {
+    assert_eq!(dec("19.99").to_string(), "19.99");
+    assert_eq!(dec("-0.05").to_string(), "-0.05");
+    assert_eq!(dec("+10.500").to_string(), "10.500");
+    assert_eq!(
+        dec("12345678901234567890.123456789").to_string(),
+        "12345678901234567890.123456789"
+    );
+    assert_eq!(
+        Decimal::from_f64(0.1).map(|d| d.to_string()),
+        Ok("0.1".to_string())
+    );
+    assert!(Decimal::parse("1.").is_err());
+    assert!(Decimal::parse(".").is_err());
+    assert!(Decimal::parse("1e").is_err());
+    assert!(Decimal::parse("e5").is_err());
+    assert!(Decimal::parse("1e400").is_err());
+}
+
+#[test]
+fn parses_leading_points_and_exponents() {
+    assert_eq!(dec(".5").to_string(), "0.5");
+    assert_eq!(dec("-.25").to_string(), "-0.25");
+    assert_eq!(dec("1e2").to_string(), "100");
+    assert_eq!(dec("1.5E+3").to_string(), "1500");
+    assert_eq!(dec("-2.5e-3").to_string(), "-0.0025");
+    assert_eq!(dec("12.50e1").to_string(), "125.0");
+    let err = Decimal::parse("1,5").unwrap_err();
+    assert!(
+        err.contains("`-12.50`, `.5` or `1e2`"),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn json_numbers_past_f64_precision_are_rejected() {
+    assert_eq!(
+        Decimal::from_json_number(19.99).map(|d| d.to_string()),
+        Ok("19.99".to_string())
+    );
+    assert_eq!(
+        Decimal::from_json_number(1e20).map(|d| d.to_string()),
+        Ok("100000000000000000000".to_string())
+    );
+    let err = Decimal::from_json_number(12345678901234567.89).unwrap_err();
+    assert!(err.contains("send the decimal as a string"), "{err}");
+}
+
+#[test]
+fn arithmetic_is_exact() {
+    assert_eq!(
+        dec("0.1").checked_add(dec("0.2")).unwrap().to_string(),
+        "0.3"
+    );
+    assert_eq!(
+        dec("10.00").checked_sub(dec("0.01")).unwrap().to_string(),
+        "9.99"
+    );
+    assert_eq!(
+        dec("1.10").checked_mul(dec("3")).unwrap().to_string(),
+        "3.30"
+    );
+    assert_eq!(
+        dec("10.00").checked_div(dec("4")).unwrap().to_string(),
+        "2.50"
+    );
+    assert_eq!(
+        dec("1").checked_div(dec("3")).unwrap().to_string(),
+        "0.333333333333333333"
+    );
+    assert_eq!(dec("7.5").checked_rem(dec("2")).unwrap().to_string(), "1.5");
+    assert!(dec("1").checked_div(dec("0.00")).is_err());
+    assert_eq!(dec("1.0"), dec("1.000"));
+    assert!(dec("-2.5") < dec("-2.45"));
+}
+
+#[test]
+fn rounds_with_every_mode() {
+    let cases = [
+        ("2.345", RoundingMode::HalfEven, "2.34"),
+        ("2.355", RoundingMode::HalfEven, "2.36"),
+        ("2.345", RoundingMode::HalfUp, "2.35"),
+        ("2.345", RoundingMode::HalfDown, "2.34"),
+        ("2.341", RoundingMode::Up, "2.35"),
+        ("2.349", RoundingMode::Down, "2.34"),
+        ("-2.341", RoundingMode::Ceiling, "-2.34"),
+        ("-2.341", RoundingMode::Floor, "-2.35"),
+        ("-2.345", RoundingMode::HalfUp, "-2.35"),
+    ];
+    for (input, mode, expected) in cases {
+        assert_eq!(
+            dec(input).round(2, mode).unwrap().to_string(),
+            expected,
+            "{input} {mode:?}"
+        );
+    }
+    assert_eq!(
+        dec("5")
+            .round(2, RoundingMode::HalfEven)
+            .unwrap()
+            .to_string(),
+        "5.00"
+    );
+    assert!(RoundingMode::parse("banker").is_err());
+}
@@ -0,0 +1,55 @@
+use fuse_rt::time::{
+    format_date, format_datetime, format_duration, format_instant, parse_date, parse_datetime,
+    parse_duration, parse_instant, parse_offset,
+};
+
+#[test]
+fn formats_and_parses_instants() {
+    assert_eq!(format_instant(0), "1970-01-01T00:00:00Z");
+    assert_eq!(format_instant(-1), "1969-12-31T23:59:59.999Z");
+    assert_eq!(parse_instant("1970-01-01T00:00:00Z"), Ok(0));
+    assert_eq!(parse_instant("2000-01-01t00:00:00.5z"), Ok(946_684_800_500));
+    assert_eq!(
+        parse_instant("2024-01-01T02:00:00+02:00"),
+        parse_instant("2024-01-01T00:00:00Z")
+    );
+    assert!(parse_instant("2024-01-01T24:00:00Z").is_err());
+    assert!(parse_instant("2024-01-01T00:00:00").is_err());
+}
+
+#[test]
+fn formats_and_parses_dates() {
+    assert_eq!(format_date(0), "1970-01-01");
+    assert_eq!(format_date(-1), "1969-12-31");
+    assert_eq!(parse_date("2000-03-01"), Ok(11_017));
+    assert!(parse_date("2100-02-29").is_err());
+    assert!(parse_date("2024-1-01").is_err());
+}
+
+#[test]
+fn keeps_utc_offsets_on_zoned_date_times() {
+    let (unix_ms, offset) = parse_datetime("2024-06-01T12:00:00+05:30").unwrap();
+    assert_eq!(offset, 330);
+    assert_eq!(
+        format_datetime(unix_ms, offset),
+        "2024-06-01T12:00:00+05:30"
+    );
+    assert_eq!(format_datetime(unix_ms, -420), "2024-05-31T23:30:00-07:00");
+    assert_eq!(parse_offset("Z"), Ok(0));
+    assert_eq!(parse_offset("-03:00"), Ok(-180));
+    assert!(parse_offset("+24:00").is_err());
+    assert!(parse_offset("Europe/Berlin").is_err());
+}
+
+#[test]
+fn formats_and_parses_iso_durations() {
+    assert_eq!(format_duration(0), "PT0S");
+    assert_eq!(format_duration(90_061_500), "P1DT1H1M1.5S");
+    assert_eq!(format_duration(-30_000), "-PT30S");
+    assert_eq!(parse_duration("PT1.5S"), Ok(1500));
+    assert_eq!(parse_duration("P1W"), Ok(604_800_000));
+    assert_eq!(parse_duration("-P1DT2H"), Ok(-93_600_000));
+    assert!(parse_duration("P1M").is_err());
+    assert!(parse_duration("PT").is_err());
+    assert!(parse_duration("1H").is_err());
+}
@@ -1,4 +1,4 @@
-use std::collections::{BTreeSet, HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap};
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -7,6 +7,7 @@ use std::time::SystemTime;
 
 use fuse_rt::error::{ValidationError, ValidationField};
 use fuse_rt::json as rt_json;
+use fusec::cli_binding::{CliApp, CliInvocation};
 
 use crate::cli_output::diagnostics_json_enabled;
 
@@ -15,12 +16,6 @@ pub(crate) struct BuildArtifacts {
     pub(crate) meta: super::IrMeta,
 }
 
-#[derive(Default)]
-struct RawProgramArgs {
-    values: HashMap<String, Vec<String>>,
-    bools: HashMap<String, bool>,
-}
-
 pub(crate) fn default_aot_output_path() -> String {
     if cfg!(windows) {
         ".fuse/build/program.aot.exe".to_string()
@@ -35,6 +30,7 @@ pub(crate) fn write_native_binary(
     app: Option<&str>,
     out_path: &str,
     release: bool,
+    completions: &[(String, String)],
 ) -> Result<(), String> {
     let build_dir = super::build_dir(manifest_dir)?;
     if !build_dir.exists() {
@@ -67,6 +63,7 @@ pub(crate) fn write_native_binary(
         &type_bytes,
         &artifact.config_defaults,
         release,
+        completions,
     )?;
     let out_path = resolve_output_path(manifest_dir, out_path)?;
     if let Some(parent) = out_path.parent() {
@@ -100,6 +97,7 @@ fn write_native_runner(
     type_bytes: &[u8],
     config_defaults: &[fusec::native::ConfigDefaultSymbol],
     release: bool,
+    completions: &[(String, String)],
 ) -> Result<(), String> {
     let interned = if interned_strings.is_empty() {
         "&[]".to_string()
@@ -122,6 +120,13 @@ fn write_native_runner(
         let bytes: Vec<String> = program_bytes.iter().map(|b| b.to_string()).collect();
         format!("&[{}]", bytes.join(", "))
     };
+    let completion_scripts = {
+        let items: Vec<String> = completions
+            .iter()
+            .map(|(shell, script)| format!("({shell:?}, {script:?})"))
+            .collect();
+        format!("&[{}]", items.join(", "))
+    };
     let type_blob = if type_bytes.is_empty() {
         "&[]".to_string()
     } else {
@@ -176,6 +181,7 @@ const INTERNED_STRINGS: &[&str] = {interned};
 const PROGRAM_BYTES: &[u8] = {program_blob};
 const CONFIG_BYTES: &[u8] = {config_blob};
 const TYPE_BYTES: &[u8] = {type_blob};
+const COMPLETION_SCRIPTS: &[(&str, &str)] = {completion_scripts};
 const AOT_STARTUP_MODE: &str = {mode_literal};
 const AOT_BUILD_PROFILE: &str = {profile_literal};
 const AOT_BUILD_TARGET: &str = {target_literal};
@@ -317,6 +323,18 @@ fn main() {{
         println!("{{}}", build_info_line());
         return;
     }}
+    if let Ok(shell) = std::env::var("FUSE_AOT_COMPLETIONS") {{
+        match COMPLETION_SCRIPTS.iter().find(|(name, _)| *name == shell) {{
+            Some((_, script)) => {{
+                print!("{{}}", script);
+                return;
+            }}
+            None => {{
+                eprintln!("no {{}} completions in this binary", shell);
+                std::process::exit(1);
+            }}
+        }}
+    }}
     apply_release_structured_log_default();
     emit_startup_trace();
     let status = match std::panic::catch_unwind(run_program) {{
@@ -918,170 +936,38 @@ fn prepare_cached_cli_call(
             return Err(1);
         }
     };
-    let main_decl = root.program.items.iter().find_map(|item| match item {
-        fusec::ast::Item::Fn(decl) if decl.name.name == "main" => Some(decl),
-        _ => None,
-    });
-    let Some(main_decl) = main_decl else {
-        super::emit_cli_error("no root fn main found for CLI binding");
+    let prog_name = entry
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "app".to_string());
+    let Some(cli) = CliApp::from_program(&root.program, &src, &prog_name) else {
+        super::emit_cli_error("no root fn main or pub fn subcommands found for CLI binding");
         return Err(1);
     };
 
-    let raw = match parse_program_args(program_args) {
-        Ok(raw) => raw,
-        Err(err) => {
-            emit_validation_error("$", "invalid_args", &err);
-            return Err(2);
-        }
-    };
-
     let mut interp = fusec::interp::Interpreter::with_registry(&registry);
-    let mut args_map = HashMap::new();
-    let mut errors = Vec::new();
-    let param_names: HashSet<String> = main_decl
-        .params
-        .iter()
-        .map(|param| param.name.name.clone())
-        .collect();
-    for (name, _) in &raw.values {
-        if !param_names.contains(name) {
-            errors.push(ValidationField {
-                path: name.clone(),
-                code: "unknown_flag".to_string(),
-                message: "unknown flag".to_string(),
-            });
-        }
-    }
-    for (name, _) in &raw.bools {
-        if !param_names.contains(name) {
-            errors.push(ValidationField {
-                path: name.clone(),
-                code: "unknown_flag".to_string(),
-                message: "unknown flag".to_string(),
-            });
+    let (function, args_map) = match cli.bind(&mut interp, program_args) {
+        Ok(CliInvocation::Call { function, args }) => (function, args),
+        Ok(CliInvocation::Help(text)) => {
+            print!("{text}");
+            return Err(0);
         }
-    }
-    for param in &main_decl.params {
-        let name = &param.name.name;
-        if let Some(flag) = raw.bools.get(name) {
-            if !is_bool_type(&param.ty) {
-                errors.push(ValidationField {
-                    path: name.clone(),
-                    code: "invalid_type".to_string(),
-                    message: "expected Bool flag".to_string(),
-                });
-                continue;
-            }
-            args_map.insert(name.clone(), fusec::interp::Value::Bool(*flag));
-            continue;
-        }
-        if let Some(values) = raw.values.get(name) {
-            if values.len() != 1 {
-                errors.push(ValidationField {
-                    path: name.clone(),
-                    code: "invalid_type".to_string(),
-                    message: "multiple values not supported".to_string(),
-                });
-                continue;
-            }
-            match interp.parse_cli_value(&param.ty, &values[0]) {
-                Ok(value) => {
-                    args_map.insert(name.clone(), value);
-                }
-                Err(msg) => {
-                    errors.push(ValidationField {
-                        path: name.clone(),
-                        code: "invalid_value".to_string(),
-                        message: msg,
-                    });
-                }
-            }
-            continue;
-        }
-        if param.default.is_none() && !is_optional(&param.ty) {
-            errors.push(ValidationField {
-                path: name.clone(),
-                code: "missing_field".to_string(),
-                message: "missing flag".to_string(),
-            });
+        Err(errors) => {
+            emit_validation_error_fields(errors);
+            return Err(2);
         }
-    }
-    if !errors.is_empty() {
-        emit_validation_error_fields(errors);
-        return Err(2);
-    }
-    let args = match interp.prepare_call_with_named_args("main", &args_map) {
+    };
+    let args = match interp.prepare_call_with_named_args(&function, &args_map) {
         Ok(args) => args,
         Err(err) => {
             emit_error_json_message(&err);
             return Err(2);
         }
     };
-    let entry_name = fusec::ir::lower::canonical_function_name(registry.root, "main");
+    let entry_name = fusec::ir::lower::canonical_function_name(registry.root, &function);
     Ok((entry_name, args))
 }
 
-fn parse_program_args(args: &[String]) -> Result<RawProgramArgs, String> {
-    let mut values: HashMap<String, Vec<String>> = HashMap::new();
-    let mut bools: HashMap<String, bool> = HashMap::new();
-    let mut idx = 0usize;
-    while idx < args.len() {
-        let arg = &args[idx];
-        if !arg.starts_with("--") {
-            return Err(format!("unexpected argument: {arg}"));
-        }
-        if let Some((name, val)) = arg.strip_prefix("--").and_then(|s| s.split_once('=')) {
-            values
-                .entry(name.to_string())
-                .or_default()
-                .push(val.to_string());
-            idx += 1;
-            continue;
-        }
-        if let Some(name) = arg.strip_prefix("--no-") {
-            bools.insert(name.to_string(), false);
-            idx += 1;
-            continue;
-        }
-        let name = arg.trim_start_matches("--");
-        if idx + 1 < args.len() && !args[idx + 1].starts_with("--") {
-            values
-                .entry(name.to_string())
-                .or_default()
-                .push(args[idx + 1].clone());
-            idx += 2;
-        } else {
-            bools.insert(name.to_string(), true);
-            idx += 1;
-        }
-    }
-    Ok(RawProgramArgs { values, bools })
-}
-
-fn is_optional(ty: &fusec::ast::TypeRef) -> bool {
-    match &ty.kind {
-        fusec::ast::TypeRefKind::Optional(_) => true,
-        fusec::ast::TypeRefKind::Generic { base, .. } => base.name == "Option",
-        _ => false,
-    }
-}
-
-fn is_bool_type(ty: &fusec::ast::TypeRef) -> bool {
-    match &ty.kind {
-        fusec::ast::TypeRefKind::Simple(ident) => ident.name == "Bool",
-        fusec::ast::TypeRefKind::Refined { base, .. } => base.name == "Bool",
-        fusec::ast::TypeRefKind::Optional(inner) => is_bool_type(inner),
-        fusec::ast::TypeRefKind::Generic { base, args } => {
-            if base.name == "Option" && args.len() == 1 {
-                is_bool_type(&args[0])
-            } else {
-                false
-            }
-        }
-        _ => false,
-    }
-}
-
 fn emit_validation_error(path: &str, code: &str, message: &str) {
     emit_validation_error_fields(vec![ValidationField {
         path: path.to_string(),
@@ -1149,6 +1035,7 @@ fn classify_runtime_error_code(message: &str) -> &'static str {
     if message.starts_with("range expects")
         || message == "range start must be <= end"
         || message == "invalid range bounds"
+        || message.starts_with("range step")
     {
         return "runtime_range_error";
     }
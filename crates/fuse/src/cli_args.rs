@@ -42,6 +42,35 @@ pub fn discover_diagnostics_format(args: &[String]) -> Option<DiagnosticsFormat>
     None
 }
 
+/// Removes the `fuse db types` options (`--out <path>`, `--check`) from
+/// `args`, leaving the rest for [`parse_common_args`].
+pub fn take_db_types_args(args: &[String]) -> Result<(Vec<String>, Option<PathBuf>, bool), String> {
+    let mut rest = Vec::with_capacity(args.len());
+    let mut out = None;
+    let mut check = false;
+    let mut idx = 0;
+    while idx < args.len() {
+        let arg = &args[idx];
+        if arg == "--" {
+            rest.extend(args[idx..].iter().cloned());
+            break;
+        }
+        if arg == "--out" {
+            idx += 1;
+            let Some(path) = args.get(idx) else {
+                return Err("--out expects a path".to_string());
+            };
+            out = Some(PathBuf::from(path));
+        } else if arg == "--check" {
+            check = true;
+        } else {
+            rest.push(arg.clone());
+        }
+        idx += 1;
+    }
+    Ok((rest, out, check))
+}
+
 pub fn parse_common_args(
     args: &[String],
     allow_program_args: bool,
@@ -49,6 +78,7 @@ pub fn parse_common_args(
     allow_build_mode: bool,
     allow_test_filter: bool,
     allow_frozen: bool,
+    allow_migrate: bool,
 ) -> Result<CommonArgs, String> {
     let mut out = CommonArgs::default();
     let mut idx = 0;
@@ -203,6 +233,29 @@ pub fn parse_common_args(
             idx += 1;
             continue;
         }
+        if arg == "--to" {
+            if !allow_migrate {
+                return Err("--to is only supported for fuse migrate down".to_string());
+            }
+            idx += 1;
+            let Some(name) = args.get(idx) else {
+                return Err("--to expects a migration name".to_string());
+            };
+            out.migrate_to = Some(name.clone());
+            idx += 1;
+            continue;
+        }
+        if arg == "--dry-run" {
+            if !allow_migrate {
+                return Err("--dry-run is only supported for fuse migrate".to_string());
+            }
+            out.dry_run = true;
+            idx += 1;
+            continue;
+        }
+        if arg == "--out" || arg == "--check" {
+            return Err(format!("{arg} is only supported for fuse db types"));
+        }
         if arg.starts_with("--") {
             return Err(format!("unknown option: {arg}"));
         }
@@ -109,7 +109,7 @@ fn command_tag(command: Command) -> Option<&'static str> {
         Command::Build => Some("build"),
         Command::Test => Some("test"),
         Command::Clean => Some("clean"),
-        Command::Dev | Command::Fmt | Command::Openapi | Command::Migrate => None,
+        Command::Dev | Command::Fmt | Command::Openapi | Command::Migrate | Command::Db => None,
     }
 }
 
@@ -220,7 +220,9 @@ fn classify_cli_message_code(level: &str, message: &str) -> Option<&'static str>
     if message.starts_with("run error:") {
         return Some("wrapper_run_error");
     }
-    if message == "no root module loaded" || message == "no root fn main found for CLI binding" {
+    if message == "no root module loaded"
+        || message == "no root fn main or pub fn subcommands found for CLI binding"
+    {
         return Some("wrapper_runtime_setup");
     }
     if message == "formatting aborted due to parse/sema errors" {
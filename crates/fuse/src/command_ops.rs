@@ -60,12 +60,18 @@ pub fn run_build(
         return 1;
     }
     if let Some(native_bin) = aot_out {
+        let bin_name = Path::new(&native_bin)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| native_bin.clone());
+        let completions = super::completions::program_scripts(entry, &bin_name);
         if let Err(err) = super::aot::write_native_binary(
             manifest_dir,
             &artifacts.native,
             app,
             &native_bin,
             release,
+            &completions,
         ) {
             super::emit_cli_error(&err);
             return 1;
@@ -241,6 +247,94 @@ fn collect_project_files(
     Ok(files.into_iter().collect())
 }
 
+pub fn run_db_types(
+    entry: &Path,
+    manifest: Option<&Manifest>,
+    manifest_dir: Option<&Path>,
+    deps: &HashMap<String, PathBuf>,
+    out: Option<&Path>,
+    check: bool,
+    strict_architecture: bool,
+) -> i32 {
+    let out_path = match out {
+        Some(path) => Some(path.to_path_buf()),
+        None => manifest
+            .and_then(|m| m.db.as_ref().and_then(|db| db.types.as_ref()))
+            .map(|path| match manifest_dir {
+                Some(dir) => dir.join(path),
+                None => PathBuf::from(path),
+            }),
+    };
+    if check && out_path.is_none() {
+        super::emit_cli_error(
+            "--check needs a types file (pass --out <path> or set [db].types in fuse.toml)",
+        );
+        return 1;
+    }
+    let source = match generate_db_types(entry, deps, strict_architecture) {
+        Ok(source) => source,
+        Err(err) => {
+            super::emit_cli_error(&err);
+            return 1;
+        }
+    };
+    let Some(out_path) = out_path else {
+        print!("{source}");
+        return 0;
+    };
+    if check {
+        let current = fs::read_to_string(&out_path).unwrap_or_default();
+        if current != source {
+            super::emit_cli_error(&format!(
+                "db types are out of date: {} (run `fuse db types`)",
+                out_path.display()
+            ));
+            return 1;
+        }
+        println!("db types up to date: {}", out_path.display());
+        return 0;
+    }
+    if let Some(parent) = out_path.parent()
+        && !parent.as_os_str().is_empty()
+        && !parent.exists()
+        && let Err(err) = fs::create_dir_all(parent)
+    {
+        super::emit_cli_error(&format!("failed to create {}: {err}", parent.display()));
+        return 1;
+    }
+    if let Err(err) = fs::write(&out_path, source) {
+        super::emit_cli_error(&format!("failed to write {}: {err}", out_path.display()));
+        return 1;
+    }
+    println!("wrote {}", out_path.display());
+    0
+}
+
+fn generate_db_types(
+    entry: &Path,
+    deps: &HashMap<String, PathBuf>,
+    strict_architecture: bool,
+) -> Result<String, String> {
+    let src = fs::read_to_string(entry)
+        .map_err(|err| format!("failed to read {}: {err}", entry.display()))?;
+    let (registry, diags) = fusec::load_program_with_modules_and_deps(entry, &src, deps);
+    if !diags.is_empty() {
+        super::emit_diags_with_fallback(&diags, Some((entry, &src)));
+        return Err("db types failed".to_string());
+    }
+    let (_analysis, diags) = fusec::sema::analyze_registry_with_options(
+        &registry,
+        fusec::sema::AnalyzeOptions {
+            strict_architecture,
+        },
+    );
+    if !diags.is_empty() {
+        super::emit_diags_with_fallback(&diags, Some((entry, &src)));
+        return Err("db types failed".to_string());
+    }
+    fusec::db_types::generate_db_types(&registry).map_err(|err| format!("db types error: {err}"))
+}
+
 fn write_openapi(
     entry: &Path,
     out_path: &Path,
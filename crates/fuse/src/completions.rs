@@ -0,0 +1,286 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use fusec::cli_binding::CliApp;
+use fusec::cli_completions::{CompletionCommand, CompletionFlag, CompletionSpec, Shell};
+
+use super::{CommonArgs, Manifest};
+
+struct CompletionsArgs {
+    shell: Shell,
+    program: bool,
+    bin: Option<String>,
+    manifest_path: Option<PathBuf>,
+    entry: Option<String>,
+}
+
+/// `fuse completions <shell> [--program [--bin <name>]]`: prints a completion
+/// script for the `fuse` tool, or for the package's CLI app.
+pub(crate) fn run_completions_command(args: &[String]) -> i32 {
+    let parsed = match parse_completions_args(args) {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            super::emit_cli_error(&err);
+            super::emit_usage();
+            return 1;
+        }
+    };
+    if !parsed.program {
+        print!("{}", fuse_spec().render(parsed.shell));
+        return 0;
+    }
+    let (manifest, manifest_dir) =
+        match super::manifest::load_manifest(parsed.manifest_path.as_deref()) {
+            Ok(value) => value,
+            Err(err) => {
+                super::emit_cli_error(&err);
+                return 1;
+            }
+        };
+    let explicit_entry = parsed.entry.is_some();
+    let common = CommonArgs {
+        entry: parsed.entry,
+        ..CommonArgs::default()
+    };
+    let entry =
+        match super::manifest::resolve_entry(&common, manifest.as_ref(), manifest_dir.as_deref()) {
+            Ok(entry) => entry,
+            Err(err) => {
+                super::emit_cli_error(&err);
+                return 1;
+            }
+        };
+    let name = parsed
+        .bin
+        .or_else(|| {
+            if explicit_entry {
+                None
+            } else {
+                native_bin_name(manifest.as_ref())
+            }
+        })
+        .unwrap_or_else(|| file_stem(&entry));
+    let src = match fs::read_to_string(&entry) {
+        Ok(src) => src,
+        Err(err) => {
+            super::emit_cli_error(&format!("failed to read {}: {err}", entry.display()));
+            return 1;
+        }
+    };
+    let (program, diags) = fusec::parse_source(&src);
+    if !diags.is_empty() {
+        super::emit_diags_with_fallback(&diags, Some((&entry, &src)));
+        return 1;
+    }
+    let Some(app) = CliApp::from_program(&program, &src, &name) else {
+        super::emit_cli_error(&format!(
+            "{} has no root fn main or pub fn to complete",
+            entry.display()
+        ));
+        return 1;
+    };
+    print!(
+        "{}",
+        CompletionSpec::from_cli_app(&app).render(parsed.shell)
+    );
+    0
+}
+
+/// Completion scripts for every supported shell, embedded into AOT binaries.
+/// Empty when the entry module is not a CLI app.
+pub(crate) fn program_scripts(entry: &Path, name: &str) -> Vec<(String, String)> {
+    let Ok(src) = fs::read_to_string(entry) else {
+        return Vec::new();
+    };
+    let (program, _) = fusec::parse_source(&src);
+    let Some(app) = CliApp::from_program(&program, &src, name) else {
+        return Vec::new();
+    };
+    let spec = CompletionSpec::from_cli_app(&app);
+    Shell::ALL
+        .iter()
+        .map(|shell| (shell.as_str().to_string(), spec.render(*shell)))
+        .collect()
+}
+
+fn native_bin_name(manifest: Option<&Manifest>) -> Option<String> {
+    let native_bin = manifest?.build.as_ref()?.native_bin.as_ref()?;
+    Path::new(native_bin)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "app".to_string())
+}
+
+fn parse_completions_args(args: &[String]) -> Result<CompletionsArgs, String> {
+    let mut shell = None;
+    let mut out = CompletionsArgs {
+        shell: Shell::Bash,
+        program: false,
+        bin: None,
+        manifest_path: None,
+        entry: None,
+    };
+    let mut idx = 0usize;
+    while idx < args.len() {
+        let arg = &args[idx];
+        idx += 1;
+        match arg.as_str() {
+            "--program" => out.program = true,
+            "--bin" | "--manifest-path" | "--file" | "--diagnostics" | "--color" => {
+                let Some(value) = args.get(idx) else {
+                    return Err(format!("{arg} expects a value"));
+                };
+                idx += 1;
+                match arg.as_str() {
+                    "--bin" => out.bin = Some(value.clone()),
+                    "--manifest-path" => out.manifest_path = Some(PathBuf::from(value)),
+                    "--file" => out.entry = Some(value.clone()),
+                    _ => {}
+                }
+            }
+            _ if arg.starts_with("--diagnostics=") || arg.starts_with("--color=") => {}
+            _ if arg.starts_with("--") => return Err(format!("unknown option: {arg}")),
+            _ if shell.is_none() => match Shell::parse(arg) {
+                Some(parsed) => shell = Some(parsed),
+                None => {
+                    return Err(format!(
+                        "unsupported shell: {arg} (expected bash, zsh, or fish)"
+                    ));
+                }
+            },
+            _ if out.entry.is_none() => out.entry = Some(arg.clone()),
+            _ => return Err(format!("unexpected argument: {arg}")),
+        }
+    }
+    let Some(shell) = shell else {
+        return Err("missing shell: expected bash, zsh, or fish".to_string());
+    };
+    if !out.program && (out.bin.is_some() || out.entry.is_some() || out.manifest_path.is_some()) {
+        return Err("--bin, --file and --manifest-path need --program".to_string());
+    }
+    out.shell = shell;
+    Ok(out)
+}
+
+/// Completion spec for the `fuse` tool itself. Flags marked "(<command>
+/// only)" in `USAGE` are offered after that command alone; the
+/// `completions_match_fuse_usage` CLI test keeps the two in sync.
+fn fuse_spec() -> CompletionSpec {
+    let command =
+        |name: &str, about: &str, args: &[&str], flags: Vec<CompletionFlag>| CompletionCommand {
+            name: name.to_string(),
+            about: about.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            flags,
+        };
+    let frozen = || CompletionFlag::switch("frozen", "Refuse fuse.lock mutation");
+    CompletionSpec {
+        name: "fuse".to_string(),
+        commands: vec![
+            command(
+                "dev",
+                "Run package entrypoint with live reload (watch mode)",
+                &[],
+                Vec::new(),
+            ),
+            command(
+                "run",
+                "Run the package entrypoint",
+                &[],
+                vec![
+                    CompletionFlag::value("backend", "Backend override", &["ast", "native"]),
+                    frozen(),
+                ],
+            ),
+            command(
+                "test",
+                "Run tests in the package",
+                &[],
+                vec![
+                    CompletionFlag::value(
+                        "filter",
+                        "Run only tests matching substring pattern",
+                        &[],
+                    ),
+                    frozen(),
+                ],
+            ),
+            command(
+                "build",
+                "Run package checks (and optional build steps)",
+                &[],
+                vec![
+                    frozen(),
+                    CompletionFlag::switch("clean", "Remove .fuse/build before building"),
+                    CompletionFlag::switch("aot", "Emit deployable AOT binary"),
+                    CompletionFlag::switch("release", "Use release profile for build output"),
+                ],
+            ),
+            command("check", "Parse + sema check", &[], vec![frozen()]),
+            command(
+                "clean",
+                "Remove selected cache directories",
+                &[],
+                vec![CompletionFlag::switch(
+                    "cache",
+                    "Remove .fuse-cache directories",
+                )],
+            ),
+            command(
+                "deps",
+                "Dependency maintenance commands",
+                &["lock", "publish-check"],
+                vec![
+                    CompletionFlag::switch("check", "Fail if fuse.lock is out of date"),
+                    CompletionFlag::switch("update", "Refresh fuse.lock"),
+                ],
+            ),
+            command("fmt", "Format a Fuse file", &[], Vec::new()),
+            command("openapi", "Emit OpenAPI JSON", &[], Vec::new()),
+            command(
+                "migrate",
+                "Run database migrations",
+                &["status", "down", "up"],
+                vec![
+                    CompletionFlag::value("to", "Roll back every migration after <name>", &[]),
+                    CompletionFlag::switch("dry-run", "Print migration SQL without applying it"),
+                ],
+            ),
+            command(
+                "db",
+                "Database tooling",
+                &["types", "seed"],
+                vec![
+                    CompletionFlag::value("out", "Write generated types to <path>", &[]),
+                    CompletionFlag::switch("check", "Fail if the types file is out of date"),
+                ],
+            ),
+            command(
+                "completions",
+                "Print a shell completion script",
+                &["bash", "zsh", "fish"],
+                vec![
+                    CompletionFlag::switch("program", "Complete the package CLI"),
+                    CompletionFlag::value("bin", "Command name for --program completions", &[]),
+                ],
+            ),
+        ],
+        flags: vec![
+            CompletionFlag::value("manifest-path", "Path to fuse.toml", &[]),
+            CompletionFlag::value("file", "Entry file override", &[]),
+            CompletionFlag::value("app", "App name override", &[]),
+            CompletionFlag::switch("strict-architecture", "Enable strict architectural checks"),
+            CompletionFlag::value("diagnostics", "Diagnostics format", &["json", "text"]),
+            CompletionFlag::value(
+                "color",
+                "Colorized CLI output policy",
+                &["auto", "always", "never"],
+            ),
+        ],
+    }
+}
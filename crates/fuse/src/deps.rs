@@ -768,7 +768,8 @@ fn load_manifest_from_dir(dir: &Path) -> Result<Option<Manifest>, String> {
     Ok(Some(manifest))
 }
 
-pub(crate) fn check_workspace_publish_readiness(root: &Path) -> Result<(), String> {
+/// Returns migration warnings for packages that pass the readiness checks.
+pub(crate) fn check_workspace_publish_readiness(root: &Path) -> Result<Vec<String>, String> {
     let manifests: Vec<_> = fusec::manifest::find_workspace_manifests(root)
         .into_iter()
         .filter(|manifest_dir| include_workspace_manifest_in_publish_check(root, manifest_dir))
@@ -782,20 +783,26 @@ pub(crate) fn check_workspace_publish_readiness(root: &Path) -> Result<(), Strin
     }
 
     let mut failures = Vec::new();
+    let mut warnings = Vec::new();
     for manifest_dir in manifests {
-        if let Err(err) = check_manifest_publish_readiness(&manifest_dir) {
-            let label = manifest_dir
-                .strip_prefix(root)
-                .ok()
-                .filter(|path| !path.as_os_str().is_empty())
-                .map(|path| path.display().to_string())
-                .unwrap_or_else(|| ".".to_string());
-            failures.push((label, err));
+        let label = manifest_dir
+            .strip_prefix(root)
+            .ok()
+            .filter(|path| !path.as_os_str().is_empty())
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|| ".".to_string());
+        match check_manifest_publish_readiness(&manifest_dir) {
+            Ok(package_warnings) => warnings.extend(
+                package_warnings
+                    .into_iter()
+                    .map(|warning| format!("{label}: {warning}")),
+            ),
+            Err(err) => failures.push((label, err)),
         }
     }
 
     if failures.is_empty() {
-        return Ok(());
+        return Ok(warnings);
     }
 
     let mut message = format!(
@@ -823,7 +830,7 @@ fn include_workspace_manifest_in_publish_check(root: &Path, manifest_dir: &Path)
     })
 }
 
-fn check_manifest_publish_readiness(manifest_dir: &Path) -> Result<(), String> {
+fn check_manifest_publish_readiness(manifest_dir: &Path) -> Result<Vec<String>, String> {
     let manifest_path = manifest_dir.join("fuse.toml");
     let content = fs::read_to_string(&manifest_path).map_err(|err| {
         dep_error_with_hint(
@@ -839,18 +846,64 @@ fn check_manifest_publish_readiness(manifest_dir: &Path) -> Result<(), String> {
             "fix the manifest syntax and rerun the check",
         )
     })?;
-    validate_manifest_entry(&manifest, manifest_dir)?;
-    resolve_dependencies_with_options(
+    let entry_path = validate_manifest_entry(&manifest, manifest_dir)?;
+    let deps = resolve_dependencies_with_options(
         Some(&manifest),
         Some(manifest_dir),
         ResolveOptions {
             lock_mode: LockMode::Check,
         },
     )?;
-    Ok(())
+    Ok(implicit_export_warnings(manifest_dir, &entry_path, &deps))
+}
+
+/// Package modules that still export every declaration because none is
+/// marked `pub`; other packages can import all of their helpers.
+fn implicit_export_warnings(
+    manifest_dir: &Path,
+    entry_path: &Path,
+    deps: &HashMap<String, PathBuf>,
+) -> Vec<String> {
+    let Ok(src) = fs::read_to_string(entry_path) else {
+        return Vec::new();
+    };
+    let (registry, _diags) = fusec::load_program_with_modules_and_deps(entry_path, &src, deps);
+    let package_root = canonical_or_self(manifest_dir);
+    let dep_roots: Vec<PathBuf> = deps.values().map(|root| canonical_or_self(root)).collect();
+    let mut modules: Vec<PathBuf> = registry
+        .modules
+        .values()
+        .filter(|unit| {
+            !unit.exports.explicit_visibility
+                && unit
+                    .program
+                    .items
+                    .iter()
+                    .any(|item| fusec::loader::visibility_item_name(item).is_some())
+        })
+        .filter_map(|unit| unit.path.canonicalize().ok())
+        .filter(|path| {
+            path.starts_with(&package_root) && !dep_roots.iter().any(|root| path.starts_with(root))
+        })
+        .collect();
+    modules.sort();
+    modules
+        .into_iter()
+        .map(|path| {
+            let shown = path.strip_prefix(&package_root).unwrap_or(&path);
+            format!(
+                "[FUSE_VISIBILITY_IMPLICIT_EXPORTS] {} exports every declaration implicitly; mark its public API with pub",
+                shown.display()
+            )
+        })
+        .collect()
 }
 
-fn validate_manifest_entry(manifest: &Manifest, manifest_dir: &Path) -> Result<(), String> {
+fn canonical_or_self(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn validate_manifest_entry(manifest: &Manifest, manifest_dir: &Path) -> Result<PathBuf, String> {
     let manifest_path = manifest_dir.join("fuse.toml");
     let Some(entry) = manifest.package.entry.as_deref() else {
         return Err(dep_error_with_hint(
@@ -874,5 +927,5 @@ fn validate_manifest_entry(manifest: &Manifest, manifest_dir: &Path) -> Result<(
             "fix [package].entry or add the missing file",
         ));
     }
-    Ok(())
+    Ok(entry_path)
 }
@@ -7,6 +7,7 @@ mod cache;
 mod cli_args;
 mod cli_output;
 mod command_ops;
+mod completions;
 mod deps;
 mod dev;
 mod manifest;
@@ -31,16 +32,18 @@ pub(crate) use model::{
 const USAGE: &str = r#"usage: fuse <command> [options] [file] [-- <program args>]
 
 commands:
-  dev       Run package entrypoint with live reload (watch mode)
-  run       Run the package entrypoint
-  test      Run tests in the package
-  build     Run package checks (and optional build steps)
-  check     Parse + sema check
-  clean     Remove selected cache directories
-  deps      Dependency maintenance commands
-  fmt       Format a Fuse file
-  openapi   Emit OpenAPI JSON
-  migrate   Run database migrations
+  dev          Run package entrypoint with live reload (watch mode)
+  run          Run the package entrypoint
+  test         Run tests in the package
+  build        Run package checks (and optional build steps)
+  check        Parse + sema check
+  clean        Remove selected cache directories
+  deps         Dependency maintenance commands
+  fmt          Format a Fuse file
+  openapi      Emit OpenAPI JSON
+  migrate      Run database migrations (migrate status|down for history and rollback)
+  db           Database tooling (db types|seed)
+  completions  Print a shell completion script (bash|zsh|fish)
 
 options:
   --manifest-path <path>  Path to fuse.toml (defaults to nearest parent)
@@ -56,6 +59,14 @@ options:
   --cache                 Remove .fuse-cache directories under a selected root (clean only)
   --aot                   Emit deployable AOT binary (build only)
   --release               Use release profile for build output (build only; implies --aot)
+  --to <name>             Roll back every migration after <name> (migrate down only)
+  --dry-run               Print migration SQL without applying it (migrate only)
+  --out <path>            Write generated types to <path> (db types only)
+  --check                 Fail if fuse.lock or the generated types file is out of date
+                          (deps lock/db types only)
+  --update                Refresh fuse.lock (deps lock only)
+  --program               Complete the package CLI app (completions only)
+  --bin <name>            Command name for --program completions (completions only)
 
 dependency commands:
   deps lock [--check|--update] [--manifest-path <path>]
@@ -64,6 +75,25 @@ dependency commands:
                         Check workspace manifest/lock readiness for publish
   clean --cache [<path>|--manifest-path <path>]
                         Remove .fuse-cache directories under the selected root
+
+migration commands:
+  migrate [--dry-run]   Apply pending migrations
+  migrate status        List applied, pending, and modified migrations
+  migrate down [--to <name>] [--dry-run]
+                        Roll back the latest migration, or every migration after <name>
+
+database commands:
+  db types [--out <path>] [--check]
+                        Generate `type` declarations from the migrated schema
+                        (writes [db].types from fuse.toml, or stdout when unset)
+  db seed               Apply pending `seed` blocks (tracked in __fuse_seeds)
+
+completion commands:
+  completions <bash|zsh|fish>
+                        Print a completion script for the fuse tool
+  completions <bash|zsh|fish> --program [--bin <name>] [--manifest-path <path>] [file]
+                        Print a completion script for the package CLI app
+                        (named after [build].native_bin or the entry file)
 "#;
 
 const FUSE_ASSET_MAP_ENV: &str = "FUSE_ASSET_MAP";
@@ -88,6 +118,23 @@ struct CommonArgs {
     release: bool,
     strict_architecture: bool,
     frozen: bool,
+    migrate_to: Option<String>,
+    dry_run: bool,
+    db_types_out: Option<PathBuf>,
+    db_types_check: bool,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum MigrateAction {
+    Up,
+    Down,
+    Status,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum DbAction {
+    Types,
+    Seed,
 }
 
 #[derive(Copy, Clone, Eq, PartialEq)]
@@ -125,6 +172,7 @@ enum Command {
     Fmt,
     Openapi,
     Migrate,
+    Db,
 }
 
 #[derive(Copy, Clone, Eq, PartialEq)]
@@ -222,6 +270,12 @@ fn run(args: Vec<String>) -> i32 {
         }
         return run_deps_command(rest);
     }
+    if cmd == "completions" {
+        if let Some(choice) = cli_args::discover_color_choice(rest) {
+            apply_color_choice(choice);
+        }
+        return completions::run_completions_command(rest);
+    }
     if cmd == "clean" {
         if let Some(choice) = cli_args::discover_color_choice(rest) {
             apply_color_choice(choice);
@@ -237,6 +291,7 @@ fn run(args: Vec<String>) -> i32 {
         "fmt" => Command::Fmt,
         "openapi" => Command::Openapi,
         "migrate" => Command::Migrate,
+        "db" => Command::Db,
         _ => {
             emit_cli_error(&format!("unknown command: {cmd}"));
             emit_usage();
@@ -246,6 +301,35 @@ fn run(args: Vec<String>) -> i32 {
     if let Some(choice) = cli_args::discover_color_choice(rest) {
         apply_color_choice(choice);
     }
+    let (migrate_action, rest) = match (command, rest.first().map(String::as_str)) {
+        (Command::Migrate, Some("status")) => (MigrateAction::Status, &rest[1..]),
+        (Command::Migrate, Some("down")) => (MigrateAction::Down, &rest[1..]),
+        (Command::Migrate, Some("up")) => (MigrateAction::Up, &rest[1..]),
+        _ => (MigrateAction::Up, rest),
+    };
+    let (db_action, rest, db_types_out, db_types_check) =
+        match (command, rest.first().map(String::as_str)) {
+            (Command::Db, Some("types")) => match cli_args::take_db_types_args(&rest[1..]) {
+                Ok((rest, out, check)) => (DbAction::Types, rest, out, check),
+                Err(err) => {
+                    emit_cli_error(&err);
+                    emit_usage();
+                    return 1;
+                }
+            },
+            (Command::Db, Some("seed")) => (DbAction::Seed, rest[1..].to_vec(), None, false),
+            (Command::Db, Some(other)) if !other.starts_with("--") => {
+                emit_cli_error(&format!("unknown db subcommand: {other}"));
+                emit_usage();
+                return 1;
+            }
+            (Command::Db, _) => {
+                emit_cli_error("missing db subcommand");
+                emit_usage();
+                return 1;
+            }
+            _ => (DbAction::Types, rest.to_vec(), None, false),
+        };
     let allow_program_args = matches!(command, Command::Run);
     let allow_clean = matches!(command, Command::Build);
     let allow_build_mode = matches!(command, Command::Build);
@@ -254,13 +338,14 @@ fn run(args: Vec<String>) -> i32 {
         command,
         Command::Run | Command::Test | Command::Build | Command::Check
     );
-    let common = match cli_args::parse_common_args(
-        rest,
+    let mut common = match cli_args::parse_common_args(
+        &rest,
         allow_program_args,
         allow_clean,
         allow_build_mode,
         allow_test_filter,
         allow_frozen,
+        matches!(command, Command::Migrate),
     ) {
         Ok(args) => args,
         Err(err) => {
@@ -269,6 +354,18 @@ fn run(args: Vec<String>) -> i32 {
             return 1;
         }
     };
+    common.db_types_out = db_types_out;
+    common.db_types_check = db_types_check;
+    if common.migrate_to.is_some() && migrate_action != MigrateAction::Down {
+        emit_cli_error("--to is only supported for fuse migrate down");
+        emit_usage();
+        return 1;
+    }
+    if common.dry_run && migrate_action == MigrateAction::Status {
+        emit_cli_error("--dry-run is not supported for fuse migrate status");
+        emit_usage();
+        return 1;
+    }
     apply_diagnostics_format(common.diagnostics.unwrap_or(DiagnosticsFormat::Text));
     apply_color_choice(common.color.unwrap_or(ColorChoice::Auto));
 
@@ -469,13 +566,44 @@ fn run(args: Vec<String>) -> i32 {
         }
         Command::Migrate => {
             let mut args = Vec::new();
-            args.push("--migrate".to_string());
+            args.push(
+                match migrate_action {
+                    MigrateAction::Up => "--migrate",
+                    MigrateAction::Down => "--migrate-down",
+                    MigrateAction::Status => "--migrate-status",
+                }
+                .to_string(),
+            );
+            if let Some(target) = &common.migrate_to {
+                args.push("--to".to_string());
+                args.push(target.clone());
+            }
+            if common.dry_run {
+                args.push("--dry-run".to_string());
+            }
             if common.strict_architecture {
                 args.push("--strict-architecture".to_string());
             }
             args.push(entry.to_string_lossy().to_string());
             fusec::cli::run_with_deps(args, Some(&deps))
         }
+        Command::Db if db_action == DbAction::Seed => {
+            let mut args = vec!["--seed".to_string()];
+            if common.strict_architecture {
+                args.push("--strict-architecture".to_string());
+            }
+            args.push(entry.to_string_lossy().to_string());
+            fusec::cli::run_with_deps(args, Some(&deps))
+        }
+        Command::Db => command_ops::run_db_types(
+            &entry,
+            manifest.as_ref(),
+            manifest_dir.as_deref(),
+            &deps,
+            common.db_types_out.as_deref(),
+            common.db_types_check,
+            common.strict_architecture,
+        ),
         Command::Clean => unreachable!("clean is handled before manifest loading"),
     };
 
@@ -626,7 +754,12 @@ fn run_deps_publish_check_command(args: &[String]) -> i32 {
         }
     };
     match deps::check_workspace_publish_readiness(&root) {
-        Ok(()) => 0,
+        Ok(warnings) => {
+            for warning in warnings {
+                emit_cli_warning(&warning);
+            }
+            0
+        }
         Err(err) => {
             emit_cli_error(&err);
             1
@@ -13,6 +13,8 @@ pub(crate) struct Manifest {
     #[serde(default)]
     pub(crate) vite: Option<ViteConfig>,
     #[serde(default)]
+    pub(crate) db: Option<DbConfig>,
+    #[serde(default)]
     pub(crate) dependencies: BTreeMap<String, DependencySpec>,
 }
 
@@ -58,6 +60,11 @@ pub(crate) struct ViteConfig {
     pub(crate) dist_dir: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+pub(crate) struct DbConfig {
+    pub(crate) types: Option<String>,
+}
+
 #[derive(Debug, Deserialize, Clone)]
 #[serde(untagged)]
 pub(crate) enum DependencySpec {
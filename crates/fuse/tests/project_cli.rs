@@ -742,6 +742,10 @@ fn send_unix_signal(pid: u32, signal: &str) {
 
 #[path = "project_cli/clean_cmd.rs"]
 mod clean_cmd;
+#[path = "project_cli/completions_cmd.rs"]
+mod completions_cmd;
+#[path = "project_cli/db_types.rs"]
+mod db_types;
 #[path = "project_cli/deps_lock.rs"]
 mod deps_lock;
 #[path = "project_cli/output_aot.rs"]
@@ -0,0 +1,271 @@
+use super::*;
+
+const CLI_SOURCE: &str = r#"
+enum Command:
+  Add(String)
+  List
+
+enum Color:
+  Red
+  DarkBlue
+
+fn main(command: Command, color: Color = Color.Red, verbose: Bool = false):
+  print("ok")
+
+app "Demo":
+  main(Command.List)
+"#;
+
+fn run_completions(args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_fuse"))
+        .arg("completions")
+        .args(args)
+        .output()
+        .expect("run fuse completions")
+}
+
+#[test]
+fn completions_print_scripts_for_the_fuse_tool() {
+    let bash = run_completions(&["bash"]);
+    assert!(
+        bash.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&bash.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&bash.stdout);
+    assert!(
+        stdout.contains("complete -o default -F _fuse fuse"),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("migrate) words=\"status down up "),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout
+            .contains("--backend) COMPREPLY=($(compgen -W \"ast native\" -- \"$cur\")); return ;;"),
+        "stdout: {stdout}"
+    );
+
+    let zsh = run_completions(&["zsh"]);
+    let stdout = String::from_utf8_lossy(&zsh.stdout);
+    assert!(stdout.starts_with("#compdef fuse\n"), "stdout: {stdout}");
+    assert!(
+        stdout.contains("'completions:Print a shell completion script'"),
+        "stdout: {stdout}"
+    );
+
+    let fish = run_completions(&["fish"]);
+    let stdout = String::from_utf8_lossy(&fish.stdout);
+    assert!(
+        stdout.contains(
+            "complete -c fuse -n \"__fish_seen_subcommand_from completions\" -f -a \"bash zsh fish\""
+        ),
+        "stdout: {stdout}"
+    );
+
+    let bad = run_completions(&["tcsh"]);
+    assert_eq!(bad.status.code(), Some(1), "status: {:?}", bad.status);
+    let stderr = String::from_utf8_lossy(&bad.stderr);
+    assert!(
+        stderr.contains("unsupported shell: tcsh"),
+        "stderr: {stderr}"
+    );
+}
+
+/// Reads `fuse`'s usage text, joining wrapped description lines.
+fn usage_section(usage: &str, header: &str) -> Vec<(String, String)> {
+    let mut entries: Vec<(String, String)> = Vec::new();
+    let section = usage
+        .lines()
+        .skip_while(|line| *line != header)
+        .skip(1)
+        .take_while(|line| !line.is_empty());
+    for line in section {
+        let trimmed = line.trim_start();
+        if line.len() - trimmed.len() > 2 {
+            let last = entries.last_mut().expect("continuation line");
+            last.1 = format!("{} {trimmed}", last.1);
+            continue;
+        }
+        let (name, rest) = trimmed.split_once(' ').expect("name and description");
+        entries.push((name.to_string(), rest.trim().to_string()));
+    }
+    entries
+}
+
+#[test]
+fn completions_match_fuse_usage() {
+    let usage = Command::new(env!("CARGO_BIN_EXE_fuse"))
+        .env("NO_COLOR", "1")
+        .output()
+        .expect("run fuse");
+    let usage = String::from_utf8_lossy(&usage.stderr).to_string();
+    let fish = run_completions(&["fish"]);
+    let fish = String::from_utf8_lossy(&fish.stdout).to_string();
+
+    let mut commands = Vec::new();
+    let mut flags = Vec::new();
+    for line in fish.lines() {
+        let Some(line) = line.strip_prefix("complete -c fuse ") else {
+            continue;
+        };
+        if let Some(rest) = line.strip_prefix("-n \"__fish_use_subcommand\" -f -a ") {
+            let (name, about) = rest.split_once(" -d '").expect("command about");
+            commands.push((name.to_string(), about.trim_end_matches('\'').to_string()));
+            continue;
+        }
+        let (scope, rest) = match line.strip_prefix("-n \"__fish_seen_subcommand_from ") {
+            Some(rest) => {
+                let (cmd, rest) = rest.split_once("\" ").expect("scope");
+                (cmd.to_string(), rest)
+            }
+            None => (String::new(), line),
+        };
+        if let Some(rest) = rest.strip_prefix("-l ") {
+            let name = rest.split(' ').next().unwrap_or(rest);
+            flags.push(format!("{scope}--{name}"));
+        }
+    }
+
+    let usage_commands = usage_section(&usage, "commands:");
+    assert_eq!(
+        commands.iter().map(|(name, _)| name).collect::<Vec<_>>(),
+        usage_commands
+            .iter()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>(),
+        "fuse_spec commands differ from usage"
+    );
+    for ((name, about), (_, description)) in commands.iter().zip(&usage_commands) {
+        assert!(
+            description.starts_with(about.as_str()),
+            "{name}: completion text {about:?} is not the usage text {description:?}"
+        );
+    }
+
+    // "(a/b only)" in a usage line scopes the flag to those commands.
+    let mut expected = Vec::new();
+    for (name, description) in usage_section(&usage, "options:") {
+        let scopes = description
+            .split_once('(')
+            .and_then(|(_, rest)| rest.split_once(" only"))
+            .map(|(scope, _)| scope.rsplit('(').next().unwrap_or(scope));
+        match scopes {
+            Some(scopes) => {
+                for scope in scopes.split('/') {
+                    let cmd = scope.split(' ').next().unwrap_or(scope);
+                    expected.push(format!("{cmd}{name}"));
+                }
+            }
+            None => expected.push(name),
+        }
+    }
+    flags.sort();
+    expected.sort();
+    assert_eq!(flags, expected, "fuse_spec flags differ from usage");
+}
+
+#[test]
+fn completions_program_derive_flags_from_main() {
+    let dir = temp_project_dir();
+    fs::create_dir_all(&dir).expect("create temp dir");
+    write_basic_manifest_project(&dir, CLI_SOURCE);
+    let manifest = dir.to_string_lossy().to_string();
+
+    let fish = run_completions(&["fish", "--program", "--manifest-path", &manifest]);
+    assert!(
+        fish.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&fish.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&fish.stdout);
+    assert!(
+        stdout.contains("complete -c main -n \"__fish_use_subcommand\" -f -a add -d '<String>'"),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains(
+            "complete -c main -l color -x -a \"red dark-blue\" -d 'Color (default: Color.Red)'"
+        ),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("complete -c main -l no-verbose -d 'Set --verbose to false'"),
+        "stdout: {stdout}"
+    );
+
+    let bash = run_completions(&[
+        "bash",
+        "--program",
+        "--manifest-path",
+        &manifest,
+        "--bin",
+        "todo",
+    ]);
+    let stdout = String::from_utf8_lossy(&bash.stdout);
+    assert!(
+        stdout.contains("complete -o default -F _todo todo"),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("\"\") words=\"add list --color --verbose --no-verbose --help\" ;;"),
+        "stdout: {stdout}"
+    );
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn aot_binaries_embed_completion_scripts() {
+    let dir = temp_project_dir();
+    fs::create_dir_all(&dir).expect("create temp dir");
+    write_basic_manifest_project(&dir, CLI_SOURCE);
+
+    let build = Command::new(env!("CARGO_BIN_EXE_fuse"))
+        .arg("build")
+        .arg("--manifest-path")
+        .arg(&dir)
+        .arg("--aot")
+        .output()
+        .expect("run fuse build --aot");
+    assert!(
+        build.status.success(),
+        "build stderr: {}",
+        String::from_utf8_lossy(&build.stderr)
+    );
+
+    let aot = default_aot_binary_path(&dir);
+    let bin_name = aot.file_name().unwrap().to_string_lossy().to_string();
+    let zsh = Command::new(&aot)
+        .env("FUSE_AOT_COMPLETIONS", "zsh")
+        .output()
+        .expect("run aot binary");
+    assert!(
+        zsh.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&zsh.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&zsh.stdout);
+    assert!(
+        stdout.starts_with(&format!("#compdef {bin_name}\n")),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("(--color) compadd -- red dark-blue; return ;;"),
+        "stdout: {stdout}"
+    );
+
+    let missing = Command::new(&aot)
+        .env("FUSE_AOT_COMPLETIONS", "tcsh")
+        .output()
+        .expect("run aot binary");
+    assert_eq!(
+        missing.status.code(),
+        Some(1),
+        "status: {:?}",
+        missing.status
+    );
+
+    let _ = fs::remove_dir_all(&dir);
+}
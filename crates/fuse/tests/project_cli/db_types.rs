@@ -0,0 +1,104 @@
+use super::*;
+
+const MIGRATIONS: &str = r#"requires db
+
+migration "001_users":
+  db.exec("create table users (id integer primary key, email text not null, bio text)")
+
+fn main():
+  print("ok")
+
+app "Demo":
+  main()
+"#;
+
+fn write_db_project(dir: &Path) {
+    fs::create_dir_all(dir).expect("create temp dir");
+    fs::write(
+        dir.join("fuse.toml"),
+        r#"
+[package]
+entry = "main.fuse"
+app = "Demo"
+
+[db]
+types = "src/db_types.fuse"
+"#,
+    )
+    .expect("write fuse.toml");
+    fs::write(dir.join("main.fuse"), MIGRATIONS).expect("write main.fuse");
+}
+
+fn run_db_types(dir: &Path, extra: &[&str]) -> std::process::Output {
+    let exe = env!("CARGO_BIN_EXE_fuse");
+    Command::new(exe)
+        .arg("db")
+        .arg("types")
+        .args(extra)
+        .arg("--manifest-path")
+        .arg(dir)
+        .env_remove("FUSE_DB_URL")
+        .output()
+        .expect("run fuse db types")
+}
+
+#[test]
+fn db_types_writes_row_types_from_migrations() {
+    let dir = temp_project_dir();
+    write_db_project(&dir);
+
+    let output = run_db_types(&dir, &[]);
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let generated =
+        fs::read_to_string(dir.join("src").join("db_types.fuse")).expect("read generated types");
+    assert!(
+        generated.contains("type UsersRow:\n  id: Int\n  email: String\n  bio: String?\n"),
+        "generated: {generated}"
+    );
+    assert!(
+        !generated.contains("__fuse_migrations"),
+        "generated: {generated}"
+    );
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn db_types_check_fails_when_schema_drifts() {
+    let dir = temp_project_dir();
+    write_db_project(&dir);
+
+    let output = run_db_types(&dir, &[]);
+    assert!(output.status.success());
+    let output = run_db_types(&dir, &["--check"]);
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let drifted = MIGRATIONS.replace(
+        "fn main():",
+        "migration \"002_users_name\":\n  db.exec(\"alter table users add column name text not null default ''\")\n\nfn main():",
+    );
+    fs::write(dir.join("main.fuse"), drifted).expect("write main.fuse");
+    let output = run_db_types(&dir, &["--check"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("db types are out of date"),
+        "stderr: {stderr}"
+    );
+    let generated =
+        fs::read_to_string(dir.join("src").join("db_types.fuse")).expect("read generated types");
+    assert!(
+        !generated.contains("name: String"),
+        "--check must not rewrite"
+    );
+
+    let _ = fs::remove_dir_all(&dir);
+}
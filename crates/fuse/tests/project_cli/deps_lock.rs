@@ -1668,6 +1668,57 @@ app "Root":
     let _ = fs::remove_dir_all(&dir);
 }
 
+#[test]
+fn deps_publish_check_warns_about_modules_without_pub_declarations() {
+    let dir = temp_project_dir();
+    fs::create_dir_all(&dir).expect("create temp dir");
+
+    write_basic_manifest_project(
+        &dir,
+        r#"
+import Util from "./util"
+import Api from "./api"
+
+app "Demo":
+  print(Util.value() + Api.value())
+"#,
+    );
+    fs::write(
+        dir.join("util.fuse"),
+        r#"
+fn value() -> String:
+  return "util"
+"#,
+    )
+    .expect("write util module");
+    fs::write(
+        dir.join("api.fuse"),
+        r#"
+pub fn value() -> String:
+  return "api"
+"#,
+    )
+    .expect("write api module");
+
+    let lock = run_deps_command(&dir, "lock", &["--color", "never"]);
+    assert!(
+        lock.status.success(),
+        "lock stderr: {}",
+        String::from_utf8_lossy(&lock.stderr)
+    );
+
+    let publish = run_deps_command(&dir, "publish-check", &["--color", "never"]);
+    let stderr = String::from_utf8_lossy(&publish.stderr);
+    assert!(publish.status.success(), "publish stderr: {stderr}");
+    assert!(
+        stderr.contains("[FUSE_VISIBILITY_IMPLICIT_EXPORTS] util.fuse exports every declaration"),
+        "stderr: {stderr}"
+    );
+    assert!(!stderr.contains("api.fuse"), "stderr: {stderr}");
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
 #[test]
 fn deps_publish_check_reports_manifest_and_lock_failures() {
     let dir = temp_project_dir();
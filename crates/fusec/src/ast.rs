@@ -48,6 +48,7 @@ pub struct RequireDecl {
 pub enum Item {
     Import(ImportDecl),
     Type(TypeDecl),
+    TypeAlias(TypeAliasDecl),
     Enum(EnumDecl),
     Interface(InterfaceDecl),
     Impl(ImplDecl),
@@ -55,8 +56,10 @@ pub enum Item {
     Component(ComponentDecl),
     Service(ServiceDecl),
     Config(ConfigDecl),
+    Const(ConstDecl),
     App(AppDecl),
     Migration(MigrationDecl),
+    Seed(SeedDecl),
     Test(TestDecl),
 }
 
@@ -101,8 +104,11 @@ pub enum ImportSpec {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TypeDecl {
     pub name: Ident,
+    pub type_params: Vec<TypeParam>,
     pub fields: Vec<FieldDecl>,
     pub derive: Option<TypeDerive>,
+    /// Marked `pub`; see `ModuleExports` for how visibility is applied.
+    pub is_pub: bool,
     pub doc: Option<Doc>,
     pub span: Span,
 }
@@ -110,7 +116,31 @@ pub struct TypeDecl {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TypeDerive {
     pub base: Ident,
-    pub without: Vec<Ident>,
+    /// Applied left to right to the base type's fields.
+    pub ops: Vec<DeriveOp>,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum DeriveOp {
+    /// `without a, b` drops the listed fields.
+    Without(Vec<Ident>),
+    /// `pick a, b` keeps only the listed fields, in the listed order.
+    Pick(Vec<Ident>),
+    /// `partial` makes every field optional and drops its default.
+    Partial,
+    /// `with name: Type [= default], ...` appends new fields.
+    With(Vec<FieldDecl>),
+}
+
+/// `type Name = TypeRef`; a transparent alias expanded before semantic
+/// analysis, so it never introduces a new nominal type.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TypeAliasDecl {
+    pub name: Ident,
+    pub target: TypeRef,
+    pub is_pub: bool,
+    pub doc: Option<Doc>,
     pub span: Span,
 }
 
@@ -125,7 +155,9 @@ pub struct FieldDecl {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EnumDecl {
     pub name: Ident,
+    pub type_params: Vec<TypeParam>,
     pub variants: Vec<EnumVariant>,
+    pub is_pub: bool,
     pub doc: Option<Doc>,
     pub span: Span,
 }
@@ -140,7 +172,10 @@ pub struct EnumVariant {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct InterfaceDecl {
     pub name: Ident,
+    /// Parent interfaces from `extends`; their members are part of this interface.
+    pub extends: Vec<Ident>,
     pub members: Vec<InterfaceMember>,
+    pub is_pub: bool,
     pub doc: Option<Doc>,
     pub span: Span,
 }
@@ -165,6 +200,8 @@ pub struct InterfaceMember {
     pub params: Vec<Param>,
     pub ret: Option<TypeRef>,
     pub where_clause: Vec<WhereConstraint>,
+    /// Default implementation used by impls that do not define the member.
+    pub body: Option<Block>,
     pub span: Span,
 }
 
@@ -185,6 +222,7 @@ pub struct FnDecl {
     pub ret: Option<TypeRef>,
     pub where_clause: Vec<WhereConstraint>,
     pub body: Block,
+    pub is_pub: bool,
     pub doc: Option<Doc>,
     pub span: Span,
 }
@@ -247,6 +285,7 @@ pub enum HttpVerb {
 pub struct ConfigDecl {
     pub name: Ident,
     pub fields: Vec<ConfigField>,
+    pub is_pub: bool,
     pub doc: Option<Doc>,
     pub span: Span,
 }
@@ -271,6 +310,30 @@ pub struct AppDecl {
 pub struct MigrationDecl {
     pub name: String,
     pub body: Block,
+    /// Optional paired `down:` block that reverts `body`.
+    pub down: Option<Block>,
+    pub doc: Option<Doc>,
+    pub span: Span,
+}
+
+/// `const NAME: Type = expr`; the value is folded at compile time and
+/// inlined at every use.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ConstDecl {
+    pub name: Ident,
+    pub ty: TypeRef,
+    pub value: Expr,
+    pub is_pub: bool,
+    pub doc: Option<Doc>,
+    pub span: Span,
+}
+
+/// `seed "name":` block that loads repeatable data; applied once per
+/// database and tracked in `__fuse_seeds`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SeedDecl {
+    pub name: StringLit,
+    pub body: Block,
     pub doc: Option<Doc>,
     pub span: Span,
 }
@@ -307,6 +370,13 @@ pub enum StmtKind {
         ty: Option<TypeRef>,
         expr: Expr,
     },
+    /// `let`/`var` whose left-hand side is a tuple or struct destructuring pattern.
+    LetPattern {
+        pat: Pattern,
+        ty: Option<TypeRef>,
+        expr: Expr,
+        mutable: bool,
+    },
     Assign {
         target: Expr,
         expr: Expr,
@@ -322,7 +392,7 @@ pub enum StmtKind {
     },
     Match {
         expr: Expr,
-        cases: Vec<(Pattern, Block)>,
+        cases: Vec<MatchCase>,
     },
     For {
         pat: Pattern,
@@ -386,8 +456,15 @@ pub enum ExprKind {
         name: Ident,
         fields: Vec<StructField>,
     },
+    /// `base with field = value, ...`; copies a struct value with the listed
+    /// fields replaced and validates the result like a constructor call.
+    RecordUpdate {
+        base: Box<Expr>,
+        fields: Vec<StructField>,
+    },
     ListLit(Vec<Expr>),
     MapLit(Vec<(Expr, Expr)>),
+    TupleLit(Vec<Expr>),
     InterpString(Vec<InterpPart>),
     Coalesce {
         left: Box<Expr>,
@@ -417,6 +494,8 @@ pub enum ExprKind {
     },
     Await {
         expr: Box<Expr>,
+        /// Milliseconds from `await expr within ms`.
+        timeout: Option<Box<Expr>>,
     },
     Box {
         expr: Box<Expr>,
@@ -474,6 +553,8 @@ pub enum BinaryOp {
     And,
     Or,
     Range,
+    /// `start..<end`
+    RangeExclusive,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -504,6 +585,7 @@ pub enum TypeRefKind {
         base: Ident,
         args: Vec<Expr>,
     },
+    Tuple(Vec<TypeRef>),
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -512,6 +594,13 @@ pub struct Pattern {
     pub span: Span,
 }
 
+impl Pattern {
+    /// Two-element tuple pattern; `for key, value in map` iterates entries with it.
+    pub fn is_pair(&self) -> bool {
+        matches!(&self.kind, PatternKind::Tuple(items) if items.len() == 2)
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum PatternKind {
     Wildcard,
@@ -525,6 +614,26 @@ pub enum PatternKind {
         name: Ident,
         fields: Vec<PatternField>,
     },
+    Tuple(Vec<Pattern>),
+    /// `p1 | p2`; every alternative binds the same names.
+    Or(Vec<Pattern>),
+    /// `name @ pat`
+    Bind {
+        name: Ident,
+        pat: Box<Pattern>,
+    },
+    /// Inclusive `start..end` over Int or Float literals.
+    Range {
+        start: Literal,
+        end: Literal,
+    },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MatchCase {
+    pub pat: Pattern,
+    pub guard: Option<Expr>,
+    pub block: Block,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -405,6 +405,27 @@ fn builtin_signature_info(name: &str) -> Option<SignatureInfo> {
             params: vec!["path: String".to_string()],
             documentation: Some("Resolves logical asset path to public URL.".to_string()),
         }),
+        "await_all" => Some(SignatureInfo {
+            label: "fn await_all(tasks: List<Task<T>>) -> List<T>".to_string(),
+            params: vec!["tasks: List<Task<T>>".to_string()],
+            documentation: Some(
+                "Awaits every task; the first failure cancels the rest.".to_string(),
+            ),
+        }),
+        "await_any" => Some(SignatureInfo {
+            label: "fn await_any(tasks: List<Task<T>>) -> T".to_string(),
+            params: vec!["tasks: List<Task<T>>".to_string()],
+            documentation: Some(
+                "Returns the first task to finish and cancels the rest.".to_string(),
+            ),
+        }),
+        "channel" => Some(SignatureInfo {
+            label: "fn channel<T>(capacity: Int) -> Channel<T>".to_string(),
+            params: vec!["capacity: Int".to_string()],
+            documentation: Some(
+                "Creates a bounded channel for passing values between tasks.".to_string(),
+            ),
+        }),
         _ => None,
     }
 }
@@ -565,10 +586,40 @@ fn builtin_member_signature_info(base: &str, member: &str) -> Option<SignatureIn
             params: vec!["value: Html".to_string()],
             documentation: Some("Renders Html to a string.".to_string()),
         }),
+        ("time", member) => time_value_signature_info(member),
+        ("decimal", member) => decimal_signature_info(member),
         _ => None,
     }
 }
 
+fn time_value_signature_info(member: &str) -> Option<SignatureInfo> {
+    let sig = fusec::sema::check::time_value_member_sig(member)?;
+    let params: Vec<String> = sig
+        .params
+        .iter()
+        .map(|param| format!("{}: {}", param.name, param.ty))
+        .collect();
+    Some(SignatureInfo {
+        label: format!("fn time.{member}({}) -> {}", params.join(", "), sig.ret),
+        params,
+        documentation: None,
+    })
+}
+
+fn decimal_signature_info(member: &str) -> Option<SignatureInfo> {
+    let sig = fusec::sema::check::decimal_member_sig(member)?;
+    let params: Vec<String> = sig
+        .params
+        .iter()
+        .map(|param| format!("{}: {}", param.name, param.ty))
+        .collect();
+    Some(SignatureInfo {
+        label: format!("fn decimal.{member}({}) -> {}", params.join(", "), sig.ret),
+        params,
+        documentation: None,
+    })
+}
+
 fn interface_signature_candidates_for_target(
     index: Option<&WorkspaceIndex>,
     uri: &str,
@@ -810,7 +861,8 @@ fn infer_ident_type_in_block(
                 }
             }
             StmtKind::Match { cases, .. } => {
-                for (_, block) in cases {
+                for case in cases {
+                    let block = &case.block;
                     if span_contains(block.span, cursor) {
                         return infer_ident_type_in_block(
                             block,
@@ -879,9 +931,9 @@ fn infer_expr_type(
         ExprKind::StructLit { name, .. } => Some(name.name.clone()),
         ExprKind::Ident(ident) if ident.name == "self" => current_impl_target.map(str::to_string),
         ExprKind::Ident(ident) => env.get(&ident.name).cloned(),
-        ExprKind::BangChain { expr, .. } | ExprKind::Await { expr } | ExprKind::Box { expr } => {
-            infer_expr_type(expr, env, current_impl_target)
-        }
+        ExprKind::BangChain { expr, .. }
+        | ExprKind::Await { expr, .. }
+        | ExprKind::Box { expr } => infer_expr_type(expr, env, current_impl_target),
         ExprKind::Coalesce { left, right } => infer_expr_type(left, env, current_impl_target)
             .or_else(|| infer_expr_type(right, env, current_impl_target)),
         _ => None,
@@ -950,9 +1002,10 @@ fn block_uses_ident(block: &Block, ident: &str) -> bool {
 
 fn stmt_uses_ident(stmt: &Stmt, ident: &str) -> bool {
     match &stmt.kind {
-        StmtKind::Let { expr, .. } | StmtKind::Var { expr, .. } | StmtKind::Expr(expr) => {
-            expr_uses_ident(expr, ident)
-        }
+        StmtKind::Let { expr, .. }
+        | StmtKind::Var { expr, .. }
+        | StmtKind::LetPattern { expr, .. }
+        | StmtKind::Expr(expr) => expr_uses_ident(expr, ident),
         StmtKind::Assign { target, expr } => {
             expr_uses_ident(target, ident) || expr_uses_ident(expr, ident)
         }
@@ -974,7 +1027,12 @@ fn stmt_uses_ident(stmt: &Stmt, ident: &str) -> bool {
         }
         StmtKind::Match { expr, cases } => {
             expr_uses_ident(expr, ident)
-                || cases.iter().any(|(_, block)| block_uses_ident(block, ident))
+                || cases.iter().any(|case| {
+                    case.guard
+                        .as_ref()
+                        .is_some_and(|guard| expr_uses_ident(guard, ident))
+                        || block_uses_ident(&case.block, ident)
+                })
         }
         StmtKind::For { iter, block, .. } => {
             expr_uses_ident(iter, ident) || block_uses_ident(block, ident)
@@ -993,9 +1051,7 @@ fn expr_uses_ident(expr: &Expr, ident: &str) -> bool {
         ExprKind::Binary { left, right, .. } => {
             expr_uses_ident(left, ident) || expr_uses_ident(right, ident)
         }
-        ExprKind::Unary { expr, .. }
-        | ExprKind::Await { expr }
-        | ExprKind::Box { expr } => expr_uses_ident(expr, ident),
+        ExprKind::Unary { expr, .. } | ExprKind::Box { expr } => expr_uses_ident(expr, ident),
         ExprKind::Call {
             callee,
             args,
@@ -1011,7 +1067,15 @@ fn expr_uses_ident(expr: &Expr, ident: &str) -> bool {
         ExprKind::StructLit { fields, .. } => fields
             .iter()
             .any(|field| expr_uses_ident(&field.value, ident)),
-        ExprKind::ListLit(items) => items.iter().any(|item| expr_uses_ident(item, ident)),
+        ExprKind::RecordUpdate { base, fields } => {
+            expr_uses_ident(base, ident)
+                || fields
+                    .iter()
+                    .any(|field| expr_uses_ident(&field.value, ident))
+        }
+        ExprKind::ListLit(items) | ExprKind::TupleLit(items) => {
+            items.iter().any(|item| expr_uses_ident(item, ident))
+        }
         ExprKind::MapLit(items) => items
             .iter()
             .any(|(key, value)| expr_uses_ident(key, ident) || expr_uses_ident(value, ident)),
@@ -1022,7 +1086,11 @@ fn expr_uses_ident(expr: &Expr, ident: &str) -> bool {
         ExprKind::Coalesce { left, right } => {
             expr_uses_ident(left, ident) || expr_uses_ident(right, ident)
         }
-        ExprKind::BangChain { expr, error } => {
+        ExprKind::BangChain { expr, error }
+        | ExprKind::Await {
+            expr,
+            timeout: error,
+        } => {
             expr_uses_ident(expr, ident)
                 || error
                     .as_ref()
@@ -1169,9 +1237,20 @@ fn collect_call_context_item(item: &Item, cursor: usize, best: &mut Option<CallC
                 collect_call_context_expr(&field.value, cursor, best);
             }
         }
+        Item::Const(decl) => {
+            collect_call_context_type_ref(&decl.ty, cursor, best);
+            collect_call_context_expr(&decl.value, cursor, best);
+        }
+        Item::TypeAlias(decl) => collect_call_context_type_ref(&decl.target, cursor, best),
         Item::Component(decl) => collect_call_context_block(&decl.body, cursor, best),
         Item::App(decl) => collect_call_context_block(&decl.body, cursor, best),
-        Item::Migration(decl) => collect_call_context_block(&decl.body, cursor, best),
+        Item::Migration(decl) => {
+            collect_call_context_block(&decl.body, cursor, best);
+            if let Some(down) = &decl.down {
+                collect_call_context_block(down, cursor, best);
+            }
+        }
+        Item::Seed(decl) => collect_call_context_block(&decl.body, cursor, best),
         Item::Test(decl) => collect_call_context_block(&decl.body, cursor, best),
         Item::Interface(_) | Item::Impl(_) => {},
     }
@@ -1191,7 +1270,9 @@ fn collect_call_context_stmt(stmt: &Stmt, cursor: usize, best: &mut Option<CallC
         return;
     }
     match &stmt.kind {
-        StmtKind::Let { ty, expr, .. } | StmtKind::Var { ty, expr, .. } => {
+        StmtKind::Let { ty, expr, .. }
+        | StmtKind::Var { ty, expr, .. }
+        | StmtKind::LetPattern { ty, expr, .. } => {
             if let Some(ty) = ty {
                 collect_call_context_type_ref(ty, cursor, best);
             }
@@ -1224,8 +1305,11 @@ fn collect_call_context_stmt(stmt: &Stmt, cursor: usize, best: &mut Option<CallC
         }
         StmtKind::Match { expr, cases } => {
             collect_call_context_expr(expr, cursor, best);
-            for (_, block) in cases {
-                collect_call_context_block(block, cursor, best);
+            for case in cases {
+                if let Some(guard) = &case.guard {
+                    collect_call_context_expr(guard, cursor, best);
+                }
+                collect_call_context_block(&case.block, cursor, best);
             }
         }
         StmtKind::For { iter, block, .. } => {
@@ -1258,7 +1342,7 @@ fn collect_call_context_expr(expr: &Expr, cursor: usize, best: &mut Option<CallC
             collect_call_context_expr(left, cursor, best);
             collect_call_context_expr(right, cursor, best);
         }
-        ExprKind::Unary { expr, .. } | ExprKind::Await { expr } | ExprKind::Box { expr } => {
+        ExprKind::Unary { expr, .. } | ExprKind::Box { expr } => {
             collect_call_context_expr(expr, cursor, best);
         }
         ExprKind::Member { base, .. } | ExprKind::OptionalMember { base, .. } => {
@@ -1273,7 +1357,13 @@ fn collect_call_context_expr(expr: &Expr, cursor: usize, best: &mut Option<CallC
                 collect_call_context_expr(&field.value, cursor, best);
             }
         }
-        ExprKind::ListLit(items) => {
+        ExprKind::RecordUpdate { base, fields } => {
+            collect_call_context_expr(base, cursor, best);
+            for field in fields {
+                collect_call_context_expr(&field.value, cursor, best);
+            }
+        }
+        ExprKind::ListLit(items) | ExprKind::TupleLit(items) => {
             for item in items {
                 collect_call_context_expr(item, cursor, best);
             }
@@ -1295,7 +1385,11 @@ fn collect_call_context_expr(expr: &Expr, cursor: usize, best: &mut Option<CallC
             collect_call_context_expr(left, cursor, best);
             collect_call_context_expr(right, cursor, best);
         }
-        ExprKind::BangChain { expr, error } => {
+        ExprKind::BangChain { expr, error }
+        | ExprKind::Await {
+            expr,
+            timeout: error,
+        } => {
             collect_call_context_expr(expr, cursor, best);
             if let Some(error) = error {
                 collect_call_context_expr(error, cursor, best);
@@ -1342,7 +1436,7 @@ fn collect_call_context_type_ref(ty: &TypeRef, cursor: usize, best: &mut Option<
     }
     match &ty.kind {
         TypeRefKind::Simple(_) => {}
-        TypeRefKind::Generic { args, .. } => {
+        TypeRefKind::Generic { args, .. } | TypeRefKind::Tuple(args) => {
             for arg in args {
                 collect_call_context_type_ref(arg, cursor, best);
             }
@@ -1961,6 +2055,17 @@ fn completion_callable_name_at_cursor(program: &Program, cursor: usize) -> Optio
                     }
                 }
             }
+            Item::Seed(decl) => {
+                if span_contains(decl.body.span, cursor) {
+                    let size = decl.body.span.end.saturating_sub(decl.body.span.start);
+                    if best
+                        .as_ref()
+                        .is_none_or(|(_, best_size)| size < *best_size)
+                    {
+                        best = Some((decl.name.value.clone(), size));
+                    }
+                }
+            }
             Item::Test(decl) => {
                 if span_contains(decl.body.span, cursor) {
                     let size = decl.body.span.end.saturating_sub(decl.body.span.start);
@@ -1983,7 +2088,14 @@ fn completion_callable_name_at_cursor(program: &Program, cursor: usize) -> Optio
                     }
                 }
             }
-            Item::Import(_) | Item::Type(_) | Item::Enum(_) | Item::Config(_) | Item::Interface(_) | Item::Impl(_) => {}
+            Item::Import(_)
+            | Item::Type(_)
+            | Item::TypeAlias(_)
+            | Item::Enum(_)
+            | Item::Config(_)
+            | Item::Const(_)
+            | Item::Interface(_)
+            | Item::Impl(_) => {}
         }
     }
 
@@ -2066,6 +2178,7 @@ fn completion_kind_for_symbol_kind(kind: SymbolKind) -> u32 {
         | SymbolKind::Test => 3,
         SymbolKind::Param | SymbolKind::Variable => 6,
         SymbolKind::Field => 5,
+        SymbolKind::Constant => 21,
     }
 }
 
@@ -2073,18 +2186,25 @@ fn builtin_receiver_methods(receiver: &str) -> &'static [&'static str] {
     match receiver {
         "db" => &[
             "exec",
+            "execute",
             "query",
             "one",
             "from",
+            "rollback",
             "select",
             "where",
+            "or_where",
             "all",
             "first",
             "limit",
             "offset",
             "order_by",
             "insert",
+            "insert_many",
             "upsert",
+            "on_conflict",
+            "do_update",
+            "do_nothing",
             "update",
             "delete",
             "set",
@@ -2093,6 +2213,8 @@ fn builtin_receiver_methods(receiver: &str) -> &'static [&'static str] {
             "right_join",
             "group_by",
             "having",
+            "aggregate",
+            "returning",
             "count",
         ],
         "json" => &["encode", "decode"],
@@ -2101,8 +2223,46 @@ fn builtin_receiver_methods(receiver: &str) -> &'static [&'static str] {
         "request" => &["header", "cookie"],
         "response" => &["header", "cookie", "delete_cookie"],
         "http" => &["request", "get", "post"],
-        "time" => &["now", "sleep", "format", "parse"],
+        "time" => &[
+            "now",
+            "sleep",
+            "format",
+            "parse",
+            "instant",
+            "today",
+            "from_unix_ms",
+            "unix_ms",
+            "millis",
+            "seconds",
+            "minutes",
+            "hours",
+            "days",
+            "to_millis",
+            "date",
+            "date_of",
+            "start_of",
+            "in_zone",
+            "instant_of",
+            "offset_of",
+            "local_date",
+            "format_instant",
+            "format_date",
+            "format_duration",
+            "format_datetime",
+            "parse_instant",
+            "parse_date",
+            "parse_duration",
+            "parse_datetime",
+        ],
         "crypto" => &["hash", "hmac", "random_bytes", "constant_time_eq"],
+        "decimal" => &[
+            "parse",
+            "from_int",
+            "from_units",
+            "round",
+            "scale",
+            "to_float",
+        ],
         _ => &[],
     }
 }
@@ -75,10 +75,10 @@ pub(crate) const COMPLETION_KEYWORDS: [&str; 35] = [
     "without",
     "spawn",
 ];
-pub(crate) const COMPLETION_BUILTIN_RECEIVERS: [&str; 9] = [
-    "db", "json", "html", "svg", "request", "response", "http", "time", "crypto",
+pub(crate) const COMPLETION_BUILTIN_RECEIVERS: [&str; 10] = [
+    "db", "json", "html", "svg", "request", "response", "http", "time", "crypto", "decimal",
 ];
-pub(crate) const COMPLETION_BUILTIN_FUNCTIONS: [&str; 9] = [
+pub(crate) const COMPLETION_BUILTIN_FUNCTIONS: [&str; 12] = [
     "print",
     "env",
     "env_int",
@@ -88,10 +88,13 @@ pub(crate) const COMPLETION_BUILTIN_FUNCTIONS: [&str; 9] = [
     "log",
     "assert",
     "asset",
+    "await_all",
+    "await_any",
+    "channel",
 ];
-pub(crate) const COMPLETION_BUILTIN_TYPES: [&str; 14] = [
+pub(crate) const COMPLETION_BUILTIN_TYPES: [&str; 20] = [
     "Unit", "Int", "Float", "Bool", "String", "Bytes", "Html", "Id", "Email", "Error", "List",
-    "Map", "Task", "Range",
+    "Map", "Task", "Channel", "Range", "Instant", "Date", "Duration", "DateTime", "Decimal",
 ];
 pub(crate) const STD_ERROR_MODULE_SOURCE: &str = r#"
 type Error:
@@ -667,6 +670,7 @@ pub(crate) fn is_renamable_symbol_kind(kind: SymbolKind) -> bool {
             | SymbolKind::EnumVariant
             | SymbolKind::Function
             | SymbolKind::Config
+            | SymbolKind::Constant
             | SymbolKind::Param
             | SymbolKind::Variable
             | SymbolKind::Field
@@ -74,7 +74,11 @@ fn symbol_kind_nav_tier(kind: SymbolKind) -> u8 {
         | SymbolKind::App
         | SymbolKind::Migration
         | SymbolKind::Test => 0,
-        SymbolKind::Type | SymbolKind::Interface | SymbolKind::Enum | SymbolKind::Config => 1,
+        SymbolKind::Type
+        | SymbolKind::Interface
+        | SymbolKind::Enum
+        | SymbolKind::Config
+        | SymbolKind::Constant => 1,
         SymbolKind::EnumVariant | SymbolKind::Field | SymbolKind::Module => 2,
         SymbolKind::Param | SymbolKind::Variable => 3,
     }
@@ -665,10 +669,17 @@ fn interface_impl_skeleton_actions(
             if existing_pairs.contains(&pair) {
                 continue;
             }
+            let required: Vec<_> = interface
+                .decl
+                .members
+                .iter()
+                .filter(|member| member.body.is_none())
+                .cloned()
+                .collect();
             let skeleton = render_impl_skeleton(
                 &interface.decl.name.name,
                 type_name,
-                &interface.decl.members,
+                &required,
                 &interface.text,
             );
             let mut new_text = String::new();
@@ -857,9 +868,9 @@ fn find_call_args_in_expr<'a>(expr: &'a Expr, target: Span) -> Option<&'a [CallA
             }
             return find_call_args_in_expr(right, target);
         }
-        ExprKind::Unary { expr: inner, .. }
-        | ExprKind::Await { expr: inner }
-        | ExprKind::Box { expr: inner } => return find_call_args_in_expr(inner, target),
+        ExprKind::Unary { expr: inner, .. } | ExprKind::Box { expr: inner } => {
+            return find_call_args_in_expr(inner, target);
+        }
         ExprKind::Member { base, .. } | ExprKind::OptionalMember { base, .. } => {
             return find_call_args_in_expr(base, target)
         }
@@ -876,7 +887,17 @@ fn find_call_args_in_expr<'a>(expr: &'a Expr, target: Span) -> Option<&'a [CallA
                 }
             }
         }
-        ExprKind::ListLit(items) => {
+        ExprKind::RecordUpdate { base, fields } => {
+            if let Some(found) = find_call_args_in_expr(base, target) {
+                return Some(found);
+            }
+            for f in fields {
+                if let Some(found) = find_call_args_in_expr(&f.value, target) {
+                    return Some(found);
+                }
+            }
+        }
+        ExprKind::ListLit(items) | ExprKind::TupleLit(items) => {
             for item in items {
                 if let Some(found) = find_call_args_in_expr(item, target) {
                     return Some(found);
@@ -896,7 +917,11 @@ fn find_call_args_in_expr<'a>(expr: &'a Expr, target: Span) -> Option<&'a [CallA
             }
             return find_call_args_in_expr(right, target);
         }
-        ExprKind::BangChain { expr: inner, error } => {
+        ExprKind::BangChain { expr: inner, error }
+        | ExprKind::Await {
+            expr: inner,
+            timeout: error,
+        } => {
             if let Some(f) = find_call_args_in_expr(inner, target) {
                 return Some(f);
             }
@@ -969,9 +994,10 @@ fn find_call_args_in_block<'a>(block: &'a Block, target: Span) -> Option<&'a [Ca
 
 fn find_call_args_in_stmt<'a>(stmt: &'a Stmt, target: Span) -> Option<&'a [CallArg]> {
     match &stmt.kind {
-        StmtKind::Let { expr, .. } | StmtKind::Var { expr, .. } | StmtKind::Expr(expr) => {
-            find_call_args_in_expr(expr, target)
-        }
+        StmtKind::Let { expr, .. }
+        | StmtKind::Var { expr, .. }
+        | StmtKind::LetPattern { expr, .. }
+        | StmtKind::Expr(expr) => find_call_args_in_expr(expr, target),
         StmtKind::Return { expr: Some(expr) } => find_call_args_in_expr(expr, target),
         StmtKind::Return { expr: None } => None,
         StmtKind::While { cond, block } => find_call_args_in_expr(cond, target)
@@ -1001,8 +1027,15 @@ fn find_call_args_in_stmt<'a>(stmt: &'a Stmt, target: Span) -> Option<&'a [CallA
             if let Some(f) = find_call_args_in_expr(expr, target) {
                 return Some(f);
             }
-            for (_, block) in cases {
-                if let Some(f) = find_call_args_in_block(block, target) {
+            for case in cases {
+                if let Some(f) = case
+                    .guard
+                    .as_ref()
+                    .and_then(|guard| find_call_args_in_expr(guard, target))
+                {
+                    return Some(f);
+                }
+                if let Some(f) = find_call_args_in_block(&case.block, target) {
                     return Some(f);
                 }
             }
@@ -1028,7 +1061,12 @@ fn find_call_args_in_item<'a>(item: &'a Item, target: Span) -> Option<&'a [CallA
         }
         Item::Component(decl) => find_call_args_in_block(&decl.body, target),
         Item::App(decl) => find_call_args_in_block(&decl.body, target),
-        Item::Migration(decl) => find_call_args_in_block(&decl.body, target),
+        Item::Migration(decl) => find_call_args_in_block(&decl.body, target).or_else(|| {
+            decl.down
+                .as_ref()
+                .and_then(|down| find_call_args_in_block(down, target))
+        }),
+        Item::Seed(decl) => find_call_args_in_block(&decl.body, target),
         Item::Test(decl) => find_call_args_in_block(&decl.body, target),
         Item::Config(decl) => {
             for field in &decl.fields {
@@ -1048,7 +1086,12 @@ fn find_call_args_in_item<'a>(item: &'a Item, target: Span) -> Option<&'a [CallA
             }
             None
         }
-        Item::Import(_) | Item::Enum(_) | Item::Interface(_) | Item::Impl(_) => None,
+        Item::Const(decl) => find_call_args_in_expr(&decl.value, target),
+        Item::Import(_)
+        | Item::TypeAlias(_)
+        | Item::Enum(_)
+        | Item::Interface(_)
+        | Item::Impl(_) => None,
     }
 }
 
@@ -1196,9 +1239,17 @@ fn map_literal_attr_pairs_in_item(item: &Item, span: Span) -> Option<Vec<(String
             }
             None
         }
+        Item::Const(decl) => map_literal_attr_pairs_in_type_ref(&decl.ty, span)
+            .or_else(|| map_literal_attr_pairs_in_expr(&decl.value, span)),
+        Item::TypeAlias(decl) => map_literal_attr_pairs_in_type_ref(&decl.target, span),
         Item::Component(decl) => map_literal_attr_pairs_in_block(&decl.body, span),
         Item::App(decl) => map_literal_attr_pairs_in_block(&decl.body, span),
-        Item::Migration(decl) => map_literal_attr_pairs_in_block(&decl.body, span),
+        Item::Migration(decl) => map_literal_attr_pairs_in_block(&decl.body, span).or_else(|| {
+            decl.down
+                .as_ref()
+                .and_then(|down| map_literal_attr_pairs_in_block(down, span))
+        }),
+        Item::Seed(decl) => map_literal_attr_pairs_in_block(&decl.body, span),
         Item::Test(decl) => map_literal_attr_pairs_in_block(&decl.body, span),
         Item::Interface(_) | Item::Impl(_) => None,
     }
@@ -1221,7 +1272,9 @@ fn map_literal_attr_pairs_in_stmt(stmt: &Stmt, span: Span) -> Option<Vec<(String
         return None;
     }
     match &stmt.kind {
-        StmtKind::Let { ty, expr, .. } | StmtKind::Var { ty, expr, .. } => {
+        StmtKind::Let { ty, expr, .. }
+        | StmtKind::Var { ty, expr, .. }
+        | StmtKind::LetPattern { ty, expr, .. } => {
             if let Some(ty) = ty {
                 if let Some(pairs) = map_literal_attr_pairs_in_type_ref(ty, span) {
                     return Some(pairs);
@@ -1262,8 +1315,8 @@ fn map_literal_attr_pairs_in_stmt(stmt: &Stmt, span: Span) -> Option<Vec<(String
             if let Some(pairs) = map_literal_attr_pairs_in_expr(expr, span) {
                 return Some(pairs);
             }
-            for (_, block) in cases {
-                if let Some(pairs) = map_literal_attr_pairs_in_block(block, span) {
+            for case in cases {
+                if let Some(pairs) = map_literal_attr_pairs_in_block(&case.block, span) {
                     return Some(pairs);
                 }
             }
@@ -1302,7 +1355,7 @@ fn map_literal_attr_pairs_in_expr(expr: &Expr, span: Span) -> Option<Vec<(String
         }
         ExprKind::Binary { left, right, .. } => map_literal_attr_pairs_in_expr(left, span)
             .or_else(|| map_literal_attr_pairs_in_expr(right, span)),
-        ExprKind::Unary { expr, .. } | ExprKind::Await { expr } | ExprKind::Box { expr } => {
+        ExprKind::Unary { expr, .. } | ExprKind::Box { expr } => {
             map_literal_attr_pairs_in_expr(expr, span)
         }
         ExprKind::Member { base, .. } | ExprKind::OptionalMember { base, .. } => {
@@ -1320,7 +1373,18 @@ fn map_literal_attr_pairs_in_expr(expr: &Expr, span: Span) -> Option<Vec<(String
             }
             None
         }
-        ExprKind::ListLit(items) => {
+        ExprKind::RecordUpdate { base, fields } => {
+            if let Some(pairs) = map_literal_attr_pairs_in_expr(base, span) {
+                return Some(pairs);
+            }
+            for field in fields {
+                if let Some(pairs) = map_literal_attr_pairs_in_expr(&field.value, span) {
+                    return Some(pairs);
+                }
+            }
+            None
+        }
+        ExprKind::ListLit(items) | ExprKind::TupleLit(items) => {
             for item in items {
                 if let Some(pairs) = map_literal_attr_pairs_in_expr(item, span) {
                     return Some(pairs);
@@ -1351,7 +1415,11 @@ fn map_literal_attr_pairs_in_expr(expr: &Expr, span: Span) -> Option<Vec<(String
         }
         ExprKind::Coalesce { left, right } => map_literal_attr_pairs_in_expr(left, span)
             .or_else(|| map_literal_attr_pairs_in_expr(right, span)),
-        ExprKind::BangChain { expr, error } => {
+        ExprKind::BangChain { expr, error }
+        | ExprKind::Await {
+            expr,
+            timeout: error,
+        } => {
             map_literal_attr_pairs_in_expr(expr, span).or_else(|| {
                 error
                     .as_ref()
@@ -1415,7 +1483,7 @@ fn map_literal_attr_pairs_in_type_ref(ty: &TypeRef, span: Span) -> Option<Vec<(S
     }
     match &ty.kind {
         TypeRefKind::Simple(_) => None,
-        TypeRefKind::Generic { args, .. } => {
+        TypeRefKind::Generic { args, .. } | TypeRefKind::Tuple(args) => {
             for arg in args {
                 if let Some(pairs) = map_literal_attr_pairs_in_type_ref(arg, span) {
                     return Some(pairs);
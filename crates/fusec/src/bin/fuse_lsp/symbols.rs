@@ -94,6 +94,7 @@ pub(crate) enum SymbolKind {
     Param,
     Variable,
     Field,
+    Constant,
 }
 
 impl SymbolKind {
@@ -113,6 +114,7 @@ impl SymbolKind {
             SymbolKind::Param => 13,
             SymbolKind::Variable => 13,
             SymbolKind::Field => 8,
+            SymbolKind::Constant => 14,
         }
     }
 
@@ -132,6 +134,7 @@ impl SymbolKind {
             SymbolKind::Param => 11,
             SymbolKind::Variable => 12,
             SymbolKind::Field => 13,
+            SymbolKind::Constant => 14,
         }
     }
 
@@ -151,6 +154,7 @@ impl SymbolKind {
             11 => Some(SymbolKind::Param),
             12 => Some(SymbolKind::Variable),
             13 => Some(SymbolKind::Field),
+            14 => Some(SymbolKind::Constant),
             _ => None,
         }
     }
@@ -171,6 +175,7 @@ impl SymbolKind {
             SymbolKind::Param => "Parameter",
             SymbolKind::Variable => "Variable",
             SymbolKind::Field => "Field",
+            SymbolKind::Constant => "Constant",
         }
     }
 }
@@ -226,11 +231,25 @@ pub(crate) fn collect_qualified_refs(program: &Program) -> Vec<QualifiedNameRef>
                     collect_qualified_expr(&field.value, &mut out);
                 }
             }
+            Item::Const(decl) => {
+                collect_qualified_type_ref(&decl.ty, &mut out);
+                collect_qualified_expr(&decl.value, &mut out);
+            }
+            Item::TypeAlias(decl) => collect_qualified_type_ref(&decl.target, &mut out),
             Item::Component(decl) => collect_qualified_block(&decl.body, &mut out),
             Item::App(decl) => collect_qualified_block(&decl.body, &mut out),
-            Item::Migration(decl) => collect_qualified_block(&decl.body, &mut out),
+            Item::Migration(decl) => {
+                collect_qualified_block(&decl.body, &mut out);
+                if let Some(down) = &decl.down {
+                    collect_qualified_block(down, &mut out);
+                }
+            }
+            Item::Seed(decl) => collect_qualified_block(&decl.body, &mut out),
             Item::Test(decl) => collect_qualified_block(&decl.body, &mut out),
             Item::Interface(decl) => {
+                for parent in &decl.extends {
+                    collect_qualified_ident_ref(parent, &mut out);
+                }
                 for member in &decl.members {
                     for param in &member.params {
                         collect_qualified_type_ref(&param.ty, &mut out);
@@ -238,6 +257,9 @@ pub(crate) fn collect_qualified_refs(program: &Program) -> Vec<QualifiedNameRef>
                     if let Some(ret) = &member.ret {
                         collect_qualified_type_ref(ret, &mut out);
                     }
+                    if let Some(body) = &member.body {
+                        collect_qualified_block(body, &mut out);
+                    }
                 }
             }
             Item::Impl(decl) => {
@@ -273,6 +295,13 @@ fn collect_qualified_stmt(stmt: &Stmt, out: &mut Vec<QualifiedNameRef>) {
             }
             collect_qualified_expr(expr, out);
         }
+        StmtKind::LetPattern { pat, ty, expr, .. } => {
+            collect_qualified_pattern(pat, out);
+            if let Some(ty) = ty {
+                collect_qualified_type_ref(ty, out);
+            }
+            collect_qualified_expr(expr, out);
+        }
         StmtKind::Assign { target, expr } => {
             collect_qualified_expr(target, out);
             collect_qualified_expr(expr, out);
@@ -300,9 +329,12 @@ fn collect_qualified_stmt(stmt: &Stmt, out: &mut Vec<QualifiedNameRef>) {
         }
         StmtKind::Match { expr, cases } => {
             collect_qualified_expr(expr, out);
-            for (pat, block) in cases {
-                collect_qualified_pattern(pat, out);
-                collect_qualified_block(block, out);
+            for case in cases {
+                collect_qualified_pattern(&case.pat, out);
+                if let Some(guard) = &case.guard {
+                    collect_qualified_expr(guard, out);
+                }
+                collect_qualified_block(&case.block, out);
             }
         }
         StmtKind::For { pat, iter, block } => {
@@ -385,7 +417,13 @@ fn collect_qualified_expr(expr: &Expr, out: &mut Vec<QualifiedNameRef>) {
                 collect_qualified_expr(&field.value, out);
             }
         }
-        ExprKind::ListLit(items) => {
+        ExprKind::RecordUpdate { base, fields } => {
+            collect_qualified_expr(base, out);
+            for field in fields {
+                collect_qualified_expr(&field.value, out);
+            }
+        }
+        ExprKind::ListLit(items) | ExprKind::TupleLit(items) => {
             for item in items {
                 collect_qualified_expr(item, out);
             }
@@ -411,7 +449,11 @@ fn collect_qualified_expr(expr: &Expr, out: &mut Vec<QualifiedNameRef>) {
             collect_qualified_expr(left, out);
             collect_qualified_expr(right, out);
         }
-        ExprKind::BangChain { expr, error } => {
+        ExprKind::BangChain { expr, error }
+        | ExprKind::Await {
+            expr,
+            timeout: error,
+        } => {
             collect_qualified_expr(expr, out);
             if let Some(err) = error {
                 collect_qualified_expr(err, out);
@@ -449,15 +491,20 @@ fn collect_qualified_expr(expr: &Expr, out: &mut Vec<QualifiedNameRef>) {
                 collect_qualified_expr(child, out);
             }
         }
-        ExprKind::Await { expr } => collect_qualified_expr(expr, out),
         ExprKind::Box { expr } => collect_qualified_expr(expr, out),
     }
 }
 
 fn collect_qualified_pattern(pattern: &Pattern, out: &mut Vec<QualifiedNameRef>) {
     match &pattern.kind {
-        PatternKind::Wildcard | PatternKind::Literal(_) => {}
+        PatternKind::Wildcard | PatternKind::Literal(_) | PatternKind::Range { .. } => {}
         PatternKind::Ident(_) => {}
+        PatternKind::Bind { pat, .. } => collect_qualified_pattern(pat, out),
+        PatternKind::Or(alts) => {
+            for alt in alts {
+                collect_qualified_pattern(alt, out);
+            }
+        }
         PatternKind::EnumVariant { name, args } => {
             if let Some((module, item)) = split_qualified_name(&name.name) {
                 out.push(QualifiedNameRef {
@@ -484,6 +531,11 @@ fn collect_qualified_pattern(pattern: &Pattern, out: &mut Vec<QualifiedNameRef>)
                 collect_qualified_pattern(&field.pat, out);
             }
         }
+        PatternKind::Tuple(items) => {
+            for item in items {
+                collect_qualified_pattern(item, out);
+            }
+        }
     }
 }
 
@@ -524,6 +576,11 @@ fn collect_qualified_type_ref(ty: &TypeRef, out: &mut Vec<QualifiedNameRef>) {
             }
         }
         TypeRefKind::Optional(inner) => collect_qualified_type_ref(inner, out),
+        TypeRefKind::Tuple(items) => {
+            for item in items {
+                collect_qualified_type_ref(item, out);
+            }
+        }
         TypeRefKind::Result { ok, err } => {
             collect_qualified_type_ref(ok, out);
             if let Some(err) = err {
@@ -566,6 +623,7 @@ pub(crate) struct IndexBuilder<'a> {
     globals: HashMap<String, usize>,
     app_defs: HashMap<String, usize>,
     migration_defs: HashMap<String, usize>,
+    seed_defs: HashMap<String, usize>,
     test_defs: HashMap<String, usize>,
     type_defs: HashMap<String, usize>,
     enum_variants: HashMap<String, usize>,
@@ -586,6 +644,7 @@ impl<'a> IndexBuilder<'a> {
             globals: HashMap::new(),
             app_defs: HashMap::new(),
             migration_defs: HashMap::new(),
+            seed_defs: HashMap::new(),
             test_defs: HashMap::new(),
             type_defs: HashMap::new(),
             enum_variants: HashMap::new(),
@@ -616,6 +675,21 @@ impl<'a> IndexBuilder<'a> {
             match item {
                 Item::Import(decl) => self.define_import(decl),
                 Item::Type(decl) => self.define_type(decl),
+                Item::TypeAlias(decl) => {
+                    let detail = format!(
+                        "type {} = {}",
+                        decl.name.name,
+                        self.type_ref_text(&decl.target)
+                    );
+                    let def_id = self.define_global(
+                        &decl.name,
+                        SymbolKind::Type,
+                        detail,
+                        decl.doc.as_ref(),
+                        None,
+                    );
+                    self.type_defs.insert(decl.name.name.clone(), def_id);
+                }
                 Item::Enum(decl) => self.define_enum(decl),
                 Item::Fn(decl) => {
                     self.define_global(
@@ -635,6 +709,21 @@ impl<'a> IndexBuilder<'a> {
                         None,
                     );
                 }
+                Item::Const(decl) => {
+                    let detail = format!(
+                        "const {}: {} = {}",
+                        decl.name.name,
+                        self.type_ref_text(&decl.ty),
+                        self.slice_span(decl.value.span).trim()
+                    );
+                    self.define_global(
+                        &decl.name,
+                        SymbolKind::Constant,
+                        detail,
+                        decl.doc.as_ref(),
+                        None,
+                    );
+                }
                 Item::Service(decl) => {
                     self.define_global(
                         &decl.name,
@@ -665,6 +754,16 @@ impl<'a> IndexBuilder<'a> {
                     );
                     self.migration_defs.insert(decl.name.clone(), def_id);
                 }
+                Item::Seed(decl) => {
+                    let detail = format!("seed \"{}\"", decl.name.value);
+                    let def_id = self.define_literal_decl(
+                        &decl.name,
+                        SymbolKind::Migration,
+                        detail,
+                        decl.doc.as_ref(),
+                    );
+                    self.seed_defs.insert(decl.name.value.clone(), def_id);
+                }
                 Item::Test(decl) => {
                     let detail = format!("test \"{}\"", decl.name.value);
                     let def_id = self.define_literal_decl(
@@ -823,7 +922,12 @@ impl<'a> IndexBuilder<'a> {
                 self.visit_fn_decl(decl);
                 self.current_callable = prev;
             }
+            Item::TypeAlias(decl) => self.visit_type_ref(&decl.target),
             Item::Config(decl) => self.visit_config_decl(decl),
+            Item::Const(decl) => {
+                self.visit_type_ref(&decl.ty);
+                self.visit_expr(&decl.value);
+            }
             Item::Service(decl) => {
                 let prev = self.current_callable;
                 self.current_callable = self.globals.get(&decl.name.name).copied();
@@ -840,6 +944,15 @@ impl<'a> IndexBuilder<'a> {
                 let prev = self.current_callable;
                 self.current_callable = self.migration_defs.get(&decl.name).copied();
                 self.visit_block(&decl.body);
+                if let Some(down) = &decl.down {
+                    self.visit_block(down);
+                }
+                self.current_callable = prev;
+            }
+            Item::Seed(decl) => {
+                let prev = self.current_callable;
+                self.current_callable = self.seed_defs.get(&decl.name.value).copied();
+                self.visit_block(&decl.body);
                 self.current_callable = prev;
             }
             Item::Test(decl) => {
@@ -880,13 +993,45 @@ impl<'a> IndexBuilder<'a> {
     }
 
     fn visit_interface_decl(&mut self, decl: &InterfaceDecl) {
+        for parent in &decl.extends {
+            self.add_type_ref(parent);
+        }
         for member in &decl.members {
+            let Some(body) = &member.body else {
+                for param in &member.params {
+                    self.visit_type_ref(&param.ty);
+                }
+                if let Some(ret) = &member.ret {
+                    self.visit_type_ref(ret);
+                }
+                continue;
+            };
+            self.enter_scope();
+            let container = self.current_container();
             for param in &member.params {
+                let detail = format!(
+                    "param {}: {}",
+                    param.name.name,
+                    self.type_ref_text(&param.ty)
+                );
+                let def_id = self.define_local(
+                    &param.name,
+                    SymbolKind::Param,
+                    detail,
+                    None,
+                    container.clone(),
+                );
+                self.insert_local(&param.name.name, def_id);
                 self.visit_type_ref(&param.ty);
+                if let Some(expr) = &param.default {
+                    self.visit_expr(expr);
+                }
             }
             if let Some(ret) = &member.ret {
                 self.visit_type_ref(ret);
             }
+            self.visit_block_body(body);
+            self.exit_scope();
         }
     }
 
@@ -905,12 +1050,17 @@ impl<'a> IndexBuilder<'a> {
         self.enter_scope();
         let container = self.current_container();
         for tp in &decl.type_params {
-            let bound = decl
+            let bounds: Vec<&str> = decl
                 .where_clause
                 .iter()
-                .find(|c| c.type_param.name == tp.name.name)
-                .map(|c| format!(": {}", c.interface.name))
-                .unwrap_or_default();
+                .filter(|c| c.type_param.name == tp.name.name)
+                .map(|c| c.interface.name.as_str())
+                .collect();
+            let bound = if bounds.is_empty() {
+                String::new()
+            } else {
+                format!(": {}", bounds.join(" + "))
+            };
             let detail = format!("type param {}{}", tp.name.name, bound);
             let def_id = self.define_local(
                 &tp.name,
@@ -1020,6 +1170,13 @@ impl<'a> IndexBuilder<'a> {
                     self.define_local(name, SymbolKind::Variable, detail, None, container.clone());
                 self.insert_local(&name.name, def_id);
             }
+            StmtKind::LetPattern { pat, ty, expr, .. } => {
+                if let Some(ty) = ty {
+                    self.visit_type_ref(ty);
+                }
+                self.visit_expr(expr);
+                self.visit_pattern(pat);
+            }
             StmtKind::Var { name, ty, expr } => {
                 if let Some(ty) = ty {
                     self.visit_type_ref(ty);
@@ -1060,10 +1217,13 @@ impl<'a> IndexBuilder<'a> {
             }
             StmtKind::Match { expr, cases } => {
                 self.visit_expr(expr);
-                for (pat, block) in cases {
+                for case in cases {
                     self.enter_scope();
-                    self.visit_pattern(pat);
-                    self.visit_block_body(block);
+                    self.visit_pattern(&case.pat);
+                    if let Some(guard) = &case.guard {
+                        self.visit_expr(guard);
+                    }
+                    self.visit_block_body(&case.block);
                     self.exit_scope();
                 }
             }
@@ -1142,7 +1302,13 @@ impl<'a> IndexBuilder<'a> {
                     self.visit_expr(&field.value);
                 }
             }
-            ExprKind::ListLit(items) => {
+            ExprKind::RecordUpdate { base, fields } => {
+                self.visit_expr(base);
+                for field in fields {
+                    self.visit_expr(&field.value);
+                }
+            }
+            ExprKind::ListLit(items) | ExprKind::TupleLit(items) => {
                 for item in items {
                     self.visit_expr(item);
                 }
@@ -1164,7 +1330,11 @@ impl<'a> IndexBuilder<'a> {
                 self.visit_expr(left);
                 self.visit_expr(right);
             }
-            ExprKind::BangChain { expr, error } => {
+            ExprKind::BangChain { expr, error }
+            | ExprKind::Await {
+                expr,
+                timeout: error,
+            } => {
                 self.visit_expr(expr);
                 if let Some(err) = error {
                     self.visit_expr(err);
@@ -1204,7 +1374,6 @@ impl<'a> IndexBuilder<'a> {
                 }
                 self.exit_scope();
             }
-            ExprKind::Await { expr } => self.visit_expr(expr),
             ExprKind::Box { expr } => self.visit_expr(expr),
         }
     }
@@ -1219,6 +1388,11 @@ impl<'a> IndexBuilder<'a> {
                 }
             }
             TypeRefKind::Optional(inner) => self.visit_type_ref(inner),
+            TypeRefKind::Tuple(items) => {
+                for item in items {
+                    self.visit_type_ref(item);
+                }
+            }
             TypeRefKind::Result { ok, err } => {
                 self.visit_type_ref(ok);
                 if let Some(err) = err {
@@ -1262,6 +1436,30 @@ impl<'a> IndexBuilder<'a> {
                     self.visit_pattern(&field.pat);
                 }
             }
+            PatternKind::Tuple(items) => {
+                for item in items {
+                    self.visit_pattern(item);
+                }
+            }
+            PatternKind::Bind { name, pat } => {
+                self.visit_pattern(pat);
+                let detail = format!("let {}", name.name);
+                let def_id = self.define_local(
+                    name,
+                    SymbolKind::Variable,
+                    detail,
+                    None,
+                    self.current_container(),
+                );
+                self.insert_local(&name.name, def_id);
+            }
+            // Every alternative binds the same names; the first one defines them.
+            PatternKind::Or(alts) => {
+                if let Some(first) = alts.first() {
+                    self.visit_pattern(first);
+                }
+            }
+            PatternKind::Range { .. } => {}
         }
     }
 
@@ -1522,6 +1720,11 @@ fn is_builtin_type(name: &str) -> bool {
             | "Html"
             | "Id"
             | "Email"
+            | "Instant"
+            | "Date"
+            | "Duration"
+            | "DateTime"
+            | "Decimal"
             | "Error"
             | "List"
             | "Map"
@@ -417,14 +417,22 @@ fn collect_html_semantic_spans(program: &Program) -> HtmlSemanticSpans {
             }
             Item::Migration(decl) => {
                 collect_html_semantic_spans_block(&decl.body, &component_names, &mut out);
+                if let Some(down) = &decl.down {
+                    collect_html_semantic_spans_block(down, &component_names, &mut out);
+                }
+            }
+            Item::Seed(decl) => {
+                collect_html_semantic_spans_block(&decl.body, &component_names, &mut out);
             }
             Item::Test(decl) => {
                 collect_html_semantic_spans_block(&decl.body, &component_names, &mut out);
             }
             Item::Import(_)
             | Item::Type(_)
+            | Item::TypeAlias(_)
             | Item::Enum(_)
             | Item::Config(_)
+            | Item::Const(_)
             | Item::Interface(_)
             | Item::Impl(_) => {}
         }
@@ -439,7 +447,9 @@ fn collect_html_semantic_spans_block(
 ) {
     for stmt in &block.stmts {
         match &stmt.kind {
-            StmtKind::Let { expr, .. } | StmtKind::Var { expr, .. } => {
+            StmtKind::Let { expr, .. }
+            | StmtKind::Var { expr, .. }
+            | StmtKind::LetPattern { expr, .. } => {
                 collect_html_semantic_spans_expr(expr, component_names, out);
             }
             StmtKind::Assign { target, expr } => {
@@ -469,8 +479,11 @@ fn collect_html_semantic_spans_block(
             }
             StmtKind::Match { expr, cases } => {
                 collect_html_semantic_spans_expr(expr, component_names, out);
-                for (_, case_block) in cases {
-                    collect_html_semantic_spans_block(case_block, component_names, out);
+                for case in cases {
+                    if let Some(guard) = &case.guard {
+                        collect_html_semantic_spans_expr(guard, component_names, out);
+                    }
+                    collect_html_semantic_spans_block(&case.block, component_names, out);
                 }
             }
             StmtKind::For { iter, block, .. } => {
@@ -526,7 +539,7 @@ fn collect_html_semantic_spans_expr(
             collect_html_semantic_spans_expr(left, component_names, out);
             collect_html_semantic_spans_expr(right, component_names, out);
         }
-        ExprKind::Unary { expr, .. } | ExprKind::Await { expr } | ExprKind::Box { expr } => {
+        ExprKind::Unary { expr, .. } | ExprKind::Box { expr } => {
             collect_html_semantic_spans_expr(expr, component_names, out);
         }
         ExprKind::Member { base, .. } | ExprKind::OptionalMember { base, .. } => {
@@ -548,7 +561,13 @@ fn collect_html_semantic_spans_expr(
                 collect_html_semantic_spans_expr(&field.value, component_names, out);
             }
         }
-        ExprKind::ListLit(items) => {
+        ExprKind::RecordUpdate { base, fields } => {
+            collect_html_semantic_spans_expr(base, component_names, out);
+            for field in fields {
+                collect_html_semantic_spans_expr(&field.value, component_names, out);
+            }
+        }
+        ExprKind::ListLit(items) | ExprKind::TupleLit(items) => {
             for item in items {
                 collect_html_semantic_spans_expr(item, component_names, out);
             }
@@ -566,7 +585,11 @@ fn collect_html_semantic_spans_expr(
                 }
             }
         }
-        ExprKind::BangChain { expr, error } => {
+        ExprKind::BangChain { expr, error }
+        | ExprKind::Await {
+            expr,
+            timeout: error,
+        } => {
             collect_html_semantic_spans_expr(expr, component_names, out);
             if let Some(error) = error {
                 collect_html_semantic_spans_expr(error, component_names, out);
@@ -760,7 +783,15 @@ fn is_type_context(tokens: &[fusec::token::Token], idx: usize) -> bool {
 fn is_builtin_receiver(name: &str) -> bool {
     matches!(
         name,
-        "db" | "json" | "html" | "svg" | "request" | "response" | "http" | "time" | "crypto"
+        "db" | "json"
+            | "html"
+            | "svg"
+            | "request"
+            | "response"
+            | "http"
+            | "time"
+            | "crypto"
+            | "decimal"
     )
 }
 
@@ -776,6 +807,9 @@ fn is_builtin_function_name(name: &str) -> bool {
             | "log"
             | "assert"
             | "asset"
+            | "await_all"
+            | "await_any"
+            | "channel"
     ) || fusec::html_tags::is_html_tag(name)
 }
 
@@ -791,10 +825,16 @@ fn is_builtin_type_name(name: &str) -> bool {
             | "Html"
             | "Id"
             | "Email"
+            | "Instant"
+            | "Date"
+            | "Duration"
+            | "DateTime"
+            | "Decimal"
             | "Error"
             | "List"
             | "Map"
             | "Task"
+            | "Channel"
             | "Range"
     )
 }
@@ -846,7 +886,17 @@ pub(crate) fn handle_inlay_hints(
             Item::App(decl) => collect_inlay_hints_block(
                 index, &uri, &text, &offsets, &decl.body, range, &mut hints, &mut seen,
             ),
-            Item::Migration(decl) => collect_inlay_hints_block(
+            Item::Migration(decl) => {
+                collect_inlay_hints_block(
+                    index, &uri, &text, &offsets, &decl.body, range, &mut hints, &mut seen,
+                );
+                if let Some(down) = &decl.down {
+                    collect_inlay_hints_block(
+                        index, &uri, &text, &offsets, down, range, &mut hints, &mut seen,
+                    );
+                }
+            }
+            Item::Seed(decl) => collect_inlay_hints_block(
                 index, &uri, &text, &offsets, &decl.body, range, &mut hints, &mut seen,
             ),
             Item::Test(decl) => collect_inlay_hints_block(
@@ -870,7 +920,7 @@ fn semantic_type_for_symbol_kind(kind: SymbolKind) -> Option<usize> {
         | SymbolKind::Migration
         | SymbolKind::Test => Some(SEM_FUNCTION),
         SymbolKind::Param => Some(SEM_PARAMETER),
-        SymbolKind::Variable => Some(SEM_VARIABLE),
+        SymbolKind::Variable | SymbolKind::Constant => Some(SEM_VARIABLE),
         SymbolKind::Field => Some(SEM_PROPERTY),
     }
 }
@@ -922,6 +972,9 @@ fn collect_inlay_hints_block(
                 }
                 collect_inlay_hints_expr(index, uri, text, offsets, expr, range, hints, seen);
             }
+            StmtKind::LetPattern { expr, .. } => {
+                collect_inlay_hints_expr(index, uri, text, offsets, expr, range, hints, seen);
+            }
             StmtKind::Assign { target, expr } => {
                 collect_inlay_hints_expr(index, uri, text, offsets, target, range, hints, seen);
                 collect_inlay_hints_expr(index, uri, text, offsets, expr, range, hints, seen);
@@ -957,9 +1010,21 @@ fn collect_inlay_hints_block(
             }
             StmtKind::Match { expr, cases } => {
                 collect_inlay_hints_expr(index, uri, text, offsets, expr, range, hints, seen);
-                for (_, case_block) in cases {
+                for case in cases {
+                    if let Some(guard) = &case.guard {
+                        collect_inlay_hints_expr(
+                            index, uri, text, offsets, guard, range, hints, seen,
+                        );
+                    }
                     collect_inlay_hints_block(
-                        index, uri, text, offsets, case_block, range, hints, seen,
+                        index,
+                        uri,
+                        text,
+                        offsets,
+                        &case.block,
+                        range,
+                        hints,
+                        seen,
                     );
                 }
             }
@@ -1030,7 +1095,7 @@ fn collect_inlay_hints_expr(
             collect_inlay_hints_expr(index, uri, text, offsets, left, range, hints, seen);
             collect_inlay_hints_expr(index, uri, text, offsets, right, range, hints, seen);
         }
-        ExprKind::Unary { expr, .. } | ExprKind::Await { expr } | ExprKind::Box { expr } => {
+        ExprKind::Unary { expr, .. } | ExprKind::Box { expr } => {
             collect_inlay_hints_expr(index, uri, text, offsets, expr, range, hints, seen);
         }
         ExprKind::Member { base, .. } | ExprKind::OptionalMember { base, .. } => {
@@ -1061,7 +1126,22 @@ fn collect_inlay_hints_expr(
                 );
             }
         }
-        ExprKind::ListLit(items) => {
+        ExprKind::RecordUpdate { base, fields } => {
+            collect_inlay_hints_expr(index, uri, text, offsets, base, range, hints, seen);
+            for field in fields {
+                collect_inlay_hints_expr(
+                    index,
+                    uri,
+                    text,
+                    offsets,
+                    &field.value,
+                    range,
+                    hints,
+                    seen,
+                );
+            }
+        }
+        ExprKind::ListLit(items) | ExprKind::TupleLit(items) => {
             for item in items {
                 collect_inlay_hints_expr(index, uri, text, offsets, item, range, hints, seen);
             }
@@ -1083,7 +1163,11 @@ fn collect_inlay_hints_expr(
             collect_inlay_hints_expr(index, uri, text, offsets, left, range, hints, seen);
             collect_inlay_hints_expr(index, uri, text, offsets, right, range, hints, seen);
         }
-        ExprKind::BangChain { expr, error } => {
+        ExprKind::BangChain { expr, error }
+        | ExprKind::Await {
+            expr,
+            timeout: error,
+        } => {
             collect_inlay_hints_expr(index, uri, text, offsets, expr, range, hints, seen);
             if let Some(error) = error {
                 collect_inlay_hints_expr(index, uri, text, offsets, error, range, hints, seen);
@@ -1336,10 +1420,12 @@ fn infer_expr_type(index: &WorkspaceIndex, uri: &str, text: &str, expr: &Expr) -
         ExprKind::InterpString(_) => Some("String".to_string()),
         ExprKind::Spawn { .. } => Some("Task".to_string()),
         ExprKind::HtmlIf { .. } | ExprKind::HtmlFor { .. } => Some("List".to_string()),
-        ExprKind::Coalesce { left, .. } => infer_expr_type(index, uri, text, left),
-        ExprKind::Await { expr } | ExprKind::Box { expr } | ExprKind::BangChain { expr, .. } => {
-            infer_expr_type(index, uri, text, expr)
+        ExprKind::Coalesce { left, .. } | ExprKind::RecordUpdate { base: left, .. } => {
+            infer_expr_type(index, uri, text, left)
         }
+        ExprKind::Await { expr, .. }
+        | ExprKind::Box { expr }
+        | ExprKind::BangChain { expr, .. } => infer_expr_type(index, uri, text, expr),
         ExprKind::Ident(ident) => {
             let offsets = line_offsets(text);
             let (line, col) = offset_to_line_col(&offsets, ident.span.start);
@@ -1370,7 +1456,7 @@ fn infer_expr_type(index: &WorkspaceIndex, uri: &str, text: &str, expr: &Expr) -
             | BinaryOp::GtEq
             | BinaryOp::And
             | BinaryOp::Or => Some("Bool".to_string()),
-            BinaryOp::Range => Some("List".to_string()),
+            BinaryOp::Range | BinaryOp::RangeExclusive => Some("Range".to_string()),
             BinaryOp::Add => {
                 let left_ty = infer_expr_type(index, uri, text, left)?;
                 if left_ty == "String" {
@@ -1385,7 +1471,8 @@ fn infer_expr_type(index: &WorkspaceIndex, uri: &str, text: &str, expr: &Expr) -
         ExprKind::Member { .. }
         | ExprKind::OptionalMember { .. }
         | ExprKind::Index { .. }
-        | ExprKind::OptionalIndex { .. } => None,
+        | ExprKind::OptionalIndex { .. }
+        | ExprKind::TupleLit(_) => None,
     }
 }
 
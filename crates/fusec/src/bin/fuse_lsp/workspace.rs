@@ -5,8 +5,8 @@ use fuse_rt::json::JsonValue;
 use fusec::ast::{ImportDecl, ImportSpec, Item, Program};
 use fusec::diag::{Diag, Level};
 use fusec::loader::{
-    ImportPathKind, ImportedAsset, ImportedAssetKind, ImportedAssetValue, ModuleExports,
-    ModuleLink, ModuleMap, ModuleRegistry, classify_import_path,
+    FUSE_IMPORT_PRIVATE, ImportPathKind, ImportedAsset, ImportedAssetKind, ImportedAssetValue,
+    ModuleExports, ModuleLink, ModuleMap, ModuleRegistry, classify_import_path, item_is_pub,
     load_program_with_modules_and_deps_and_overrides,
 };
 use fusec::manifest::{build_transitive_deps, parse_manifest};
@@ -117,7 +117,8 @@ pub(crate) fn try_incremental_module_update(
         return false;
     };
     unit.program = next_program;
-    unit.exports = module_exports_from_program(&unit.program);
+    unit.exports = ModuleExports::from_program(&unit.program);
+    fusec::frontend::monomorphize::monomorphize_types(&mut cache.snapshot.registry);
     fusec::frontend::canonicalize::canonicalize_registry(&mut cache.snapshot.registry);
 
     let mut module_diags = HashMap::new();
@@ -211,7 +212,7 @@ fn try_partial_relink_for_structural_change(
             return false;
         };
         unit.program = program;
-        unit.exports = module_exports_from_program(&unit.program);
+        unit.exports = ModuleExports::from_program(&unit.program);
     }
 
     let mut pending_links: HashMap<
@@ -272,6 +273,7 @@ fn try_partial_relink_for_structural_change(
         return false;
     }
 
+    fusec::frontend::monomorphize::monomorphize_types(&mut snapshot.registry);
     fusec::frontend::canonicalize::canonicalize_registry(&mut snapshot.registry);
     replace_loader_diags_for_paths(&mut snapshot.loader_diags, &next_module_diags);
     refresh_global_duplicate_symbol_diags(&mut snapshot.loader_diags, &snapshot.registry);
@@ -354,6 +356,7 @@ fn try_partial_relink_for_asset_change(
     }
 
     snapshot.module_ids_by_path = path_to_id;
+    fusec::frontend::monomorphize::monomorphize_types(&mut snapshot.registry);
     fusec::frontend::canonicalize::canonicalize_registry(&mut snapshot.registry);
     replace_loader_diags_for_paths(&mut snapshot.loader_diags, &next_path_diags);
     refresh_global_duplicate_symbol_diags(&mut snapshot.loader_diags, &snapshot.registry);
@@ -826,6 +829,19 @@ fn build_module_links_for_registry(
                                 });
                                 continue;
                             }
+                            if target_exports.is_private(&name.name) {
+                                diags.push(Diag {
+                                    level: Level::Error,
+                                    code: Some(FUSE_IMPORT_PRIVATE.to_string()),
+                                    message: format!(
+                                        "{} is private to {}; mark it pub to import it",
+                                        name.name, path.value
+                                    ),
+                                    span: name.span,
+                                    path: Some(unit_path.clone()),
+                                });
+                                continue;
+                            }
                             import_items.insert(
                                 name.name.clone(),
                                 module_link_for_registry(&snapshot.registry, target_id),
@@ -1060,7 +1076,7 @@ fn ensure_module_loaded_for_relink(
         let Some(unit) = snapshot.registry.modules.get_mut(&next_id) else {
             return Err(());
         };
-        unit.exports = module_exports_from_program(&unit.program);
+        unit.exports = ModuleExports::from_program(&unit.program);
         path_to_id.insert(key.clone(), next_id);
         next_module_diags.insert(key, parse_diags);
         return Ok((next_id, true));
@@ -1100,7 +1116,7 @@ fn ensure_module_loaded_for_relink(
     let Some(unit) = snapshot.registry.modules.get_mut(&next_id) else {
         return Err(());
     };
-    unit.exports = module_exports_from_program(&unit.program);
+    unit.exports = ModuleExports::from_program(&unit.program);
     path_to_id.insert(key.clone(), next_id);
     next_module_diags.insert(key, parse_diags);
     Ok((next_id, true))
@@ -1224,43 +1240,14 @@ fn has_import_cycle(registry: &ModuleRegistry) -> bool {
     false
 }
 
-fn module_exports_from_program(program: &Program) -> ModuleExports {
-    let mut exports = ModuleExports::default();
-    for item in &program.items {
-        match item {
-            Item::Type(decl) => {
-                exports.types.insert(decl.name.name.clone());
-            }
-            Item::Enum(decl) => {
-                exports.enums.insert(decl.name.name.clone());
-            }
-            Item::Interface(decl) => {
-                exports.interfaces.insert(decl.name.name.clone());
-            }
-            Item::Fn(decl) => {
-                exports.functions.insert(decl.name.name.clone());
-            }
-            Item::Config(decl) => {
-                exports.configs.insert(decl.name.name.clone());
-            }
-            Item::Service(decl) => {
-                exports.services.insert(decl.name.name.clone());
-            }
-            Item::App(decl) => {
-                exports.apps.insert(decl.name.value.clone());
-            }
-            _ => {}
-        }
-    }
-    exports
-}
-
 fn module_exports_contains(exports: &ModuleExports, name: &str) -> bool {
     exports.types.contains(name)
         || exports.enums.contains(name)
         || exports.interfaces.contains(name)
         || exports.functions.contains(name)
         || exports.configs.contains(name)
+        || exports.consts.contains(name)
+        || exports.type_aliases.contains(name)
         || exports.services.contains(name)
         || exports.apps.contains(name)
 }
@@ -1316,11 +1303,12 @@ fn import_spec_signature(spec: &ImportSpec) -> String {
 fn module_export_signature(program: &Program) -> Vec<String> {
     let mut exports = Vec::new();
     for item in &program.items {
+        let vis = if item_is_pub(item) { "pub " } else { "" };
         match item {
-            Item::Type(decl) => exports.push(format!("type:{}", decl.name.name)),
-            Item::Enum(decl) => exports.push(format!("enum:{}", decl.name.name)),
-            Item::Fn(decl) => exports.push(format!("fn:{}", decl.name.name)),
-            Item::Config(decl) => exports.push(format!("config:{}", decl.name.name)),
+            Item::Type(decl) => exports.push(format!("{vis}type:{}", decl.name.name)),
+            Item::Enum(decl) => exports.push(format!("{vis}enum:{}", decl.name.name)),
+            Item::Fn(decl) => exports.push(format!("{vis}fn:{}", decl.name.name)),
+            Item::Config(decl) => exports.push(format!("{vis}config:{}", decl.name.name)),
             Item::Service(decl) => exports.push(format!("service:{}", decl.name.name)),
             Item::App(decl) => exports.push(format!("app:{}", decl.name.value)),
             _ => {}
@@ -1684,6 +1672,7 @@ impl WorkspaceIndex {
                 | SymbolKind::Type
                 | SymbolKind::Enum
                 | SymbolKind::Config
+                | SymbolKind::Constant
                 | SymbolKind::Service
                 | SymbolKind::App
                 | SymbolKind::Migration
@@ -2927,7 +2916,13 @@ fn build_workspace_from_registry(
         let mut alias_exports = HashMap::new();
         for (alias, module_id) in &module_aliases {
             if let Some(exports) = exports_by_module.get(module_id) {
-                alias_exports.insert(alias.clone(), exports.keys().cloned().collect());
+                let target = registry.modules.get(module_id);
+                let visible = exports
+                    .keys()
+                    .filter(|name| !target.is_some_and(|unit| unit.exports.is_private(name)))
+                    .cloned()
+                    .collect();
+                alias_exports.insert(alias.clone(), visible);
             }
         }
         if !alias_exports.is_empty() {
@@ -3212,6 +3207,7 @@ pub(crate) fn is_exported_def_kind(kind: SymbolKind) -> bool {
             | SymbolKind::Enum
             | SymbolKind::Function
             | SymbolKind::Config
+            | SymbolKind::Constant
             | SymbolKind::Service
             | SymbolKind::App
             | SymbolKind::Migration
@@ -5,13 +5,17 @@ use std::path::{Path, PathBuf};
 use fuse_rt::error::{ValidationError, ValidationField};
 use fuse_rt::json;
 
-use crate::ast::{Item, TypeRefKind};
+use crate::ast::Item;
+use crate::cli_binding::{CliApp, CliInvocation};
 use crate::diag::Diag;
-use crate::interp::{Interpreter, MigrationJob, TestJob, TestOutcome};
+use crate::interp::{
+    Interpreter, MigrationDirection, MigrationJob, MigrationPlanStep, MigrationStatus, SeedJob,
+    TestFixtures, TestJob, TestOutcome,
+};
 use crate::manifest::{find_workspace_manifests, find_workspace_root_for_entry, parse_manifest};
 use crate::{load_program_with_modules, load_program_with_modules_and_deps};
 
-const USAGE: &str = "usage: fusec [--dump-ast] [--check] [--workspace] [--fmt] [--openapi] [--run] [--migrate] [--test] [--filter PATTERN] [--strict-architecture] [--diagnostics json|text] [--backend ast|native] [--app NAME] <file>";
+const USAGE: &str = "usage: fusec [--dump-ast] [--check] [--workspace] [--fmt] [--openapi] [--db-types] [--run] [--migrate|--migrate-status|--migrate-down] [--to NAME] [--dry-run] [--seed] [--test] [--filter PATTERN] [--strict-architecture] [--diagnostics json|text] [--backend ast|native] [--app NAME] <file>";
 
 #[derive(Copy, Clone)]
 enum Backend {
@@ -52,11 +56,16 @@ where
     let mut run = false;
     let mut fmt = false;
     let mut openapi = false;
+    let mut db_types = false;
     let mut program_args: Vec<String> = Vec::new();
     let mut backend = Backend::Ast;
     let mut backend_forced = false;
     let mut app_name: Option<String> = None;
     let mut migrate = false;
+    let mut migrate_mode = MigrateMode::Up;
+    let mut migrate_to: Option<String> = None;
+    let mut dry_run = false;
+    let mut seed = false;
     let mut test = false;
     let mut test_filter: Option<String> = None;
     let mut strict_architecture = false;
@@ -90,6 +99,10 @@ where
             openapi = true;
             continue;
         }
+        if arg == "--db-types" {
+            db_types = true;
+            continue;
+        }
         if arg == "--run" {
             run = true;
             continue;
@@ -98,6 +111,38 @@ where
             migrate = true;
             continue;
         }
+        if arg == "--migrate-status" {
+            migrate = true;
+            migrate_mode = MigrateMode::Status;
+            continue;
+        }
+        if arg == "--migrate-down" {
+            migrate = true;
+            migrate_mode = MigrateMode::Down;
+            continue;
+        }
+        if arg == "--to" {
+            if let Some(name) = args.next() {
+                migrate_to = Some(name);
+            } else {
+                eprintln!("--to expects a migration name");
+                eprintln!("{USAGE}");
+                return 1;
+            }
+            continue;
+        }
+        if let Some(name) = arg.strip_prefix("--to=") {
+            migrate_to = Some(name.to_string());
+            continue;
+        }
+        if arg == "--dry-run" {
+            dry_run = true;
+            continue;
+        }
+        if arg == "--seed" {
+            seed = true;
+            continue;
+        }
         if arg == "--test" {
             test = true;
             continue;
@@ -206,6 +251,16 @@ where
         eprintln!("{USAGE}");
         return 1;
     }
+    if migrate_to.is_some() && migrate_mode != MigrateMode::Down {
+        eprintln!("--to is only supported with --migrate-down");
+        eprintln!("{USAGE}");
+        return 1;
+    }
+    if dry_run && (!migrate || migrate_mode == MigrateMode::Status) {
+        eprintln!("--dry-run is only supported with --migrate or --migrate-down");
+        eprintln!("{USAGE}");
+        return 1;
+    }
 
     let src = match fs::read_to_string(&path) {
         Ok(s) => s,
@@ -259,9 +314,28 @@ where
         }
     }
 
+    if db_types {
+        let (_analysis, diags) =
+            crate::sema::analyze_registry_with_options(&registry, sema_options);
+        if !diags.is_empty() {
+            emit_diags(&diags, diagnostics_format, Some((Path::new(&path), &src)));
+            return 1;
+        }
+        match crate::db_types::generate_db_types(&registry) {
+            Ok(source) => {
+                print!("{source}");
+                return 0;
+            }
+            Err(err) => {
+                eprintln!("db types error: {err}");
+                return 1;
+            }
+        }
+    }
+
     if check {
         // Timestamp cache: only used for pure check invocations (no run/test/migrate/fmt).
-        let check_only = !run && !migrate && !test && !fmt && !openapi && !dump_ast;
+        let check_only = !run && !migrate && !seed && !test && !fmt && !openapi && !dump_ast;
         let entry_path = Path::new(&path);
         if check_only {
             if let Some(cache) = CheckCache::load(entry_path) {
@@ -289,7 +363,7 @@ where
         if check_only {
             CheckCache::save(entry_path, &registry);
         }
-        if !run && !migrate && !test && !fmt && !openapi && !dump_ast {
+        if !run && !migrate && !seed && !test && !fmt && !openapi && !dump_ast {
             return 0;
         }
     }
@@ -314,10 +388,75 @@ where
                 return 1;
             }
         };
-        if !migrations.is_empty() {
-            let mut interp = Interpreter::with_registry(&registry);
-            if let Err(err) = interp.run_migrations(&migrations) {
-                eprintln!("migration error: {err}");
+        let mut interp = Interpreter::with_registry(&registry);
+        let result = match migrate_mode {
+            MigrateMode::Status => interp
+                .migration_status(&migrations)
+                .map(|statuses| print_migration_status(&statuses)),
+            _ if dry_run => {
+                let direction = if migrate_mode == MigrateMode::Down {
+                    MigrationDirection::Down
+                } else {
+                    MigrationDirection::Up
+                };
+                interp
+                    .dry_run_migrations(&migrations, direction, migrate_to.as_deref())
+                    .map(|steps| print_migration_plan(&steps))
+            }
+            MigrateMode::Down => interp
+                .rollback_migrations(&migrations, migrate_to.as_deref())
+                .map(|reverted| {
+                    if reverted.is_empty() {
+                        println!("no migrations to roll back");
+                    }
+                    for name in reverted {
+                        println!("rolled back {name}");
+                    }
+                }),
+            MigrateMode::Up if migrations.is_empty() => Ok(()),
+            MigrateMode::Up => interp.run_migrations(&migrations),
+        };
+        if let Err(err) = result {
+            eprintln!("migration error: {err}");
+            return 1;
+        }
+        if (!run && !seed) || migrate_mode != MigrateMode::Up || dry_run {
+            return 0;
+        }
+    }
+
+    if seed {
+        // Seeds run on the AST interpreter for the same reasons as migrations.
+        if backend_forced && !matches!(backend, Backend::Ast) {
+            eprintln!("note: --backend is ignored for --seed (always uses AST interpreter)");
+        }
+        if !migrate {
+            let (_analysis, diags) =
+                crate::sema::analyze_registry_with_options(&registry, sema_options);
+            if !diags.is_empty() {
+                emit_diags(&diags, diagnostics_format, Some((Path::new(&path), &src)));
+                return 1;
+            }
+        }
+        let seeds = match collect_seeds(&registry) {
+            Ok(seeds) => seeds,
+            Err(err) => {
+                eprintln!("seed error: {err}");
+                return 1;
+            }
+        };
+        let mut interp = Interpreter::with_registry(&registry);
+        match interp.run_seeds(&seeds) {
+            Ok(applied) => {
+                if applied.is_empty() {
+                    println!("no pending seeds");
+                }
+                for name in applied {
+                    println!("seeded {name}");
+                }
+            }
+            Err(err) => {
+                eprintln!("seed error: {err}");
                 return 1;
             }
         }
@@ -351,8 +490,30 @@ where
         if let Some(pattern) = test_filter.as_deref() {
             tests.retain(|test| test.name.contains(pattern));
         }
+        let seeds = match collect_seeds(&registry) {
+            Ok(seeds) => seeds,
+            Err(err) => {
+                eprintln!("test error: {err}");
+                return 1;
+            }
+        };
+        let migrations = if seeds.is_empty() {
+            Vec::new()
+        } else {
+            match collect_migrations(&registry) {
+                Ok(migrations) => migrations,
+                Err(err) => {
+                    eprintln!("test error: {err}");
+                    return 1;
+                }
+            }
+        };
+        let fixtures = TestFixtures {
+            migrations: &migrations,
+            seeds: &seeds,
+        };
         let mut interp = Interpreter::with_registry(&registry);
-        let outcomes = match interp.run_tests(&tests) {
+        let outcomes = match interp.run_tests(&tests, (!seeds.is_empty()).then_some(&fixtures)) {
             Ok(outcomes) => outcomes,
             Err(err) => {
                 eprintln!("test error: {err}");
@@ -378,101 +539,28 @@ where
         }
         let app = app_name.as_deref();
         if !program_args.is_empty() {
-            let main_decl = program.items.iter().find_map(|item| match item {
-                Item::Fn(decl) if decl.name.name == "main" => Some(decl),
-                _ => None,
-            });
-            let main_decl = match main_decl {
-                Some(decl) => decl,
-                None => {
-                    eprintln!("no root fn main found for CLI binding");
-                    return 1;
-                }
-            };
-            let raw = match parse_program_args(&program_args) {
-                Ok(raw) => raw,
-                Err(err) => {
-                    emit_validation_error("$", "invalid_args", &err);
-                    return 2;
-                }
+            let prog_name = Path::new(&path)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_else(|| "app".to_string());
+            let Some(cli) = CliApp::from_program(program, &src, &prog_name) else {
+                eprintln!("no root fn main or pub fn subcommands found for CLI binding");
+                return 1;
             };
             let mut interp = Interpreter::with_registry(&registry);
-            let mut args_map = HashMap::new();
-            let mut errors = Vec::new();
-            let param_names: HashSet<String> = main_decl
-                .params
-                .iter()
-                .map(|param| param.name.name.clone())
-                .collect();
-            for (name, _) in raw.values.iter() {
-                if !param_names.contains(name) {
-                    errors.push(ValidationField {
-                        path: name.clone(),
-                        code: "unknown_flag".to_string(),
-                        message: "unknown flag".to_string(),
-                    });
-                }
-            }
-            for (name, _) in raw.bools.iter() {
-                if !param_names.contains(name) {
-                    errors.push(ValidationField {
-                        path: name.clone(),
-                        code: "unknown_flag".to_string(),
-                        message: "unknown flag".to_string(),
-                    });
-                }
-            }
-            for param in &main_decl.params {
-                let name = &param.name.name;
-                if let Some(flag) = raw.bools.get(name) {
-                    if !is_bool_type(&param.ty) {
-                        errors.push(ValidationField {
-                            path: name.clone(),
-                            code: "invalid_type".to_string(),
-                            message: "expected Bool flag".to_string(),
-                        });
-                        continue;
-                    }
-                    args_map.insert(name.clone(), crate::interp::Value::Bool(*flag));
-                    continue;
-                }
-                if let Some(values) = raw.values.get(name) {
-                    if values.len() != 1 {
-                        errors.push(ValidationField {
-                            path: name.clone(),
-                            code: "invalid_type".to_string(),
-                            message: "multiple values not supported".to_string(),
-                        });
-                        continue;
-                    }
-                    match interp.parse_cli_value(&param.ty, &values[0]) {
-                        Ok(value) => {
-                            args_map.insert(name.clone(), value);
-                        }
-                        Err(msg) => {
-                            errors.push(ValidationField {
-                                path: name.clone(),
-                                code: "invalid_value".to_string(),
-                                message: msg,
-                            });
-                        }
-                    }
-                    continue;
+            let (function, args_map) = match cli.bind(&mut interp, &program_args) {
+                Ok(CliInvocation::Call { function, args }) => (function, args),
+                Ok(CliInvocation::Help(text)) => {
+                    print!("{text}");
+                    return 0;
                 }
-                if param.default.is_none() && !is_optional(&param.ty) {
-                    errors.push(ValidationField {
-                        path: name.clone(),
-                        code: "missing_field".to_string(),
-                        message: "missing flag".to_string(),
-                    });
+                Err(errors) => {
+                    emit_validation_error_fields(errors);
+                    return 2;
                 }
-            }
-            if !errors.is_empty() {
-                emit_validation_error_fields(errors);
-                return 2;
-            }
+            };
             match backend {
-                Backend::Ast => match interp.call_function_with_named_args("main", &args_map) {
+                Backend::Ast => match interp.call_function_with_named_args(&function, &args_map) {
                     Ok(_) => {}
                     Err(err) => {
                         emit_error_json(&err);
@@ -480,7 +568,7 @@ where
                     }
                 },
                 Backend::Native => {
-                    let args = match interp.prepare_call_with_named_args("main", &args_map) {
+                    let args = match interp.prepare_call_with_named_args(&function, &args_map) {
                         Ok(args) => args,
                         Err(err) => {
                             emit_error_json(&err);
@@ -488,7 +576,7 @@ where
                         }
                     };
                     let entry_name =
-                        crate::ir::lower::canonical_function_name(registry.root, "main");
+                        crate::ir::lower::canonical_function_name(registry.root, &function);
                     let native = match crate::native::compile_registry(&registry) {
                         Ok(native) => native,
                         Err(errors) => {
@@ -551,6 +639,10 @@ where
             &snap,
             diagnostics_format == DiagnosticFormat::Json,
         );
+        crate::observability::emit_db_pool_metrics(
+            &crate::db::pool_metrics(),
+            diagnostics_format == DiagnosticFormat::Json,
+        );
     }
 
     if dump_ast {
@@ -560,13 +652,63 @@ where
     0
 }
 
-fn collect_migrations<'a>(
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum MigrateMode {
+    Up,
+    Down,
+    Status,
+}
+
+fn print_migration_status(statuses: &[MigrationStatus]) {
+    if statuses.is_empty() {
+        println!("no migrations");
+        return;
+    }
+    let mut current: Option<&str> = None;
+    for status in statuses {
+        if current != Some(status.package.as_str()) {
+            let label = if status.package.is_empty() {
+                "<default>"
+            } else {
+                status.package.as_str()
+            };
+            println!("package {label}");
+            current = Some(status.package.as_str());
+        }
+        println!("  {:<9} {}", status.state.label(), status.name);
+    }
+}
+
+fn print_migration_plan(steps: &[MigrationPlanStep]) {
+    if steps.is_empty() {
+        println!("no pending migrations");
+        return;
+    }
+    for step in steps {
+        let direction = match step.direction {
+            MigrationDirection::Up => "up",
+            MigrationDirection::Down => "down",
+        };
+        let label = if step.package.is_empty() {
+            "<default>"
+        } else {
+            step.package.as_str()
+        };
+        println!("-- {direction} {} (package {label})", step.name);
+        for statement in &step.statements {
+            println!("{statement};");
+        }
+    }
+}
+
+pub(crate) fn collect_migrations<'a>(
     registry: &'a crate::ModuleRegistry,
 ) -> Result<Vec<MigrationJob<'a>>, String> {
     let mut jobs = Vec::new();
     let mut seen: HashMap<(String, String), String> = HashMap::new();
     let mut package_cache: HashMap<PathBuf, String> = HashMap::new();
     for (id, unit) in &registry.modules {
+        let source = fs::read_to_string(&unit.path).ok();
         let module_path = unit.path.display().to_string();
         let package = migration_package_name(&unit.path, &mut package_cache);
         for item in &unit.program.items {
@@ -586,12 +728,17 @@ fn collect_migrations<'a>(
                         decl.name, package_label, prev
                     ));
                 }
+                let checksum = source
+                    .as_deref()
+                    .map(|src| migration_checksum(src, decl))
+                    .unwrap_or_default();
                 jobs.push((
                     decl.name.clone(),
                     package.clone(),
                     module_path.clone(),
                     *id,
                     decl,
+                    checksum,
                 ));
             }
         }
@@ -603,15 +750,120 @@ fn collect_migrations<'a>(
     });
     Ok(jobs
         .into_iter()
-        .map(|(id, package, _path, module_id, decl)| MigrationJob {
-            package,
-            id,
-            module_id,
-            decl,
-        })
+        .map(
+            |(id, package, _path, module_id, decl, checksum)| MigrationJob {
+                package,
+                id,
+                module_id,
+                decl,
+                checksum,
+            },
+        )
         .collect())
 }
 
+fn collect_seeds<'a>(registry: &'a crate::ModuleRegistry) -> Result<Vec<SeedJob<'a>>, String> {
+    let mut jobs = Vec::new();
+    let mut seen: HashMap<(String, String), String> = HashMap::new();
+    let mut package_cache: HashMap<PathBuf, String> = HashMap::new();
+    for (id, unit) in &registry.modules {
+        let source = fs::read_to_string(&unit.path).ok();
+        let module_path = unit.path.display().to_string();
+        let package = migration_package_name(&unit.path, &mut package_cache);
+        for item in &unit.program.items {
+            let Item::Seed(decl) = item else {
+                continue;
+            };
+            let name = decl.name.value.clone();
+            if name.trim().is_empty() {
+                return Err("seed name cannot be empty".to_string());
+            }
+            if let Some(prev) = seen.insert((package.clone(), name.clone()), module_path.clone()) {
+                let package_label = if package.is_empty() {
+                    "<default>"
+                } else {
+                    package.as_str()
+                };
+                return Err(format!(
+                    "duplicate seed {name} in package {package_label} (also declared in {prev})"
+                ));
+            }
+            let checksum = source
+                .as_deref()
+                .map(|src| block_checksum(src, &decl.body))
+                .unwrap_or_default();
+            jobs.push(SeedJob {
+                package: package.clone(),
+                name,
+                module_id: *id,
+                decl,
+                checksum,
+            });
+        }
+    }
+    jobs.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.package.cmp(&b.package)));
+    Ok(jobs)
+}
+
+/// Hashes a seed body with trailing whitespace and blank lines removed, so
+/// reformatting indentation-neutral whitespace does not count as a change.
+fn block_checksum(source: &str, block: &crate::ast::Block) -> String {
+    use sha2::{Digest, Sha256};
+
+    let mut hasher = Sha256::new();
+    if !hash_block_lines(&mut hasher, source, block) {
+        return String::new();
+    }
+    hex_digest(hasher)
+}
+
+/// Like `block_checksum`, but also covers the `down:` block so reverts that
+/// changed after the migration was applied are detected. Migrations without
+/// a `down:` block hash exactly like their up body.
+fn migration_checksum(source: &str, decl: &crate::ast::MigrationDecl) -> String {
+    use sha2::{Digest, Sha256};
+
+    let mut hasher = Sha256::new();
+    if !hash_block_lines(&mut hasher, source, &decl.body) {
+        return String::new();
+    }
+    if let Some(down) = &decl.down {
+        hasher.update(b"down:\n");
+        if !hash_block_lines(&mut hasher, source, down) {
+            return String::new();
+        }
+    }
+    hex_digest(hasher)
+}
+
+fn hash_block_lines(hasher: &mut sha2::Sha256, source: &str, block: &crate::ast::Block) -> bool {
+    use sha2::Digest;
+
+    let span = block.span;
+    let Some(body) = source.get(span.start..span.end) else {
+        return false;
+    };
+    for line in body
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty())
+    {
+        hasher.update(line.as_bytes());
+        hasher.update(b"\n");
+    }
+    true
+}
+
+fn hex_digest(hasher: sha2::Sha256) -> String {
+    use sha2::Digest;
+
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
 fn migration_package_name(path: &Path, cache: &mut HashMap<PathBuf, String>) -> String {
     let root = find_workspace_root_for_entry(path);
     if !root.join("fuse.toml").exists() {
@@ -684,72 +936,6 @@ fn report_tests(outcomes: &[TestOutcome]) -> usize {
     failed
 }
 
-struct RawArgs {
-    values: HashMap<String, Vec<String>>,
-    bools: HashMap<String, bool>,
-}
-
-fn parse_program_args(args: &[String]) -> Result<RawArgs, String> {
-    let mut values: HashMap<String, Vec<String>> = HashMap::new();
-    let mut bools: HashMap<String, bool> = HashMap::new();
-    let mut idx = 0;
-    while idx < args.len() {
-        let arg = &args[idx];
-        if !arg.starts_with("--") {
-            return Err(format!("unexpected argument: {arg}"));
-        }
-        if let Some((name, val)) = arg.strip_prefix("--").and_then(|s| s.split_once('=')) {
-            values
-                .entry(name.to_string())
-                .or_default()
-                .push(val.to_string());
-            idx += 1;
-            continue;
-        }
-        if let Some(name) = arg.strip_prefix("--no-") {
-            bools.insert(name.to_string(), false);
-            idx += 1;
-            continue;
-        }
-        let name = arg.trim_start_matches("--");
-        if idx + 1 < args.len() && !args[idx + 1].starts_with("--") {
-            values
-                .entry(name.to_string())
-                .or_default()
-                .push(args[idx + 1].clone());
-            idx += 2;
-        } else {
-            bools.insert(name.to_string(), true);
-            idx += 1;
-        }
-    }
-    Ok(RawArgs { values, bools })
-}
-
-fn is_optional(ty: &crate::ast::TypeRef) -> bool {
-    match &ty.kind {
-        TypeRefKind::Optional(_) => true,
-        TypeRefKind::Generic { base, .. } => base.name == "Option",
-        _ => false,
-    }
-}
-
-fn is_bool_type(ty: &crate::ast::TypeRef) -> bool {
-    match &ty.kind {
-        TypeRefKind::Simple(ident) => ident.name == "Bool",
-        TypeRefKind::Refined { base, .. } => base.name == "Bool",
-        TypeRefKind::Optional(inner) => is_bool_type(inner),
-        TypeRefKind::Generic { base, args } => {
-            if base.name == "Option" && args.len() == 1 {
-                is_bool_type(&args[0])
-            } else {
-                false
-            }
-        }
-        _ => false,
-    }
-}
-
 fn emit_validation_error(path: &str, code: &str, message: &str) {
     emit_validation_error_fields(vec![ValidationField {
         path: path.to_string(),
@@ -818,6 +1004,7 @@ fn classify_runtime_error_code(message: &str) -> &'static str {
     if message.starts_with("range expects")
         || message == "range start must be <= end"
         || message == "invalid range bounds"
+        || message.starts_with("range step")
     {
         return "runtime_range_error";
     }
@@ -0,0 +1,683 @@
+//! Binding of command-line arguments to FUSE entry points.
+//!
+//! A program is a CLI app when its root module declares `fn main`, or, failing
+//! that, one or more `pub fn` commands. Parameters bind by signature:
+//!
+//! - `Bool` parameters are switches (`--name`, `--no-name`, `--name=false`);
+//! - required parameters are positional arguments in declaration order;
+//! - parameters with a default, or of optional type, are `--name value` flags;
+//! - a trailing `List<String>` parameter collects the remaining positionals;
+//! - an enum-typed positional takes a variant name followed by its payload.
+//!
+//! Every non-`Bool` parameter may also be passed as a flag. `-h`/`--help` asks
+//! for generated usage text instead of a call.
+
+use std::collections::{HashMap, VecDeque};
+
+use fuse_rt::error::ValidationField;
+
+use crate::ast::{EnumDecl, FnDecl, Item, Param, Program, TypeRef, TypeRefKind};
+use crate::interp::{Interpreter, Value};
+
+/// The CLI surface of a program: either a single root `main`, or one command
+/// per root `pub fn`.
+#[derive(Clone, Debug)]
+pub struct CliApp {
+    pub name: String,
+    pub commands: Vec<CliCommand>,
+    /// True when `commands` are `pub fn` subcommands rather than a lone `main`.
+    pub subcommands: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct CliCommand {
+    /// Command name as typed on the command line.
+    pub name: String,
+    /// Root-module function the command calls.
+    pub function: String,
+    pub doc: Option<String>,
+    pub params: Vec<CliParam>,
+}
+
+#[derive(Clone, Debug)]
+pub struct CliParam {
+    pub name: String,
+    /// Flag spelling without the leading `--`.
+    pub flag: String,
+    pub kind: CliParamKind,
+    pub ty: TypeRef,
+    /// Type and default as written in the source, for usage text.
+    pub ty_text: String,
+    pub default_text: Option<String>,
+    pub required: bool,
+    /// Variants accepted by an enum-typed parameter.
+    pub variants: Vec<CliVariant>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CliParamKind {
+    /// `Bool` parameter: `--name` / `--no-name`.
+    Switch,
+    /// Required parameter, bound from the next positional argument.
+    Positional,
+    /// Parameter with a default or optional type, bound only by `--name`.
+    Flag,
+    /// Trailing `List<String>`, collects every remaining positional.
+    Rest,
+}
+
+#[derive(Clone, Debug)]
+pub struct CliVariant {
+    pub enum_name: String,
+    pub variant: String,
+    /// Variant spelling on the command line (`DryRun` is `dry-run`).
+    pub cli_name: String,
+    pub payload: Vec<TypeRef>,
+    pub payload_text: Vec<String>,
+}
+
+/// Result of binding a command line.
+#[derive(Clone, Debug)]
+pub enum CliInvocation {
+    Call {
+        function: String,
+        args: HashMap<String, Value>,
+    },
+    Help(String),
+}
+
+impl CliApp {
+    /// Builds the CLI surface of a root module. `source` is the root module
+    /// text, used to render types and defaults in usage text. Returns `None`
+    /// when the module has neither `fn main` nor any `pub fn`.
+    pub fn from_program(program: &Program, source: &str, name: &str) -> Option<CliApp> {
+        let enums: HashMap<&str, &EnumDecl> = program
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Enum(decl) if decl.type_params.is_empty() => {
+                    Some((decl.name.name.as_str(), decl))
+                }
+                _ => None,
+            })
+            .collect();
+        let fns: Vec<&FnDecl> = program
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Fn(decl) => Some(decl),
+                _ => None,
+            })
+            .collect();
+        if let Some(main) = fns.iter().find(|decl| decl.name.name == "main") {
+            return Some(CliApp {
+                name: name.to_string(),
+                commands: vec![command(main, name, &enums, source)],
+                subcommands: false,
+            });
+        }
+        let commands: Vec<CliCommand> = fns
+            .iter()
+            .filter(|decl| decl.is_pub && decl.type_params.is_empty())
+            .map(|decl| command(decl, &cli_name(&decl.name.name), &enums, source))
+            .collect();
+        if commands.is_empty() {
+            return None;
+        }
+        Some(CliApp {
+            name: name.to_string(),
+            commands,
+            subcommands: true,
+        })
+    }
+
+    /// Binds `args` to a call, or to a help request. Errors are reported as
+    /// validation fields, all at once.
+    pub fn bind(
+        &self,
+        interp: &mut Interpreter,
+        args: &[String],
+    ) -> Result<CliInvocation, Vec<ValidationField>> {
+        if !self.subcommands {
+            return self.bind_command(&self.commands[0], interp, args);
+        }
+        let Some(first) = args.first() else {
+            return Ok(CliInvocation::Help(self.help()));
+        };
+        if matches!(first.as_str(), "-h" | "--help" | "help") {
+            return Ok(CliInvocation::Help(self.help()));
+        }
+        if first.starts_with('-') {
+            return Err(vec![field(
+                "$",
+                "missing_command",
+                &format!("expected a command before {first}"),
+            )]);
+        }
+        let wanted = cli_name(first);
+        match self.commands.iter().find(|cmd| cmd.name == wanted) {
+            Some(cmd) => self.bind_command(cmd, interp, &args[1..]),
+            None => Err(vec![field(
+                "$",
+                "unknown_command",
+                &format!(
+                    "unknown command {first}; expected one of {}",
+                    self.commands
+                        .iter()
+                        .map(|cmd| cmd.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            )]),
+        }
+    }
+
+    /// Usage text for the whole app.
+    pub fn help(&self) -> String {
+        if !self.subcommands {
+            return self.command_help(&self.commands[0]);
+        }
+        let mut out = format!("Usage: {} <command> [options]\n\nCommands:\n", self.name);
+        let rows: Vec<(String, String)> = self
+            .commands
+            .iter()
+            .map(|cmd| (cmd.name.clone(), summary(cmd.doc.as_deref())))
+            .collect();
+        push_rows(&mut out, &rows);
+        out.push_str(&format!(
+            "\nRun `{} <command> --help` for the options of a command.\n",
+            self.name
+        ));
+        out
+    }
+
+    /// Usage text for one command.
+    pub fn command_help(&self, cmd: &CliCommand) -> String {
+        let mut out = String::new();
+        if let Some(doc) = cmd
+            .doc
+            .as_deref()
+            .map(str::trim)
+            .filter(|doc| !doc.is_empty())
+        {
+            out.push_str(doc);
+            out.push_str("\n\n");
+        }
+        let mut usage = format!("Usage: {}", self.name);
+        if self.subcommands {
+            usage.push(' ');
+            usage.push_str(&cmd.name);
+        }
+        let mut commands = Vec::new();
+        let mut arguments = Vec::new();
+        let mut options = Vec::new();
+        for (idx, param) in cmd.params.iter().enumerate() {
+            match param.kind {
+                CliParamKind::Positional if idx == 0 && !param.variants.is_empty() => {
+                    usage.push_str(" <command>");
+                    commands = param
+                        .variants
+                        .iter()
+                        .map(|variant| {
+                            let mut left = variant.cli_name.clone();
+                            for text in &variant.payload_text {
+                                left.push_str(&format!(" <{text}>"));
+                            }
+                            (left, String::new())
+                        })
+                        .collect();
+                }
+                CliParamKind::Positional => {
+                    usage.push_str(&format!(" <{}>", param.flag));
+                    arguments.push((format!("<{}>", param.flag), describe(param)));
+                }
+                CliParamKind::Rest => {
+                    usage.push_str(&format!(" [{}...]", param.flag));
+                    arguments.push((format!("[{}...]", param.flag), describe(param)));
+                }
+                CliParamKind::Flag if param.variants.is_empty() => {
+                    // The placeholder already names the type; only the
+                    // default is left to describe.
+                    let placeholder = param.ty_text.trim_end_matches('?');
+                    let note = match &param.default_text {
+                        Some(default) => format!("(default: {default})"),
+                        None => "(optional)".to_string(),
+                    };
+                    options.push((format!("--{} <{placeholder}>", param.flag), note));
+                }
+                CliParamKind::Flag => {
+                    let placeholder = variant_list(param, "|");
+                    options.push((format!("--{} <{placeholder}>", param.flag), describe(param)));
+                }
+                CliParamKind::Switch => {
+                    options.push((
+                        format!("--{}, --no-{}", param.flag, param.flag),
+                        describe(param),
+                    ));
+                }
+            }
+        }
+        options.push(("-h, --help".to_string(), "Print this help".to_string()));
+        usage.push_str(" [options]\n");
+        out.push_str(&usage);
+        for (title, rows) in [
+            ("Commands", &commands),
+            ("Arguments", &arguments),
+            ("Options", &options),
+        ] {
+            if rows.is_empty() {
+                continue;
+            }
+            out.push_str(&format!("\n{title}:\n"));
+            push_rows(&mut out, rows);
+        }
+        out
+    }
+
+    fn bind_command(
+        &self,
+        cmd: &CliCommand,
+        interp: &mut Interpreter,
+        args: &[String],
+    ) -> Result<CliInvocation, Vec<ValidationField>> {
+        let mut flagged: HashMap<usize, Vec<String>> = HashMap::new();
+        let mut positionals = VecDeque::new();
+        let mut errors = Vec::new();
+        let mut flags_done = false;
+        let mut idx = 0;
+        while idx < args.len() {
+            let arg = &args[idx];
+            idx += 1;
+            if flags_done || !arg.starts_with("--") {
+                if !flags_done && arg == "-h" {
+                    return Ok(CliInvocation::Help(self.command_help(cmd)));
+                }
+                positionals.push_back(arg.clone());
+                continue;
+            }
+            if arg == "--" {
+                flags_done = true;
+                continue;
+            }
+            if arg == "--help" {
+                return Ok(CliInvocation::Help(self.command_help(cmd)));
+            }
+            let body = &arg[2..];
+            let (name, inline) = match body.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (body, None),
+            };
+            let next_value = |idx: usize| args.get(idx).filter(|next| !next.starts_with("--"));
+            match cmd.param_index(name) {
+                Some(pos) if cmd.params[pos].kind == CliParamKind::Switch => {
+                    let value = inline.or_else(|| {
+                        let next = args
+                            .get(idx)
+                            .filter(|next| *next == "true" || *next == "false");
+                        next.inspect(|_| idx += 1).cloned()
+                    });
+                    flagged
+                        .entry(pos)
+                        .or_default()
+                        .push(value.unwrap_or_else(|| "true".to_string()));
+                }
+                Some(pos) => {
+                    let value = inline.or_else(|| next_value(idx).inspect(|_| idx += 1).cloned());
+                    match value {
+                        Some(value) => flagged.entry(pos).or_default().push(value),
+                        None => errors.push(field(
+                            &cmd.params[pos].name,
+                            "invalid_type",
+                            "expected a value",
+                        )),
+                    }
+                }
+                None => {
+                    let negated = name
+                        .strip_prefix("no-")
+                        .filter(|_| inline.is_none())
+                        .map(|name| (name, cmd.param_index(name)));
+                    match negated {
+                        Some((_, Some(pos))) if cmd.params[pos].kind == CliParamKind::Switch => {
+                            flagged.entry(pos).or_default().push("false".to_string());
+                        }
+                        Some((_, Some(pos))) => errors.push(field(
+                            &cmd.params[pos].name,
+                            "invalid_type",
+                            "expected Bool flag",
+                        )),
+                        Some((name, None)) => {
+                            errors.push(field(name, "unknown_flag", "unknown flag"));
+                        }
+                        None => {
+                            errors.push(field(name, "unknown_flag", "unknown flag"));
+                            if inline.is_none() && next_value(idx).is_some() {
+                                idx += 1;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut bound = HashMap::new();
+        for (pos, param) in cmd.params.iter().enumerate() {
+            let given = flagged.remove(&pos).unwrap_or_default();
+            let value = match param.kind {
+                CliParamKind::Rest => {
+                    let mut items = Vec::new();
+                    for raw in given {
+                        if raw.trim_start().starts_with('[') {
+                            match interp.parse_cli_value(&param.ty, &raw) {
+                                Ok(Value::List(values)) => items.extend(values),
+                                Ok(other) => items.push(other),
+                                Err(msg) => errors.push(field(&param.name, "invalid_value", &msg)),
+                            }
+                        } else {
+                            items.push(Value::String(raw));
+                        }
+                    }
+                    items.extend(positionals.drain(..).map(Value::String));
+                    Some(Ok(Value::List(items)))
+                }
+                CliParamKind::Switch => given
+                    .last()
+                    .map(|raw| interp.parse_cli_value(&param.ty, raw)),
+                CliParamKind::Positional | CliParamKind::Flag => {
+                    if given.len() > 1 {
+                        errors.push(field(
+                            &param.name,
+                            "invalid_type",
+                            "multiple values not supported",
+                        ));
+                        continue;
+                    }
+                    match given.into_iter().next() {
+                        Some(raw) => Some(parse_value(interp, param, &raw, None)),
+                        None if param.kind == CliParamKind::Positional => positionals
+                            .pop_front()
+                            .map(|raw| parse_value(interp, param, &raw, Some(&mut positionals))),
+                        None => None,
+                    }
+                }
+            };
+            match value {
+                Some(Ok(value)) => {
+                    bound.insert(param.name.clone(), value);
+                }
+                Some(Err(msg)) => errors.push(field(&param.name, "invalid_value", &msg)),
+                None if param.required => {
+                    let message = if param.kind == CliParamKind::Positional {
+                        "missing argument"
+                    } else {
+                        "missing flag"
+                    };
+                    errors.push(field(&param.name, "missing_field", message));
+                }
+                None => {}
+            }
+        }
+        for extra in positionals {
+            errors.push(field(
+                "$",
+                "invalid_args",
+                &format!("unexpected argument: {extra}"),
+            ));
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        Ok(CliInvocation::Call {
+            function: cmd.function.clone(),
+            args: bound,
+        })
+    }
+}
+
+impl CliCommand {
+    fn param_index(&self, flag: &str) -> Option<usize> {
+        let flag = cli_name(flag);
+        self.params.iter().position(|param| param.flag == flag)
+    }
+}
+
+fn command(
+    decl: &FnDecl,
+    name: &str,
+    enums: &HashMap<&str, &EnumDecl>,
+    source: &str,
+) -> CliCommand {
+    // The variadic parameter is a `List<String>` with no positional after it.
+    let rest = decl
+        .params
+        .iter()
+        .rposition(|param| is_string_list(&param.ty))
+        .filter(|&idx| {
+            decl.params[idx + 1..].iter().all(|param| {
+                is_bool_type(&param.ty) || param.default.is_some() || is_optional(&param.ty)
+            })
+        });
+    let params = decl
+        .params
+        .iter()
+        .enumerate()
+        .map(|(idx, param)| cli_param(param, rest == Some(idx), enums, source))
+        .collect();
+    CliCommand {
+        name: name.to_string(),
+        function: decl.name.name.clone(),
+        doc: decl.doc.clone(),
+        params,
+    }
+}
+
+fn cli_param(
+    param: &Param,
+    rest: bool,
+    enums: &HashMap<&str, &EnumDecl>,
+    source: &str,
+) -> CliParam {
+    let required = param.default.is_none() && !is_optional(&param.ty);
+    let kind = if rest {
+        CliParamKind::Rest
+    } else if is_bool_type(&param.ty) {
+        CliParamKind::Switch
+    } else if required {
+        CliParamKind::Positional
+    } else {
+        CliParamKind::Flag
+    };
+    let variants = enum_name(&param.ty)
+        .and_then(|name| enums.get(name))
+        .map(|decl| {
+            decl.variants
+                .iter()
+                .map(|variant| CliVariant {
+                    enum_name: decl.name.name.clone(),
+                    variant: variant.name.name.clone(),
+                    cli_name: cli_name(&variant.name.name),
+                    payload: variant.payload.clone(),
+                    payload_text: variant
+                        .payload
+                        .iter()
+                        .map(|ty| slice(source, ty.span.start, ty.span.end))
+                        .collect(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    CliParam {
+        name: param.name.name.clone(),
+        flag: cli_name(&param.name.name),
+        kind,
+        ty: param.ty.clone(),
+        ty_text: slice(source, param.ty.span.start, param.ty.span.end),
+        default_text: param
+            .default
+            .as_ref()
+            .map(|expr| slice(source, expr.span.start, expr.span.end)),
+        required: required && kind != CliParamKind::Rest,
+        variants,
+    }
+}
+
+/// Parses one raw argument. Enum parameters take a variant name; when the
+/// value came from a positional, payload values follow as more positionals.
+fn parse_value(
+    interp: &mut Interpreter,
+    param: &CliParam,
+    raw: &str,
+    rest: Option<&mut VecDeque<String>>,
+) -> Result<Value, String> {
+    if param.variants.is_empty() || raw.trim_start().starts_with('{') {
+        return interp.parse_cli_value(&param.ty, raw);
+    }
+    let wanted = cli_name(raw);
+    let Some(variant) = param
+        .variants
+        .iter()
+        .find(|variant| variant.cli_name == wanted)
+    else {
+        return Err(format!(
+            "unknown variant {raw}; expected one of {}",
+            variant_list(param, ", ")
+        ));
+    };
+    let mut payload = Vec::new();
+    if !variant.payload.is_empty() {
+        let Some(rest) = rest else {
+            return Err(format!(
+                "variant {} takes arguments; pass it positionally",
+                variant.cli_name
+            ));
+        };
+        for ty in &variant.payload {
+            let Some(raw) = rest.pop_front() else {
+                return Err(format!(
+                    "{} expects {} argument(s)",
+                    variant.cli_name,
+                    variant.payload.len()
+                ));
+            };
+            payload.push(interp.parse_cli_value(ty, &raw)?);
+        }
+    }
+    Ok(Value::Enum {
+        name: variant.enum_name.clone(),
+        variant: variant.variant.clone(),
+        payload,
+    })
+}
+
+/// Command-line spelling of an identifier: `dry_run` and `DryRun` are both
+/// `dry-run`.
+pub fn cli_name(name: &str) -> String {
+    let mut out = String::new();
+    let mut prev_lower = false;
+    for ch in name.chars() {
+        if ch == '_' || ch == '-' {
+            out.push('-');
+            prev_lower = false;
+        } else if ch.is_uppercase() {
+            if prev_lower {
+                out.push('-');
+            }
+            out.extend(ch.to_lowercase());
+            prev_lower = false;
+        } else {
+            out.push(ch);
+            prev_lower = ch.is_lowercase() || ch.is_ascii_digit();
+        }
+    }
+    out
+}
+
+pub(crate) fn describe(param: &CliParam) -> String {
+    let mut text = if param.variants.is_empty() || param.kind == CliParamKind::Flag {
+        param.ty_text.clone()
+    } else {
+        format!("{} (one of {})", param.ty_text, variant_list(param, ", "))
+    };
+    if let Some(default) = &param.default_text {
+        text.push_str(&format!(" (default: {default})"));
+    }
+    text
+}
+
+fn variant_list(param: &CliParam, sep: &str) -> String {
+    param
+        .variants
+        .iter()
+        .map(|variant| variant.cli_name.as_str())
+        .collect::<Vec<_>>()
+        .join(sep)
+}
+
+pub(crate) fn summary(doc: Option<&str>) -> String {
+    doc.and_then(|doc| doc.lines().map(str::trim).find(|line| !line.is_empty()))
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn push_rows(out: &mut String, rows: &[(String, String)]) {
+    let width = rows.iter().map(|(left, _)| left.len()).max().unwrap_or(0);
+    for (left, right) in rows {
+        let line = format!("  {left:width$}  {right}");
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+}
+
+fn field(path: &str, code: &str, message: &str) -> ValidationField {
+    ValidationField {
+        path: path.to_string(),
+        code: code.to_string(),
+        message: message.to_string(),
+    }
+}
+
+fn slice(source: &str, start: usize, end: usize) -> String {
+    source
+        .get(start..end)
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+}
+
+fn enum_name(ty: &TypeRef) -> Option<&str> {
+    match &ty.kind {
+        TypeRefKind::Simple(ident) => Some(ident.name.as_str()),
+        TypeRefKind::Optional(inner) => enum_name(inner),
+        _ => None,
+    }
+}
+
+fn is_string_list(ty: &TypeRef) -> bool {
+    match &ty.kind {
+        TypeRefKind::Generic { base, args } => {
+            base.name == "List"
+                && matches!(&args[..], [arg] if matches!(&arg.kind, TypeRefKind::Simple(ident) if ident.name == "String"))
+        }
+        _ => false,
+    }
+}
+
+fn is_optional(ty: &TypeRef) -> bool {
+    match &ty.kind {
+        TypeRefKind::Optional(_) => true,
+        TypeRefKind::Generic { base, .. } => base.name == "Option",
+        _ => false,
+    }
+}
+
+fn is_bool_type(ty: &TypeRef) -> bool {
+    match &ty.kind {
+        TypeRefKind::Simple(ident) => ident.name == "Bool",
+        TypeRefKind::Refined { base, .. } => base.name == "Bool",
+        TypeRefKind::Optional(inner) => is_bool_type(inner),
+        TypeRefKind::Generic { base, args } if base.name == "Option" && args.len() == 1 => {
+            is_bool_type(&args[0])
+        }
+        _ => false,
+    }
+}
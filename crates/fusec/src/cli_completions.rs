@@ -0,0 +1,416 @@
+//! Shell completion scripts for command-line interfaces.
+//!
+//! A [`CompletionSpec`] describes what a shell can complete: commands (with
+//! the words that may follow them) and `--long` flags, optionally with a
+//! fixed set of values. Specs come from a FUSE [`CliApp`] or are written by
+//! hand, as the `fuse` tool does for itself.
+
+use crate::cli_binding::{self, CliApp, CliParam, CliParamKind};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl Shell {
+    pub const ALL: [Shell; 3] = [Shell::Bash, Shell::Zsh, Shell::Fish];
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw {
+            "bash" => Some(Self::Bash),
+            "zsh" => Some(Self::Zsh),
+            "fish" => Some(Self::Fish),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Bash => "bash",
+            Self::Zsh => "zsh",
+            Self::Fish => "fish",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CompletionSpec {
+    /// Command name the script registers for.
+    pub name: String,
+    pub commands: Vec<CompletionCommand>,
+    /// Flags accepted everywhere.
+    pub flags: Vec<CompletionFlag>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CompletionCommand {
+    pub name: String,
+    pub about: String,
+    /// Words completed after the command, such as nested subcommands.
+    pub args: Vec<String>,
+    /// Flags accepted only after this command.
+    pub flags: Vec<CompletionFlag>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CompletionFlag {
+    /// Long flag name without the leading `--`.
+    pub name: String,
+    pub about: String,
+    pub takes_value: bool,
+    /// Fixed values for the flag; empty means any value (or a path).
+    pub values: Vec<String>,
+}
+
+impl CompletionFlag {
+    pub fn switch(name: &str, about: &str) -> Self {
+        CompletionFlag {
+            name: name.to_string(),
+            about: about.to_string(),
+            ..CompletionFlag::default()
+        }
+    }
+
+    pub fn value(name: &str, about: &str, values: &[&str]) -> Self {
+        CompletionFlag {
+            name: name.to_string(),
+            about: about.to_string(),
+            takes_value: true,
+            values: values.iter().map(|value| value.to_string()).collect(),
+        }
+    }
+}
+
+impl CompletionSpec {
+    /// Derives completions from a FUSE CLI: its subcommands (or the variants
+    /// of an enum command parameter), flags with their `--no-` negations, and
+    /// the variants of enum-valued flags.
+    pub fn from_cli_app(app: &CliApp) -> Self {
+        let help = CompletionFlag::switch("help", "Print this help");
+        if app.subcommands {
+            return CompletionSpec {
+                name: app.name.clone(),
+                commands: app
+                    .commands
+                    .iter()
+                    .map(|cmd| CompletionCommand {
+                        name: cmd.name.clone(),
+                        about: cli_binding::summary(cmd.doc.as_deref()),
+                        args: Vec::new(),
+                        flags: param_flags(&cmd.params),
+                    })
+                    .collect(),
+                flags: vec![help],
+            };
+        }
+        let params = &app.commands[0].params;
+        let (commands, params) = match params.first() {
+            Some(first) if first.kind == CliParamKind::Positional && !first.variants.is_empty() => {
+                let commands = first
+                    .variants
+                    .iter()
+                    .map(|variant| CompletionCommand {
+                        name: variant.cli_name.clone(),
+                        about: variant
+                            .payload_text
+                            .iter()
+                            .map(|text| format!("<{text}>"))
+                            .collect::<Vec<_>>()
+                            .join(" "),
+                        ..CompletionCommand::default()
+                    })
+                    .collect();
+                (commands, &params[1..])
+            }
+            _ => (Vec::new(), &params[..]),
+        };
+        let mut flags = param_flags(params);
+        flags.push(help);
+        CompletionSpec {
+            name: app.name.clone(),
+            commands,
+            flags,
+        }
+    }
+
+    pub fn render(&self, shell: Shell) -> String {
+        match shell {
+            Shell::Bash => self.render_bash(),
+            Shell::Zsh => self.render_zsh(),
+            Shell::Fish => self.render_fish(),
+        }
+    }
+
+    fn render_bash(&self) -> String {
+        let func = format!("_{}", ident(&self.name));
+        let mut out = format!(
+            "# bash completion for {name}\n{func}() {{\n    local cur prev cmd words i\n    cur=\"${{COMP_WORDS[COMP_CWORD]}}\"\n    prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"\n    cmd=\"\"\n",
+            name = self.name
+        );
+        let value_flags = self.value_flags();
+        let value_pattern = value_flags
+            .iter()
+            .map(|flag| format!("--{}", flag.name))
+            .collect::<Vec<_>>()
+            .join("|");
+        if !self.commands.is_empty() {
+            out.push_str("    for ((i = 1; i < COMP_CWORD; i++)); do\n");
+            if !value_pattern.is_empty() {
+                out.push_str(&format!(
+                    "        case \"${{COMP_WORDS[i-1]}}\" in\n            {value_pattern}) continue ;;\n        esac\n"
+                ));
+            }
+            out.push_str(
+                "        case \"${COMP_WORDS[i]}\" in\n            -*) ;;\n            *) cmd=\"${COMP_WORDS[i]}\"; break ;;\n        esac\n    done\n",
+            );
+        }
+        if !value_flags.is_empty() {
+            out.push_str("    case \"$prev\" in\n");
+            for flag in &value_flags {
+                if flag.values.is_empty() {
+                    out.push_str(&format!("        --{}) return ;;\n", flag.name));
+                } else {
+                    out.push_str(&format!(
+                        "        --{}) COMPREPLY=($(compgen -W \"{}\" -- \"$cur\")); return ;;\n",
+                        flag.name,
+                        flag.values.join(" ")
+                    ));
+                }
+            }
+            out.push_str("    esac\n");
+        }
+        let global = flag_words(&self.flags);
+        if self.commands.is_empty() {
+            out.push_str(&format!("    words=\"{global}\"\n"));
+        } else {
+            let names: Vec<&str> = self.commands.iter().map(|cmd| cmd.name.as_str()).collect();
+            out.push_str("    case \"$cmd\" in\n");
+            out.push_str(&format!(
+                "        \"\") words=\"{}\" ;;\n",
+                join_words(&[names.join(" "), global.clone()])
+            ));
+            for cmd in &self.commands {
+                out.push_str(&format!(
+                    "        {}) words=\"{}\" ;;\n",
+                    cmd.name,
+                    join_words(&[cmd.args.join(" "), flag_words(&cmd.flags), global.clone()])
+                ));
+            }
+            out.push_str(&format!("        *) words=\"{global}\" ;;\n    esac\n"));
+        }
+        out.push_str("    COMPREPLY=($(compgen -W \"$words\" -- \"$cur\"))\n}\n");
+        out.push_str(&format!("complete -o default -F {func} {}\n", self.name));
+        out
+    }
+
+    fn render_zsh(&self) -> String {
+        let func = format!("_{}", ident(&self.name));
+        let mut out = format!(
+            "#compdef {name}\n\n{func}() {{\n    local cmd=\"\" i\n    local -a items\n",
+            name = self.name
+        );
+        let value_flags = self.value_flags();
+        let value_pattern = value_flags
+            .iter()
+            .map(|flag| format!("--{}", flag.name))
+            .collect::<Vec<_>>()
+            .join("|");
+        if !self.commands.is_empty() {
+            out.push_str("    for ((i = 2; i < CURRENT; i++)); do\n");
+            if !value_pattern.is_empty() {
+                out.push_str(&format!(
+                    "        case \"${{words[i-1]}}\" in\n            ({value_pattern}) continue ;;\n        esac\n"
+                ));
+            }
+            out.push_str(
+                "        case \"${words[i]}\" in\n            (-*) ;;\n            (*) cmd=\"${words[i]}\"; break ;;\n        esac\n    done\n",
+            );
+        }
+        if !value_flags.is_empty() {
+            out.push_str("    case \"${words[CURRENT-1]}\" in\n");
+            for flag in &value_flags {
+                if flag.values.is_empty() {
+                    out.push_str(&format!("        (--{}) _files; return ;;\n", flag.name));
+                } else {
+                    out.push_str(&format!(
+                        "        (--{}) compadd -- {}; return ;;\n",
+                        flag.name,
+                        flag.values.join(" ")
+                    ));
+                }
+            }
+            out.push_str("    esac\n");
+        }
+        let global = zsh_flag_items(&self.flags);
+        if self.commands.is_empty() {
+            out.push_str(&format!("    items=({global})\n"));
+        } else {
+            let names = self
+                .commands
+                .iter()
+                .map(|cmd| zsh_item(&cmd.name, &cmd.about))
+                .collect::<Vec<_>>()
+                .join(" ");
+            out.push_str("    case \"$cmd\" in\n");
+            out.push_str(&format!(
+                "        (\"\") items=({}) ;;\n",
+                join_words(&[names, global.clone()])
+            ));
+            for cmd in &self.commands {
+                let args = cmd
+                    .args
+                    .iter()
+                    .map(|arg| zsh_item(arg, ""))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                out.push_str(&format!(
+                    "        ({}) items=({}) ;;\n",
+                    cmd.name,
+                    join_words(&[args, zsh_flag_items(&cmd.flags), global.clone()])
+                ));
+            }
+            out.push_str(&format!("        (*) items=({global}) ;;\n    esac\n"));
+        }
+        out.push_str(&format!(
+            "    _describe -t values '{name}' items || _files\n}}\n\nif [ \"$funcstack[1]\" = \"{func}\" ]; then\n    {func} \"$@\"\nelse\n    compdef {func} {name}\nfi\n",
+            name = self.name
+        ));
+        out
+    }
+
+    fn render_fish(&self) -> String {
+        let name = &self.name;
+        let mut out = format!("# fish completion for {name}\n");
+        for cmd in &self.commands {
+            out.push_str(&format!(
+                "complete -c {name} -n \"__fish_use_subcommand\" -f -a {}{}\n",
+                cmd.name,
+                fish_about(&cmd.about)
+            ));
+        }
+        for cmd in &self.commands {
+            let condition = format!("-n \"__fish_seen_subcommand_from {}\"", cmd.name);
+            if !cmd.args.is_empty() {
+                out.push_str(&format!(
+                    "complete -c {name} {condition} -f -a \"{}\"\n",
+                    cmd.args.join(" ")
+                ));
+            }
+            for flag in &cmd.flags {
+                out.push_str(&format!(
+                    "complete -c {name} {condition} {}\n",
+                    fish_flag(flag)
+                ));
+            }
+        }
+        for flag in &self.flags {
+            out.push_str(&format!("complete -c {name} {}\n", fish_flag(flag)));
+        }
+        out
+    }
+
+    /// Value-taking flags across the whole spec, first declaration wins.
+    fn value_flags(&self) -> Vec<&CompletionFlag> {
+        let mut flags: Vec<&CompletionFlag> = Vec::new();
+        let all = self
+            .flags
+            .iter()
+            .chain(self.commands.iter().flat_map(|cmd| cmd.flags.iter()));
+        for flag in all {
+            if flag.takes_value && !flags.iter().any(|seen| seen.name == flag.name) {
+                flags.push(flag);
+            }
+        }
+        flags
+    }
+}
+
+fn param_flags(params: &[CliParam]) -> Vec<CompletionFlag> {
+    let mut flags = Vec::new();
+    for param in params {
+        let about = cli_binding::describe(param);
+        if param.kind == CliParamKind::Switch {
+            flags.push(CompletionFlag::switch(&param.flag, &about));
+            flags.push(CompletionFlag::switch(
+                &format!("no-{}", param.flag),
+                &format!("Set --{} to false", param.flag),
+            ));
+            continue;
+        }
+        flags.push(CompletionFlag {
+            name: param.flag.clone(),
+            about,
+            takes_value: true,
+            values: param
+                .variants
+                .iter()
+                .map(|variant| variant.cli_name.clone())
+                .collect(),
+        });
+    }
+    flags
+}
+
+fn flag_words(flags: &[CompletionFlag]) -> String {
+    flags
+        .iter()
+        .map(|flag| format!("--{}", flag.name))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn join_words(parts: &[String]) -> String {
+    parts
+        .iter()
+        .filter(|part| !part.is_empty())
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn zsh_flag_items(flags: &[CompletionFlag]) -> String {
+    flags
+        .iter()
+        .map(|flag| zsh_item(&format!("--{}", flag.name), &flag.about))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn zsh_item(word: &str, about: &str) -> String {
+    let word = word.replace(':', "\\:");
+    let item = if about.is_empty() {
+        word
+    } else {
+        format!("{word}:{about}")
+    };
+    format!("'{}'", item.replace('\'', "'\\''"))
+}
+
+fn fish_flag(flag: &CompletionFlag) -> String {
+    let mut out = format!("-l {}", flag.name);
+    if flag.takes_value {
+        if flag.values.is_empty() {
+            out.push_str(" -r");
+        } else {
+            out.push_str(&format!(" -x -a \"{}\"", flag.values.join(" ")));
+        }
+    }
+    out.push_str(&fish_about(&flag.about));
+    out
+}
+
+fn fish_about(about: &str) -> String {
+    if about.is_empty() {
+        return String::new();
+    }
+    format!(" -d '{}'", about.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+/// Shell function name fragment for a command name.
+fn ident(name: &str) -> String {
+    name.chars()
+        .map(|ch| if ch.is_ascii_alphanumeric() { ch } else { '_' })
+        .collect()
+}
@@ -19,6 +19,9 @@ static TOTAL_COMPLETED: AtomicU64 = AtomicU64::new(0);
 /// has not yet started executing (i.e., sitting in the channel buffer).
 static QUEUE_DEPTH: AtomicI64 = AtomicI64::new(0);
 
+/// Messages buffered in `Channel<T>` values that have not been received yet.
+static CHANNEL_QUEUE_DEPTH: AtomicI64 = AtomicI64::new(0);
+
 /// Sum of all task wall-clock durations in microseconds (enqueue → complete).
 static TOTAL_LATENCY_US: AtomicU64 = AtomicU64::new(0);
 
@@ -61,6 +64,17 @@ pub(crate) fn record_task_completed(elapsed_us: u64) {
     LATENCY_HIST[latency_bucket(elapsed_us)].fetch_add(1, Ordering::Relaxed);
 }
 
+/// Called when a message is buffered in a channel.
+pub(crate) fn record_channel_sent() {
+    CHANNEL_QUEUE_DEPTH.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Called when `count` buffered channel messages are received or dropped
+/// with their channel.
+pub(crate) fn record_channel_drained(count: usize) {
+    CHANNEL_QUEUE_DEPTH.fetch_add(-(count as i64), Ordering::Relaxed);
+}
+
 /// Store the worker count once, at pool initialisation.
 pub(crate) fn set_worker_count(n: usize) {
     WORKER_COUNT.store(n, Ordering::Relaxed);
@@ -87,6 +101,8 @@ pub struct ConcurrencySnapshot {
     pub total_spawned: u64,
     pub total_completed: u64,
     pub queue_depth: i64,
+    /// Messages waiting in channel buffers across all channels.
+    pub channel_queue_depth: i64,
     /// Latency histogram buckets: <1 ms, 1–10 ms, 10–100 ms, 100 ms–1 s, ≥1 s.
     pub latency_hist: [u64; 5],
     /// Mean task latency in microseconds (0 if no tasks have completed).
@@ -107,6 +123,7 @@ pub fn snapshot() -> ConcurrencySnapshot {
         total_spawned: TOTAL_SPAWNED.load(Ordering::Relaxed),
         total_completed,
         queue_depth: QUEUE_DEPTH.load(Ordering::Relaxed),
+        channel_queue_depth: CHANNEL_QUEUE_DEPTH.load(Ordering::Relaxed),
         latency_hist: [
             LATENCY_HIST[0].load(Ordering::Relaxed),
             LATENCY_HIST[1].load(Ordering::Relaxed),
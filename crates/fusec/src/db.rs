@@ -1,40 +1,128 @@
-use std::cell::RefCell;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, OnceLock, PoisonError, Weak};
+use std::time::{Duration, Instant};
 
 use rusqlite::types::{Value as SqlValue, ValueRef};
 use rusqlite::{Connection, Params, params_from_iter};
 
 use crate::interp::Value;
 
+/// A handle on a connection pool. Handles opened with [`Db::open_shared`] on
+/// the same URL share one pool, so they can be used from different threads.
+/// A handle is `Send + Sync`, but transaction scope is tracked per handle:
+/// threads sharing one handle share its open transaction, so tasks that need
+/// their own transactions open their own handle.
 pub struct Db {
-    state: RefCell<DbState>,
+    pool: Arc<DbPool>,
+    state: Mutex<DbState>,
 }
 
 pub const DEFAULT_DB_POOL_SIZE: usize = 1;
+pub const DEFAULT_DB_BUSY_TIMEOUT_MS: u64 = 5000;
+/// SQLite's default cap on `?` parameters bound to one statement.
+pub const SQLITE_MAX_VARIABLE_NUMBER: usize = 32766;
+
+/// Pool configuration (`FUSE_DB_POOL_SIZE`, `FUSE_DB_BUSY_TIMEOUT_MS`,
+/// `FUSE_DB_WAL` and their `App.*` fallbacks).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DbOptions {
+    pub pool_size: usize,
+    /// How long a statement waits on a locked database, and how long a
+    /// checkout waits for a free connection, before failing.
+    pub busy_timeout_ms: u64,
+    /// Switches the database to `journal_mode = wal` when opened.
+    pub wal: bool,
+}
+
+impl Default for DbOptions {
+    fn default() -> Self {
+        Self {
+            pool_size: DEFAULT_DB_POOL_SIZE,
+            busy_timeout_ms: DEFAULT_DB_BUSY_TIMEOUT_MS,
+            wal: false,
+        }
+    }
+}
+
+/// Connections shared by every handle on the pool. A checkout takes an idle
+/// connection or waits (up to the busy timeout) for one to be returned.
+struct DbPool {
+    idle: Mutex<Vec<Connection>>,
+    returned: Condvar,
+    options: DbOptions,
+}
+
+/// Current occupancy of a handle's pool.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DbPoolStats {
+    pub size: usize,
+    pub idle: usize,
+}
+
+/// Process-wide pool checkout counters, reported by the `db.pool.snapshot`
+/// metric after `--run`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct DbPoolMetrics {
+    pub checkouts: u64,
+    /// Checkouts that found no idle connection and had to wait.
+    pub waits: u64,
+    /// Total time spent waiting for a connection, in microseconds.
+    pub wait_time_us: u64,
+    /// Checkouts that gave up after the busy timeout.
+    pub timeouts: u64,
+}
+
+static POOL_CHECKOUTS: AtomicU64 = AtomicU64::new(0);
+static POOL_WAITS: AtomicU64 = AtomicU64::new(0);
+static POOL_WAIT_US: AtomicU64 = AtomicU64::new(0);
+static POOL_TIMEOUTS: AtomicU64 = AtomicU64::new(0);
+
+const DB_RESULT_STRUCT_NAME: &str = "db.Result";
+
+/// Outcome of a write statement: the number of changed rows and, for inserts
+/// that changed at least one row, the rowid of the last inserted row.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct WriteResult {
+    pub rows_affected: usize,
+    pub last_insert_id: Option<i64>,
+}
 
 struct DbState {
-    conns: Vec<Connection>,
-    next_conn_idx: usize,
-    tx_conn_idx: Option<usize>,
+    /// Connection checked out for as long as a transaction is open.
+    tx_conn: Option<Connection>,
+    /// One entry per open transaction level (the outermost `BEGIN` first,
+    /// then one per `SAVEPOINT`); `true` marks a level as rollback-only.
+    tx_levels: Vec<bool>,
+    captured: Option<Vec<String>>,
 }
 
 #[derive(Clone, Debug)]
 pub struct Query {
     table: String,
     kind: QueryKind,
+    /// Projected columns; for insert/upsert/update/delete queries these are
+    /// the `returning` columns.
     select: Vec<String>,
-    wheres: Vec<WhereClause>,
-    order_by: Option<(String, OrderDir)>,
+    aggregates: Vec<Aggregate>,
+    joins: Vec<JoinClause>,
+    wheres: Vec<Vec<WhereClause>>,
+    group_by: Vec<String>,
+    having: Vec<WhereClause>,
+    order_by: Vec<(String, OrderDir)>,
     limit: Option<i64>,
+    offset: Option<i64>,
 }
 
 #[derive(Clone, Debug)]
 enum QueryKind {
     Select,
     Count,
+    /// `values` holds every row's values back to back, in `columns` order.
     Insert {
         columns: Vec<String>,
         values: Vec<Value>,
+        on_conflict: Option<Box<OnConflict>>,
     },
     Upsert {
         columns: Vec<String>,
@@ -46,12 +134,56 @@ enum QueryKind {
     Delete,
 }
 
+/// `on conflict (target) do ...` clause of an insert; `action` stays `None`
+/// until `do_update`/`do_nothing` is chained.
+#[derive(Clone, Debug)]
+struct OnConflict {
+    target: Vec<String>,
+    action: Option<ConflictAction>,
+}
+
+#[derive(Clone, Debug)]
+enum ConflictAction {
+    Nothing,
+    Update(Vec<String>),
+}
+
 #[derive(Clone, Debug)]
 struct SetClause {
     column: String,
     value: Value,
 }
 
+#[derive(Clone, Debug)]
+struct JoinClause {
+    kind: JoinKind,
+    table: String,
+    left: String,
+    right: String,
+}
+
+#[derive(Copy, Clone, Debug)]
+enum JoinKind {
+    Inner,
+    Left,
+}
+
+#[derive(Clone, Debug)]
+struct Aggregate {
+    func: AggregateFn,
+    column: String,
+    alias: String,
+}
+
+#[derive(Copy, Clone, Debug)]
+enum AggregateFn {
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
 #[derive(Clone, Debug)]
 struct WhereClause {
     column: String,
@@ -75,6 +207,8 @@ enum WhereOp {
     Ge,
     Like,
     In,
+    Is,
+    IsNot,
 }
 
 impl Query {
@@ -86,9 +220,14 @@ impl Query {
             table,
             kind: QueryKind::Select,
             select: Vec::new(),
+            aggregates: Vec::new(),
+            joins: Vec::new(),
             wheres: Vec::new(),
-            order_by: None,
+            group_by: Vec::new(),
+            having: Vec::new(),
+            order_by: Vec::new(),
             limit: None,
+            offset: None,
         })
     }
 
@@ -100,7 +239,7 @@ impl Query {
             return Err("select expects at least one column".to_string());
         }
         for col in &columns {
-            if !is_valid_identifier(col) {
+            if !is_valid_select_column(col) {
                 return Err(format!("invalid column name {col}"));
             }
         }
@@ -116,29 +255,67 @@ impl Query {
         ) {
             return Err("where is not supported for insert/upsert queries".to_string());
         }
-        if !is_valid_identifier(&column) {
-            return Err(format!("invalid column name {column}"));
+        let clause = build_where_clause(column, &op, value)?;
+        let mut next = self.clone();
+        match next.wheres.last_mut() {
+            Some(group) => group.push(clause),
+            None => next.wheres.push(vec![clause]),
         }
-        let op = parse_where_op(&op)?;
-        let values = if matches!(op, WhereOp::In) {
-            match value.unboxed() {
-                Value::List(items) => {
-                    if items.is_empty() {
-                        return Err("in expects a non-empty list".to_string());
-                    }
-                    for item in &items {
-                        validate_param_value(item)?;
-                    }
-                    items
-                }
-                _ => return Err("in expects a list value".to_string()),
+        Ok(next)
+    }
+
+    pub fn or_where(&self, column: String, op: String, value: Value) -> Result<Self, String> {
+        if matches!(
+            self.kind,
+            QueryKind::Insert { .. } | QueryKind::Upsert { .. }
+        ) {
+            return Err("or_where is not supported for insert/upsert queries".to_string());
+        }
+        if self.wheres.is_empty() {
+            return Err("or_where expects a preceding where".to_string());
+        }
+        let clause = build_where_clause(column, &op, value)?;
+        let mut next = self.clone();
+        next.wheres.push(vec![clause]);
+        Ok(next)
+    }
+
+    pub fn join(&self, table: String, left: String, right: String) -> Result<Self, String> {
+        self.push_join(JoinKind::Inner, table, left, right)
+    }
+
+    pub fn left_join(&self, table: String, left: String, right: String) -> Result<Self, String> {
+        self.push_join(JoinKind::Left, table, left, right)
+    }
+
+    fn push_join(
+        &self,
+        kind: JoinKind,
+        table: String,
+        left: String,
+        right: String,
+    ) -> Result<Self, String> {
+        if !matches!(self.kind, QueryKind::Select) {
+            return Err(format!(
+                "{} is only supported on select queries",
+                kind.method_name()
+            ));
+        }
+        if !is_valid_identifier(&table) {
+            return Err("invalid table name".to_string());
+        }
+        for col in [&left, &right] {
+            if !is_valid_identifier(col) {
+                return Err(format!("invalid column name {col}"));
             }
-        } else {
-            validate_param_value(&value)?;
-            vec![value]
-        };
+        }
         let mut next = self.clone();
-        next.wheres.push(WhereClause { column, op, values });
+        next.joins.push(JoinClause {
+            kind,
+            table,
+            left,
+            right,
+        });
         Ok(next)
     }
 
@@ -151,7 +328,7 @@ impl Query {
         }
         let dir = parse_order_dir(&dir)?;
         let mut next = self.clone();
-        next.order_by = Some((column, dir));
+        next.order_by.push((column, dir));
         Ok(next)
     }
 
@@ -167,14 +344,175 @@ impl Query {
         Ok(next)
     }
 
+    pub fn offset(&self, value: i64) -> Result<Self, String> {
+        if !matches!(self.kind, QueryKind::Select) {
+            return Err("offset is only supported on select queries".to_string());
+        }
+        if value < 0 {
+            return Err("offset must be >= 0".to_string());
+        }
+        let mut next = self.clone();
+        next.offset = Some(value);
+        Ok(next)
+    }
+
+    pub fn group_by(&self, columns: Vec<String>) -> Result<Self, String> {
+        if !matches!(self.kind, QueryKind::Select) {
+            return Err("group_by is only supported on select queries".to_string());
+        }
+        if columns.is_empty() {
+            return Err("group_by expects at least one column".to_string());
+        }
+        for col in &columns {
+            if !is_valid_identifier(col) {
+                return Err(format!("invalid column name {col}"));
+            }
+        }
+        let mut next = self.clone();
+        next.group_by.extend(columns);
+        Ok(next)
+    }
+
+    pub fn having(&self, column: String, op: String, value: Value) -> Result<Self, String> {
+        if !matches!(self.kind, QueryKind::Select) {
+            return Err("having is only supported on select queries".to_string());
+        }
+        let clause = build_where_clause(column, &op, value)?;
+        let mut next = self.clone();
+        next.having.push(clause);
+        Ok(next)
+    }
+
+    pub fn aggregate(&self, func: String, column: String, alias: String) -> Result<Self, String> {
+        if !matches!(self.kind, QueryKind::Select) {
+            return Err("aggregate is only supported on select queries".to_string());
+        }
+        let func = parse_aggregate_fn(&func)?;
+        if column == "*" {
+            if !matches!(func, AggregateFn::Count) {
+                return Err("aggregate column * is only supported for count".to_string());
+            }
+        } else if !is_valid_identifier(&column) {
+            return Err(format!("invalid column name {column}"));
+        }
+        if !is_valid_ident_part(&alias) {
+            return Err(format!("invalid aggregate alias {alias}"));
+        }
+        let mut next = self.clone();
+        next.aggregates.push(Aggregate {
+            func,
+            column,
+            alias,
+        });
+        Ok(next)
+    }
+
     pub fn insert_struct(&self, value: Value) -> Result<Self, String> {
         let (columns, values) = struct_write_fields(value, "insert")?;
+        Ok(self.with_insert(columns, values))
+    }
+
+    /// Inserts every struct in `rows` with multi-row statements. All rows
+    /// must carry the same fields; see [`Query::build_statements`] for how
+    /// large batches are split.
+    pub fn insert_many(&self, rows: Vec<Value>) -> Result<Self, String> {
+        if rows.is_empty() {
+            return Err("insert_many expects at least one row".to_string());
+        }
+        let mut columns = Vec::new();
+        let mut values = Vec::new();
+        for row in rows {
+            let (row_columns, row_values) = struct_write_fields(row, "insert_many")?;
+            if columns.is_empty() {
+                columns = row_columns;
+            } else if row_columns != columns {
+                return Err("insert_many rows must have the same fields".to_string());
+            }
+            values.extend(row_values);
+        }
+        Ok(self.with_insert(columns, values))
+    }
+
+    fn with_insert(&self, columns: Vec<String>, values: Vec<Value>) -> Self {
         let mut next = self.clone();
-        next.kind = QueryKind::Insert { columns, values };
+        next.kind = QueryKind::Insert {
+            columns,
+            values,
+            on_conflict: None,
+        };
         next.wheres.clear();
-        next.select.clear();
-        next.order_by = None;
-        next.limit = None;
+        next.clear_select_clauses();
+        next
+    }
+
+    pub fn on_conflict(&self, target: Vec<String>) -> Result<Self, String> {
+        if !matches!(self.kind, QueryKind::Insert { .. }) {
+            return Err("on_conflict is only supported on insert queries".to_string());
+        }
+        if target.is_empty() {
+            return Err("on_conflict expects at least one column".to_string());
+        }
+        for column in &target {
+            if !is_valid_identifier(column) {
+                return Err(format!("invalid column name {column}"));
+            }
+        }
+        let mut next = self.clone();
+        if let QueryKind::Insert { on_conflict, .. } = &mut next.kind {
+            *on_conflict = Some(Box::new(OnConflict {
+                target,
+                action: None,
+            }));
+        }
+        Ok(next)
+    }
+
+    /// Resolves the pending conflict by updating `columns` (default: every
+    /// inserted column outside the conflict target) from the rejected row.
+    pub fn do_update(&self, columns: Option<Vec<String>>) -> Result<Self, String> {
+        let mut next = self.clone();
+        let QueryKind::Insert {
+            columns: inserted,
+            on_conflict: Some(conflict),
+            ..
+        } = &mut next.kind
+        else {
+            return Err("do_update expects a preceding on_conflict".to_string());
+        };
+        let columns = match columns {
+            Some(columns) => {
+                for column in &columns {
+                    if !inserted.contains(column) {
+                        return Err(format!(
+                            "do_update column {column} is not an inserted field"
+                        ));
+                    }
+                }
+                columns
+            }
+            None => inserted
+                .iter()
+                .filter(|column| !conflict.target.contains(column))
+                .cloned()
+                .collect(),
+        };
+        if columns.is_empty() {
+            return Err("do_update has no columns to update".to_string());
+        }
+        conflict.action = Some(ConflictAction::Update(columns));
+        Ok(next)
+    }
+
+    pub fn do_nothing(&self) -> Result<Self, String> {
+        let mut next = self.clone();
+        let QueryKind::Insert {
+            on_conflict: Some(conflict),
+            ..
+        } = &mut next.kind
+        else {
+            return Err("do_nothing expects a preceding on_conflict".to_string());
+        };
+        conflict.action = Some(ConflictAction::Nothing);
         Ok(next)
     }
 
@@ -183,9 +521,7 @@ impl Query {
         let mut next = self.clone();
         next.kind = QueryKind::Upsert { columns, values };
         next.wheres.clear();
-        next.select.clear();
-        next.order_by = None;
-        next.limit = None;
+        next.clear_select_clauses();
         Ok(next)
     }
 
@@ -204,13 +540,13 @@ impl Query {
             QueryKind::Insert { .. } | QueryKind::Upsert { .. } => {
                 return Err("update is not supported after insert/upsert".to_string());
             }
-            _ => QueryKind::Update {
-                sets: vec![SetClause { column, value }],
-            },
+            _ => {
+                next.clear_select_clauses();
+                QueryKind::Update {
+                    sets: vec![SetClause { column, value }],
+                }
+            }
         };
-        next.select.clear();
-        next.order_by = None;
-        next.limit = None;
         Ok(next)
     }
 
@@ -223,9 +559,26 @@ impl Query {
         }
         let mut next = self.clone();
         next.kind = QueryKind::Delete;
-        next.select.clear();
-        next.order_by = None;
-        next.limit = None;
+        next.clear_select_clauses();
+        Ok(next)
+    }
+
+    pub fn returning(&self, columns: Vec<String>) -> Result<Self, String> {
+        if matches!(self.kind, QueryKind::Select | QueryKind::Count) {
+            return Err(
+                "returning is only supported on insert/upsert/update/delete queries".to_string(),
+            );
+        }
+        if columns.is_empty() {
+            return Err("returning expects at least one column".to_string());
+        }
+        for column in &columns {
+            if column != "*" && !is_valid_select_column(column) {
+                return Err(format!("invalid column name {column}"));
+            }
+        }
+        let mut next = self.clone();
+        next.select = columns;
         Ok(next)
     }
 
@@ -236,14 +589,34 @@ impl Query {
         ) {
             return Err("count is not supported for insert/upsert queries".to_string());
         }
+        if !self.group_by.is_empty() {
+            return Err(
+                "count is not supported on grouped queries; use aggregate(\"count\", \"*\", alias)"
+                    .to_string(),
+            );
+        }
         let mut next = self.clone();
         next.kind = QueryKind::Count;
         next.select.clear();
-        next.order_by = None;
+        next.aggregates.clear();
+        next.having.clear();
+        next.order_by.clear();
         next.limit = None;
+        next.offset = None;
         Ok(next)
     }
 
+    fn clear_select_clauses(&mut self) {
+        self.select.clear();
+        self.aggregates.clear();
+        self.joins.clear();
+        self.group_by.clear();
+        self.having.clear();
+        self.order_by.clear();
+        self.limit = None;
+        self.offset = None;
+    }
+
     pub fn sql(&self) -> Result<String, String> {
         self.build_sql(None).map(|(sql, _)| sql)
     }
@@ -253,21 +626,72 @@ impl Query {
     }
 
     pub fn build_sql(&self, limit_override: Option<i64>) -> Result<(String, Vec<Value>), String> {
+        let (mut sql, params) = self.build_statement_sql(limit_override)?;
+        if !matches!(self.kind, QueryKind::Select | QueryKind::Count) && !self.select.is_empty() {
+            sql.push_str(" returning ");
+            sql.push_str(&self.select.join(", "));
+        }
+        Ok((sql, params))
+    }
+
+    /// Builds the statements that run this query. Everything but a
+    /// multi-row insert is a single statement; an insert whose parameters
+    /// exceed [`SQLITE_MAX_VARIABLE_NUMBER`] is split into chunks of
+    /// `SQLITE_MAX_VARIABLE_NUMBER / columns` rows, which callers run inside
+    /// one transaction.
+    pub fn build_statements(
+        &self,
+        limit_override: Option<i64>,
+    ) -> Result<Vec<(String, Vec<Value>)>, String> {
+        let QueryKind::Insert {
+            columns,
+            values,
+            on_conflict,
+        } = &self.kind
+        else {
+            return Ok(vec![self.build_sql(limit_override)?]);
+        };
+        if columns.is_empty() || values.len() <= SQLITE_MAX_VARIABLE_NUMBER {
+            return Ok(vec![self.build_sql(limit_override)?]);
+        }
+        let rows_per_chunk = (SQLITE_MAX_VARIABLE_NUMBER / columns.len()).max(1);
+        let mut statements = Vec::new();
+        for chunk in values.chunks(rows_per_chunk * columns.len()) {
+            let mut part = self.clone();
+            part.kind = QueryKind::Insert {
+                columns: columns.clone(),
+                values: chunk.to_vec(),
+                on_conflict: on_conflict.clone(),
+            };
+            statements.push(part.build_sql(limit_override)?);
+        }
+        Ok(statements)
+    }
+
+    fn build_statement_sql(
+        &self,
+        limit_override: Option<i64>,
+    ) -> Result<(String, Vec<Value>), String> {
         match &self.kind {
             QueryKind::Select => {
-                let mut sql = String::new();
-                if self.select.is_empty() {
-                    sql.push_str("select * from ");
+                let mut columns = self.select.clone();
+                columns.extend(self.aggregates.iter().map(Aggregate::to_sql));
+                let mut sql = if columns.is_empty() {
+                    "select * from ".to_string()
                 } else {
-                    sql.push_str("select ");
-                    sql.push_str(&self.select.join(", "));
-                    sql.push_str(" from ");
-                }
+                    format!("select {} from ", columns.join(", "))
+                };
                 sql.push_str(&self.table);
+                append_join_sql(&mut sql, &self.joins);
                 let mut params = Vec::new();
                 append_where_sql(&mut sql, &self.wheres, &mut params);
-                if let Some((column, dir)) = &self.order_by {
-                    sql.push_str(" order by ");
+                if !self.group_by.is_empty() {
+                    sql.push_str(" group by ");
+                    sql.push_str(&self.group_by.join(", "));
+                }
+                append_predicate_sql(&mut sql, " having ", &self.having, &mut params);
+                for (idx, (column, dir)) in self.order_by.iter().enumerate() {
+                    sql.push_str(if idx == 0 { " order by " } else { ", " });
                     sql.push_str(column);
                     sql.push(' ');
                     sql.push_str(dir.as_str());
@@ -276,26 +700,43 @@ impl Query {
                 if let Some(limit) = limit {
                     sql.push_str(" limit ?");
                     params.push(Value::Int(limit));
+                } else if self.offset.is_some() {
+                    // SQLite only accepts OFFSET after LIMIT; -1 means unbounded.
+                    sql.push_str(" limit -1");
+                }
+                if let Some(offset) = self.offset {
+                    sql.push_str(" offset ?");
+                    params.push(Value::Int(offset));
                 }
                 Ok((sql, params))
             }
             QueryKind::Count => {
                 let mut sql = format!("select count(*) as c from {}", self.table);
+                append_join_sql(&mut sql, &self.joins);
                 let mut params = Vec::new();
                 append_where_sql(&mut sql, &self.wheres, &mut params);
                 Ok((sql, params))
             }
-            QueryKind::Insert { columns, values } => {
+            QueryKind::Insert {
+                columns,
+                values,
+                on_conflict,
+            } => {
                 if columns.is_empty() {
                     return Err("insert expects at least one field".to_string());
                 }
                 let placeholders: Vec<&str> = columns.iter().map(|_| "?").collect();
-                let sql = format!(
-                    "insert into {} ({}) values ({})",
+                let row = format!("({})", placeholders.join(", "));
+                let rows = vec![row; values.len() / columns.len()];
+                let mut sql = format!(
+                    "insert into {} ({}) values {}",
                     self.table,
                     columns.join(", "),
-                    placeholders.join(", ")
+                    rows.join(", ")
                 );
+                if let Some(conflict) = on_conflict {
+                    append_on_conflict_sql(&mut sql, conflict)?;
+                }
                 Ok((sql, values.clone()))
             }
             QueryKind::Upsert { columns, values } => {
@@ -344,22 +785,59 @@ impl Db {
     }
 
     pub fn open_with_pool(url: &str, pool_size: usize) -> Result<Self, String> {
-        if pool_size < 1 {
-            return Err("db pool size must be >= 1".to_string());
-        }
-        let path = parse_sqlite_url(url)?;
-        let mut conns = Vec::with_capacity(pool_size);
-        for _ in 0..pool_size {
-            let conn = Connection::open(path).map_err(|err| format!("db open failed: {err}"))?;
-            conns.push(conn);
+        Self::open_with_options(
+            url,
+            &DbOptions {
+                pool_size,
+                ..DbOptions::default()
+            },
+        )
+    }
+
+    /// Opens a new pool that is not shared with other handles.
+    pub fn open_with_options(url: &str, options: &DbOptions) -> Result<Self, String> {
+        Ok(Self::with_pool(Arc::new(DbPool::open(url, options)?)))
+    }
+
+    /// Returns a handle on the process-wide pool for `url`, opening it with
+    /// `options` if no live handle uses it yet. The runtimes use this so that
+    /// spawned tasks and request handlers draw from the same connections.
+    /// Fails when the live pool was opened with different options.
+    pub fn open_shared(url: &str, options: &DbOptions) -> Result<Self, String> {
+        static POOLS: OnceLock<Mutex<HashMap<String, Weak<DbPool>>>> = OnceLock::new();
+        let mut pools = lock(POOLS.get_or_init(Default::default));
+        if let Some(pool) = pools.get(url).and_then(Weak::upgrade) {
+            if pool.options != *options {
+                return Err(format!(
+                    "db pool for {url} is already open with {}; requested {}",
+                    describe_options(&pool.options),
+                    describe_options(options)
+                ));
+            }
+            return Ok(Self::with_pool(pool));
         }
-        Ok(Self {
-            state: RefCell::new(DbState {
-                conns,
-                next_conn_idx: 0,
-                tx_conn_idx: None,
+        let pool = Arc::new(DbPool::open(url, options)?);
+        pools.retain(|_, pool| pool.strong_count() > 0);
+        pools.insert(url.to_string(), Arc::downgrade(&pool));
+        Ok(Self::with_pool(pool))
+    }
+
+    fn with_pool(pool: Arc<DbPool>) -> Self {
+        Self {
+            pool,
+            state: Mutex::new(DbState {
+                tx_conn: None,
+                tx_levels: Vec::new(),
+                captured: None,
             }),
-        })
+        }
+    }
+
+    pub fn pool_stats(&self) -> DbPoolStats {
+        DbPoolStats {
+            size: self.pool.options.pool_size,
+            idle: lock(&self.pool.idle).len(),
+        }
     }
 
     pub fn exec(&self, sql: &str) -> Result<(), String> {
@@ -367,19 +845,100 @@ impl Db {
     }
 
     pub fn exec_params(&self, sql: &str, params: &[Value]) -> Result<(), String> {
+        self.exec_result(sql, params).map(|_| ())
+    }
+
+    /// Executes a single statement and reports how many rows it changed.
+    pub fn exec_result(&self, sql: &str, params: &[Value]) -> Result<WriteResult, String> {
         let sql_params =
             params_to_sql(params).map_err(|err| format_db_error("exec", err, sql, params))?;
+        self.record_statement(sql, params);
+        let in_transaction = self.in_transaction();
         self.with_connection(|conn| {
-            conn.execute(sql, params_from_iter(sql_params))
-                .map(|_| ())
-                .map_err(|err| format_db_error("exec", err, sql, params))
+            let previous_rowid = conn.last_insert_rowid();
+            let started = Instant::now();
+            let rows_affected = conn
+                .execute(sql, params_from_iter(sql_params))
+                .map_err(|err| format_db_error("exec", err, sql, params))?;
+            observe_statement(sql, params.len(), rows_affected, started, in_transaction);
+            let rowid = conn.last_insert_rowid();
+            let last_insert_id = match insert_kind(sql) {
+                InsertKind::Insert if rows_affected > 0 => Some(rowid),
+                // The update path of an upsert leaves `last_insert_rowid`
+                // at whatever the connection inserted before.
+                InsertKind::Upsert if rows_affected > 0 && rowid != previous_rowid => Some(rowid),
+                _ => None,
+            };
+            Ok(WriteResult {
+                rows_affected,
+                last_insert_id,
+            })
+        })
+    }
+
+    /// Runs the statements from [`Query::build_statements`]. Several
+    /// statements share one transaction (a savepoint when nested), so a
+    /// chunked insert applies all of its rows or none.
+    pub fn exec_statements(
+        &self,
+        statements: &[(String, Vec<Value>)],
+    ) -> Result<WriteResult, String> {
+        self.in_batch(statements, |db, sql, params, total: &mut WriteResult| {
+            let result = db.exec_result(sql, params)?;
+            total.rows_affected += result.rows_affected;
+            if result.last_insert_id.is_some() {
+                total.last_insert_id = result.last_insert_id;
+            }
+            Ok(())
+        })
+    }
+
+    /// Like [`Db::exec_statements`], collecting the rows every statement
+    /// returns.
+    pub fn query_statements(
+        &self,
+        statements: &[(String, Vec<Value>)],
+    ) -> Result<Vec<HashMap<String, Value>>, String> {
+        self.in_batch(statements, |db, sql, params, rows: &mut Vec<_>| {
+            rows.extend(db.query_params(sql, params)?);
+            Ok(())
         })
     }
 
+    fn in_batch<T: Default>(
+        &self,
+        statements: &[(String, Vec<Value>)],
+        mut run: impl FnMut(&Self, &str, &[Value], &mut T) -> Result<(), String>,
+    ) -> Result<T, String> {
+        let mut out = T::default();
+        if let [(sql, params)] = statements {
+            run(self, sql, params, &mut out)?;
+            return Ok(out);
+        }
+        self.begin_transaction()?;
+        for (sql, params) in statements {
+            if let Err(err) = run(self, sql, params, &mut out) {
+                let _ = self.rollback_transaction();
+                return Err(err);
+            }
+        }
+        if let Err(err) = self.commit_transaction() {
+            let _ = self.rollback_transaction();
+            return Err(err);
+        }
+        Ok(out)
+    }
+
     pub fn execute<P: Params>(&self, sql: &str, params: P) -> Result<usize, String> {
+        self.record_statement(sql, &[]);
+        let in_transaction = self.in_transaction();
         self.with_connection(|conn| {
-            conn.execute(sql, params)
-                .map_err(|err| format!("db exec failed: {err}; sql: {sql}; params: <opaque>"))
+            let started = Instant::now();
+            let rows_affected = conn
+                .execute(sql, params)
+                .map_err(|err| format!("db exec failed: {err}; sql: {sql}; params: <opaque>"))?;
+            observe_statement(sql, 0, rows_affected, started, in_transaction);
+            Ok(rows_affected)
         })
     }
 
@@ -394,7 +953,10 @@ impl Db {
     ) -> Result<Vec<HashMap<String, Value>>, String> {
         let sql_params =
             params_to_sql(params).map_err(|err| format_db_error("query", err, sql, params))?;
+        self.record_statement(sql, params);
+        let in_transaction = self.in_transaction();
         self.with_connection(|conn| {
+            let started = Instant::now();
             let mut stmt = conn
                 .prepare(sql)
                 .map_err(|err| format_db_error("query", err, sql, params))?;
@@ -420,72 +982,429 @@ impl Db {
                 }
                 out.push(map);
             }
+            observe_statement(sql, params.len(), out.len(), started, in_transaction);
             Ok(out)
         })
     }
 
+    /// Opens a transaction, or a savepoint nested in the active one.
     pub fn begin_transaction(&self) -> Result<(), String> {
-        let mut state = self.state.borrow_mut();
-        if state.tx_conn_idx.is_some() {
-            return Err("db transaction already active".to_string());
+        let mut state = lock(&self.state);
+        let conn = match state.tx_conn.take() {
+            Some(conn) => conn,
+            None => self.pool.checkout()?,
+        };
+        let sql = match state.tx_levels.len() {
+            0 => "BEGIN".to_string(),
+            depth => format!("SAVEPOINT {}", savepoint_name(depth)),
+        };
+        if let Err(err) = conn.execute(&sql, ()) {
+            if state.tx_levels.is_empty() {
+                self.pool.release(conn);
+            } else {
+                state.tx_conn = Some(conn);
+            }
+            return Err(format!("db exec failed: {err}"));
         }
-        let idx = state.take_connection_index();
-        state.conns[idx]
-            .execute("BEGIN", ())
-            .map_err(|err| format!("db exec failed: {err}"))?;
-        state.tx_conn_idx = Some(idx);
+        state.tx_conn = Some(conn);
+        state.tx_levels.push(false);
         Ok(())
     }
 
+    /// Commits the innermost transaction level (releasing its savepoint when
+    /// nested). A level marked with [`Db::mark_rollback`] is rolled back
+    /// instead.
     pub fn commit_transaction(&self) -> Result<(), String> {
-        let mut state = self.state.borrow_mut();
-        let idx = state
-            .tx_conn_idx
-            .ok_or_else(|| "db transaction not active".to_string())?;
-        state.conns[idx]
-            .execute("COMMIT", ())
+        let (depth, rollback_only) = {
+            let state = lock(&self.state);
+            if state.tx_conn.is_none() {
+                return Err("db transaction not active".to_string());
+            }
+            let depth = state.tx_levels.len() - 1;
+            (depth, state.tx_levels[depth])
+        };
+        if rollback_only {
+            return self.rollback_transaction();
+        }
+        let mut state = lock(&self.state);
+        let sql = match depth {
+            0 => "COMMIT".to_string(),
+            depth => format!("RELEASE SAVEPOINT {}", savepoint_name(depth)),
+        };
+        state
+            .tx_conn
+            .as_ref()
+            .expect("transaction connection")
+            .execute(&sql, ())
             .map_err(|err| format!("db exec failed: {err}"))?;
-        state.tx_conn_idx = None;
+        self.pop_transaction_level(&mut state);
         Ok(())
     }
 
+    /// Rolls back the innermost transaction level; a nested level only
+    /// discards the work done since its savepoint.
     pub fn rollback_transaction(&self) -> Result<(), String> {
-        let mut state = self.state.borrow_mut();
-        let Some(idx) = state.tx_conn_idx else {
+        let mut state = lock(&self.state);
+        let Some(conn) = state.tx_conn.as_ref() else {
             return Ok(());
         };
-        let result = state.conns[idx]
+        let result = match state.tx_levels.len() - 1 {
+            0 => conn.execute("ROLLBACK", ()).map(|_| ()),
+            depth => {
+                let name = savepoint_name(depth);
+                conn.execute_batch(&format!(
+                    "ROLLBACK TO SAVEPOINT {name}; RELEASE SAVEPOINT {name}"
+                ))
+            }
+        }
+        .map_err(|err| format!("db exec failed: {err}"));
+        self.pop_transaction_level(&mut state);
+        result
+    }
+
+    /// Rolls back every open transaction level.
+    pub fn rollback_all_transactions(&self) -> Result<(), String> {
+        let mut state = lock(&self.state);
+        let Some(conn) = state.tx_conn.take() else {
+            return Ok(());
+        };
+        let result = conn
             .execute("ROLLBACK", ())
             .map(|_| ())
             .map_err(|err| format!("db exec failed: {err}"));
-        state.tx_conn_idx = None;
+        state.tx_levels.clear();
+        self.pool.release(conn);
         result
     }
 
+    /// Marks the innermost transaction level so that finishing it rolls back
+    /// instead of committing (`db.rollback()`).
+    pub fn mark_rollback(&self) -> Result<(), String> {
+        let mut state = lock(&self.state);
+        let Some(level) = state.tx_levels.last_mut() else {
+            return Err("db.rollback requires an active transaction".to_string());
+        };
+        *level = true;
+        Ok(())
+    }
+
+    /// Starts recording every statement issued through this handle (used by
+    /// migration dry runs). Any previously captured statements are discarded.
+    pub fn begin_statement_capture(&self) {
+        lock(&self.state).captured = Some(Vec::new());
+    }
+
+    /// Stops recording and returns the statements captured since
+    /// `begin_statement_capture`.
+    pub fn take_statement_capture(&self) -> Vec<String> {
+        lock(&self.state).captured.take().unwrap_or_default()
+    }
+
+    fn record_statement(&self, sql: &str, params: &[Value]) {
+        let mut state = lock(&self.state);
+        let Some(captured) = state.captured.as_mut() else {
+            return;
+        };
+        if params.is_empty() {
+            captured.push(sql.to_string());
+        } else {
+            captured.push(format!("-- params: {}\n{sql}", summarize_db_params(params)));
+        }
+    }
+
+    fn in_transaction(&self) -> bool {
+        lock(&self.state).tx_conn.is_some()
+    }
+
     fn with_connection<T>(
         &self,
         f: impl FnOnce(&Connection) -> Result<T, String>,
     ) -> Result<T, String> {
-        let mut state = self.state.borrow_mut();
-        let idx = state.connection_index();
-        let conn = &state.conns[idx];
-        f(conn)
+        {
+            let state = lock(&self.state);
+            if let Some(conn) = state.tx_conn.as_ref() {
+                return f(conn);
+            }
+        }
+        let conn = self.pool.checkout()?;
+        let result = f(&conn);
+        self.pool.release(conn);
+        result
+    }
+
+    fn pop_transaction_level(&self, state: &mut DbState) {
+        state.tx_levels.pop();
+        if state.tx_levels.is_empty()
+            && let Some(conn) = state.tx_conn.take()
+        {
+            self.pool.release(conn);
+        }
+    }
+}
+
+impl Drop for Db {
+    fn drop(&mut self) {
+        let _ = self.rollback_all_transactions();
+    }
+}
+
+impl DbPool {
+    fn open(url: &str, options: &DbOptions) -> Result<Self, String> {
+        if options.pool_size < 1 {
+            return Err("db pool size must be >= 1".to_string());
+        }
+        let path = parse_sqlite_url(url)?;
+        let mut conns = Vec::with_capacity(options.pool_size);
+        for _ in 0..options.pool_size {
+            let conn = Connection::open(path).map_err(|err| format!("db open failed: {err}"))?;
+            conn.busy_timeout(Duration::from_millis(options.busy_timeout_ms))
+                .map_err(|err| format!("db open failed: {err}"))?;
+            if options.wal {
+                conn.query_row("pragma journal_mode = wal", (), |_| Ok(()))
+                    .map_err(|err| format!("db open failed: {err}"))?;
+            }
+            conns.push(conn);
+        }
+        Ok(Self {
+            idle: Mutex::new(conns),
+            returned: Condvar::new(),
+            options: options.clone(),
+        })
+    }
+
+    fn checkout(&self) -> Result<Connection, String> {
+        POOL_CHECKOUTS.fetch_add(1, Ordering::Relaxed);
+        let mut idle = lock(&self.idle);
+        if let Some(conn) = idle.pop() {
+            return Ok(conn);
+        }
+        POOL_WAITS.fetch_add(1, Ordering::Relaxed);
+        let started = Instant::now();
+        let timeout = Duration::from_millis(self.options.busy_timeout_ms);
+        loop {
+            let waited = started.elapsed();
+            if waited >= timeout {
+                POOL_WAIT_US.fetch_add(waited.as_micros() as u64, Ordering::Relaxed);
+                POOL_TIMEOUTS.fetch_add(1, Ordering::Relaxed);
+                return Err(format!(
+                    "db pool exhausted: no connection available after {}ms (pool size {})",
+                    self.options.busy_timeout_ms, self.options.pool_size
+                ));
+            }
+            idle = self
+                .returned
+                .wait_timeout(idle, timeout - waited)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+            if let Some(conn) = idle.pop() {
+                POOL_WAIT_US.fetch_add(started.elapsed().as_micros() as u64, Ordering::Relaxed);
+                return Ok(conn);
+            }
+        }
+    }
+
+    fn release(&self, conn: Connection) {
+        lock(&self.idle).push(conn);
+        self.returned.notify_one();
+    }
+}
+
+/// Snapshot of the process-wide pool checkout counters.
+pub fn pool_metrics() -> DbPoolMetrics {
+    DbPoolMetrics {
+        checkouts: POOL_CHECKOUTS.load(Ordering::Relaxed),
+        waits: POOL_WAITS.load(Ordering::Relaxed),
+        wait_time_us: POOL_WAIT_US.load(Ordering::Relaxed),
+        timeouts: POOL_TIMEOUTS.load(Ordering::Relaxed),
+    }
+}
+
+fn observe_statement(
+    sql: &str,
+    params: usize,
+    rows: usize,
+    started: Instant,
+    in_transaction: bool,
+) {
+    crate::observability::emit_db_query_observability(&crate::observability::DbQueryEvent {
+        sql,
+        params,
+        rows,
+        duration: started.elapsed(),
+        in_transaction,
+    });
+}
+
+/// Normalizes a statement for grouping in `db.query` events: literals become
+/// `?`, whitespace collapses, and `?` lists and multi-row value lists fold into
+/// a single `(?)`, so the same statement shape always has the same fingerprint.
+pub fn sql_fingerprint(sql: &str) -> String {
+    let mut out = String::with_capacity(sql.len());
+    let mut chars = sql.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\'' => {
+                while let Some(next) = chars.next() {
+                    if next == '\'' && chars.next_if_eq(&'\'').is_none() {
+                        break;
+                    }
+                }
+                out.push('?');
+            }
+            ch if ch.is_ascii_digit()
+                && !out.ends_with(|prev: char| prev.is_ascii_alphanumeric() || prev == '_') =>
+            {
+                while chars
+                    .next_if(|next| next.is_ascii_alphanumeric() || *next == '.')
+                    .is_some()
+                {}
+                out.push('?');
+            }
+            ch if ch.is_whitespace() => {
+                if !out.is_empty() && !out.ends_with([' ', '(']) {
+                    out.push(' ');
+                }
+            }
+            ',' | ')' => {
+                out.truncate(out.trim_end().len());
+                out.push(ch);
+                if ch == ',' {
+                    out.push(' ');
+                }
+            }
+            ch => out.push(ch.to_ascii_lowercase()),
+        }
+    }
+    let mut out = out.trim_end().to_string();
+    for (list, folded) in [("?, ?", "?"), ("(?), (?)", "(?)")] {
+        while out.contains(list) {
+            out = out.replace(list, folded);
+        }
+    }
+    out
+}
+
+fn describe_options(options: &DbOptions) -> String {
+    format!(
+        "pool size {}, busy timeout {}ms, wal {}",
+        options.pool_size, options.busy_timeout_ms, options.wal
+    )
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Converts a [`WriteResult`] into the `db.result` value returned by
+/// `db.execute(...)` and `Query.execute()`.
+pub fn write_result_value(result: WriteResult) -> Value {
+    let mut fields = HashMap::new();
+    fields.insert(
+        "rows_affected".to_string(),
+        Value::Int(result.rows_affected as i64),
+    );
+    fields.insert(
+        "last_insert_id".to_string(),
+        match result.last_insert_id {
+            Some(id) => Value::Int(id),
+            None => Value::Null,
+        },
+    );
+    Value::Struct {
+        name: DB_RESULT_STRUCT_NAME.to_string(),
+        fields,
     }
 }
 
-impl DbState {
-    fn connection_index(&mut self) -> usize {
-        if let Some(idx) = self.tx_conn_idx {
-            return idx;
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum InsertKind {
+    NotInsert,
+    Insert,
+    /// `insert ... on conflict ... do update`, which may update instead.
+    Upsert,
+}
+
+/// Classifies a statement by its main verb, looking past comments and any
+/// leading `with` clause.
+fn insert_kind(sql: &str) -> InsertKind {
+    let words = top_level_words(sql);
+    let verb = match words.first().map(String::as_str) {
+        Some("with") => words.iter().map(String::as_str).find(|word| {
+            matches!(
+                *word,
+                "select" | "insert" | "update" | "delete" | "replace" | "values"
+            )
+        }),
+        first => first,
+    };
+    match verb {
+        Some("insert" | "replace") => {
+            if words
+                .windows(2)
+                .any(|pair| pair[0] == "do" && pair[1] == "update")
+            {
+                InsertKind::Upsert
+            } else {
+                InsertKind::Insert
+            }
         }
-        self.take_connection_index()
+        _ => InsertKind::NotInsert,
     }
+}
 
-    fn take_connection_index(&mut self) -> usize {
-        let idx = self.next_conn_idx % self.conns.len();
-        self.next_conn_idx = (idx + 1) % self.conns.len();
-        idx
+/// Lowercased keywords and identifiers outside parentheses, string literals,
+/// quoted identifiers, and comments.
+fn top_level_words(sql: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut depth = 0usize;
+    let mut chars = sql.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch.is_ascii_alphanumeric() || ch == '_' {
+            if depth == 0 {
+                word.push(ch.to_ascii_lowercase());
+            }
+            continue;
+        }
+        if !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        match ch {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            '\'' | '"' | '`' | '[' => {
+                let close = if ch == '[' { ']' } else { ch };
+                while let Some(next) = chars.next() {
+                    if next == close && (close == ']' || chars.next_if_eq(&close).is_none()) {
+                        break;
+                    }
+                }
+            }
+            '-' if chars.next_if_eq(&'-').is_some() => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.next_if_eq(&'*').is_some() => {
+                while let Some(next) = chars.next() {
+                    if next == '*' && chars.next_if_eq(&'/').is_some() {
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
     }
+    words
+}
+
+fn savepoint_name(depth: usize) -> String {
+    format!("fuse_tx_{depth}")
 }
 
 fn invalid_pool_size_message(source: &str) -> String {
@@ -503,19 +1422,58 @@ pub fn parse_db_pool_size(raw: &str, source: &str) -> Result<usize, String> {
     if value < 1 {
         return Err(invalid_pool_size_message(source));
     }
-    usize::try_from(value).map_err(|_| invalid_pool_size_message(source))
+    usize::try_from(value).map_err(|_| invalid_pool_size_message(source))
+}
+
+pub fn parse_db_pool_size_value(value: &Value, source: &str) -> Result<usize, String> {
+    match value.unboxed() {
+        Value::Int(v) => {
+            if v < 1 {
+                return Err(invalid_pool_size_message(source));
+            }
+            usize::try_from(v).map_err(|_| invalid_pool_size_message(source))
+        }
+        Value::String(raw) => parse_db_pool_size(&raw, source),
+        _ => Err(invalid_pool_size_message(source)),
+    }
+}
+
+pub fn parse_db_busy_timeout(raw: &str, source: &str) -> Result<u64, String> {
+    raw.trim()
+        .parse()
+        .map_err(|_| format!("invalid {source}: expected integer milliseconds >= 0"))
+}
+
+pub fn parse_db_slow_query_ms(raw: &str, source: &str) -> Result<u64, String> {
+    raw.trim()
+        .parse()
+        .map_err(|_| format!("invalid {source}: expected integer milliseconds >= 0"))
+}
+
+pub fn parse_db_busy_timeout_value(value: &Value, source: &str) -> Result<u64, String> {
+    match value.unboxed() {
+        Value::Int(v) => u64::try_from(v)
+            .map_err(|_| format!("invalid {source}: expected integer milliseconds >= 0")),
+        Value::String(raw) => parse_db_busy_timeout(&raw, source),
+        _ => Err(format!(
+            "invalid {source}: expected integer milliseconds >= 0"
+        )),
+    }
 }
 
-pub fn parse_db_pool_size_value(value: &Value, source: &str) -> Result<usize, String> {
+pub fn parse_db_wal(raw: &str, source: &str) -> Result<bool, String> {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "true" | "1" => Ok(true),
+        "false" | "0" => Ok(false),
+        _ => Err(format!("invalid {source}: expected true or false")),
+    }
+}
+
+pub fn parse_db_wal_value(value: &Value, source: &str) -> Result<bool, String> {
     match value.unboxed() {
-        Value::Int(v) => {
-            if v < 1 {
-                return Err(invalid_pool_size_message(source));
-            }
-            usize::try_from(v).map_err(|_| invalid_pool_size_message(source))
-        }
-        Value::String(raw) => parse_db_pool_size(&raw, source),
-        _ => Err(invalid_pool_size_message(source)),
+        Value::Bool(v) => Ok(v),
+        Value::String(raw) => parse_db_wal(&raw, source),
+        _ => Err(format!("invalid {source}: expected true or false")),
     }
 }
 
@@ -534,6 +1492,7 @@ fn param_to_sql(param: &Value) -> Result<SqlValue, String> {
         Value::Float(v) => Ok(SqlValue::Real(v)),
         Value::Bool(v) => Ok(SqlValue::Integer(if v { 1 } else { 0 })),
         Value::String(v) => Ok(SqlValue::Text(v)),
+        Value::Decimal(v) => Ok(SqlValue::Text(v.to_string())),
         Value::Bytes(v) => Ok(SqlValue::Blob(v)),
         Value::Boxed(inner) => param_to_sql(&inner.lock().expect("box lock")),
         Value::ResultOk(inner) => param_to_sql(&inner),
@@ -552,6 +1511,7 @@ fn validate_param_value(value: &Value) -> Result<(), String> {
         | Value::Float(_)
         | Value::Bool(_)
         | Value::String(_)
+        | Value::Decimal(_)
         | Value::Bytes(_) => Ok(()),
         Value::Boxed(inner) => validate_param_value(&inner.lock().expect("box lock")),
         Value::ResultOk(inner) => validate_param_value(&inner),
@@ -573,10 +1533,48 @@ fn parse_where_op(raw: &str) -> Result<WhereOp, String> {
         ">=" => Ok(WhereOp::Ge),
         "like" => Ok(WhereOp::Like),
         "in" => Ok(WhereOp::In),
+        "is" => Ok(WhereOp::Is),
+        "is not" => Ok(WhereOp::IsNot),
         _ => Err("unsupported where operator".to_string()),
     }
 }
 
+fn build_where_clause(column: String, op: &str, value: Value) -> Result<WhereClause, String> {
+    if !is_valid_identifier(&column) {
+        return Err(format!("invalid column name {column}"));
+    }
+    let op = parse_where_op(op)?;
+    let values = if matches!(op, WhereOp::In) {
+        match value.unboxed() {
+            Value::List(items) => {
+                if items.is_empty() {
+                    return Err("in expects a non-empty list".to_string());
+                }
+                for item in &items {
+                    validate_param_value(item)?;
+                }
+                items
+            }
+            _ => return Err("in expects a list value".to_string()),
+        }
+    } else {
+        validate_param_value(&value)?;
+        vec![value]
+    };
+    Ok(WhereClause { column, op, values })
+}
+
+fn parse_aggregate_fn(raw: &str) -> Result<AggregateFn, String> {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "count" => Ok(AggregateFn::Count),
+        "sum" => Ok(AggregateFn::Sum),
+        "avg" => Ok(AggregateFn::Avg),
+        "min" => Ok(AggregateFn::Min),
+        "max" => Ok(AggregateFn::Max),
+        _ => Err("aggregate expects count, sum, avg, min or max".to_string()),
+    }
+}
+
 fn parse_order_dir(raw: &str) -> Result<OrderDir, String> {
     match raw.trim().to_ascii_lowercase().as_str() {
         "asc" => Ok(OrderDir::Asc),
@@ -605,10 +1603,46 @@ impl WhereOp {
             WhereOp::Ge => ">=",
             WhereOp::Like => "like",
             WhereOp::In => "in",
+            WhereOp::Is => "is",
+            WhereOp::IsNot => "is not",
+        }
+    }
+}
+
+impl JoinKind {
+    fn method_name(&self) -> &'static str {
+        match self {
+            JoinKind::Inner => "join",
+            JoinKind::Left => "left_join",
+        }
+    }
+
+    fn as_sql(&self) -> &'static str {
+        match self {
+            JoinKind::Inner => "join",
+            JoinKind::Left => "left join",
+        }
+    }
+}
+
+impl AggregateFn {
+    fn as_str(&self) -> &'static str {
+        match self {
+            AggregateFn::Count => "count",
+            AggregateFn::Sum => "sum",
+            AggregateFn::Avg => "avg",
+            AggregateFn::Min => "min",
+            AggregateFn::Max => "max",
         }
     }
 }
 
+impl Aggregate {
+    fn to_sql(&self) -> String {
+        format!("{}({}) as {}", self.func.as_str(), self.column, self.alias)
+    }
+}
+
 fn is_valid_identifier(value: &str) -> bool {
     let mut parts = value.split('.');
     let first = match parts.next() {
@@ -630,6 +1664,27 @@ fn is_valid_identifier(value: &str) -> bool {
     true
 }
 
+/// Select columns accept an optional `as alias` suffix so joined tables can
+/// project colliding column names under distinct keys.
+fn is_valid_select_column(value: &str) -> bool {
+    match split_select_alias(value) {
+        Some((column, alias)) => is_valid_identifier(column) && is_valid_ident_part(alias),
+        None => is_valid_identifier(value),
+    }
+}
+
+/// Splits `column as alias` (case-insensitive `as`) into its parts.
+pub fn split_select_alias(value: &str) -> Option<(&str, &str)> {
+    let mut parts = value.split_whitespace();
+    let column = parts.next()?;
+    let keyword = parts.next()?;
+    let alias = parts.next()?;
+    if parts.next().is_some() || !keyword.eq_ignore_ascii_case("as") {
+        return None;
+    }
+    Some((column, alias))
+}
+
 fn is_valid_ident_part(value: &str) -> bool {
     let mut chars = value.chars();
     let Some(first) = chars.next() else {
@@ -687,12 +1742,70 @@ fn struct_write_fields(value: Value, action: &str) -> Result<(Vec<String>, Vec<V
     Ok((columns, values))
 }
 
-fn append_where_sql(sql: &mut String, wheres: &[WhereClause], params: &mut Vec<Value>) {
-    if wheres.is_empty() {
+fn append_on_conflict_sql(sql: &mut String, conflict: &OnConflict) -> Result<(), String> {
+    sql.push_str(" on conflict (");
+    sql.push_str(&conflict.target.join(", "));
+    sql.push(')');
+    match &conflict.action {
+        None => return Err("on_conflict expects do_update or do_nothing".to_string()),
+        Some(ConflictAction::Nothing) => sql.push_str(" do nothing"),
+        Some(ConflictAction::Update(columns)) => {
+            sql.push_str(" do update set ");
+            let sets: Vec<String> = columns
+                .iter()
+                .map(|column| format!("{column} = excluded.{column}"))
+                .collect();
+            sql.push_str(&sets.join(", "));
+        }
+    }
+    Ok(())
+}
+
+fn append_join_sql(sql: &mut String, joins: &[JoinClause]) {
+    for join in joins {
+        sql.push(' ');
+        sql.push_str(join.kind.as_sql());
+        sql.push(' ');
+        sql.push_str(&join.table);
+        sql.push_str(" on ");
+        sql.push_str(&join.left);
+        sql.push_str(" = ");
+        sql.push_str(&join.right);
+    }
+}
+
+fn append_where_sql(sql: &mut String, groups: &[Vec<WhereClause>], params: &mut Vec<Value>) {
+    if groups.len() <= 1 {
+        let clauses = groups.first().map(Vec::as_slice).unwrap_or_default();
+        append_predicate_sql(sql, " where ", clauses, params);
         return;
     }
     sql.push_str(" where ");
-    for (idx, clause) in wheres.iter().enumerate() {
+    for (idx, group) in groups.iter().enumerate() {
+        if idx > 0 {
+            sql.push_str(" or ");
+        }
+        sql.push('(');
+        append_clauses_sql(sql, group, params);
+        sql.push(')');
+    }
+}
+
+fn append_predicate_sql(
+    sql: &mut String,
+    keyword: &str,
+    clauses: &[WhereClause],
+    params: &mut Vec<Value>,
+) {
+    if clauses.is_empty() {
+        return;
+    }
+    sql.push_str(keyword);
+    append_clauses_sql(sql, clauses, params);
+}
+
+fn append_clauses_sql(sql: &mut String, clauses: &[WhereClause], params: &mut Vec<Value>) {
+    for (idx, clause) in clauses.iter().enumerate() {
         if idx > 0 {
             sql.push_str(" and ");
         }
@@ -721,19 +1834,33 @@ fn format_db_error(
     params: &[Value],
 ) -> String {
     format!(
-        "db {action} failed: {err}; sql: {sql}; params: {}",
+        "db {action} failed: {err}; sql: {}; params: {}",
+        summarize_sql(sql),
         summarize_db_params(params)
     )
 }
 
+/// Keeps errors from multi-row statements readable by cutting the SQL short.
+fn summarize_sql(sql: &str) -> String {
+    const MAX_CHARS: usize = 160;
+    match sql.char_indices().nth(MAX_CHARS) {
+        Some((end, _)) => format!("{}... ({} chars)", &sql[..end], sql.chars().count()),
+        None => sql.to_string(),
+    }
+}
+
 fn summarize_db_params(params: &[Value]) -> String {
+    const MAX_PARAMS: usize = 16;
     let mut out = String::from("[");
-    for (idx, value) in params.iter().enumerate() {
+    for (idx, value) in params.iter().take(MAX_PARAMS).enumerate() {
         if idx > 0 {
             out.push_str(", ");
         }
         out.push_str(&summarize_db_param(value));
     }
+    if params.len() > MAX_PARAMS {
+        out.push_str(&format!(", ... {} more", params.len() - MAX_PARAMS));
+    }
     out.push(']');
     out
 }
@@ -745,6 +1872,7 @@ fn summarize_db_param(value: &Value) -> String {
         Value::Float(v) => format!("float({v})"),
         Value::Bool(v) => format!("bool({v})"),
         Value::String(v) => format!("string({})", summarize_string(&v)),
+        Value::Decimal(v) => format!("decimal({v})"),
         Value::Bytes(v) => format!("bytes(len={})", v.len()),
         Value::Boxed(inner) => summarize_db_param(&inner.lock().expect("box lock")),
         Value::ResultOk(inner) => summarize_db_param(&inner),
@@ -819,6 +1947,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_db_slow_query_ms_rejects_invalid_values() {
+        assert_eq!(
+            parse_db_slow_query_ms(" 250 ", "FUSE_DB_SLOW_QUERY_MS").unwrap(),
+            250
+        );
+        for raw in ["", "-1", "abc", "1.5"] {
+            let err =
+                parse_db_slow_query_ms(raw, "FUSE_DB_SLOW_QUERY_MS").expect_err("expected failure");
+            assert!(
+                err.contains("FUSE_DB_SLOW_QUERY_MS"),
+                "error should mention source, got: {err}"
+            );
+        }
+    }
+
     #[test]
     fn parse_db_pool_size_value_supports_int_and_string() {
         assert_eq!(
@@ -839,8 +1983,7 @@ mod tests {
     #[test]
     fn open_with_pool_uses_requested_size() {
         let db = Db::open_with_pool(&temp_db_url("fuse_db_pool_size"), 3).unwrap();
-        let state = db.state.borrow();
-        assert_eq!(state.conns.len(), 3);
+        assert_eq!(db.pool_stats(), DbPoolStats { size: 3, idle: 3 });
     }
 
     #[test]
@@ -859,6 +2002,107 @@ mod tests {
         assert_eq!(scalar_i64(&after_rollback, "c"), 0);
     }
 
+    #[test]
+    fn shared_pool_hands_connections_across_threads() {
+        let url = temp_db_url("fuse_db_shared_pool");
+        let options = DbOptions {
+            pool_size: 1,
+            busy_timeout_ms: 200,
+            wal: true,
+        };
+        let db = Db::open_shared(&url, &options).unwrap();
+        db.exec("create table if not exists items (id integer)")
+            .unwrap();
+
+        db.begin_transaction().unwrap();
+        let waiter = {
+            let url = url.clone();
+            let options = options.clone();
+            std::thread::spawn(move || {
+                let db = Db::open_shared(&url, &options).unwrap();
+                assert_eq!(db.pool_stats(), DbPoolStats { size: 1, idle: 0 });
+                db.exec("insert into items (id) values (2)")
+            })
+        };
+        std::thread::sleep(Duration::from_millis(400));
+        db.exec("insert into items (id) values (1)").unwrap();
+        db.commit_transaction().unwrap();
+        let err = waiter.join().unwrap().unwrap_err();
+        assert_eq!(
+            err,
+            "db pool exhausted: no connection available after 200ms (pool size 1)"
+        );
+
+        let worker = {
+            let url = url.clone();
+            let options = options.clone();
+            std::thread::spawn(move || {
+                let db = Db::open_shared(&url, &options).unwrap();
+                db.exec("insert into items (id) values (3)")
+            })
+        };
+        worker.join().unwrap().unwrap();
+        let rows = db.query("select sum(id) as s from items").unwrap();
+        assert_eq!(scalar_i64(&rows, "s"), 4);
+        assert_eq!(db.pool_stats(), DbPoolStats { size: 1, idle: 1 });
+        let metrics = pool_metrics();
+        assert!(metrics.waits >= 1 && metrics.timeouts >= 1);
+
+        let err = Db::open_shared(
+            &url,
+            &DbOptions {
+                pool_size: 4,
+                ..options
+            },
+        )
+        .err()
+        .unwrap();
+        assert_eq!(
+            err,
+            format!(
+                "db pool for {url} is already open with pool size 1, busy timeout 200ms, wal true; \
+                 requested pool size 4, busy timeout 200ms, wal true"
+            )
+        );
+
+        // One handle can be shared by reference across threads.
+        fn assert_sync<T: Send + Sync>(_: &T) {}
+        assert_sync(&db);
+        std::thread::scope(|scope| {
+            scope.spawn(|| db.exec("insert into items (id) values (5)").unwrap());
+        });
+        let rows = db.query("select sum(id) as s from items").unwrap();
+        assert_eq!(scalar_i64(&rows, "s"), 9);
+    }
+
+    #[test]
+    fn nested_transactions_roll_back_to_savepoints() {
+        let db = Db::open_with_pool(&temp_db_url("fuse_db_nested_tx"), 2).unwrap();
+        db.exec("create table if not exists items (id integer)")
+            .unwrap();
+
+        db.begin_transaction().unwrap();
+        db.exec("insert into items (id) values (1)").unwrap();
+        db.begin_transaction().unwrap();
+        db.exec("insert into items (id) values (2)").unwrap();
+        db.rollback_transaction().unwrap();
+        db.begin_transaction().unwrap();
+        db.exec("insert into items (id) values (3)").unwrap();
+        db.mark_rollback().unwrap();
+        db.commit_transaction().unwrap();
+        db.begin_transaction().unwrap();
+        db.exec("insert into items (id) values (4)").unwrap();
+        db.commit_transaction().unwrap();
+        db.commit_transaction().unwrap();
+
+        let rows = db.query("select sum(id) as s from items").unwrap();
+        assert_eq!(scalar_i64(&rows, "s"), 5);
+        assert_eq!(
+            db.mark_rollback().unwrap_err(),
+            "db.rollback requires an active transaction"
+        );
+    }
+
     #[test]
     fn query_builder_insert_update_delete_count_flow() {
         let db = Db::open_with_pool(&temp_db_url("fuse_db_query_builder_write"), 1).unwrap();
@@ -976,6 +2220,330 @@ mod tests {
         assert_eq!(title.as_deref(), Some("Bea"));
     }
 
+    #[test]
+    fn query_builder_insert_many_with_conflict_clauses() {
+        let note = |id: &str, title: &str| {
+            let mut fields = HashMap::new();
+            fields.insert("id".to_string(), Value::String(id.to_string()));
+            fields.insert("title".to_string(), Value::String(title.to_string()));
+            Value::Struct {
+                name: "Note".to_string(),
+                fields,
+            }
+        };
+        let notes = Query::new("notes".to_string()).unwrap();
+
+        let batch = notes
+            .insert_many(vec![note("n1", "Ada"), note("n2", "Bea")])
+            .unwrap();
+        let (sql, params) = batch.build_sql(None).unwrap();
+        assert_eq!(sql, "insert into notes (id, title) values (?, ?), (?, ?)");
+        assert_eq!(params.len(), 4);
+
+        let sync = batch.on_conflict(vec!["id".to_string()]).unwrap();
+        assert_eq!(
+            sync.sql().unwrap_err(),
+            "on_conflict expects do_update or do_nothing"
+        );
+        assert_eq!(
+            sync.do_update(None).unwrap().sql().unwrap(),
+            "insert into notes (id, title) values (?, ?), (?, ?) \
+             on conflict (id) do update set title = excluded.title"
+        );
+        assert_eq!(
+            sync.do_nothing().unwrap().sql().unwrap(),
+            "insert into notes (id, title) values (?, ?), (?, ?) on conflict (id) do nothing"
+        );
+        assert_eq!(
+            sync.do_update(Some(vec!["body".to_string()])).unwrap_err(),
+            "do_update column body is not an inserted field"
+        );
+        assert_eq!(
+            notes.do_nothing().unwrap_err(),
+            "do_nothing expects a preceding on_conflict"
+        );
+        assert_eq!(
+            notes.insert_many(Vec::new()).unwrap_err(),
+            "insert_many expects at least one row"
+        );
+    }
+
+    #[test]
+    fn insert_many_splits_large_batches_in_one_transaction() {
+        let db = Db::open_with_pool(&temp_db_url("fuse_db_insert_many_chunks"), 1).unwrap();
+        db.exec("create table if not exists events (id integer primary key, a int, b int, c int)")
+            .unwrap();
+        let event = |id: i64| {
+            let mut fields = HashMap::new();
+            for name in ["id", "a", "b", "c"] {
+                fields.insert(name.to_string(), Value::Int(id));
+            }
+            Value::Struct {
+                name: "Event".to_string(),
+                fields,
+            }
+        };
+        let events = Query::new("events".to_string()).unwrap();
+
+        let batch = events.insert_many((1..=8192).map(event).collect()).unwrap();
+        let statements = batch.build_statements(None).unwrap();
+        assert_eq!(statements.len(), 2);
+        assert!(
+            statements
+                .iter()
+                .all(|(_, params)| params.len() <= SQLITE_MAX_VARIABLE_NUMBER)
+        );
+        let result = db.exec_statements(&statements).unwrap();
+        assert_eq!(result.rows_affected, 8192);
+        assert_eq!(result.last_insert_id, Some(8192));
+
+        // The duplicate key sits in the last chunk, so the first chunk rolls
+        // back with it.
+        let mut rows: Vec<Value> = (8193..16384).map(event).collect();
+        rows.push(event(1));
+        let statements = events
+            .insert_many(rows)
+            .unwrap()
+            .build_statements(None)
+            .unwrap();
+        let err = db.exec_statements(&statements).unwrap_err();
+        assert!(err.contains("UNIQUE constraint failed"), "{err}");
+
+        // Errors from a full chunk keep the SQL and params summary short.
+        let mut rows = vec![event(1)];
+        rows.extend((8193..16384).map(event));
+        let statements = events
+            .insert_many(rows)
+            .unwrap()
+            .build_statements(None)
+            .unwrap();
+        let err = db.exec_statements(&statements).unwrap_err();
+        assert!(err.len() < 1024, "{err}");
+        assert!(err.contains(" chars); params: "), "{err}");
+        assert!(err.contains(", ... 32748 more]"), "{err}");
+        let count = db.query("select count(*) as c from events").unwrap();
+        assert!(matches!(count[0].get("c"), Some(Value::Int(8192))));
+    }
+
+    #[test]
+    fn query_builder_joins_or_groups_and_paging() {
+        let query = Query::new("notes".to_string())
+            .unwrap()
+            .left_join(
+                "users".to_string(),
+                "notes.author_id".to_string(),
+                "users.id".to_string(),
+            )
+            .unwrap()
+            .select(vec![
+                "notes.id".to_string(),
+                "users.name as author".to_string(),
+            ])
+            .unwrap()
+            .where_clause(
+                "notes.archived".to_string(),
+                "=".to_string(),
+                Value::Bool(false),
+            )
+            .unwrap()
+            .where_clause("users.name".to_string(), "is not".to_string(), Value::Null)
+            .unwrap()
+            .or_where(
+                "notes.pinned".to_string(),
+                "=".to_string(),
+                Value::Bool(true),
+            )
+            .unwrap()
+            .order_by("notes.pinned".to_string(), "desc".to_string())
+            .unwrap()
+            .order_by("notes.id".to_string(), "asc".to_string())
+            .unwrap()
+            .offset(20)
+            .unwrap();
+        let (sql, params) = query.build_sql(None).unwrap();
+        assert_eq!(
+            sql,
+            "select notes.id, users.name as author from notes left join users on notes.author_id = users.id \
+             where (notes.archived = ? and users.name is not ?) or (notes.pinned = ?) \
+             order by notes.pinned desc, notes.id asc limit -1 offset ?"
+        );
+        assert_eq!(params.len(), 4);
+
+        let (one_sql, _) = query.build_sql(Some(1)).unwrap();
+        assert!(one_sql.ends_with("limit ? offset ?"), "sql: {one_sql}");
+
+        let err = Query::new("notes".to_string())
+            .unwrap()
+            .or_where("id".to_string(), "=".to_string(), Value::Int(1))
+            .unwrap_err();
+        assert_eq!(err, "or_where expects a preceding where");
+        let err = Query::new("notes".to_string())
+            .unwrap()
+            .select(vec!["id as bad alias".to_string()])
+            .unwrap_err();
+        assert_eq!(err, "invalid column name id as bad alias");
+    }
+
+    #[test]
+    fn query_builder_group_by_having_and_aggregates() {
+        let db = Db::open_with_pool(&temp_db_url("fuse_db_query_builder_group"), 1).unwrap();
+        db.exec("create table if not exists orders (id integer primary key, customer text, amount integer)")
+            .unwrap();
+        db.exec(
+            "insert into orders (customer, amount) values ('ada', 10), ('ada', 30), ('bob', 5), ('cy', 50)",
+        )
+        .unwrap();
+
+        let query = Query::new("orders".to_string())
+            .unwrap()
+            .select(vec!["customer".to_string()])
+            .unwrap()
+            .aggregate("sum".to_string(), "amount".to_string(), "total".to_string())
+            .unwrap()
+            .aggregate("count".to_string(), "*".to_string(), "n".to_string())
+            .unwrap()
+            .group_by(vec!["customer".to_string()])
+            .unwrap()
+            .having("total".to_string(), ">=".to_string(), Value::Int(10))
+            .unwrap()
+            .order_by("total".to_string(), "desc".to_string())
+            .unwrap();
+        let (sql, params) = query.build_sql(None).unwrap();
+        assert_eq!(
+            sql,
+            "select customer, sum(amount) as total, count(*) as n from orders group by customer \
+             having total >= ? order by total desc"
+        );
+        let rows = db.query_params(&sql, &params).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(scalar_i64(&rows, "total"), 50);
+        assert_eq!(scalar_i64(&rows[1..], "n"), 2);
+
+        assert!(query.count().is_err());
+        let err = Query::new("orders".to_string())
+            .unwrap()
+            .aggregate("sum".to_string(), "*".to_string(), "total".to_string())
+            .unwrap_err();
+        assert_eq!(err, "aggregate column * is only supported for count");
+    }
+
+    #[test]
+    fn exec_result_reports_rows_affected_and_returning_rows() {
+        let db = Db::open_with_pool(&temp_db_url("fuse_db_exec_result"), 1).unwrap();
+        db.exec("create table if not exists notes (id integer primary key, title text not null)")
+            .unwrap();
+
+        let inserted = db
+            .exec_result(
+                "insert into notes (title) values (?)",
+                &[Value::String("Ada".to_string())],
+            )
+            .unwrap();
+        assert_eq!(
+            inserted,
+            WriteResult {
+                rows_affected: 1,
+                last_insert_id: Some(1),
+            }
+        );
+        let missed = db
+            .exec_result(
+                "update notes set title = 'Bea' where id = ?",
+                &[Value::Int(7)],
+            )
+            .unwrap();
+        assert_eq!(missed.rows_affected, 0);
+        assert_eq!(missed.last_insert_id, None);
+
+        let mut fields = HashMap::new();
+        fields.insert("title".to_string(), Value::String("Cy".to_string()));
+        let insert = Query::new("notes".to_string())
+            .unwrap()
+            .insert_struct(Value::Struct {
+                name: "Note".to_string(),
+                fields,
+            })
+            .unwrap()
+            .returning(vec!["id".to_string(), "title".to_string()])
+            .unwrap();
+        let (sql, params) = insert.build_sql(Some(1)).unwrap();
+        assert_eq!(
+            sql,
+            "insert into notes (title) values (?) returning id, title"
+        );
+        let rows = db.query_params(&sql, &params).unwrap();
+        assert_eq!(scalar_i64(&rows, "id"), 2);
+
+        let err = Query::new("notes".to_string())
+            .unwrap()
+            .returning(vec!["id".to_string()])
+            .unwrap_err();
+        assert_eq!(
+            err,
+            "returning is only supported on insert/upsert/update/delete queries"
+        );
+    }
+
+    #[test]
+    fn exec_result_reports_insert_ids_for_ctes_and_upserts() {
+        let db = Db::open_with_pool(&temp_db_url("fuse_db_exec_result_kinds"), 1).unwrap();
+        db.exec("create table if not exists notes (id integer primary key, slug text unique, n integer)")
+            .unwrap();
+        db.exec("create table if not exists tags (id integer primary key, name text)")
+            .unwrap();
+
+        let inserted = db
+            .exec_result(
+                "-- seed\nwith src(slug) as (select 'a') insert into notes (slug, n) select slug, 1 from src",
+                &[],
+            )
+            .unwrap();
+        assert_eq!(inserted.last_insert_id, Some(1));
+
+        let upsert = "insert into notes (slug, n) values (?, 1) \
+                      on conflict (slug) do update set n = notes.n + 1";
+        let updated = db
+            .exec_result(upsert, &[Value::String("a".to_string())])
+            .unwrap();
+        assert_eq!(updated.rows_affected, 1);
+        assert_eq!(updated.last_insert_id, None);
+        let fresh = db
+            .exec_result(upsert, &[Value::String("b".to_string())])
+            .unwrap();
+        assert_eq!(fresh.last_insert_id, Some(2));
+
+        // A plain insert reports its rowid even when it repeats the last one.
+        let tagged = db
+            .exec_result("insert into tags (id, name) values (2, 'x')", &[])
+            .unwrap();
+        assert_eq!(tagged.last_insert_id, Some(2));
+
+        assert_eq!(
+            insert_kind("replace into tags (name) values ('z')"),
+            InsertKind::Insert
+        );
+        assert_eq!(
+            insert_kind("with del as (select 1) delete from tags where name = 'insert'"),
+            InsertKind::NotInsert
+        );
+        assert_eq!(
+            insert_kind("update notes set slug = '(do update)' where id = 1"),
+            InsertKind::NotInsert
+        );
+    }
+
+    #[test]
+    fn sql_fingerprint_folds_literals_and_value_lists() {
+        assert_eq!(
+            sql_fingerprint("SELECT id FROM notes\n  WHERE owner = 'o''b' AND n IN (1,2, 3)"),
+            "select id from notes where owner = ? and n in (?)"
+        );
+        assert_eq!(
+            sql_fingerprint("insert into t2 (a, b) values (?, ?), (?, ?)"),
+            sql_fingerprint("insert into t2 (a, b) values ( 1.5, 'x' )")
+        );
+    }
+
     #[test]
     fn db_errors_include_sql_and_params() {
         let db = Db::open_with_pool(&temp_db_url("fuse_db_error_message"), 1).unwrap();
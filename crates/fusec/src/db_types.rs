@@ -0,0 +1,248 @@
+//! `type` declarations generated from the migrated database schema.
+//!
+//! Migrations are applied to a scratch in-memory SQLite database and every
+//! user table is read back through `pragma table_info`. Each table becomes a
+//! `<Table>Row` type whose fields follow the column order, with SQLite
+//! declared types mapped to FUSE types and nullable columns as `T?`.
+
+use std::collections::HashMap;
+
+use crate::db::{Db, DbOptions};
+use crate::interp::{Interpreter, Value};
+use crate::loader::ModuleRegistry;
+use crate::token::Keyword;
+
+const SCRATCH_DB_URL: &str = "sqlite::memory:";
+
+pub const GENERATED_HEADER: &str = "# Generated by `fuse db types` from migrations. Do not edit.";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SchemaTable {
+    pub name: String,
+    pub columns: Vec<SchemaColumn>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SchemaColumn {
+    pub name: String,
+    pub decl_type: String,
+    pub not_null: bool,
+    pub primary_key: bool,
+}
+
+/// Applies every migration in `registry` to a scratch database and renders
+/// the resulting schema as a FUSE module.
+pub fn generate_db_types(registry: &ModuleRegistry) -> Result<String, String> {
+    let migrations = crate::cli::collect_migrations(registry)?;
+    let db = Db::open_with_options(SCRATCH_DB_URL, &DbOptions::default())?;
+    let mut interp = Interpreter::with_registry(registry);
+    interp.attach_db(db);
+    if !migrations.is_empty() {
+        interp.run_migrations(&migrations)?;
+    }
+    let db = interp
+        .detach_db()
+        .ok_or_else(|| "scratch database was not opened".to_string())?;
+    render_db_types(&introspect_schema(&db)?)
+}
+
+/// Reads user tables (SQLite internals and migration/seed history excluded) and
+/// their columns in declaration order.
+pub fn introspect_schema(db: &Db) -> Result<Vec<SchemaTable>, String> {
+    let rows = db.query(
+        "select name from sqlite_master where type = 'table' \
+         and name not like 'sqlite_%' and name not in ('__fuse_migrations', '__fuse_seeds') \
+         order by name",
+    )?;
+    let mut tables = Vec::with_capacity(rows.len());
+    for row in rows {
+        let name = string_field(&row, "name");
+        let info = db.query(&format!(
+            "pragma table_info(\"{}\")",
+            name.replace('"', "\"\"")
+        ))?;
+        let columns = info
+            .iter()
+            .map(|column| SchemaColumn {
+                name: string_field(column, "name"),
+                decl_type: string_field(column, "type"),
+                not_null: int_field(column, "notnull") != 0,
+                primary_key: int_field(column, "pk") != 0,
+            })
+            .collect();
+        tables.push(SchemaTable { name, columns });
+    }
+    Ok(tables)
+}
+
+pub fn render_db_types(tables: &[SchemaTable]) -> Result<String, String> {
+    let mut out = String::from(GENERATED_HEADER);
+    out.push('\n');
+    for table in tables {
+        let type_name = row_type_name(&table.name).ok_or_else(|| {
+            format!(
+                "table {} cannot be named as a type (expected letters, digits, and _)",
+                table.name
+            )
+        })?;
+        out.push('\n');
+        out.push_str(&format!("type {type_name}:\n"));
+        let single_key = table.columns.iter().filter(|c| c.primary_key).count() == 1;
+        for column in &table.columns {
+            if !is_field_name(&column.name) {
+                return Err(format!(
+                    "column {}.{} is not a valid field name",
+                    table.name, column.name
+                ));
+            }
+            if is_reserved_word(&column.name) {
+                return Err(format!(
+                    "column {}.{} is a FUSE keyword and cannot be a field name; rename the column",
+                    table.name, column.name
+                ));
+            }
+            let mut ty = column_type(&column.decl_type).to_string();
+            // Only an `INTEGER PRIMARY KEY` (the rowid alias) is never NULL;
+            // SQLite lets other primary key columns hold NULL.
+            let rowid_key = single_key
+                && column.primary_key
+                && column.decl_type.eq_ignore_ascii_case("integer");
+            if !column.not_null && !rowid_key {
+                ty.push('?');
+            }
+            out.push_str(&format!("  {}: {ty}\n", column.name));
+        }
+    }
+    Ok(out)
+}
+
+/// Maps a declared column type by SQLite affinity
+/// (https://sqlite.org/datatype3.html, 3.1). NUMERIC affinity and untyped
+/// columns map to `String`, except `BOOL`/`BOOLEAN` which map to `Bool`.
+pub fn column_type(decl_type: &str) -> &'static str {
+    let upper = decl_type.to_ascii_uppercase();
+    if upper.contains("INT") {
+        "Int"
+    } else if upper.contains("CHAR") || upper.contains("CLOB") || upper.contains("TEXT") {
+        "String"
+    } else if upper.contains("BLOB") {
+        "Bytes"
+    } else if upper.contains("REAL") || upper.contains("FLOA") || upper.contains("DOUB") {
+        "Float"
+    } else if upper.starts_with("BOOL") {
+        "Bool"
+    } else {
+        "String"
+    }
+}
+
+/// `note_likes` becomes `NoteLikesRow`.
+fn row_type_name(table: &str) -> Option<String> {
+    let mut name = String::new();
+    for part in table.split('_').filter(|part| !part.is_empty()) {
+        if !part.chars().all(|ch| ch.is_ascii_alphanumeric()) {
+            return None;
+        }
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            name.push(first.to_ascii_uppercase());
+            name.extend(chars);
+        }
+    }
+    if name.is_empty() || name.starts_with(|ch: char| ch.is_ascii_digit()) {
+        return None;
+    }
+    name.push_str("Row");
+    Some(name)
+}
+
+fn is_field_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(ch) if ch.is_ascii_alphabetic() || ch == '_')
+        && chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+}
+
+/// Words the lexer never reads as identifiers.
+fn is_reserved_word(name: &str) -> bool {
+    Keyword::from_str(name).is_some() || matches!(name, "true" | "false" | "null")
+}
+
+fn string_field(row: &HashMap<String, Value>, key: &str) -> String {
+    match row.get(key) {
+        Some(Value::String(value)) => value.clone(),
+        _ => String::new(),
+    }
+}
+
+fn int_field(row: &HashMap<String, Value>, key: &str) -> i64 {
+    match row.get(key) {
+        Some(Value::Int(value)) => *value,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_row_types_with_mapped_types_and_nullability() {
+        let db = Db::open_with_options(SCRATCH_DB_URL, &DbOptions::default()).expect("open db");
+        db.exec(
+            "create table note_likes (id integer primary key, note_id text not null, \
+             score real, payload blob, flagged boolean not null, created_at datetime)",
+        )
+        .expect("create table");
+        let tables = introspect_schema(&db).expect("introspect");
+        let source = render_db_types(&tables).expect("render");
+        assert_eq!(
+            source,
+            format!(
+                "{GENERATED_HEADER}\n\ntype NoteLikesRow:\n  id: Int\n  note_id: String\n  \
+                 score: Float?\n  payload: Bytes?\n  flagged: Bool\n  created_at: String?\n"
+            )
+        );
+        let (_program, diags) = crate::parse_source(&source);
+        assert!(
+            diags.is_empty(),
+            "generated module does not parse: {diags:?}"
+        );
+    }
+
+    #[test]
+    fn only_integer_row_id_keys_are_never_null() {
+        let db = Db::open_with_options(SCRATCH_DB_URL, &DbOptions::default()).expect("open db");
+        db.exec("create table tags (slug text primary key, label text not null)")
+            .expect("create tags");
+        db.exec(
+            "create table note_tags (note_id integer, tag text not null, \
+             primary key (note_id, tag))",
+        )
+        .expect("create note_tags");
+        let tables = introspect_schema(&db).expect("introspect");
+        let source = render_db_types(&tables).expect("render");
+        assert_eq!(
+            source,
+            format!(
+                "{GENERATED_HEADER}\n\ntype NoteTagsRow:\n  note_id: Int?\n  tag: String\n\n\
+                 type TagsRow:\n  slug: String?\n  label: String\n"
+            )
+        );
+    }
+
+    #[test]
+    fn keyword_columns_are_rejected() {
+        let db = Db::open_with_options(SCRATCH_DB_URL, &DbOptions::default()).expect("open db");
+        db.exec("create table notes (id integer primary key, body text not null, type text)")
+            .expect("create table");
+        let tables = introspect_schema(&db).expect("introspect");
+        assert_eq!(
+            render_db_types(&tables),
+            Err(
+                "column notes.body is a FUSE keyword and cannot be a field name; \
+                 rename the column"
+                    .to_string()
+            )
+        );
+    }
+}
@@ -114,6 +114,21 @@ impl Diagnostics {
         });
     }
 
+    pub fn warning_at_path_with_code<P, C, S>(&mut self, path: P, span: Span, code: C, message: S)
+    where
+        P: Into<PathBuf>,
+        C: Into<String>,
+        S: Into<String>,
+    {
+        self.diags.push(Diag {
+            level: Level::Warning,
+            code: Some(code.into()),
+            message: message.into(),
+            span,
+            path: Some(path.into()),
+        });
+    }
+
     pub fn has_errors(&self) -> bool {
         self.diags.iter().any(|d| matches!(d.level, Level::Error))
     }
@@ -4,11 +4,27 @@ use crate::ast::{
     Block, CallArg, Expr, ExprKind, Item, Literal, Pattern, PatternKind, Program, Stmt, StmtKind,
     TypeRef, TypeRefKind,
 };
+use crate::frontend::consts::{ConstIndex, with_span};
 use crate::frontend::html_tag_builtin::should_use_html_tag_builtin;
 use crate::html_tags;
 use crate::loader::ModuleRegistry;
 
 pub fn canonicalize_registry(registry: &mut ModuleRegistry) {
+    canonicalize_registry_with(registry, false);
+}
+
+/// Canonicalizes `registry` and additionally replaces every expression that
+/// reads a foldable const with its value.
+///
+/// The shared pipeline only folds const declarations and refined type
+/// arguments so tooling still sees the references; backends run this on
+/// their private copy before lowering.
+pub fn inline_registry_consts(registry: &mut ModuleRegistry) {
+    canonicalize_registry_with(registry, true);
+}
+
+fn canonicalize_registry_with(registry: &mut ModuleRegistry, inline_const_uses: bool) {
+    let const_index = ConstIndex::from_registry(registry);
     let mut ids: Vec<_> = registry.modules.keys().copied().collect();
     ids.sort_unstable();
     for id in ids {
@@ -48,6 +64,9 @@ pub fn canonicalize_registry(registry: &mut ModuleRegistry) {
             component_names,
             component_params,
             import_item_names,
+            consts: const_index.visible_in(id),
+            qualified_consts: const_index.qualified_in(id),
+            inline_const_uses,
         };
         canonicalizer.canonicalize_program(&mut unit.program.items);
     }
@@ -79,12 +98,16 @@ pub fn canonicalize_program(program: &mut Program) {
             _ => {}
         }
     }
+    let const_index = ConstIndex::from_program(program);
     let mut canonicalizer = Canonicalizer {
         fn_names,
         config_names,
         component_names,
         component_params,
         import_item_names: HashSet::new(),
+        consts: const_index.visible_in(0),
+        qualified_consts: HashMap::new(),
+        inline_const_uses: false,
     };
     canonicalizer.canonicalize_program(&mut program.items);
 }
@@ -95,6 +118,14 @@ struct Canonicalizer {
     component_names: HashSet<String>,
     component_params: HashMap<String, Vec<String>>,
     import_item_names: HashSet<String>,
+    /// Folded consts by written name; references not shadowed by a local are
+    /// replaced with their value.
+    consts: HashMap<String, Expr>,
+    /// Folded consts reachable as `alias.NAME`.
+    qualified_consts: HashMap<String, Expr>,
+    /// Whether const references in ordinary expressions are inlined; refined
+    /// type arguments are always inlined.
+    inline_const_uses: bool,
 }
 
 impl Canonicalizer {
@@ -109,6 +140,9 @@ impl Canonicalizer {
                         }
                     }
                 }
+                Item::TypeAlias(decl) => {
+                    self.canonicalize_type_ref(&mut decl.target, &ScopeStack::new());
+                }
                 Item::Enum(decl) => {
                     for variant in &mut decl.variants {
                         for ty in &mut variant.payload {
@@ -118,14 +152,19 @@ impl Canonicalizer {
                 }
                 Item::Interface(decl) => {
                     for member in &mut decl.members {
+                        let mut scope = ScopeStack::new();
                         for param in &mut member.params {
-                            self.canonicalize_type_ref(&mut param.ty, &ScopeStack::new());
+                            self.canonicalize_type_ref(&mut param.ty, &scope);
                             if let Some(default) = &mut param.default {
-                                self.canonicalize_expr(default, &mut ScopeStack::new());
+                                self.canonicalize_expr(default, &mut scope.clone());
                             }
+                            scope.declare(param.name.name.clone());
                         }
                         if let Some(ret) = &mut member.ret {
-                            self.canonicalize_type_ref(ret, &ScopeStack::new());
+                            self.canonicalize_type_ref(ret, &scope);
+                        }
+                        if let Some(body) = &mut member.body {
+                            self.canonicalize_block(body, &mut scope);
                         }
                     }
                 }
@@ -174,6 +213,13 @@ impl Canonicalizer {
                         self.canonicalize_expr(&mut field.value, &mut ScopeStack::new());
                     }
                 }
+                Item::Const(decl) => {
+                    self.canonicalize_type_ref(&mut decl.ty, &ScopeStack::new());
+                    match self.consts.get(&decl.name.name) {
+                        Some(value) => decl.value = with_span(value.clone(), decl.value.span),
+                        None => self.canonicalize_expr(&mut decl.value, &mut ScopeStack::new()),
+                    }
+                }
                 Item::Component(decl) => {
                     let mut scope = ScopeStack::new();
                     scope.declare("attrs".to_string());
@@ -182,8 +228,12 @@ impl Canonicalizer {
                 }
                 Item::App(decl) => self.canonicalize_block(&mut decl.body, &mut ScopeStack::new()),
                 Item::Migration(decl) => {
-                    self.canonicalize_block(&mut decl.body, &mut ScopeStack::new())
+                    self.canonicalize_block(&mut decl.body, &mut ScopeStack::new());
+                    if let Some(down) = &mut decl.down {
+                        self.canonicalize_block(down, &mut ScopeStack::new());
+                    }
                 }
+                Item::Seed(decl) => self.canonicalize_block(&mut decl.body, &mut ScopeStack::new()),
                 Item::Test(decl) => self.canonicalize_block(&mut decl.body, &mut ScopeStack::new()),
                 Item::Import(_) => {}
             }
@@ -200,15 +250,17 @@ impl Canonicalizer {
                     self.canonicalize_type_ref(err, scope);
                 }
             }
-            TypeRefKind::Generic { args, .. } => {
+            TypeRefKind::Generic { args, .. } | TypeRefKind::Tuple(args) => {
                 for arg in args {
                     self.canonicalize_type_ref(arg, scope);
                 }
             }
             TypeRefKind::Refined { args, .. } => {
+                let inline = std::mem::replace(&mut self.inline_const_uses, true);
                 for arg in args {
                     self.canonicalize_expr(arg, &mut scope.clone());
                 }
+                self.inline_const_uses = inline;
             }
         }
     }
@@ -230,6 +282,17 @@ impl Canonicalizer {
                 self.canonicalize_expr(expr, scope);
                 scope.declare(name.name.clone());
             }
+            StmtKind::LetPattern { pat, ty, expr, .. } => {
+                if let Some(ty) = ty {
+                    self.canonicalize_type_ref(ty, scope);
+                }
+                self.canonicalize_expr(expr, scope);
+                let mut names = Vec::new();
+                collect_pattern_bindings(pat, &mut names);
+                for name in names {
+                    scope.declare(name);
+                }
+            }
             StmtKind::Assign { target, expr } => {
                 self.canonicalize_expr(target, scope);
                 self.canonicalize_expr(expr, scope);
@@ -260,14 +323,17 @@ impl Canonicalizer {
             }
             StmtKind::Match { expr, cases } => {
                 self.canonicalize_expr(expr, scope);
-                for (pattern, block) in cases {
+                for case in cases {
                     let mut case_scope = scope.clone();
                     let mut names = Vec::new();
-                    collect_pattern_bindings(pattern, &mut names);
+                    collect_pattern_bindings(&case.pat, &mut names);
                     for name in names {
                         case_scope.declare(name);
                     }
-                    self.canonicalize_block(block, &mut case_scope);
+                    if let Some(guard) = &mut case.guard {
+                        self.canonicalize_expr(guard, &mut case_scope);
+                    }
+                    self.canonicalize_block(&mut case.block, &mut case_scope);
                 }
             }
             StmtKind::For { pat, iter, block } => {
@@ -294,7 +360,30 @@ impl Canonicalizer {
         }
     }
 
+    fn const_value(&self, expr: &Expr, scope: &ScopeStack) -> Option<Expr> {
+        if !self.inline_const_uses {
+            return None;
+        }
+        match &expr.kind {
+            ExprKind::Ident(ident) if !scope.contains(&ident.name) => {
+                self.consts.get(&ident.name).cloned()
+            }
+            ExprKind::Member { base, name } => match &base.kind {
+                ExprKind::Ident(alias) if !scope.contains(&alias.name) => self
+                    .qualified_consts
+                    .get(&format!("{}.{}", alias.name, name.name))
+                    .cloned(),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
     fn canonicalize_expr(&mut self, expr: &mut Expr, scope: &mut ScopeStack) {
+        if let Some(value) = self.const_value(expr, scope) {
+            *expr = with_span(value, expr.span);
+            return;
+        }
         match &mut expr.kind {
             ExprKind::Literal(_) | ExprKind::Ident(_) => {}
             ExprKind::Binary { left, right, .. } => {
@@ -334,7 +423,13 @@ impl Canonicalizer {
                     self.canonicalize_expr(&mut field.value, scope);
                 }
             }
-            ExprKind::ListLit(items) => {
+            ExprKind::RecordUpdate { base, fields } => {
+                self.canonicalize_expr(base, scope);
+                for field in fields {
+                    self.canonicalize_expr(&mut field.value, scope);
+                }
+            }
+            ExprKind::ListLit(items) | ExprKind::TupleLit(items) => {
                 for item in items {
                     self.canonicalize_expr(item, scope);
                 }
@@ -356,7 +451,11 @@ impl Canonicalizer {
                 self.canonicalize_expr(left, scope);
                 self.canonicalize_expr(right, scope);
             }
-            ExprKind::BangChain { expr, error } => {
+            ExprKind::BangChain { expr, error }
+            | ExprKind::Await {
+                expr,
+                timeout: error,
+            } => {
                 self.canonicalize_expr(expr, scope);
                 if let Some(error) = error {
                     self.canonicalize_expr(error, scope);
@@ -405,7 +504,7 @@ impl Canonicalizer {
                     self.canonicalize_expr(child, &mut loop_scope);
                 }
             }
-            ExprKind::Await { expr } | ExprKind::Box { expr } => {
+            ExprKind::Box { expr } => {
                 self.canonicalize_expr(expr, scope);
             }
         }
@@ -588,13 +687,22 @@ fn canonicalize_html_attr_shorthand(args: &mut Vec<CallArg>) {
 
 fn collect_pattern_bindings(pattern: &Pattern, out: &mut Vec<String>) {
     match &pattern.kind {
-        PatternKind::Wildcard | PatternKind::Literal(_) => {}
+        PatternKind::Wildcard | PatternKind::Literal(_) | PatternKind::Range { .. } => {}
         PatternKind::Ident(ident) => {
             if ident.name != "_" {
                 out.push(ident.name.clone());
             }
         }
-        PatternKind::EnumVariant { args, .. } => {
+        PatternKind::Bind { name, pat } => {
+            out.push(name.name.clone());
+            collect_pattern_bindings(pat, out);
+        }
+        PatternKind::Or(alts) => {
+            if let Some(first) = alts.first() {
+                collect_pattern_bindings(first, out);
+            }
+        }
+        PatternKind::EnumVariant { args, .. } | PatternKind::Tuple(args) => {
             for arg in args {
                 collect_pattern_bindings(arg, out);
             }
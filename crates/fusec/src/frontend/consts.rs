@@ -0,0 +1,345 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::ast::{
+    BinaryOp, Expr, ExprKind, InterpPart, Item, Literal, Program, TypeRef, TypeRefKind, UnaryOp,
+};
+use crate::loader::{ModuleId, ModuleLink, ModuleMap, ModuleRegistry};
+use crate::span::Span;
+
+/// Folded values of every module-level `const`, keyed by owner module.
+///
+/// A const folds when its value is built from literals, list/tuple/map
+/// literals, interpolation, arithmetic, comparison and boolean operators, and
+/// references to other consts (local, imported by name, or `alias.NAME`).
+/// Anything else, including reference cycles, leaves the const unfolded so
+/// sema can report it.
+pub(crate) struct ConstIndex {
+    decls: HashMap<(ModuleId, String), Expr>,
+    import_items: HashMap<ModuleId, HashMap<String, ModuleLink>>,
+    modules: HashMap<ModuleId, ModuleMap>,
+    folded: HashMap<(ModuleId, String), Option<Expr>>,
+}
+
+impl ConstIndex {
+    pub(crate) fn from_registry(registry: &ModuleRegistry) -> Self {
+        let mut index = Self::empty();
+        for unit in registry.modules.values() {
+            index.add_module(unit.id, &unit.program);
+            index
+                .import_items
+                .insert(unit.id, unit.import_items.clone());
+            index.modules.insert(unit.id, unit.modules.clone());
+        }
+        index.fold_all();
+        index
+    }
+
+    pub(crate) fn from_program(program: &Program) -> Self {
+        let mut index = Self::empty();
+        index.add_module(0, program);
+        index.fold_all();
+        index
+    }
+
+    fn empty() -> Self {
+        Self {
+            decls: HashMap::new(),
+            import_items: HashMap::new(),
+            modules: HashMap::new(),
+            folded: HashMap::new(),
+        }
+    }
+
+    fn add_module(&mut self, module_id: ModuleId, program: &Program) {
+        for item in &program.items {
+            if let Item::Const(decl) = item {
+                self.decls
+                    .insert((module_id, decl.name.name.clone()), decl.value.clone());
+            }
+        }
+    }
+
+    fn fold_all(&mut self) {
+        let keys: Vec<_> = self.decls.keys().cloned().collect();
+        for key in keys {
+            let mut visiting = HashSet::new();
+            self.fold_const(&key, &mut visiting);
+        }
+    }
+
+    /// Consts visible by written name in `module_id`: its own and the ones
+    /// imported by name.
+    pub(crate) fn visible_in(&self, module_id: ModuleId) -> HashMap<String, Expr> {
+        let mut out = HashMap::new();
+        if let Some(imports) = self.import_items.get(&module_id) {
+            for (name, link) in imports {
+                if let Some(Some(value)) = self.folded.get(&(link.id, name.clone())) {
+                    out.insert(name.clone(), value.clone());
+                }
+            }
+        }
+        for ((owner, name), value) in &self.folded {
+            if *owner == module_id
+                && let Some(value) = value
+            {
+                out.insert(name.clone(), value.clone());
+            }
+        }
+        out
+    }
+
+    /// Consts reachable as `alias.NAME` through the module aliases of
+    /// `module_id`.
+    pub(crate) fn qualified_in(&self, module_id: ModuleId) -> HashMap<String, Expr> {
+        let mut out = HashMap::new();
+        let Some(modules) = self.modules.get(&module_id) else {
+            return out;
+        };
+        for (alias, link) in &modules.modules {
+            for ((owner, name), value) in &self.folded {
+                if *owner == link.id
+                    && let Some(value) = value
+                {
+                    out.insert(format!("{alias}.{name}"), value.clone());
+                }
+            }
+        }
+        out
+    }
+
+    fn fold_const(
+        &mut self,
+        key: &(ModuleId, String),
+        visiting: &mut HashSet<(ModuleId, String)>,
+    ) -> Option<Expr> {
+        if let Some(done) = self.folded.get(key) {
+            return done.clone();
+        }
+        if !visiting.insert(key.clone()) {
+            return None;
+        }
+        let value = self.decls.get(key)?.clone();
+        let folded = self.fold_expr(key.0, &value, visiting);
+        visiting.remove(key);
+        self.folded.insert(key.clone(), folded.clone());
+        folded
+    }
+
+    fn resolve_ref(&self, module_id: ModuleId, expr: &Expr) -> Option<(ModuleId, String)> {
+        match &expr.kind {
+            ExprKind::Ident(ident) => {
+                let local = (module_id, ident.name.clone());
+                if self.decls.contains_key(&local) {
+                    return Some(local);
+                }
+                let link = self.import_items.get(&module_id)?.get(&ident.name)?;
+                let imported = (link.id, ident.name.clone());
+                self.decls.contains_key(&imported).then_some(imported)
+            }
+            ExprKind::Member { base, name } => {
+                let ExprKind::Ident(alias) = &base.kind else {
+                    return None;
+                };
+                let link = self.modules.get(&module_id)?.get(&alias.name)?;
+                let key = (link.id, name.name.clone());
+                self.decls.contains_key(&key).then_some(key)
+            }
+            _ => None,
+        }
+    }
+
+    fn fold_expr(
+        &mut self,
+        module_id: ModuleId,
+        expr: &Expr,
+        visiting: &mut HashSet<(ModuleId, String)>,
+    ) -> Option<Expr> {
+        if let Some(key) = self.resolve_ref(module_id, expr) {
+            return self
+                .fold_const(&key, visiting)
+                .map(|value| with_span(value, expr.span));
+        }
+        let kind = match &expr.kind {
+            ExprKind::Literal(lit) => ExprKind::Literal(lit.clone()),
+            ExprKind::ListLit(items) => ExprKind::ListLit(
+                items
+                    .iter()
+                    .map(|item| self.fold_expr(module_id, item, visiting))
+                    .collect::<Option<_>>()?,
+            ),
+            ExprKind::TupleLit(items) => ExprKind::TupleLit(
+                items
+                    .iter()
+                    .map(|item| self.fold_expr(module_id, item, visiting))
+                    .collect::<Option<_>>()?,
+            ),
+            ExprKind::MapLit(pairs) => {
+                let mut folded = Vec::with_capacity(pairs.len());
+                for (key, value) in pairs {
+                    folded.push((
+                        self.fold_expr(module_id, key, visiting)?,
+                        self.fold_expr(module_id, value, visiting)?,
+                    ));
+                }
+                ExprKind::MapLit(folded)
+            }
+            ExprKind::Unary { op, expr: inner } => {
+                let lit = self.fold_literal(module_id, inner, visiting)?;
+                ExprKind::Literal(match (op, lit) {
+                    (UnaryOp::Neg, Literal::Int(v)) => Literal::Int(v.checked_neg()?),
+                    (UnaryOp::Neg, Literal::Float(v)) => Literal::Float(-v),
+                    (UnaryOp::Not, Literal::Bool(v)) => Literal::Bool(!v),
+                    _ => return None,
+                })
+            }
+            ExprKind::Binary { op, left, right } => {
+                let left = self.fold_literal(module_id, left, visiting)?;
+                let right = self.fold_literal(module_id, right, visiting)?;
+                ExprKind::Literal(fold_binary(op, left, right)?)
+            }
+            ExprKind::InterpString(parts) => {
+                let mut out = String::new();
+                for part in parts {
+                    match part {
+                        InterpPart::Text(text) => out.push_str(text),
+                        InterpPart::Expr(inner) => {
+                            match self.fold_literal(module_id, inner, visiting)? {
+                                Literal::String(text) => out.push_str(&text),
+                                Literal::Int(v) => out.push_str(&v.to_string()),
+                                Literal::Bool(v) => out.push_str(&v.to_string()),
+                                Literal::Float(_) | Literal::Null => return None,
+                            }
+                        }
+                    }
+                }
+                ExprKind::Literal(Literal::String(out))
+            }
+            _ => return None,
+        };
+        Some(Expr {
+            kind,
+            span: expr.span,
+        })
+    }
+
+    fn fold_literal(
+        &mut self,
+        module_id: ModuleId,
+        expr: &Expr,
+        visiting: &mut HashSet<(ModuleId, String)>,
+    ) -> Option<Literal> {
+        match self.fold_expr(module_id, expr, visiting)?.kind {
+            ExprKind::Literal(lit) => Some(lit),
+            _ => None,
+        }
+    }
+}
+
+fn fold_binary(op: &BinaryOp, left: Literal, right: Literal) -> Option<Literal> {
+    use Literal::{Bool, Float, Int, String};
+    Some(match (op, left, right) {
+        (BinaryOp::Add, Int(a), Int(b)) => Int(a.checked_add(b)?),
+        (BinaryOp::Sub, Int(a), Int(b)) => Int(a.checked_sub(b)?),
+        (BinaryOp::Mul, Int(a), Int(b)) => Int(a.checked_mul(b)?),
+        (BinaryOp::Div, Int(a), Int(b)) => Int(a.checked_div(b)?),
+        (BinaryOp::Mod, Int(a), Int(b)) => Int(a.checked_rem(b)?),
+        (BinaryOp::Add, Float(a), Float(b)) => Float(a + b),
+        (BinaryOp::Sub, Float(a), Float(b)) => Float(a - b),
+        (BinaryOp::Mul, Float(a), Float(b)) => Float(a * b),
+        (BinaryOp::Div, Float(a), Float(b)) if b != 0.0 => Float(a / b),
+        (BinaryOp::Add, String(a), String(b)) => String(a + &b),
+        (BinaryOp::Eq | BinaryOp::NotEq, left, right) => {
+            let equal = match (left, right) {
+                (Int(a), Int(b)) => a == b,
+                (Float(a), Float(b)) => a == b,
+                (Bool(a), Bool(b)) => a == b,
+                (String(a), String(b)) => a == b,
+                _ => return None,
+            };
+            Bool(equal == matches!(op, BinaryOp::Eq))
+        }
+        (BinaryOp::Lt, Int(a), Int(b)) => Bool(a < b),
+        (BinaryOp::LtEq, Int(a), Int(b)) => Bool(a <= b),
+        (BinaryOp::Gt, Int(a), Int(b)) => Bool(a > b),
+        (BinaryOp::GtEq, Int(a), Int(b)) => Bool(a >= b),
+        (BinaryOp::Lt, Float(a), Float(b)) => Bool(a < b),
+        (BinaryOp::LtEq, Float(a), Float(b)) => Bool(a <= b),
+        (BinaryOp::Gt, Float(a), Float(b)) => Bool(a > b),
+        (BinaryOp::GtEq, Float(a), Float(b)) => Bool(a >= b),
+        (BinaryOp::And, Bool(a), Bool(b)) => Bool(a && b),
+        (BinaryOp::Or, Bool(a), Bool(b)) => Bool(a || b),
+        _ => return None,
+    })
+}
+
+/// Replaces const references inside refinement arguments (`String(1..MAX)`)
+/// with their folded values; used when a type written in one module is copied
+/// into another where those names are not visible.
+pub(crate) fn inline_type_ref_consts(ty: &mut TypeRef, consts: &HashMap<String, Expr>) {
+    match &mut ty.kind {
+        TypeRefKind::Simple(_) => {}
+        TypeRefKind::Refined { args, .. } => {
+            for arg in args {
+                inline_expr_consts(arg, consts);
+            }
+        }
+        TypeRefKind::Generic { args, .. } | TypeRefKind::Tuple(args) => {
+            for arg in args {
+                inline_type_ref_consts(arg, consts);
+            }
+        }
+        TypeRefKind::Optional(inner) => inline_type_ref_consts(inner, consts),
+        TypeRefKind::Result { ok, err } => {
+            inline_type_ref_consts(ok, consts);
+            if let Some(err) = err {
+                inline_type_ref_consts(err, consts);
+            }
+        }
+    }
+}
+
+fn inline_expr_consts(expr: &mut Expr, consts: &HashMap<String, Expr>) {
+    match &mut expr.kind {
+        ExprKind::Ident(ident) => {
+            if let Some(value) = consts.get(&ident.name) {
+                *expr = with_span(value.clone(), expr.span);
+            }
+        }
+        ExprKind::Binary { left, right, .. } => {
+            inline_expr_consts(left, consts);
+            inline_expr_consts(right, consts);
+        }
+        ExprKind::Unary { expr: inner, .. } => inline_expr_consts(inner, consts),
+        ExprKind::Call { args, .. } => {
+            for arg in args {
+                inline_expr_consts(&mut arg.value, consts);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Re-anchors a folded value at `span` so diagnostics point at the use site
+/// rather than into the declaring module.
+pub(crate) fn with_span(mut expr: Expr, span: Span) -> Expr {
+    reanchor(&mut expr, span);
+    expr
+}
+
+fn reanchor(expr: &mut Expr, span: Span) {
+    expr.span = span;
+    match &mut expr.kind {
+        ExprKind::ListLit(items) | ExprKind::TupleLit(items) => {
+            for item in items {
+                reanchor(item, span);
+            }
+        }
+        ExprKind::MapLit(pairs) => {
+            for (key, value) in pairs {
+                reanchor(key, span);
+                reanchor(value, span);
+            }
+        }
+        _ => {}
+    }
+}
@@ -2,8 +2,9 @@ use std::collections::HashMap;
 
 use crate::ast::{
     AppDecl, Block, CallArg, ComponentDecl, ConfigDecl, Expr, ExprKind, FnDecl, Ident, ImplDecl,
-    InterpPart, Item, Literal, MigrationDecl, Param, Pattern, PatternKind, Program, RouteDecl,
-    ServiceDecl, Stmt, StmtKind, TestDecl, TypeRef, TypeRefKind,
+    InterfaceDecl, InterpPart, Item, Literal, MatchCase, MigrationDecl, Param, Pattern,
+    PatternKind, Program, RouteDecl, SeedDecl, ServiceDecl, Stmt, StmtKind, TestDecl, TypeRef,
+    TypeRefKind,
 };
 use crate::diag::Diagnostics;
 use crate::loader::{ModuleId, ModuleLink, ModuleMap, ModuleRegistry};
@@ -171,6 +172,103 @@ impl GlobalSymbols {
     }
 }
 
+/// Copies interface default bodies, inherited ones included, into every impl
+/// that does not define the member. Backends run this before
+/// monomorphization so the copies go through the same passes as hand-written
+/// impl methods.
+pub fn with_default_methods(registry: &ModuleRegistry) -> ModuleRegistry {
+    let mut expanded = registry.clone();
+    for (module_id, unit) in expanded.modules.iter_mut() {
+        for item in &mut unit.program.items {
+            let Item::Impl(decl) = item else {
+                continue;
+            };
+            for method in default_method_decls(registry, *module_id, &decl.interface.name) {
+                if decl
+                    .methods
+                    .iter()
+                    .all(|existing| existing.name.name != method.name.name)
+                {
+                    decl.methods.push(method);
+                }
+            }
+        }
+    }
+    expanded
+}
+
+fn default_method_decls(
+    registry: &ModuleRegistry,
+    module_id: ModuleId,
+    interface: &str,
+) -> Vec<FnDecl> {
+    let mut seen_interfaces: Vec<(ModuleId, &str)> = Vec::new();
+    let mut seen_members: Vec<&str> = Vec::new();
+    let mut out = Vec::new();
+    let mut pending = vec![(module_id, interface.to_string())];
+    while let Some((scope, name)) = pending.pop() {
+        let Some((owner, decl)) = interface_decl_in(registry, scope, &name) else {
+            continue;
+        };
+        if seen_interfaces.contains(&(owner, decl.name.name.as_str())) {
+            continue;
+        }
+        seen_interfaces.push((owner, decl.name.name.as_str()));
+        pending.extend(
+            decl.extends
+                .iter()
+                .rev()
+                .map(|parent| (owner, parent.name.clone())),
+        );
+        for member in &decl.members {
+            // A member redeclared by a child interface hides the parent's default.
+            if seen_members.contains(&member.name.name.as_str()) {
+                continue;
+            }
+            seen_members.push(member.name.name.as_str());
+            let Some(body) = &member.body else {
+                continue;
+            };
+            out.push(FnDecl {
+                name: member.name.clone(),
+                type_params: member.type_params.clone(),
+                params: member.params.clone(),
+                ret: member.ret.clone(),
+                where_clause: member.where_clause.clone(),
+                body: body.clone(),
+                is_pub: false,
+                doc: None,
+                span: member.span,
+            });
+        }
+    }
+    out
+}
+
+fn interface_decl_in<'r>(
+    registry: &'r ModuleRegistry,
+    module_id: ModuleId,
+    name: &str,
+) -> Option<(ModuleId, &'r InterfaceDecl)> {
+    let unit = registry.modules.get(&module_id)?;
+    let find = |owner: ModuleId, item_name: &str| {
+        registry
+            .modules
+            .get(&owner)?
+            .program
+            .items
+            .iter()
+            .find_map(|item| match item {
+                Item::Interface(decl) if decl.name.name == item_name => Some((owner, decl)),
+                _ => None,
+            })
+    };
+    if let Some((module_name, item_name)) = split_qualified_type_name(name) {
+        return find(unit.modules.get(module_name)?.id, item_name);
+    }
+    find(module_id, name).or_else(|| find(unit.import_items.get(name)?.id, name))
+}
+
 pub fn desugar_registry(registry: &ModuleRegistry) -> ModuleRegistry {
     let mut diags = Diagnostics::default();
     let mut symbols_by_id = HashMap::new();
@@ -253,6 +351,8 @@ struct InterfaceRewriter<'a> {
     synth_index: &'a SyntheticIndex,
     scopes: Vec<HashMap<String, Ty>>,
     current_self_type: Option<Ty>,
+    /// Numbers the hidden locals of desugared iterator `for` loops.
+    iter_loops: usize,
 }
 
 impl<'a> InterfaceRewriter<'a> {
@@ -275,6 +375,7 @@ impl<'a> InterfaceRewriter<'a> {
             synth_index,
             scopes: vec![HashMap::new()],
             current_self_type: None,
+            iter_loops: 0,
         }
     }
 
@@ -282,7 +383,11 @@ impl<'a> InterfaceRewriter<'a> {
         for item in &mut program.items {
             match item {
                 Item::Type(decl) => self.rewrite_type_decl(decl),
-                Item::Enum(_) | Item::Import(_) | Item::Interface(_) => {}
+                Item::Enum(_)
+                | Item::Import(_)
+                | Item::Interface(_)
+                | Item::TypeAlias(_)
+                | Item::Const(_) => {}
                 Item::Impl(decl) => self.rewrite_impl_decl(decl),
                 Item::Fn(decl) => {
                     let self_ty = self
@@ -295,6 +400,7 @@ impl<'a> InterfaceRewriter<'a> {
                 Item::Config(decl) => self.rewrite_config_decl(decl),
                 Item::App(decl) => self.rewrite_app_decl(decl),
                 Item::Migration(decl) => self.rewrite_migration_decl(decl),
+                Item::Seed(decl) => self.rewrite_seed_decl(decl),
                 Item::Test(decl) => self.rewrite_test_decl(decl),
             }
         }
@@ -350,6 +456,13 @@ impl<'a> InterfaceRewriter<'a> {
 
     fn rewrite_migration_decl(&mut self, decl: &mut MigrationDecl) {
         self.rewrite_block(&mut decl.body);
+        if let Some(down) = &mut decl.down {
+            self.rewrite_block(down);
+        }
+    }
+
+    fn rewrite_seed_decl(&mut self, decl: &mut SeedDecl) {
+        self.rewrite_block(&mut decl.body);
     }
 
     fn rewrite_test_decl(&mut self, decl: &mut TestDecl) {
@@ -390,6 +503,9 @@ impl<'a> InterfaceRewriter<'a> {
     }
 
     fn rewrite_stmt(&mut self, stmt: &mut Stmt) {
+        if let Some(kind) = self.desugar_iterator_for(stmt) {
+            stmt.kind = kind;
+        }
         match &mut stmt.kind {
             StmtKind::Let { name, ty, expr } | StmtKind::Var { name, ty, expr } => {
                 self.rewrite_expr(expr);
@@ -399,6 +515,15 @@ impl<'a> InterfaceRewriter<'a> {
                 };
                 self.bind_local(&name.name, value_ty);
             }
+            StmtKind::LetPattern { pat, ty, expr, .. } => {
+                self.rewrite_expr(expr);
+                self.rewrite_pattern(pat);
+                let value_ty = match ty {
+                    Some(ty) => self.resolve_type_ref(ty),
+                    None => self.expr_ty(expr),
+                };
+                self.bind_pattern(pat, &value_ty);
+            }
             StmtKind::Assign { target, expr } => {
                 self.rewrite_expr(target);
                 self.rewrite_expr(expr);
@@ -427,11 +552,14 @@ impl<'a> InterfaceRewriter<'a> {
             StmtKind::Match { expr, cases } => {
                 self.rewrite_expr(expr);
                 let expr_ty = self.expr_ty(expr);
-                for (pattern, block) in cases {
-                    self.rewrite_pattern(pattern);
+                for case in cases {
+                    self.rewrite_pattern(&mut case.pat);
                     self.scopes.push(HashMap::new());
-                    self.bind_pattern(pattern, &expr_ty);
-                    self.rewrite_block(block);
+                    self.bind_pattern(&case.pat, &expr_ty);
+                    if let Some(guard) = &mut case.guard {
+                        self.rewrite_expr(guard);
+                    }
+                    self.rewrite_block(&mut case.block);
                     self.scopes.pop();
                 }
             }
@@ -439,7 +567,8 @@ impl<'a> InterfaceRewriter<'a> {
                 self.rewrite_expr(iter);
                 self.rewrite_pattern(pat);
                 let item_ty = match self.expr_ty(iter) {
-                    Ty::List(inner) => *inner,
+                    Ty::List(inner) | Ty::Range(inner) => *inner,
+                    Ty::Map(key, value) if pat.is_pair() => Ty::Tuple(vec![*key, *value]),
                     Ty::Map(_, value) => *value,
                     _ => Ty::Unknown,
                 };
@@ -462,8 +591,14 @@ impl<'a> InterfaceRewriter<'a> {
 
     fn rewrite_pattern(&mut self, pattern: &mut Pattern) {
         match &mut pattern.kind {
-            PatternKind::Wildcard | PatternKind::Literal(_) | PatternKind::Ident(_) => {}
-            PatternKind::EnumVariant { args, .. } => {
+            PatternKind::Wildcard
+            | PatternKind::Literal(_)
+            | PatternKind::Ident(_)
+            | PatternKind::Range { .. } => {}
+            PatternKind::Bind { pat, .. } => self.rewrite_pattern(pat),
+            PatternKind::EnumVariant { args, .. }
+            | PatternKind::Tuple(args)
+            | PatternKind::Or(args) => {
                 for arg in args {
                     self.rewrite_pattern(arg);
                 }
@@ -481,6 +616,109 @@ impl<'a> InterfaceRewriter<'a> {
         }
     }
 
+    /// Rewrites `for pat in iter` over a type with an `impl Iterator` into a
+    /// loop that threads the iterator through `next()`:
+    ///
+    /// ```text
+    /// if true:
+    ///   var __fuse_iter_N = iter
+    ///   while true:
+    ///     match __fuse_iter_N.next():
+    ///       None -> break
+    ///       Some((pat, __fuse_iter_next_N)) ->
+    ///         __fuse_iter_N = __fuse_iter_next_N
+    ///         body
+    /// ```
+    ///
+    /// The iterator advances before the body runs, so `continue` keeps
+    /// working. The result is rewritten like any other statement, which turns
+    /// the `next()` call into the synthetic impl function.
+    fn desugar_iterator_for(&mut self, stmt: &mut Stmt) -> Option<StmtKind> {
+        let StmtKind::For { pat, iter, block } = &mut stmt.kind else {
+            return None;
+        };
+        let (Ty::Struct(target) | Ty::Enum(target)) = self.expr_ty(iter) else {
+            return None;
+        };
+        self.synth_index
+            .resolve(&target, "next", InterfaceMethodKind::Instance)?;
+        let span = stmt.span;
+        let iter_name = format!("__fuse_iter_{}", self.iter_loops);
+        let next_name = format!("__fuse_iter_next_{}", self.iter_loops);
+        self.iter_loops += 1;
+        let ident = |name: &str| Ident {
+            name: name.to_string(),
+            span,
+        };
+        let ident_expr = |name: &str| Expr {
+            kind: ExprKind::Ident(ident(name)),
+            span,
+        };
+        let stmt_of = |kind: StmtKind| Stmt { kind, span };
+        let block_of = |stmts: Vec<Stmt>| Block { stmts, span };
+        let pattern_of = |kind: PatternKind| Pattern { kind, span };
+        let true_expr = Expr {
+            kind: ExprKind::Literal(Literal::Bool(true)),
+            span,
+        };
+        let next_call = Expr {
+            kind: ExprKind::Call {
+                callee: Box::new(Expr {
+                    kind: ExprKind::Member {
+                        base: Box::new(ident_expr(&iter_name)),
+                        name: ident("next"),
+                    },
+                    span,
+                }),
+                args: Vec::new(),
+                type_args: Vec::new(),
+            },
+            span,
+        };
+        let item_pat = std::mem::replace(pat, pattern_of(PatternKind::Wildcard));
+        let mut body = vec![stmt_of(StmtKind::Assign {
+            target: ident_expr(&iter_name),
+            expr: ident_expr(&next_name),
+        })];
+        body.append(&mut block.stmts);
+        let cases = vec![
+            MatchCase {
+                pat: pattern_of(PatternKind::Ident(ident("None"))),
+                guard: None,
+                block: block_of(vec![stmt_of(StmtKind::Break)]),
+            },
+            MatchCase {
+                pat: pattern_of(PatternKind::EnumVariant {
+                    name: ident("Some"),
+                    args: vec![pattern_of(PatternKind::Tuple(vec![
+                        item_pat,
+                        pattern_of(PatternKind::Ident(ident(&next_name))),
+                    ]))],
+                }),
+                guard: None,
+                block: block_of(body),
+            },
+        ];
+        let init = stmt_of(StmtKind::Var {
+            name: ident(&iter_name),
+            ty: None,
+            expr: std::mem::replace(iter, true_expr.clone()),
+        });
+        let looped = stmt_of(StmtKind::While {
+            cond: true_expr.clone(),
+            block: block_of(vec![stmt_of(StmtKind::Match {
+                expr: next_call,
+                cases,
+            })]),
+        });
+        Some(StmtKind::If {
+            cond: true_expr,
+            then_block: block_of(vec![init, looped]),
+            else_if: Vec::new(),
+            else_block: None,
+        })
+    }
+
     fn rewrite_expr(&mut self, expr: &mut Expr) -> Ty {
         match &mut expr.kind {
             ExprKind::Literal(_) | ExprKind::Ident(_) => {}
@@ -489,7 +727,10 @@ impl<'a> InterfaceRewriter<'a> {
                 self.rewrite_expr(right);
             }
             ExprKind::Unary { expr, .. }
-            | ExprKind::Await { expr }
+            | ExprKind::Await {
+                expr,
+                timeout: None,
+            }
             | ExprKind::Box { expr }
             | ExprKind::BangChain { expr, error: None } => {
                 self.rewrite_expr(expr);
@@ -497,6 +738,10 @@ impl<'a> InterfaceRewriter<'a> {
             ExprKind::BangChain {
                 expr,
                 error: Some(error),
+            }
+            | ExprKind::Await {
+                expr,
+                timeout: Some(error),
             } => {
                 self.rewrite_expr(expr);
                 self.rewrite_expr(error);
@@ -528,7 +773,13 @@ impl<'a> InterfaceRewriter<'a> {
                     self.rewrite_expr(&mut field.value);
                 }
             }
-            ExprKind::ListLit(items) => {
+            ExprKind::RecordUpdate { base, fields } => {
+                self.rewrite_expr(base);
+                for field in fields {
+                    self.rewrite_expr(&mut field.value);
+                }
+            }
+            ExprKind::ListLit(items) | ExprKind::TupleLit(items) => {
                 for item in items {
                     self.rewrite_expr(item);
                 }
@@ -575,7 +826,7 @@ impl<'a> InterfaceRewriter<'a> {
                 self.rewrite_expr(iter);
                 self.rewrite_pattern(pat);
                 let item_ty = match self.expr_ty(iter) {
-                    Ty::List(inner) => *inner,
+                    Ty::List(inner) | Ty::Range(inner) => *inner,
                     Ty::Map(_, value) => *value,
                     _ => Ty::Unknown,
                 };
@@ -674,7 +925,7 @@ impl<'a> InterfaceRewriter<'a> {
             ExprKind::Member { base, name } => self.resolve_member(base, name, false).ty,
             ExprKind::OptionalMember { base, name } => self.resolve_member(base, name, true).ty,
             ExprKind::Index { base, .. } => match self.expr_ty(base) {
-                Ty::List(inner) => *inner,
+                Ty::List(inner) | Ty::Range(inner) => *inner,
                 Ty::Map(_, value) => *value,
                 _ => Ty::Unknown,
             },
@@ -687,10 +938,14 @@ impl<'a> InterfaceRewriter<'a> {
                 _ => Ty::Option(Box::new(Ty::Unknown)),
             },
             ExprKind::StructLit { name, .. } => self.resolve_struct_lit_ty(&name.name),
+            ExprKind::RecordUpdate { base, .. } => self.expr_ty(base),
             ExprKind::ListLit(items) => {
                 let inner = items.first().map(|item| self.expr_ty(item)).unwrap_or(Ty::Unknown);
                 Ty::List(Box::new(inner))
             }
+            ExprKind::TupleLit(items) => {
+                Ty::Tuple(items.iter().map(|item| self.expr_ty(item)).collect())
+            }
             ExprKind::MapLit(items) => {
                 let value_ty = items
                     .first()
@@ -710,10 +965,16 @@ impl<'a> InterfaceRewriter<'a> {
             },
             ExprKind::Spawn { block } => Ty::Task(Box::new(self.block_ty(block))),
             ExprKind::HtmlIf { .. } | ExprKind::HtmlFor { .. } => Ty::List(Box::new(Ty::Html)),
-            ExprKind::Await { expr } => match self.expr_ty(expr) {
-                Ty::Task(inner) => *inner,
-                other => other,
-            },
+            ExprKind::Await { expr, timeout } => {
+                let value = match self.expr_ty(expr) {
+                    Ty::Task(inner) => *inner,
+                    other => other,
+                };
+                match timeout {
+                    Some(_) => Ty::Result(Box::new(value), Box::new(Ty::Error)),
+                    None => value,
+                }
+            }
             ExprKind::Box { expr } => Ty::Boxed(Box::new(self.expr_ty(expr))),
         }
     }
@@ -763,9 +1024,8 @@ impl<'a> InterfaceRewriter<'a> {
             return Ty::Module(name.to_string());
         }
         match name {
-            "db" | "json" | "html" | "svg" | "request" | "response" | "http" | "time" | "crypto" => {
-                Ty::External(name.to_string())
-            }
+            "db" | "json" | "html" | "svg" | "request" | "response" | "http" | "time"
+            | "crypto" | "decimal" => Ty::External(name.to_string()),
             _ => Ty::Unknown,
         }
     }
@@ -932,6 +1192,20 @@ impl<'a> InterfaceRewriter<'a> {
                     Box::new(Ty::Unknown),
                 )))),
             }),
+            ("db", "execute") => Ty::Fn(FnSig {
+                type_params: Vec::new(),
+                params: vec![ParamSig {
+                    name: "sql".to_string(),
+                    ty: Ty::String,
+                    has_default: false,
+                }],
+                ret: Box::new(Ty::External("db.result".to_string())),
+            }),
+            ("db", "rollback") => Ty::Fn(FnSig {
+                type_params: Vec::new(),
+                params: Vec::new(),
+                ret: Box::new(Ty::Unit),
+            }),
             ("db", "from") => Ty::Fn(FnSig {
                 type_params: Vec::new(),
                 params: vec![ParamSig {
@@ -946,12 +1220,24 @@ impl<'a> InterfaceRewriter<'a> {
                     method,
                     "select"
                         | "where"
+                        | "or_where"
+                        | "join"
+                        | "left_join"
                         | "order_by"
                         | "limit"
+                        | "offset"
+                        | "group_by"
+                        | "having"
+                        | "aggregate"
                         | "insert"
+                        | "insert_many"
                         | "upsert"
+                        | "on_conflict"
+                        | "do_update"
+                        | "do_nothing"
                         | "update"
                         | "delete"
+                        | "returning"
                 ) => Ty::Fn(FnSig {
                 type_params: Vec::new(),
                 params: Vec::new(),
@@ -983,6 +1269,11 @@ impl<'a> InterfaceRewriter<'a> {
                 params: Vec::new(),
                 ret: Box::new(Ty::Unit),
             }),
+            ("query", "execute") => Ty::Fn(FnSig {
+                type_params: Vec::new(),
+                params: Vec::new(),
+                ret: Box::new(Ty::External("db.result".to_string())),
+            }),
             ("query", "sql") => Ty::Fn(FnSig {
                 type_params: Vec::new(),
                 params: Vec::new(),
@@ -1080,6 +1371,12 @@ impl<'a> InterfaceRewriter<'a> {
                     repr: format!("{}(...)", base.name),
                 }
             }
+            TypeRefKind::Tuple(items) => Ty::Tuple(
+                items
+                    .iter()
+                    .map(|item| self.resolve_type_ref_in(module_id, item))
+                    .collect(),
+            ),
         }
     }
 
@@ -1136,6 +1433,11 @@ impl<'a> InterfaceRewriter<'a> {
             "Html" => Ty::Html,
             "Id" => Ty::Id,
             "Email" => Ty::Email,
+            "Instant" => Ty::Instant,
+            "Date" => Ty::Date,
+            "Duration" => Ty::Duration,
+            "DateTime" => Ty::DateTime,
+            "Decimal" => Ty::Decimal,
             "Error" => Ty::Error,
             _ => Ty::Unknown,
         }
@@ -1197,7 +1499,11 @@ impl<'a> InterfaceRewriter<'a> {
                 self.bind_local(&ident.name, ty.clone());
             }
             PatternKind::EnumVariant { args, .. } => {
-                if let Ty::Enum(enum_name) = ty {
+                if let (Ty::Option(inner), Some("Some"), [arg]) =
+                    (ty, pattern_enum_name(pattern), args.as_slice())
+                {
+                    self.bind_pattern(arg, inner);
+                } else if let Ty::Enum(enum_name) = ty {
                     if let Some(variant) = self.lookup_enum_variant(enum_name, pattern_enum_name(pattern).unwrap_or_default()) {
                         for (arg, payload_ty) in args.iter().zip(variant.payload.iter()) {
                             let payload_ty = self.resolve_type_ref(payload_ty);
@@ -1218,7 +1524,25 @@ impl<'a> InterfaceRewriter<'a> {
                     }
                 }
             }
-            PatternKind::Wildcard | PatternKind::Literal(_) => {}
+            PatternKind::Tuple(items) => {
+                for (idx, item) in items.iter().enumerate() {
+                    let item_ty = match ty {
+                        Ty::Tuple(elems) => elems.get(idx).cloned().unwrap_or(Ty::Unknown),
+                        _ => Ty::Unknown,
+                    };
+                    self.bind_pattern(item, &item_ty);
+                }
+            }
+            PatternKind::Bind { name, pat } => {
+                self.bind_local(&name.name, ty.clone());
+                self.bind_pattern(pat, ty);
+            }
+            PatternKind::Or(alts) => {
+                if let Some(first) = alts.first() {
+                    self.bind_pattern(first, ty);
+                }
+            }
+            PatternKind::Wildcard | PatternKind::Literal(_) | PatternKind::Range { .. } => {}
         }
     }
 
@@ -1256,7 +1580,9 @@ impl<'a> InterfaceRewriter<'a> {
             | crate::ast::BinaryOp::GtEq
             | crate::ast::BinaryOp::And
             | crate::ast::BinaryOp::Or => Ty::Bool,
-            crate::ast::BinaryOp::Range => Ty::Range(Box::new(left.clone())),
+            crate::ast::BinaryOp::Range | crate::ast::BinaryOp::RangeExclusive => {
+                Ty::Range(Box::new(left.clone()))
+            }
         }
     }
 
@@ -1453,7 +1779,7 @@ fn rewrite_self_in_type_ref(ty: &mut TypeRef, target: &Ident) {
         TypeRefKind::Simple(ident) if ident.name == "Self" => {
             *ident = target.clone();
         }
-        TypeRefKind::Generic { args, .. } => {
+        TypeRefKind::Generic { args, .. } | TypeRefKind::Tuple(args) => {
             for arg in args {
                 rewrite_self_in_type_ref(arg, target);
             }
@@ -1478,6 +1804,10 @@ fn rewrite_self_in_block(block: &mut Block, target: &Ident) {
 fn rewrite_self_in_stmt(stmt: &mut Stmt, target: &Ident) {
     match &mut stmt.kind {
         StmtKind::Let { expr, .. } | StmtKind::Var { expr, .. } => rewrite_self_in_expr(expr, target),
+        StmtKind::LetPattern { pat, expr, .. } => {
+            rewrite_self_in_pattern(pat, target);
+            rewrite_self_in_expr(expr, target);
+        }
         StmtKind::Assign { target: lhs, expr } => {
             rewrite_self_in_expr(lhs, target);
             rewrite_self_in_expr(expr, target);
@@ -1505,9 +1835,12 @@ fn rewrite_self_in_stmt(stmt: &mut Stmt, target: &Ident) {
         }
         StmtKind::Match { expr, cases } => {
             rewrite_self_in_expr(expr, target);
-            for (pattern, block) in cases {
-                rewrite_self_in_pattern(pattern, target);
-                rewrite_self_in_block(block, target);
+            for case in cases {
+                rewrite_self_in_pattern(&mut case.pat, target);
+                if let Some(guard) = &mut case.guard {
+                    rewrite_self_in_expr(guard, target);
+                }
+                rewrite_self_in_block(&mut case.block, target);
             }
         }
         StmtKind::For { pat, iter, block } => {
@@ -1527,7 +1860,9 @@ fn rewrite_self_in_stmt(stmt: &mut Stmt, target: &Ident) {
 
 fn rewrite_self_in_pattern(pattern: &mut Pattern, target: &Ident) {
     match &mut pattern.kind {
-        PatternKind::EnumVariant { args, .. } => {
+        PatternKind::EnumVariant { args, .. }
+        | PatternKind::Tuple(args)
+        | PatternKind::Or(args) => {
             for arg in args {
                 rewrite_self_in_pattern(arg, target);
             }
@@ -1540,7 +1875,11 @@ fn rewrite_self_in_pattern(pattern: &mut Pattern, target: &Ident) {
                 rewrite_self_in_pattern(&mut field.pat, target);
             }
         }
-        PatternKind::Wildcard | PatternKind::Literal(_) | PatternKind::Ident(_) => {}
+        PatternKind::Bind { pat, .. } => rewrite_self_in_pattern(pat, target),
+        PatternKind::Wildcard
+        | PatternKind::Literal(_)
+        | PatternKind::Ident(_)
+        | PatternKind::Range { .. } => {}
     }
 }
 
@@ -1552,12 +1891,19 @@ fn rewrite_self_in_expr(expr: &mut Expr, target: &Ident) {
             rewrite_self_in_expr(right, target);
         }
         ExprKind::Unary { expr, .. }
-        | ExprKind::Await { expr }
+        | ExprKind::Await {
+            expr,
+            timeout: None,
+        }
         | ExprKind::Box { expr }
         | ExprKind::BangChain { expr, error: None } => rewrite_self_in_expr(expr, target),
         ExprKind::BangChain {
             expr,
             error: Some(error),
+        }
+        | ExprKind::Await {
+            expr,
+            timeout: Some(error),
         } => {
             rewrite_self_in_expr(expr, target);
             rewrite_self_in_expr(error, target);
@@ -1587,7 +1933,13 @@ fn rewrite_self_in_expr(expr: &mut Expr, target: &Ident) {
                 rewrite_self_in_expr(&mut field.value, target);
             }
         }
-        ExprKind::ListLit(items) => {
+        ExprKind::RecordUpdate { base, fields } => {
+            rewrite_self_in_expr(base, target);
+            for field in fields {
+                rewrite_self_in_expr(&mut field.value, target);
+            }
+        }
+        ExprKind::ListLit(items) | ExprKind::TupleLit(items) => {
             for item in items {
                 rewrite_self_in_expr(item, target);
             }
@@ -1774,6 +2126,21 @@ fn resolve_type_ref_with_self(
             )),
             repr: format!("{}(...)", base.name),
         },
+        TypeRefKind::Tuple(items) => Ty::Tuple(
+            items
+                .iter()
+                .map(|item| {
+                    resolve_type_ref_with_self(
+                        module_id,
+                        item,
+                        current_self,
+                        modules,
+                        import_items,
+                        symbols_by_id,
+                    )
+                })
+                .collect(),
+        ),
     }
 }
 
@@ -1797,6 +2164,11 @@ fn resolve_simple_type_name(
         "Html" => Ty::Html,
         "Id" => Ty::Id,
         "Email" => Ty::Email,
+        "Instant" => Ty::Instant,
+        "Date" => Ty::Date,
+        "Duration" => Ty::Duration,
+        "DateTime" => Ty::DateTime,
+        "Decimal" => Ty::Decimal,
         "Error" => Ty::Error,
         _ => Ty::Unknown,
     }
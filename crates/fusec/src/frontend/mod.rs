@@ -1,4 +1,5 @@
 pub mod canonicalize;
+pub mod consts;
 pub mod html_shorthand;
 pub mod interface_desugar;
 pub mod html_tag_builtin;
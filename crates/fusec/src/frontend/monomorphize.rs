@@ -1,10 +1,12 @@
 use std::collections::{HashMap, HashSet};
 
 use crate::ast::{
-    Block, Expr, ExprKind, FnDecl, Ident, InterpPart, Item, Program, Stmt, StmtKind, TypeRef,
-    TypeRefKind,
+    BinaryOp, Block, CallArg, EnumDecl, Expr, ExprKind, FnDecl, Ident, InterpPart, Item, Literal,
+    Param, Program, Stmt, StmtKind, StructField, TypeDecl, TypeParam, TypeRef, TypeRefKind,
+    UnaryOp,
 };
-use crate::loader::{ModuleId, ModuleRegistry};
+use crate::frontend::consts::{ConstIndex, inline_type_ref_consts};
+use crate::loader::{ModuleId, ModuleLink, ModuleMap, ModuleRegistry};
 
 /// Run frontend monomorphization: specialise every generic function call,
 /// producing concrete copies and rewriting call sites.  Iteration continues
@@ -191,6 +193,26 @@ fn collect_generic_calls_in_program(
                 collect_in_block(&decl.body, caller_module_id, modules, import_items, index, &mut out)
             }
             Item::Migration(decl) => {
+                collect_in_block(
+                    &decl.body,
+                    caller_module_id,
+                    modules,
+                    import_items,
+                    index,
+                    &mut out,
+                );
+                if let Some(down) = &decl.down {
+                    collect_in_block(
+                        down,
+                        caller_module_id,
+                        modules,
+                        import_items,
+                        index,
+                        &mut out,
+                    );
+                }
+            }
+            Item::Seed(decl) => {
                 collect_in_block(&decl.body, caller_module_id, modules, import_items, index, &mut out)
             }
             Item::Test(decl) => {
@@ -229,7 +251,9 @@ fn collect_in_stmt(
     out: &mut Vec<(ModuleId, String, Vec<TypeRef>)>,
 ) {
     match &stmt.kind {
-        StmtKind::Let { expr, .. } | StmtKind::Var { expr, .. } => {
+        StmtKind::Let { expr, .. }
+        | StmtKind::Var { expr, .. }
+        | StmtKind::LetPattern { expr, .. } => {
             collect_in_expr(expr, caller_module_id, modules, import_items, index, out)
         }
         StmtKind::Assign { target, expr } => {
@@ -259,8 +283,18 @@ fn collect_in_stmt(
         }
         StmtKind::Match { expr, cases } => {
             collect_in_expr(expr, caller_module_id, modules, import_items, index, out);
-            for (_, b) in cases {
-                collect_in_block(b, caller_module_id, modules, import_items, index, out);
+            for case in cases {
+                if let Some(guard) = &case.guard {
+                    collect_in_expr(guard, caller_module_id, modules, import_items, index, out);
+                }
+                collect_in_block(
+                    &case.block,
+                    caller_module_id,
+                    modules,
+                    import_items,
+                    index,
+                    out,
+                );
             }
         }
         StmtKind::For { iter, block, .. } => {
@@ -316,11 +350,16 @@ fn collect_in_expr(
             collect_in_expr(right, caller_module_id, modules, import_items, index, out);
         }
         ExprKind::Unary { expr, .. }
-        | ExprKind::Await { expr }
         | ExprKind::Box { expr }
         | ExprKind::BangChain { expr, .. } => {
             collect_in_expr(expr, caller_module_id, modules, import_items, index, out)
         }
+        ExprKind::Await { expr, timeout } => {
+            collect_in_expr(expr, caller_module_id, modules, import_items, index, out);
+            if let Some(timeout) = timeout {
+                collect_in_expr(timeout, caller_module_id, modules, import_items, index, out);
+            }
+        }
         ExprKind::Member { base, .. } | ExprKind::OptionalMember { base, .. } => {
             collect_in_expr(base, caller_module_id, modules, import_items, index, out)
         }
@@ -333,7 +372,13 @@ fn collect_in_expr(
                 collect_in_expr(&f.value, caller_module_id, modules, import_items, index, out);
             }
         }
-        ExprKind::ListLit(items) => {
+        ExprKind::RecordUpdate { base, fields } => {
+            collect_in_expr(base, caller_module_id, modules, import_items, index, out);
+            for f in fields {
+                collect_in_expr(&f.value, caller_module_id, modules, import_items, index, out);
+            }
+        }
+        ExprKind::ListLit(items) | ExprKind::TupleLit(items) => {
             for e in items {
                 collect_in_expr(e, caller_module_id, modules, import_items, index, out);
             }
@@ -525,7 +570,19 @@ fn rewrite_program(
                 import_items,
                 index,
             ),
-            Item::Migration(decl) => rewrite_block(
+            Item::Migration(decl) => {
+                rewrite_block(
+                    &mut decl.body,
+                    caller_module_id,
+                    modules,
+                    import_items,
+                    index,
+                );
+                if let Some(down) = &mut decl.down {
+                    rewrite_block(down, caller_module_id, modules, import_items, index);
+                }
+            }
+            Item::Seed(decl) => rewrite_block(
                 &mut decl.body,
                 caller_module_id,
                 modules,
@@ -575,7 +632,9 @@ fn rewrite_stmt(
     index: &GenericFnIndex,
 ) {
     match &mut stmt.kind {
-        StmtKind::Let { expr, .. } | StmtKind::Var { expr, .. } => {
+        StmtKind::Let { expr, .. }
+        | StmtKind::Var { expr, .. }
+        | StmtKind::LetPattern { expr, .. } => {
             rewrite_expr(expr, caller_module_id, modules, import_items, index)
         }
         StmtKind::Assign { target, expr } => {
@@ -605,8 +664,17 @@ fn rewrite_stmt(
         }
         StmtKind::Match { expr, cases } => {
             rewrite_expr(expr, caller_module_id, modules, import_items, index);
-            for (_, b) in cases {
-                rewrite_block(b, caller_module_id, modules, import_items, index);
+            for case in cases {
+                if let Some(guard) = &mut case.guard {
+                    rewrite_expr(guard, caller_module_id, modules, import_items, index);
+                }
+                rewrite_block(
+                    &mut case.block,
+                    caller_module_id,
+                    modules,
+                    import_items,
+                    index,
+                );
             }
         }
         StmtKind::For { iter, block, .. } => {
@@ -685,7 +753,10 @@ fn rewrite_expr(
             rewrite_expr(right, caller_module_id, modules, import_items, index);
         }
         ExprKind::Unary { expr: inner, .. }
-        | ExprKind::Await { expr: inner }
+        | ExprKind::Await {
+            expr: inner,
+            timeout: None,
+        }
         | ExprKind::Box { expr: inner }
         | ExprKind::BangChain {
             expr: inner,
@@ -694,6 +765,10 @@ fn rewrite_expr(
         ExprKind::BangChain {
             expr: inner,
             error: Some(err),
+        }
+        | ExprKind::Await {
+            expr: inner,
+            timeout: Some(err),
         } => {
             rewrite_expr(inner, caller_module_id, modules, import_items, index);
             rewrite_expr(err, caller_module_id, modules, import_items, index);
@@ -710,7 +785,13 @@ fn rewrite_expr(
                 rewrite_expr(&mut f.value, caller_module_id, modules, import_items, index);
             }
         }
-        ExprKind::ListLit(items) => {
+        ExprKind::RecordUpdate { base, fields } => {
+            rewrite_expr(base, caller_module_id, modules, import_items, index);
+            for f in fields {
+                rewrite_expr(&mut f.value, caller_module_id, modules, import_items, index);
+            }
+        }
+        ExprKind::ListLit(items) | ExprKind::TupleLit(items) => {
             for e in items {
                 rewrite_expr(e, caller_module_id, modules, import_items, index);
             }
@@ -837,6 +918,12 @@ fn subst_type_ref(ty: &mut TypeRef, bindings: &HashMap<String, TypeRef>) {
                 ty.kind = TypeRefKind::Refined { base, args };
             }
         }
+        TypeRefKind::Tuple(mut items) => {
+            for item in &mut items {
+                subst_type_ref(item, bindings);
+            }
+            ty.kind = TypeRefKind::Tuple(items);
+        }
     }
 }
 
@@ -852,7 +939,9 @@ fn subst_block(block: &mut Block, bindings: &HashMap<String, TypeRef>) {
 
 fn subst_stmt(stmt: &mut Stmt, bindings: &HashMap<String, TypeRef>) {
     match &mut stmt.kind {
-        StmtKind::Let { ty, expr, .. } | StmtKind::Var { ty, expr, .. } => {
+        StmtKind::Let { ty, expr, .. }
+        | StmtKind::Var { ty, expr, .. }
+        | StmtKind::LetPattern { ty, expr, .. } => {
             if let Some(t) = ty {
                 subst_type_ref(t, bindings);
             }
@@ -885,8 +974,11 @@ fn subst_stmt(stmt: &mut Stmt, bindings: &HashMap<String, TypeRef>) {
         }
         StmtKind::Match { expr, cases } => {
             subst_expr(expr, bindings);
-            for (_, b) in cases {
-                subst_block(b, bindings);
+            for case in cases {
+                if let Some(guard) = &mut case.guard {
+                    subst_expr(guard, bindings);
+                }
+                subst_block(&mut case.block, bindings);
             }
         }
         StmtKind::For { iter, block, .. } => {
@@ -920,7 +1012,10 @@ fn subst_expr(expr: &mut Expr, bindings: &HashMap<String, TypeRef>) {
             subst_expr(right, bindings);
         }
         ExprKind::Unary { expr: inner, .. }
-        | ExprKind::Await { expr: inner }
+        | ExprKind::Await {
+            expr: inner,
+            timeout: None,
+        }
         | ExprKind::Box { expr: inner }
         | ExprKind::BangChain {
             expr: inner,
@@ -929,6 +1024,10 @@ fn subst_expr(expr: &mut Expr, bindings: &HashMap<String, TypeRef>) {
         ExprKind::BangChain {
             expr: inner,
             error: Some(err),
+        }
+        | ExprKind::Await {
+            expr: inner,
+            timeout: Some(err),
         } => {
             subst_expr(inner, bindings);
             subst_expr(err, bindings);
@@ -964,7 +1063,13 @@ fn subst_expr(expr: &mut Expr, bindings: &HashMap<String, TypeRef>) {
                 subst_expr(&mut f.value, bindings);
             }
         }
-        ExprKind::ListLit(items) => {
+        ExprKind::RecordUpdate { base, fields } => {
+            subst_expr(base, bindings);
+            for f in fields {
+                subst_expr(&mut f.value, bindings);
+            }
+        }
+        ExprKind::ListLit(items) | ExprKind::TupleLit(items) => {
             for e in items {
                 subst_expr(e, bindings);
             }
@@ -1014,37 +1119,1103 @@ fn subst_expr(expr: &mut Expr, bindings: &HashMap<String, TypeRef>) {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Generic `type` and `enum` declarations
+// ---------------------------------------------------------------------------
+
+/// Upper bound on instances generated per module; stops polymorphic recursion
+/// such as `type Nest<T>: inner: Nest<List<T>>?` from expanding forever.
+const MAX_TYPE_INSTANCES: usize = 256;
+
+/// Instantiate generic `type` and `enum` declarations for every concrete use.
+///
+/// `Page<Note>` in a type position becomes a reference to a generated
+/// `Page_Note` declaration in the using module, `Page<Note>(...)` becomes a
+/// struct literal of that declaration, and `Outcome.Ok<Int, String>(...)`
+/// constructs a variant of `Outcome_Int_String`.  This runs before semantic
+/// analysis so every later stage (sema, both backends, JSON, typed queries,
+/// OpenAPI) only ever sees plain nominal types.  References that cannot be
+/// instantiated (wrong arity, type parameters as arguments) are left in place
+/// for sema to report.
+///
+/// The same walk expands `type Alias = TypeRef` aliases, so a reference to an
+/// alias becomes its target before any generic in that target is
+/// instantiated.  Self-referential aliases are left in place for sema.
+pub fn monomorphize_types(registry: &mut ModuleRegistry) {
+    let index = GenericTypeIndex::build(
+        registry
+            .modules
+            .values()
+            .map(|unit| (unit.id, &unit.program, &unit.import_items)),
+    );
+    if index.decls.is_empty() && index.aliases.is_empty() {
+        return;
+    }
+    let consts = ConstIndex::from_registry(registry);
+    let mut ids: Vec<_> = registry.modules.keys().copied().collect();
+    ids.sort_unstable();
+    for id in ids {
+        let Some(unit) = registry.modules.get_mut(&id) else {
+            continue;
+        };
+        let mut instantiator = TypeInstantiator::new(
+            id,
+            &index,
+            &consts,
+            &unit.program,
+            &unit.modules,
+            &unit.import_items,
+        );
+        instantiator.run(&mut unit.program.items);
+        for (name, link) in instantiator.added_imports {
+            unit.import_items.entry(name).or_insert(link);
+        }
+    }
+}
+
+/// Single-program variant of [`monomorphize_types`] (no imports).
+pub fn monomorphize_program_types(program: &mut Program) {
+    let import_items = HashMap::new();
+    let index = GenericTypeIndex::build([(0, &*program, &import_items)]);
+    if index.decls.is_empty() && index.aliases.is_empty() {
+        return;
+    }
+    let consts = ConstIndex::from_program(program);
+    let modules = ModuleMap::default();
+    let mut instantiator =
+        TypeInstantiator::new(0, &index, &consts, program, &modules, &import_items);
+    instantiator.run(&mut program.items);
+}
+
+#[derive(Clone)]
+enum GenericTypeDecl {
+    Type(TypeDecl),
+    Enum(EnumDecl),
+}
+
+impl GenericTypeDecl {
+    fn name(&self) -> &str {
+        match self {
+            GenericTypeDecl::Type(decl) => &decl.name.name,
+            GenericTypeDecl::Enum(decl) => &decl.name.name,
+        }
+    }
+
+    fn type_params(&self) -> &[TypeParam] {
+        match self {
+            GenericTypeDecl::Type(decl) => &decl.type_params,
+            GenericTypeDecl::Enum(decl) => &decl.type_params,
+        }
+    }
+
+    fn type_refs(&self) -> Vec<&TypeRef> {
+        match self {
+            GenericTypeDecl::Type(decl) => decl.fields.iter().map(|field| &field.ty).collect(),
+            GenericTypeDecl::Enum(decl) => decl
+                .variants
+                .iter()
+                .flat_map(|variant| variant.payload.iter())
+                .collect(),
+        }
+    }
+
+    fn variant_arity(&self, variant: &str) -> Option<usize> {
+        match self {
+            GenericTypeDecl::Type(_) => None,
+            GenericTypeDecl::Enum(decl) => decl
+                .variants
+                .iter()
+                .find(|item| item.name.name == variant)
+                .map(|item| item.payload.len()),
+        }
+    }
+
+    fn instantiate(&self, name: &str, bindings: &HashMap<String, TypeRef>) -> Item {
+        match self {
+            GenericTypeDecl::Type(decl) => {
+                let mut decl = decl.clone();
+                decl.name.name = name.to_string();
+                decl.type_params = Vec::new();
+                for field in &mut decl.fields {
+                    subst_type_ref(&mut field.ty, bindings);
+                    if let Some(default) = &mut field.default {
+                        subst_expr(default, bindings);
+                    }
+                }
+                Item::Type(decl)
+            }
+            GenericTypeDecl::Enum(decl) => {
+                let mut decl = decl.clone();
+                decl.name.name = name.to_string();
+                decl.type_params = Vec::new();
+                for variant in &mut decl.variants {
+                    for ty in &mut variant.payload {
+                        subst_type_ref(ty, bindings);
+                    }
+                }
+                Item::Enum(decl)
+            }
+        }
+    }
+}
+
+/// Generic declarations and type aliases of every module, plus what each
+/// owner module can see so instances created elsewhere can resolve the
+/// owner's helper types.
+struct GenericTypeIndex {
+    decls: HashMap<(ModuleId, String), GenericTypeDecl>,
+    aliases: HashMap<(ModuleId, String), TypeRef>,
+    declared: HashMap<ModuleId, HashSet<String>>,
+    import_items: HashMap<ModuleId, HashMap<String, ModuleLink>>,
+}
+
+impl GenericTypeIndex {
+    fn build<'p>(
+        modules: impl IntoIterator<Item = (ModuleId, &'p Program, &'p HashMap<String, ModuleLink>)>,
+    ) -> Self {
+        let mut decls = HashMap::new();
+        let mut aliases = HashMap::new();
+        let mut declared: HashMap<ModuleId, HashSet<String>> = HashMap::new();
+        let mut import_items = HashMap::new();
+        for (id, program, imports) in modules {
+            let names = declared.entry(id).or_default();
+            for item in &program.items {
+                let generic = match item {
+                    Item::Type(decl) => {
+                        names.insert(decl.name.name.clone());
+                        (!decl.type_params.is_empty()).then(|| GenericTypeDecl::Type(decl.clone()))
+                    }
+                    Item::Enum(decl) => {
+                        names.insert(decl.name.name.clone());
+                        (!decl.type_params.is_empty()).then(|| GenericTypeDecl::Enum(decl.clone()))
+                    }
+                    Item::TypeAlias(decl) => {
+                        names.insert(decl.name.name.clone());
+                        aliases.insert((id, decl.name.name.clone()), decl.target.clone());
+                        None
+                    }
+                    _ => None,
+                };
+                if let Some(generic) = generic {
+                    decls.insert((id, generic.name().to_string()), generic);
+                }
+            }
+            import_items.insert(id, imports.clone());
+        }
+        Self {
+            decls,
+            aliases,
+            declared,
+            import_items,
+        }
+    }
+}
+
+/// Rewrites one module and collects the instances it needs.
+struct TypeInstantiator<'a> {
+    module_id: ModuleId,
+    index: &'a GenericTypeIndex,
+    consts: &'a ConstIndex,
+    /// Generic types visible by written name: local, imported by name, or
+    /// `alias.Name` through a module alias.
+    visible: HashMap<String, (ModuleId, Option<ModuleLink>)>,
+    /// Type aliases visible by written name, resolved the same way.
+    visible_aliases: HashMap<String, (ModuleId, Option<ModuleLink>)>,
+    /// Aliases currently being expanded; a reference back to one of them is a
+    /// cycle and stays unexpanded.
+    expanding: Vec<(ModuleId, String)>,
+    /// Type names already bound in this module (declared or imported).
+    bound_names: HashSet<String>,
+    /// Type parameters of the declaration currently being rewritten.
+    type_params: HashSet<String>,
+    /// Declared return type of the function or route being rewritten, as
+    /// written; `return` values take their type arguments from it.
+    expected_ret: Option<TypeRef>,
+    /// Instance names by owner module, declaration name, and mangled type
+    /// arguments (refinements included).
+    instances: HashMap<(ModuleId, String, String), String>,
+    /// Type names declared in this module or generated for an instance.
+    taken: HashSet<String>,
+    pending: Vec<Item>,
+    added_imports: HashMap<String, ModuleLink>,
+}
+
+impl<'a> TypeInstantiator<'a> {
+    fn new(
+        module_id: ModuleId,
+        index: &'a GenericTypeIndex,
+        consts: &'a ConstIndex,
+        program: &Program,
+        modules: &ModuleMap,
+        import_items: &HashMap<String, ModuleLink>,
+    ) -> Self {
+        let visible = visible_names(module_id, index.decls.keys(), modules, import_items);
+        let visible_aliases = visible_names(module_id, index.aliases.keys(), modules, import_items);
+        let mut bound_names: HashSet<String> = import_items.keys().cloned().collect();
+        let mut taken = HashSet::new();
+        for item in &program.items {
+            match item {
+                Item::Type(decl) => {
+                    bound_names.insert(decl.name.name.clone());
+                    taken.insert(decl.name.name.clone());
+                }
+                Item::Enum(decl) => {
+                    bound_names.insert(decl.name.name.clone());
+                    taken.insert(decl.name.name.clone());
+                }
+                _ => {}
+            }
+        }
+        Self {
+            module_id,
+            index,
+            consts,
+            visible,
+            visible_aliases,
+            expanding: Vec::new(),
+            bound_names,
+            type_params: HashSet::new(),
+            expected_ret: None,
+            instances: HashMap::new(),
+            taken,
+            pending: Vec::new(),
+            added_imports: HashMap::new(),
+        }
+    }
+
+    fn run(&mut self, items: &mut Vec<Item>) {
+        for item in items.iter_mut() {
+            self.rewrite_item(item);
+        }
+        let mut generated = 0;
+        while let Some(mut item) = self.pending.pop() {
+            generated += 1;
+            if generated > MAX_TYPE_INSTANCES {
+                break;
+            }
+            self.rewrite_item(&mut item);
+            items.push(item);
+        }
+    }
+
+    /// Returns the instance name for `base<args>`, generating it on first use.
+    fn instantiate(&mut self, base: &str, args: &[TypeRef]) -> Option<String> {
+        let index = self.index;
+        let (owner, link) = self.visible.get(base)?.clone();
+        let decl = index.decls.get(&(owner, base_name(base).to_string()))?;
+        if decl.type_params().len() != args.len() || !args.iter().all(|arg| self.is_concrete(arg)) {
+            return None;
+        }
+        let arg_names: Vec<String> = args.iter().map(type_ref_to_name).collect();
+        let key = (owner, decl.name().to_string(), arg_names.join("_"));
+        if let Some(name) = self.instances.get(&key) {
+            return Some(name.clone());
+        }
+        // A declared or imported type, or an instance of a same-named generic
+        // from another module, may already use the mangled name.
+        let mangled = mono_fn_name(decl.name(), &arg_names);
+        let mut name = mangled.clone();
+        let mut suffix = 1;
+        while self.taken.contains(&name) || self.bound_names.contains(&name) {
+            name = format!("{mangled}_8{suffix}");
+            suffix += 1;
+        }
+        self.taken.insert(name.clone());
+        self.instances.insert(key, name.clone());
+        let bindings: HashMap<String, TypeRef> = decl
+            .type_params()
+            .iter()
+            .map(|param| param.name.name.clone())
+            .zip(args.iter().cloned())
+            .collect();
+        if let Some(link) = link {
+            let params: HashSet<&str> = decl
+                .type_params()
+                .iter()
+                .map(|param| param.name.name.as_str())
+                .collect();
+            let mut names = Vec::new();
+            for ty in decl.type_refs() {
+                collect_type_names(ty, &mut names);
+            }
+            names.retain(|name| !params.contains(name.as_str()));
+            self.import_owner_names(owner, &link, names);
+        }
+        self.pending.push(decl.instantiate(&name, &bindings));
+        Some(name)
+    }
+
+    /// An instance of a generic (or an alias target) declared in another
+    /// module refers to that module's types; make them resolvable here unless
+    /// a local name shadows them.
+    fn import_owner_names(&mut self, owner: ModuleId, link: &ModuleLink, names: Vec<String>) {
+        for name in names {
+            if self.bound_names.contains(&name) {
+                continue;
+            }
+            let owner_link = if self
+                .index
+                .declared
+                .get(&owner)
+                .is_some_and(|declared| declared.contains(&name))
+            {
+                Some(link.clone())
+            } else {
+                self.index
+                    .import_items
+                    .get(&owner)
+                    .and_then(|imports| imports.get(&name))
+                    .cloned()
+            };
+            let Some(owner_link) = owner_link else {
+                continue;
+            };
+            if self
+                .index
+                .decls
+                .contains_key(&(owner_link.id, name.clone()))
+            {
+                self.visible
+                    .insert(name.clone(), (owner_link.id, Some(owner_link.clone())));
+            }
+            if self
+                .index
+                .aliases
+                .contains_key(&(owner_link.id, name.clone()))
+            {
+                self.visible_aliases
+                    .insert(name.clone(), (owner_link.id, Some(owner_link.clone())));
+            }
+            self.bound_names.insert(name.clone());
+            self.added_imports.insert(name, owner_link);
+        }
+    }
+
+    fn is_concrete(&self, ty: &TypeRef) -> bool {
+        match &ty.kind {
+            TypeRefKind::Simple(ident) => !self.type_params.contains(&ident.name),
+            TypeRefKind::Generic { base, args } => {
+                !self.type_params.contains(&base.name)
+                    && args.iter().all(|arg| self.is_concrete(arg))
+            }
+            TypeRefKind::Optional(inner) => self.is_concrete(inner),
+            TypeRefKind::Result { ok, err } => {
+                self.is_concrete(ok) && err.as_deref().is_none_or(|err| self.is_concrete(err))
+            }
+            TypeRefKind::Refined { base, .. } => !self.type_params.contains(&base.name),
+            TypeRefKind::Tuple(items) => items.iter().all(|item| self.is_concrete(item)),
+        }
+    }
+
+    fn with_type_params(&mut self, params: &[TypeParam], f: impl FnOnce(&mut Self)) {
+        let saved = std::mem::replace(
+            &mut self.type_params,
+            params.iter().map(|param| param.name.name.clone()).collect(),
+        );
+        f(self);
+        self.type_params = saved;
+    }
+
+    fn rewrite_item(&mut self, item: &mut Item) {
+        match item {
+            Item::Type(decl) => {
+                // Generic declarations are templates; only their instances
+                // are rewritten.
+                if decl.type_params.is_empty() {
+                    for field in &mut decl.fields {
+                        self.rewrite_type_ref(&mut field.ty);
+                        if let Some(default) = &mut field.default {
+                            self.rewrite_expr(default);
+                        }
+                    }
+                }
+            }
+            Item::Enum(decl) => {
+                if decl.type_params.is_empty() {
+                    for variant in &mut decl.variants {
+                        for ty in &mut variant.payload {
+                            self.rewrite_type_ref(ty);
+                        }
+                    }
+                }
+            }
+            Item::TypeAlias(decl) => {
+                self.expanding
+                    .push((self.module_id, decl.name.name.clone()));
+                self.rewrite_type_ref(&mut decl.target);
+                self.expanding.pop();
+            }
+            Item::Interface(decl) => {
+                for member in &mut decl.members {
+                    self.with_type_params(&member.type_params, |this| {
+                        for param in &mut member.params {
+                            this.rewrite_param(param);
+                        }
+                        if let Some(ret) = &mut member.ret {
+                            this.rewrite_type_ref(ret);
+                        }
+                    });
+                }
+            }
+            Item::Impl(decl) => {
+                for method in &mut decl.methods {
+                    self.rewrite_fn(method);
+                }
+            }
+            Item::Fn(decl) => self.rewrite_fn(decl),
+            Item::Component(decl) => self.with_type_params(&decl.type_params, |this| {
+                for param in &mut decl.params {
+                    this.rewrite_param(param);
+                }
+                this.rewrite_block(&mut decl.body);
+            }),
+            Item::Service(decl) => {
+                for route in &mut decl.routes {
+                    if let Some(body_type) = &mut route.body_type {
+                        self.rewrite_type_ref(body_type);
+                    }
+                    let saved = self.expected_ret.replace(route.ret_type.clone());
+                    self.rewrite_type_ref(&mut route.ret_type);
+                    self.rewrite_block(&mut route.body);
+                    self.expected_ret = saved;
+                }
+            }
+            Item::Config(decl) => {
+                for field in &mut decl.fields {
+                    self.rewrite_type_ref(&mut field.ty);
+                    self.rewrite_expr(&mut field.value);
+                }
+            }
+            Item::Const(decl) => {
+                self.rewrite_type_ref(&mut decl.ty);
+                self.rewrite_expr(&mut decl.value);
+            }
+            Item::App(decl) => self.rewrite_block(&mut decl.body),
+            Item::Migration(decl) => {
+                self.rewrite_block(&mut decl.body);
+                if let Some(down) = &mut decl.down {
+                    self.rewrite_block(down);
+                }
+            }
+            Item::Seed(decl) => self.rewrite_block(&mut decl.body),
+            Item::Test(decl) => self.rewrite_block(&mut decl.body),
+            Item::Import(_) => {}
+        }
+    }
+
+    fn rewrite_fn(&mut self, decl: &mut FnDecl) {
+        self.with_type_params(&decl.type_params, |this| {
+            for param in &mut decl.params {
+                this.rewrite_param(param);
+            }
+            let saved = std::mem::replace(&mut this.expected_ret, decl.ret.clone());
+            if let Some(ret) = &mut decl.ret {
+                this.rewrite_type_ref(ret);
+            }
+            this.rewrite_block(&mut decl.body);
+            this.expected_ret = saved;
+        });
+    }
+
+    fn rewrite_param(&mut self, param: &mut Param) {
+        self.rewrite_type_ref(&mut param.ty);
+        if let Some(default) = &mut param.default {
+            self.rewrite_expr(default);
+        }
+    }
+
+    /// Returns the expanded target when `name` is a visible, non-cyclic alias.
+    fn expand_alias(&mut self, name: &str) -> Option<TypeRef> {
+        let (owner, link) = self.visible_aliases.get(name)?.clone();
+        let key = (owner, base_name(name).to_string());
+        if self.expanding.contains(&key) {
+            return None;
+        }
+        let mut target = self.index.aliases.get(&key)?.clone();
+        if let Some(link) = link {
+            let mut names = Vec::new();
+            collect_type_names(&target, &mut names);
+            self.import_owner_names(owner, &link, names);
+            inline_type_ref_consts(&mut target, &self.consts.visible_in(owner));
+        }
+        self.expanding.push(key);
+        self.rewrite_type_ref(&mut target);
+        self.expanding.pop();
+        Some(target)
+    }
+
+    fn rewrite_type_ref(&mut self, ty: &mut TypeRef) {
+        match &mut ty.kind {
+            TypeRefKind::Simple(ident) => {
+                let name = ident.name.clone();
+                if let Some(target) = self.expand_alias(&name) {
+                    ty.kind = target.kind;
+                }
+            }
+            TypeRefKind::Refined { .. } => {}
+            TypeRefKind::Tuple(items) => {
+                for item in items.iter_mut() {
+                    self.rewrite_type_ref(item);
+                }
+            }
+            TypeRefKind::Generic { base, args } => {
+                for arg in args.iter_mut() {
+                    self.rewrite_type_ref(arg);
+                }
+                let base = base.name.clone();
+                let args = args.clone();
+                if let Some(name) = self.instantiate(&base, &args) {
+                    ty.kind = TypeRefKind::Simple(Ident {
+                        name,
+                        span: ty.span,
+                    });
+                }
+            }
+            TypeRefKind::Optional(inner) => self.rewrite_type_ref(inner),
+            TypeRefKind::Result { ok, err } => {
+                self.rewrite_type_ref(ok);
+                if let Some(err) = err {
+                    self.rewrite_type_ref(err);
+                }
+            }
+        }
+    }
+
+    fn rewrite_block(&mut self, block: &mut Block) {
+        for stmt in &mut block.stmts {
+            self.rewrite_stmt(stmt);
+        }
+    }
+
+    fn rewrite_stmt(&mut self, stmt: &mut Stmt) {
+        match &mut stmt.kind {
+            StmtKind::Let { ty, expr, .. }
+            | StmtKind::Var { ty, expr, .. }
+            | StmtKind::LetPattern { ty, expr, .. } => {
+                if let Some(ty) = ty {
+                    self.fill_type_args(ty, expr);
+                    self.rewrite_type_ref(ty);
+                }
+                self.rewrite_expr(expr);
+            }
+            StmtKind::Assign { target, expr } => {
+                self.rewrite_expr(target);
+                self.rewrite_expr(expr);
+            }
+            StmtKind::Return { expr } => {
+                if let Some(expr) = expr {
+                    if let Some(ret) = self.expected_ret.clone() {
+                        self.fill_type_args(&ret, expr);
+                    }
+                    self.rewrite_expr(expr);
+                }
+            }
+            StmtKind::If {
+                cond,
+                then_block,
+                else_if,
+                else_block,
+            } => {
+                self.rewrite_expr(cond);
+                self.rewrite_block(then_block);
+                for (cond, block) in else_if {
+                    self.rewrite_expr(cond);
+                    self.rewrite_block(block);
+                }
+                if let Some(block) = else_block {
+                    self.rewrite_block(block);
+                }
+            }
+            StmtKind::Match { expr, cases } => {
+                self.rewrite_expr(expr);
+                for case in cases {
+                    if let Some(guard) = &mut case.guard {
+                        self.rewrite_expr(guard);
+                    }
+                    self.rewrite_block(&mut case.block);
+                }
+            }
+            StmtKind::For { iter, block, .. } => {
+                self.rewrite_expr(iter);
+                self.rewrite_block(block);
+            }
+            StmtKind::While { cond, block } => {
+                self.rewrite_expr(cond);
+                self.rewrite_block(block);
+            }
+            StmtKind::Transaction { block } => self.rewrite_block(block),
+            StmtKind::Expr(expr) => self.rewrite_expr(expr),
+            StmtKind::Break | StmtKind::Continue => {}
+        }
+    }
+
+    fn rewrite_expr(&mut self, expr: &mut Expr) {
+        match &mut expr.kind {
+            ExprKind::Literal(_) | ExprKind::Ident(_) => {}
+            ExprKind::Binary { left, right, .. } | ExprKind::Coalesce { left, right } => {
+                self.rewrite_expr(left);
+                self.rewrite_expr(right);
+            }
+            ExprKind::Unary { expr: inner, .. }
+            | ExprKind::Await {
+                expr: inner,
+                timeout: None,
+            }
+            | ExprKind::Box { expr: inner }
+            | ExprKind::BangChain {
+                expr: inner,
+                error: None,
+            } => self.rewrite_expr(inner),
+            ExprKind::BangChain {
+                expr: inner,
+                error: Some(err),
+            }
+            | ExprKind::Await {
+                expr: inner,
+                timeout: Some(err),
+            } => {
+                self.rewrite_expr(inner);
+                self.rewrite_expr(err);
+            }
+            ExprKind::Call {
+                callee,
+                args,
+                type_args,
+            } => {
+                self.rewrite_expr(callee);
+                for arg in args.iter_mut() {
+                    self.rewrite_expr(&mut arg.value);
+                }
+                for ty in type_args.iter_mut() {
+                    self.rewrite_type_ref(ty);
+                }
+            }
+            ExprKind::Member { base, .. } | ExprKind::OptionalMember { base, .. } => {
+                self.rewrite_expr(base);
+            }
+            ExprKind::Index { base, index } | ExprKind::OptionalIndex { base, index } => {
+                self.rewrite_expr(base);
+                self.rewrite_expr(index);
+            }
+            ExprKind::StructLit { fields, .. } => {
+                for field in fields {
+                    self.rewrite_expr(&mut field.value);
+                }
+            }
+            ExprKind::RecordUpdate { base, fields } => {
+                self.rewrite_expr(base);
+                for field in fields {
+                    self.rewrite_expr(&mut field.value);
+                }
+            }
+            ExprKind::ListLit(items) | ExprKind::TupleLit(items) => {
+                for item in items {
+                    self.rewrite_expr(item);
+                }
+            }
+            ExprKind::MapLit(pairs) => {
+                for (key, value) in pairs {
+                    self.rewrite_expr(key);
+                    self.rewrite_expr(value);
+                }
+            }
+            ExprKind::InterpString(parts) => {
+                for part in parts {
+                    if let InterpPart::Expr(expr) = part {
+                        self.rewrite_expr(expr);
+                    }
+                }
+            }
+            ExprKind::Spawn { block } => self.rewrite_block(block),
+            ExprKind::HtmlIf {
+                cond,
+                then_children,
+                else_if,
+                else_children,
+            } => {
+                self.rewrite_expr(cond);
+                for child in then_children {
+                    self.rewrite_expr(child);
+                }
+                for (cond, children) in else_if {
+                    self.rewrite_expr(cond);
+                    for child in children {
+                        self.rewrite_expr(child);
+                    }
+                }
+                for child in else_children {
+                    self.rewrite_expr(child);
+                }
+            }
+            ExprKind::HtmlFor {
+                iter,
+                body_children,
+                ..
+            } => {
+                self.rewrite_expr(iter);
+                for child in body_children {
+                    self.rewrite_expr(child);
+                }
+            }
+        }
+        if matches!(&expr.kind, ExprKind::Call { type_args, .. } if !type_args.is_empty()) {
+            self.rewrite_generic_construction(expr);
+        }
+    }
+
+    /// Copies the type arguments of an expected `Page<Note>` or
+    /// `Outcome<Int, String>` (also under `?` or `!`) onto a construction
+    /// written without them: `Page(...)`, `Outcome.Ok(...)` or a unit
+    /// variant such as `Outcome.Empty`.
+    fn fill_type_args(&self, expected: &TypeRef, expr: &mut Expr) {
+        let expected = match &expected.kind {
+            TypeRefKind::Optional(inner) => inner,
+            TypeRefKind::Result { ok, .. } => ok,
+            _ => expected,
+        };
+        let TypeRefKind::Generic {
+            base,
+            args: type_args,
+        } = &expected.kind
+        else {
+            return;
+        };
+        let names = |written: Option<String>| written.is_some_and(|path| path == base.name);
+        match &mut expr.kind {
+            ExprKind::Call {
+                callee,
+                type_args: call_type_args,
+                ..
+            } if call_type_args.is_empty() => {
+                let fills = match &callee.kind {
+                    _ if names(written_path(callee)) => self.is_generic_struct(&base.name),
+                    ExprKind::Member { base: owner, name } => {
+                        names(written_path(owner))
+                            && self.generic_variant_arity(&base.name, &name.name).is_some()
+                    }
+                    _ => false,
+                };
+                if fills {
+                    *call_type_args = type_args.clone();
+                }
+            }
+            ExprKind::StructLit { name, fields }
+                if name.name == base.name && self.is_generic_struct(&base.name) =>
+            {
+                let callee = Expr {
+                    kind: ExprKind::Ident(name.clone()),
+                    span: name.span,
+                };
+                let args = fields
+                    .iter()
+                    .map(|field| CallArg {
+                        name: Some(field.name.clone()),
+                        value: field.value.clone(),
+                        span: field.span,
+                        comma_before: field.comma_before,
+                        is_block_sugar: false,
+                    })
+                    .collect();
+                expr.kind = ExprKind::Call {
+                    callee: Box::new(callee),
+                    args,
+                    type_args: type_args.clone(),
+                };
+            }
+            ExprKind::Member { base: owner, name }
+                if names(written_path(owner))
+                    && self.generic_variant_arity(&base.name, &name.name) == Some(0) =>
+            {
+                expr.kind = ExprKind::Call {
+                    callee: Box::new(expr.clone()),
+                    args: Vec::new(),
+                    type_args: type_args.clone(),
+                };
+            }
+            _ => {}
+        }
+    }
+
+    /// `Page<Note>(items=...)` becomes a `Page_Note` struct literal and
+    /// `Outcome.Ok<Int, String>(value)` a call on `Outcome_Int_String.Ok`
+    /// (a unit variant drops the empty call).
+    fn rewrite_generic_construction(&mut self, expr: &mut Expr) {
+        let ExprKind::Call {
+            callee,
+            args,
+            type_args,
+        } = &expr.kind
+        else {
+            return;
+        };
+        if let Some(type_name) = written_path(callee)
+            && self.is_generic_struct(&type_name)
+            && args.iter().all(|arg| arg.name.is_some())
+        {
+            let Some(name) = self.instantiate(&type_name, type_args) else {
+                return;
+            };
+            let fields = args
+                .iter()
+                .filter_map(|arg| {
+                    arg.name.clone().map(|field| StructField {
+                        name: field,
+                        value: arg.value.clone(),
+                        span: arg.span,
+                        comma_before: arg.comma_before,
+                    })
+                })
+                .collect();
+            expr.kind = ExprKind::StructLit {
+                name: Ident {
+                    name,
+                    span: callee.span,
+                },
+                fields,
+            };
+            return;
+        }
+        let ExprKind::Member {
+            base,
+            name: variant,
+        } = &callee.kind
+        else {
+            return;
+        };
+        let Some(enum_name) = written_path(base) else {
+            return;
+        };
+        let Some(arity) = self.generic_variant_arity(&enum_name, &variant.name) else {
+            return;
+        };
+        let Some(name) = self.instantiate(&enum_name, type_args) else {
+            return;
+        };
+        let member = Expr {
+            kind: ExprKind::Member {
+                base: Box::new(Expr {
+                    kind: ExprKind::Ident(Ident {
+                        name,
+                        span: base.span,
+                    }),
+                    span: base.span,
+                }),
+                name: variant.clone(),
+            },
+            span: callee.span,
+        };
+        expr.kind = if arity == 0 && args.is_empty() {
+            member.kind
+        } else {
+            ExprKind::Call {
+                callee: Box::new(member),
+                args: args.clone(),
+                type_args: Vec::new(),
+            }
+        };
+    }
+
+    fn generic_decl(&self, written: &str) -> Option<&GenericTypeDecl> {
+        let (owner, _) = self.visible.get(written)?;
+        self.index
+            .decls
+            .get(&(*owner, base_name(written).to_string()))
+    }
+
+    fn is_generic_struct(&self, written: &str) -> bool {
+        matches!(self.generic_decl(written), Some(GenericTypeDecl::Type(_)))
+    }
+
+    fn generic_variant_arity(&self, written: &str, variant: &str) -> Option<usize> {
+        self.generic_decl(written)?.variant_arity(variant)
+    }
+}
+
+/// `Name` or `alias.Name` as written at a construction site.
+fn written_path(expr: &Expr) -> Option<String> {
+    match &expr.kind {
+        ExprKind::Ident(ident) => Some(ident.name.clone()),
+        ExprKind::Member { base, name } => match &base.kind {
+            ExprKind::Ident(alias) => Some(format!("{}.{}", alias.name, name.name)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Names in `keys` owned by `module_id` or reachable from it by import or
+/// module alias, with the link used to reach them.
+fn visible_names<'k>(
+    module_id: ModuleId,
+    keys: impl Iterator<Item = &'k (ModuleId, String)> + Clone,
+    modules: &ModuleMap,
+    import_items: &HashMap<String, ModuleLink>,
+) -> HashMap<String, (ModuleId, Option<ModuleLink>)> {
+    let mut visible = HashMap::new();
+    for (owner, name) in keys.clone() {
+        if *owner == module_id {
+            visible.insert(name.clone(), (module_id, None));
+        }
+    }
+    let owned: HashSet<&(ModuleId, String)> = keys.collect();
+    for (name, link) in import_items {
+        if owned.contains(&(link.id, name.clone())) {
+            visible.insert(name.clone(), (link.id, Some(link.clone())));
+        }
+    }
+    for (alias, link) in &modules.modules {
+        for (owner, name) in &owned {
+            if *owner == link.id {
+                visible.insert(format!("{alias}.{name}"), (link.id, Some(link.clone())));
+            }
+        }
+    }
+    visible
+}
+
+fn base_name(written: &str) -> &str {
+    written.rsplit('.').next().unwrap_or(written)
+}
+
+fn collect_type_names(ty: &TypeRef, out: &mut Vec<String>) {
+    match &ty.kind {
+        TypeRefKind::Simple(ident) => out.push(ident.name.clone()),
+        TypeRefKind::Generic { base, args } => {
+            out.push(base.name.clone());
+            for arg in args {
+                collect_type_names(arg, out);
+            }
+        }
+        TypeRefKind::Optional(inner) => collect_type_names(inner, out),
+        TypeRefKind::Result { ok, err } => {
+            collect_type_names(ok, out);
+            if let Some(err) = err {
+                collect_type_names(err, out);
+            }
+        }
+        TypeRefKind::Tuple(items) => {
+            for item in items {
+                collect_type_names(item, out);
+            }
+        }
+        TypeRefKind::Refined { base, .. } => out.push(base.name.clone()),
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Naming helpers
 // ---------------------------------------------------------------------------
 
+/// Mangled name of a type argument, used to name generic instances
+/// (`Page<Note>` becomes `Page_Note`).  Plain identifiers stay readable;
+/// everything else is spelled with `_` followed by a digit, which an escaped
+/// identifier never produces:
+///
+/// - `_` in an identifier is `_0` and `alias.Name` is `alias_1Name`
+/// - `Map<K, V>` is `Map_2K_V_3`, `T?` is `T_4`, `T!E` is `T_5E_3`, and
+///   `(A, B)` is `_6A_B_3`
+/// - `String(1..5)` is `String_7` + the refinement text + `_3`, with every
+///   byte other than an ASCII letter or digit written as `_9` and two hex
+///   digits
+///
+/// Arguments are joined with a bare `_`, so distinct argument lists never
+/// share a name: `Pair<A_B, C>` is `Pair_A_0B_C` and `Pair<A, B_C>` is
+/// `Pair_A_B_0C`.  A type instance whose name is already taken in its module
+/// gets `_8` and a counter appended.
 fn type_ref_to_name(ty: &TypeRef) -> String {
     match &ty.kind {
-        TypeRefKind::Simple(ident) => ident.name.replace('.', "_"),
+        TypeRefKind::Simple(ident) => escape_ident(&ident.name),
         TypeRefKind::Generic { base, args } => {
-            format!(
-                "{}_{}",
-                base.name,
-                args.iter()
-                    .map(type_ref_to_name)
-                    .collect::<Vec<_>>()
-                    .join("_")
-            )
-        }
-        TypeRefKind::Optional(inner) => format!("Opt_{}", type_ref_to_name(inner)),
-        TypeRefKind::Result { ok, err } => {
-            let err_str = err
-                .as_deref()
-                .map(type_ref_to_name)
-                .unwrap_or_else(|| "Err".to_string());
-            format!("Result_{}_{}", type_ref_to_name(ok), err_str)
+            format!("{}_2{}_3", escape_ident(&base.name), join_type_names(args))
+        }
+        TypeRefKind::Optional(inner) => format!("{}_4", type_ref_to_name(inner)),
+        TypeRefKind::Result { ok, err } => format!(
+            "{}_5{}_3",
+            type_ref_to_name(ok),
+            err.as_deref().map(type_ref_to_name).unwrap_or_default()
+        ),
+        TypeRefKind::Refined { base, args } => {
+            let text = args
+                .iter()
+                .map(refinement_text)
+                .collect::<Vec<_>>()
+                .join(",");
+            format!("{}_7{}_3", escape_ident(&base.name), escape_bytes(&text))
+        }
+        TypeRefKind::Tuple(items) => format!("_6{}_3", join_type_names(items)),
+    }
+}
+
+fn join_type_names(items: &[TypeRef]) -> String {
+    items
+        .iter()
+        .map(type_ref_to_name)
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+fn escape_ident(name: &str) -> String {
+    name.replace('_', "_0").replace('.', "_1")
+}
+
+fn escape_bytes(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("_9{byte:02x}"));
         }
-        TypeRefKind::Refined { base, .. } => base.name.clone(),
+    }
+    out
+}
+
+/// Source-like text of a refinement argument (`1..5`, `regex("^a")`,
+/// `predicate(is_slug)`).  Only the forms refinements accept are spelled
+/// out; sema rejects anything else before the name is ever shown.
+fn refinement_text(expr: &Expr) -> String {
+    match &expr.kind {
+        ExprKind::Literal(Literal::Int(value)) => value.to_string(),
+        ExprKind::Literal(Literal::Float(value)) => format!("{value:?}"),
+        ExprKind::Literal(Literal::Bool(value)) => value.to_string(),
+        ExprKind::Literal(Literal::String(value)) => format!("{value:?}"),
+        ExprKind::Literal(Literal::Null) => "null".to_string(),
+        ExprKind::Ident(ident) => ident.name.clone(),
+        ExprKind::Unary { op, expr } => {
+            let op = match op {
+                UnaryOp::Neg => "-",
+                UnaryOp::Not => "!",
+            };
+            format!("{op}{}", refinement_text(expr))
+        }
+        ExprKind::Binary {
+            op: op @ (BinaryOp::Range | BinaryOp::RangeExclusive),
+            left,
+            right,
+        } => {
+            let op = if matches!(op, BinaryOp::Range) {
+                ".."
+            } else {
+                "..<"
+            };
+            format!("{}{op}{}", refinement_text(left), refinement_text(right))
+        }
+        ExprKind::Call { callee, args, .. } => format!(
+            "{}({})",
+            refinement_text(callee),
+            args.iter()
+                .map(|arg| refinement_text(&arg.value))
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+        _ => "?".to_string(),
     }
 }
 
+/// `name` followed by its mangled type arguments.  The name is escaped like
+/// an identifier argument, so `pair_of<Int>` and `pair<of, Int>` differ.
 fn mono_fn_name(fn_name: &str, type_arg_names: &[String]) -> String {
-    format!("{}_{}", fn_name, type_arg_names.join("_"))
+    format!("{}_{}", escape_ident(fn_name), type_arg_names.join("_"))
 }
 
 fn canonical_name(module_id: ModuleId, name: &str) -> String {
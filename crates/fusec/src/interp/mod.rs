@@ -1,13 +1,15 @@
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::fs;
 use std::io::{Read, Write};
 use std::net::{TcpListener, TcpStream};
 use std::path::{Component, Path};
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::{Arc, Mutex, mpsc};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError, mpsc};
 use std::thread;
 use std::time::{Duration, Instant};
 
+use fuse_rt::decimal::Decimal;
 use fuse_rt::{
     bytes as rt_bytes, config as rt_config, error as rt_error, json as rt_json,
     validate as rt_validate,
@@ -16,13 +18,16 @@ use fuse_rt::{
 use crate::ast::{
     AppDecl, BinaryOp, Block, ComponentDecl, ConfigDecl, EnumDecl, Expr, ExprKind, FnDecl,
     HttpVerb, Ident, InterpPart, Item, Literal, MigrationDecl, Param, Pattern, PatternField,
-    PatternKind, Program, RouteDecl, ServiceDecl, Stmt, StmtKind, StructField, TestDecl, TypeDecl,
-    TypeRef, TypeRefKind, UnaryOp,
+    PatternKind, Program, RouteDecl, SeedDecl, ServiceDecl, Stmt, StmtKind, StructField, TestDecl,
+    TypeDecl, TypeRef, TypeRefKind, UnaryOp,
 };
 use crate::callbind::{
     CallArgSpec, CallBindError, ParamBinding, ParamSpec, bind_call_args, bind_positional_args,
 };
-use crate::db::{DEFAULT_DB_POOL_SIZE, Db, Query, parse_db_pool_size, parse_db_pool_size_value};
+use crate::db::{
+    Db, DbOptions, Query, parse_db_busy_timeout, parse_db_busy_timeout_value, parse_db_pool_size,
+    parse_db_pool_size_value, parse_db_wal, parse_db_wal_value,
+};
 use crate::frontend::html_shorthand::{CanonicalizationPhase, validate_named_args_for_phase};
 use crate::frontend::html_tag_builtin::should_use_html_tag_builtin;
 use crate::html_tags::{self, HtmlTagKind};
@@ -33,6 +38,7 @@ use crate::observability;
 use crate::refinement::{
     NumberLiteral, RefinementConstraint, base_is_string_like, parse_constraints,
 };
+use crate::runtime_decimal::decimal_binary;
 use crate::span::Span;
 
 #[derive(Clone, Debug)]
@@ -46,6 +52,7 @@ pub enum Value {
     Unit,
     Int(i64),
     Float(f64),
+    Decimal(Decimal),
     Bool(bool),
     String(String),
     Bytes(Vec<u8>),
@@ -54,8 +61,10 @@ pub enum Value {
     List(Vec<Value>),
     Map(HashMap<String, Value>),
     Boxed(Arc<Mutex<Value>>),
-    Query(Query),
+    Query(Box<Query>),
     Task(Task),
+    Channel(Channel),
+    Range(RangeValue),
     Iterator(IteratorValue),
     Struct {
         name: String,
@@ -151,11 +160,102 @@ pub struct Task {
 pub struct IteratorValue {
     pub values: Vec<Value>,
     pub index: usize,
+    /// Set when iterating a range; elements are computed instead of read
+    /// from `values`.
+    pub range: Option<RangeValue>,
 }
 
 impl IteratorValue {
     pub fn new(values: Vec<Value>) -> Self {
-        Self { values, index: 0 }
+        Self {
+            values,
+            index: 0,
+            range: None,
+        }
+    }
+}
+
+/// Numeric range produced by `start..end` or `start..<end`. Elements are
+/// computed on demand, so iterating or indexing never builds the full list.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RangeValue {
+    Int {
+        start: i64,
+        end: i64,
+        step: i64,
+        inclusive: bool,
+    },
+    Float {
+        start: f64,
+        end: f64,
+        step: f64,
+        inclusive: bool,
+    },
+}
+
+/// Float ranges treat bounds within this distance as reached.
+const RANGE_EPSILON: f64 = 1e-9;
+
+impl RangeValue {
+    pub fn len(&self) -> usize {
+        match *self {
+            RangeValue::Int {
+                start,
+                end,
+                step,
+                inclusive,
+            } => {
+                let last = if inclusive {
+                    i128::from(end)
+                } else {
+                    i128::from(end) - 1
+                };
+                let start = i128::from(start);
+                if last < start {
+                    return 0;
+                }
+                usize::try_from((last - start) / i128::from(step) + 1).unwrap_or(usize::MAX)
+            }
+            RangeValue::Float {
+                start,
+                end,
+                step,
+                inclusive,
+            } => {
+                let span = (end - start) / step;
+                let count = if inclusive {
+                    (span + RANGE_EPSILON).floor() + 1.0
+                } else {
+                    (span - RANGE_EPSILON).ceil()
+                };
+                if count > 0.0 { count as usize } else { 0 }
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Element at `index`, or `None` past the end.
+    pub fn get(&self, index: usize) -> Option<Value> {
+        if index >= self.len() {
+            return None;
+        }
+        match *self {
+            RangeValue::Int { start, step, .. } => Some(Value::Int(start + index as i64 * step)),
+            RangeValue::Float { start, step, .. } => {
+                Some(Value::Float(start + index as f64 * step))
+            }
+        }
+    }
+
+    pub fn iter(self) -> impl Iterator<Item = Value> {
+        (0..self.len()).filter_map(move |index| self.get(index))
+    }
+
+    pub fn to_list(self) -> Vec<Value> {
+        self.iter().collect()
     }
 }
 
@@ -164,10 +264,40 @@ struct TaskState {
     id: u64,
     result: Option<TaskResult>,
     rx: Option<mpsc::Receiver<TaskResult>>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl Drop for TaskState {
+    // Dropping the last handle to an unfinished task (for example when its
+    // scope exits early through `?!`) asks the worker to stop.
+    fn drop(&mut self) {
+        if self.result.is_none() {
+            self.cancel.store(true, Ordering::Relaxed);
+        }
+    }
 }
 
 static NEXT_TASK_ID: AtomicU64 = AtomicU64::new(1);
 
+pub(crate) const TASK_CANCELLED: &str = "task cancelled";
+pub(crate) const TASK_POLL_INTERVAL: Duration = Duration::from_millis(2);
+
+thread_local! {
+    static CURRENT_TASK_CANCEL: RefCell<Option<Arc<AtomicBool>>> = const { RefCell::new(None) };
+}
+
+/// True when the task running on this thread has been cancelled. Both
+/// backends poll this at their cancellation points and stop with
+/// [`TASK_CANCELLED`].
+pub(crate) fn task_cancelled() -> bool {
+    CURRENT_TASK_CANCEL.with(|current| {
+        current
+            .borrow()
+            .as_ref()
+            .is_some_and(|flag| flag.load(Ordering::Relaxed))
+    })
+}
+
 #[derive(Clone, Debug)]
 pub(crate) enum TaskResult {
     Ok(Value),
@@ -182,6 +312,7 @@ impl Task {
                 id: NEXT_TASK_ID.fetch_add(1, Ordering::Relaxed),
                 result: Some(result),
                 rx: None,
+                cancel: Arc::new(AtomicBool::new(false)),
             })),
         }
     }
@@ -191,18 +322,59 @@ impl Task {
         F: FnOnce() -> TaskResult + Send + 'static,
     {
         let (tx, rx) = mpsc::channel::<TaskResult>();
+        let request_id = crate::observability::current_request_id();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let worker_cancel = Arc::clone(&cancel);
         crate::task_pool::submit(move || {
+            if worker_cancel.load(Ordering::Relaxed) {
+                let _ = tx.send(TaskResult::Runtime(TASK_CANCELLED.to_string()));
+                return;
+            }
+            let previous = crate::observability::set_current_request_id(request_id);
+            let previous_cancel =
+                CURRENT_TASK_CANCEL.with(|current| current.replace(Some(worker_cancel)));
             let _ = tx.send(job());
+            CURRENT_TASK_CANCEL.with(|current| current.replace(previous_cancel));
+            crate::observability::set_current_request_id(previous);
         });
         Task {
             state: Arc::new(Mutex::new(TaskState {
                 id: NEXT_TASK_ID.fetch_add(1, Ordering::Relaxed),
                 result: None,
                 rx: Some(rx),
+                cancel,
             })),
         }
     }
 
+    /// Asks an unfinished task to stop at its next cancellation point.
+    pub(crate) fn cancel(&self) {
+        let mut state = self.state.lock().expect("task state lock");
+        Self::poll(&mut state);
+        if state.result.is_none() {
+            state.cancel.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Waits up to `timeout` for the task; `None` means it is still running.
+    pub(crate) fn result_within(&self, timeout: Duration) -> Option<TaskResult> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if self.is_done() {
+                return Some(self.result_raw());
+            }
+            if task_cancelled() {
+                self.cancel();
+                return Some(TaskResult::Runtime(TASK_CANCELLED.to_string()));
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return None;
+            }
+            thread::sleep((deadline - now).min(TASK_POLL_INTERVAL));
+        }
+    }
+
     pub(crate) fn from_exec_result(result: ExecResult<Value>) -> TaskResult {
         match result {
             Ok(value) | Err(ExecError::Return(value)) => TaskResult::Ok(value),
@@ -256,9 +428,24 @@ impl Task {
             }
         };
 
-        let result = rx.recv().unwrap_or_else(|_| {
-            TaskResult::Runtime("task execution failed (worker disconnected)".to_string())
-        });
+        // A waiter that is itself a cancelled task stops waiting and passes
+        // the cancellation on to the task it was awaiting.
+        let result = loop {
+            match rx.recv_timeout(TASK_POLL_INTERVAL) {
+                Ok(result) => break result,
+                Err(mpsc::RecvTimeoutError::Timeout) if task_cancelled() => {
+                    let state = self.state.lock().expect("task state lock");
+                    state.cancel.store(true, Ordering::Relaxed);
+                    break TaskResult::Runtime(TASK_CANCELLED.to_string());
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    break TaskResult::Runtime(
+                        "task execution failed (worker disconnected)".to_string(),
+                    );
+                }
+            }
+        };
         let mut state = self.state.lock().expect("task state lock");
         state.result = Some(result.clone());
         result
@@ -283,12 +470,135 @@ impl Task {
     }
 }
 
+/// Bounded FIFO shared by the tasks that hold a `Channel<T>`. Clones share
+/// the same buffer.
+#[derive(Clone, Debug)]
+pub struct Channel {
+    state: Arc<ChannelState>,
+}
+
+#[derive(Debug)]
+struct ChannelState {
+    id: u64,
+    capacity: usize,
+    buffer: Mutex<ChannelBuffer>,
+    changed: Condvar,
+}
+
+#[derive(Debug)]
+struct ChannelBuffer {
+    items: VecDeque<Value>,
+    closed: bool,
+}
+
+impl Drop for ChannelState {
+    fn drop(&mut self) {
+        if let Ok(buffer) = self.buffer.get_mut() {
+            crate::concurrency_metrics::record_channel_drained(buffer.items.len());
+        }
+    }
+}
+
+static NEXT_CHANNEL_ID: AtomicU64 = AtomicU64::new(1);
+
+impl Channel {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Channel {
+            state: Arc::new(ChannelState {
+                id: NEXT_CHANNEL_ID.fetch_add(1, Ordering::Relaxed),
+                capacity,
+                buffer: Mutex::new(ChannelBuffer {
+                    items: VecDeque::with_capacity(capacity),
+                    closed: false,
+                }),
+                changed: Condvar::new(),
+            }),
+        }
+    }
+
+    pub fn id(&self) -> u64 {
+        self.state.id
+    }
+
+    /// Buffers `value`, waiting while the channel is full. Sending on a closed
+    /// channel is a runtime error.
+    pub(crate) fn send(&self, value: Value) -> Result<(), String> {
+        let mut buffer =
+            self.wait_for(|buffer| buffer.closed || buffer.items.len() < self.state.capacity)?;
+        if buffer.closed {
+            return Err("send on closed channel".to_string());
+        }
+        buffer.items.push_back(value);
+        crate::concurrency_metrics::record_channel_sent();
+        drop(buffer);
+        self.state.changed.notify_all();
+        Ok(())
+    }
+
+    /// Takes the oldest message, waiting while the channel is empty. Returns
+    /// `None` once the channel is closed and drained.
+    pub(crate) fn recv(&self) -> Result<Option<Value>, String> {
+        let mut buffer = self.wait_for(|buffer| buffer.closed || !buffer.items.is_empty())?;
+        let value = buffer.items.pop_front();
+        if value.is_some() {
+            crate::concurrency_metrics::record_channel_drained(1);
+        }
+        drop(buffer);
+        self.state.changed.notify_all();
+        Ok(value)
+    }
+
+    /// Closes the channel. Buffered messages can still be received; closing
+    /// twice is a no-op.
+    pub(crate) fn close(&self) {
+        self.lock().closed = true;
+        self.state.changed.notify_all();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, ChannelBuffer> {
+        self.state
+            .buffer
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Waits until `ready` holds and returns the locked buffer. Waiting is a
+    /// cancellation point and hands the worker's queue to another thread (see
+    /// `task_pool::blocking`) so the task on the other end can still run.
+    fn wait_for(
+        &self,
+        ready: impl Fn(&ChannelBuffer) -> bool,
+    ) -> Result<MutexGuard<'_, ChannelBuffer>, String> {
+        let buffer = self.lock();
+        if ready(&buffer) {
+            return Ok(buffer);
+        }
+        drop(buffer);
+        crate::task_pool::blocking(|| {
+            let mut buffer = self.lock();
+            while !ready(&buffer) {
+                if task_cancelled() {
+                    return Err(TASK_CANCELLED.to_string());
+                }
+                buffer = self
+                    .state
+                    .changed
+                    .wait_timeout(buffer, TASK_POLL_INTERVAL)
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .0;
+            }
+            Ok(buffer)
+        })
+    }
+}
+
 impl Value {
     pub fn to_string_value(&self) -> String {
         match self.unboxed() {
             Value::Unit => "()".to_string(),
             Value::Int(v) => v.to_string(),
             Value::Float(v) => v.to_string(),
+            Value::Decimal(v) => v.to_string(),
             Value::Bool(v) => v.to_string(),
             Value::String(v) => v.clone(),
             Value::Bytes(v) => rt_bytes::encode_base64(&v),
@@ -313,6 +623,8 @@ impl Value {
             Value::Boxed(_) => "<box>".to_string(),
             Value::Query(_) => "<query>".to_string(),
             Value::Task(_) => "<task>".to_string(),
+            Value::Channel(_) => "<channel>".to_string(),
+            Value::Range(range) => crate::runtime_ranges::range_text(&range),
             Value::Iterator(_) => "<iterator>".to_string(),
             Value::Struct { name, fields } => match fields.get("message") {
                 Some(Value::String(msg)) => format!("{name}({msg})"),
@@ -414,6 +726,7 @@ fn is_html_response_type(ty: &TypeRef) -> bool {
             "Option" | "Result" => args.first().is_some_and(is_html_response_type),
             _ => false,
         },
+        TypeRefKind::Tuple(_) => false,
     }
 }
 
@@ -464,16 +777,29 @@ fn is_query_method(name: &str) -> bool {
         name,
         "select"
             | "where"
+            | "or_where"
+            | "join"
+            | "left_join"
             | "order_by"
             | "limit"
+            | "offset"
+            | "group_by"
+            | "having"
+            | "aggregate"
             | "insert"
+            | "insert_many"
             | "upsert"
+            | "on_conflict"
+            | "do_update"
+            | "do_nothing"
             | "update"
             | "delete"
+            | "returning"
             | "count"
             | "one"
             | "all"
             | "exec"
+            | "execute"
             | "sql"
             | "params"
     )
@@ -485,6 +811,19 @@ fn parse_canonical_function_name(name: &str) -> Option<(ModuleId, &str)> {
     Some((module_id.parse().ok()?, raw_name))
 }
 
+fn query_column_list(builtin: &str, value: &Value) -> Result<Vec<String>, String> {
+    let Value::List(items) = value.unboxed() else {
+        return Err(format!("{builtin} expects a list of strings"));
+    };
+    items
+        .into_iter()
+        .map(|item| match item {
+            Value::String(text) => Ok(text),
+            _ => Err(format!("{builtin} expects a list of strings")),
+        })
+        .collect()
+}
+
 fn force_html_input_tag_call(name: &str, args: &[crate::ast::CallArg]) -> bool {
     if name != "input" {
         return false;
@@ -648,6 +987,178 @@ pub struct MigrationJob<'a> {
     pub id: String,
     pub module_id: ModuleId,
     pub decl: &'a MigrationDecl,
+    /// Digest of the migration body and `down:` block source; empty when the
+    /// source is unavailable.
+    pub checksum: String,
+}
+
+pub struct SeedJob<'a> {
+    pub package: String,
+    pub name: String,
+    pub module_id: ModuleId,
+    pub decl: &'a SeedDecl,
+    /// Digest of the seed body source; empty when the source is unavailable.
+    pub checksum: String,
+}
+
+/// Migrations and seeds applied to a fresh in-memory database before each test.
+pub struct TestFixtures<'m, 'a> {
+    pub migrations: &'m [MigrationJob<'a>],
+    pub seeds: &'m [SeedJob<'a>],
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MigrationState {
+    Applied,
+    Pending,
+    /// Applied, but the body changed since (checksum mismatch).
+    Modified,
+    /// Recorded as applied, but no longer declared in source.
+    Missing,
+}
+
+impl MigrationState {
+    pub fn label(self) -> &'static str {
+        match self {
+            MigrationState::Applied => "applied",
+            MigrationState::Pending => "pending",
+            MigrationState::Modified => "modified",
+            MigrationState::Missing => "missing",
+        }
+    }
+}
+
+pub struct MigrationStatus {
+    pub package: String,
+    pub name: String,
+    pub state: MigrationState,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MigrationDirection {
+    Up,
+    Down,
+}
+
+/// Statements a migration issued during a dry run.
+pub struct MigrationPlanStep {
+    pub package: String,
+    pub name: String,
+    pub direction: MigrationDirection,
+    pub statements: Vec<String>,
+}
+
+fn checksum_mismatch(stored: &str, current: &str) -> bool {
+    !stored.is_empty() && !current.is_empty() && stored != current
+}
+
+fn verify_migration_checksums(
+    migrations: &[MigrationJob<'_>],
+    applied: &HashMap<(String, String), String>,
+) -> Result<(), String> {
+    for job in migrations {
+        let key = (job.package.clone(), job.id.clone());
+        if let Some(stored) = applied.get(&key)
+            && checksum_mismatch(stored, &job.checksum)
+        {
+            return Err(format!(
+                "migration {} in package {} was modified after it was applied (checksum mismatch)",
+                job.id,
+                migration_package_label(&job.package)
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn migration_package_label(package: &str) -> &str {
+    if package.is_empty() {
+        "<default>"
+    } else {
+        package
+    }
+}
+
+fn pending_migrations<'m, 'a>(
+    migrations: &'m [MigrationJob<'a>],
+    applied: &HashMap<(String, String), String>,
+) -> Vec<&'m MigrationJob<'a>> {
+    migrations
+        .iter()
+        .filter(|job| !applied.contains_key(&(job.package.clone(), job.id.clone())))
+        .collect()
+}
+
+/// Resolves a `--to` target to the package that declares it. `target` is a
+/// migration name, or `<package>:<name>` when several packages declare it.
+fn rollback_target<'t>(
+    migrations: &[MigrationJob<'_>],
+    target: &'t str,
+) -> Result<(String, &'t str), String> {
+    let (package, name) = match target.split_once(':') {
+        Some((package, name)) => (Some(package), name),
+        None => (None, target),
+    };
+    let packages: Vec<&str> = migrations
+        .iter()
+        .filter(|job| job.id == name && package.is_none_or(|package| job.package == package))
+        .map(|job| job.package.as_str())
+        .collect();
+    match packages.as_slice() {
+        [] => Err(format!("unknown migration {target}")),
+        [package] => Ok((package.to_string(), name)),
+        _ => Err(format!(
+            "migration {name} is declared in packages {}; use --to <package>:{name}",
+            packages
+                .iter()
+                .map(|package| migration_package_label(package))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+fn rollback_migrations_plan<'m, 'a>(
+    migrations: &'m [MigrationJob<'a>],
+    applied: &HashMap<(String, String), String>,
+    target: Option<&str>,
+) -> Result<Vec<&'m MigrationJob<'a>>, String> {
+    let target = match target {
+        Some(target) => Some(rollback_target(migrations, target)?),
+        None => None,
+    };
+    if let Some((target_package, target)) = &target {
+        for (package, name) in applied.keys() {
+            if package == target_package
+                && name.as_str() > *target
+                && !migrations
+                    .iter()
+                    .any(|job| &job.package == package && &job.id == name)
+            {
+                return Err(format!(
+                    "migration {name} in package {} is applied but no longer declared",
+                    migration_package_label(package)
+                ));
+            }
+        }
+    }
+    let mut applied_jobs = migrations
+        .iter()
+        .filter(|job| applied.contains_key(&(job.package.clone(), job.id.clone())));
+    let mut steps: Vec<&MigrationJob<'a>> = match &target {
+        // `--to` only reverts migrations of the package that declares the target.
+        Some((target_package, target)) => applied_jobs
+            .filter(|job| &job.package == target_package && job.id.as_str() > *target)
+            .collect(),
+        None => applied_jobs.next_back().into_iter().collect(),
+    };
+    steps.reverse();
+    for job in &steps {
+        if job.decl.down.is_none() {
+            return Err(format!("migration {} has no down block", job.id));
+        }
+    }
+    Ok(steps)
 }
 
 pub struct TestJob<'a> {
@@ -710,6 +1221,22 @@ impl crate::runtime_types::RuntimeTypeHost for Interpreter {
     ) -> Result<(), Self::Error> {
         Interpreter::check_refined(self, value, base, args, path)
     }
+
+    fn struct_field_types(&self, name: &str) -> Option<Vec<(String, TypeRef)>> {
+        let decl = self.types.get(name)?;
+        Some(
+            decl.fields
+                .iter()
+                .map(|field| (field.name.name.clone(), field.ty.clone()))
+                .collect(),
+        )
+    }
+
+    fn enum_payload_types(&self, name: &str, variant: &str) -> Option<Vec<TypeRef>> {
+        let decl = self.enums.get(name)?;
+        let variant = decl.variants.iter().find(|v| v.name.name == variant)?;
+        Some(variant.payload.clone())
+    }
 }
 
 impl Interpreter {
@@ -794,7 +1321,10 @@ impl Interpreter {
     }
 
     pub fn with_registry(registry: &ModuleRegistry) -> Self {
-        let monomorphized = crate::frontend::monomorphize::monomorphize_registry(registry);
+        let with_defaults = crate::frontend::interface_desugar::with_default_methods(registry);
+        let mut monomorphized =
+            crate::frontend::monomorphize::monomorphize_registry(&with_defaults);
+        crate::frontend::canonicalize::inline_registry_consts(&mut monomorphized);
         let lowered_registry = crate::frontend::interface_desugar::desugar_registry(&monomorphized);
         let registry = &lowered_registry;
         let mut functions: HashMap<ModuleId, HashMap<String, FnDecl>> = HashMap::new();
@@ -971,94 +1501,379 @@ impl Interpreter {
         out
     }
 
+    /// Runs database work against `db` instead of the configured database URL.
+    pub fn attach_db(&mut self, db: Db) {
+        self.db = Some(db);
+    }
+
+    pub fn detach_db(&mut self) -> Option<Db> {
+        self.db.take()
+    }
+
     pub fn run_migrations(&mut self, migrations: &[MigrationJob<'_>]) -> Result<(), String> {
+        let applied = self.prepare_migration_history(migrations)?;
+        let pending = pending_migrations(migrations, &applied);
+        for job in pending {
+            self.apply_migration_step(job, MigrationDirection::Up)?;
+        }
+        Ok(())
+    }
+
+    /// Applies seeds not yet recorded in `__fuse_seeds`, each in its own
+    /// transaction together with its history row, and returns their names.
+    pub fn run_seeds(&mut self, seeds: &[SeedJob<'_>]) -> Result<Vec<String>, String> {
         if let Err(err) = self.eval_configs() {
             return Err(self.render_exec_error(err));
         }
-        {
+        let applied = {
             let db = match self.db_mut() {
                 Ok(db) => db,
                 Err(err) => return Err(self.render_exec_error(err)),
             };
-            Self::ensure_migration_history_schema(db)?;
+            db.exec(
+                "CREATE TABLE IF NOT EXISTS __fuse_seeds (package TEXT NOT NULL DEFAULT '', name TEXT NOT NULL, applied_at TEXT NOT NULL, checksum TEXT NOT NULL DEFAULT '', PRIMARY KEY (package, name))",
+            )?;
+            let mut applied = HashMap::new();
+            for row in db.query("SELECT package, name, checksum FROM __fuse_seeds")? {
+                let field = |key: &str| {
+                    row.get(key)
+                        .and_then(Self::value_as_string)
+                        .unwrap_or_default()
+                };
+                applied.insert((field("package"), field("name")), field("checksum"));
+            }
+            applied
+        };
+        for job in seeds {
+            if let Some(stored) = applied.get(&(job.package.clone(), job.name.clone()))
+                && checksum_mismatch(stored, &job.checksum)
+            {
+                return Err(format!(
+                    "seed {} in package {} was modified after it was applied (checksum mismatch)",
+                    job.name,
+                    migration_package_label(&job.package)
+                ));
+            }
+        }
+        let mut out = Vec::new();
+        for job in seeds {
+            if applied.contains_key(&(job.package.clone(), job.name.clone())) {
+                continue;
+            }
+            self.apply_seed(job)?;
+            out.push(job.name.clone());
         }
-        let applied_rows = {
+        Ok(out)
+    }
+
+    fn apply_seed(&mut self, job: &SeedJob<'_>) -> Result<(), String> {
+        {
             let db = match self.db_mut() {
                 Ok(db) => db,
                 Err(err) => return Err(self.render_exec_error(err)),
             };
-            db.query("SELECT package, name FROM __fuse_migrations")?
+            db.begin_transaction()?;
+        }
+        let prev_module = self.current_module;
+        self.current_module = job.module_id;
+        let result = self.eval_block(&job.decl.body);
+        self.current_module = prev_module;
+        let result = match result {
+            Ok(_) => Ok(()),
+            Err(ExecError::Return(_)) => Err("return not allowed in seed".to_string()),
+            Err(err) => Err(format!(
+                "seed {}: {}",
+                job.name,
+                self.render_exec_error(err)
+            )),
         };
-        let mut applied: HashSet<(String, String)> = HashSet::new();
-        for row in applied_rows {
-            let package = row
-                .get("package")
-                .and_then(Self::value_as_string)
-                .unwrap_or_default();
-            if let Some(name) = row.get("name").and_then(Self::value_as_string) {
-                applied.insert((package, name));
-            }
+        let db = match self.db_mut() {
+            Ok(db) => db,
+            Err(err) => return Err(self.render_exec_error(err)),
+        };
+        let recorded = result.and_then(|_| {
+            db.execute(
+                "INSERT INTO __fuse_seeds (package, name, applied_at, checksum) VALUES (?1, ?2, CURRENT_TIMESTAMP, ?3)",
+                (&job.package, &job.name, &job.checksum),
+            )
+            .map(|_| ())
+        });
+        if let Err(err) = recorded.and_then(|_| db.commit_transaction()) {
+            let _ = db.rollback_transaction();
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Reverts applied migrations newest-first. With `target`, every applied
+    /// migration ordered after `target` is reverted and `target` itself stays
+    /// applied; without it only the most recently ordered migration is reverted.
+    pub fn rollback_migrations(
+        &mut self,
+        migrations: &[MigrationJob<'_>],
+        target: Option<&str>,
+    ) -> Result<Vec<String>, String> {
+        let applied = self.prepare_migration_history(migrations)?;
+        let steps = rollback_migrations_plan(migrations, &applied, target)?;
+        let mut out = Vec::with_capacity(steps.len());
+        for job in steps {
+            self.apply_migration_step(job, MigrationDirection::Down)?;
+            out.push(job.id.clone());
+        }
+        Ok(out)
+    }
+
+    pub fn migration_status(
+        &mut self,
+        migrations: &[MigrationJob<'_>],
+    ) -> Result<Vec<MigrationStatus>, String> {
+        if let Err(err) = self.eval_configs() {
+            return Err(self.render_exec_error(err));
         }
+        let applied = {
+            let db = match self.db_mut() {
+                Ok(db) => db,
+                Err(err) => return Err(self.render_exec_error(err)),
+            };
+            Self::load_applied_migrations(db)?
+        };
+        let mut out = Vec::with_capacity(migrations.len());
+        let mut declared = HashSet::new();
         for job in migrations {
             let key = (job.package.clone(), job.id.clone());
-            if applied.contains(&key) {
-                continue;
-            }
-            {
-                let db = match self.db_mut() {
-                    Ok(db) => db,
-                    Err(err) => return Err(self.render_exec_error(err)),
-                };
-                db.begin_transaction()?;
-            }
-            let prev_module = self.current_module;
-            self.current_module = job.module_id;
-            let result = self.eval_block(&job.decl.body);
-            self.current_module = prev_module;
-            match result {
-                Ok(_) => {}
-                Err(ExecError::Return(_)) => {
-                    if let Ok(db) = self.db_mut() {
-                        let _ = db.rollback_transaction();
-                    }
-                    return Err("return not allowed in migration".to_string());
-                }
-                Err(err) => {
-                    if let Ok(db) = self.db_mut() {
-                        let _ = db.rollback_transaction();
-                    }
-                    return Err(self.render_exec_error(err));
-                }
-            }
-            {
-                let db = match self.db_mut() {
-                    Ok(db) => db,
-                    Err(err) => return Err(self.render_exec_error(err)),
-                };
-                if let Err(err) = db.execute(
-                    "INSERT INTO __fuse_migrations (package, name, applied_at) VALUES (?1, ?2, CURRENT_TIMESTAMP)",
-                    (&job.package, &job.id),
-                ) {
-                    let _ = db.rollback_transaction();
-                    return Err(err);
-                }
-                if let Err(err) = db.commit_transaction() {
-                    let _ = db.rollback_transaction();
-                    return Err(err);
+            let state = match applied.get(&key) {
+                Some(stored) if checksum_mismatch(stored, &job.checksum) => {
+                    MigrationState::Modified
                 }
+                Some(_) => MigrationState::Applied,
+                None => MigrationState::Pending,
+            };
+            declared.insert(key);
+            out.push(MigrationStatus {
+                package: job.package.clone(),
+                name: job.id.clone(),
+                state,
+            });
+        }
+        for (package, name) in applied.keys() {
+            if declared.contains(&(package.clone(), name.clone())) {
+                continue;
             }
-            applied.insert(key);
+            out.push(MigrationStatus {
+                package: package.clone(),
+                name: name.clone(),
+                state: MigrationState::Missing,
+            });
         }
-        Ok(())
+        out.sort_by(|a, b| a.package.cmp(&b.package).then_with(|| a.name.cmp(&b.name)));
+        Ok(out)
     }
 
-    fn ensure_migration_history_schema(db: &Db) -> Result<(), String> {
-        db.exec(
-            "CREATE TABLE IF NOT EXISTS __fuse_migrations (package TEXT NOT NULL DEFAULT '', name TEXT NOT NULL, applied_at TEXT NOT NULL, PRIMARY KEY (package, name))",
-        )?;
+    /// Runs the migrations `direction` would execute inside a single
+    /// transaction that is always rolled back, returning the captured SQL.
+    pub fn dry_run_migrations(
+        &mut self,
+        migrations: &[MigrationJob<'_>],
+        direction: MigrationDirection,
+        target: Option<&str>,
+    ) -> Result<Vec<MigrationPlanStep>, String> {
+        if let Err(err) = self.eval_configs() {
+            return Err(self.render_exec_error(err));
+        }
+        let applied = {
+            let db = match self.db_mut() {
+                Ok(db) => db,
+                Err(err) => return Err(self.render_exec_error(err)),
+            };
+            Self::load_applied_migrations(db)?
+        };
+        verify_migration_checksums(migrations, &applied)?;
+        let steps = match direction {
+            MigrationDirection::Up => pending_migrations(migrations, &applied),
+            MigrationDirection::Down => rollback_migrations_plan(migrations, &applied, target)?,
+        };
+        {
+            let db = match self.db_mut() {
+                Ok(db) => db,
+                Err(err) => return Err(self.render_exec_error(err)),
+            };
+            db.begin_transaction()?;
+        }
+        let mut out = Vec::with_capacity(steps.len());
+        let mut failure = None;
+        for job in steps {
+            if let Ok(db) = self.db_mut() {
+                db.begin_statement_capture();
+            }
+            let result = self.eval_migration_block(job, direction);
+            let statements = match self.db_mut() {
+                Ok(db) => db.take_statement_capture(),
+                Err(_) => Vec::new(),
+            };
+            if let Err(err) = result {
+                failure = Some(err);
+                break;
+            }
+            out.push(MigrationPlanStep {
+                package: job.package.clone(),
+                name: job.id.clone(),
+                direction,
+                statements,
+            });
+        }
+        if let Ok(db) = self.db_mut() {
+            let _ = db.rollback_transaction();
+        }
+        match failure {
+            Some(err) => Err(err),
+            None => Ok(out),
+        }
+    }
+
+    fn prepare_migration_history(
+        &mut self,
+        migrations: &[MigrationJob<'_>],
+    ) -> Result<HashMap<(String, String), String>, String> {
+        if let Err(err) = self.eval_configs() {
+            return Err(self.render_exec_error(err));
+        }
+        let db = match self.db_mut() {
+            Ok(db) => db,
+            Err(err) => return Err(self.render_exec_error(err)),
+        };
+        Self::ensure_migration_history_schema(db)?;
+        let applied = Self::load_applied_migrations(db)?;
+        verify_migration_checksums(migrations, &applied)?;
+        // Rows recorded before checksums existed adopt the current body digest.
+        for job in migrations {
+            let key = (job.package.clone(), job.id.clone());
+            if matches!(applied.get(&key), Some(stored) if stored.is_empty())
+                && !job.checksum.is_empty()
+            {
+                db.execute(
+                    "UPDATE __fuse_migrations SET checksum = ?1 WHERE package = ?2 AND name = ?3",
+                    (&job.checksum, &job.package, &job.id),
+                )?;
+            }
+        }
+        Ok(applied)
+    }
+
+    fn apply_migration_step(
+        &mut self,
+        job: &MigrationJob<'_>,
+        direction: MigrationDirection,
+    ) -> Result<(), String> {
+        {
+            let db = match self.db_mut() {
+                Ok(db) => db,
+                Err(err) => return Err(self.render_exec_error(err)),
+            };
+            db.begin_transaction()?;
+        }
+        if let Err(err) = self.eval_migration_block(job, direction) {
+            if let Ok(db) = self.db_mut() {
+                let _ = db.rollback_transaction();
+            }
+            return Err(err);
+        }
+        let db = match self.db_mut() {
+            Ok(db) => db,
+            Err(err) => return Err(self.render_exec_error(err)),
+        };
+        let recorded = match direction {
+            MigrationDirection::Up => db.execute(
+                "INSERT INTO __fuse_migrations (package, name, applied_at, checksum) VALUES (?1, ?2, CURRENT_TIMESTAMP, ?3)",
+                (&job.package, &job.id, &job.checksum),
+            ),
+            MigrationDirection::Down => db.execute(
+                "DELETE FROM __fuse_migrations WHERE package = ?1 AND name = ?2",
+                (&job.package, &job.id),
+            ),
+        };
+        if let Err(err) = recorded {
+            let _ = db.rollback_transaction();
+            return Err(err);
+        }
+        if let Err(err) = db.commit_transaction() {
+            let _ = db.rollback_transaction();
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    fn eval_migration_block(
+        &mut self,
+        job: &MigrationJob<'_>,
+        direction: MigrationDirection,
+    ) -> Result<(), String> {
+        let block = match direction {
+            MigrationDirection::Up => &job.decl.body,
+            MigrationDirection::Down => job
+                .decl
+                .down
+                .as_ref()
+                .ok_or_else(|| format!("migration {} has no down block", job.id))?,
+        };
+        let prev_module = self.current_module;
+        self.current_module = job.module_id;
+        let result = self.eval_block(block);
+        self.current_module = prev_module;
+        match result {
+            Ok(_) => Ok(()),
+            Err(ExecError::Return(_)) => Err("return not allowed in migration".to_string()),
+            Err(err) => Err(self.render_exec_error(err)),
+        }
+    }
+
+    /// Reads `__fuse_migrations` without creating or upgrading it; returns the
+    /// stored checksum (empty for legacy rows) keyed by `(package, name)`.
+    fn load_applied_migrations(db: &Db) -> Result<HashMap<(String, String), String>, String> {
+        let tables = db.query(
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name = '__fuse_migrations'",
+        )?;
+        if tables.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let mut has_package = false;
+        let mut has_checksum = false;
+        for row in db.query("PRAGMA table_info(__fuse_migrations)")? {
+            match row.get("name").and_then(Self::value_as_string).as_deref() {
+                Some("package") => has_package = true,
+                Some("checksum") => has_checksum = true,
+                _ => {}
+            }
+        }
+        let sql = match (has_package, has_checksum) {
+            (true, true) => "SELECT package, name, checksum FROM __fuse_migrations",
+            (true, false) => "SELECT package, name, '' AS checksum FROM __fuse_migrations",
+            (false, _) => "SELECT '' AS package, id AS name, '' AS checksum FROM __fuse_migrations",
+        };
+        let mut applied = HashMap::new();
+        for row in db.query(sql)? {
+            let package = row
+                .get("package")
+                .and_then(Self::value_as_string)
+                .unwrap_or_default();
+            let checksum = row
+                .get("checksum")
+                .and_then(Self::value_as_string)
+                .unwrap_or_default();
+            if let Some(name) = row.get("name").and_then(Self::value_as_string) {
+                applied.insert((package, name), checksum);
+            }
+        }
+        Ok(applied)
+    }
+
+    fn ensure_migration_history_schema(db: &Db) -> Result<(), String> {
+        db.exec(
+            "CREATE TABLE IF NOT EXISTS __fuse_migrations (package TEXT NOT NULL DEFAULT '', name TEXT NOT NULL, applied_at TEXT NOT NULL, checksum TEXT NOT NULL DEFAULT '', PRIMARY KEY (package, name))",
+        )?;
         let info_rows = db.query("PRAGMA table_info(__fuse_migrations)")?;
         let mut has_package = false;
         let mut has_name = false;
+        let mut has_checksum = false;
         let mut has_legacy_id = false;
         for row in info_rows {
             let Some(column) = row.get("name").and_then(Self::value_as_string) else {
@@ -1067,11 +1882,17 @@ impl Interpreter {
             match column.as_str() {
                 "package" => has_package = true,
                 "name" => has_name = true,
+                "checksum" => has_checksum = true,
                 "id" => has_legacy_id = true,
                 _ => {}
             }
         }
         if has_package && has_name {
+            if !has_checksum {
+                db.exec(
+                    "ALTER TABLE __fuse_migrations ADD COLUMN checksum TEXT NOT NULL DEFAULT ''",
+                )?;
+            }
             return Ok(());
         }
         if !has_legacy_id {
@@ -1085,7 +1906,7 @@ impl Interpreter {
         let result = (|| -> Result<(), String> {
             db.exec("ALTER TABLE __fuse_migrations RENAME TO __fuse_migrations_legacy")?;
             db.exec(
-                "CREATE TABLE __fuse_migrations (package TEXT NOT NULL DEFAULT '', name TEXT NOT NULL, applied_at TEXT NOT NULL, PRIMARY KEY (package, name))",
+                "CREATE TABLE __fuse_migrations (package TEXT NOT NULL DEFAULT '', name TEXT NOT NULL, applied_at TEXT NOT NULL, checksum TEXT NOT NULL DEFAULT '', PRIMARY KEY (package, name))",
             )?;
             db.exec(
                 "INSERT INTO __fuse_migrations (package, name, applied_at) SELECT '' as package, id as name, applied_at FROM __fuse_migrations_legacy",
@@ -1109,12 +1930,28 @@ impl Interpreter {
         }
     }
 
-    pub fn run_tests(&mut self, tests: &[TestJob<'_>]) -> Result<Vec<TestOutcome>, String> {
+    /// Runs `tests` in order. With `fixtures`, each test gets a fresh
+    /// in-memory database with the migrations and seeds applied first.
+    pub fn run_tests(
+        &mut self,
+        tests: &[TestJob<'_>],
+        fixtures: Option<&TestFixtures<'_, '_>>,
+    ) -> Result<Vec<TestOutcome>, String> {
         if let Err(err) = self.eval_configs() {
             return Err(self.render_exec_error(err));
         }
         let mut out = Vec::new();
         for job in tests {
+            if let Some(fixtures) = fixtures
+                && let Err(err) = self.prepare_test_fixtures(fixtures)
+            {
+                out.push(TestOutcome {
+                    name: job.name.clone(),
+                    ok: false,
+                    message: Some(format!("test fixtures failed: {err}")),
+                });
+                continue;
+            }
             let prev_module = self.current_module;
             self.current_module = job.module_id;
             let result = self.eval_block(&job.decl.body);
@@ -1137,9 +1974,22 @@ impl Interpreter {
                 }),
             }
         }
+        if fixtures.is_some() {
+            self.db = None;
+        }
         Ok(out)
     }
 
+    fn prepare_test_fixtures(&mut self, fixtures: &TestFixtures<'_, '_>) -> Result<(), String> {
+        self.db = Some(Db::open_with_options(
+            "sqlite::memory:",
+            &DbOptions::default(),
+        )?);
+        self.run_migrations(fixtures.migrations)?;
+        self.run_seeds(fixtures.seeds)?;
+        Ok(())
+    }
+
     pub fn parse_cli_value(&mut self, ty: &TypeRef, raw: &str) -> Result<Value, String> {
         match self.parse_env_value(ty, raw) {
             Ok(value) => Ok(value),
@@ -1423,6 +2273,9 @@ impl Interpreter {
     }
 
     fn eval_stmt(&mut self, stmt: &Stmt) -> ExecResult<Value> {
+        if task_cancelled() {
+            return Err(ExecError::Runtime(TASK_CANCELLED.to_string()));
+        }
         match &stmt.kind {
             StmtKind::Let { name, expr, .. } => {
                 let value = self.eval_expr(expr)?;
@@ -1434,6 +2287,19 @@ impl Interpreter {
                 self.env.insert(&name.name, value);
                 Ok(Value::Unit)
             }
+            StmtKind::LetPattern { pat, expr, .. } => {
+                let value = self.eval_expr(expr)?;
+                let mut bindings = HashMap::new();
+                if !self.match_pattern(&value, pat, &mut bindings)? {
+                    return Err(ExecError::Runtime(
+                        "let pattern did not match value".to_string(),
+                    ));
+                }
+                for (name, value) in bindings {
+                    self.env.insert(&name, value);
+                }
+                Ok(Value::Unit)
+            }
             StmtKind::Assign { target, expr } => match &target.kind {
                 _ => {
                     let value = self.eval_expr(expr)?;
@@ -1477,26 +2343,51 @@ impl Interpreter {
             }
             StmtKind::Match { expr, cases } => {
                 let value = self.eval_expr(expr)?;
-                for (pat, block) in cases {
+                for case in cases {
                     let mut bindings = HashMap::new();
-                    if self.match_pattern(&value, pat, &mut bindings)? {
-                        self.env.push();
-                        for (name, value) in bindings {
-                            self.env.insert(&name, value);
+                    if !self.match_pattern(&value, &case.pat, &mut bindings)? {
+                        continue;
+                    }
+                    self.env.push();
+                    for (name, value) in bindings {
+                        self.env.insert(&name, value);
+                    }
+                    if let Some(guard) = &case.guard {
+                        let passed = match self.eval_expr(guard) {
+                            Ok(value) => self.as_bool(&value),
+                            Err(err) => Err(err),
+                        };
+                        match passed {
+                            Ok(true) => {}
+                            Ok(false) => {
+                                self.env.pop();
+                                continue;
+                            }
+                            Err(err) => {
+                                self.env.pop();
+                                return Err(err);
+                            }
                         }
-                        let result = self.eval_block(block);
-                        self.env.pop();
-                        return result;
                     }
+                    let result = self.eval_block(&case.block);
+                    self.env.pop();
+                    return result;
                 }
                 Ok(Value::Unit)
             }
             StmtKind::For { pat, iter, block } => {
                 let iter_value = self.eval_expr(iter)?;
                 let iter_value = iter_value.unboxed();
-                let items = match iter_value {
-                    Value::List(items) => items,
-                    Value::Map(items) => items.into_values().collect(),
+                // Ranges are walked lazily; lists and maps are already in memory.
+                let items: Box<dyn Iterator<Item = Value>> = match iter_value {
+                    Value::List(items) => Box::new(items.into_iter()),
+                    Value::Range(range) => Box::new(range.iter()),
+                    Value::Map(items) if pat.is_pair() => Box::new(
+                        items
+                            .into_iter()
+                            .map(|(key, value)| Value::List(vec![Value::String(key), value])),
+                    ),
+                    Value::Map(items) => Box::new(items.into_values()),
                     other => {
                         return Err(ExecError::Runtime(format!(
                             "cannot iterate over {}",
@@ -1587,7 +2478,10 @@ impl Interpreter {
                     | BinaryOp::Gt
                     | BinaryOp::GtEq => self.eval_compare(op, left_val, right_val),
                     BinaryOp::And | BinaryOp::Or => self.eval_bool(op, left_val, right_val),
-                    BinaryOp::Range => self.eval_range(left_val, right_val),
+                    BinaryOp::Range => self.eval_range("range", left_val, right_val),
+                    BinaryOp::RangeExclusive => {
+                        self.eval_range("range.exclusive", left_val, right_val)
+                    }
                 }
             }
             ExprKind::Unary { op, expr } => {
@@ -1596,6 +2490,7 @@ impl Interpreter {
                     UnaryOp::Neg => match value {
                         Value::Int(v) => Ok(Value::Int(-v)),
                         Value::Float(v) => Ok(Value::Float(-v)),
+                        Value::Decimal(v) => Ok(Value::Decimal(-v)),
                         _ => Err(ExecError::Runtime("unary '-' expects number".to_string())),
                     },
                     UnaryOp::Not => Ok(Value::Bool(!self.as_bool(&value)?)),
@@ -1635,6 +2530,25 @@ impl Interpreter {
                         }
                     }
                     let base_val = self.eval_expr(base)?.unboxed();
+                    if !type_args.is_empty() && matches!(&base_val, Value::Builtin(b) if b == "db")
+                    {
+                        if !matches!(name.name.as_str(), "query" | "one") {
+                            return Err(ExecError::Runtime(
+                                "type arguments are only supported on db.query<T>() and db.one<T>()"
+                                    .to_string(),
+                            ));
+                        }
+                        if type_args.len() != 1 {
+                            return Err(ExecError::Runtime(
+                                "typed query methods expect exactly one type argument".to_string(),
+                            ));
+                        }
+                        let type_name = self.query_typed_type_name(&type_args[0])?;
+                        let mut db_args = Vec::with_capacity(arg_vals.len() + 1);
+                        db_args.push(Value::String(type_name));
+                        db_args.extend(arg_vals);
+                        return self.eval_builtin(&format!("db.{}_typed", name.name), db_args);
+                    }
                     if is_query_method(&name.name) && matches!(base_val, Value::Query(_)) {
                         if !type_args.is_empty() {
                             if !matches!(name.name.as_str(), "one" | "all") {
@@ -1663,6 +2577,22 @@ impl Interpreter {
                         query_args.extend(arg_vals);
                         return self.eval_builtin(&format!("query.{}", name.name), query_args);
                     }
+                    if crate::runtime_ranges::is_range_method(&name.name)
+                        && matches!(base_val, Value::Range(_))
+                    {
+                        let mut range_args = Vec::with_capacity(arg_vals.len() + 1);
+                        range_args.push(base_val);
+                        range_args.extend(arg_vals);
+                        return self.eval_builtin(&format!("range.{}", name.name), range_args);
+                    }
+                    if crate::runtime_channels::is_channel_method(&name.name)
+                        && matches!(base_val, Value::Channel(_))
+                    {
+                        let mut channel_args = Vec::with_capacity(arg_vals.len() + 1);
+                        channel_args.push(base_val);
+                        channel_args.extend(arg_vals);
+                        return self.eval_builtin(&format!("channel.{}", name.name), channel_args);
+                    }
                     let callee_val = self.eval_member(base_val, &name.name)?;
                     return self.eval_call(callee_val, arg_vals);
                 }
@@ -1708,7 +2638,42 @@ impl Interpreter {
                 }
             }
             ExprKind::StructLit { name, fields } => self.eval_struct_lit(name, fields),
-            ExprKind::ListLit(items) => {
+            ExprKind::RecordUpdate { base, fields } => {
+                let (name, mut values) = match self.eval_expr(base)?.unboxed() {
+                    Value::Struct { name, fields } => (name, fields),
+                    _ => {
+                        return Err(ExecError::Runtime(
+                            "record update expects a struct".to_string(),
+                        ));
+                    }
+                };
+                let decl = match self.types.get(&name) {
+                    Some(decl) => decl.clone(),
+                    None => return Err(ExecError::Runtime(format!("unknown type {}", name))),
+                };
+                for field in fields {
+                    if !decl.fields.iter().any(|f| f.name.name == field.name.name) {
+                        return Err(ExecError::Runtime(format!(
+                            "unknown field {}.{}",
+                            name, field.name.name
+                        )));
+                    }
+                    let value = self.eval_expr(&field.value)?;
+                    values.insert(field.name.name.clone(), value);
+                }
+                // Validate the whole result, as constructing it from scratch would.
+                for field_decl in &decl.fields {
+                    if let Some(value) = values.get(&field_decl.name.name) {
+                        let path = format!("{}.{}", name, field_decl.name.name);
+                        self.validate_value(value, &field_decl.ty, &path)?;
+                    }
+                }
+                Ok(Value::Struct {
+                    name,
+                    fields: values,
+                })
+            }
+            ExprKind::ListLit(items) | ExprKind::TupleLit(items) => {
                 let mut values = Vec::with_capacity(items.len());
                 for item in items {
                     values.push(self.eval_expr(item)?);
@@ -1791,9 +2756,10 @@ impl Interpreter {
                 body_children,
             } => {
                 let iter_value = self.eval_expr(iter)?.unboxed();
-                let items = match iter_value {
-                    Value::List(items) => items,
-                    Value::Map(items) => items.into_values().collect(),
+                let items: Box<dyn Iterator<Item = Value>> = match iter_value {
+                    Value::List(items) => Box::new(items.into_iter()),
+                    Value::Range(range) => Box::new(range.iter()),
+                    Value::Map(items) => Box::new(items.into_values()),
                     other => {
                         return Err(ExecError::Runtime(format!(
                             "cannot iterate over {}",
@@ -1820,13 +2786,25 @@ impl Interpreter {
                 }
                 Ok(Value::List(out))
             }
-            ExprKind::Await { expr } => {
+            ExprKind::Await {
+                expr,
+                timeout: None,
+            } => {
                 let value = self.eval_expr(expr)?;
                 match value {
                     Value::Task(task) => task.result(),
                     _ => Err(ExecError::Runtime("await expects a Task value".to_string())),
                 }
             }
+            ExprKind::Await {
+                expr,
+                timeout: Some(timeout),
+            } => {
+                let args = [self.eval_expr(expr)?, self.eval_expr(timeout)?];
+                let result = crate::runtime_tasks::task_call("task.await_within", &args)
+                    .map_err(ExecError::Runtime)?;
+                Task::from_task_result(result).result()
+            }
             ExprKind::Box { expr } => {
                 let value = self.eval_expr(expr)?;
                 match value {
@@ -1861,25 +2839,40 @@ impl Interpreter {
         ))
     }
 
-    fn db_pool_size(&self) -> ExecResult<usize> {
+    fn db_options(&self) -> ExecResult<DbOptions> {
+        let mut options = DbOptions::default();
         if let Ok(raw) = std::env::var("FUSE_DB_POOL_SIZE") {
-            return parse_db_pool_size(&raw, "FUSE_DB_POOL_SIZE").map_err(ExecError::Runtime);
+            options.pool_size =
+                parse_db_pool_size(&raw, "FUSE_DB_POOL_SIZE").map_err(ExecError::Runtime)?;
+        } else if let Some(value) = self.app_config_field("dbPoolSize") {
+            options.pool_size =
+                parse_db_pool_size_value(value, "App.dbPoolSize").map_err(ExecError::Runtime)?;
         }
-        if let Some(value) = self
-            .configs
-            .get("App")
-            .and_then(|config| config.get("dbPoolSize"))
-        {
-            return parse_db_pool_size_value(value, "App.dbPoolSize").map_err(ExecError::Runtime);
+        if let Ok(raw) = std::env::var("FUSE_DB_BUSY_TIMEOUT_MS") {
+            options.busy_timeout_ms = parse_db_busy_timeout(&raw, "FUSE_DB_BUSY_TIMEOUT_MS")
+                .map_err(ExecError::Runtime)?;
+        } else if let Some(value) = self.app_config_field("dbBusyTimeoutMs") {
+            options.busy_timeout_ms = parse_db_busy_timeout_value(value, "App.dbBusyTimeoutMs")
+                .map_err(ExecError::Runtime)?;
         }
-        Ok(DEFAULT_DB_POOL_SIZE)
+        if let Ok(raw) = std::env::var("FUSE_DB_WAL") {
+            options.wal = parse_db_wal(&raw, "FUSE_DB_WAL").map_err(ExecError::Runtime)?;
+        } else if let Some(value) = self.app_config_field("dbWal") {
+            options.wal = parse_db_wal_value(value, "App.dbWal").map_err(ExecError::Runtime)?;
+        }
+        crate::observability::db_slow_query_threshold_ms().map_err(ExecError::Runtime)?;
+        Ok(options)
+    }
+
+    fn app_config_field(&self, field: &str) -> Option<&Value> {
+        self.configs.get("App").and_then(|config| config.get(field))
     }
 
     fn db_mut(&mut self) -> ExecResult<&mut Db> {
         if self.db.is_none() {
-            let pool_size = self.db_pool_size()?;
+            let options = self.db_options()?;
             let url = self.db_url()?;
-            let db = Db::open_with_pool(&url, pool_size).map_err(ExecError::Runtime)?;
+            let db = Db::open_shared(&url, &options).map_err(ExecError::Runtime)?;
             self.db = Some(db);
         }
         Ok(self.db.as_mut().expect("db initialized"))
@@ -1905,7 +2898,9 @@ impl Interpreter {
         match name {
             "print" | "input" | "env" | "env_int" | "env_float" | "env_bool" | "serve" | "log"
             | "db" | "assert" | "asset" | "json" | "html" | "svg" | "request" | "response"
-            | "http" | "time" | "crypto" => Ok(Value::Builtin(name.to_string())),
+            | "http" | "time" | "crypto" | "decimal" | "await_all" | "await_any" | "channel" => {
+                Ok(Value::Builtin(name.to_string()))
+            }
             _ if html_tags::is_html_tag(name) => Ok(Value::Builtin(name.to_string())),
             _ => Err(ExecError::Runtime(format!("unknown identifier {name}"))),
         }
@@ -1988,6 +2983,29 @@ impl Interpreter {
         self.decode_json_value(&json, &ty, "$")
     }
 
+    /// Splits the arguments of a lowered `db.query<T>` / `db.one<T>` call:
+    /// the result type name, the SQL, and optional params.
+    fn db_typed_args(
+        builtin_name: &str,
+        args: &[Value],
+    ) -> Result<(String, String, Vec<Value>), String> {
+        if args.len() < 2 || args.len() > 3 {
+            return Err(format!("{builtin_name} expects 2 or 3 arguments"));
+        }
+        let Some(Value::String(type_name)) = args.first() else {
+            return Err(format!("{builtin_name} expects a type name string"));
+        };
+        let Some(Value::String(sql)) = args.get(1) else {
+            return Err(format!("{builtin_name} expects a SQL string"));
+        };
+        let params = match args.get(2) {
+            Some(Value::List(items)) => items.clone(),
+            Some(_) => return Err(format!("{builtin_name} params must be a list")),
+            None => Vec::new(),
+        };
+        Ok((type_name.clone(), sql.clone(), params))
+    }
+
     fn eval_typed_env_builtin(
         &self,
         builtin_name: &str,
@@ -2209,6 +3227,36 @@ impl Interpreter {
                     ))),
                 }
             }
+            name if crate::runtime_capabilities::is_time_value_builtin(name) => {
+                match crate::runtime_capabilities::time_value_call(name, &args)
+                    .map_err(ExecError::Runtime)?
+                {
+                    Ok(value) => Ok(value),
+                    Err(message) => Ok(Value::ResultErr(Box::new(
+                        self.default_error_value(message),
+                    ))),
+                }
+            }
+            name if crate::runtime_ranges::is_range_builtin(name) => {
+                crate::runtime_ranges::range_call(name, &args).map_err(ExecError::Runtime)
+            }
+            name if crate::runtime_channels::is_channel_builtin(name) => {
+                crate::runtime_channels::channel_call(name, &args).map_err(ExecError::Runtime)
+            }
+            name if crate::runtime_tasks::is_task_builtin(name) => Task::from_task_result(
+                crate::runtime_tasks::task_call(name, &args).map_err(ExecError::Runtime)?,
+            )
+            .result(),
+            name if crate::runtime_decimal::is_decimal_builtin(name) => {
+                match crate::runtime_decimal::decimal_call(name, &args)
+                    .map_err(ExecError::Runtime)?
+                {
+                    Ok(value) => Ok(value),
+                    Err(message) => Ok(Value::ResultErr(Box::new(
+                        self.default_error_value(message),
+                    ))),
+                }
+            }
             "crypto.hash" => {
                 if args.len() != 2 {
                     return Err(ExecError::Runtime(
@@ -2472,6 +3520,33 @@ impl Interpreter {
                 db.exec_params(&sql, &params).map_err(ExecError::Runtime)?;
                 Ok(Value::Unit)
             }
+            "db.execute" => {
+                if args.is_empty() || args.len() > 2 {
+                    return Err(ExecError::Runtime(
+                        "db.execute expects 1 or 2 arguments".to_string(),
+                    ));
+                }
+                let sql = match args.first() {
+                    Some(Value::String(s)) => s.clone(),
+                    _ => {
+                        return Err(ExecError::Runtime(
+                            "db.execute expects a SQL string".to_string(),
+                        ));
+                    }
+                };
+                let params = match args.get(1) {
+                    Some(Value::List(items)) => items.clone(),
+                    Some(_) => {
+                        return Err(ExecError::Runtime(
+                            "db.execute params must be a list".to_string(),
+                        ));
+                    }
+                    None => Vec::new(),
+                };
+                let db = self.db_mut()?;
+                let result = db.exec_result(&sql, &params).map_err(ExecError::Runtime)?;
+                Ok(crate::db::write_result_value(result))
+            }
             "db.tx_begin" => {
                 if !args.is_empty() {
                     return Err(ExecError::Runtime(
@@ -2495,6 +3570,16 @@ impl Interpreter {
                 }
                 Ok(Value::Unit)
             }
+            "db.rollback" => {
+                if !args.is_empty() {
+                    return Err(ExecError::Runtime(
+                        "db.rollback expects no arguments".to_string(),
+                    ));
+                }
+                let db = self.db_mut()?;
+                db.mark_rollback().map_err(ExecError::Runtime)?;
+                Ok(Value::Unit)
+            }
             "db.tx_rollback" => {
                 if !args.is_empty() {
                     return Err(ExecError::Runtime(
@@ -2515,84 +3600,342 @@ impl Interpreter {
                     Some(Value::String(s)) => s.clone(),
                     _ => {
                         return Err(ExecError::Runtime(
-                            "db.query expects a SQL string".to_string(),
+                            "db.query expects a SQL string".to_string(),
+                        ));
+                    }
+                };
+                let params = if args.len() > 1 {
+                    match args.get(1) {
+                        Some(Value::List(items)) => items.clone(),
+                        _ => {
+                            return Err(ExecError::Runtime(
+                                "db.query params must be a list".to_string(),
+                            ));
+                        }
+                    }
+                } else {
+                    Vec::new()
+                };
+                let db = self.db_mut()?;
+                let rows = db.query_params(&sql, &params).map_err(ExecError::Runtime)?;
+                let list = rows.into_iter().map(Value::Map).collect();
+                Ok(Value::List(list))
+            }
+            "db.one" => {
+                if args.len() > 2 {
+                    return Err(ExecError::Runtime(
+                        "db.one expects 1 or 2 arguments".to_string(),
+                    ));
+                }
+                let sql = match args.get(0) {
+                    Some(Value::String(s)) => s.clone(),
+                    _ => {
+                        return Err(ExecError::Runtime(
+                            "db.one expects a SQL string".to_string(),
+                        ));
+                    }
+                };
+                let params = if args.len() > 1 {
+                    match args.get(1) {
+                        Some(Value::List(items)) => items.clone(),
+                        _ => {
+                            return Err(ExecError::Runtime(
+                                "db.one params must be a list".to_string(),
+                            ));
+                        }
+                    }
+                } else {
+                    Vec::new()
+                };
+                let db = self.db_mut()?;
+                let rows = db.query_params(&sql, &params).map_err(ExecError::Runtime)?;
+                if let Some(row) = rows.into_iter().next() {
+                    Ok(Value::Map(row))
+                } else {
+                    Ok(Value::Null)
+                }
+            }
+            "db.query_typed" => {
+                let (type_name, sql, params) =
+                    Self::db_typed_args("db.query_typed", &args).map_err(ExecError::Runtime)?;
+                let db = self.db_mut()?;
+                let rows = db.query_params(&sql, &params).map_err(ExecError::Runtime)?;
+                let mut list = Vec::with_capacity(rows.len());
+                for row in rows {
+                    list.push(self.decode_query_row_typed(row, &type_name)?);
+                }
+                Ok(Value::List(list))
+            }
+            "db.one_typed" => {
+                let (type_name, sql, params) =
+                    Self::db_typed_args("db.one_typed", &args).map_err(ExecError::Runtime)?;
+                let db = self.db_mut()?;
+                let rows = db.query_params(&sql, &params).map_err(ExecError::Runtime)?;
+                if let Some(row) = rows.into_iter().next() {
+                    self.decode_query_row_typed(row, &type_name)
+                } else {
+                    Ok(Value::Null)
+                }
+            }
+            "db.from" => {
+                let table = match args.get(0) {
+                    Some(Value::String(s)) => s.clone(),
+                    _ => {
+                        return Err(ExecError::Runtime(
+                            "db.from expects a table name string".to_string(),
+                        ));
+                    }
+                };
+                let query = Query::new(table).map_err(ExecError::Runtime)?;
+                Ok(Value::Query(Box::new(query)))
+            }
+            "query.select" => {
+                if args.len() != 2 {
+                    return Err(ExecError::Runtime(
+                        "query.select expects 2 arguments".to_string(),
+                    ));
+                }
+                let query = match args.get(0) {
+                    Some(Value::Query(query)) => query.clone(),
+                    _ => {
+                        return Err(ExecError::Runtime(
+                            "query.select expects a Query".to_string(),
+                        ));
+                    }
+                };
+                let columns = match args.get(1) {
+                    Some(Value::List(items)) => {
+                        let mut out = Vec::with_capacity(items.len());
+                        for item in items {
+                            match item {
+                                Value::String(text) => out.push(text.clone()),
+                                _ => {
+                                    return Err(ExecError::Runtime(
+                                        "query.select expects a list of strings".to_string(),
+                                    ));
+                                }
+                            }
+                        }
+                        out
+                    }
+                    _ => {
+                        return Err(ExecError::Runtime(
+                            "query.select expects a list of strings".to_string(),
+                        ));
+                    }
+                };
+                let next = query.select(columns).map_err(ExecError::Runtime)?;
+                Ok(Value::Query(Box::new(next)))
+            }
+            "query.where" => {
+                if args.len() != 4 {
+                    return Err(ExecError::Runtime(
+                        "query.where expects 4 arguments".to_string(),
+                    ));
+                }
+                let query = match args.get(0) {
+                    Some(Value::Query(query)) => query.clone(),
+                    _ => {
+                        return Err(ExecError::Runtime(
+                            "query.where expects a Query".to_string(),
+                        ));
+                    }
+                };
+                let column = match args.get(1) {
+                    Some(Value::String(s)) => s.clone(),
+                    _ => {
+                        return Err(ExecError::Runtime(
+                            "query.where expects a column string".to_string(),
+                        ));
+                    }
+                };
+                let op = match args.get(2) {
+                    Some(Value::String(s)) => s.clone(),
+                    _ => {
+                        return Err(ExecError::Runtime(
+                            "query.where expects an operator string".to_string(),
+                        ));
+                    }
+                };
+                let value = args
+                    .get(3)
+                    .cloned()
+                    .ok_or_else(|| ExecError::Runtime("query.where expects a value".to_string()))?;
+                let next = query
+                    .where_clause(column, op, value)
+                    .map_err(ExecError::Runtime)?;
+                Ok(Value::Query(Box::new(next)))
+            }
+            "query.order_by" => {
+                if args.len() != 3 {
+                    return Err(ExecError::Runtime(
+                        "query.order_by expects 3 arguments".to_string(),
+                    ));
+                }
+                let query = match args.get(0) {
+                    Some(Value::Query(query)) => query.clone(),
+                    _ => {
+                        return Err(ExecError::Runtime(
+                            "query.order_by expects a Query".to_string(),
+                        ));
+                    }
+                };
+                let column = match args.get(1) {
+                    Some(Value::String(s)) => s.clone(),
+                    _ => {
+                        return Err(ExecError::Runtime(
+                            "query.order_by expects a column string".to_string(),
+                        ));
+                    }
+                };
+                let dir = match args.get(2) {
+                    Some(Value::String(s)) => s.clone(),
+                    _ => {
+                        return Err(ExecError::Runtime(
+                            "query.order_by expects a direction string".to_string(),
+                        ));
+                    }
+                };
+                let next = query.order_by(column, dir).map_err(ExecError::Runtime)?;
+                Ok(Value::Query(Box::new(next)))
+            }
+            "query.limit" => {
+                if args.len() != 2 {
+                    return Err(ExecError::Runtime(
+                        "query.limit expects 2 arguments".to_string(),
+                    ));
+                }
+                let query = match args.get(0) {
+                    Some(Value::Query(query)) => query.clone(),
+                    _ => {
+                        return Err(ExecError::Runtime(
+                            "query.limit expects a Query".to_string(),
+                        ));
+                    }
+                };
+                let limit = match args.get(1) {
+                    Some(Value::Int(v)) => *v,
+                    Some(Value::Float(v)) => *v as i64,
+                    _ => return Err(ExecError::Runtime("query.limit expects an Int".to_string())),
+                };
+                let next = query.limit(limit).map_err(ExecError::Runtime)?;
+                Ok(Value::Query(Box::new(next)))
+            }
+            "query.or_where" => {
+                if args.len() != 4 {
+                    return Err(ExecError::Runtime(
+                        "query.or_where expects 4 arguments".to_string(),
+                    ));
+                }
+                let query = match args.first() {
+                    Some(Value::Query(query)) => query.clone(),
+                    _ => {
+                        return Err(ExecError::Runtime(
+                            "query.or_where expects a Query".to_string(),
+                        ));
+                    }
+                };
+                let column = match args.get(1) {
+                    Some(Value::String(s)) => s.clone(),
+                    _ => {
+                        return Err(ExecError::Runtime(
+                            "query.or_where expects a column string".to_string(),
+                        ));
+                    }
+                };
+                let op = match args.get(2) {
+                    Some(Value::String(s)) => s.clone(),
+                    _ => {
+                        return Err(ExecError::Runtime(
+                            "query.or_where expects an operator string".to_string(),
                         ));
                     }
                 };
-                let params = if args.len() > 1 {
-                    match args.get(1) {
-                        Some(Value::List(items)) => items.clone(),
-                        _ => {
-                            return Err(ExecError::Runtime(
-                                "db.query params must be a list".to_string(),
-                            ));
-                        }
-                    }
-                } else {
-                    Vec::new()
-                };
-                let db = self.db_mut()?;
-                let rows = db.query_params(&sql, &params).map_err(ExecError::Runtime)?;
-                let list = rows.into_iter().map(Value::Map).collect();
-                Ok(Value::List(list))
+                let value = args.get(3).cloned().ok_or_else(|| {
+                    ExecError::Runtime("query.or_where expects a value".to_string())
+                })?;
+                let next = query
+                    .or_where(column, op, value)
+                    .map_err(ExecError::Runtime)?;
+                Ok(Value::Query(Box::new(next)))
             }
-            "db.one" => {
-                if args.len() > 2 {
-                    return Err(ExecError::Runtime(
-                        "db.one expects 1 or 2 arguments".to_string(),
-                    ));
+            "query.join" | "query.left_join" => {
+                if args.len() != 4 {
+                    return Err(ExecError::Runtime(format!("{name} expects 4 arguments")));
                 }
-                let sql = match args.get(0) {
+                let query = match args.first() {
+                    Some(Value::Query(query)) => query.clone(),
+                    _ => return Err(ExecError::Runtime(format!("{name} expects a Query"))),
+                };
+                let table = match args.get(1) {
                     Some(Value::String(s)) => s.clone(),
                     _ => {
-                        return Err(ExecError::Runtime(
-                            "db.one expects a SQL string".to_string(),
-                        ));
+                        return Err(ExecError::Runtime(format!(
+                            "{name} expects a table name string"
+                        )));
                     }
                 };
-                let params = if args.len() > 1 {
-                    match args.get(1) {
-                        Some(Value::List(items)) => items.clone(),
-                        _ => {
-                            return Err(ExecError::Runtime(
-                                "db.one params must be a list".to_string(),
-                            ));
-                        }
+                let left = match args.get(2) {
+                    Some(Value::String(s)) => s.clone(),
+                    _ => {
+                        return Err(ExecError::Runtime(format!(
+                            "{name} expects a left column string"
+                        )));
                     }
-                } else {
-                    Vec::new()
                 };
-                let db = self.db_mut()?;
-                let rows = db.query_params(&sql, &params).map_err(ExecError::Runtime)?;
-                if let Some(row) = rows.into_iter().next() {
-                    Ok(Value::Map(row))
+                let right = match args.get(3) {
+                    Some(Value::String(s)) => s.clone(),
+                    _ => {
+                        return Err(ExecError::Runtime(format!(
+                            "{name} expects a right column string"
+                        )));
+                    }
+                };
+                let next = if name == "query.left_join" {
+                    query.left_join(table, left, right)
                 } else {
-                    Ok(Value::Null)
+                    query.join(table, left, right)
                 }
+                .map_err(ExecError::Runtime)?;
+                Ok(Value::Query(Box::new(next)))
             }
-            "db.from" => {
-                let table = match args.get(0) {
-                    Some(Value::String(s)) => s.clone(),
+            "query.offset" => {
+                if args.len() != 2 {
+                    return Err(ExecError::Runtime(
+                        "query.offset expects 2 arguments".to_string(),
+                    ));
+                }
+                let query = match args.first() {
+                    Some(Value::Query(query)) => query.clone(),
                     _ => {
                         return Err(ExecError::Runtime(
-                            "db.from expects a table name string".to_string(),
+                            "query.offset expects a Query".to_string(),
                         ));
                     }
                 };
-                let query = Query::new(table).map_err(ExecError::Runtime)?;
-                Ok(Value::Query(query))
+                let offset = match args.get(1) {
+                    Some(Value::Int(v)) => *v,
+                    Some(Value::Float(v)) => *v as i64,
+                    _ => {
+                        return Err(ExecError::Runtime(
+                            "query.offset expects an Int".to_string(),
+                        ));
+                    }
+                };
+                let next = query.offset(offset).map_err(ExecError::Runtime)?;
+                Ok(Value::Query(Box::new(next)))
             }
-            "query.select" => {
+            "query.group_by" => {
                 if args.len() != 2 {
                     return Err(ExecError::Runtime(
-                        "query.select expects 2 arguments".to_string(),
+                        "query.group_by expects 2 arguments".to_string(),
                     ));
                 }
-                let query = match args.get(0) {
+                let query = match args.first() {
                     Some(Value::Query(query)) => query.clone(),
                     _ => {
                         return Err(ExecError::Runtime(
-                            "query.select expects a Query".to_string(),
+                            "query.group_by expects a Query".to_string(),
                         ));
                     }
                 };
@@ -2604,7 +3947,7 @@ impl Interpreter {
                                 Value::String(text) => out.push(text.clone()),
                                 _ => {
                                     return Err(ExecError::Runtime(
-                                        "query.select expects a list of strings".to_string(),
+                                        "query.group_by expects a list of strings".to_string(),
                                     ));
                                 }
                             }
@@ -2613,24 +3956,24 @@ impl Interpreter {
                     }
                     _ => {
                         return Err(ExecError::Runtime(
-                            "query.select expects a list of strings".to_string(),
+                            "query.group_by expects a list of strings".to_string(),
                         ));
                     }
                 };
-                let next = query.select(columns).map_err(ExecError::Runtime)?;
-                Ok(Value::Query(next))
+                let next = query.group_by(columns).map_err(ExecError::Runtime)?;
+                Ok(Value::Query(Box::new(next)))
             }
-            "query.where" => {
+            "query.having" => {
                 if args.len() != 4 {
                     return Err(ExecError::Runtime(
-                        "query.where expects 4 arguments".to_string(),
+                        "query.having expects 4 arguments".to_string(),
                     ));
                 }
-                let query = match args.get(0) {
+                let query = match args.first() {
                     Some(Value::Query(query)) => query.clone(),
                     _ => {
                         return Err(ExecError::Runtime(
-                            "query.where expects a Query".to_string(),
+                            "query.having expects a Query".to_string(),
                         ));
                     }
                 };
@@ -2638,7 +3981,7 @@ impl Interpreter {
                     Some(Value::String(s)) => s.clone(),
                     _ => {
                         return Err(ExecError::Runtime(
-                            "query.where expects a column string".to_string(),
+                            "query.having expects a column string".to_string(),
                         ));
                     }
                 };
@@ -2646,73 +3989,60 @@ impl Interpreter {
                     Some(Value::String(s)) => s.clone(),
                     _ => {
                         return Err(ExecError::Runtime(
-                            "query.where expects an operator string".to_string(),
+                            "query.having expects an operator string".to_string(),
                         ));
                     }
                 };
-                let value = args
-                    .get(3)
-                    .cloned()
-                    .ok_or_else(|| ExecError::Runtime("query.where expects a value".to_string()))?;
+                let value = args.get(3).cloned().ok_or_else(|| {
+                    ExecError::Runtime("query.having expects a value".to_string())
+                })?;
                 let next = query
-                    .where_clause(column, op, value)
+                    .having(column, op, value)
                     .map_err(ExecError::Runtime)?;
-                Ok(Value::Query(next))
+                Ok(Value::Query(Box::new(next)))
             }
-            "query.order_by" => {
-                if args.len() != 3 {
+            "query.aggregate" => {
+                if args.len() != 4 {
                     return Err(ExecError::Runtime(
-                        "query.order_by expects 3 arguments".to_string(),
+                        "query.aggregate expects 4 arguments".to_string(),
                     ));
                 }
-                let query = match args.get(0) {
+                let query = match args.first() {
                     Some(Value::Query(query)) => query.clone(),
                     _ => {
                         return Err(ExecError::Runtime(
-                            "query.order_by expects a Query".to_string(),
+                            "query.aggregate expects a Query".to_string(),
                         ));
                     }
                 };
-                let column = match args.get(1) {
+                let func = match args.get(1) {
                     Some(Value::String(s)) => s.clone(),
                     _ => {
                         return Err(ExecError::Runtime(
-                            "query.order_by expects a column string".to_string(),
+                            "query.aggregate expects a function string".to_string(),
                         ));
                     }
                 };
-                let dir = match args.get(2) {
+                let column = match args.get(2) {
                     Some(Value::String(s)) => s.clone(),
                     _ => {
                         return Err(ExecError::Runtime(
-                            "query.order_by expects a direction string".to_string(),
+                            "query.aggregate expects a column string".to_string(),
                         ));
                     }
                 };
-                let next = query.order_by(column, dir).map_err(ExecError::Runtime)?;
-                Ok(Value::Query(next))
-            }
-            "query.limit" => {
-                if args.len() != 2 {
-                    return Err(ExecError::Runtime(
-                        "query.limit expects 2 arguments".to_string(),
-                    ));
-                }
-                let query = match args.get(0) {
-                    Some(Value::Query(query)) => query.clone(),
+                let alias = match args.get(3) {
+                    Some(Value::String(s)) => s.clone(),
                     _ => {
                         return Err(ExecError::Runtime(
-                            "query.limit expects a Query".to_string(),
+                            "query.aggregate expects an alias string".to_string(),
                         ));
                     }
                 };
-                let limit = match args.get(1) {
-                    Some(Value::Int(v)) => *v,
-                    Some(Value::Float(v)) => *v as i64,
-                    _ => return Err(ExecError::Runtime("query.limit expects an Int".to_string())),
-                };
-                let next = query.limit(limit).map_err(ExecError::Runtime)?;
-                Ok(Value::Query(next))
+                let next = query
+                    .aggregate(func, column, alias)
+                    .map_err(ExecError::Runtime)?;
+                Ok(Value::Query(Box::new(next)))
             }
             "query.insert" => {
                 if args.len() != 2 {
@@ -2732,7 +4062,57 @@ impl Interpreter {
                     ExecError::Runtime("query.insert expects a struct".to_string())
                 })?;
                 let next = query.insert_struct(value).map_err(ExecError::Runtime)?;
-                Ok(Value::Query(next))
+                Ok(Value::Query(Box::new(next)))
+            }
+            "query.insert_many" => {
+                if args.len() != 2 {
+                    return Err(ExecError::Runtime(
+                        "query.insert_many expects 2 arguments".to_string(),
+                    ));
+                }
+                let Some(Value::Query(query)) = args.first() else {
+                    return Err(ExecError::Runtime(
+                        "query.insert_many expects a Query".to_string(),
+                    ));
+                };
+                let Some(Value::List(rows)) = args.get(1).map(Value::unboxed) else {
+                    return Err(ExecError::Runtime(
+                        "query.insert_many expects a list of structs".to_string(),
+                    ));
+                };
+                let next = query.insert_many(rows).map_err(ExecError::Runtime)?;
+                Ok(Value::Query(Box::new(next)))
+            }
+            "query.on_conflict" | "query.do_update" | "query.do_nothing" => {
+                let Some(Value::Query(query)) = args.first() else {
+                    return Err(ExecError::Runtime(format!("{name} expects a Query")));
+                };
+                let next = match (name, &args[1..]) {
+                    ("query.on_conflict", [target]) => query
+                        .on_conflict(query_column_list(name, target).map_err(ExecError::Runtime)?),
+                    ("query.on_conflict", _) => {
+                        return Err(ExecError::Runtime(
+                            "query.on_conflict expects 2 arguments".to_string(),
+                        ));
+                    }
+                    ("query.do_update", []) => query.do_update(None),
+                    ("query.do_update", [columns]) => query.do_update(Some(
+                        query_column_list(name, columns).map_err(ExecError::Runtime)?,
+                    )),
+                    ("query.do_update", _) => {
+                        return Err(ExecError::Runtime(
+                            "query.do_update expects 1 or 2 arguments".to_string(),
+                        ));
+                    }
+                    (_, []) => query.do_nothing(),
+                    _ => {
+                        return Err(ExecError::Runtime(
+                            "query.do_nothing expects 1 argument".to_string(),
+                        ));
+                    }
+                }
+                .map_err(ExecError::Runtime)?;
+                Ok(Value::Query(Box::new(next)))
             }
             "query.upsert" => {
                 if args.len() != 2 {
@@ -2752,7 +4132,7 @@ impl Interpreter {
                     ExecError::Runtime("query.upsert expects a struct".to_string())
                 })?;
                 let next = query.upsert_struct(value).map_err(ExecError::Runtime)?;
-                Ok(Value::Query(next))
+                Ok(Value::Query(Box::new(next)))
             }
             "query.update" => {
                 if args.len() != 3 {
@@ -2782,7 +4162,7 @@ impl Interpreter {
                 let next = query
                     .update_set(column, value)
                     .map_err(ExecError::Runtime)?;
-                Ok(Value::Query(next))
+                Ok(Value::Query(Box::new(next)))
             }
             "query.delete" => {
                 if args.len() != 1 {
@@ -2799,7 +4179,7 @@ impl Interpreter {
                     }
                 };
                 let next = query.delete_rows().map_err(ExecError::Runtime)?;
-                Ok(Value::Query(next))
+                Ok(Value::Query(Box::new(next)))
             }
             "query.count" => {
                 if args.len() != 1 {
@@ -2839,9 +4219,13 @@ impl Interpreter {
                     Some(Value::Query(query)) => query.clone(),
                     _ => return Err(ExecError::Runtime("query.one expects a Query".to_string())),
                 };
-                let (sql, params) = query.build_sql(Some(1)).map_err(ExecError::Runtime)?;
+                let statements = query
+                    .build_statements(Some(1))
+                    .map_err(ExecError::Runtime)?;
                 let db = self.db_mut()?;
-                let rows = db.query_params(&sql, &params).map_err(ExecError::Runtime)?;
+                let rows = db
+                    .query_statements(&statements)
+                    .map_err(ExecError::Runtime)?;
                 if let Some(row) = rows.into_iter().next() {
                     Ok(Value::Map(row))
                 } else {
@@ -2870,9 +4254,13 @@ impl Interpreter {
                         ));
                     }
                 };
-                let (sql, params) = query.build_sql(Some(1)).map_err(ExecError::Runtime)?;
+                let statements = query
+                    .build_statements(Some(1))
+                    .map_err(ExecError::Runtime)?;
                 let db = self.db_mut()?;
-                let rows = db.query_params(&sql, &params).map_err(ExecError::Runtime)?;
+                let rows = db
+                    .query_statements(&statements)
+                    .map_err(ExecError::Runtime)?;
                 if let Some(row) = rows.into_iter().next() {
                     self.decode_query_row_typed(row, &type_name)
                 } else {
@@ -2889,9 +4277,11 @@ impl Interpreter {
                     Some(Value::Query(query)) => query.clone(),
                     _ => return Err(ExecError::Runtime("query.all expects a Query".to_string())),
                 };
-                let (sql, params) = query.build_sql(None).map_err(ExecError::Runtime)?;
+                let statements = query.build_statements(None).map_err(ExecError::Runtime)?;
                 let db = self.db_mut()?;
-                let rows = db.query_params(&sql, &params).map_err(ExecError::Runtime)?;
+                let rows = db
+                    .query_statements(&statements)
+                    .map_err(ExecError::Runtime)?;
                 let list = rows.into_iter().map(Value::Map).collect();
                 Ok(Value::List(list))
             }
@@ -2917,9 +4307,11 @@ impl Interpreter {
                         ));
                     }
                 };
-                let (sql, params) = query.build_sql(None).map_err(ExecError::Runtime)?;
+                let statements = query.build_statements(None).map_err(ExecError::Runtime)?;
                 let db = self.db_mut()?;
-                let rows = db.query_params(&sql, &params).map_err(ExecError::Runtime)?;
+                let rows = db
+                    .query_statements(&statements)
+                    .map_err(ExecError::Runtime)?;
                 let mut list = Vec::with_capacity(rows.len());
                 for row in rows {
                     list.push(self.decode_query_row_typed(row, &type_name)?);
@@ -2936,11 +4328,71 @@ impl Interpreter {
                     Some(Value::Query(query)) => query.clone(),
                     _ => return Err(ExecError::Runtime("query.exec expects a Query".to_string())),
                 };
-                let (sql, params) = query.build_sql(None).map_err(ExecError::Runtime)?;
+                let statements = query.build_statements(None).map_err(ExecError::Runtime)?;
                 let db = self.db_mut()?;
-                db.exec_params(&sql, &params).map_err(ExecError::Runtime)?;
+                db.exec_statements(&statements)
+                    .map_err(ExecError::Runtime)?;
                 Ok(Value::Unit)
             }
+            "query.execute" => {
+                if args.len() != 1 {
+                    return Err(ExecError::Runtime(
+                        "query.execute expects 1 argument".to_string(),
+                    ));
+                }
+                let query = match args.first() {
+                    Some(Value::Query(query)) => query.clone(),
+                    _ => {
+                        return Err(ExecError::Runtime(
+                            "query.execute expects a Query".to_string(),
+                        ));
+                    }
+                };
+                let statements = query.build_statements(None).map_err(ExecError::Runtime)?;
+                let db = self.db_mut()?;
+                let result = db
+                    .exec_statements(&statements)
+                    .map_err(ExecError::Runtime)?;
+                Ok(crate::db::write_result_value(result))
+            }
+            "query.returning" => {
+                if args.len() != 2 {
+                    return Err(ExecError::Runtime(
+                        "query.returning expects 2 arguments".to_string(),
+                    ));
+                }
+                let query = match args.first() {
+                    Some(Value::Query(query)) => query.clone(),
+                    _ => {
+                        return Err(ExecError::Runtime(
+                            "query.returning expects a Query".to_string(),
+                        ));
+                    }
+                };
+                let columns = match args.get(1) {
+                    Some(Value::List(items)) => {
+                        let mut out = Vec::with_capacity(items.len());
+                        for item in items {
+                            match item {
+                                Value::String(text) => out.push(text.clone()),
+                                _ => {
+                                    return Err(ExecError::Runtime(
+                                        "query.returning expects a list of strings".to_string(),
+                                    ));
+                                }
+                            }
+                        }
+                        out
+                    }
+                    _ => {
+                        return Err(ExecError::Runtime(
+                            "query.returning expects a list of strings".to_string(),
+                        ));
+                    }
+                };
+                let next = query.returning(columns).map_err(ExecError::Runtime)?;
+                Ok(Value::Query(Box::new(next)))
+            }
             "query.sql" => {
                 if args.len() != 1 {
                     return Err(ExecError::Runtime(
@@ -3455,7 +4907,7 @@ impl Interpreter {
                         Some(&response_meta),
                     ))
                 } else {
-                    let json = self.value_to_json(&ok);
+                    let json = crate::runtime_types::encode_json_value(self, &ok, &route.ret_type);
                     Ok(self.http_response_with_meta(
                         200,
                         rt_json::encode(&json),
@@ -3474,7 +4926,8 @@ impl Interpreter {
                         Some(&response_meta),
                     ))
                 } else {
-                    let json = self.value_to_json(&other);
+                    let json =
+                        crate::runtime_types::encode_json_value(self, &other, &route.ret_type);
                     Ok(self.http_response_with_meta(
                         200,
                         rt_json::encode(&json),
@@ -3487,6 +4940,7 @@ impl Interpreter {
     }
 
     fn begin_http_route_context(&mut self, request: &HttpRequest) {
+        observability::set_current_request_id(Some(request.request_id.clone()));
         self.current_http_request = Some(HttpRequestContext {
             headers: request.headers.clone(),
             cookies: parse_cookie_map(request.headers.get("cookie").map(String::as_str)),
@@ -3498,6 +4952,7 @@ impl Interpreter {
     }
 
     fn end_http_route_context(&mut self) -> HttpResponseMeta {
+        observability::set_current_request_id(None);
         self.current_http_request = None;
         self.current_http_response.take().unwrap_or_default()
     }
@@ -4003,7 +5458,9 @@ impl Interpreter {
     fn eval_member(&mut self, base: Value, field: &str) -> ExecResult<Value> {
         match base.unboxed() {
             Value::Builtin(name) if name == "db" => match field {
-                "exec" | "query" | "one" | "from" => Ok(Value::Builtin(format!("db.{field}"))),
+                "exec" | "execute" | "query" | "one" | "from" | "rollback" => {
+                    Ok(Value::Builtin(format!("db.{field}")))
+                }
                 _ => Err(ExecError::Runtime(format!("unknown db method {field}"))),
             },
             Value::Builtin(name) if name == "json" => match field {
@@ -4038,6 +5495,9 @@ impl Interpreter {
             },
             Value::Builtin(name) if name == "time" => match field {
                 "now" | "sleep" | "format" | "parse" => Ok(Value::Builtin(format!("time.{field}"))),
+                _ if crate::runtime_capabilities::TIME_VALUE_BUILTINS.contains(&field) => {
+                    Ok(Value::Builtin(format!("time.{field}")))
+                }
                 _ => Err(ExecError::Runtime(format!("unknown time method {field}"))),
             },
             Value::Builtin(name) if name == "crypto" => match field {
@@ -4046,6 +5506,15 @@ impl Interpreter {
                 }
                 _ => Err(ExecError::Runtime(format!("unknown crypto method {field}"))),
             },
+            Value::Builtin(name) if name == "decimal" => {
+                if crate::runtime_decimal::DECIMAL_BUILTINS.contains(&field) {
+                    Ok(Value::Builtin(format!("decimal.{field}")))
+                } else {
+                    Err(ExecError::Runtime(format!(
+                        "unknown decimal method {field}"
+                    )))
+                }
+            }
             Value::Config(name) => {
                 let map = self
                     .configs
@@ -4074,6 +5543,12 @@ impl Interpreter {
                     .cloned()
                     .ok_or_else(|| ExecError::Runtime("index out of bounds".to_string()))
             }
+            Value::Range(range) => {
+                let idx = Self::list_index(&index)?;
+                range
+                    .get(idx)
+                    .ok_or_else(|| ExecError::Runtime("index out of bounds".to_string()))
+            }
             Value::Map(items) => {
                 let key = Self::map_key(&index)?;
                 Ok(items.get(&key).cloned().unwrap_or(Value::Null))
@@ -4446,6 +5921,9 @@ impl Interpreter {
     fn eval_add(&self, left: Value, right: Value) -> ExecResult<Value> {
         let left = left.unboxed();
         let right = right.unboxed();
+        if let Some(result) = decimal_binary(&BinaryOp::Add, &left, &right) {
+            return result.map_err(ExecError::Runtime);
+        }
         match (left, right) {
             (Value::List(mut a), Value::List(mut b)) => {
                 a.append(&mut b);
@@ -4465,6 +5943,9 @@ impl Interpreter {
     fn eval_arith(&self, op: &BinaryOp, left: Value, right: Value) -> ExecResult<Value> {
         let left = left.unboxed();
         let right = right.unboxed();
+        if let Some(result) = decimal_binary(op, &left, &right) {
+            return result.map_err(ExecError::Runtime);
+        }
         match (left, right) {
             (Value::Int(a), Value::Int(b)) => match op {
                 BinaryOp::Sub => Ok(Value::Int(a - b)),
@@ -4506,44 +5987,16 @@ impl Interpreter {
         }
     }
 
-    fn eval_range(&self, left: Value, right: Value) -> ExecResult<Value> {
-        match (left.unboxed(), right.unboxed()) {
-            (Value::Int(start), Value::Int(end)) => {
-                if start > end {
-                    return Err(ExecError::Runtime("range start must be <= end".to_string()));
-                }
-                let items = (start..=end).map(Value::Int).collect();
-                Ok(Value::List(items))
-            }
-            (Value::Float(start), Value::Float(end)) => self.eval_float_range(start, end),
-            (Value::Int(start), Value::Float(end)) => self.eval_float_range(start as f64, end),
-            (Value::Float(start), Value::Int(end)) => self.eval_float_range(start, end as f64),
-            _ => Err(ExecError::Runtime(
-                "range expects numeric bounds".to_string(),
-            )),
-        }
-    }
-
-    fn eval_float_range(&self, start: f64, end: f64) -> ExecResult<Value> {
-        if !start.is_finite() || !end.is_finite() {
-            return Err(ExecError::Runtime("invalid range bounds".to_string()));
-        }
-        if start > end {
-            return Err(ExecError::Runtime("range start must be <= end".to_string()));
-        }
-        let mut items = Vec::new();
-        let mut current = start;
-        let epsilon = 1e-9;
-        while current <= end + epsilon {
-            items.push(Value::Float(current));
-            current += 1.0;
-        }
-        Ok(Value::List(items))
+    fn eval_range(&self, name: &str, left: Value, right: Value) -> ExecResult<Value> {
+        crate::runtime_ranges::range_call(name, &[left, right]).map_err(ExecError::Runtime)
     }
 
     fn eval_compare(&self, op: &BinaryOp, left: Value, right: Value) -> ExecResult<Value> {
         let left = left.unboxed();
         let right = right.unboxed();
+        if let Some(result) = decimal_binary(op, &left, &right) {
+            return result.map_err(ExecError::Runtime);
+        }
         let result = match (left, right) {
             (Value::Int(a), Value::Int(b)) => match op {
                 BinaryOp::Eq => a == b,
@@ -4627,6 +6080,42 @@ impl Interpreter {
             PatternKind::Struct { name, fields } => {
                 self.match_struct_pattern(&value, &name.name, fields, bindings)
             }
+            PatternKind::Tuple(items) => match &value {
+                Value::List(values) if values.len() == items.len() => {
+                    for (item, value) in items.iter().zip(values) {
+                        if !self.match_pattern(value, item, bindings)? {
+                            return Ok(false);
+                        }
+                    }
+                    Ok(true)
+                }
+                _ => Ok(false),
+            },
+            PatternKind::Or(alts) => {
+                for alt in alts {
+                    let mut alt_bindings = HashMap::new();
+                    if self.match_pattern(&value, alt, &mut alt_bindings)? {
+                        bindings.extend(alt_bindings);
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+            PatternKind::Bind { name, pat } => {
+                if !self.match_pattern(&value, pat, bindings)? {
+                    return Ok(false);
+                }
+                bindings.insert(name.name.clone(), value.clone());
+                Ok(true)
+            }
+            PatternKind::Range { start, end } => Ok(match (&value, start, end) {
+                (Value::Int(v), Literal::Int(lo), Literal::Int(hi)) => lo <= v && v <= hi,
+                (Value::Float(v), Literal::Float(lo), Literal::Float(hi)) => lo <= v && v <= hi,
+                (Value::Float(v), Literal::Int(lo), Literal::Int(hi)) => {
+                    *lo as f64 <= *v && *v <= *hi as f64
+                }
+                _ => false,
+            }),
         }
     }
 
@@ -4953,9 +6442,31 @@ impl Interpreter {
         })?;
         for constraint in constraints {
             match constraint {
+                RefinementConstraint::Range { min, max, .. } if base == "Decimal" => {
+                    let violation = crate::runtime_decimal::check_decimal_range(value, min, max)
+                        .map_err(ExecError::Runtime)?;
+                    if let Some(message) = violation {
+                        return Err(ExecError::Error(self.validation_error_value(
+                            path,
+                            "invalid_value",
+                            message,
+                        )));
+                    }
+                }
                 RefinementConstraint::Range { min, max, .. } => {
                     self.check_refined_range(value, base, min, max, path)?;
                 }
+                RefinementConstraint::TextRange { min, max, .. } => {
+                    let violation = crate::runtime_types::check_time_range(value, base, &min, &max)
+                        .map_err(ExecError::Runtime)?;
+                    if let Some(message) = violation {
+                        return Err(ExecError::Error(self.validation_error_value(
+                            path,
+                            "invalid_value",
+                            message,
+                        )));
+                    }
+                }
                 RefinementConstraint::Regex { pattern, .. } => {
                     if !base_is_string_like(base) {
                         return Err(ExecError::Runtime(format!(
@@ -5443,6 +6954,7 @@ fn component_decl_to_fn(decl: &ComponentDecl) -> FnDecl {
         ret: Some(mk_simple("Html")),
         where_clause: decl.where_clause.clone(),
         body: decl.body.clone(),
+        is_pub: false,
         doc: decl.doc.clone(),
         span,
     }
@@ -6,8 +6,8 @@ use fuse_rt::json as rt_json;
 
 use crate::ast::{
     AppDecl, BinaryOp, Block, ComponentDecl, EnumDecl, Expr, ExprKind, FnDecl, Ident, Item,
-    Literal, Param, Pattern, PatternKind, Program, ServiceDecl, Stmt, StmtKind, TypeDecl, TypeRef,
-    TypeRefKind, UnaryOp,
+    Literal, MatchCase, Param, Pattern, PatternKind, Program, ServiceDecl, Stmt, StmtKind,
+    TypeDecl, TypeRef, TypeRefKind, UnaryOp,
 };
 use crate::callbind::{CallArgSpec, CallBindError, ParamBinding, ParamSpec, bind_call_args};
 use crate::frontend::html_shorthand::{CanonicalizationPhase, validate_named_args_for_phase};
@@ -28,16 +28,29 @@ fn is_query_method(name: &str) -> bool {
         name,
         "select"
             | "where"
+            | "or_where"
+            | "join"
+            | "left_join"
             | "order_by"
             | "limit"
+            | "offset"
+            | "group_by"
+            | "having"
+            | "aggregate"
             | "insert"
+            | "insert_many"
             | "upsert"
+            | "on_conflict"
+            | "do_update"
+            | "do_nothing"
             | "update"
             | "delete"
+            | "returning"
             | "count"
             | "one"
             | "all"
             | "exec"
+            | "execute"
             | "sql"
             | "params"
     )
@@ -84,6 +97,11 @@ fn rewrite_predicate_names_in_type_ref(
                 rewrite_predicate_names_in_type_ref(err, module_id, import_items, fn_decls);
             }
         }
+        TypeRefKind::Tuple(items) => {
+            for item in items {
+                rewrite_predicate_names_in_type_ref(item, module_id, import_items, fn_decls);
+            }
+        }
         TypeRefKind::Simple(_) => {}
     }
 }
@@ -193,7 +211,9 @@ fn lower_program_in_module(
 }
 
 pub fn lower_registry(registry: &ModuleRegistry) -> Result<IrProgram, Vec<String>> {
-    let monomorphized = crate::frontend::monomorphize::monomorphize_registry(registry);
+    let with_defaults = crate::frontend::interface_desugar::with_default_methods(registry);
+    let mut monomorphized = crate::frontend::monomorphize::monomorphize_registry(&with_defaults);
+    crate::frontend::canonicalize::inline_registry_consts(&mut monomorphized);
     let lowered_registry = crate::frontend::interface_desugar::desugar_registry(&monomorphized);
     let registry = &lowered_registry;
     let mut module_fn_decls = HashMap::new();
@@ -347,6 +367,10 @@ impl<'a> Lowerer<'a> {
             "http",
             "time",
             "crypto",
+            "decimal",
+            "await_all",
+            "await_any",
+            "channel",
         ]
         .into_iter()
         .map(|s| s.to_string())
@@ -814,6 +838,7 @@ fn component_decl_to_fn(decl: &ComponentDecl) -> FnDecl {
         ret: Some(mk_simple("Html")),
         where_clause: decl.where_clause.clone(),
         body: decl.body.clone(),
+        is_pub: false,
         doc: decl.doc.clone(),
         span,
     }
@@ -976,16 +1001,202 @@ impl FuncBuilder {
         captured.into_iter().collect()
     }
 
+    fn declare_pattern_bindings(&mut self, pat: &Pattern) -> Vec<(String, usize)> {
+        let mut bindings = Vec::new();
+        self.collect_bindings(pat, &mut bindings);
+        let mut binding_slots = Vec::new();
+        let mut seen = HashSet::new();
+        for ident in bindings {
+            if seen.insert(ident.name.clone()) {
+                let slot = self.declare(&ident);
+                binding_slots.push((ident.name.clone(), slot));
+            }
+        }
+        binding_slots
+    }
+
+    /// Matches the value in `slot` against `pat`. Tuple, struct, option and
+    /// nested enum patterns are split into element loads, null checks and flat
+    /// `MatchLocal` checks, the only pattern shapes the native backend handles;
+    /// every emitted check is pushed to `fails` for the caller to patch.
+    fn lower_pattern_match(
+        &mut self,
+        slot: usize,
+        pat: &Pattern,
+        binding_slots: &[(String, usize)],
+        fails: &mut Vec<usize>,
+    ) {
+        match &pat.kind {
+            PatternKind::Tuple(items) => {
+                for (idx, item) in items.iter().enumerate() {
+                    if matches!(item.kind, PatternKind::Wildcard) {
+                        continue;
+                    }
+                    let item_slot = self.declare_temp();
+                    self.emit(Instr::LoadLocal(slot));
+                    self.emit(Instr::Push(Const::Int(idx as i64)));
+                    self.emit(Instr::GetIndex);
+                    self.emit(Instr::StoreLocal(item_slot));
+                    self.lower_pattern_match(item_slot, item, binding_slots, fails);
+                }
+            }
+            PatternKind::Struct { fields, .. } => {
+                for field in fields {
+                    if matches!(field.pat.kind, PatternKind::Wildcard) {
+                        continue;
+                    }
+                    let field_slot = self.declare_temp();
+                    self.emit(Instr::LoadLocal(slot));
+                    self.emit(Instr::GetField {
+                        field: field.name.name.clone(),
+                    });
+                    self.emit(Instr::StoreLocal(field_slot));
+                    self.lower_pattern_match(field_slot, &field.pat, binding_slots, fails);
+                }
+            }
+            PatternKind::Ident(ident) if ident.name == "None" => {
+                let null = Pattern {
+                    kind: PatternKind::Literal(Literal::Null),
+                    span: pat.span,
+                };
+                fails.push(self.emit_match(slot, null, Vec::new()));
+            }
+            PatternKind::EnumVariant { name, args } if name.name == "Some" && args.len() == 1 => {
+                // Options are nullable values at runtime, so `Some(p)` is a
+                // null check followed by matching `p` against the value itself.
+                self.emit(Instr::LoadLocal(slot));
+                fails.push(self.emit_placeholder());
+                self.emit(Instr::JumpIfNull(0));
+                self.lower_pattern_match(slot, &args[0], binding_slots, fails);
+            }
+            PatternKind::EnumVariant { name, args }
+                if args.iter().any(|arg| !self.is_flat_variant_arg(arg)) =>
+            {
+                let mut flat_args = Vec::with_capacity(args.len());
+                let mut flat_bindings = self.pattern_binding_slots(pat, binding_slots);
+                let mut nested = Vec::new();
+                for arg in args {
+                    if self.is_flat_variant_arg(arg) {
+                        flat_args.push(arg.clone());
+                        continue;
+                    }
+                    let arg_slot = self.declare_temp();
+                    let arg_name = format!("__pat{arg_slot}");
+                    flat_args.push(Pattern {
+                        kind: PatternKind::Ident(Ident {
+                            name: arg_name.clone(),
+                            span: arg.span,
+                        }),
+                        span: arg.span,
+                    });
+                    flat_bindings.push((arg_name, arg_slot));
+                    nested.push((arg_slot, arg));
+                }
+                let flat = Pattern {
+                    kind: PatternKind::EnumVariant {
+                        name: name.clone(),
+                        args: flat_args,
+                    },
+                    span: pat.span,
+                };
+                fails.push(self.emit_match(slot, flat, flat_bindings));
+                for (arg_slot, arg) in nested {
+                    self.lower_pattern_match(arg_slot, arg, binding_slots, fails);
+                }
+            }
+            PatternKind::Or(alts) => {
+                // Each alternative falls through to the next one on failure;
+                // only the last alternative's checks fail the whole pattern.
+                let mut matched = Vec::new();
+                for (idx, alt) in alts.iter().enumerate() {
+                    let mut alt_fails = Vec::new();
+                    self.lower_pattern_match(slot, alt, binding_slots, &mut alt_fails);
+                    if idx + 1 == alts.len() {
+                        fails.extend(alt_fails);
+                        break;
+                    }
+                    matched.push(self.emit_placeholder());
+                    self.emit(Instr::Jump(0));
+                    let next_alt = self.code.len();
+                    for fail in alt_fails {
+                        self.patch_match_jump(fail, next_alt);
+                    }
+                }
+                let end = self.code.len();
+                for jump in matched {
+                    self.patch_jump(jump, end);
+                }
+            }
+            // Plain bindings copy the local so the binding keeps the subject's
+            // native type, which guards comparing it against numbers rely on.
+            PatternKind::Ident(ident) if self.is_binding_ident(ident) => {
+                self.copy_binding(slot, &ident.name, binding_slots);
+            }
+            PatternKind::Bind { name, pat: inner } => {
+                self.lower_pattern_match(slot, inner, binding_slots, fails);
+                self.copy_binding(slot, &name.name, binding_slots);
+            }
+            PatternKind::Range { start, end } => {
+                self.emit(Instr::LoadLocal(slot));
+                self.emit(Instr::Push(self.const_from_lit(start)));
+                self.emit(Instr::GtEq);
+                fails.push(self.emit_placeholder());
+                self.emit(Instr::JumpIfFalse(0));
+                self.emit(Instr::LoadLocal(slot));
+                self.emit(Instr::Push(self.const_from_lit(end)));
+                self.emit(Instr::LtEq);
+                fails.push(self.emit_placeholder());
+                self.emit(Instr::JumpIfFalse(0));
+            }
+            _ => {
+                let bindings = self.pattern_binding_slots(pat, binding_slots);
+                fails.push(self.emit_match(slot, pat.clone(), bindings));
+            }
+        }
+    }
+
+    fn copy_binding(&mut self, slot: usize, name: &str, binding_slots: &[(String, usize)]) {
+        if let Some((_, bound)) = binding_slots.iter().find(|(n, _)| n == name) {
+            self.emit(Instr::LoadLocal(slot));
+            self.emit(Instr::StoreLocal(*bound));
+        }
+    }
+
+    fn is_flat_variant_arg(&self, arg: &Pattern) -> bool {
+        match &arg.kind {
+            PatternKind::Wildcard => true,
+            PatternKind::Ident(ident) => self.is_binding_ident(ident),
+            _ => false,
+        }
+    }
+
+    fn is_binding_ident(&self, ident: &Ident) -> bool {
+        !matches!(ident.name.as_str(), "Some" | "None" | "Ok" | "Err")
+            && !self.enum_variant_names.contains(&ident.name)
+    }
+
+    fn pattern_binding_slots(
+        &self,
+        pat: &Pattern,
+        binding_slots: &[(String, usize)],
+    ) -> Vec<(String, usize)> {
+        let mut names = Vec::new();
+        self.collect_bindings(pat, &mut names);
+        binding_slots
+            .iter()
+            .filter(|(name, _)| names.iter().any(|ident| &ident.name == name))
+            .cloned()
+            .collect()
+    }
+
     fn collect_bindings(&self, pat: &Pattern, out: &mut Vec<Ident>) {
         match &pat.kind {
             PatternKind::Ident(ident) => {
-                if !matches!(ident.name.as_str(), "Some" | "None" | "Ok" | "Err")
-                    && !self.enum_variant_names.contains(&ident.name)
-                {
+                if self.is_binding_ident(ident) {
                     out.push(ident.clone());
                 }
             }
-            PatternKind::EnumVariant { args, .. } => {
+            PatternKind::EnumVariant { args, .. } | PatternKind::Tuple(args) => {
                 for arg in args {
                     self.collect_bindings(arg, out);
                 }
@@ -995,7 +1206,17 @@ impl FuncBuilder {
                     self.collect_bindings(&field.pat, out);
                 }
             }
-            PatternKind::Wildcard | PatternKind::Literal(_) => {}
+            PatternKind::Bind { name, pat } => {
+                out.push(name.clone());
+                self.collect_bindings(pat, out);
+            }
+            // Sema checks that every alternative binds the same names.
+            PatternKind::Or(alts) => {
+                if let Some(first) = alts.first() {
+                    self.collect_bindings(first, out);
+                }
+            }
+            PatternKind::Wildcard | PatternKind::Literal(_) | PatternKind::Range { .. } => {}
         }
     }
 
@@ -1087,6 +1308,25 @@ impl FuncBuilder {
                 self.emit(Instr::StoreLocal(slot));
                 self.emit(Instr::Push(Const::Unit));
             }
+            StmtKind::LetPattern { pat, expr, .. } => {
+                let temp = self.declare_temp();
+                self.lower_expr(expr);
+                self.emit(Instr::StoreLocal(temp));
+                let binding_slots = self.declare_pattern_bindings(pat);
+                let mut fails = Vec::new();
+                self.lower_pattern_match(temp, pat, &binding_slots, &mut fails);
+                let done = self.emit_placeholder();
+                self.emit(Instr::Jump(0));
+                let pattern_error = self.code.len();
+                self.emit(Instr::RuntimeError(
+                    "let pattern did not match value".to_string(),
+                ));
+                self.patch_jump(done, self.code.len());
+                for fail in fails {
+                    self.patch_match_jump(fail, pattern_error);
+                }
+                self.emit(Instr::Push(Const::Unit));
+            }
             StmtKind::Assign { target, expr } => match &target.kind {
                 ExprKind::Ident(ident) => match self.resolve(&ident.name) {
                     Some(slot) => {
@@ -1246,31 +1486,30 @@ impl FuncBuilder {
         }
     }
 
-    fn lower_match(&mut self, expr: &Expr, cases: &[(Pattern, Block)]) {
+    fn lower_match(&mut self, expr: &Expr, cases: &[MatchCase]) {
         let temp = self.declare_temp();
         self.lower_expr(expr);
         self.emit(Instr::StoreLocal(temp));
 
         let mut end_jumps = Vec::new();
-        for (pat, block) in cases {
+        for case in cases {
             self.enter_scope();
-            let mut bindings = Vec::new();
-            self.collect_bindings(pat, &mut bindings);
-            let mut binding_slots = Vec::new();
-            let mut seen = HashSet::new();
-            for ident in bindings {
-                if seen.insert(ident.name.clone()) {
-                    let slot = self.declare(&ident);
-                    binding_slots.push((ident.name.clone(), slot));
-                }
-            }
-            let match_idx = self.emit_match(temp, pat.clone(), binding_slots);
-            self.lower_block(block);
+            let binding_slots = self.declare_pattern_bindings(&case.pat);
+            let mut fails = Vec::new();
+            self.lower_pattern_match(temp, &case.pat, &binding_slots, &mut fails);
+            if let Some(guard) = &case.guard {
+                self.lower_expr(guard);
+                fails.push(self.emit_placeholder());
+                self.emit(Instr::JumpIfFalse(0));
+            }
+            self.lower_block(&case.block);
             self.exit_scope();
             end_jumps.push(self.emit_placeholder());
             self.emit(Instr::Jump(0));
             let next_case = self.code.len();
-            self.patch_match_jump(match_idx, next_case);
+            for fail in fails {
+                self.patch_match_jump(fail, next_case);
+            }
         }
 
         self.emit(Instr::Push(Const::Unit));
@@ -1369,22 +1608,13 @@ impl FuncBuilder {
         self.emit(Instr::StoreLocal(out_slot));
 
         self.lower_expr(iter);
-        self.emit(Instr::IterInit);
+        self.emit(Instr::IterInit { entries: false });
         let iter_slot = self.declare_temp();
         self.emit(Instr::StoreLocal(iter_slot));
         let item_slot = self.declare_temp();
 
         self.enter_scope();
-        let mut bindings = Vec::new();
-        self.collect_bindings(pat, &mut bindings);
-        let mut binding_slots = Vec::new();
-        let mut seen = HashSet::new();
-        for ident in bindings {
-            if seen.insert(ident.name.clone()) {
-                let slot = self.declare(&ident);
-                binding_slots.push((ident.name.clone(), slot));
-            }
-        }
+        let binding_slots = self.declare_pattern_bindings(pat);
 
         let loop_start = self.code.len();
         self.emit(Instr::LoadLocal(iter_slot));
@@ -1393,7 +1623,8 @@ impl FuncBuilder {
         self.emit(Instr::StoreLocal(iter_slot));
         self.emit(Instr::StoreLocal(item_slot));
 
-        let match_idx = self.emit_match(item_slot, pat.clone(), binding_slots);
+        let mut fails = Vec::new();
+        self.lower_pattern_match(item_slot, pat, &binding_slots, &mut fails);
         self.emit(Instr::LoadLocal(out_slot));
         self.lower_html_children_list(body_children);
         self.emit(Instr::Add);
@@ -1407,7 +1638,9 @@ impl FuncBuilder {
 
         let end = self.code.len();
         self.patch_jump(iter_next, end);
-        self.patch_match_jump(match_idx, pattern_error);
+        for fail in fails {
+            self.patch_match_jump(fail, pattern_error);
+        }
         self.exit_scope();
         self.emit(Instr::LoadLocal(out_slot));
     }
@@ -1448,22 +1681,15 @@ impl FuncBuilder {
 
     fn lower_for(&mut self, pat: &Pattern, iter: &Expr, block: &Block) {
         self.lower_expr(iter);
-        self.emit(Instr::IterInit);
+        self.emit(Instr::IterInit {
+            entries: pat.is_pair(),
+        });
         let iter_slot = self.declare_temp();
         self.emit(Instr::StoreLocal(iter_slot));
         let item_slot = self.declare_temp();
 
         self.enter_scope();
-        let mut bindings = Vec::new();
-        self.collect_bindings(pat, &mut bindings);
-        let mut binding_slots = Vec::new();
-        let mut seen = HashSet::new();
-        for ident in bindings {
-            if seen.insert(ident.name.clone()) {
-                let slot = self.declare(&ident);
-                binding_slots.push((ident.name.clone(), slot));
-            }
-        }
+        let binding_slots = self.declare_pattern_bindings(pat);
 
         let loop_start = self.code.len();
         self.emit(Instr::LoadLocal(iter_slot));
@@ -1478,7 +1704,8 @@ impl FuncBuilder {
             continue_target: loop_start,
         });
 
-        let match_idx = self.emit_match(item_slot, pat.clone(), binding_slots);
+        let mut fails = Vec::new();
+        self.lower_pattern_match(item_slot, pat, &binding_slots, &mut fails);
         self.lower_block(block);
         self.emit(Instr::Pop);
         self.emit(Instr::Jump(loop_start));
@@ -1496,7 +1723,9 @@ impl FuncBuilder {
 
         let end = self.code.len();
         self.patch_jump(iter_next, end);
-        self.patch_match_jump(match_idx, pattern_error);
+        for fail in fails {
+            self.patch_match_jump(fail, pattern_error);
+        }
         for jump in loop_ctx.break_jumps {
             self.patch_jump(jump, end);
         }
@@ -1580,6 +1809,7 @@ impl FuncBuilder {
     fn patch_match_jump(&mut self, at: usize, target: usize) {
         match self.code.get_mut(at) {
             Some(Instr::MatchLocal { jump, .. }) => *jump = target,
+            Some(Instr::JumpIfNull(jump)) | Some(Instr::JumpIfFalse(jump)) => *jump = target,
             _ => {
                 self.errors.push("invalid match patch".to_string());
             }
@@ -1623,6 +1853,13 @@ impl FuncBuilder {
                             kind: CallKind::Builtin,
                         });
                     }
+                    BinaryOp::RangeExclusive => {
+                        self.emit(Instr::Call {
+                            name: "range.exclusive".to_string(),
+                            argc: 2,
+                            kind: CallKind::Builtin,
+                        });
+                    }
                 }
             }
             ExprKind::Unary { op, expr } => {
@@ -1638,7 +1875,8 @@ impl FuncBuilder {
                 type_args,
             } => match &callee.kind {
                 ExprKind::Ident(ident) => {
-                    if !type_args.is_empty() {
+                    // `channel<T>(n)` only uses its type argument in sema.
+                    if !type_args.is_empty() && ident.name != "channel" {
                         self.errors.push(
                             "type arguments are only supported on query.one<T>() and query.all<T>()"
                                 .to_string(),
@@ -1697,7 +1935,39 @@ impl FuncBuilder {
                             || ident.name == "http"
                             || ident.name == "time"
                             || ident.name == "crypto"
+                            || ident.name == "decimal"
                         {
+                            if ident.name == "db" && !type_args.is_empty() {
+                                if !matches!(name.name.as_str(), "query" | "one") {
+                                    self.errors.push(
+                                        "type arguments are only supported on db.query<T>() and db.one<T>()"
+                                            .to_string(),
+                                    );
+                                } else if type_args.len() != 1 {
+                                    self.errors.push(
+                                        "typed query methods expect exactly one type argument"
+                                            .to_string(),
+                                    );
+                                } else if let Some(type_name) =
+                                    self.query_type_arg_name_for_builtin(&type_args[0])
+                                {
+                                    self.emit(Instr::Push(Const::String(type_name)));
+                                    for arg in args {
+                                        self.lower_expr(&arg.value);
+                                    }
+                                    self.emit(Instr::Call {
+                                        name: format!("db.{}_typed", name.name),
+                                        argc: args.len() + 1,
+                                        kind: CallKind::Builtin,
+                                    });
+                                    return;
+                                } else {
+                                    self.errors.push(
+                                        "typed query result type must be a declared type name"
+                                            .to_string(),
+                                    );
+                                }
+                            }
                             for arg in args {
                                 self.lower_expr(&arg.value);
                             }
@@ -1803,19 +2073,36 @@ impl FuncBuilder {
                             }
                         }
                     }
-                    if let ExprKind::Ident(enum_name) = &base.kind {
-                        if self.enum_names.contains(&enum_name.name) {
-                            for arg in args {
-                                self.lower_expr(&arg.value);
-                            }
-                            self.emit(Instr::MakeEnum {
-                                name: enum_name.name.clone(),
-                                variant: name.name.clone(),
-                                argc: args.len(),
-                            });
+                    if let ExprKind::Ident(enum_name) = &base.kind
+                        && self.enum_names.contains(&enum_name.name)
+                    {
+                        for arg in args {
+                            self.lower_expr(&arg.value);
+                        }
+                        self.emit(Instr::MakeEnum {
+                            name: enum_name.name.clone(),
+                            variant: name.name.clone(),
+                            argc: args.len(),
+                        });
+                    } else if crate::runtime_channels::is_channel_method(&name.name)
+                        || crate::runtime_ranges::is_range_method(&name.name)
+                    {
+                        // Sema only admits these methods on `Channel<T>` and
+                        // `Range<T>` receivers respectively.
+                        let owner = if crate::runtime_ranges::is_range_method(&name.name) {
+                            "range"
                         } else {
-                            self.lower_non_callable_target(callee, args);
+                            "channel"
+                        };
+                        self.lower_expr(base);
+                        for arg in args {
+                            self.lower_expr(&arg.value);
                         }
+                        self.emit(Instr::Call {
+                            name: format!("{owner}.{}", name.name),
+                            argc: args.len() + 1,
+                            kind: CallKind::Builtin,
+                        });
                     } else {
                         self.lower_non_callable_target(callee, args);
                     }
@@ -1917,7 +2204,20 @@ impl FuncBuilder {
                     fields: field_names,
                 });
             }
-            ExprKind::ListLit(items) => {
+            ExprKind::RecordUpdate { base, fields } => {
+                self.lower_expr(base);
+                for field in fields {
+                    self.emit(Instr::Push(Const::String(field.name.name.clone())));
+                    self.lower_expr(&field.value);
+                }
+                self.emit(Instr::Call {
+                    name: "struct.update".to_string(),
+                    argc: 1 + fields.len() * 2,
+                    kind: CallKind::Builtin,
+                });
+            }
+            // Tuples share the list representation at runtime.
+            ExprKind::ListLit(items) | ExprKind::TupleLit(items) => {
                 for item in items {
                     self.lower_expr(item);
                 }
@@ -2027,10 +2327,25 @@ impl FuncBuilder {
             } => {
                 self.lower_html_for_expr(pat, iter, body_children);
             }
-            ExprKind::Await { expr } => {
+            ExprKind::Await {
+                expr,
+                timeout: None,
+            } => {
                 self.lower_expr(expr);
                 self.emit(Instr::Await);
             }
+            ExprKind::Await {
+                expr,
+                timeout: Some(timeout),
+            } => {
+                self.lower_expr(expr);
+                self.lower_expr(timeout);
+                self.emit(Instr::Call {
+                    name: "task.await_within".to_string(),
+                    argc: 2,
+                    kind: CallKind::Builtin,
+                });
+            }
         }
     }
 
@@ -96,7 +96,10 @@ pub enum Instr {
         config: String,
         field: String,
     },
-    IterInit,
+    IterInit {
+        /// Yield `[key, value]` pairs when iterating a map.
+        entries: bool,
+    },
     IterNext {
         jump: usize,
     },
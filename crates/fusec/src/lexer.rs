@@ -492,6 +492,8 @@ fn match_punct(s: &str) -> Option<(Punct, usize)> {
         '%' => Punct::Percent,
         '?' => Punct::Question,
         '!' => Punct::Bang,
+        '|' => Punct::Pipe,
+        '@' => Punct::At,
         _ => return None,
     };
     Some((punct, ch.len_utf8()))
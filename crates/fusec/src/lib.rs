@@ -1,8 +1,11 @@
 pub mod ast;
 pub mod callbind;
 pub mod cli;
+pub mod cli_binding;
+pub mod cli_completions;
 pub mod concurrency_metrics;
 pub mod db;
+pub mod db_types;
 pub mod diag;
 pub mod diag_render;
 pub mod format;
@@ -21,8 +24,12 @@ pub mod parser;
 pub mod refinement;
 mod runtime_assets;
 mod runtime_capabilities;
+mod runtime_channels;
+mod runtime_decimal;
 mod runtime_io;
+mod runtime_ranges;
 mod runtime_svg;
+mod runtime_tasks;
 pub mod runtime_types;
 pub mod sema;
 pub mod span;
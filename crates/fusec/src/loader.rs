@@ -7,7 +7,10 @@ use fuse_rt::json as rt_json;
 
 use crate::manifest::build_transitive_deps;
 
-use crate::ast::{FieldDecl, ImportDecl, ImportSpec, Item, Program, TypeDecl, TypeDerive};
+use crate::ast::{
+    DeriveOp, Expr, ExprKind, FieldDecl, ImportDecl, ImportSpec, Item, Literal, Program, TypeDecl,
+    TypeDerive, TypeRef, TypeRefKind,
+};
 use crate::diag::{Diag, Diagnostics};
 use crate::parse_source;
 use crate::span::Span;
@@ -20,6 +23,7 @@ const FUSE_IMPORT_ASSET_FORM: &str = "FUSE_IMPORT_ASSET_FORM";
 const FUSE_IMPORT_UNSUPPORTED_EXTENSION: &str = "FUSE_IMPORT_UNSUPPORTED_EXTENSION";
 const FUSE_IMPORT_DUPLICATE: &str = "FUSE_IMPORT_DUPLICATE";
 const FUSE_IMPORT_UNKNOWN: &str = "FUSE_IMPORT_UNKNOWN";
+pub const FUSE_IMPORT_PRIVATE: &str = "FUSE_IMPORT_PRIVATE";
 const FUSE_IMPORT_DEP_PATH: &str = "FUSE_IMPORT_DEP_PATH";
 const FUSE_IMPORT_UNKNOWN_DEPENDENCY: &str = "FUSE_IMPORT_UNKNOWN_DEPENDENCY";
 const FUSE_IMPORT_ROOT_PATH: &str = "FUSE_IMPORT_ROOT_PATH";
@@ -94,14 +98,31 @@ pub struct ModuleExports {
     pub interfaces: HashSet<String>,
     pub functions: HashSet<String>,
     pub configs: HashSet<String>,
+    pub consts: HashSet<String>,
+    pub type_aliases: HashSet<String>,
     pub services: HashSet<String>,
     pub apps: HashSet<String>,
+    /// Set once any top-level declaration is marked `pub`; from then on
+    /// only `pub` declarations are visible to importing modules.
+    pub explicit_visibility: bool,
+    /// Declarations hidden from other modules. They stay in the sets above
+    /// because the backends resolve module members through them.
+    pub private: HashSet<String>,
 }
 
 impl ModuleExports {
-    fn from_program(program: &Program) -> Self {
-        let mut exports = ModuleExports::default();
+    pub fn from_program(program: &Program) -> Self {
+        let mut exports = ModuleExports {
+            explicit_visibility: program.items.iter().any(item_is_pub),
+            ..ModuleExports::default()
+        };
         for item in &program.items {
+            if exports.explicit_visibility
+                && !item_is_pub(item)
+                && let Some(name) = visibility_item_name(item)
+            {
+                exports.private.insert(name.to_string());
+            }
             match item {
                 Item::Type(decl) => {
                     exports.types.insert(decl.name.name.clone());
@@ -118,6 +139,12 @@ impl ModuleExports {
                 Item::Config(decl) => {
                     exports.configs.insert(decl.name.name.clone());
                 }
+                Item::Const(decl) => {
+                    exports.consts.insert(decl.name.name.clone());
+                }
+                Item::TypeAlias(decl) => {
+                    exports.type_aliases.insert(decl.name.name.clone());
+                }
                 Item::Service(decl) => {
                     exports.services.insert(decl.name.name.clone());
                 }
@@ -136,9 +163,44 @@ impl ModuleExports {
             || self.interfaces.contains(name)
             || self.functions.contains(name)
             || self.configs.contains(name)
+            || self.consts.contains(name)
+            || self.type_aliases.contains(name)
             || self.services.contains(name)
             || self.apps.contains(name)
     }
+
+    /// Whether `name` is declared in the module but hidden from importers.
+    pub fn is_private(&self, name: &str) -> bool {
+        self.private.contains(name)
+    }
+}
+
+pub fn item_is_pub(item: &Item) -> bool {
+    match item {
+        Item::Type(decl) => decl.is_pub,
+        Item::TypeAlias(decl) => decl.is_pub,
+        Item::Enum(decl) => decl.is_pub,
+        Item::Interface(decl) => decl.is_pub,
+        Item::Fn(decl) => decl.is_pub,
+        Item::Config(decl) => decl.is_pub,
+        Item::Const(decl) => decl.is_pub,
+        _ => false,
+    }
+}
+
+/// Name of a declaration that accepts `pub`, or `None` for items without
+/// visibility (services and apps are always global).
+pub fn visibility_item_name(item: &Item) -> Option<&str> {
+    match item {
+        Item::Type(decl) => Some(&decl.name.name),
+        Item::TypeAlias(decl) => Some(&decl.name.name),
+        Item::Enum(decl) => Some(&decl.name.name),
+        Item::Interface(decl) => Some(&decl.name.name),
+        Item::Fn(decl) => Some(&decl.name.name),
+        Item::Config(decl) => Some(&decl.name.name),
+        Item::Const(decl) => Some(&decl.name.name),
+        _ => None,
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -235,6 +297,7 @@ pub fn load_program_with_modules_and_deps(
         root,
         modules: loader.modules,
     };
+    crate::frontend::monomorphize::monomorphize_types(&mut registry);
     crate::frontend::canonicalize::canonicalize_registry(&mut registry);
     (registry, loader.diags.into_vec())
 }
@@ -258,6 +321,7 @@ pub fn load_program_with_modules_and_deps_and_overrides(
         root,
         modules: loader.modules,
     };
+    crate::frontend::monomorphize::monomorphize_types(&mut registry);
     crate::frontend::canonicalize::canonicalize_registry(&mut registry);
     (registry, loader.diags.into_vec())
 }
@@ -546,6 +610,17 @@ impl ModuleLoader {
                                     );
                                     continue;
                                 }
+                                if exports.is_private(&name.name) {
+                                    self.diags.error_with_code(
+                                        name.span,
+                                        FUSE_IMPORT_PRIVATE,
+                                        format!(
+                                            "{} is private to {}; mark it pub to import it",
+                                            name.name, path.value
+                                        ),
+                                    );
+                                    continue;
+                                }
                                 import_items.insert(name.name.clone(), self.link_for(module_id));
                                 import_item_spans.insert(name.name.clone(), name.span);
                             }
@@ -649,7 +724,7 @@ impl ModuleLoader {
         };
 
         let fields = if let Some(derive) = &decl.derive {
-            self.resolve_without_fields(module_id, derive, cache, visiting)
+            self.resolve_derive(module_id, derive, cache, visiting)
         } else {
             Some(decl.fields.clone())
         };
@@ -661,7 +736,7 @@ impl ModuleLoader {
         fields
     }
 
-    fn resolve_without_fields(
+    fn resolve_derive(
         &mut self,
         module_id: ModuleId,
         derive: &TypeDerive,
@@ -688,26 +763,11 @@ impl ModuleLoader {
             return None;
         }
         let base_fields = self.resolve_derived_fields(base_module, &base_name, cache, visiting)?;
-
-        let mut removed = HashSet::new();
-        for field in &derive.without {
-            removed.insert(field.name.clone());
+        let (fields, errors) = apply_derive_ops(base_fields, derive);
+        for (span, message) in errors {
+            self.diags
+                .error_with_code(span, FUSE_TYPE_DERIVE_FIELD, message);
         }
-
-        for field in &derive.without {
-            if !base_fields.iter().any(|f| f.name.name == field.name) {
-                self.diags.error_with_code(
-                    field.span,
-                    FUSE_TYPE_DERIVE_FIELD,
-                    format!("unknown field {} in {}", field.name, derive.base.name),
-                );
-            }
-        }
-
-        let fields = base_fields
-            .into_iter()
-            .filter(|field| !removed.contains(&field.name.name))
-            .collect();
         Some(fields)
     }
 
@@ -1013,6 +1073,79 @@ fn workspace_root_for_entry(path: &Path) -> PathBuf {
     start
 }
 
+/// Applies the operations of a type derivation to the resolved base fields.
+/// Returns the derived fields plus `(span, message)` for every field the
+/// operations name that the base does not have (or, for `with`, already has).
+pub(crate) fn apply_derive_ops(
+    base_fields: Vec<FieldDecl>,
+    derive: &TypeDerive,
+) -> (Vec<FieldDecl>, Vec<(Span, String)>) {
+    let base = &derive.base.name;
+    let mut fields = base_fields;
+    let mut errors = Vec::new();
+    for op in &derive.ops {
+        match op {
+            DeriveOp::Without(names) => {
+                for name in names {
+                    if !fields.iter().any(|field| field.name.name == name.name) {
+                        errors.push((
+                            name.span,
+                            format!("unknown field {} in {}", name.name, base),
+                        ));
+                    }
+                }
+                fields.retain(|field| !names.iter().any(|name| name.name == field.name.name));
+            }
+            DeriveOp::Pick(names) => {
+                let mut picked = Vec::new();
+                for name in names {
+                    match fields.iter().find(|field| field.name.name == name.name) {
+                        Some(field) => picked.push(field.clone()),
+                        None => errors.push((
+                            name.span,
+                            format!("unknown field {} in {}", name.name, base),
+                        )),
+                    }
+                }
+                fields = picked;
+            }
+            DeriveOp::Partial => {
+                // Omitted fields decode and construct as null rather than
+                // picking up the base type's defaults.
+                for field in &mut fields {
+                    field.default = Some(Expr {
+                        kind: ExprKind::Literal(Literal::Null),
+                        span: field.span,
+                    });
+                    if !matches!(field.ty.kind, TypeRefKind::Optional(_)) {
+                        let inner = field.ty.clone();
+                        field.ty = TypeRef {
+                            span: inner.span,
+                            kind: TypeRefKind::Optional(Box::new(inner)),
+                        };
+                    }
+                }
+            }
+            DeriveOp::With(added) => {
+                for field in added {
+                    if fields
+                        .iter()
+                        .any(|existing| existing.name.name == field.name.name)
+                    {
+                        errors.push((
+                            field.name.span,
+                            format!("field {} already exists in {}", field.name.name, base),
+                        ));
+                        continue;
+                    }
+                    fields.push(field.clone());
+                }
+            }
+        }
+    }
+    (fields, errors)
+}
+
 fn split_qualified_name(name: &str) -> Option<(&str, &str)> {
     let mut parts = name.split('.');
     let module = parts.next()?;
@@ -1027,7 +1160,7 @@ fn std_error_virtual_path() -> PathBuf {
     PathBuf::from("<std.Error>")
 }
 
-fn is_std_error_virtual_path(path: &Path) -> bool {
+pub(crate) fn is_std_error_virtual_path(path: &Path) -> bool {
     if path.to_string_lossy() == "<std.Error>" {
         return true;
     }
@@ -2,7 +2,8 @@ use std::cell::{Cell, RefCell};
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 use cranelift_codegen::ir::{
-    AbiParam, BlockArg, InstBuilder, MemFlags, StackSlotData, StackSlotKind, Value as ClifValue,
+    AbiParam, BlockArg, InstBuilder, MemFlags, StackSlotData, StackSlotKind, TrapCode,
+    Value as ClifValue,
     condcodes::{FloatCC, IntCC},
     types,
 };
@@ -14,7 +15,9 @@ use cranelift_native::builder as native_builder;
 use cranelift_object::{ObjectBuilder, ObjectModule};
 
 use crate::ast::{BinaryOp, Expr, Literal, PatternKind, TypeRef, TypeRefKind};
-use crate::db::{DEFAULT_DB_POOL_SIZE, parse_db_pool_size, parse_db_pool_size_value};
+use crate::db::{
+    DbOptions, parse_db_busy_timeout_value, parse_db_pool_size_value, parse_db_wal_value,
+};
 use crate::interp::{HtmlNode, Value};
 use crate::ir::{CallKind, Const, Function, Instr, Program as IrProgram};
 use crate::native::value::{
@@ -218,12 +221,21 @@ pub(crate) struct HostCalls {
     interp_string: FuncId,
     bang: FuncId,
     iter_init: FuncId,
+    iter_entries_init: FuncId,
     iter_next: FuncId,
     task_await: FuncId,
     spawn_async: FuncId,
     add: FuncId,
     eq: FuncId,
     not_eq: FuncId,
+    sub: FuncId,
+    mul: FuncId,
+    div: FuncId,
+    rem: FuncId,
+    lt: FuncId,
+    lt_eq: FuncId,
+    gt: FuncId,
+    gt_eq: FuncId,
     range: FuncId,
     builtin_log: FuncId,
     builtin_print: FuncId,
@@ -247,6 +259,10 @@ pub(crate) struct HostCalls {
     builtin_time_sleep: FuncId,
     builtin_time_format: FuncId,
     builtin_time_parse: FuncId,
+    builtin_time_value: FuncId,
+    builtin_decimal: FuncId,
+    builtin_task: FuncId,
+    builtin_channel: FuncId,
     builtin_crypto_hash: FuncId,
     builtin_crypto_hmac: FuncId,
     builtin_crypto_random_bytes: FuncId,
@@ -259,12 +275,24 @@ pub(crate) struct HostCalls {
     db_tx_begin: FuncId,
     db_tx_commit: FuncId,
     db_tx_rollback: FuncId,
+    db_rollback: FuncId,
     query_select: FuncId,
     query_where: FuncId,
     query_order_by: FuncId,
     query_limit: FuncId,
+    query_or_where: FuncId,
+    query_join: FuncId,
+    query_left_join: FuncId,
+    query_offset: FuncId,
+    query_group_by: FuncId,
+    query_having: FuncId,
+    query_aggregate: FuncId,
     query_insert: FuncId,
     query_upsert: FuncId,
+    query_insert_many: FuncId,
+    query_on_conflict: FuncId,
+    query_do_update: FuncId,
+    query_do_nothing: FuncId,
     query_update: FuncId,
     query_delete: FuncId,
     query_count: FuncId,
@@ -272,7 +300,12 @@ pub(crate) struct HostCalls {
     query_one_typed: FuncId,
     query_all: FuncId,
     query_all_typed: FuncId,
+    db_query_typed: FuncId,
+    db_one_typed: FuncId,
     query_exec: FuncId,
+    query_execute: FuncId,
+    query_returning: FuncId,
+    db_execute: FuncId,
     query_sql: FuncId,
     query_params: FuncId,
     json_encode: FuncId,
@@ -283,6 +316,7 @@ pub(crate) struct HostCalls {
     html_render: FuncId,
     svg_inline: FuncId,
     validate_struct: FuncId,
+    struct_update: FuncId,
 }
 
 pub(crate) struct JitRuntime {
@@ -341,6 +375,10 @@ impl JitRuntime {
         );
         builder.symbol("fuse_native_bang", fuse_native_bang as *const u8);
         builder.symbol("fuse_native_iter_init", fuse_native_iter_init as *const u8);
+        builder.symbol(
+            "fuse_native_iter_entries_init",
+            fuse_native_iter_entries_init as *const u8,
+        );
         builder.symbol("fuse_native_iter_next", fuse_native_iter_next as *const u8);
         builder.symbol(
             "fuse_native_task_await",
@@ -353,6 +391,14 @@ impl JitRuntime {
         builder.symbol("fuse_native_add", fuse_native_add as *const u8);
         builder.symbol("fuse_native_eq", fuse_native_eq as *const u8);
         builder.symbol("fuse_native_not_eq", fuse_native_not_eq as *const u8);
+        builder.symbol("fuse_native_sub", fuse_native_sub as *const u8);
+        builder.symbol("fuse_native_mul", fuse_native_mul as *const u8);
+        builder.symbol("fuse_native_div", fuse_native_div as *const u8);
+        builder.symbol("fuse_native_rem", fuse_native_rem as *const u8);
+        builder.symbol("fuse_native_lt", fuse_native_lt as *const u8);
+        builder.symbol("fuse_native_lt_eq", fuse_native_lt_eq as *const u8);
+        builder.symbol("fuse_native_gt", fuse_native_gt as *const u8);
+        builder.symbol("fuse_native_gt_eq", fuse_native_gt_eq as *const u8);
         builder.symbol("fuse_native_range", fuse_native_range as *const u8);
         builder.symbol(
             "fuse_native_builtin_log",
@@ -442,6 +488,22 @@ impl JitRuntime {
             "fuse_native_builtin_time_parse",
             fuse_native_builtin_time_parse as *const u8,
         );
+        builder.symbol(
+            "fuse_native_builtin_time_value",
+            fuse_native_builtin_time_value as *const u8,
+        );
+        builder.symbol(
+            "fuse_native_builtin_decimal",
+            fuse_native_builtin_decimal as *const u8,
+        );
+        builder.symbol(
+            "fuse_native_builtin_task",
+            fuse_native_builtin_task as *const u8,
+        );
+        builder.symbol(
+            "fuse_native_builtin_channel",
+            fuse_native_builtin_channel as *const u8,
+        );
         builder.symbol(
             "fuse_native_builtin_crypto_hash",
             fuse_native_builtin_crypto_hash as *const u8,
@@ -485,6 +547,10 @@ impl JitRuntime {
             "fuse_native_validate_struct",
             fuse_native_validate_struct as *const u8,
         );
+        builder.symbol(
+            "fuse_native_struct_update",
+            fuse_native_struct_update as *const u8,
+        );
         builder.symbol("fuse_native_db_exec", fuse_native_db_exec as *const u8);
         builder.symbol("fuse_native_db_query", fuse_native_db_query as *const u8);
         builder.symbol("fuse_native_db_one", fuse_native_db_one as *const u8);
@@ -501,6 +567,10 @@ impl JitRuntime {
             "fuse_native_db_tx_rollback",
             fuse_native_db_tx_rollback as *const u8,
         );
+        builder.symbol(
+            "fuse_native_db_rollback",
+            fuse_native_db_rollback as *const u8,
+        );
         builder.symbol(
             "fuse_native_query_select",
             fuse_native_query_select as *const u8,
@@ -517,6 +587,34 @@ impl JitRuntime {
             "fuse_native_query_limit",
             fuse_native_query_limit as *const u8,
         );
+        builder.symbol(
+            "fuse_native_query_or_where",
+            fuse_native_query_or_where as *const u8,
+        );
+        builder.symbol(
+            "fuse_native_query_join",
+            fuse_native_query_join as *const u8,
+        );
+        builder.symbol(
+            "fuse_native_query_left_join",
+            fuse_native_query_left_join as *const u8,
+        );
+        builder.symbol(
+            "fuse_native_query_offset",
+            fuse_native_query_offset as *const u8,
+        );
+        builder.symbol(
+            "fuse_native_query_group_by",
+            fuse_native_query_group_by as *const u8,
+        );
+        builder.symbol(
+            "fuse_native_query_having",
+            fuse_native_query_having as *const u8,
+        );
+        builder.symbol(
+            "fuse_native_query_aggregate",
+            fuse_native_query_aggregate as *const u8,
+        );
         builder.symbol(
             "fuse_native_query_insert",
             fuse_native_query_insert as *const u8,
@@ -525,6 +623,22 @@ impl JitRuntime {
             "fuse_native_query_upsert",
             fuse_native_query_upsert as *const u8,
         );
+        builder.symbol(
+            "fuse_native_query_insert_many",
+            fuse_native_query_insert_many as *const u8,
+        );
+        builder.symbol(
+            "fuse_native_query_on_conflict",
+            fuse_native_query_on_conflict as *const u8,
+        );
+        builder.symbol(
+            "fuse_native_query_do_update",
+            fuse_native_query_do_update as *const u8,
+        );
+        builder.symbol(
+            "fuse_native_query_do_nothing",
+            fuse_native_query_do_nothing as *const u8,
+        );
         builder.symbol(
             "fuse_native_query_update",
             fuse_native_query_update as *const u8,
@@ -547,10 +661,30 @@ impl JitRuntime {
             "fuse_native_query_all_typed",
             fuse_native_query_all_typed as *const u8,
         );
+        builder.symbol(
+            "fuse_native_db_query_typed",
+            fuse_native_db_query_typed as *const u8,
+        );
+        builder.symbol(
+            "fuse_native_db_one_typed",
+            fuse_native_db_one_typed as *const u8,
+        );
         builder.symbol(
             "fuse_native_query_exec",
             fuse_native_query_exec as *const u8,
         );
+        builder.symbol(
+            "fuse_native_query_execute",
+            fuse_native_query_execute as *const u8,
+        );
+        builder.symbol(
+            "fuse_native_query_returning",
+            fuse_native_query_returning as *const u8,
+        );
+        builder.symbol(
+            "fuse_native_db_execute",
+            fuse_native_db_execute as *const u8,
+        );
         builder.symbol("fuse_native_query_sql", fuse_native_query_sql as *const u8);
         builder.symbol(
             "fuse_native_query_params",
@@ -884,6 +1018,9 @@ impl HostCalls {
         let iter_init = module
             .declare_function("fuse_native_iter_init", Linkage::Import, &iter_sig)
             .expect("declare iter init hostcall");
+        let iter_entries_init = module
+            .declare_function("fuse_native_iter_entries_init", Linkage::Import, &iter_sig)
+            .expect("declare iter entries init hostcall");
         let iter_next = module
             .declare_function("fuse_native_iter_next", Linkage::Import, &iter_sig)
             .expect("declare iter next hostcall");
@@ -916,6 +1053,30 @@ impl HostCalls {
         let not_eq = module
             .declare_function("fuse_native_not_eq", Linkage::Import, &builtin_sig)
             .expect("declare not_eq hostcall");
+        let sub = module
+            .declare_function("fuse_native_sub", Linkage::Import, &builtin_sig)
+            .expect("declare sub hostcall");
+        let mul = module
+            .declare_function("fuse_native_mul", Linkage::Import, &builtin_sig)
+            .expect("declare mul hostcall");
+        let div = module
+            .declare_function("fuse_native_div", Linkage::Import, &builtin_sig)
+            .expect("declare div hostcall");
+        let rem = module
+            .declare_function("fuse_native_rem", Linkage::Import, &builtin_sig)
+            .expect("declare rem hostcall");
+        let lt = module
+            .declare_function("fuse_native_lt", Linkage::Import, &builtin_sig)
+            .expect("declare lt hostcall");
+        let lt_eq = module
+            .declare_function("fuse_native_lt_eq", Linkage::Import, &builtin_sig)
+            .expect("declare lt_eq hostcall");
+        let gt = module
+            .declare_function("fuse_native_gt", Linkage::Import, &builtin_sig)
+            .expect("declare gt hostcall");
+        let gt_eq = module
+            .declare_function("fuse_native_gt_eq", Linkage::Import, &builtin_sig)
+            .expect("declare gt_eq hostcall");
         let get_index = module
             .declare_function("fuse_native_get_index", Linkage::Import, &builtin_sig)
             .expect("declare get_index hostcall");
@@ -1050,6 +1211,22 @@ impl HostCalls {
                 &builtin_sig,
             )
             .expect("declare builtin time.parse hostcall");
+        let builtin_time_value = module
+            .declare_function(
+                "fuse_native_builtin_time_value",
+                Linkage::Import,
+                &builtin_sig,
+            )
+            .expect("declare builtin time value hostcall");
+        let builtin_decimal = module
+            .declare_function("fuse_native_builtin_decimal", Linkage::Import, &builtin_sig)
+            .expect("declare builtin decimal hostcall");
+        let builtin_task = module
+            .declare_function("fuse_native_builtin_task", Linkage::Import, &builtin_sig)
+            .expect("declare builtin task hostcall");
+        let builtin_channel = module
+            .declare_function("fuse_native_builtin_channel", Linkage::Import, &builtin_sig)
+            .expect("declare builtin channel hostcall");
         let builtin_crypto_hash = module
             .declare_function(
                 "fuse_native_builtin_crypto_hash",
@@ -1102,6 +1279,9 @@ impl HostCalls {
         let db_tx_rollback = module
             .declare_function("fuse_native_db_tx_rollback", Linkage::Import, &builtin_sig)
             .expect("declare db tx rollback hostcall");
+        let db_rollback = module
+            .declare_function("fuse_native_db_rollback", Linkage::Import, &builtin_sig)
+            .expect("declare db rollback hostcall");
         let query_select = module
             .declare_function("fuse_native_query_select", Linkage::Import, &builtin_sig)
             .expect("declare query select hostcall");
@@ -1114,12 +1294,57 @@ impl HostCalls {
         let query_limit = module
             .declare_function("fuse_native_query_limit", Linkage::Import, &builtin_sig)
             .expect("declare query limit hostcall");
+        let query_or_where = module
+            .declare_function("fuse_native_query_or_where", Linkage::Import, &builtin_sig)
+            .expect("declare query or_where hostcall");
+        let query_join = module
+            .declare_function("fuse_native_query_join", Linkage::Import, &builtin_sig)
+            .expect("declare query join hostcall");
+        let query_left_join = module
+            .declare_function("fuse_native_query_left_join", Linkage::Import, &builtin_sig)
+            .expect("declare query left_join hostcall");
+        let query_offset = module
+            .declare_function("fuse_native_query_offset", Linkage::Import, &builtin_sig)
+            .expect("declare query offset hostcall");
+        let query_group_by = module
+            .declare_function("fuse_native_query_group_by", Linkage::Import, &builtin_sig)
+            .expect("declare query group_by hostcall");
+        let query_having = module
+            .declare_function("fuse_native_query_having", Linkage::Import, &builtin_sig)
+            .expect("declare query having hostcall");
+        let query_aggregate = module
+            .declare_function("fuse_native_query_aggregate", Linkage::Import, &builtin_sig)
+            .expect("declare query aggregate hostcall");
         let query_insert = module
             .declare_function("fuse_native_query_insert", Linkage::Import, &builtin_sig)
             .expect("declare query insert hostcall");
         let query_upsert = module
             .declare_function("fuse_native_query_upsert", Linkage::Import, &builtin_sig)
             .expect("declare query upsert hostcall");
+        let query_insert_many = module
+            .declare_function(
+                "fuse_native_query_insert_many",
+                Linkage::Import,
+                &builtin_sig,
+            )
+            .expect("declare query insert_many hostcall");
+        let query_on_conflict = module
+            .declare_function(
+                "fuse_native_query_on_conflict",
+                Linkage::Import,
+                &builtin_sig,
+            )
+            .expect("declare query on_conflict hostcall");
+        let query_do_update = module
+            .declare_function("fuse_native_query_do_update", Linkage::Import, &builtin_sig)
+            .expect("declare query do_update hostcall");
+        let query_do_nothing = module
+            .declare_function(
+                "fuse_native_query_do_nothing",
+                Linkage::Import,
+                &builtin_sig,
+            )
+            .expect("declare query do_nothing hostcall");
         let query_update = module
             .declare_function("fuse_native_query_update", Linkage::Import, &builtin_sig)
             .expect("declare query update hostcall");
@@ -1141,9 +1366,24 @@ impl HostCalls {
         let query_all_typed = module
             .declare_function("fuse_native_query_all_typed", Linkage::Import, &builtin_sig)
             .expect("declare query all_typed hostcall");
+        let db_query_typed = module
+            .declare_function("fuse_native_db_query_typed", Linkage::Import, &builtin_sig)
+            .expect("declare db query_typed hostcall");
+        let db_one_typed = module
+            .declare_function("fuse_native_db_one_typed", Linkage::Import, &builtin_sig)
+            .expect("declare db one_typed hostcall");
         let query_exec = module
             .declare_function("fuse_native_query_exec", Linkage::Import, &builtin_sig)
             .expect("declare query exec hostcall");
+        let query_execute = module
+            .declare_function("fuse_native_query_execute", Linkage::Import, &builtin_sig)
+            .expect("declare query execute hostcall");
+        let query_returning = module
+            .declare_function("fuse_native_query_returning", Linkage::Import, &builtin_sig)
+            .expect("declare query returning hostcall");
+        let db_execute = module
+            .declare_function("fuse_native_db_execute", Linkage::Import, &builtin_sig)
+            .expect("declare db execute hostcall");
         let query_sql = module
             .declare_function("fuse_native_query_sql", Linkage::Import, &builtin_sig)
             .expect("declare query sql hostcall");
@@ -1171,6 +1411,9 @@ impl HostCalls {
         let svg_inline = module
             .declare_function("fuse_native_svg_inline", Linkage::Import, &builtin_sig)
             .expect("declare svg inline hostcall");
+        let struct_update = module
+            .declare_function("fuse_native_struct_update", Linkage::Import, &builtin_sig)
+            .expect("declare struct update hostcall");
         let mut validate_sig = module.make_signature();
         validate_sig.params.push(AbiParam::new(pointer_ty));
         validate_sig.params.push(AbiParam::new(types::I64));
@@ -1200,12 +1443,21 @@ impl HostCalls {
             interp_string,
             bang,
             iter_init,
+            iter_entries_init,
             iter_next,
             task_await,
             spawn_async,
             add,
             eq,
             not_eq,
+            sub,
+            mul,
+            div,
+            rem,
+            lt,
+            lt_eq,
+            gt,
+            gt_eq,
             range,
             builtin_log,
             builtin_print,
@@ -1229,6 +1481,10 @@ impl HostCalls {
             builtin_time_sleep,
             builtin_time_format,
             builtin_time_parse,
+            builtin_time_value,
+            builtin_decimal,
+            builtin_task,
+            builtin_channel,
             builtin_crypto_hash,
             builtin_crypto_hmac,
             builtin_crypto_random_bytes,
@@ -1241,12 +1497,24 @@ impl HostCalls {
             db_tx_begin,
             db_tx_commit,
             db_tx_rollback,
+            db_rollback,
             query_select,
             query_where,
             query_order_by,
             query_limit,
+            query_or_where,
+            query_join,
+            query_left_join,
+            query_offset,
+            query_group_by,
+            query_having,
+            query_aggregate,
             query_insert,
             query_upsert,
+            query_insert_many,
+            query_on_conflict,
+            query_do_update,
+            query_do_nothing,
             query_update,
             query_delete,
             query_count,
@@ -1254,7 +1522,12 @@ impl HostCalls {
             query_one_typed,
             query_all,
             query_all_typed,
+            db_query_typed,
+            db_one_typed,
             query_exec,
+            query_execute,
+            query_returning,
+            db_execute,
             query_sql,
             query_params,
             json_encode,
@@ -1265,6 +1538,7 @@ impl HostCalls {
             html_render,
             svg_inline,
             validate_struct,
+            struct_update,
         }
     }
 }
@@ -1399,6 +1673,14 @@ impl crate::runtime_types::RuntimeTypeHost for JitValidateHost {
             other => Err(other),
         }
     }
+
+    fn struct_field_types(&self, _name: &str) -> Option<Vec<(String, TypeRef)>> {
+        None
+    }
+
+    fn enum_payload_types(&self, _name: &str, _variant: &str) -> Option<Vec<TypeRef>> {
+        None
+    }
 }
 
 fn validate_value(value: &Value, ty: &TypeRef, path: &str) -> ValidateResult {
@@ -1418,12 +1700,38 @@ fn check_refined(value: &Value, base: &str, args: &[Expr], path: &str) -> Valida
     };
     for constraint in constraints {
         match constraint {
+            RefinementConstraint::Range { min, max, .. } if base == "Decimal" => {
+                match crate::runtime_decimal::check_decimal_range(value, min, max) {
+                    Ok(None) => {}
+                    Ok(Some(message)) => {
+                        return ValidateResult::Error(validation_error_value(
+                            path,
+                            "invalid_value",
+                            message,
+                        ));
+                    }
+                    Err(message) => return ValidateResult::Runtime(message),
+                }
+            }
             RefinementConstraint::Range { min, max, .. } => {
                 match check_refined_range(value, base, min, max, path) {
                     ValidateResult::Ok => {}
                     other => return other,
                 }
             }
+            RefinementConstraint::TextRange { min, max, .. } => {
+                match crate::runtime_types::check_time_range(value, base, &min, &max) {
+                    Ok(None) => {}
+                    Ok(Some(message)) => {
+                        return ValidateResult::Error(validation_error_value(
+                            path,
+                            "invalid_value",
+                            message,
+                        ));
+                    }
+                    Err(message) => return ValidateResult::Runtime(message),
+                }
+            }
             RefinementConstraint::Regex { pattern, .. } => {
                 if !base_is_string_like(base) {
                     return ValidateResult::Runtime(format!(
@@ -1633,25 +1941,45 @@ fn db_url() -> Result<String, String> {
     Err("db url not configured (set FUSE_DB_URL or App.dbUrl)".to_string())
 }
 
-fn db_pool_size(heap: &NativeHeap) -> Result<usize, String> {
-    if let Ok(raw) = std::env::var("FUSE_DB_POOL_SIZE") {
-        return parse_db_pool_size(&raw, "FUSE_DB_POOL_SIZE");
+fn db_options(heap: &NativeHeap) -> Result<DbOptions, String> {
+    let mut options = DbOptions::default();
+    if let Some((value, source)) = db_setting(heap, "FUSE_DB_POOL_SIZE", "dbPoolSize")? {
+        options.pool_size = parse_db_pool_size_value(&value, &source)?;
+    }
+    if let Some((value, source)) = db_setting(heap, "FUSE_DB_BUSY_TIMEOUT_MS", "dbBusyTimeoutMs")? {
+        options.busy_timeout_ms = parse_db_busy_timeout_value(&value, &source)?;
+    }
+    if let Some((value, source)) = db_setting(heap, "FUSE_DB_WAL", "dbWal")? {
+        options.wal = parse_db_wal_value(&value, &source)?;
+    }
+    crate::observability::db_slow_query_threshold_ms()?;
+    Ok(options)
+}
+
+/// Resolves a DB setting from its env var, then `App.<field>` (loaded config,
+/// env override, config file). Returns the raw value and the source name used
+/// in error messages.
+fn db_setting(
+    heap: &NativeHeap,
+    env_key: &str,
+    field: &str,
+) -> Result<Option<(Value, String)>, String> {
+    if let Ok(raw) = std::env::var(env_key) {
+        return Ok(Some((Value::String(raw), env_key.to_string())));
     }
-    if let Some(value) = heap.config_field("App", "dbPoolSize") {
-        return parse_db_pool_size_value(&value, "App.dbPoolSize");
+    let source = format!("App.{field}");
+    if let Some(value) = heap.config_field("App", field) {
+        return Ok(Some((value, source)));
     }
-    let key = rt_config::env_key("App", "dbPoolSize");
-    if let Ok(raw) = std::env::var(key) {
-        return parse_db_pool_size(&raw, "App.dbPoolSize");
+    if let Ok(raw) = std::env::var(rt_config::env_key("App", field)) {
+        return Ok(Some((Value::String(raw), source)));
     }
     let config_path = std::env::var("FUSE_CONFIG").unwrap_or_else(|_| "config.toml".to_string());
     let file_values = rt_config::load_config_file(&config_path)?;
-    if let Some(section) = file_values.get("App") {
-        if let Some(raw) = section.get("dbPoolSize") {
-            return parse_db_pool_size(raw, "App.dbPoolSize");
-        }
-    }
-    Ok(DEFAULT_DB_POOL_SIZE)
+    Ok(file_values
+        .get("App")
+        .and_then(|section| section.get(field))
+        .map(|raw| (Value::String(raw.clone()), source)))
 }
 
 #[unsafe(no_mangle)]
@@ -1839,6 +2167,13 @@ extern "C" fn fuse_native_get_index(
                 .cloned()
                 .ok_or_else(|| "index out of bounds".to_string())
         }
+        Value::Range(range) => match index {
+            Value::Int(v) if v >= 0 => range
+                .get(v as usize)
+                .ok_or_else(|| "index out of bounds".to_string()),
+            Value::Int(_) => Err("index out of bounds".to_string()),
+            _ => Err("list index must be Int".to_string()),
+        },
         Value::Map(items) => match index {
             Value::String(key) => Ok(items.get(&key).cloned().unwrap_or(Value::Null)),
             _ => Err("map keys must be strings".to_string()),
@@ -2135,6 +2470,83 @@ extern "C" fn fuse_native_set_field(
     0
 }
 
+#[unsafe(no_mangle)]
+extern "C" fn fuse_native_struct_update(
+    heap: *mut NativeHeap,
+    args: *const NativeValue,
+    len: u64,
+    out: *mut NativeValue,
+) -> u8 {
+    let heap = unsafe { heap.as_mut() };
+    let Some(heap) = heap else {
+        return 2;
+    };
+    let Some(out) = (unsafe { out.as_mut() }) else {
+        return 2;
+    };
+    if len == 0 || len.is_multiple_of(2) {
+        return builtin_runtime_error(out, heap, "record update expects a struct and field pairs");
+    }
+    let args = unsafe { std::slice::from_raw_parts(args, len as usize) };
+    let heap_ref: &NativeHeap = heap;
+    let (type_name, mut fields) = match args[0].to_value(heap_ref).map(|value| value.unboxed()) {
+        Some(Value::Struct { name, fields }) => (name, fields),
+        _ => return builtin_runtime_error(out, heap, "record update expects a struct"),
+    };
+    let Some(type_info) = heap_ref.type_info(&type_name) else {
+        return builtin_runtime_error(out, heap, format!("unknown type {type_name}"));
+    };
+    let field_infos = type_info.fields.clone();
+    for pair in args[1..].chunks(2) {
+        let field_name = match pair[0].to_value(heap_ref) {
+            Some(Value::String(text)) => text,
+            _ => {
+                return builtin_runtime_error(out, heap, "struct field names must be strings");
+            }
+        };
+        if !field_infos.iter().any(|field| field.name == field_name) {
+            return builtin_runtime_error(
+                out,
+                heap,
+                format!("unknown field {type_name}.{field_name}"),
+            );
+        }
+        let Some(value) = pair[1].to_value(heap_ref) else {
+            return builtin_runtime_error(out, heap, "invalid field value");
+        };
+        fields.insert(field_name, value);
+    }
+    // Validate the whole result, as constructing it from scratch would.
+    for field_info in &field_infos {
+        let Some(value) = fields.get(&field_info.name) else {
+            continue;
+        };
+        let path = format!("{}.{}", type_name, field_info.name);
+        match validate_value(value, &field_info.ty, &path) {
+            ValidateResult::Ok => {}
+            ValidateResult::Error(err_value) => {
+                let Some(native) = NativeValue::from_value(&err_value, heap) else {
+                    return builtin_runtime_error(out, heap, "validation error");
+                };
+                *out = native;
+                return 1;
+            }
+            ValidateResult::Runtime(message) => {
+                return builtin_runtime_error(out, heap, message);
+            }
+        }
+    }
+    let updated = Value::Struct {
+        name: type_name,
+        fields,
+    };
+    let Some(native) = NativeValue::from_value(&updated, heap) else {
+        return builtin_runtime_error(out, heap, "record update unsupported");
+    };
+    *out = native;
+    0
+}
+
 #[unsafe(no_mangle)]
 extern "C" fn fuse_native_make_enum(
     heap: *mut NativeHeap,
@@ -2332,6 +2744,24 @@ extern "C" fn fuse_native_iter_init(
     heap: *mut NativeHeap,
     value: *const NativeValue,
     out: *mut NativeValue,
+) -> u8 {
+    iter_init_with(heap, value, out, false)
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn fuse_native_iter_entries_init(
+    heap: *mut NativeHeap,
+    value: *const NativeValue,
+    out: *mut NativeValue,
+) -> u8 {
+    iter_init_with(heap, value, out, true)
+}
+
+fn iter_init_with(
+    heap: *mut NativeHeap,
+    value: *const NativeValue,
+    out: *mut NativeValue,
+    entries: bool,
 ) -> u8 {
     let heap = unsafe { heap.as_mut() };
     let Some(heap) = heap else {
@@ -2348,8 +2778,20 @@ extern "C" fn fuse_native_iter_init(
         return builtin_runtime_error(out, heap, "cannot iterate over value");
     };
     let value = value.unboxed();
+    if let Value::Range(range) = value {
+        let handle = heap.insert(HeapValue::Iterator(NativeIterator::range(range)));
+        *out = NativeValue {
+            tag: NativeTag::Heap,
+            payload: handle,
+        };
+        return 0;
+    }
     let iter_values = match value {
         Value::List(items) => items,
+        Value::Map(items) if entries => items
+            .into_iter()
+            .map(|(key, value)| Value::List(vec![Value::String(key), value]))
+            .collect(),
         Value::Map(items) => items.into_values().collect(),
         other => {
             return builtin_runtime_error(
@@ -2390,6 +2832,9 @@ extern "C" fn fuse_native_iter_next(
     let Some(out) = (unsafe { out.as_mut() }) else {
         return 2;
     };
+    if crate::interp::task_cancelled() {
+        return builtin_runtime_error(out, heap, crate::interp::TASK_CANCELLED);
+    }
     let heap_ref: &NativeHeap = heap;
     let handle = match iter.tag {
         NativeTag::Heap => iter.payload,
@@ -2406,6 +2851,23 @@ extern "C" fn fuse_native_iter_next(
         .to_value(heap_ref)
         .map(|value| value_type_name(&value))
         .unwrap_or_else(|| "Unknown".to_string());
+    if let Some(HeapValue::Iterator(NativeIterator {
+        range: Some(range),
+        index,
+        ..
+    })) = heap.get_mut(handle)
+    {
+        // Range elements are computed on demand instead of read from `values`.
+        let Some(item) = range.get(*index) else {
+            return 1;
+        };
+        *index += 1;
+        let Some(item) = NativeValue::from_value(&item, heap) else {
+            return builtin_runtime_error(out, heap, "iterator value unsupported");
+        };
+        *out = item;
+        return 0;
+    }
     let Some(slot) = heap.get_mut(handle) else {
         return builtin_runtime_error(out, heap, format!("expected iterator, got {type_name}"));
     };
@@ -2589,6 +3051,9 @@ extern "C" fn fuse_native_add(
     };
     let left = left.unboxed();
     let right = right.unboxed();
+    if let Some(result) = crate::runtime_decimal::decimal_binary(&BinaryOp::Add, &left, &right) {
+        return native_value_result(result, heap, out);
+    }
     let value = match (left, right) {
         (Value::List(mut a), Value::List(mut b)) => {
             a.append(&mut b);
@@ -2610,6 +3075,8 @@ extern "C" fn fuse_native_add(
     0
 }
 
+/// Serves `range` / `range.exclusive` (the `..` and `..<` operators) and the
+/// range methods, named the same way as the time helpers.
 #[unsafe(no_mangle)]
 extern "C" fn fuse_native_range(
     heap: *mut NativeHeap,
@@ -2617,97 +3084,14 @@ extern "C" fn fuse_native_range(
     len: u64,
     out: *mut NativeValue,
 ) -> u8 {
-    let heap = unsafe { heap.as_mut() };
-    let Some(heap) = heap else {
-        return 2;
-    };
-    let Some(out) = (unsafe { out.as_mut() }) else {
-        return 2;
-    };
-    if len != 2 {
-        return builtin_runtime_error(out, heap, "range expects 2 arguments");
-    }
-    let args = unsafe { std::slice::from_raw_parts(args, len as usize) };
-    let heap_ref: &NativeHeap = heap;
-    let Some(left) = args.get(0) else {
-        return builtin_runtime_error(out, heap, "range expects 2 arguments");
-    };
-    let Some(right) = args.get(1) else {
-        return builtin_runtime_error(out, heap, "range expects 2 arguments");
-    };
-    let Some(left) = left.to_value(heap_ref) else {
-        return builtin_runtime_error(out, heap, "range expects numeric bounds");
-    };
-    let Some(right) = right.to_value(heap_ref) else {
-        return builtin_runtime_error(out, heap, "range expects numeric bounds");
-    };
-    let left = left.unboxed();
-    let right = right.unboxed();
-    let value = match (left, right) {
-        (Value::Int(start), Value::Int(end)) => {
-            if start > end {
-                return builtin_runtime_error(out, heap, "range start must be <= end");
-            }
-            let items = (start..=end).map(Value::Int).collect();
-            Value::List(items)
-        }
-        (Value::Float(start), Value::Float(end)) => {
-            if !start.is_finite() || !end.is_finite() {
-                return builtin_runtime_error(out, heap, "invalid range bounds");
-            }
-            if start > end {
-                return builtin_runtime_error(out, heap, "range start must be <= end");
-            }
-            let mut items = Vec::new();
-            let mut current = start;
-            let epsilon = 1e-9;
-            while current <= end + epsilon {
-                items.push(Value::Float(current));
-                current += 1.0;
-            }
-            Value::List(items)
-        }
-        (Value::Int(start), Value::Float(end)) => {
-            if !end.is_finite() {
-                return builtin_runtime_error(out, heap, "invalid range bounds");
-            }
-            let start = start as f64;
-            if start > end {
-                return builtin_runtime_error(out, heap, "range start must be <= end");
-            }
-            let mut items = Vec::new();
-            let mut current = start;
-            let epsilon = 1e-9;
-            while current <= end + epsilon {
-                items.push(Value::Float(current));
-                current += 1.0;
-            }
-            Value::List(items)
-        }
-        (Value::Float(start), Value::Int(end)) => {
-            if !start.is_finite() {
-                return builtin_runtime_error(out, heap, "invalid range bounds");
-            }
-            let end = end as f64;
-            if start > end {
-                return builtin_runtime_error(out, heap, "range start must be <= end");
-            }
-            let mut items = Vec::new();
-            let mut current = start;
-            let epsilon = 1e-9;
-            while current <= end + epsilon {
-                items.push(Value::Float(current));
-                current += 1.0;
-            }
-            Value::List(items)
-        }
-        _ => return builtin_runtime_error(out, heap, "range expects numeric bounds"),
-    };
-    let Some(native) = NativeValue::from_value(&value, heap) else {
-        return builtin_runtime_error(out, heap, "range result unsupported");
-    };
-    *out = native;
-    0
+    named_builtin_call(
+        "range",
+        |name, args| crate::runtime_ranges::range_call(name, args).map(Ok),
+        heap,
+        args,
+        len,
+        out,
+    )
 }
 
 #[unsafe(no_mangle)]
@@ -2730,58 +3114,207 @@ extern "C" fn fuse_native_not_eq(
     native_compare(BinaryOp::NotEq, heap, args, len, out)
 }
 
-fn native_compare(
-    op: BinaryOp,
+#[unsafe(no_mangle)]
+extern "C" fn fuse_native_sub(
     heap: *mut NativeHeap,
     args: *const NativeValue,
     len: u64,
     out: *mut NativeValue,
 ) -> u8 {
-    let heap = unsafe { heap.as_mut() };
-    let Some(heap) = heap else {
-        return 2;
-    };
-    let Some(out) = (unsafe { out.as_mut() }) else {
-        return 2;
-    };
-    if len != 2 {
-        return builtin_runtime_error(out, heap, "compare expects 2 values");
-    }
-    let args = unsafe { std::slice::from_raw_parts(args, len as usize) };
-    let heap_ref: &NativeHeap = heap;
-    let Some(left) = args.get(0) else {
-        return builtin_runtime_error(out, heap, "compare expects 2 values");
-    };
-    let Some(right) = args.get(1) else {
-        return builtin_runtime_error(out, heap, "compare expects 2 values");
-    };
-    let Some(left) = left.to_value(heap_ref) else {
-        return builtin_runtime_error(out, heap, "compare expects 2 values");
-    };
-    let Some(right) = right.to_value(heap_ref) else {
-        return builtin_runtime_error(out, heap, "compare expects 2 values");
-    };
-    let left = left.unboxed();
-    let right = right.unboxed();
-    let value = match (left, right) {
-        (Value::Int(a), Value::Int(b)) => match op {
-            BinaryOp::Eq => Value::Bool(a == b),
-            BinaryOp::NotEq => Value::Bool(a != b),
-            _ => return builtin_runtime_error(out, heap, "unsupported comparison"),
-        },
-        (Value::Float(a), Value::Float(b)) => match op {
-            BinaryOp::Eq => Value::Bool(a == b),
-            BinaryOp::NotEq => Value::Bool(a != b),
-            _ => return builtin_runtime_error(out, heap, "unsupported comparison"),
-        },
-        (Value::Bool(a), Value::Bool(b)) => match op {
-            BinaryOp::Eq => Value::Bool(a == b),
-            BinaryOp::NotEq => Value::Bool(a != b),
-            _ => return builtin_runtime_error(out, heap, "unsupported comparison"),
-        },
-        (Value::String(a), Value::String(b)) => match op {
-            BinaryOp::Eq => Value::Bool(a == b),
-            BinaryOp::NotEq => Value::Bool(a != b),
+    native_arith(BinaryOp::Sub, heap, args, len, out)
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn fuse_native_mul(
+    heap: *mut NativeHeap,
+    args: *const NativeValue,
+    len: u64,
+    out: *mut NativeValue,
+) -> u8 {
+    native_arith(BinaryOp::Mul, heap, args, len, out)
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn fuse_native_div(
+    heap: *mut NativeHeap,
+    args: *const NativeValue,
+    len: u64,
+    out: *mut NativeValue,
+) -> u8 {
+    native_arith(BinaryOp::Div, heap, args, len, out)
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn fuse_native_rem(
+    heap: *mut NativeHeap,
+    args: *const NativeValue,
+    len: u64,
+    out: *mut NativeValue,
+) -> u8 {
+    native_arith(BinaryOp::Mod, heap, args, len, out)
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn fuse_native_lt(
+    heap: *mut NativeHeap,
+    args: *const NativeValue,
+    len: u64,
+    out: *mut NativeValue,
+) -> u8 {
+    native_compare(BinaryOp::Lt, heap, args, len, out)
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn fuse_native_lt_eq(
+    heap: *mut NativeHeap,
+    args: *const NativeValue,
+    len: u64,
+    out: *mut NativeValue,
+) -> u8 {
+    native_compare(BinaryOp::LtEq, heap, args, len, out)
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn fuse_native_gt(
+    heap: *mut NativeHeap,
+    args: *const NativeValue,
+    len: u64,
+    out: *mut NativeValue,
+) -> u8 {
+    native_compare(BinaryOp::Gt, heap, args, len, out)
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn fuse_native_gt_eq(
+    heap: *mut NativeHeap,
+    args: *const NativeValue,
+    len: u64,
+    out: *mut NativeValue,
+) -> u8 {
+    native_compare(BinaryOp::GtEq, heap, args, len, out)
+}
+
+fn native_arith(
+    op: BinaryOp,
+    heap: *mut NativeHeap,
+    args: *const NativeValue,
+    len: u64,
+    out: *mut NativeValue,
+) -> u8 {
+    let heap = unsafe { heap.as_mut() };
+    let Some(heap) = heap else {
+        return 2;
+    };
+    let Some(out) = (unsafe { out.as_mut() }) else {
+        return 2;
+    };
+    if len != 2 {
+        return builtin_runtime_error(out, heap, "arithmetic expects 2 values");
+    }
+    let args = unsafe { std::slice::from_raw_parts(args, len as usize) };
+    let heap_ref: &NativeHeap = heap;
+    let (Some(left), Some(right)) = (args[0].to_value(heap_ref), args[1].to_value(heap_ref)) else {
+        return builtin_runtime_error(out, heap, "arithmetic expects 2 values");
+    };
+    if let Some(result) = crate::runtime_decimal::decimal_binary(&op, &left, &right) {
+        return native_value_result(result, heap, out);
+    }
+    let value = match (left.unboxed(), right.unboxed()) {
+        (Value::Int(a), Value::Int(b)) => {
+            let result = match op {
+                BinaryOp::Sub => a.checked_sub(b),
+                BinaryOp::Mul => a.checked_mul(b),
+                BinaryOp::Div => a.checked_div(b),
+                _ => a.checked_rem(b),
+            };
+            match result {
+                Some(value) => Value::Int(value),
+                None if b == 0 && matches!(op, BinaryOp::Div | BinaryOp::Mod) => {
+                    return builtin_runtime_error(out, heap, "division by zero");
+                }
+                None => return builtin_runtime_error(out, heap, "integer overflow"),
+            }
+        }
+        (left, right) => {
+            let (a, b) = match (left, right) {
+                (Value::Float(a), Value::Float(b)) => (a, b),
+                (Value::Int(a), Value::Float(b)) => (a as f64, b),
+                (Value::Float(a), Value::Int(b)) => (a, b as f64),
+                _ => return builtin_runtime_error(out, heap, "unsupported arithmetic operands"),
+            };
+            match op {
+                BinaryOp::Sub => Value::Float(a - b),
+                BinaryOp::Mul => Value::Float(a * b),
+                BinaryOp::Div => Value::Float(a / b),
+                _ => return builtin_runtime_error(out, heap, "mod not supported for float"),
+            }
+        }
+    };
+    let Some(native) = NativeValue::from_value(&value, heap) else {
+        return builtin_runtime_error(out, heap, "arithmetic result unsupported");
+    };
+    *out = native;
+    0
+}
+
+fn native_compare(
+    op: BinaryOp,
+    heap: *mut NativeHeap,
+    args: *const NativeValue,
+    len: u64,
+    out: *mut NativeValue,
+) -> u8 {
+    let heap = unsafe { heap.as_mut() };
+    let Some(heap) = heap else {
+        return 2;
+    };
+    let Some(out) = (unsafe { out.as_mut() }) else {
+        return 2;
+    };
+    if len != 2 {
+        return builtin_runtime_error(out, heap, "compare expects 2 values");
+    }
+    let args = unsafe { std::slice::from_raw_parts(args, len as usize) };
+    let heap_ref: &NativeHeap = heap;
+    let Some(left) = args.get(0) else {
+        return builtin_runtime_error(out, heap, "compare expects 2 values");
+    };
+    let Some(right) = args.get(1) else {
+        return builtin_runtime_error(out, heap, "compare expects 2 values");
+    };
+    let Some(left) = left.to_value(heap_ref) else {
+        return builtin_runtime_error(out, heap, "compare expects 2 values");
+    };
+    let Some(right) = right.to_value(heap_ref) else {
+        return builtin_runtime_error(out, heap, "compare expects 2 values");
+    };
+    let left = left.unboxed();
+    let right = right.unboxed();
+    if let Some(result) = crate::runtime_decimal::decimal_binary(&op, &left, &right) {
+        return native_value_result(result, heap, out);
+    }
+    let value = match (left, right) {
+        (Value::Int(a), Value::Int(b)) => Value::Bool(compare_ordered(&op, a.cmp(&b))),
+        (Value::Float(a), Value::Float(b)) => match a.partial_cmp(&b) {
+            Some(ordering) => Value::Bool(compare_ordered(&op, ordering)),
+            None => Value::Bool(matches!(op, BinaryOp::NotEq)),
+        },
+        (Value::Int(a), Value::Float(b)) => match (a as f64).partial_cmp(&b) {
+            Some(ordering) => Value::Bool(compare_ordered(&op, ordering)),
+            None => Value::Bool(matches!(op, BinaryOp::NotEq)),
+        },
+        (Value::Float(a), Value::Int(b)) => match a.partial_cmp(&(b as f64)) {
+            Some(ordering) => Value::Bool(compare_ordered(&op, ordering)),
+            None => Value::Bool(matches!(op, BinaryOp::NotEq)),
+        },
+        (Value::Bool(a), Value::Bool(b)) => match op {
+            BinaryOp::Eq => Value::Bool(a == b),
+            BinaryOp::NotEq => Value::Bool(a != b),
+            _ => return builtin_runtime_error(out, heap, "unsupported comparison"),
+        },
+        (Value::String(a), Value::String(b)) => match op {
+            BinaryOp::Eq => Value::Bool(a == b),
+            BinaryOp::NotEq => Value::Bool(a != b),
             _ => return builtin_runtime_error(out, heap, "unsupported comparison"),
         },
         _ => return builtin_runtime_error(out, heap, "unsupported comparison operands"),
@@ -2793,6 +3326,33 @@ fn native_compare(
     0
 }
 
+fn native_value_result(
+    result: Result<Value, String>,
+    heap: &mut NativeHeap,
+    out: &mut NativeValue,
+) -> u8 {
+    let value = match result {
+        Ok(value) => value,
+        Err(err) => return builtin_runtime_error(out, heap, err),
+    };
+    let Some(native) = NativeValue::from_value(&value, heap) else {
+        return builtin_runtime_error(out, heap, "operator result unsupported");
+    };
+    *out = native;
+    0
+}
+
+fn compare_ordered(op: &BinaryOp, ordering: std::cmp::Ordering) -> bool {
+    match op {
+        BinaryOp::Eq => ordering.is_eq(),
+        BinaryOp::NotEq => ordering.is_ne(),
+        BinaryOp::Lt => ordering.is_lt(),
+        BinaryOp::LtEq => ordering.is_le(),
+        BinaryOp::Gt => ordering.is_gt(),
+        _ => ordering.is_ge(),
+    }
+}
+
 #[unsafe(no_mangle)]
 extern "C" fn fuse_native_builtin_log(
     heap: *mut NativeHeap,
@@ -3623,6 +4183,157 @@ extern "C" fn fuse_native_builtin_time_parse(
     }
 }
 
+/// Serves every typed `time.*` helper; the call site passes the builtin name
+/// as the first argument.
+#[unsafe(no_mangle)]
+extern "C" fn fuse_native_builtin_time_value(
+    heap: *mut NativeHeap,
+    args: *const NativeValue,
+    len: u64,
+    out: *mut NativeValue,
+) -> u8 {
+    named_builtin_call(
+        "time",
+        crate::runtime_capabilities::time_value_call,
+        heap,
+        args,
+        len,
+        out,
+    )
+}
+
+/// Serves every `decimal.*` helper, named the same way as the time helpers.
+#[unsafe(no_mangle)]
+extern "C" fn fuse_native_builtin_decimal(
+    heap: *mut NativeHeap,
+    args: *const NativeValue,
+    len: u64,
+    out: *mut NativeValue,
+) -> u8 {
+    named_builtin_call(
+        "decimal",
+        crate::runtime_decimal::decimal_call,
+        heap,
+        args,
+        len,
+        out,
+    )
+}
+
+/// Serves `await_all`, `await_any` and `await ... within`, named the same
+/// way as the time helpers. Task failures propagate exactly like `await`.
+#[unsafe(no_mangle)]
+extern "C" fn fuse_native_builtin_task(
+    heap: *mut NativeHeap,
+    args: *const NativeValue,
+    len: u64,
+    out: *mut NativeValue,
+) -> u8 {
+    let heap = unsafe { heap.as_mut() };
+    let Some(heap) = heap else {
+        return 2;
+    };
+    let Some(out) = (unsafe { out.as_mut() }) else {
+        return 2;
+    };
+    let args = if len == 0 {
+        &[][..]
+    } else {
+        unsafe { std::slice::from_raw_parts(args, len as usize) }
+    };
+    let heap_ref: &NativeHeap = heap;
+    let Some(values) = args
+        .iter()
+        .map(|arg| arg.to_value(heap_ref))
+        .collect::<Option<Vec<_>>>()
+    else {
+        return builtin_runtime_error(out, heap, "task builtin received an unsupported value");
+    };
+    let Some((Value::String(name), call_args)) = values.split_first() else {
+        return builtin_runtime_error(out, heap, "task builtin name missing");
+    };
+    let (status, value) = match crate::runtime_tasks::task_call(name, call_args) {
+        Ok(crate::interp::TaskResult::Ok(value)) => (0, value),
+        Ok(crate::interp::TaskResult::Error(value)) => (1, value),
+        Ok(crate::interp::TaskResult::Runtime(message)) | Err(message) => {
+            return builtin_runtime_error(out, heap, message);
+        }
+    };
+    let Some(native) = NativeValue::from_value(&value, heap) else {
+        return builtin_runtime_error(out, heap, format!("{name} result unsupported"));
+    };
+    *out = native;
+    status
+}
+
+/// Serves `channel` and the `channel.*` methods, named the same way as the
+/// time helpers.
+#[unsafe(no_mangle)]
+extern "C" fn fuse_native_builtin_channel(
+    heap: *mut NativeHeap,
+    args: *const NativeValue,
+    len: u64,
+    out: *mut NativeValue,
+) -> u8 {
+    named_builtin_call(
+        "channel",
+        |name, args| crate::runtime_channels::channel_call(name, args).map(Ok),
+        heap,
+        args,
+        len,
+        out,
+    )
+}
+
+type NamedBuiltin = fn(&str, &[Value]) -> Result<Result<Value, String>, String>;
+
+fn named_builtin_call(
+    family: &str,
+    call: NamedBuiltin,
+    heap: *mut NativeHeap,
+    args: *const NativeValue,
+    len: u64,
+    out: *mut NativeValue,
+) -> u8 {
+    let heap = unsafe { heap.as_mut() };
+    let Some(heap) = heap else {
+        return 2;
+    };
+    let Some(out) = (unsafe { out.as_mut() }) else {
+        return 2;
+    };
+    let args = if len == 0 {
+        &[][..]
+    } else {
+        unsafe { std::slice::from_raw_parts(args, len as usize) }
+    };
+    let heap_ref: &NativeHeap = heap;
+    let Some(values) = args
+        .iter()
+        .map(|arg| arg.to_value(heap_ref))
+        .collect::<Option<Vec<_>>>()
+    else {
+        return builtin_runtime_error(
+            out,
+            heap,
+            format!("{family} builtin received an unsupported value"),
+        );
+    };
+    let Some((Value::String(name), call_args)) = values.split_first() else {
+        return builtin_runtime_error(out, heap, format!("{family} builtin name missing"));
+    };
+    let value = match call(name, call_args) {
+        Ok(Ok(value)) => value,
+        Ok(Err(message)) => Value::ResultErr(Box::new(native_default_error_value(message))),
+        Err(err) => return builtin_runtime_error(out, heap, err),
+    };
+    let Some(native) = NativeValue::from_value(&value, heap) else {
+        return builtin_runtime_error(out, heap, format!("{name} result unsupported"));
+    };
+    *out = native;
+    0
+}
+
 #[unsafe(no_mangle)]
 extern "C" fn fuse_native_builtin_crypto_hash(
     heap: *mut NativeHeap,
@@ -4074,6 +4785,9 @@ extern "C" fn fuse_native_task_cancel(
     let cancelled = if task.done || task.cancelled {
         false
     } else {
+        if let Some(pending) = &task.pending {
+            pending.cancel();
+        }
         task.cancelled = true;
         true
     };
@@ -4509,15 +5223,15 @@ extern "C" fn fuse_native_db_exec(
     } else {
         Vec::new()
     };
-    let pool_size = match db_pool_size(heap) {
-        Ok(size) => size,
+    let options = match db_options(heap) {
+        Ok(options) => options,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
     let url = match db_url() {
         Ok(url) => url,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
-    let db = match heap.db_mut(url, pool_size) {
+    let db = match heap.db_mut(url, &options) {
         Ok(db) => db,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
@@ -4529,7 +5243,7 @@ extern "C" fn fuse_native_db_exec(
 }
 
 #[unsafe(no_mangle)]
-extern "C" fn fuse_native_db_query(
+extern "C" fn fuse_native_db_execute(
     heap: *mut NativeHeap,
     args: *const NativeValue,
     len: u64,
@@ -4542,51 +5256,105 @@ extern "C" fn fuse_native_db_query(
     let Some(out) = (unsafe { out.as_mut() }) else {
         return 2;
     };
-
     if len == 0 || len > 2 {
-        return builtin_runtime_error(out, heap, "db.query expects 1 or 2 arguments");
+        return builtin_runtime_error(out, heap, "db.execute expects 1 or 2 arguments");
     }
     let args = unsafe { std::slice::from_raw_parts(args, len as usize) };
     let heap_ref: &NativeHeap = heap;
-    let Some(value) = args.get(0) else {
-        return builtin_runtime_error(out, heap, "db.query expects a SQL string");
-    };
-    let Some(value) = value.to_value(heap_ref) else {
-        return builtin_runtime_error(out, heap, "db.query expects a SQL string");
-    };
-    let sql = match value {
-        Value::String(text) => text,
-        _ => return builtin_runtime_error(out, heap, "db.query expects a SQL string"),
+    let Some(Value::String(sql)) = args.first().and_then(|v| v.to_value(heap_ref)) else {
+        return builtin_runtime_error(out, heap, "db.execute expects a SQL string");
     };
     let params = if len > 1 {
-        let Some(params_value) = args.get(1).and_then(|v| v.to_value(heap_ref)) else {
-            return builtin_runtime_error(out, heap, "db.query params must be a list");
-        };
-        match params_value {
-            Value::List(items) => items,
-            _ => return builtin_runtime_error(out, heap, "db.query params must be a list"),
+        match args.get(1).and_then(|v| v.to_value(heap_ref)) {
+            Some(Value::List(items)) => items,
+            _ => return builtin_runtime_error(out, heap, "db.execute params must be a list"),
         }
     } else {
         Vec::new()
     };
-    let pool_size = match db_pool_size(heap) {
-        Ok(size) => size,
+    let options = match db_options(heap) {
+        Ok(options) => options,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
     let url = match db_url() {
         Ok(url) => url,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
-    let db = match heap.db_mut(url, pool_size) {
+    let db = match heap.db_mut(url, &options) {
         Ok(db) => db,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
-    let rows = match db.query_params(&sql, &params) {
-        Ok(rows) => rows,
+    let result = match db.exec_result(&sql, &params) {
+        Ok(result) => result,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
-    let list: Vec<Value> = rows.into_iter().map(Value::Map).collect();
-    let value = Value::List(list);
+    let value = crate::db::write_result_value(result);
+    let Some(native) = NativeValue::from_value(&value, heap) else {
+        return builtin_runtime_error(out, heap, "db.execute result unsupported");
+    };
+    *out = native;
+    0
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn fuse_native_db_query(
+    heap: *mut NativeHeap,
+    args: *const NativeValue,
+    len: u64,
+    out: *mut NativeValue,
+) -> u8 {
+    let heap = unsafe { heap.as_mut() };
+    let Some(heap) = heap else {
+        return 2;
+    };
+    let Some(out) = (unsafe { out.as_mut() }) else {
+        return 2;
+    };
+
+    if len == 0 || len > 2 {
+        return builtin_runtime_error(out, heap, "db.query expects 1 or 2 arguments");
+    }
+    let args = unsafe { std::slice::from_raw_parts(args, len as usize) };
+    let heap_ref: &NativeHeap = heap;
+    let Some(value) = args.get(0) else {
+        return builtin_runtime_error(out, heap, "db.query expects a SQL string");
+    };
+    let Some(value) = value.to_value(heap_ref) else {
+        return builtin_runtime_error(out, heap, "db.query expects a SQL string");
+    };
+    let sql = match value {
+        Value::String(text) => text,
+        _ => return builtin_runtime_error(out, heap, "db.query expects a SQL string"),
+    };
+    let params = if len > 1 {
+        let Some(params_value) = args.get(1).and_then(|v| v.to_value(heap_ref)) else {
+            return builtin_runtime_error(out, heap, "db.query params must be a list");
+        };
+        match params_value {
+            Value::List(items) => items,
+            _ => return builtin_runtime_error(out, heap, "db.query params must be a list"),
+        }
+    } else {
+        Vec::new()
+    };
+    let options = match db_options(heap) {
+        Ok(options) => options,
+        Err(err) => return builtin_runtime_error(out, heap, err),
+    };
+    let url = match db_url() {
+        Ok(url) => url,
+        Err(err) => return builtin_runtime_error(out, heap, err),
+    };
+    let db = match heap.db_mut(url, &options) {
+        Ok(db) => db,
+        Err(err) => return builtin_runtime_error(out, heap, err),
+    };
+    let rows = match db.query_params(&sql, &params) {
+        Ok(rows) => rows,
+        Err(err) => return builtin_runtime_error(out, heap, err),
+    };
+    let list: Vec<Value> = rows.into_iter().map(Value::Map).collect();
+    let value = Value::List(list);
     let Some(native) = NativeValue::from_value(&value, heap) else {
         return builtin_runtime_error(out, heap, "db.query result unsupported");
     };
@@ -4635,15 +5403,15 @@ extern "C" fn fuse_native_db_one(
     } else {
         Vec::new()
     };
-    let pool_size = match db_pool_size(heap) {
-        Ok(size) => size,
+    let options = match db_options(heap) {
+        Ok(options) => options,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
     let url = match db_url() {
         Ok(url) => url,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
-    let db = match heap.db_mut(url, pool_size) {
+    let db = match heap.db_mut(url, &options) {
         Ok(db) => db,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
@@ -4664,6 +5432,132 @@ extern "C" fn fuse_native_db_one(
     }
 }
 
+/// Shared argument handling for `db.query_typed` / `db.one_typed`: the lowered
+/// call passes the result type name first, then the SQL and optional params.
+fn db_typed_rows(
+    heap: &mut NativeHeap,
+    args: *const NativeValue,
+    len: u64,
+    out: &mut NativeValue,
+    builtin: &str,
+) -> Result<(String, Vec<HashMap<String, Value>>), u8> {
+    if !(2..=3).contains(&len) {
+        return Err(builtin_runtime_error(
+            out,
+            heap,
+            format!("{builtin} expects 2 or 3 arguments"),
+        ));
+    }
+    let args = unsafe { std::slice::from_raw_parts(args, len as usize) };
+    let heap_ref: &NativeHeap = heap;
+    let Some(Value::String(type_name)) = args.first().and_then(|v| v.to_value(heap_ref)) else {
+        return Err(builtin_runtime_error(
+            out,
+            heap,
+            format!("{builtin} expects a type name string"),
+        ));
+    };
+    let Some(Value::String(sql)) = args.get(1).and_then(|v| v.to_value(heap_ref)) else {
+        return Err(builtin_runtime_error(
+            out,
+            heap,
+            format!("{builtin} expects a SQL string"),
+        ));
+    };
+    let params = if len > 2 {
+        match args.get(2).and_then(|v| v.to_value(heap_ref)) {
+            Some(Value::List(items)) => items,
+            _ => {
+                return Err(builtin_runtime_error(
+                    out,
+                    heap,
+                    format!("{builtin} params must be a list"),
+                ));
+            }
+        }
+    } else {
+        Vec::new()
+    };
+    let options = db_options(heap).map_err(|err| builtin_runtime_error(out, heap, err))?;
+    let url = db_url().map_err(|err| builtin_runtime_error(out, heap, err))?;
+    let db = match heap.db_mut(url, &options) {
+        Ok(db) => db,
+        Err(err) => return Err(builtin_runtime_error(out, heap, err)),
+    };
+    let rows = match db.query_params(&sql, &params) {
+        Ok(rows) => rows,
+        Err(err) => return Err(builtin_runtime_error(out, heap, err)),
+    };
+    Ok((type_name, rows))
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn fuse_native_db_query_typed(
+    heap: *mut NativeHeap,
+    args: *const NativeValue,
+    len: u64,
+    out: *mut NativeValue,
+) -> u8 {
+    let heap = unsafe { heap.as_mut() };
+    let Some(heap) = heap else {
+        return 2;
+    };
+    let Some(out) = (unsafe { out.as_mut() }) else {
+        return 2;
+    };
+    let (type_name, rows) = match db_typed_rows(heap, args, len, out, "db.query_typed") {
+        Ok(result) => result,
+        Err(status) => return status,
+    };
+    let mut list = Vec::with_capacity(rows.len());
+    for row in rows {
+        let value = match decode_query_row_typed(row, &type_name, heap, out) {
+            Ok(value) => value,
+            Err(status) => return status,
+        };
+        list.push(value);
+    }
+    let value = Value::List(list);
+    let Some(native) = NativeValue::from_value(&value, heap) else {
+        return builtin_runtime_error(out, heap, "db.query_typed result unsupported");
+    };
+    *out = native;
+    0
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn fuse_native_db_one_typed(
+    heap: *mut NativeHeap,
+    args: *const NativeValue,
+    len: u64,
+    out: *mut NativeValue,
+) -> u8 {
+    let heap = unsafe { heap.as_mut() };
+    let Some(heap) = heap else {
+        return 2;
+    };
+    let Some(out) = (unsafe { out.as_mut() }) else {
+        return 2;
+    };
+    let (type_name, rows) = match db_typed_rows(heap, args, len, out, "db.one_typed") {
+        Ok(result) => result,
+        Err(status) => return status,
+    };
+    if let Some(row) = rows.into_iter().next() {
+        let value = match decode_query_row_typed(row, &type_name, heap, out) {
+            Ok(value) => value,
+            Err(status) => return status,
+        };
+        let Some(native) = NativeValue::from_value(&value, heap) else {
+            return builtin_runtime_error(out, heap, "db.one_typed result unsupported");
+        };
+        *out = native;
+    } else {
+        *out = NativeValue::null();
+    }
+    0
+}
+
 #[unsafe(no_mangle)]
 extern "C" fn fuse_native_db_from(
     heap: *mut NativeHeap,
@@ -4697,7 +5591,7 @@ extern "C" fn fuse_native_db_from(
         Ok(query) => query,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
-    let value = Value::Query(query);
+    let value = Value::Query(Box::new(query));
     let Some(native) = NativeValue::from_value(&value, heap) else {
         return builtin_runtime_error(out, heap, "db.from result unsupported");
     };
@@ -4722,15 +5616,15 @@ extern "C" fn fuse_native_db_tx_begin(
     if len != 0 {
         return builtin_runtime_error(out, heap, "db.tx_begin expects no arguments");
     }
-    let pool_size = match db_pool_size(heap) {
-        Ok(size) => size,
+    let options = match db_options(heap) {
+        Ok(options) => options,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
     let url = match db_url() {
         Ok(url) => url,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
-    if let Err(err) = heap.begin_db_transaction(url, pool_size) {
+    if let Err(err) = heap.begin_db_transaction(url, &options) {
         return builtin_runtime_error(out, heap, err);
     }
     *out = NativeValue::int(0);
@@ -4786,6 +5680,30 @@ extern "C" fn fuse_native_db_tx_rollback(
     0
 }
 
+#[unsafe(no_mangle)]
+extern "C" fn fuse_native_db_rollback(
+    heap: *mut NativeHeap,
+    _args: *const NativeValue,
+    len: u64,
+    out: *mut NativeValue,
+) -> u8 {
+    let heap = unsafe { heap.as_mut() };
+    let Some(heap) = heap else {
+        return 2;
+    };
+    let Some(out) = (unsafe { out.as_mut() }) else {
+        return 2;
+    };
+    if len != 0 {
+        return builtin_runtime_error(out, heap, "db.rollback expects no arguments");
+    }
+    if let Err(err) = heap.mark_db_rollback() {
+        return builtin_runtime_error(out, heap, err);
+    }
+    *out = NativeValue::int(0);
+    0
+}
+
 #[unsafe(no_mangle)]
 extern "C" fn fuse_native_query_select(
     heap: *mut NativeHeap,
@@ -4830,7 +5748,7 @@ extern "C" fn fuse_native_query_select(
         Ok(next) => next,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
-    let value = Value::Query(next);
+    let value = Value::Query(Box::new(next));
     let Some(native) = NativeValue::from_value(&value, heap) else {
         return builtin_runtime_error(out, heap, "query.select result unsupported");
     };
@@ -4882,7 +5800,7 @@ extern "C" fn fuse_native_query_where(
         Ok(next) => next,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
-    let value = Value::Query(next);
+    let value = Value::Query(Box::new(next));
     let Some(native) = NativeValue::from_value(&value, heap) else {
         return builtin_runtime_error(out, heap, "query.where result unsupported");
     };
@@ -4931,7 +5849,7 @@ extern "C" fn fuse_native_query_order_by(
         Ok(next) => next,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
-    let value = Value::Query(next);
+    let value = Value::Query(Box::new(next));
     let Some(native) = NativeValue::from_value(&value, heap) else {
         return builtin_runtime_error(out, heap, "query.order_by result unsupported");
     };
@@ -4976,7 +5894,7 @@ extern "C" fn fuse_native_query_limit(
         Ok(next) => next,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
-    let value = Value::Query(next);
+    let value = Value::Query(Box::new(next));
     let Some(native) = NativeValue::from_value(&value, heap) else {
         return builtin_runtime_error(out, heap, "query.limit result unsupported");
     };
@@ -4984,6 +5902,376 @@ extern "C" fn fuse_native_query_limit(
     0
 }
 
+#[unsafe(no_mangle)]
+extern "C" fn fuse_native_query_or_where(
+    heap: *mut NativeHeap,
+    args: *const NativeValue,
+    len: u64,
+    out: *mut NativeValue,
+) -> u8 {
+    let heap = unsafe { heap.as_mut() };
+    let Some(heap) = heap else {
+        return 2;
+    };
+    let Some(out) = (unsafe { out.as_mut() }) else {
+        return 2;
+    };
+    if len != 4 {
+        return builtin_runtime_error(out, heap, "query.or_where expects 4 arguments");
+    }
+    let args = unsafe { std::slice::from_raw_parts(args, len as usize) };
+    let heap_ref: &NativeHeap = heap;
+    let Some(query_val) = args.first().and_then(|v| v.to_value(heap_ref)) else {
+        return builtin_runtime_error(out, heap, "query.or_where expects a Query");
+    };
+    let Value::Query(query) = query_val else {
+        return builtin_runtime_error(out, heap, "query.or_where expects a Query");
+    };
+    let Some(column_val) = args.get(1).and_then(|v| v.to_value(heap_ref)) else {
+        return builtin_runtime_error(out, heap, "query.or_where expects a column string");
+    };
+    let Value::String(column) = column_val else {
+        return builtin_runtime_error(out, heap, "query.or_where expects a column string");
+    };
+    let Some(op_val) = args.get(2).and_then(|v| v.to_value(heap_ref)) else {
+        return builtin_runtime_error(out, heap, "query.or_where expects an operator string");
+    };
+    let Value::String(op) = op_val else {
+        return builtin_runtime_error(out, heap, "query.or_where expects an operator string");
+    };
+    let Some(value) = args.get(3).and_then(|v| v.to_value(heap_ref)) else {
+        return builtin_runtime_error(out, heap, "query.or_where expects a value");
+    };
+    let next = match query.or_where(column, op, value) {
+        Ok(next) => next,
+        Err(err) => return builtin_runtime_error(out, heap, err),
+    };
+    let value = Value::Query(Box::new(next));
+    let Some(native) = NativeValue::from_value(&value, heap) else {
+        return builtin_runtime_error(out, heap, "query.or_where result unsupported");
+    };
+    *out = native;
+    0
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn fuse_native_query_join(
+    heap: *mut NativeHeap,
+    args: *const NativeValue,
+    len: u64,
+    out: *mut NativeValue,
+) -> u8 {
+    let heap = unsafe { heap.as_mut() };
+    let Some(heap) = heap else {
+        return 2;
+    };
+    let Some(out) = (unsafe { out.as_mut() }) else {
+        return 2;
+    };
+    if len != 4 {
+        return builtin_runtime_error(out, heap, "query.join expects 4 arguments");
+    }
+    let args = unsafe { std::slice::from_raw_parts(args, len as usize) };
+    let heap_ref: &NativeHeap = heap;
+    let Some(query_val) = args.first().and_then(|v| v.to_value(heap_ref)) else {
+        return builtin_runtime_error(out, heap, "query.join expects a Query");
+    };
+    let Value::Query(query) = query_val else {
+        return builtin_runtime_error(out, heap, "query.join expects a Query");
+    };
+    let Some(table_val) = args.get(1).and_then(|v| v.to_value(heap_ref)) else {
+        return builtin_runtime_error(out, heap, "query.join expects a table name string");
+    };
+    let Value::String(table) = table_val else {
+        return builtin_runtime_error(out, heap, "query.join expects a table name string");
+    };
+    let Some(left_val) = args.get(2).and_then(|v| v.to_value(heap_ref)) else {
+        return builtin_runtime_error(out, heap, "query.join expects a left column string");
+    };
+    let Value::String(left) = left_val else {
+        return builtin_runtime_error(out, heap, "query.join expects a left column string");
+    };
+    let Some(right_val) = args.get(3).and_then(|v| v.to_value(heap_ref)) else {
+        return builtin_runtime_error(out, heap, "query.join expects a right column string");
+    };
+    let Value::String(right) = right_val else {
+        return builtin_runtime_error(out, heap, "query.join expects a right column string");
+    };
+    let next = match query.join(table, left, right) {
+        Ok(next) => next,
+        Err(err) => return builtin_runtime_error(out, heap, err),
+    };
+    let value = Value::Query(Box::new(next));
+    let Some(native) = NativeValue::from_value(&value, heap) else {
+        return builtin_runtime_error(out, heap, "query.join result unsupported");
+    };
+    *out = native;
+    0
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn fuse_native_query_left_join(
+    heap: *mut NativeHeap,
+    args: *const NativeValue,
+    len: u64,
+    out: *mut NativeValue,
+) -> u8 {
+    let heap = unsafe { heap.as_mut() };
+    let Some(heap) = heap else {
+        return 2;
+    };
+    let Some(out) = (unsafe { out.as_mut() }) else {
+        return 2;
+    };
+    if len != 4 {
+        return builtin_runtime_error(out, heap, "query.left_join expects 4 arguments");
+    }
+    let args = unsafe { std::slice::from_raw_parts(args, len as usize) };
+    let heap_ref: &NativeHeap = heap;
+    let Some(query_val) = args.first().and_then(|v| v.to_value(heap_ref)) else {
+        return builtin_runtime_error(out, heap, "query.left_join expects a Query");
+    };
+    let Value::Query(query) = query_val else {
+        return builtin_runtime_error(out, heap, "query.left_join expects a Query");
+    };
+    let Some(table_val) = args.get(1).and_then(|v| v.to_value(heap_ref)) else {
+        return builtin_runtime_error(out, heap, "query.left_join expects a table name string");
+    };
+    let Value::String(table) = table_val else {
+        return builtin_runtime_error(out, heap, "query.left_join expects a table name string");
+    };
+    let Some(left_val) = args.get(2).and_then(|v| v.to_value(heap_ref)) else {
+        return builtin_runtime_error(out, heap, "query.left_join expects a left column string");
+    };
+    let Value::String(left) = left_val else {
+        return builtin_runtime_error(out, heap, "query.left_join expects a left column string");
+    };
+    let Some(right_val) = args.get(3).and_then(|v| v.to_value(heap_ref)) else {
+        return builtin_runtime_error(out, heap, "query.left_join expects a right column string");
+    };
+    let Value::String(right) = right_val else {
+        return builtin_runtime_error(out, heap, "query.left_join expects a right column string");
+    };
+    let next = match query.left_join(table, left, right) {
+        Ok(next) => next,
+        Err(err) => return builtin_runtime_error(out, heap, err),
+    };
+    let value = Value::Query(Box::new(next));
+    let Some(native) = NativeValue::from_value(&value, heap) else {
+        return builtin_runtime_error(out, heap, "query.left_join result unsupported");
+    };
+    *out = native;
+    0
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn fuse_native_query_offset(
+    heap: *mut NativeHeap,
+    args: *const NativeValue,
+    len: u64,
+    out: *mut NativeValue,
+) -> u8 {
+    let heap = unsafe { heap.as_mut() };
+    let Some(heap) = heap else {
+        return 2;
+    };
+    let Some(out) = (unsafe { out.as_mut() }) else {
+        return 2;
+    };
+    if len != 2 {
+        return builtin_runtime_error(out, heap, "query.offset expects 2 arguments");
+    }
+    let args = unsafe { std::slice::from_raw_parts(args, len as usize) };
+    let heap_ref: &NativeHeap = heap;
+    let Some(query_val) = args.first().and_then(|v| v.to_value(heap_ref)) else {
+        return builtin_runtime_error(out, heap, "query.offset expects a Query");
+    };
+    let Value::Query(query) = query_val else {
+        return builtin_runtime_error(out, heap, "query.offset expects a Query");
+    };
+    let Some(offset_val) = args.get(1).and_then(|v| v.to_value(heap_ref)) else {
+        return builtin_runtime_error(out, heap, "query.offset expects an Int");
+    };
+    let offset = match offset_val {
+        Value::Int(v) => v,
+        Value::Float(v) => v as i64,
+        _ => return builtin_runtime_error(out, heap, "query.offset expects an Int"),
+    };
+    let next = match query.offset(offset) {
+        Ok(next) => next,
+        Err(err) => return builtin_runtime_error(out, heap, err),
+    };
+    let value = Value::Query(Box::new(next));
+    let Some(native) = NativeValue::from_value(&value, heap) else {
+        return builtin_runtime_error(out, heap, "query.offset result unsupported");
+    };
+    *out = native;
+    0
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn fuse_native_query_group_by(
+    heap: *mut NativeHeap,
+    args: *const NativeValue,
+    len: u64,
+    out: *mut NativeValue,
+) -> u8 {
+    let heap = unsafe { heap.as_mut() };
+    let Some(heap) = heap else {
+        return 2;
+    };
+    let Some(out) = (unsafe { out.as_mut() }) else {
+        return 2;
+    };
+    if len != 2 {
+        return builtin_runtime_error(out, heap, "query.group_by expects 2 arguments");
+    }
+    let args = unsafe { std::slice::from_raw_parts(args, len as usize) };
+    let heap_ref: &NativeHeap = heap;
+    let Some(query_val) = args.first().and_then(|v| v.to_value(heap_ref)) else {
+        return builtin_runtime_error(out, heap, "query.group_by expects a Query");
+    };
+    let Value::Query(query) = query_val else {
+        return builtin_runtime_error(out, heap, "query.group_by expects a Query");
+    };
+    let Some(cols_val) = args.get(1).and_then(|v| v.to_value(heap_ref)) else {
+        return builtin_runtime_error(out, heap, "query.group_by expects a list of strings");
+    };
+    let Value::List(items) = cols_val else {
+        return builtin_runtime_error(out, heap, "query.group_by expects a list of strings");
+    };
+    let mut columns = Vec::with_capacity(items.len());
+    for item in items {
+        match item {
+            Value::String(text) => columns.push(text),
+            _ => {
+                return builtin_runtime_error(
+                    out,
+                    heap,
+                    "query.group_by expects a list of strings",
+                );
+            }
+        }
+    }
+    let next = match query.group_by(columns) {
+        Ok(next) => next,
+        Err(err) => return builtin_runtime_error(out, heap, err),
+    };
+    let value = Value::Query(Box::new(next));
+    let Some(native) = NativeValue::from_value(&value, heap) else {
+        return builtin_runtime_error(out, heap, "query.group_by result unsupported");
+    };
+    *out = native;
+    0
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn fuse_native_query_having(
+    heap: *mut NativeHeap,
+    args: *const NativeValue,
+    len: u64,
+    out: *mut NativeValue,
+) -> u8 {
+    let heap = unsafe { heap.as_mut() };
+    let Some(heap) = heap else {
+        return 2;
+    };
+    let Some(out) = (unsafe { out.as_mut() }) else {
+        return 2;
+    };
+    if len != 4 {
+        return builtin_runtime_error(out, heap, "query.having expects 4 arguments");
+    }
+    let args = unsafe { std::slice::from_raw_parts(args, len as usize) };
+    let heap_ref: &NativeHeap = heap;
+    let Some(query_val) = args.first().and_then(|v| v.to_value(heap_ref)) else {
+        return builtin_runtime_error(out, heap, "query.having expects a Query");
+    };
+    let Value::Query(query) = query_val else {
+        return builtin_runtime_error(out, heap, "query.having expects a Query");
+    };
+    let Some(column_val) = args.get(1).and_then(|v| v.to_value(heap_ref)) else {
+        return builtin_runtime_error(out, heap, "query.having expects a column string");
+    };
+    let Value::String(column) = column_val else {
+        return builtin_runtime_error(out, heap, "query.having expects a column string");
+    };
+    let Some(op_val) = args.get(2).and_then(|v| v.to_value(heap_ref)) else {
+        return builtin_runtime_error(out, heap, "query.having expects an operator string");
+    };
+    let Value::String(op) = op_val else {
+        return builtin_runtime_error(out, heap, "query.having expects an operator string");
+    };
+    let Some(value) = args.get(3).and_then(|v| v.to_value(heap_ref)) else {
+        return builtin_runtime_error(out, heap, "query.having expects a value");
+    };
+    let next = match query.having(column, op, value) {
+        Ok(next) => next,
+        Err(err) => return builtin_runtime_error(out, heap, err),
+    };
+    let value = Value::Query(Box::new(next));
+    let Some(native) = NativeValue::from_value(&value, heap) else {
+        return builtin_runtime_error(out, heap, "query.having result unsupported");
+    };
+    *out = native;
+    0
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn fuse_native_query_aggregate(
+    heap: *mut NativeHeap,
+    args: *const NativeValue,
+    len: u64,
+    out: *mut NativeValue,
+) -> u8 {
+    let heap = unsafe { heap.as_mut() };
+    let Some(heap) = heap else {
+        return 2;
+    };
+    let Some(out) = (unsafe { out.as_mut() }) else {
+        return 2;
+    };
+    if len != 4 {
+        return builtin_runtime_error(out, heap, "query.aggregate expects 4 arguments");
+    }
+    let args = unsafe { std::slice::from_raw_parts(args, len as usize) };
+    let heap_ref: &NativeHeap = heap;
+    let Some(query_val) = args.first().and_then(|v| v.to_value(heap_ref)) else {
+        return builtin_runtime_error(out, heap, "query.aggregate expects a Query");
+    };
+    let Value::Query(query) = query_val else {
+        return builtin_runtime_error(out, heap, "query.aggregate expects a Query");
+    };
+    let Some(func_val) = args.get(1).and_then(|v| v.to_value(heap_ref)) else {
+        return builtin_runtime_error(out, heap, "query.aggregate expects a function string");
+    };
+    let Value::String(func) = func_val else {
+        return builtin_runtime_error(out, heap, "query.aggregate expects a function string");
+    };
+    let Some(column_val) = args.get(2).and_then(|v| v.to_value(heap_ref)) else {
+        return builtin_runtime_error(out, heap, "query.aggregate expects a column string");
+    };
+    let Value::String(column) = column_val else {
+        return builtin_runtime_error(out, heap, "query.aggregate expects a column string");
+    };
+    let Some(alias_val) = args.get(3).and_then(|v| v.to_value(heap_ref)) else {
+        return builtin_runtime_error(out, heap, "query.aggregate expects an alias string");
+    };
+    let Value::String(alias) = alias_val else {
+        return builtin_runtime_error(out, heap, "query.aggregate expects an alias string");
+    };
+    let next = match query.aggregate(func, column, alias) {
+        Ok(next) => next,
+        Err(err) => return builtin_runtime_error(out, heap, err),
+    };
+    let value = Value::Query(Box::new(next));
+    let Some(native) = NativeValue::from_value(&value, heap) else {
+        return builtin_runtime_error(out, heap, "query.aggregate result unsupported");
+    };
+    *out = native;
+    0
+}
+
 #[unsafe(no_mangle)]
 extern "C" fn fuse_native_query_insert(
     heap: *mut NativeHeap,
@@ -5006,24 +6294,164 @@ extern "C" fn fuse_native_query_insert(
     let Some(query_val) = args.get(0).and_then(|v| v.to_value(heap_ref)) else {
         return builtin_runtime_error(out, heap, "query.insert expects a Query");
     };
-    let Value::Query(query) = query_val else {
-        return builtin_runtime_error(out, heap, "query.insert expects a Query");
+    let Value::Query(query) = query_val else {
+        return builtin_runtime_error(out, heap, "query.insert expects a Query");
+    };
+    let Some(value) = args.get(1).and_then(|v| v.to_value(heap_ref)) else {
+        return builtin_runtime_error(out, heap, "query.insert expects a struct");
+    };
+    let next = match query.insert_struct(value) {
+        Ok(next) => next,
+        Err(err) => return builtin_runtime_error(out, heap, err),
+    };
+    let value = Value::Query(Box::new(next));
+    let Some(native) = NativeValue::from_value(&value, heap) else {
+        return builtin_runtime_error(out, heap, "query.insert result unsupported");
+    };
+    *out = native;
+    0
+}
+
+/// Shared body of the query builder hostcalls whose arguments are plain
+/// values: converts the receiver and arguments, then stores the next `Query`.
+fn native_query_builder(
+    heap: *mut NativeHeap,
+    args: *const NativeValue,
+    len: u64,
+    out: *mut NativeValue,
+    builtin: &str,
+    build: impl FnOnce(&crate::db::Query, &[Value]) -> Result<crate::db::Query, String>,
+) -> u8 {
+    let heap = unsafe { heap.as_mut() };
+    let Some(heap) = heap else {
+        return 2;
+    };
+    let Some(out) = (unsafe { out.as_mut() }) else {
+        return 2;
     };
-    let Some(value) = args.get(1).and_then(|v| v.to_value(heap_ref)) else {
-        return builtin_runtime_error(out, heap, "query.insert expects a struct");
+    let args = if len == 0 {
+        &[][..]
+    } else {
+        unsafe { std::slice::from_raw_parts(args, len as usize) }
     };
-    let next = match query.insert_struct(value) {
+    let heap_ref: &NativeHeap = heap;
+    let Some(Value::Query(query)) = args.first().and_then(|v| v.to_value(heap_ref)) else {
+        return builtin_runtime_error(out, heap, format!("{builtin} expects a Query"));
+    };
+    let mut values = Vec::with_capacity(args.len() - 1);
+    for arg in &args[1..] {
+        let Some(value) = arg.to_value(heap_ref) else {
+            return builtin_runtime_error(out, heap, format!("{builtin} argument unsupported"));
+        };
+        values.push(value);
+    }
+    let next = match build(&query, &values) {
         Ok(next) => next,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
-    let value = Value::Query(next);
+    let value = Value::Query(Box::new(next));
     let Some(native) = NativeValue::from_value(&value, heap) else {
-        return builtin_runtime_error(out, heap, "query.insert result unsupported");
+        return builtin_runtime_error(out, heap, format!("{builtin} result unsupported"));
     };
     *out = native;
     0
 }
 
+fn native_column_list(builtin: &str, value: &Value) -> Result<Vec<String>, String> {
+    let Value::List(items) = value else {
+        return Err(format!("{builtin} expects a list of strings"));
+    };
+    items
+        .iter()
+        .map(|item| match item {
+            Value::String(text) => Ok(text.clone()),
+            _ => Err(format!("{builtin} expects a list of strings")),
+        })
+        .collect()
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn fuse_native_query_insert_many(
+    heap: *mut NativeHeap,
+    args: *const NativeValue,
+    len: u64,
+    out: *mut NativeValue,
+) -> u8 {
+    native_query_builder(
+        heap,
+        args,
+        len,
+        out,
+        "query.insert_many",
+        |query, args| match args {
+            [Value::List(rows)] => query.insert_many(rows.clone()),
+            [_] => Err("query.insert_many expects a list of structs".to_string()),
+            _ => Err("query.insert_many expects 2 arguments".to_string()),
+        },
+    )
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn fuse_native_query_on_conflict(
+    heap: *mut NativeHeap,
+    args: *const NativeValue,
+    len: u64,
+    out: *mut NativeValue,
+) -> u8 {
+    native_query_builder(
+        heap,
+        args,
+        len,
+        out,
+        "query.on_conflict",
+        |query, args| match args {
+            [target] => query.on_conflict(native_column_list("query.on_conflict", target)?),
+            _ => Err("query.on_conflict expects 2 arguments".to_string()),
+        },
+    )
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn fuse_native_query_do_update(
+    heap: *mut NativeHeap,
+    args: *const NativeValue,
+    len: u64,
+    out: *mut NativeValue,
+) -> u8 {
+    native_query_builder(
+        heap,
+        args,
+        len,
+        out,
+        "query.do_update",
+        |query, args| match args {
+            [] => query.do_update(None),
+            [columns] => query.do_update(Some(native_column_list("query.do_update", columns)?)),
+            _ => Err("query.do_update expects 1 or 2 arguments".to_string()),
+        },
+    )
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn fuse_native_query_do_nothing(
+    heap: *mut NativeHeap,
+    args: *const NativeValue,
+    len: u64,
+    out: *mut NativeValue,
+) -> u8 {
+    native_query_builder(
+        heap,
+        args,
+        len,
+        out,
+        "query.do_nothing",
+        |query, args| match args {
+            [] => query.do_nothing(),
+            _ => Err("query.do_nothing expects 1 argument".to_string()),
+        },
+    )
+}
+
 #[unsafe(no_mangle)]
 extern "C" fn fuse_native_query_upsert(
     heap: *mut NativeHeap,
@@ -5056,7 +6484,7 @@ extern "C" fn fuse_native_query_upsert(
         Ok(next) => next,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
-    let value = Value::Query(next);
+    let value = Value::Query(Box::new(next));
     let Some(native) = NativeValue::from_value(&value, heap) else {
         return builtin_runtime_error(out, heap, "query.upsert result unsupported");
     };
@@ -5102,7 +6530,7 @@ extern "C" fn fuse_native_query_update(
         Ok(next) => next,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
-    let value = Value::Query(next);
+    let value = Value::Query(Box::new(next));
     let Some(native) = NativeValue::from_value(&value, heap) else {
         return builtin_runtime_error(out, heap, "query.update result unsupported");
     };
@@ -5139,7 +6567,7 @@ extern "C" fn fuse_native_query_delete(
         Ok(next) => next,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
-    let value = Value::Query(next);
+    let value = Value::Query(Box::new(next));
     let Some(native) = NativeValue::from_value(&value, heap) else {
         return builtin_runtime_error(out, heap, "query.delete result unsupported");
     };
@@ -5180,15 +6608,15 @@ extern "C" fn fuse_native_query_count(
         Ok(result) => result,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
-    let pool_size = match db_pool_size(heap) {
-        Ok(size) => size,
+    let options = match db_options(heap) {
+        Ok(options) => options,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
     let url = match db_url() {
         Ok(url) => url,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
-    let db = match heap.db_mut(url, pool_size) {
+    let db = match heap.db_mut(url, &options) {
         Ok(db) => db,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
@@ -5250,6 +6678,7 @@ fn decode_query_row_typed(
             *out = native;
             return Err(1);
         };
+        let value = crate::runtime_decimal::coerce_decimal_column(&field.ty, value);
         let path = format!("{decl_name}.{}", field.name);
         match validate_value(&value, &field.ty, &path) {
             ValidateResult::Ok => {}
@@ -5297,23 +6726,23 @@ extern "C" fn fuse_native_query_one(
     let Value::Query(query) = query_val else {
         return builtin_runtime_error(out, heap, "query.one expects a Query");
     };
-    let (sql, params) = match query.build_sql(Some(1)) {
+    let statements = match query.build_statements(Some(1)) {
         Ok(result) => result,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
-    let pool_size = match db_pool_size(heap) {
-        Ok(size) => size,
+    let options = match db_options(heap) {
+        Ok(options) => options,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
     let url = match db_url() {
         Ok(url) => url,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
-    let db = match heap.db_mut(url, pool_size) {
+    let db = match heap.db_mut(url, &options) {
         Ok(db) => db,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
-    let rows = match db.query_params(&sql, &params) {
+    let rows = match db.query_statements(&statements) {
         Ok(rows) => rows,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
@@ -5360,23 +6789,23 @@ extern "C" fn fuse_native_query_one_typed(
     let Value::String(type_name) = type_name_val else {
         return builtin_runtime_error(out, heap, "query.one_typed expects a type name string");
     };
-    let (sql, params) = match query.build_sql(Some(1)) {
+    let statements = match query.build_statements(Some(1)) {
         Ok(result) => result,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
-    let pool_size = match db_pool_size(heap) {
-        Ok(size) => size,
+    let options = match db_options(heap) {
+        Ok(options) => options,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
     let url = match db_url() {
         Ok(url) => url,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
-    let db = match heap.db_mut(url, pool_size) {
+    let db = match heap.db_mut(url, &options) {
         Ok(db) => db,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
-    let rows = match db.query_params(&sql, &params) {
+    let rows = match db.query_statements(&statements) {
         Ok(rows) => rows,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
@@ -5420,23 +6849,23 @@ extern "C" fn fuse_native_query_all(
     let Value::Query(query) = query_val else {
         return builtin_runtime_error(out, heap, "query.all expects a Query");
     };
-    let (sql, params) = match query.build_sql(None) {
+    let statements = match query.build_statements(None) {
         Ok(result) => result,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
-    let pool_size = match db_pool_size(heap) {
-        Ok(size) => size,
+    let options = match db_options(heap) {
+        Ok(options) => options,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
     let url = match db_url() {
         Ok(url) => url,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
-    let db = match heap.db_mut(url, pool_size) {
+    let db = match heap.db_mut(url, &options) {
         Ok(db) => db,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
-    let rows = match db.query_params(&sql, &params) {
+    let rows = match db.query_statements(&statements) {
         Ok(rows) => rows,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
@@ -5480,23 +6909,23 @@ extern "C" fn fuse_native_query_all_typed(
     let Value::String(type_name) = type_name_val else {
         return builtin_runtime_error(out, heap, "query.all_typed expects a type name string");
     };
-    let (sql, params) = match query.build_sql(None) {
+    let statements = match query.build_statements(None) {
         Ok(result) => result,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
-    let pool_size = match db_pool_size(heap) {
-        Ok(size) => size,
+    let options = match db_options(heap) {
+        Ok(options) => options,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
     let url = match db_url() {
         Ok(url) => url,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
-    let db = match heap.db_mut(url, pool_size) {
+    let db = match heap.db_mut(url, &options) {
         Ok(db) => db,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
-    let rows = match db.query_params(&sql, &params) {
+    let rows = match db.query_statements(&statements) {
         Ok(rows) => rows,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
@@ -5541,29 +6970,129 @@ extern "C" fn fuse_native_query_exec(
     let Value::Query(query) = query_val else {
         return builtin_runtime_error(out, heap, "query.exec expects a Query");
     };
-    let (sql, params) = match query.build_sql(None) {
+    let statements = match query.build_statements(None) {
         Ok(result) => result,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
-    let pool_size = match db_pool_size(heap) {
-        Ok(size) => size,
+    let options = match db_options(heap) {
+        Ok(options) => options,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
     let url = match db_url() {
         Ok(url) => url,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
-    let db = match heap.db_mut(url, pool_size) {
+    let db = match heap.db_mut(url, &options) {
         Ok(db) => db,
         Err(err) => return builtin_runtime_error(out, heap, err),
     };
-    if let Err(err) = db.exec_params(&sql, &params) {
+    if let Err(err) = db.exec_statements(&statements) {
         return builtin_runtime_error(out, heap, err);
     }
     *out = NativeValue::int(0);
     0
 }
 
+#[unsafe(no_mangle)]
+extern "C" fn fuse_native_query_execute(
+    heap: *mut NativeHeap,
+    args: *const NativeValue,
+    len: u64,
+    out: *mut NativeValue,
+) -> u8 {
+    let heap = unsafe { heap.as_mut() };
+    let Some(heap) = heap else {
+        return 2;
+    };
+    let Some(out) = (unsafe { out.as_mut() }) else {
+        return 2;
+    };
+    if len != 1 {
+        return builtin_runtime_error(out, heap, "query.execute expects 1 argument");
+    }
+    let args = unsafe { std::slice::from_raw_parts(args, len as usize) };
+    let heap_ref: &NativeHeap = heap;
+    let Some(Value::Query(query)) = args.first().and_then(|v| v.to_value(heap_ref)) else {
+        return builtin_runtime_error(out, heap, "query.execute expects a Query");
+    };
+    let statements = match query.build_statements(None) {
+        Ok(result) => result,
+        Err(err) => return builtin_runtime_error(out, heap, err),
+    };
+    let options = match db_options(heap) {
+        Ok(options) => options,
+        Err(err) => return builtin_runtime_error(out, heap, err),
+    };
+    let url = match db_url() {
+        Ok(url) => url,
+        Err(err) => return builtin_runtime_error(out, heap, err),
+    };
+    let db = match heap.db_mut(url, &options) {
+        Ok(db) => db,
+        Err(err) => return builtin_runtime_error(out, heap, err),
+    };
+    let result = match db.exec_statements(&statements) {
+        Ok(result) => result,
+        Err(err) => return builtin_runtime_error(out, heap, err),
+    };
+    let value = crate::db::write_result_value(result);
+    let Some(native) = NativeValue::from_value(&value, heap) else {
+        return builtin_runtime_error(out, heap, "query.execute result unsupported");
+    };
+    *out = native;
+    0
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn fuse_native_query_returning(
+    heap: *mut NativeHeap,
+    args: *const NativeValue,
+    len: u64,
+    out: *mut NativeValue,
+) -> u8 {
+    let heap = unsafe { heap.as_mut() };
+    let Some(heap) = heap else {
+        return 2;
+    };
+    let Some(out) = (unsafe { out.as_mut() }) else {
+        return 2;
+    };
+    if len != 2 {
+        return builtin_runtime_error(out, heap, "query.returning expects 2 arguments");
+    }
+    let args = unsafe { std::slice::from_raw_parts(args, len as usize) };
+    let heap_ref: &NativeHeap = heap;
+    let Some(Value::Query(query)) = args.first().and_then(|v| v.to_value(heap_ref)) else {
+        return builtin_runtime_error(out, heap, "query.returning expects a Query");
+    };
+    let Some(Value::List(items)) = args.get(1).and_then(|v| v.to_value(heap_ref)) else {
+        return builtin_runtime_error(out, heap, "query.returning expects a list of strings");
+    };
+    let mut columns = Vec::with_capacity(items.len());
+    for item in items {
+        match item {
+            Value::String(text) => columns.push(text),
+            _ => {
+                return builtin_runtime_error(
+                    out,
+                    heap,
+                    "query.returning expects a list of strings",
+                );
+            }
+        }
+    }
+    let next = match query.returning(columns) {
+        Ok(next) => next,
+        Err(err) => return builtin_runtime_error(out, heap, err),
+    };
+    let value = Value::Query(Box::new(next));
+    let Some(native) = NativeValue::from_value(&value, heap) else {
+        return builtin_runtime_error(out, heap, "query.returning result unsupported");
+    };
+    *out = native;
+    0
+}
+
 #[unsafe(no_mangle)]
 extern "C" fn fuse_native_query_sql(
     heap: *mut NativeHeap,
@@ -5706,10 +7235,11 @@ fn compile_function<M: Module>(
         Some(starts) => starts,
         None => jit_fail!(func, None::<usize>, None::<&Instr>, "invalid control flow"),
     };
-    let (local_types, entry_stacks) = match analyze_types(func, param_types, &starts, program) {
-        Some(result) => result,
-        None => jit_fail!(func, None::<usize>, None::<&Instr>, "type analysis failed"),
-    };
+    let (local_types, entry_stacks, reachable) =
+        match analyze_types(func, param_types, &starts, program) {
+            Some(result) => result,
+            None => jit_fail!(func, None::<usize>, None::<&Instr>, "type analysis failed"),
+        };
     let mut ctx = module.make_context();
     let pointer_ty = module.target_config().pointer_type();
     ctx.func.signature = entry_signature(module);
@@ -5803,6 +7333,12 @@ fn compile_function<M: Module>(
         if block_idx != 0 {
             builder.switch_to_block(block);
         }
+        if !reachable.get(block_idx).copied().unwrap_or(true) {
+            // Dead code, e.g. the tail of an `if` whose body ends in `break`.
+            // Type analysis never entered it, so there is no stack to compile against.
+            builder.ins().trap(TrapCode::unwrap_user(1));
+            continue;
+        }
         let end = if block_idx + 1 < starts.len() {
             starts[block_idx + 1]
         } else {
@@ -6708,7 +8244,7 @@ fn compile_function<M: Module>(
                     terminated = true;
                     break;
                 }
-                Instr::IterInit => {
+                Instr::IterInit { entries } => {
                     let value = stack.pop()?;
                     let value_ptr = if value.kind == JitType::Value {
                         value.value
@@ -6728,7 +8264,12 @@ fn compile_function<M: Module>(
                         NATIVE_VALUE_ALIGN_SHIFT,
                     ));
                     let iter_out_ptr = builder.ins().stack_addr(pointer_ty, iter_slot, 0);
-                    let func_ref = module.declare_func_in_func(hostcalls.iter_init, builder.func);
+                    let init = if *entries {
+                        hostcalls.iter_entries_init
+                    } else {
+                        hostcalls.iter_init
+                    };
+                    let func_ref = module.declare_func_in_func(init, builder.func);
                     let call = builder
                         .ins()
                         .call(func_ref, &[heap_ptr, value_ptr, iter_out_ptr]);
@@ -6904,6 +8445,22 @@ fn compile_function<M: Module>(
                         args.push(stack.pop()?);
                     }
                     args.reverse();
+                    if matches!(kind, CallKind::Builtin)
+                        && (crate::runtime_capabilities::is_time_value_builtin(name)
+                            || crate::runtime_decimal::is_decimal_builtin(name)
+                            || crate::runtime_tasks::is_task_builtin(name)
+                            || crate::runtime_channels::is_channel_builtin(name)
+                            || crate::runtime_ranges::is_range_builtin(name))
+                    {
+                        let handle = NativeValue::intern_string(name.clone(), heap).payload;
+                        args.insert(
+                            0,
+                            StackValue {
+                                value: builder.ins().iconst(types::I64, handle as i64),
+                                kind: JitType::Heap,
+                            },
+                        );
+                    }
                     let count = u32::try_from(args.len()).ok()?;
                     let base = if count == 0 {
                         builder.ins().iconst(pointer_ty, 0)
@@ -6954,26 +8511,56 @@ fn compile_function<M: Module>(
                                 "time.sleep" => hostcalls.builtin_time_sleep,
                                 "time.format" => hostcalls.builtin_time_format,
                                 "time.parse" => hostcalls.builtin_time_parse,
+                                name if crate::runtime_capabilities::is_time_value_builtin(
+                                    name,
+                                ) =>
+                                {
+                                    hostcalls.builtin_time_value
+                                }
+                                name if crate::runtime_decimal::is_decimal_builtin(name) => {
+                                    hostcalls.builtin_decimal
+                                }
+                                name if crate::runtime_tasks::is_task_builtin(name) => {
+                                    hostcalls.builtin_task
+                                }
+                                name if crate::runtime_channels::is_channel_builtin(name) => {
+                                    hostcalls.builtin_channel
+                                }
                                 "crypto.hash" => hostcalls.builtin_crypto_hash,
                                 "crypto.hmac" => hostcalls.builtin_crypto_hmac,
                                 "crypto.random_bytes" => hostcalls.builtin_crypto_random_bytes,
                                 "crypto.constant_time_eq" => {
                                     hostcalls.builtin_crypto_constant_time_eq
                                 }
-                                "range" => hostcalls.range,
+                                name if crate::runtime_ranges::is_range_builtin(name) => {
+                                    hostcalls.range
+                                }
                                 "db.exec" => hostcalls.db_exec,
+                                "db.execute" => hostcalls.db_execute,
                                 "db.query" => hostcalls.db_query,
                                 "db.one" => hostcalls.db_one,
                                 "db.from" => hostcalls.db_from,
                                 "db.tx_begin" => hostcalls.db_tx_begin,
                                 "db.tx_commit" => hostcalls.db_tx_commit,
                                 "db.tx_rollback" => hostcalls.db_tx_rollback,
+                                "db.rollback" => hostcalls.db_rollback,
                                 "query.select" => hostcalls.query_select,
                                 "query.where" => hostcalls.query_where,
                                 "query.order_by" => hostcalls.query_order_by,
                                 "query.limit" => hostcalls.query_limit,
+                                "query.or_where" => hostcalls.query_or_where,
+                                "query.join" => hostcalls.query_join,
+                                "query.left_join" => hostcalls.query_left_join,
+                                "query.offset" => hostcalls.query_offset,
+                                "query.group_by" => hostcalls.query_group_by,
+                                "query.having" => hostcalls.query_having,
+                                "query.aggregate" => hostcalls.query_aggregate,
                                 "query.insert" => hostcalls.query_insert,
                                 "query.upsert" => hostcalls.query_upsert,
+                                "query.insert_many" => hostcalls.query_insert_many,
+                                "query.on_conflict" => hostcalls.query_on_conflict,
+                                "query.do_update" => hostcalls.query_do_update,
+                                "query.do_nothing" => hostcalls.query_do_nothing,
                                 "query.update" => hostcalls.query_update,
                                 "query.delete" => hostcalls.query_delete,
                                 "query.count" => hostcalls.query_count,
@@ -6981,7 +8568,11 @@ fn compile_function<M: Module>(
                                 "query.one_typed" => hostcalls.query_one_typed,
                                 "query.all" => hostcalls.query_all,
                                 "query.all_typed" => hostcalls.query_all_typed,
+                                "db.query_typed" => hostcalls.db_query_typed,
+                                "db.one_typed" => hostcalls.db_one_typed,
                                 "query.exec" => hostcalls.query_exec,
+                                "query.execute" => hostcalls.query_execute,
+                                "query.returning" => hostcalls.query_returning,
                                 "query.sql" => hostcalls.query_sql,
                                 "query.params" => hostcalls.query_params,
                                 "json.encode" => hostcalls.json_encode,
@@ -6991,6 +8582,7 @@ fn compile_function<M: Module>(
                                 "html.node" => hostcalls.html_node,
                                 "html.render" => hostcalls.html_render,
                                 "svg.inline" => hostcalls.svg_inline,
+                                "struct.update" => hostcalls.struct_update,
                                 _ => jit_fail!(
                                     func,
                                     Some(ip),
@@ -7360,6 +8952,64 @@ fn compile_function<M: Module>(
                             value: builder.ins().fneg(value.value),
                             kind: JitType::Float,
                         }),
+                        JitType::Value => {
+                            // Dynamic operand (for example a Decimal): negate as `0 - value`.
+                            let zero = StackValue {
+                                value: builder.ins().iconst(types::I64, 0),
+                                kind: JitType::Int,
+                            };
+                            let slot = builder.create_sized_stack_slot(StackSlotData::new(
+                                StackSlotKind::ExplicitSlot,
+                                (NATIVE_VALUE_SIZE * 2) as u32,
+                                NATIVE_VALUE_ALIGN_SHIFT,
+                            ));
+                            let base = builder.ins().stack_addr(pointer_ty, slot, 0);
+                            store_native_value(&mut builder, base, 0, zero)?;
+                            store_native_value(&mut builder, base, NATIVE_VALUE_SIZE, value)?;
+                            let len_val = builder.ins().iconst(types::I64, 2);
+                            let out_slot = builder.create_sized_stack_slot(StackSlotData::new(
+                                StackSlotKind::ExplicitSlot,
+                                NATIVE_VALUE_SIZE as u32,
+                                NATIVE_VALUE_ALIGN_SHIFT,
+                            ));
+                            let neg_out_ptr = builder.ins().stack_addr(pointer_ty, out_slot, 0);
+                            let func_ref = module.declare_func_in_func(hostcalls.sub, builder.func);
+                            let call = builder
+                                .ins()
+                                .call(func_ref, &[heap_ptr, base, len_val, neg_out_ptr]);
+                            let status = builder.inst_results(call)[0];
+                            let ok_idx = *block_for_start.get(&(ip + 1))?;
+                            let mut ok_stack = stack.clone();
+                            ok_stack.push(StackValue {
+                                value: neg_out_ptr,
+                                kind: JitType::Value,
+                            });
+                            let ok_args = coerce_stack_args(
+                                &mut builder,
+                                pointer_ty,
+                                &ok_stack,
+                                entry_stacks.get(ok_idx)?,
+                            )?;
+                            let err_block = builder.create_block();
+                            builder.append_block_param(err_block, types::I8);
+                            builder.append_block_param(err_block, pointer_ty);
+                            let is_ok = builder.ins().icmp_imm(IntCC::Equal, status, 0);
+                            let err_args = [BlockArg::Value(status), BlockArg::Value(neg_out_ptr)];
+                            builder.ins().brif(
+                                is_ok,
+                                blocks[ok_idx],
+                                &ok_args,
+                                err_block,
+                                &err_args,
+                            );
+                            builder.switch_to_block(err_block);
+                            let status_val = builder.block_params(err_block)[0];
+                            let err_out_ptr = builder.block_params(err_block)[1];
+                            copy_native_value(&mut builder, err_out_ptr, out_ptr);
+                            builder.ins().return_(&[status_val]);
+                            terminated = true;
+                            break;
+                        }
                         _ => return None,
                     }
                 }
@@ -7432,8 +9082,66 @@ fn compile_function<M: Module>(
                 Instr::Sub | Instr::Mul | Instr::Div | Instr::Mod => {
                     let rhs = stack.pop()?;
                     let lhs = stack.pop()?;
-                    let out = numeric_binop(&mut builder, &lhs, &rhs, &func.code[ip])?;
-                    stack.push(out);
+                    if let Some(out) = numeric_binop(&mut builder, &lhs, &rhs, &func.code[ip]) {
+                        stack.push(out);
+                        continue;
+                    }
+                    if !dynamic_operands(lhs.kind, rhs.kind) {
+                        return None;
+                    }
+                    let slot = builder.create_sized_stack_slot(StackSlotData::new(
+                        StackSlotKind::ExplicitSlot,
+                        (NATIVE_VALUE_SIZE * 2) as u32,
+                        NATIVE_VALUE_ALIGN_SHIFT,
+                    ));
+                    let base = builder.ins().stack_addr(pointer_ty, slot, 0);
+                    store_native_value(&mut builder, base, 0, lhs)?;
+                    store_native_value(&mut builder, base, NATIVE_VALUE_SIZE, rhs)?;
+                    let len_val = builder.ins().iconst(types::I64, 2);
+                    let out_slot = builder.create_sized_stack_slot(StackSlotData::new(
+                        StackSlotKind::ExplicitSlot,
+                        NATIVE_VALUE_SIZE as u32,
+                        NATIVE_VALUE_ALIGN_SHIFT,
+                    ));
+                    let arith_out_ptr = builder.ins().stack_addr(pointer_ty, out_slot, 0);
+                    let hostcall = match func.code[ip] {
+                        Instr::Sub => hostcalls.sub,
+                        Instr::Mul => hostcalls.mul,
+                        Instr::Div => hostcalls.div,
+                        _ => hostcalls.rem,
+                    };
+                    let func_ref = module.declare_func_in_func(hostcall, builder.func);
+                    let call = builder
+                        .ins()
+                        .call(func_ref, &[heap_ptr, base, len_val, arith_out_ptr]);
+                    let status = builder.inst_results(call)[0];
+                    let ok_idx = *block_for_start.get(&(ip + 1))?;
+                    let mut ok_stack = stack.clone();
+                    ok_stack.push(StackValue {
+                        value: arith_out_ptr,
+                        kind: JitType::Value,
+                    });
+                    let ok_args = coerce_stack_args(
+                        &mut builder,
+                        pointer_ty,
+                        &ok_stack,
+                        entry_stacks.get(ok_idx)?,
+                    )?;
+                    let err_block = builder.create_block();
+                    builder.append_block_param(err_block, types::I8);
+                    builder.append_block_param(err_block, pointer_ty);
+                    let is_ok = builder.ins().icmp_imm(IntCC::Equal, status, 0);
+                    let err_args = [BlockArg::Value(status), BlockArg::Value(arith_out_ptr)];
+                    builder
+                        .ins()
+                        .brif(is_ok, blocks[ok_idx], &ok_args, err_block, &err_args);
+                    builder.switch_to_block(err_block);
+                    let status_val = builder.block_params(err_block)[0];
+                    let err_out_ptr = builder.block_params(err_block)[1];
+                    copy_native_value(&mut builder, err_out_ptr, out_ptr);
+                    builder.ins().return_(&[status_val]);
+                    terminated = true;
+                    break;
                 }
                 Instr::Eq | Instr::NotEq | Instr::Lt | Instr::LtEq | Instr::Gt | Instr::GtEq => {
                     let rhs = stack.pop()?;
@@ -7442,7 +9150,9 @@ fn compile_function<M: Module>(
                         stack.push(out);
                         continue;
                     }
-                    if !matches!(func.code[ip], Instr::Eq | Instr::NotEq) {
+                    if !matches!(func.code[ip], Instr::Eq | Instr::NotEq)
+                        && !dynamic_operands(lhs.kind, rhs.kind)
+                    {
                         return None;
                     }
                     let slot = builder.create_sized_stack_slot(StackSlotData::new(
@@ -7462,7 +9172,11 @@ fn compile_function<M: Module>(
                     let cmp_out_ptr = builder.ins().stack_addr(pointer_ty, out_slot, 0);
                     let func_ref = match func.code[ip] {
                         Instr::Eq => module.declare_func_in_func(hostcalls.eq, builder.func),
-                        _ => module.declare_func_in_func(hostcalls.not_eq, builder.func),
+                        Instr::NotEq => module.declare_func_in_func(hostcalls.not_eq, builder.func),
+                        Instr::Lt => module.declare_func_in_func(hostcalls.lt, builder.func),
+                        Instr::LtEq => module.declare_func_in_func(hostcalls.lt_eq, builder.func),
+                        Instr::Gt => module.declare_func_in_func(hostcalls.gt, builder.func),
+                        _ => module.declare_func_in_func(hostcalls.gt_eq, builder.func),
                     };
                     let call = builder
                         .ins()
@@ -7832,7 +9546,7 @@ fn block_starts(code: &[Instr]) -> Option<Vec<usize>> {
                     starts.insert(ip + 1);
                 }
             }
-            Instr::IterInit => {
+            Instr::IterInit { .. } => {
                 if ip + 1 < code.len() {
                     starts.insert(ip + 1);
                 }
@@ -7851,9 +9565,18 @@ fn block_starts(code: &[Instr]) -> Option<Vec<usize>> {
                     starts.insert(ip + 1);
                 }
             }
-            Instr::Add
+            Instr::Neg
+            | Instr::Add
+            | Instr::Sub
+            | Instr::Mul
+            | Instr::Div
+            | Instr::Mod
             | Instr::Eq
             | Instr::NotEq
+            | Instr::Lt
+            | Instr::LtEq
+            | Instr::Gt
+            | Instr::GtEq
             | Instr::GetField { .. }
             | Instr::GetIndex
             | Instr::SetIndex
@@ -7904,18 +9627,33 @@ fn block_starts(code: &[Instr]) -> Option<Vec<usize>> {
                     | "crypto.constant_time_eq"
                     | "range"
                     | "db.exec"
+                    | "db.execute"
                     | "db.query"
                     | "db.one"
+                    | "db.query_typed"
+                    | "db.one_typed"
                     | "db.from"
                     | "db.tx_begin"
                     | "db.tx_commit"
                     | "db.tx_rollback"
+                    | "db.rollback"
                     | "query.select"
                     | "query.where"
                     | "query.order_by"
                     | "query.limit"
+                    | "query.or_where"
+                    | "query.join"
+                    | "query.left_join"
+                    | "query.offset"
+                    | "query.group_by"
+                    | "query.having"
+                    | "query.aggregate"
                     | "query.insert"
                     | "query.upsert"
+                    | "query.insert_many"
+                    | "query.on_conflict"
+                    | "query.do_update"
+                    | "query.do_nothing"
                     | "query.update"
                     | "query.delete"
                     | "query.count"
@@ -7924,6 +9662,8 @@ fn block_starts(code: &[Instr]) -> Option<Vec<usize>> {
                     | "query.all"
                     | "query.all_typed"
                     | "query.exec"
+                    | "query.execute"
+                    | "query.returning"
                     | "query.sql"
                     | "query.params"
                     | "json.encode"
@@ -7933,7 +9673,12 @@ fn block_starts(code: &[Instr]) -> Option<Vec<usize>> {
                     | "html.node"
                     | "html.render"
                     | "svg.inline"
-            ) =>
+                    | "struct.update"
+            ) || crate::runtime_capabilities::is_time_value_builtin(name)
+                || crate::runtime_decimal::is_decimal_builtin(name)
+                || crate::runtime_tasks::is_task_builtin(name)
+                || crate::runtime_channels::is_channel_builtin(name)
+                || crate::runtime_ranges::is_range_builtin(name) =>
             {
                 if ip + 1 < code.len() {
                     starts.insert(ip + 1);
@@ -8101,7 +9846,7 @@ fn store_native_value(
 
 fn return_kind(ty: &TypeRef, program: &IrProgram) -> Option<ReturnKind> {
     match &ty.kind {
-        TypeRefKind::Simple(name) if name.name == "Int" => Some(ReturnKind::Int),
+        TypeRefKind::Simple(name) if is_int_backed(&name.name) => Some(ReturnKind::Int),
         TypeRefKind::Simple(name) if name.name == "Bool" => Some(ReturnKind::Bool),
         TypeRefKind::Simple(name) if name.name == "Float" => Some(ReturnKind::Float),
         TypeRefKind::Simple(name) if name.name == "Unit" => Some(ReturnKind::Value),
@@ -8116,7 +9861,7 @@ fn return_kind(ty: &TypeRef, program: &IrProgram) -> Option<ReturnKind> {
         }
         TypeRefKind::Optional(_) => Some(ReturnKind::Value),
         TypeRefKind::Result { ok, .. } => return_kind(ok, program),
-        TypeRefKind::Refined { base, .. } if base.name == "Int" => Some(ReturnKind::Int),
+        TypeRefKind::Refined { base, .. } if is_int_backed(&base.name) => Some(ReturnKind::Int),
         TypeRefKind::Refined { base, .. } if base.name == "Float" => Some(ReturnKind::Float),
         TypeRefKind::Refined { base, .. } if base.name == "Unit" => Some(ReturnKind::Value),
         TypeRefKind::Refined { base, .. } if base.name == "String" => Some(ReturnKind::Heap),
@@ -8126,7 +9871,10 @@ fn return_kind(ty: &TypeRef, program: &IrProgram) -> Option<ReturnKind> {
             Some(ReturnKind::Heap)
         }
         TypeRefKind::Generic { base, .. }
-            if base.name == "List" || base.name == "Map" || base.name == "Task" =>
+            if matches!(
+                base.name.as_str(),
+                "List" | "Map" | "Task" | "Channel" | "Range"
+            ) =>
         {
             Some(ReturnKind::Heap)
         }
@@ -8138,6 +9886,7 @@ fn return_kind(ty: &TypeRef, program: &IrProgram) -> Option<ReturnKind> {
             let ok = args.get(0)?;
             return_kind(ok, program)
         }
+        TypeRefKind::Tuple(_) => Some(ReturnKind::Heap),
         _ => None,
     }
 }
@@ -8152,9 +9901,13 @@ fn return_jit_kind(ret: Option<&TypeRef>, program: &IrProgram) -> Option<JitType
 
 fn builtin_result_kind(name: &str) -> JitType {
     match name {
-        "time.now" => JitType::Int,
-        "query.count" => JitType::Int,
+        "time.now" | "time.instant" | "time.today" | "time.from_unix_ms" | "time.unix_ms"
+        | "time.millis" | "time.seconds" | "time.minutes" | "time.hours" | "time.days"
+        | "time.to_millis" | "time.date_of" | "time.start_of" | "time.instant_of"
+        | "time.local_date" => JitType::Int,
+        "query.count" | "decimal.scale" => JitType::Int,
         "crypto.constant_time_eq" => JitType::Bool,
+        "struct.update" => JitType::Struct,
         _ => JitType::Value,
     }
 }
@@ -8166,19 +9919,28 @@ fn jit_kind_for_type_ref(ty: &TypeRef, program: &IrProgram) -> JitType {
         TypeRefKind::Result { .. } => JitType::Value,
         TypeRefKind::Refined { base, .. } => jit_kind_for_name(&base.name, program),
         TypeRefKind::Generic { base, .. }
-            if base.name == "List" || base.name == "Map" || base.name == "Task" =>
+            if matches!(
+                base.name.as_str(),
+                "List" | "Map" | "Task" | "Channel" | "Range"
+            ) =>
         {
             JitType::Heap
         }
         TypeRefKind::Generic { base, .. } if base.name == "Option" => JitType::Value,
         TypeRefKind::Generic { base, .. } if base.name == "Result" => JitType::Value,
+        TypeRefKind::Tuple(_) => JitType::Heap,
         _ => JitType::Value,
     }
 }
 
+/// `Instant`, `Date` and `Duration` share the Int representation.
+fn is_int_backed(name: &str) -> bool {
+    matches!(name, "Int" | "Instant" | "Date" | "Duration")
+}
+
 fn jit_kind_for_name(name: &str, program: &IrProgram) -> JitType {
     match name {
-        "Int" => JitType::Int,
+        "Int" | "Instant" | "Date" | "Duration" => JitType::Int,
         "Bool" => JitType::Bool,
         "Float" => JitType::Float,
         "Unit" => JitType::Unit,
@@ -8215,6 +9977,8 @@ fn value_kind(value: &Value) -> Option<JitType> {
         | Value::ResultErr(_)
         | Value::Boxed(_)
         | Value::Task(_)
+        | Value::Channel(_)
+        | Value::Range(_)
         | Value::Query(_)
         | Value::Iterator(_) => Some(JitType::Value),
         _ => None,
@@ -8230,12 +9994,16 @@ fn clif_type(kind: &JitType, pointer_ty: types::Type) -> types::Type {
     }
 }
 
+/// Local kinds, the stack kinds on entry to each block, and which blocks
+/// are reachable from the function entry.
+type TypeAnalysis = (Vec<JitType>, Vec<Vec<JitType>>, Vec<bool>);
+
 fn analyze_types(
     func: &Function,
     param_types: &[JitType],
     starts: &[usize],
     program: &IrProgram,
-) -> Option<(Vec<JitType>, Vec<Vec<JitType>>)> {
+) -> Option<TypeAnalysis> {
     let mut locals: Vec<Option<JitType>> = vec![None; func.locals];
     for (idx, kind) in param_types.iter().enumerate() {
         if idx < locals.len() {
@@ -8328,7 +10096,7 @@ fn analyze_types(
                     terminated = true;
                     break;
                 }
-                Instr::IterInit => {
+                Instr::IterInit { .. } => {
                     let _ = stack.pop()?;
                     stack.push(JitType::Value);
                     let ok_ip = ip + 1;
@@ -8391,6 +10159,19 @@ fn analyze_types(
                     let kind = stack.pop()?;
                     match kind {
                         JitType::Int | JitType::Float => stack.push(kind),
+                        JitType::Value => {
+                            stack.push(JitType::Value);
+                            let ok_ip = ip + 1;
+                            let ok_idx = *block_for_start.get(&ok_ip)?;
+                            merge_block_stack(
+                                &mut entry_stacks[ok_idx],
+                                &stack,
+                                &mut worklist,
+                                ok_idx,
+                            )?;
+                            terminated = true;
+                            break;
+                        }
                         _ => return None,
                     }
                 }
@@ -8423,15 +10204,32 @@ fn analyze_types(
                 Instr::Sub | Instr::Mul | Instr::Div | Instr::Mod => {
                     let rhs = stack.pop()?;
                     let lhs = stack.pop()?;
-                    let out = numeric_kind(lhs, rhs, &func.code[ip])?;
-                    stack.push(out);
+                    if let Some(out) = numeric_kind(lhs, rhs, &func.code[ip]) {
+                        stack.push(out);
+                    } else if dynamic_operands(lhs, rhs) {
+                        stack.push(JitType::Value);
+                        let ok_ip = ip + 1;
+                        let ok_idx = *block_for_start.get(&ok_ip)?;
+                        merge_block_stack(
+                            &mut entry_stacks[ok_idx],
+                            &stack,
+                            &mut worklist,
+                            ok_idx,
+                        )?;
+                        terminated = true;
+                        break;
+                    } else {
+                        return None;
+                    }
                 }
                 Instr::Eq | Instr::NotEq | Instr::Lt | Instr::LtEq | Instr::Gt | Instr::GtEq => {
                     let rhs = stack.pop()?;
                     let lhs = stack.pop()?;
                     if let Some(out) = compare_kind(lhs, rhs, &func.code[ip]) {
                         stack.push(out);
-                    } else if matches!(func.code[ip], Instr::Eq | Instr::NotEq) {
+                    } else if matches!(func.code[ip], Instr::Eq | Instr::NotEq)
+                        || dynamic_operands(lhs, rhs)
+                    {
                         stack.push(JitType::Bool);
                     } else {
                         return None;
@@ -8488,18 +10286,33 @@ fn analyze_types(
                                 | "crypto.constant_time_eq"
                                 | "range"
                                 | "db.exec"
+                                | "db.execute"
                                 | "db.query"
                                 | "db.one"
+                                | "db.query_typed"
+                                | "db.one_typed"
                                 | "db.from"
                                 | "db.tx_begin"
                                 | "db.tx_commit"
                                 | "db.tx_rollback"
+                                | "db.rollback"
                                 | "query.select"
                                 | "query.where"
                                 | "query.order_by"
                                 | "query.limit"
+                                | "query.or_where"
+                                | "query.join"
+                                | "query.left_join"
+                                | "query.offset"
+                                | "query.group_by"
+                                | "query.having"
+                                | "query.aggregate"
                                 | "query.insert"
                                 | "query.upsert"
+                                | "query.insert_many"
+                                | "query.on_conflict"
+                                | "query.do_update"
+                                | "query.do_nothing"
                                 | "query.update"
                                 | "query.delete"
                                 | "query.count"
@@ -8508,6 +10321,8 @@ fn analyze_types(
                                 | "query.all"
                                 | "query.all_typed"
                                 | "query.exec"
+                                | "query.execute"
+                                | "query.returning"
                                 | "query.sql"
                                 | "query.params"
                                 | "json.encode"
@@ -8516,7 +10331,15 @@ fn analyze_types(
                                 | "html.raw"
                                 | "html.node"
                                 | "html.render"
-                                | "svg.inline" => {}
+                                | "svg.inline"
+                                | "struct.update" => {}
+                                name if crate::runtime_capabilities::is_time_value_builtin(
+                                    name,
+                                ) => {}
+                                name if crate::runtime_decimal::is_decimal_builtin(name) => {}
+                                name if crate::runtime_tasks::is_task_builtin(name) => {}
+                                name if crate::runtime_channels::is_channel_builtin(name) => {}
+                                name if crate::runtime_ranges::is_range_builtin(name) => {}
                                 _ => return None,
                             }
                             builtin_result_kind(name)
@@ -8714,11 +10537,12 @@ fn analyze_types(
         .into_iter()
         .map(|kind| kind.unwrap_or(JitType::Int))
         .collect();
+    let reachable = entry_stacks.iter().map(Option::is_some).collect();
     let entry_stacks = entry_stacks
         .into_iter()
         .map(|stack| stack.unwrap_or_default())
         .collect();
-    Some((locals, entry_stacks))
+    Some((locals, entry_stacks, reachable))
 }
 
 fn merge_block_stack(
@@ -8847,6 +10671,13 @@ fn numeric_kind(lhs: JitType, rhs: JitType, op: &Instr) -> Option<JitType> {
     }
 }
 
+/// Arithmetic and ordering on a dynamic `Value` operand (for example an
+/// unwrapped `?!` result) go through a hostcall instead of inline code.
+fn dynamic_operands(lhs: JitType, rhs: JitType) -> bool {
+    let numeric = |kind| matches!(kind, JitType::Int | JitType::Float | JitType::Value);
+    (lhs == JitType::Value || rhs == JitType::Value) && numeric(lhs) && numeric(rhs)
+}
+
 fn compare_kind(lhs: JitType, rhs: JitType, op: &Instr) -> Option<JitType> {
     match (lhs, rhs) {
         (JitType::Int, JitType::Int) => Some(JitType::Bool),
@@ -357,6 +357,22 @@ impl<'a> crate::runtime_types::RuntimeTypeHost for NativeVm<'a> {
     ) -> Result<(), Self::Error> {
         NativeVm::check_refined(self, value, base, args, path)
     }
+
+    fn struct_field_types(&self, name: &str) -> Option<Vec<(String, TypeRef)>> {
+        let info = self.program.ir.types.get(name)?;
+        Some(
+            info.fields
+                .iter()
+                .map(|field| (field.name.clone(), field.ty.clone()))
+                .collect(),
+        )
+    }
+
+    fn enum_payload_types(&self, name: &str, variant: &str) -> Option<Vec<TypeRef>> {
+        let info = self.program.ir.enums.get(name)?;
+        let variant = info.variants.iter().find(|v| v.name == variant)?;
+        Some(variant.payload.clone())
+    }
 }
 
 impl<'a> NativeVm<'a> {
@@ -981,7 +997,7 @@ impl<'a> NativeVm<'a> {
                         Some(&response_meta),
                     ))
                 } else {
-                    let json = self.value_to_json(&ok);
+                    let json = crate::runtime_types::encode_json_value(self, &ok, &route.ret_type);
                     Ok(self.http_response_with_meta(
                         200,
                         rt_json::encode(&json),
@@ -1000,7 +1016,8 @@ impl<'a> NativeVm<'a> {
                         Some(&response_meta),
                     ))
                 } else {
-                    let json = self.value_to_json(&other);
+                    let json =
+                        crate::runtime_types::encode_json_value(self, &other, &route.ret_type);
                     Ok(self.http_response_with_meta(
                         200,
                         rt_json::encode(&json),
@@ -1013,6 +1030,7 @@ impl<'a> NativeVm<'a> {
     }
 
     fn begin_http_route_context(&mut self, request: &HttpRequest) {
+        observability::set_current_request_id(Some(request.request_id.clone()));
         self.current_http_request = Some(HttpRequestContext {
             headers: request.headers.clone(),
             cookies: parse_cookie_map(request.headers.get("cookie").map(String::as_str)),
@@ -1024,6 +1042,7 @@ impl<'a> NativeVm<'a> {
     }
 
     fn end_http_route_context(&mut self) -> HttpResponseMeta {
+        observability::set_current_request_id(None);
         self.current_http_request = None;
         self.current_http_response.take().unwrap_or_default()
     }
@@ -1512,9 +1531,31 @@ impl<'a> NativeVm<'a> {
         })?;
         for constraint in constraints {
             match constraint {
+                RefinementConstraint::Range { min, max, .. } if base == "Decimal" => {
+                    let violation = crate::runtime_decimal::check_decimal_range(value, min, max)
+                        .map_err(NativeError::Runtime)?;
+                    if let Some(message) = violation {
+                        return Err(NativeError::Error(self.validation_error_value(
+                            path,
+                            "invalid_value",
+                            message,
+                        )));
+                    }
+                }
                 RefinementConstraint::Range { min, max, .. } => {
                     self.check_refined_range(value, base, min, max, path)?;
                 }
+                RefinementConstraint::TextRange { min, max, .. } => {
+                    let violation = crate::runtime_types::check_time_range(value, base, &min, &max)
+                        .map_err(NativeError::Runtime)?;
+                    if let Some(message) = violation {
+                        return Err(NativeError::Error(self.validation_error_value(
+                            path,
+                            "invalid_value",
+                            message,
+                        )));
+                    }
+                }
                 RefinementConstraint::Regex { pattern, .. } => {
                     if !base_is_string_like(base) {
                         return Err(NativeError::Runtime(format!(
@@ -1884,7 +1925,7 @@ fn call_function_native_only_with(
         let out = match result {
             Ok(value) => Ok(wrap_function_result(func, value)),
             Err(JitCallError::Error(err_val)) => {
-                if let Err(rollback_err) = heap.rollback_db_transaction() {
+                if let Err(rollback_err) = heap.rollback_all_db_transactions() {
                     heap.collect_garbage();
                     return Err(format!("db rollback failed: {rollback_err}"));
                 }
@@ -1895,14 +1936,14 @@ fn call_function_native_only_with(
                 }
             }
             Err(JitCallError::Runtime(message)) => {
-                if let Err(rollback_err) = heap.rollback_db_transaction() {
+                if let Err(rollback_err) = heap.rollback_all_db_transactions() {
                     heap.collect_garbage();
                     return Err(format!("db rollback failed: {rollback_err}"));
                 }
                 Err(message)
             }
             Err(JitCallError::Compile(message)) => {
-                if let Err(rollback_err) = heap.rollback_db_transaction() {
+                if let Err(rollback_err) = heap.rollback_all_db_transactions() {
                     heap.collect_garbage();
                     return Err(format!("db rollback failed: {rollback_err}"));
                 }
@@ -2040,6 +2081,22 @@ where
     ) -> Result<(), Self::Error> {
         ConfigEvaluator::check_refined(self, value, base, args, path)
     }
+
+    fn struct_field_types(&self, name: &str) -> Option<Vec<(String, TypeRef)>> {
+        let info = self.types.get(name)?;
+        Some(
+            info.fields
+                .iter()
+                .map(|field| (field.name.clone(), field.ty.clone()))
+                .collect(),
+        )
+    }
+
+    fn enum_payload_types(&self, name: &str, variant: &str) -> Option<Vec<TypeRef>> {
+        let info = self.enums.get(name)?;
+        let variant = info.variants.iter().find(|v| v.name == variant)?;
+        Some(variant.payload.clone())
+    }
 }
 
 impl<F> ConfigEvaluator<'_, F>
@@ -2287,9 +2344,31 @@ where
         })?;
         for constraint in constraints {
             match constraint {
+                RefinementConstraint::Range { min, max, .. } if base == "Decimal" => {
+                    let violation = crate::runtime_decimal::check_decimal_range(value, min, max)
+                        .map_err(NativeError::Runtime)?;
+                    if let Some(message) = violation {
+                        return Err(NativeError::Error(self.validation_error_value(
+                            path,
+                            "invalid_value",
+                            message,
+                        )));
+                    }
+                }
                 RefinementConstraint::Range { min, max, .. } => {
                     self.check_refined_range(value, base, min, max, path)?;
                 }
+                RefinementConstraint::TextRange { min, max, .. } => {
+                    let violation = crate::runtime_types::check_time_range(value, base, &min, &max)
+                        .map_err(NativeError::Runtime)?;
+                    if let Some(message) = violation {
+                        return Err(NativeError::Error(self.validation_error_value(
+                            path,
+                            "invalid_value",
+                            message,
+                        )));
+                    }
+                }
                 RefinementConstraint::Regex { pattern, .. } => {
                     if !base_is_string_like(base) {
                         return Err(NativeError::Runtime(format!(
@@ -2506,6 +2585,7 @@ fn is_html_response_type(ty: &TypeRef) -> bool {
             "Option" | "Result" => args.first().is_some_and(is_html_response_type),
             _ => false,
         },
+        TypeRefKind::Tuple(_) => false,
     }
 }
 
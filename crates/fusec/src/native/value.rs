@@ -1,8 +1,8 @@
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
-use crate::db::Db;
-use crate::interp::{HtmlNode, IteratorValue, Task, TaskResult, Value};
+use crate::db::{Db, DbOptions};
+use crate::interp::{Channel, HtmlNode, IteratorValue, RangeValue, Task, TaskResult, Value};
 use crate::ir::TypeInfo;
 
 #[repr(u64)]
@@ -20,6 +20,7 @@ pub enum NativeTag {
 pub enum HeapValue {
     String(String),
     Bytes(Vec<u8>),
+    Decimal(fuse_rt::decimal::Decimal),
     Html(HtmlNode),
     List(Vec<NativeValue>),
     Map(std::collections::HashMap<String, NativeValue>),
@@ -38,6 +39,8 @@ pub enum HeapValue {
     ResultErr(NativeValue),
     Boxed(NativeValue),
     Task(TaskValue),
+    Channel(Channel),
+    Range(RangeValue),
 }
 
 #[derive(Clone, Debug)]
@@ -63,11 +66,26 @@ pub enum TaskResultValue {
 pub struct NativeIterator {
     pub values: Vec<NativeValue>,
     pub index: usize,
+    /// Set when iterating a range; elements are computed instead of read
+    /// from `values`.
+    pub range: Option<RangeValue>,
 }
 
 impl NativeIterator {
     pub fn new(values: Vec<NativeValue>) -> Self {
-        Self { values, index: 0 }
+        Self {
+            values,
+            index: 0,
+            range: None,
+        }
+    }
+
+    pub fn range(range: RangeValue) -> Self {
+        Self {
+            values: Vec::new(),
+            index: 0,
+            range: Some(range),
+        }
     }
 }
 
@@ -134,16 +152,16 @@ impl NativeHeap {
             .collect()
     }
 
-    pub fn db_mut(&mut self, url: String, pool_size: usize) -> Result<&mut Db, String> {
+    pub fn db_mut(&mut self, url: String, options: &DbOptions) -> Result<&mut Db, String> {
         if self.db.is_none() {
-            let db = Db::open_with_pool(&url, pool_size)?;
+            let db = Db::open_shared(&url, options)?;
             self.db = Some(db);
         }
         Ok(self.db.as_mut().expect("db initialized"))
     }
 
-    pub fn begin_db_transaction(&mut self, url: String, pool_size: usize) -> Result<(), String> {
-        let db = self.db_mut(url, pool_size)?;
+    pub fn begin_db_transaction(&mut self, url: String, options: &DbOptions) -> Result<(), String> {
+        let db = self.db_mut(url, options)?;
         db.begin_transaction()
     }
 
@@ -161,6 +179,20 @@ impl NativeHeap {
         db.rollback_transaction()
     }
 
+    pub fn rollback_all_db_transactions(&mut self) -> Result<(), String> {
+        let Some(db) = self.db.as_ref() else {
+            return Ok(());
+        };
+        db.rollback_all_transactions()
+    }
+
+    pub fn mark_db_rollback(&mut self) -> Result<(), String> {
+        let Some(db) = self.db.as_ref() else {
+            return Err("db.rollback requires an active transaction".to_string());
+        };
+        db.mark_rollback()
+    }
+
     pub fn set_configs(
         &mut self,
         configs: std::collections::HashMap<String, std::collections::HashMap<String, Value>>,
@@ -246,7 +278,10 @@ impl NativeHeap {
 
     fn mark_children(&self, value: &HeapValue, marks: &mut [bool], stack: &mut Vec<u64>) {
         match value {
-            HeapValue::String(_) | HeapValue::Bytes(_) | HeapValue::Html(_) => {}
+            HeapValue::String(_)
+            | HeapValue::Bytes(_)
+            | HeapValue::Decimal(_)
+            | HeapValue::Html(_) => {}
             HeapValue::List(items) => {
                 for item in items {
                     self.mark_native_value(item, marks, stack);
@@ -272,7 +307,7 @@ impl NativeHeap {
                     self.mark_native_value(value, marks, stack);
                 }
             }
-            HeapValue::Query(_) => {}
+            HeapValue::Query(_) | HeapValue::Channel(_) | HeapValue::Range(_) => {}
             HeapValue::ResultOk(value) | HeapValue::ResultErr(value) => {
                 self.mark_native_value(value, marks, stack);
             }
@@ -455,6 +490,10 @@ impl NativeValue {
             Value::Null => Some(Self::null()),
             Value::String(v) => Some(Self::string(v.clone(), heap)),
             Value::Bytes(v) => Some(Self::bytes(v.clone(), heap)),
+            Value::Decimal(v) => Some(Self {
+                tag: NativeTag::Heap,
+                payload: heap.insert(HeapValue::Decimal(*v)),
+            }),
             Value::Html(node) => {
                 let handle = heap.insert(HeapValue::Html(node.clone()));
                 Some(Self {
@@ -507,6 +546,18 @@ impl NativeValue {
                 let boxed = Self::from_value(&inner, heap)?;
                 Some(Self::boxed(boxed, heap))
             }
+            // Unfinished tasks (e.g. captured by a spawn block) stay pending
+            // rather than blocking the conversion on their result.
+            Value::Task(task) if !task.is_done() => Some(Self::task(
+                TaskValue {
+                    id: task.id(),
+                    done: false,
+                    cancelled: false,
+                    result: TaskResultValue::Ok(Self::null()),
+                    pending: Some(task.clone()),
+                },
+                heap,
+            )),
             Value::Task(task) => {
                 let result = match task.result_raw() {
                     TaskResult::Ok(value) => TaskResultValue::Ok(Self::from_value(&value, heap)?),
@@ -532,6 +583,7 @@ impl NativeValue {
                 let handle = heap.insert(HeapValue::Iterator(NativeIterator {
                     values: out,
                     index: iter.index,
+                    range: iter.range,
                 }));
                 Some(Self {
                     tag: NativeTag::Heap,
@@ -539,7 +591,21 @@ impl NativeValue {
                 })
             }
             Value::Query(query) => {
-                let handle = heap.insert(HeapValue::Query(query.clone()));
+                let handle = heap.insert(HeapValue::Query((**query).clone()));
+                Some(Self {
+                    tag: NativeTag::Heap,
+                    payload: handle,
+                })
+            }
+            Value::Range(range) => {
+                let handle = heap.insert(HeapValue::Range(*range));
+                Some(Self {
+                    tag: NativeTag::Heap,
+                    payload: handle,
+                })
+            }
+            Value::Channel(channel) => {
+                let handle = heap.insert(HeapValue::Channel(channel.clone()));
                 Some(Self {
                     tag: NativeTag::Heap,
                     payload: handle,
@@ -559,6 +625,7 @@ impl NativeValue {
             NativeTag::Heap => match heap.get(self.payload)? {
                 HeapValue::String(value) => Some(Value::String(value.clone())),
                 HeapValue::Bytes(value) => Some(Value::Bytes(value.clone())),
+                HeapValue::Decimal(value) => Some(Value::Decimal(*value)),
                 HeapValue::Html(node) => Some(Value::Html(node.clone())),
                 HeapValue::List(values) => {
                     let mut out = Vec::with_capacity(values.len());
@@ -582,6 +649,7 @@ impl NativeValue {
                     Some(Value::Iterator(IteratorValue {
                         values: out,
                         index: iter.index,
+                        range: iter.range,
                     }))
                 }
                 HeapValue::Struct { name, fields } => {
@@ -609,7 +677,9 @@ impl NativeValue {
                         payload: out,
                     })
                 }
-                HeapValue::Query(query) => Some(Value::Query(query.clone())),
+                HeapValue::Query(query) => Some(Value::Query(Box::new(query.clone()))),
+                HeapValue::Channel(channel) => Some(Value::Channel(channel.clone())),
+                HeapValue::Range(range) => Some(Value::Range(*range)),
                 HeapValue::ResultOk(value) => {
                     let inner = value.to_value(heap)?;
                     Some(Value::ResultOk(Box::new(inner)))
@@ -622,6 +692,10 @@ impl NativeValue {
                     let inner = value.to_value(heap)?;
                     Some(Value::Boxed(Arc::new(Mutex::new(inner))))
                 }
+                HeapValue::Task(TaskValue {
+                    pending: Some(task),
+                    ..
+                }) => Some(Value::Task(task.clone())),
                 HeapValue::Task(task) => {
                     let result = match &task.result {
                         TaskResultValue::Ok(value) => TaskResult::Ok(value.to_value(heap)?),
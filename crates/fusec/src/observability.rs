@@ -1,12 +1,14 @@
 use std::any::Any;
+use std::cell::RefCell;
 use std::collections::{BTreeMap, HashMap};
-use std::sync::Once;
 use std::sync::atomic::{AtomicBool, AtomicI32, AtomicU64, Ordering};
+use std::sync::{Once, OnceLock};
 use std::time::Duration;
 
 use fuse_rt::json as rt_json;
 
 use crate::concurrency_metrics::ConcurrencySnapshot;
+use crate::db::DbPoolMetrics;
 
 pub const REQUEST_ID_HEADER: &str = "x-request-id";
 pub const REQUEST_ID_FALLBACK_HEADER: &str = "x-correlation-id";
@@ -17,6 +19,10 @@ static SHUTDOWN_REQUESTED: AtomicBool = AtomicBool::new(false);
 static LAST_SHUTDOWN_SIGNAL: AtomicI32 = AtomicI32::new(0);
 static SHUTDOWN_SIGNAL_INIT: Once = Once::new();
 
+thread_local! {
+    static CURRENT_REQUEST_ID: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct PanicDetails {
     pub kind: &'static str,
@@ -419,6 +425,10 @@ pub fn emit_concurrency_metrics(snapshot: &ConcurrencySnapshot, diagnostics_json
             "event".to_string(),
             rt_json::JsonValue::String("concurrency.snapshot".to_string()),
         );
+        obj.insert(
+            "channel_queue_depth".to_string(),
+            rt_json::JsonValue::Number(snapshot.channel_queue_depth as f64),
+        );
         obj.insert("latency_hist".to_string(), build_hist());
         obj.insert(
             "queue_depth".to_string(),
@@ -449,6 +459,10 @@ pub fn emit_concurrency_metrics(snapshot: &ConcurrencySnapshot, diagnostics_json
             "avg_latency_us".to_string(),
             rt_json::JsonValue::Number(snapshot.avg_latency_us),
         );
+        obj.insert(
+            "channel_queue_depth".to_string(),
+            rt_json::JsonValue::Number(snapshot.channel_queue_depth as f64),
+        );
         obj.insert("latency_hist".to_string(), build_hist());
         obj.insert(
             "metric".to_string(),
@@ -477,6 +491,181 @@ pub fn emit_concurrency_metrics(snapshot: &ConcurrencySnapshot, diagnostics_json
     }
 }
 
+/// Sets the request id attached to `db.query` events emitted on this thread and
+/// returns the previous one.
+pub fn set_current_request_id(request_id: Option<String>) -> Option<String> {
+    CURRENT_REQUEST_ID.with(|current| current.replace(request_id))
+}
+
+pub fn current_request_id() -> Option<String> {
+    CURRENT_REQUEST_ID.with(|current| current.borrow().clone())
+}
+
+/// One successfully executed SQL statement.
+pub struct DbQueryEvent<'a> {
+    pub sql: &'a str,
+    pub params: usize,
+    /// Rows returned by a query, or rows changed by a write.
+    pub rows: usize,
+    pub duration: Duration,
+    pub in_transaction: bool,
+}
+
+/// Emit a `db.query` event/metric for an executed statement, and a WARN log line when
+/// the statement took at least `FUSE_DB_SLOW_QUERY_MS`.
+///
+/// - `FUSE_REQUEST_LOG=structured` emits `{"event":"db.query",...}` to stderr.
+/// - `FUSE_METRICS_HOOK=stderr` emits `metrics: {"metric":"db.query",...}`.
+pub fn emit_db_query_observability(event: &DbQueryEvent<'_>) {
+    let request_logging = structured_request_logging_enabled();
+    let metrics = metrics_hook_mode() == MetricsHookMode::Stderr;
+    let slow_threshold_ms = db_slow_query_threshold_ms()
+        .ok()
+        .flatten()
+        .filter(|threshold| event.duration >= Duration::from_millis(*threshold));
+    if !request_logging && !metrics && slow_threshold_ms.is_none() {
+        return;
+    }
+
+    let build_obj = || {
+        let mut obj = BTreeMap::new();
+        obj.insert(
+            "duration_ms".to_string(),
+            rt_json::JsonValue::Number(event.duration.as_secs_f64() * 1000.0),
+        );
+        obj.insert(
+            "fingerprint".to_string(),
+            rt_json::JsonValue::String(crate::db::sql_fingerprint(event.sql)),
+        );
+        obj.insert(
+            "params".to_string(),
+            rt_json::JsonValue::Number(event.params as f64),
+        );
+        obj.insert(
+            "request_id".to_string(),
+            match current_request_id() {
+                Some(request_id) => rt_json::JsonValue::String(request_id),
+                None => rt_json::JsonValue::Null,
+            },
+        );
+        obj.insert(
+            "rows".to_string(),
+            rt_json::JsonValue::Number(event.rows as f64),
+        );
+        obj.insert(
+            "transaction".to_string(),
+            rt_json::JsonValue::Bool(event.in_transaction),
+        );
+        obj
+    };
+
+    if request_logging {
+        let mut obj = build_obj();
+        obj.insert(
+            "event".to_string(),
+            rt_json::JsonValue::String("db.query".to_string()),
+        );
+        eprintln!("{}", rt_json::encode(&rt_json::JsonValue::Object(obj)));
+    }
+
+    if metrics {
+        let mut obj = build_obj();
+        obj.insert(
+            "metric".to_string(),
+            rt_json::JsonValue::String("db.query".to_string()),
+        );
+        eprintln!(
+            "metrics: {}",
+            rt_json::encode(&rt_json::JsonValue::Object(obj))
+        );
+    }
+
+    if let Some(threshold_ms) = slow_threshold_ms
+        && warn_logs_enabled()
+    {
+        let mut data = build_obj();
+        data.insert(
+            "threshold_ms".to_string(),
+            rt_json::JsonValue::Number(threshold_ms as f64),
+        );
+        let mut obj = BTreeMap::new();
+        obj.insert("data".to_string(), rt_json::JsonValue::Object(data));
+        obj.insert(
+            "level".to_string(),
+            rt_json::JsonValue::String("warn".to_string()),
+        );
+        obj.insert(
+            "message".to_string(),
+            rt_json::JsonValue::String("slow db query".to_string()),
+        );
+        eprintln!("{}", rt_json::encode(&rt_json::JsonValue::Object(obj)));
+    }
+}
+
+/// `FUSE_DB_SLOW_QUERY_MS`, read and validated once per process. Backends check it when
+/// opening the database so an invalid value fails like `FUSE_DB_POOL_SIZE` does.
+pub fn db_slow_query_threshold_ms() -> Result<Option<u64>, String> {
+    static THRESHOLD: OnceLock<Result<Option<u64>, String>> = OnceLock::new();
+    THRESHOLD
+        .get_or_init(|| match std::env::var("FUSE_DB_SLOW_QUERY_MS") {
+            Ok(raw) => crate::db::parse_db_slow_query_ms(&raw, "FUSE_DB_SLOW_QUERY_MS").map(Some),
+            Err(_) => Ok(None),
+        })
+        .clone()
+}
+
+fn warn_logs_enabled() -> bool {
+    !matches!(
+        std::env::var("FUSE_LOG")
+            .map(|raw| raw.trim().to_ascii_lowercase())
+            .as_deref(),
+        Ok("error")
+    )
+}
+
+/// Emit a `db.pool.snapshot` metric line after a `--run` invocation completes.
+///
+/// Only emits when the program checked out at least one pooled connection. Uses the
+/// same channels as [`emit_concurrency_metrics`].
+pub fn emit_db_pool_metrics(metrics: &DbPoolMetrics, diagnostics_json: bool) {
+    if metrics.checkouts == 0 {
+        return;
+    }
+
+    let build_obj = |kind_key: &str| {
+        let mut obj = BTreeMap::new();
+        obj.insert(
+            "checkouts".to_string(),
+            rt_json::JsonValue::Number(metrics.checkouts as f64),
+        );
+        obj.insert(
+            kind_key.to_string(),
+            rt_json::JsonValue::String("db.pool.snapshot".to_string()),
+        );
+        obj.insert(
+            "timeouts".to_string(),
+            rt_json::JsonValue::Number(metrics.timeouts as f64),
+        );
+        obj.insert(
+            "wait_time_us".to_string(),
+            rt_json::JsonValue::Number(metrics.wait_time_us as f64),
+        );
+        obj.insert(
+            "waits".to_string(),
+            rt_json::JsonValue::Number(metrics.waits as f64),
+        );
+        rt_json::JsonValue::Object(obj)
+    };
+
+    if diagnostics_json {
+        eprintln!("{}", rt_json::encode(&build_obj("event")));
+    }
+
+    if metrics_hook_mode() == MetricsHookMode::Stderr {
+        eprintln!("metrics: {}", rt_json::encode(&build_obj("metric")));
+    }
+}
+
 fn structured_request_logging_enabled() -> bool {
     env_true("FUSE_REQUEST_LOG", &["structured", "json"])
 }
@@ -5,7 +5,9 @@ use crate::ast::{
     TypeRefKind,
 };
 use crate::loader::{ModuleId, ModuleRegistry, ModuleUnit};
-use crate::refinement::{RefinementConstraint, base_is_string_like, parse_constraints};
+use crate::refinement::{
+    NumberLiteral, RefinementConstraint, base_is_string_like, parse_constraints,
+};
 use fuse_rt::json::JsonValue;
 
 pub fn generate_openapi(registry: &ModuleRegistry) -> Result<String, String> {
@@ -95,15 +97,17 @@ impl<'a> OpenApiBuilder<'a> {
     }
 
     fn collect_schema_names(&mut self) {
+        // Generic declarations get no schema of their own; each
+        // instantiation (`Page_Note`) is an ordinary declaration.
         for (id, unit) in &self.registry.modules {
             for item in &unit.program.items {
                 match item {
-                    Item::Type(decl) => {
+                    Item::Type(decl) if decl.type_params.is_empty() => {
                         let key = (unit.id, decl.name.name.clone());
                         self.schema_names
                             .insert(key, format!("m{}_{}", id, decl.name.name));
                     }
-                    Item::Enum(decl) => {
+                    Item::Enum(decl) if decl.type_params.is_empty() => {
                         let key = (unit.id, decl.name.name.clone());
                         self.schema_names
                             .insert(key, format!("m{}_{}", id, decl.name.name));
@@ -371,6 +375,31 @@ impl<'a> OpenApiBuilder<'a> {
                 schema
             }
             TypeRefKind::Simple(ident) => self.schema_for_named_type(unit, &ident.name),
+            TypeRefKind::Tuple(items) => {
+                // OpenAPI 3.0 has no positional item schemas; mirror multi-payload enum variants.
+                let choices = items
+                    .iter()
+                    .map(|item| self.schema_for_type_ref(unit, item))
+                    .collect();
+                let mut out = BTreeMap::new();
+                out.insert("type".to_string(), JsonValue::String("array".to_string()));
+                out.insert(
+                    "items".to_string(),
+                    JsonValue::Object(BTreeMap::from([(
+                        "oneOf".to_string(),
+                        JsonValue::Array(choices),
+                    )])),
+                );
+                out.insert(
+                    "minItems".to_string(),
+                    JsonValue::Number(items.len() as f64),
+                );
+                out.insert(
+                    "maxItems".to_string(),
+                    JsonValue::Number(items.len() as f64),
+                );
+                JsonValue::Object(out)
+            }
         }
     }
 
@@ -663,7 +692,18 @@ fn split_qualified(name: &str) -> Option<(&str, &str)> {
 fn is_builtin_type_name(name: &str) -> bool {
     matches!(
         name,
-        "Int" | "Float" | "Bool" | "String" | "Id" | "Email" | "Bytes"
+        "Int"
+            | "Float"
+            | "Bool"
+            | "String"
+            | "Id"
+            | "Email"
+            | "Bytes"
+            | "Instant"
+            | "Date"
+            | "Duration"
+            | "DateTime"
+            | "Decimal"
     )
 }
 
@@ -695,6 +735,31 @@ fn primitive_schema(name: &str) -> Option<JsonValue> {
             schema.insert("type".to_string(), JsonValue::String("string".to_string()));
             schema.insert("format".to_string(), JsonValue::String("byte".to_string()));
         }
+        "Instant" | "DateTime" => {
+            schema.insert("type".to_string(), JsonValue::String("string".to_string()));
+            schema.insert(
+                "format".to_string(),
+                JsonValue::String("date-time".to_string()),
+            );
+        }
+        "Date" => {
+            schema.insert("type".to_string(), JsonValue::String("string".to_string()));
+            schema.insert("format".to_string(), JsonValue::String("date".to_string()));
+        }
+        "Duration" => {
+            schema.insert("type".to_string(), JsonValue::String("string".to_string()));
+            schema.insert(
+                "format".to_string(),
+                JsonValue::String("duration".to_string()),
+            );
+        }
+        "Decimal" => {
+            schema.insert("type".to_string(), JsonValue::String("string".to_string()));
+            schema.insert(
+                "format".to_string(),
+                JsonValue::String("decimal".to_string()),
+            );
+        }
         _ => return None,
     }
     Some(JsonValue::Object(schema))
@@ -789,6 +854,12 @@ fn refined_constraints(base: &str, args: &[Expr]) -> BTreeMap<String, JsonValue>
                     out.insert("minimum".to_string(), JsonValue::Number(min.as_f64()));
                     out.insert("maximum".to_string(), JsonValue::Number(max.as_f64()));
                 }
+                "Decimal" => {
+                    // Validators ignore `minimum`/`maximum` on a string schema, so
+                    // decimal bounds travel as exact text in extension keywords.
+                    out.insert("x-minimum".to_string(), JsonValue::String(number_text(min)));
+                    out.insert("x-maximum".to_string(), JsonValue::String(number_text(max)));
+                }
                 _ => {}
             },
             RefinementConstraint::Regex { pattern, .. } => {
@@ -796,12 +867,19 @@ fn refined_constraints(base: &str, args: &[Expr]) -> BTreeMap<String, JsonValue>
                     out.insert("pattern".to_string(), JsonValue::String(pattern));
                 }
             }
-            RefinementConstraint::Predicate { .. } => {}
+            RefinementConstraint::TextRange { .. } | RefinementConstraint::Predicate { .. } => {}
         }
     }
     out
 }
 
+fn number_text(value: NumberLiteral) -> String {
+    match value {
+        NumberLiteral::Int(v) => v.to_string(),
+        NumberLiteral::Float(v) => v.to_string(),
+    }
+}
+
 fn tagged_result_variant_schema(tag: &str, data_schema: JsonValue) -> JsonValue {
     let mut out = BTreeMap::new();
     out.insert("type".to_string(), JsonValue::String("object".to_string()));
@@ -90,13 +90,24 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_item(&mut self, doc: Option<Doc>) -> Option<Item> {
+        // `pub` is contextual so existing `pub` identifiers keep working.
+        if matches!(self.peek_kind(), TokenKind::Ident(name) if name == "pub") {
+            let pub_span = self.bump().span;
+            let mut item = self.parse_item(doc)?;
+            if !mark_item_pub(&mut item) {
+                self.diags.error(
+                    pub_span,
+                    "pub is only allowed on fn, type, enum, interface, config and const declarations",
+                );
+            }
+            return Some(item);
+        }
         if self.eat_keyword(Keyword::Import).is_some() {
             let decl = self.parse_import_decl();
             return Some(Item::Import(decl));
         }
         if self.eat_keyword(Keyword::Type).is_some() {
-            let decl = self.parse_type_decl(doc);
-            return Some(Item::Type(decl));
+            return Some(self.parse_type_decl(doc));
         }
         if self.eat_keyword(Keyword::Enum).is_some() {
             let decl = self.parse_enum_decl(doc);
@@ -138,6 +149,24 @@ impl<'a> Parser<'a> {
             let decl = self.parse_component_decl(doc);
             return Some(Item::Component(decl));
         }
+        // `seed` is contextual so existing `seed` identifiers keep working.
+        if matches!(
+            (self.peek_kind(), self.peek_kind_n(1)),
+            (TokenKind::Ident(name), TokenKind::String(_)) if name == "seed"
+        ) {
+            self.bump();
+            let decl = self.parse_seed_decl(doc);
+            return Some(Item::Seed(decl));
+        }
+        // `const` is contextual for the same reason.
+        if matches!(
+            (self.peek_kind(), self.peek_kind_n(1)),
+            (TokenKind::Ident(name), TokenKind::Ident(_)) if name == "const"
+        ) {
+            self.bump();
+            let decl = self.parse_const_decl(doc);
+            return Some(Item::Const(decl));
+        }
 
         self.error_here("expected a top-level declaration");
         None
@@ -180,8 +209,9 @@ impl<'a> Parser<'a> {
         }
     }
 
-    fn parse_type_decl(&mut self, doc: Option<Doc>) -> TypeDecl {
+    fn parse_type_decl(&mut self, doc: Option<Doc>) -> Item {
         let name = self.parse_type_name();
+        let type_params = self.parse_type_params();
         if self.eat_punct(Punct::Colon).is_some() {
             self.expect_newline();
             self.expect_indent();
@@ -191,71 +221,132 @@ impl<'a> Parser<'a> {
                 if self.at_dedent() || self.at_eof() {
                     break;
                 }
-                let field_start = self.peek_span();
-                let field_name = self.expect_ident();
-                self.expect_punct(Punct::Colon);
-                let ty = self.parse_type_ref();
-                let default = if self.eat_punct(Punct::Assign).is_some() {
-                    Some(self.parse_expr())
-                } else {
-                    None
-                };
+                fields.push(self.parse_field_decl());
                 self.expect_newline();
-                let field_end = self.prev_span();
-                fields.push(FieldDecl {
-                    name: field_name,
-                    ty,
-                    default,
-                    span: field_start.merge(field_end),
-                });
             }
             let end = self.expect_dedent();
             let span = name.span.merge(end);
-            TypeDecl {
+            Item::Type(TypeDecl {
                 name,
+                type_params,
                 fields,
                 derive: None,
+                is_pub: false,
                 doc,
                 span,
-            }
+            })
         } else if self.eat_punct(Punct::Assign).is_some() {
-            let base = self.parse_type_name();
-            self.expect_keyword(Keyword::Without);
-            let mut without = Vec::new();
-            loop {
-                without.push(self.expect_ident());
-                if self.eat_punct(Punct::Comma).is_none() {
-                    break;
+            let target = self.parse_type_ref();
+            let base = match &target.kind {
+                TypeRefKind::Simple(base) if self.at_derive_op() => base.clone(),
+                _ => {
+                    if let Some(param) = type_params.first() {
+                        self.diags
+                            .error(param.span, "type aliases do not accept type parameters");
+                    }
+                    self.expect_newline();
+                    let span = name.span.merge(self.prev_span());
+                    return Item::TypeAlias(TypeAliasDecl {
+                        name,
+                        target,
+                        is_pub: false,
+                        doc,
+                        span,
+                    });
                 }
+            };
+            if let Some(param) = type_params.first() {
+                self.diags
+                    .error(param.span, "type derivations do not accept type parameters");
+            }
+            let mut ops = Vec::new();
+            while self.at_derive_op() {
+                ops.push(self.parse_derive_op());
             }
             self.expect_newline();
             let span = name.span.merge(self.prev_span());
-            TypeDecl {
+            Item::Type(TypeDecl {
                 name,
+                type_params: Vec::new(),
                 fields: Vec::new(),
-                derive: Some(TypeDerive {
-                    base,
-                    without,
-                    span,
-                }),
+                derive: Some(TypeDerive { base, ops, span }),
+                is_pub: false,
                 doc,
                 span,
-            }
+            })
         } else {
             self.error_here("expected ':' or '=' after type name");
             let span = name.span;
-            TypeDecl {
+            Item::Type(TypeDecl {
                 name,
+                type_params,
                 fields: Vec::new(),
                 derive: None,
+                is_pub: false,
                 doc,
                 span,
+            })
+        }
+    }
+
+    fn parse_field_decl(&mut self) -> FieldDecl {
+        let start = self.peek_span();
+        let name = self.expect_ident();
+        self.expect_punct(Punct::Colon);
+        let ty = self.parse_type_ref();
+        let default = if self.eat_punct(Punct::Assign).is_some() {
+            Some(self.parse_expr())
+        } else {
+            None
+        };
+        FieldDecl {
+            name,
+            ty,
+            default,
+            span: start.merge(self.prev_span()),
+        }
+    }
+
+    fn at_derive_op(&self) -> bool {
+        match self.peek_kind() {
+            TokenKind::Keyword(Keyword::Without) => true,
+            TokenKind::Ident(word) => matches!(word.as_str(), "with" | "pick" | "partial"),
+            _ => false,
+        }
+    }
+
+    fn parse_derive_op(&mut self) -> DeriveOp {
+        let word = match self.bump().kind {
+            TokenKind::Keyword(Keyword::Without) => "without".to_string(),
+            TokenKind::Ident(word) => word,
+            _ => unreachable!("checked by at_derive_op"),
+        };
+        match word.as_str() {
+            "partial" => DeriveOp::Partial,
+            "with" => {
+                let mut fields = vec![self.parse_field_decl()];
+                while self.eat_punct(Punct::Comma).is_some() {
+                    fields.push(self.parse_field_decl());
+                }
+                DeriveOp::With(fields)
+            }
+            _ => {
+                let mut names = vec![self.expect_ident()];
+                while self.eat_punct(Punct::Comma).is_some() {
+                    names.push(self.expect_ident());
+                }
+                if word == "pick" {
+                    DeriveOp::Pick(names)
+                } else {
+                    DeriveOp::Without(names)
+                }
             }
         }
     }
 
     fn parse_enum_decl(&mut self, doc: Option<Doc>) -> EnumDecl {
         let name = self.parse_type_name();
+        let type_params = self.parse_type_params();
         self.expect_punct(Punct::Colon);
         self.expect_newline();
         self.expect_indent();
@@ -291,7 +382,9 @@ impl<'a> Parser<'a> {
         let span = name.span.merge(end);
         EnumDecl {
             name,
+            type_params,
             variants,
+            is_pub: false,
             doc,
             span,
         }
@@ -299,6 +392,15 @@ impl<'a> Parser<'a> {
 
     fn parse_interface_decl(&mut self, doc: Option<Doc>) -> InterfaceDecl {
         let name = self.expect_ident();
+        let mut extends = Vec::new();
+        if self.eat_contextual_ident("extends").is_some() {
+            loop {
+                extends.push(self.parse_type_name());
+                if self.eat_punct(Punct::Comma).is_none() {
+                    break;
+                }
+            }
+        }
         self.expect_punct(Punct::Colon);
         self.expect_newline();
         self.expect_indent();
@@ -314,7 +416,9 @@ impl<'a> Parser<'a> {
         let span = name.span.merge(end);
         InterfaceDecl {
             name,
+            extends,
             members,
+            is_pub: false,
             doc,
             span,
         }
@@ -324,14 +428,22 @@ impl<'a> Parser<'a> {
         let start = self.peek_span();
         self.expect_keyword(Keyword::Fn);
         let (name, type_params, params, ret, where_clause) = self.parse_fn_signature();
-        self.expect_newline();
-        let end = self.prev_span();
+        let body = if self.eat_punct(Punct::Colon).is_some() {
+            Some(self.parse_block())
+        } else {
+            self.expect_newline();
+            None
+        };
+        let end = body
+            .as_ref()
+            .map_or_else(|| self.prev_span(), |body| body.span);
         InterfaceMember {
             name,
             type_params,
             params,
             ret,
             where_clause,
+            body,
             span: start.merge(end),
         }
     }
@@ -376,6 +488,7 @@ impl<'a> Parser<'a> {
             ret,
             where_clause,
             body,
+            is_pub: false,
             doc,
             span,
         }
@@ -569,6 +682,7 @@ impl<'a> Parser<'a> {
         ConfigDecl {
             name,
             fields,
+            is_pub: false,
             doc,
             span,
         }
@@ -603,10 +717,56 @@ impl<'a> Parser<'a> {
         };
         self.expect_punct(Punct::Colon);
         let body = self.parse_block();
-        let span = start.merge(body.span);
+        let mut span = start.merge(body.span);
+        let down = if matches!(
+            (self.peek_kind(), self.peek_kind_n(1)),
+            (TokenKind::Ident(name), TokenKind::Punct(Punct::Colon)) if name == "down"
+        ) {
+            self.bump();
+            self.expect_punct(Punct::Colon);
+            let block = self.parse_block();
+            span = span.merge(block.span);
+            Some(block)
+        } else {
+            None
+        };
         MigrationDecl {
             name,
             body,
+            down,
+            doc,
+            span,
+        }
+    }
+
+    fn parse_seed_decl(&mut self, doc: Option<Doc>) -> SeedDecl {
+        let start = self.prev_span();
+        let name = self.expect_string_lit();
+        self.expect_punct(Punct::Colon);
+        let body = self.parse_block();
+        let span = start.merge(body.span);
+        SeedDecl {
+            name,
+            body,
+            doc,
+            span,
+        }
+    }
+
+    fn parse_const_decl(&mut self, doc: Option<Doc>) -> ConstDecl {
+        let start = self.prev_span();
+        let name = self.expect_ident();
+        self.expect_punct(Punct::Colon);
+        let ty = self.parse_type_ref();
+        self.expect_punct(Punct::Assign);
+        let value = self.parse_expr();
+        self.expect_newline();
+        let span = start.merge(self.prev_span());
+        ConstDecl {
+            name,
+            ty,
+            value,
+            is_pub: false,
             doc,
             span,
         }
@@ -655,13 +815,19 @@ impl<'a> Parser<'a> {
         loop {
             let type_param = self.expect_ident();
             self.expect_punct(Punct::Colon);
-            let interface = self.parse_type_name();
-            let span = type_param.span.merge(interface.span);
-            constraints.push(WhereConstraint {
-                type_param,
-                interface,
-                span,
-            });
+            // `T: A + B` is shorthand for one constraint per interface.
+            loop {
+                let interface = self.parse_type_name();
+                let span = type_param.span.merge(interface.span);
+                constraints.push(WhereConstraint {
+                    type_param: type_param.clone(),
+                    interface,
+                    span,
+                });
+                if self.eat_punct(Punct::Plus).is_none() {
+                    break;
+                }
+            }
             if self.eat_punct(Punct::Comma).is_none() {
                 break;
             }
@@ -709,6 +875,14 @@ impl<'a> Parser<'a> {
     fn parse_stmt(&mut self) -> Stmt {
         let start = self.peek_span();
         let kind = match self.peek_kind() {
+            TokenKind::Keyword(Keyword::Let) if self.at_let_pattern() => {
+                self.bump();
+                self.parse_let_pattern(false)
+            }
+            TokenKind::Keyword(Keyword::Var) if self.at_let_pattern() => {
+                self.bump();
+                self.parse_let_pattern(true)
+            }
             TokenKind::Keyword(Keyword::Let) => {
                 self.bump();
                 let name = self.expect_ident();
@@ -783,6 +957,7 @@ impl<'a> Parser<'a> {
             StmtKind::Expr(expr) => !block_expr(expr),
             StmtKind::Let { expr, .. } => !block_expr(expr),
             StmtKind::Var { expr, .. } => !block_expr(expr),
+            StmtKind::LetPattern { expr, .. } => !block_expr(expr),
             StmtKind::Assign { expr, .. } => !block_expr(expr),
             StmtKind::Return { expr } => expr.as_ref().map(block_expr).map(|v| !v).unwrap_or(true),
             _ => true,
@@ -1078,6 +1253,11 @@ impl<'a> Parser<'a> {
                 break;
             }
             let pat = self.parse_pattern();
+            let guard = if self.eat_keyword(Keyword::If).is_some() {
+                Some(self.parse_expr())
+            } else {
+                None
+            };
             let block = if self.eat_punct(Punct::Arrow).is_some() {
                 let expr = self.parse_expr();
                 self.expect_newline();
@@ -1095,7 +1275,7 @@ impl<'a> Parser<'a> {
                 self.expect_punct(Punct::Colon);
                 self.parse_block()
             };
-            cases.push((pat, block));
+            cases.push(MatchCase { pat, guard, block });
         }
         self.expect_dedent();
         StmtKind::Match { expr, cases }
@@ -1103,7 +1283,20 @@ impl<'a> Parser<'a> {
 
     fn parse_for_stmt(&mut self) -> StmtKind {
         self.expect_keyword(Keyword::For);
-        let pat = self.parse_pattern();
+        let first = self.parse_pattern();
+        let pat = if self.at_punct(Punct::Comma) {
+            let mut items = vec![first];
+            while self.eat_punct(Punct::Comma).is_some() {
+                items.push(self.parse_pattern());
+            }
+            let span = items[0].span.merge(self.prev_span());
+            Pattern {
+                kind: PatternKind::Tuple(items),
+                span,
+            }
+        } else {
+            first
+        };
         self.expect_keyword(Keyword::In);
         let iter = self.parse_expr();
         self.expect_punct(Punct::Colon);
@@ -1126,9 +1319,94 @@ impl<'a> Parser<'a> {
         StmtKind::Transaction { block }
     }
 
+    /// `let (a, b) = ...` and `let Point(x=px) = ...` destructure; plain `let name` does not.
+    fn at_let_pattern(&self) -> bool {
+        match self.peek_kind_n(1) {
+            TokenKind::Punct(Punct::LParen) => true,
+            TokenKind::Ident(_) => matches!(self.peek_kind_n(2), TokenKind::Punct(Punct::LParen)),
+            _ => false,
+        }
+    }
+
+    fn parse_let_pattern(&mut self, mutable: bool) -> StmtKind {
+        let pat = self.parse_pattern();
+        if !matches!(pat.kind, PatternKind::Tuple(_) | PatternKind::Struct { .. }) {
+            self.diags
+                .error(pat.span, "let patterns must destructure a tuple or struct");
+        }
+        let ty = if self.eat_punct(Punct::Colon).is_some() {
+            Some(self.parse_type_ref())
+        } else {
+            None
+        };
+        self.expect_punct(Punct::Assign);
+        let expr = self.parse_expr_with_html_block();
+        StmtKind::LetPattern {
+            pat,
+            ty,
+            expr,
+            mutable,
+        }
+    }
+
     fn parse_pattern(&mut self) -> Pattern {
+        let first = self.parse_bind_pattern();
+        if !self.at_punct(Punct::Pipe) {
+            return first;
+        }
+        let mut alts = vec![first];
+        while self.eat_punct(Punct::Pipe).is_some() {
+            alts.push(self.parse_bind_pattern());
+        }
+        let span = alts[0].span.merge(self.prev_span());
+        Pattern {
+            kind: PatternKind::Or(alts),
+            span,
+        }
+    }
+
+    fn parse_bind_pattern(&mut self) -> Pattern {
+        let is_bind = matches!(self.peek_kind(), TokenKind::Ident(_))
+            && matches!(self.peek_kind_n(1), TokenKind::Punct(Punct::At));
+        if !is_bind {
+            return self.parse_primary_pattern();
+        }
+        let name = self.expect_ident();
+        self.expect_punct(Punct::At);
+        let pat = self.parse_primary_pattern();
+        let span = name.span.merge(pat.span);
+        Pattern {
+            kind: PatternKind::Bind {
+                name,
+                pat: Box::new(pat),
+            },
+            span,
+        }
+    }
+
+    fn parse_primary_pattern(&mut self) -> Pattern {
         let start = self.peek_span();
         let kind = match self.peek_kind() {
+            TokenKind::Punct(Punct::LParen) => {
+                self.bump();
+                let mut items = Vec::new();
+                if !self.at_punct(Punct::RParen) {
+                    loop {
+                        items.push(self.parse_pattern());
+                        if self.eat_punct(Punct::Comma).is_none() {
+                            break;
+                        }
+                    }
+                }
+                let end = self.expect_punct(Punct::RParen);
+                if items.len() < 2 {
+                    self.diags.error(
+                        start.merge(end),
+                        "tuple patterns need at least two elements",
+                    );
+                }
+                PatternKind::Tuple(items)
+            }
             TokenKind::Ident(name) if name == "_" => {
                 self.bump();
                 PatternKind::Wildcard
@@ -1225,11 +1503,16 @@ impl<'a> Parser<'a> {
                     PatternKind::Ident(ident)
                 }
             }
-            TokenKind::Int(_)
-            | TokenKind::Float(_)
-            | TokenKind::Bool(_)
-            | TokenKind::String(_)
-            | TokenKind::Null => {
+            TokenKind::Int(_) | TokenKind::Float(_) | TokenKind::Punct(Punct::Minus) => {
+                let lit = self.parse_number_pattern_literal();
+                if self.eat_punct(Punct::DotDot).is_some() {
+                    let end = self.parse_number_pattern_literal();
+                    PatternKind::Range { start: lit, end }
+                } else {
+                    PatternKind::Literal(lit)
+                }
+            }
+            TokenKind::Bool(_) | TokenKind::String(_) | TokenKind::Null => {
                 let lit = self.parse_literal();
                 PatternKind::Literal(lit)
             }
@@ -1246,6 +1529,19 @@ impl<'a> Parser<'a> {
         }
     }
 
+    fn parse_number_pattern_literal(&mut self) -> Literal {
+        let negative = self.eat_punct(Punct::Minus).is_some();
+        if !matches!(self.peek_kind(), TokenKind::Int(_) | TokenKind::Float(_)) {
+            self.error_here("expected number");
+            return Literal::Int(0);
+        }
+        match self.parse_literal() {
+            Literal::Int(value) if negative => Literal::Int(-value),
+            Literal::Float(value) if negative => Literal::Float(-value),
+            lit => lit,
+        }
+    }
+
     fn struct_literal_name(&self, expr: &Expr) -> Option<Ident> {
         match &expr.kind {
             ExprKind::Ident(ident) => Some(ident.clone()),
@@ -1264,7 +1560,48 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_expr(&mut self) -> Expr {
-        self.parse_coalesce()
+        let mut expr = self.parse_coalesce();
+        while matches!(self.peek_kind(), TokenKind::Ident(word) if word == "with")
+            && self.next_is_update_field()
+        {
+            self.bump();
+            let mut fields = Vec::new();
+            loop {
+                let start = self.peek_span();
+                let name = self.expect_ident();
+                self.expect_punct(Punct::Assign);
+                let value = self.parse_coalesce();
+                fields.push(StructField {
+                    name,
+                    span: start.merge(value.span),
+                    value,
+                    comma_before: None,
+                });
+                // A trailing `, name = value` continues the update; anything
+                // else after the comma belongs to the enclosing list.
+                if !(matches!(self.peek_kind(), TokenKind::Punct(Punct::Comma))
+                    && self.next_is_update_field())
+                {
+                    break;
+                }
+                self.bump();
+            }
+            let span = expr.span.merge(self.prev_span());
+            expr = Expr {
+                kind: ExprKind::RecordUpdate {
+                    base: Box::new(expr),
+                    fields,
+                },
+                span,
+            };
+        }
+        expr
+    }
+
+    /// Whether the two tokens after the current `with` or comma are `name =`.
+    fn next_is_update_field(&self) -> bool {
+        matches!(self.peek_kind_n(1), TokenKind::Ident(_))
+            && matches!(self.peek_kind_n(2), TokenKind::Punct(Punct::Assign))
     }
 
     fn parse_coalesce(&mut self) -> Expr {
@@ -1380,11 +1717,16 @@ impl<'a> Parser<'a> {
     fn parse_range(&mut self) -> Expr {
         let mut expr = self.parse_add();
         while self.eat_punct(Punct::DotDot).is_some() {
+            let op = if self.eat_punct(Punct::Lt).is_some() {
+                BinaryOp::RangeExclusive
+            } else {
+                BinaryOp::Range
+            };
             let right = self.parse_add();
             let span = expr.span.merge(right.span);
             expr = Expr {
                 kind: ExprKind::Binary {
-                    op: BinaryOp::Range,
+                    op,
                     left: Box::new(expr),
                     right: Box::new(right),
                 },
@@ -1478,13 +1820,42 @@ impl<'a> Parser<'a> {
         }
         if self.eat_keyword(Keyword::Await).is_some() {
             let expr = self.parse_unary();
-            let span = start.merge(expr.span);
-            return Expr {
+            // `within` is contextual so existing `within` identifiers keep working.
+            let Some(_) = self.eat_contextual_ident("within") else {
+                let span = start.merge(expr.span);
+                return Expr {
+                    kind: ExprKind::Await {
+                        expr: Box::new(expr),
+                        timeout: None,
+                    },
+                    span,
+                };
+            };
+            // `await t within ms ?! err` applies `?!` to the timed await, so
+            // bang chains parsed onto the timeout are moved outside it.
+            let mut timeout = self.parse_unary();
+            let mut chains = Vec::new();
+            while let ExprKind::BangChain { expr: inner, error } = timeout.kind {
+                chains.push((error, timeout.span));
+                timeout = *inner;
+            }
+            let mut awaited = Expr {
+                span: start.merge(timeout.span),
                 kind: ExprKind::Await {
                     expr: Box::new(expr),
+                    timeout: Some(Box::new(timeout)),
                 },
-                span,
             };
+            for (error, span) in chains.into_iter().rev() {
+                awaited = Expr {
+                    kind: ExprKind::BangChain {
+                        expr: Box::new(awaited),
+                        error,
+                    },
+                    span: start.merge(span),
+                };
+            }
+            return awaited;
         }
         if self.eat_keyword(Keyword::Box).is_some() {
             let expr = self.parse_unary();
@@ -1736,6 +2107,20 @@ impl<'a> Parser<'a> {
             TokenKind::Punct(Punct::LParen) => {
                 self.bump();
                 let mut expr = self.parse_expr();
+                if self.at_punct(Punct::Comma) {
+                    let mut items = vec![expr];
+                    while self.eat_punct(Punct::Comma).is_some() {
+                        if self.at_punct(Punct::RParen) {
+                            break;
+                        }
+                        items.push(self.parse_expr());
+                    }
+                    let end = self.expect_punct(Punct::RParen);
+                    return Expr {
+                        kind: ExprKind::TupleLit(items),
+                        span: start.merge(end),
+                    };
+                }
                 let end = self.expect_punct(Punct::RParen);
                 expr.span = expr.span.merge(end);
                 expr
@@ -1934,10 +2319,15 @@ impl<'a> Parser<'a> {
         if n == 0 {
             return false;
         }
+        // A `(` at the same or a shallower indentation starts a tuple (for
+        // example a match arm pattern), so calls only continue onto an
+        // indented line.
+        if matches!(self.peek_kind_n(n), TokenKind::Punct(Punct::LParen)) {
+            return (0..n).any(|idx| matches!(self.peek_kind_n(idx), TokenKind::Indent));
+        }
         matches!(
             self.peek_kind_n(n),
-            TokenKind::Punct(Punct::LParen)
-                | TokenKind::Punct(Punct::Dot)
+            TokenKind::Punct(Punct::Dot)
                 | TokenKind::Punct(Punct::LBracket)
                 | TokenKind::Punct(Punct::Question)
                 | TokenKind::Punct(Punct::QuestionBang)
@@ -2011,33 +2401,24 @@ impl<'a> Parser<'a> {
 
     fn parse_type_ref(&mut self) -> TypeRef {
         let start = self.peek_span();
-        let base = self.parse_type_name();
-        let mut kind = if self.eat_punct(Punct::Lt).is_some() {
-            let mut args = Vec::new();
-            if !self.at_punct(Punct::Gt) {
-                loop {
-                    args.push(self.parse_type_ref());
-                    if self.eat_punct(Punct::Comma).is_none() {
-                        break;
-                    }
-                }
-            }
-            self.expect_punct(Punct::Gt);
-            TypeRefKind::Generic { base, args }
-        } else if self.eat_punct(Punct::LParen).is_some() {
-            let mut args = Vec::new();
+        let mut kind = if self.eat_punct(Punct::LParen).is_some() {
+            let mut items = Vec::new();
             if !self.at_punct(Punct::RParen) {
                 loop {
-                    args.push(self.parse_expr());
+                    items.push(self.parse_type_ref());
                     if self.eat_punct(Punct::Comma).is_none() {
                         break;
                     }
                 }
             }
-            self.expect_punct(Punct::RParen);
-            TypeRefKind::Refined { base, args }
+            let end = self.expect_punct(Punct::RParen);
+            if items.len() < 2 {
+                self.diags
+                    .error(start.merge(end), "tuple types need at least two elements");
+            }
+            TypeRefKind::Tuple(items)
         } else {
-            TypeRefKind::Simple(base)
+            self.parse_named_type_ref_kind()
         };
         let mut span = start.merge(self.prev_span());
         loop {
@@ -2066,6 +2447,37 @@ impl<'a> Parser<'a> {
         TypeRef { kind, span }
     }
 
+    fn parse_named_type_ref_kind(&mut self) -> TypeRefKind {
+        let base = self.parse_type_name();
+        if self.eat_punct(Punct::Lt).is_some() {
+            let mut args = Vec::new();
+            if !self.at_punct(Punct::Gt) {
+                loop {
+                    args.push(self.parse_type_ref());
+                    if self.eat_punct(Punct::Comma).is_none() {
+                        break;
+                    }
+                }
+            }
+            self.expect_punct(Punct::Gt);
+            TypeRefKind::Generic { base, args }
+        } else if self.eat_punct(Punct::LParen).is_some() {
+            let mut args = Vec::new();
+            if !self.at_punct(Punct::RParen) {
+                loop {
+                    args.push(self.parse_expr());
+                    if self.eat_punct(Punct::Comma).is_none() {
+                        break;
+                    }
+                }
+            }
+            self.expect_punct(Punct::RParen);
+            TypeRefKind::Refined { base, args }
+        } else {
+            TypeRefKind::Simple(base)
+        }
+    }
+
     fn eat_contextual_ident(&mut self, expected: &str) -> Option<Token> {
         match self.peek_kind() {
             TokenKind::Ident(name) if name == expected => Some(self.bump()),
@@ -2449,6 +2861,22 @@ fn collect_top_level_component_names(tokens: &[Token]) -> HashSet<String> {
     out
 }
 
+/// Sets the `pub` flag on declarations that can be exported; returns
+/// `false` for items that have no visibility.
+fn mark_item_pub(item: &mut Item) -> bool {
+    match item {
+        Item::Type(decl) => decl.is_pub = true,
+        Item::TypeAlias(decl) => decl.is_pub = true,
+        Item::Enum(decl) => decl.is_pub = true,
+        Item::Interface(decl) => decl.is_pub = true,
+        Item::Fn(decl) => decl.is_pub = true,
+        Item::Config(decl) => decl.is_pub = true,
+        Item::Const(decl) => decl.is_pub = true,
+        _ => return false,
+    }
+    true
+}
+
 trait ItemStart {
     fn is_keyword_item_start(&self) -> bool;
 }
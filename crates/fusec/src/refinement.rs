@@ -31,6 +31,11 @@ pub enum RefinementConstraint {
         max: NumberLiteral,
         span: Span,
     },
+    TextRange {
+        min: String,
+        max: String,
+        span: Span,
+    },
     Regex {
         pattern: String,
         span: Span,
@@ -80,6 +85,15 @@ fn parse_constraint(expr: &Expr) -> Result<RefinementConstraint, RefinementParse
         right,
     } = &expr.kind
     {
+        if let (ExprKind::Literal(Literal::String(min)), ExprKind::Literal(Literal::String(max))) =
+            (&left.kind, &right.kind)
+        {
+            return Ok(RefinementConstraint::TextRange {
+                min: min.clone(),
+                max: max.clone(),
+                span: expr.span,
+            });
+        }
         let min = literal_number(left).ok_or_else(|| RefinementParseError {
             span: left.span,
             message: "range lower bound must be a numeric literal".to_string(),
@@ -192,3 +206,26 @@ pub fn literal_number(expr: &Expr) -> Option<NumberLiteral> {
 pub fn base_is_string_like(base: &str) -> bool {
     matches!(base, "String" | "Id" | "Email")
 }
+
+/// Parses the text bounds of a `"min".."max"` refinement on a builtin time
+/// type into the runtime representation shared by every backend.
+pub fn time_range_bounds(base: &str, min: &str, max: &str) -> Result<(i64, i64), String> {
+    let parse: fn(&str) -> Result<i64, String> = match base {
+        "Instant" | "DateTime" => fuse_rt::time::parse_instant,
+        "Date" => fuse_rt::time::parse_date,
+        "Duration" => fuse_rt::time::parse_duration,
+        _ => {
+            return Err(format!(
+                "text range bounds are only supported for Instant, Date, Duration and DateTime, found {base}"
+            ));
+        }
+    };
+    let lo = parse(min).map_err(|err| format!("invalid {base} range bound \"{min}\": {err}"))?;
+    let hi = parse(max).map_err(|err| format!("invalid {base} range bound \"{max}\": {err}"))?;
+    if lo > hi {
+        return Err(format!(
+            "{base} range lower bound must not exceed its upper bound"
+        ));
+    }
+    Ok((lo, hi))
+}
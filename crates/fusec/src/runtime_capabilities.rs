@@ -2,9 +2,12 @@ use std::thread;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use fuse_rt::time as rt_time;
 use hmac::{Hmac, Mac};
 use sha2::{Digest, Sha256, Sha512};
 
+use crate::interp::Value;
+
 #[derive(Copy, Clone)]
 enum DigestAlgo {
     Sha256,
@@ -63,6 +66,137 @@ pub(crate) fn time_parse_epoch_ms(text: &str, fmt: &str) -> Result<i64, String>
     Err(format!("time.parse failed for format `{fmt}`"))
 }
 
+/// `time.*` helpers that operate on the builtin `Instant`, `Date`,
+/// `Duration` and `DateTime` types (see `runtime_types::parse_time_text` for
+/// their runtime representation).
+pub(crate) const TIME_VALUE_BUILTINS: &[&str] = &[
+    "instant",
+    "today",
+    "from_unix_ms",
+    "unix_ms",
+    "millis",
+    "seconds",
+    "minutes",
+    "hours",
+    "days",
+    "to_millis",
+    "date",
+    "date_of",
+    "start_of",
+    "in_zone",
+    "instant_of",
+    "offset_of",
+    "local_date",
+    "format_instant",
+    "format_date",
+    "format_duration",
+    "format_datetime",
+    "parse_instant",
+    "parse_date",
+    "parse_duration",
+    "parse_datetime",
+];
+
+pub(crate) fn is_time_value_builtin(name: &str) -> bool {
+    name.strip_prefix("time.")
+        .is_some_and(|member| TIME_VALUE_BUILTINS.contains(&member))
+}
+
+/// Evaluates one of [`TIME_VALUE_BUILTINS`]. The outer error is a runtime
+/// failure. The fallible helpers (`date`, `in_zone` and `parse_*`) wrap
+/// their success in `ResultOk` and report the inner error message, which
+/// each backend turns into its default `Error` value.
+pub(crate) fn time_value_call(name: &str, args: &[Value]) -> Result<Result<Value, String>, String> {
+    let member = name.strip_prefix("time.").unwrap_or(name);
+    let arity = match member {
+        "instant" | "today" => 0,
+        "date" => 3,
+        "in_zone" => 2,
+        _ => 1,
+    };
+    if args.len() != arity {
+        let noun = if arity == 1 { "argument" } else { "arguments" };
+        return Err(format!("{name} expects {arity} {noun}"));
+    }
+    let int_arg = |idx: usize| match args[idx].unboxed() {
+        Value::Int(value) => Ok(value),
+        _ => Err(format!("{name} expects an Int-backed time value")),
+    };
+    let text_arg = |idx: usize| match args[idx].unboxed() {
+        Value::String(value) => Ok(value),
+        _ => Err(format!("{name} expects a String-backed time value")),
+    };
+    let zoned_arg = |idx: usize| {
+        rt_time::parse_datetime(&text_arg(idx)?).map_err(|err| format!("{name} failed: {err}"))
+    };
+    let scaled = |unit: i64| {
+        int_arg(0)?
+            .checked_mul(unit)
+            .map(Value::Int)
+            .ok_or_else(|| format!("{name} overflow"))
+    };
+    let value = match member {
+        "instant" => Value::Int(time_now_unix_ms()?),
+        "today" => Value::Int(time_now_unix_ms()?.div_euclid(rt_time::MILLIS_PER_DAY)),
+        "from_unix_ms" | "unix_ms" | "to_millis" | "millis" => Value::Int(int_arg(0)?),
+        "seconds" => scaled(1000)?,
+        "minutes" => scaled(60_000)?,
+        "hours" => scaled(3_600_000)?,
+        "days" => scaled(rt_time::MILLIS_PER_DAY)?,
+        "date" => {
+            let (year, month, day) = (int_arg(0)?, int_arg(1)?, int_arg(2)?);
+            let days = u32::try_from(month)
+                .ok()
+                .zip(u32::try_from(day).ok())
+                .and_then(|(month, day)| rt_time::days_from_civil(year, month, day));
+            match days {
+                Some(days) => Value::ResultOk(Box::new(Value::Int(days))),
+                None => {
+                    return Ok(Err(format!(
+                        "time.date invalid calendar date {year:04}-{month:02}-{day:02}"
+                    )));
+                }
+            }
+        }
+        "date_of" => Value::Int(int_arg(0)?.div_euclid(rt_time::MILLIS_PER_DAY)),
+        "start_of" => scaled(rt_time::MILLIS_PER_DAY)?,
+        "in_zone" => {
+            let at = int_arg(0)?;
+            match rt_time::parse_offset(&text_arg(1)?) {
+                Ok(offset) => Value::ResultOk(Box::new(Value::String(rt_time::format_datetime(
+                    at, offset,
+                )))),
+                Err(err) => return Ok(Err(format!("time.in_zone failed: {err}"))),
+            }
+        }
+        "instant_of" => Value::Int(zoned_arg(0)?.0),
+        "offset_of" => Value::String(rt_time::format_offset(zoned_arg(0)?.1)),
+        "local_date" => {
+            let (unix_ms, offset) = zoned_arg(0)?;
+            let local_ms = unix_ms + i64::from(offset) * 60_000;
+            Value::Int(local_ms.div_euclid(rt_time::MILLIS_PER_DAY))
+        }
+        "format_instant" => Value::String(rt_time::format_instant(int_arg(0)?)),
+        "format_date" => Value::String(rt_time::format_date(int_arg(0)?)),
+        "format_duration" => Value::String(rt_time::format_duration(int_arg(0)?)),
+        "format_datetime" => Value::String(text_arg(0)?),
+        "parse_instant" | "parse_date" | "parse_duration" | "parse_datetime" => {
+            let type_name = match member {
+                "parse_instant" => "Instant",
+                "parse_date" => "Date",
+                "parse_duration" => "Duration",
+                _ => "DateTime",
+            };
+            let text = text_arg(0)?;
+            return Ok(crate::runtime_types::parse_time_text(type_name, &text)
+                .map(|value| Value::ResultOk(Box::new(value)))
+                .map_err(|err| format!("{name} failed: {err}")));
+        }
+        _ => return Err(format!("unknown time builtin {name}")),
+    };
+    Ok(Ok(value))
+}
+
 pub(crate) fn crypto_hash(algo: &str, data: &[u8]) -> Result<Vec<u8>, String> {
     let Some(algo) = parse_digest_algo(algo) else {
         return Err(format!("crypto.hash unsupported algorithm {algo}"));
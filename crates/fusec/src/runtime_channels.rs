@@ -0,0 +1,51 @@
+use crate::interp::{Channel, Value};
+
+/// `Channel<T>` builtins shared by both backends. `channel` creates a channel;
+/// the `channel.*` methods are dispatched on a channel receiver.
+pub(crate) const CHANNEL_BUILTINS: &[&str] =
+    &["channel", "channel.send", "channel.recv", "channel.close"];
+
+pub(crate) fn is_channel_builtin(name: &str) -> bool {
+    CHANNEL_BUILTINS.contains(&name)
+}
+
+/// Method names callable on a `Channel<T>` value.
+pub(crate) fn is_channel_method(name: &str) -> bool {
+    matches!(name, "send" | "recv" | "close")
+}
+
+/// Evaluates one of [`CHANNEL_BUILTINS`]. Errors are runtime failures.
+pub(crate) fn channel_call(name: &str, args: &[Value]) -> Result<Value, String> {
+    if name == "channel" {
+        let [capacity] = args else {
+            return Err("channel expects 1 argument".to_string());
+        };
+        let Value::Int(capacity) = capacity.unboxed() else {
+            return Err("channel expects an Int capacity".to_string());
+        };
+        return match usize::try_from(capacity) {
+            Ok(capacity) if capacity > 0 => Ok(Value::Channel(Channel::new(capacity))),
+            _ => Err("channel capacity must be at least 1".to_string()),
+        };
+    }
+    let Some((Value::Channel(channel), rest)) = args
+        .split_first()
+        .map(|(receiver, rest)| (receiver.unboxed(), rest))
+    else {
+        return Err(format!("{name} expects a Channel"));
+    };
+    match (name, rest) {
+        ("channel.send", [value]) => {
+            channel.send(value.clone())?;
+            Ok(Value::Unit)
+        }
+        ("channel.recv", []) => Ok(channel.recv()?.unwrap_or(Value::Null)),
+        ("channel.close", []) => {
+            channel.close();
+            Ok(Value::Unit)
+        }
+        ("channel.send", _) => Err("channel.send expects 1 argument".to_string()),
+        ("channel.recv" | "channel.close", _) => Err(format!("{name} expects no arguments")),
+        _ => Err(format!("unknown channel builtin {name}")),
+    }
+}
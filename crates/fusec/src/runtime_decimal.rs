@@ -0,0 +1,153 @@
+use fuse_rt::decimal::{Decimal, RoundingMode};
+
+use crate::ast::{BinaryOp, TypeRef, TypeRefKind};
+use crate::interp::Value;
+use crate::refinement::NumberLiteral;
+
+/// `decimal.*` helpers shared by both backends.
+pub(crate) const DECIMAL_BUILTINS: &[&str] = &[
+    "parse",
+    "from_int",
+    "from_units",
+    "round",
+    "scale",
+    "to_float",
+];
+
+pub(crate) fn is_decimal_builtin(name: &str) -> bool {
+    name.strip_prefix("decimal.")
+        .is_some_and(|member| DECIMAL_BUILTINS.contains(&member))
+}
+
+/// Evaluates one of [`DECIMAL_BUILTINS`]. The outer error is a runtime
+/// failure; `decimal.parse` reports bad input through the inner error, which
+/// each backend turns into its default `Error` value.
+pub(crate) fn decimal_call(name: &str, args: &[Value]) -> Result<Result<Value, String>, String> {
+    let member = name.strip_prefix("decimal.").unwrap_or(name);
+    let (min, max) = match member {
+        "from_units" => (2, 2),
+        "round" => (2, 3),
+        _ => (1, 1),
+    };
+    if args.len() < min || args.len() > max {
+        return Err(match (min, max) {
+            (1, 1) => format!("{name} expects 1 argument"),
+            _ if min == max => format!("{name} expects {min} arguments"),
+            _ => format!("{name} expects {min} or {max} arguments"),
+        });
+    }
+    let int_arg = |idx: usize| match args[idx].unboxed() {
+        Value::Int(value) => Ok(value),
+        _ => Err(format!("{name} expects an Int")),
+    };
+    let decimal_arg = |idx: usize| match args[idx].unboxed() {
+        Value::Decimal(value) => Ok(value),
+        _ => Err(format!("{name} expects a Decimal")),
+    };
+    let scale_arg = |idx: usize| {
+        u32::try_from(int_arg(idx)?).map_err(|_| format!("{name} expects a non-negative scale"))
+    };
+    let value = match member {
+        "parse" => {
+            let Value::String(text) = args[0].unboxed() else {
+                return Err(format!("{name} expects a String"));
+            };
+            return Ok(Decimal::parse(&text)
+                .map(|value| Value::ResultOk(Box::new(Value::Decimal(value))))
+                .map_err(|err| format!("{name} failed: {err}")));
+        }
+        "from_int" => Value::Decimal(Decimal::from_i64(int_arg(0)?)),
+        "from_units" => Value::Decimal(Decimal::new(i128::from(int_arg(0)?), scale_arg(1)?)?),
+        "round" => {
+            let mode = match args.get(2).map(Value::unboxed) {
+                None => RoundingMode::HalfEven,
+                Some(Value::String(mode)) => RoundingMode::parse(&mode)?,
+                Some(_) => return Err(format!("{name} expects the rounding mode as a String")),
+            };
+            Value::Decimal(decimal_arg(0)?.round(scale_arg(1)?, mode)?)
+        }
+        "scale" => Value::Int(i64::from(decimal_arg(0)?.scale())),
+        "to_float" => Value::Float(decimal_arg(0)?.to_f64()),
+        _ => return Err(format!("unknown decimal builtin {name}")),
+    };
+    Ok(Ok(value))
+}
+
+/// Applies a binary operator when at least one operand is a `Decimal` and
+/// the other is a `Decimal` or an `Int`. Returns `None` for any other pair
+/// so callers fall through to their own numeric rules.
+pub(crate) fn decimal_binary(
+    op: &BinaryOp,
+    left: &Value,
+    right: &Value,
+) -> Option<Result<Value, String>> {
+    let as_decimal = |value: &Value| match value.unboxed() {
+        Value::Decimal(value) => Some(value),
+        Value::Int(value) => Some(Decimal::from_i64(value)),
+        _ => None,
+    };
+    if !matches!(left.unboxed(), Value::Decimal(_)) && !matches!(right.unboxed(), Value::Decimal(_))
+    {
+        return None;
+    }
+    let (left, right) = (as_decimal(left)?, as_decimal(right)?);
+    let result = match op {
+        BinaryOp::Add => left.checked_add(right).map(Value::Decimal),
+        BinaryOp::Sub => left.checked_sub(right).map(Value::Decimal),
+        BinaryOp::Mul => left.checked_mul(right).map(Value::Decimal),
+        BinaryOp::Div => left.checked_div(right).map(Value::Decimal),
+        BinaryOp::Mod => left.checked_rem(right).map(Value::Decimal),
+        BinaryOp::Eq => Ok(Value::Bool(left == right)),
+        BinaryOp::NotEq => Ok(Value::Bool(left != right)),
+        BinaryOp::Lt => Ok(Value::Bool(left < right)),
+        BinaryOp::LtEq => Ok(Value::Bool(left <= right)),
+        BinaryOp::Gt => Ok(Value::Bool(left > right)),
+        BinaryOp::GtEq => Ok(Value::Bool(left >= right)),
+        _ => return None,
+    };
+    Some(result)
+}
+
+/// Checks a numeric `min..max` refinement on `Decimal`. Float bounds go
+/// through their shortest text form, so `0.01` is compared exactly. Returns
+/// the violation message when the value falls outside the range.
+pub(crate) fn check_decimal_range(
+    value: &Value,
+    min: NumberLiteral,
+    max: NumberLiteral,
+) -> Result<Option<String>, String> {
+    let bound = |literal: NumberLiteral| match literal {
+        NumberLiteral::Int(value) => Ok(Decimal::from_i64(value)),
+        NumberLiteral::Float(value) => Decimal::from_f64(value),
+    };
+    let Value::Decimal(value) = value.unboxed() else {
+        return Err("refined Decimal expects a Decimal".to_string());
+    };
+    let (lo, hi) = (bound(min)?, bound(max)?);
+    if value >= lo && value <= hi {
+        return Ok(None);
+    }
+    Ok(Some(format!("value {value} out of range {lo}..{hi}")))
+}
+
+/// SQLite hands `Decimal` columns back as text (or as a number when the
+/// column has numeric affinity); converts them for a typed row field.
+/// Values that do not parse are returned unchanged for validation to reject.
+pub(crate) fn coerce_decimal_column(ty: &TypeRef, value: Value) -> Value {
+    let base = match &ty.kind {
+        TypeRefKind::Simple(ident) => &ident.name,
+        TypeRefKind::Refined { base, .. } => &base.name,
+        TypeRefKind::Optional(inner) => return coerce_decimal_column(inner, value),
+        _ => return value,
+    };
+    if base != "Decimal" {
+        return value;
+    }
+    let parsed = match &value {
+        Value::String(text) => Decimal::parse(text),
+        Value::Int(units) => Ok(Decimal::from_i64(*units)),
+        Value::Float(number) => Decimal::from_f64(*number),
+        _ => return value,
+    };
+    parsed.map(Value::Decimal).unwrap_or(value)
+}
@@ -0,0 +1,159 @@
+use crate::interp::{RangeValue, Value};
+
+/// Range builtins shared by both backends. `range` and `range.exclusive` back
+/// the `..` and `..<` operators; the rest are methods on a range receiver.
+pub(crate) const RANGE_BUILTINS: &[&str] =
+    &["range", "range.exclusive", "range.step", "range.to_list"];
+
+pub(crate) fn is_range_builtin(name: &str) -> bool {
+    RANGE_BUILTINS.contains(&name)
+}
+
+/// Method names callable on a `Range<T>` value.
+pub(crate) fn is_range_method(name: &str) -> bool {
+    matches!(name, "step" | "to_list")
+}
+
+/// Evaluates one of [`RANGE_BUILTINS`]. Errors are runtime failures.
+pub(crate) fn range_call(name: &str, args: &[Value]) -> Result<Value, String> {
+    match (name, args) {
+        ("range", [start, end]) => new_range(start, end, true),
+        ("range.exclusive", [start, end]) => new_range(start, end, false),
+        ("range" | "range.exclusive", _) => Err("range expects 2 arguments".to_string()),
+        ("range.step", [range, step]) => with_step(&expect_range(name, range)?, step),
+        ("range.step", _) => Err("range.step expects 1 argument".to_string()),
+        ("range.to_list", [range]) => Ok(Value::List(expect_range(name, range)?.to_list())),
+        ("range.to_list", _) => Err("range.to_list expects no arguments".to_string()),
+        _ => Err(format!("unknown range builtin {name}")),
+    }
+}
+
+/// Source-like rendering used when a range is printed or interpolated.
+pub(crate) fn range_text(range: &RangeValue) -> String {
+    let (start, end, step, inclusive) = match *range {
+        RangeValue::Int {
+            start,
+            end,
+            step,
+            inclusive,
+        } => (
+            start.to_string(),
+            end.to_string(),
+            (step != 1).then(|| step.to_string()),
+            inclusive,
+        ),
+        RangeValue::Float {
+            start,
+            end,
+            step,
+            inclusive,
+        } => (
+            start.to_string(),
+            end.to_string(),
+            (step != 1.0).then(|| step.to_string()),
+            inclusive,
+        ),
+    };
+    let op = if inclusive { ".." } else { "..<" };
+    match step {
+        Some(step) => format!("({start}{op}{end}).step({step})"),
+        None => format!("{start}{op}{end}"),
+    }
+}
+
+fn new_range(start: &Value, end: &Value, inclusive: bool) -> Result<Value, String> {
+    let range = match (start.unboxed(), end.unboxed()) {
+        (Value::Int(start), Value::Int(end)) => {
+            if start > end {
+                return Err("range start must be <= end".to_string());
+            }
+            RangeValue::Int {
+                start,
+                end,
+                step: 1,
+                inclusive,
+            }
+        }
+        (start, end) => {
+            let (Some(start), Some(end)) = (as_float(&start), as_float(&end)) else {
+                return Err("range expects numeric bounds".to_string());
+            };
+            if !start.is_finite() || !end.is_finite() {
+                return Err("invalid range bounds".to_string());
+            }
+            if start > end {
+                return Err("range start must be <= end".to_string());
+            }
+            RangeValue::Float {
+                start,
+                end,
+                step: 1.0,
+                inclusive,
+            }
+        }
+    };
+    Ok(Value::Range(range))
+}
+
+fn with_step(range: &RangeValue, step: &Value) -> Result<Value, String> {
+    let range = match (*range, step.unboxed()) {
+        (RangeValue::Int { .. }, Value::Int(step)) if step <= 0 => {
+            return Err("range step must be > 0".to_string());
+        }
+        (
+            RangeValue::Int {
+                start,
+                end,
+                inclusive,
+                ..
+            },
+            Value::Int(step),
+        ) => RangeValue::Int {
+            start,
+            end,
+            step,
+            inclusive,
+        },
+        (RangeValue::Int { .. }, _) => {
+            return Err("range step must be Int for an Int range".to_string());
+        }
+        (
+            RangeValue::Float {
+                start,
+                end,
+                inclusive,
+                ..
+            },
+            step,
+        ) => {
+            let Some(step) = as_float(&step) else {
+                return Err("range step must be numeric".to_string());
+            };
+            if !step.is_finite() || step <= 0.0 {
+                return Err("range step must be > 0".to_string());
+            }
+            RangeValue::Float {
+                start,
+                end,
+                step,
+                inclusive,
+            }
+        }
+    };
+    Ok(Value::Range(range))
+}
+
+fn expect_range(name: &str, value: &Value) -> Result<RangeValue, String> {
+    match value.unboxed() {
+        Value::Range(range) => Ok(range),
+        _ => Err(format!("{name} expects a Range")),
+    }
+}
+
+fn as_float(value: &Value) -> Option<f64> {
+    match value {
+        Value::Int(value) => Some(*value as f64),
+        Value::Float(value) => Some(*value),
+        _ => None,
+    }
+}
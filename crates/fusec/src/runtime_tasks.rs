@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::thread;
+use std::time::Duration;
+
+use crate::interp::{TASK_CANCELLED, TASK_POLL_INTERVAL, Task, TaskResult, Value, task_cancelled};
+
+/// Task-group builtins shared by both backends. `task.await_within` is not
+/// callable by name; `await task within ms` lowers to it.
+pub(crate) const TASK_BUILTINS: &[&str] = &["await_all", "await_any", "task.await_within"];
+
+pub(crate) fn is_task_builtin(name: &str) -> bool {
+    TASK_BUILTINS.contains(&name)
+}
+
+/// Evaluates one of [`TASK_BUILTINS`]. The outer error is a runtime failure
+/// for malformed arguments; the returned [`TaskResult`] carries what the
+/// awaited tasks produced so each backend propagates it like `await`.
+pub(crate) fn task_call(name: &str, args: &[Value]) -> Result<TaskResult, String> {
+    match name {
+        "await_all" => Ok(await_all(&task_list_arg(name, args)?)),
+        "await_any" => {
+            let tasks = task_list_arg(name, args)?;
+            if tasks.is_empty() {
+                return Err("await_any expects at least one task".to_string());
+            }
+            Ok(await_any(&tasks))
+        }
+        "task.await_within" => {
+            let (Some(Value::Task(task)), Some(Value::Int(ms)), 2) = (
+                args.first().map(Value::unboxed),
+                args.get(1).map(Value::unboxed),
+                args.len(),
+            ) else {
+                return Err("await within expects a Task and a timeout in milliseconds".to_string());
+            };
+            let timeout = u64::try_from(ms)
+                .map_err(|_| "await within expects a non-negative timeout".to_string())?;
+            Ok(await_within(&task, Duration::from_millis(timeout), ms))
+        }
+        _ => Err(format!("unknown task builtin {name}")),
+    }
+}
+
+fn task_list_arg(name: &str, args: &[Value]) -> Result<Vec<Task>, String> {
+    let [arg] = args else {
+        return Err(format!("{name} expects 1 argument"));
+    };
+    let Value::List(items) = arg.unboxed() else {
+        return Err(format!("{name} expects a List of tasks"));
+    };
+    items
+        .into_iter()
+        .map(|item| match item.unboxed() {
+            Value::Task(task) => Ok(task),
+            _ => Err(format!("{name} expects a List of tasks")),
+        })
+        .collect()
+}
+
+/// Waits for every task and returns their values in list order. The first
+/// failure wins: the remaining tasks are cancelled and the failure is
+/// returned as-is.
+fn await_all(tasks: &[Task]) -> TaskResult {
+    let mut values: Vec<Option<Value>> = vec![None; tasks.len()];
+    loop {
+        let mut pending = false;
+        for (idx, task) in tasks.iter().enumerate() {
+            if values[idx].is_some() {
+                continue;
+            }
+            if !task.is_done() {
+                pending = true;
+                continue;
+            }
+            match task.result_raw() {
+                TaskResult::Ok(value) => values[idx] = Some(value),
+                failure => {
+                    cancel_all(tasks);
+                    return failure;
+                }
+            }
+        }
+        if !pending {
+            return TaskResult::Ok(Value::List(values.into_iter().flatten().collect()));
+        }
+        if let Some(cancelled) = cancel_if_waiter_cancelled(tasks) {
+            return cancelled;
+        }
+        thread::sleep(TASK_POLL_INTERVAL);
+    }
+}
+
+/// Returns the result of whichever task finishes first and cancels the rest.
+fn await_any(tasks: &[Task]) -> TaskResult {
+    loop {
+        if let Some(task) = tasks.iter().find(|task| task.is_done()) {
+            let result = task.result_raw();
+            cancel_all(tasks);
+            return result;
+        }
+        if let Some(cancelled) = cancel_if_waiter_cancelled(tasks) {
+            return cancelled;
+        }
+        thread::sleep(TASK_POLL_INTERVAL);
+    }
+}
+
+/// Wraps the task's value in `Ok`, or cancels it and returns an `Err` once
+/// `timeout` elapses. Failures raised by the task itself still propagate.
+fn await_within(task: &Task, timeout: Duration, ms: i64) -> TaskResult {
+    match task.result_within(timeout) {
+        Some(TaskResult::Ok(value)) => TaskResult::Ok(Value::ResultOk(Box::new(value))),
+        Some(failure) => failure,
+        None => {
+            task.cancel();
+            TaskResult::Ok(Value::ResultErr(Box::new(timeout_error(ms))))
+        }
+    }
+}
+
+fn cancel_all(tasks: &[Task]) {
+    for task in tasks {
+        task.cancel();
+    }
+}
+
+/// A group awaited from a task that was cancelled stops waiting and cancels
+/// its members.
+fn cancel_if_waiter_cancelled(tasks: &[Task]) -> Option<TaskResult> {
+    if !task_cancelled() {
+        return None;
+    }
+    cancel_all(tasks);
+    Some(TaskResult::Runtime(TASK_CANCELLED.to_string()))
+}
+
+fn timeout_error(ms: i64) -> Value {
+    let mut fields = HashMap::new();
+    fields.insert(
+        "message".to_string(),
+        Value::String(format!("task timed out after {ms}ms")),
+    );
+    Value::Struct {
+        name: "std.Error".to_string(),
+        fields,
+    }
+}
@@ -1,6 +1,10 @@
 use std::collections::{BTreeMap, HashMap};
 
-use fuse_rt::{bytes as rt_bytes, config as rt_config, json as rt_json, validate as rt_validate};
+use fuse_rt::decimal::Decimal;
+use fuse_rt::{
+    bytes as rt_bytes, config as rt_config, json as rt_json, time as rt_time,
+    validate as rt_validate,
+};
 
 use crate::ast::{Expr, TypeRef, TypeRefKind};
 use crate::interp::Value;
@@ -35,6 +39,9 @@ pub(crate) trait RuntimeTypeHost {
         args: &[Expr],
         path: &str,
     ) -> Result<(), Self::Error>;
+
+    fn struct_field_types(&self, name: &str) -> Option<Vec<(String, TypeRef)>>;
+    fn enum_payload_types(&self, name: &str, variant: &str) -> Option<Vec<TypeRef>>;
 }
 
 pub fn split_type_name(name: &str) -> (Option<&str>, &str) {
@@ -52,6 +59,7 @@ pub fn value_type_name(value: &Value) -> String {
         Value::Unit => "Unit".to_string(),
         Value::Int(_) => "Int".to_string(),
         Value::Float(_) => "Float".to_string(),
+        Value::Decimal(_) => "Decimal".to_string(),
         Value::Bool(_) => "Bool".to_string(),
         Value::String(_) => "String".to_string(),
         Value::Bytes(_) => "Bytes".to_string(),
@@ -61,6 +69,8 @@ pub fn value_type_name(value: &Value) -> String {
         Value::Map(_) => "Map".to_string(),
         Value::Query(_) => "Query".to_string(),
         Value::Task(_) => "Task".to_string(),
+        Value::Channel(_) => "Channel".to_string(),
+        Value::Range(_) => "Range".to_string(),
         Value::Iterator(_) => "Iterator".to_string(),
         Value::Struct { name, .. } => name.clone(),
         Value::Enum { name, .. } => name.clone(),
@@ -106,6 +116,12 @@ pub fn parse_simple_env(name: &str, raw: &str) -> Result<Value, String> {
             _ => Err(format!("invalid Bool: {raw}")),
         },
         "String" | "Id" | "Email" => Ok(Value::String(raw.to_string())),
+        "Instant" | "Date" | "Duration" | "DateTime" => {
+            parse_time_text(name, raw).map_err(|msg| format!("invalid {name}: {msg}"))
+        }
+        "Decimal" => Decimal::parse(raw)
+            .map(Value::Decimal)
+            .map_err(|msg| format!("invalid Decimal: {msg}")),
         "Bytes" => {
             let bytes = rt_bytes::decode_base64(raw)
                 .map_err(|msg| format!("invalid Bytes (base64): {msg}"))?;
@@ -190,7 +206,8 @@ pub(crate) fn parse_env_value<H: RuntimeTypeHost>(
         TypeRefKind::Simple(ident) => {
             let (_, simple_name) = split_type_name(&ident.name);
             match simple_name {
-                "Int" | "Float" | "Bool" | "String" | "Id" | "Email" | "Bytes" | "Html" => {
+                "Int" | "Float" | "Bool" | "String" | "Id" | "Email" | "Bytes" | "Html"
+                | "Instant" | "Date" | "Duration" | "DateTime" | "Decimal" => {
                     parse_simple_env(simple_name, raw).map_err(|msg| host.runtime_error(msg))
                 }
                 _ => {
@@ -203,6 +220,11 @@ pub(crate) fn parse_env_value<H: RuntimeTypeHost>(
         TypeRefKind::Result { .. } => {
             Err(host.runtime_error("Result is not supported for config env overrides".to_string()))
         }
+        TypeRefKind::Tuple(_) => {
+            let json = rt_json::decode(raw)
+                .map_err(|msg| host.runtime_error(format!("invalid JSON value: {msg}")))?;
+            decode_json_value(host, &json, ty, "$")
+        }
         TypeRefKind::Generic { base, args } => match base.name.as_str() {
             "Option" => {
                 if args.len() != 1 {
@@ -262,6 +284,24 @@ pub(crate) fn validate_value<H: RuntimeTypeHost>(
             host.check_refined_value(&value, &base.name, args, path)
         }
         TypeRefKind::Simple(ident) => validate_simple_value(host, &value, &ident.name, path),
+        TypeRefKind::Tuple(items) => match value {
+            Value::List(values) if values.len() == items.len() => {
+                for (idx, (item, item_ty)) in values.iter().zip(items).enumerate() {
+                    let item_path = format!("{path}[{idx}]");
+                    validate_value(host, item, item_ty, &item_path)?;
+                }
+                Ok(())
+            }
+            _ => Err(host.validation_error(
+                path,
+                "type_mismatch",
+                format!(
+                    "expected tuple of {} elements, got {}",
+                    items.len(),
+                    value_type_name(&value)
+                ),
+            )),
+        },
         TypeRefKind::Generic { base, args } => match base.name.as_str() {
             "Option" => {
                 if args.len() != 1 {
@@ -327,6 +367,19 @@ pub(crate) fn validate_value<H: RuntimeTypeHost>(
                     )),
                 }
             }
+            "Range" => {
+                if args.len() != 1 {
+                    return Err(host.runtime_error("Range expects 1 type argument".to_string()));
+                }
+                match value {
+                    Value::Range(_) => Ok(()),
+                    _ => Err(host.validation_error(
+                        path,
+                        "type_mismatch",
+                        format!("expected Range, got {}", value_type_name(&value)),
+                    )),
+                }
+            }
             _ => Err(host.runtime_error(format!("validation not supported for {}", base.name))),
         },
     }
@@ -386,6 +439,32 @@ pub(crate) fn decode_json_value<H: RuntimeTypeHost>(
         TypeRefKind::Result { ok, err } => {
             return decode_json_result_value(host, json, ok, err.as_deref(), path);
         }
+        TypeRefKind::Tuple(item_tys) => {
+            let rt_json::JsonValue::Array(items) = json else {
+                return Err(host.validation_error(
+                    path,
+                    "type_mismatch",
+                    "expected tuple array".to_string(),
+                ));
+            };
+            if items.len() != item_tys.len() {
+                return Err(host.validation_error(
+                    path,
+                    "type_mismatch",
+                    format!(
+                        "expected {} tuple elements, got {}",
+                        item_tys.len(),
+                        items.len()
+                    ),
+                ));
+            }
+            let mut values = Vec::with_capacity(items.len());
+            for (idx, (item, item_ty)) in items.iter().zip(item_tys).enumerate() {
+                let item_path = format!("{path}[{idx}]");
+                values.push(decode_json_value(host, item, item_ty, &item_path)?);
+            }
+            Value::List(values)
+        }
         TypeRefKind::Generic { base, args } => match base.name.as_str() {
             "Option" => {
                 if args.len() != 1 {
@@ -540,6 +619,43 @@ fn validate_simple_value<H: RuntimeTypeHost>(
                     ));
                 }
             },
+            "Instant" | "Date" | "Duration" => {
+                if matches!(value, Value::Int(_)) {
+                    return Ok(());
+                }
+                return Err(host.validation_error(
+                    path,
+                    "type_mismatch",
+                    format!("expected {simple_name}, got {type_name}"),
+                ));
+            }
+            "Decimal" => {
+                if matches!(value, Value::Decimal(_)) {
+                    return Ok(());
+                }
+                return Err(host.validation_error(
+                    path,
+                    "type_mismatch",
+                    format!("expected Decimal, got {type_name}"),
+                ));
+            }
+            "DateTime" => match value {
+                Value::String(s) if rt_time::parse_datetime(&s).is_ok() => return Ok(()),
+                Value::String(_) => {
+                    return Err(host.validation_error(
+                        path,
+                        "invalid_value",
+                        "invalid RFC 3339 date-time".to_string(),
+                    ));
+                }
+                _ => {
+                    return Err(host.validation_error(
+                        path,
+                        "type_mismatch",
+                        format!("expected DateTime, got {type_name}"),
+                    ));
+                }
+            },
             "Bytes" => {
                 if matches!(value, Value::Bytes(_)) {
                     return Ok(());
@@ -644,6 +760,35 @@ fn decode_simple_json<H: RuntimeTypeHost>(
                 ));
             }
         },
+        "Instant" | "Date" | "Duration" | "DateTime" => match json {
+            rt_json::JsonValue::String(v) => parse_time_text(name, v).map_err(|msg| {
+                host.validation_error(path, "invalid_value", format!("invalid {name}: {msg}"))
+            })?,
+            _ => {
+                return Err(host.validation_error(
+                    path,
+                    "type_mismatch",
+                    "expected String".to_string(),
+                ));
+            }
+        },
+        "Decimal" => {
+            let parsed = match json {
+                rt_json::JsonValue::String(v) => Decimal::parse(v),
+                rt_json::JsonValue::Number(v) => Decimal::from_json_number(*v),
+                _ => {
+                    return Err(host.validation_error(
+                        path,
+                        "type_mismatch",
+                        "expected Decimal".to_string(),
+                    ));
+                }
+            };
+            let value = parsed.map_err(|msg| {
+                host.validation_error(path, "invalid_value", format!("invalid Decimal: {msg}"))
+            })?;
+            Value::Decimal(value)
+        }
         "Html" => {
             return Err(host.validation_error(path, "type_mismatch", "expected Html".to_string()));
         }
@@ -700,11 +845,182 @@ fn decode_json_result_value<H: RuntimeTypeHost>(
     }
 }
 
+/// Parses boundary text for a builtin time type into its runtime value:
+/// `Instant` and `Duration` are Int milliseconds, `Date` is Int days since
+/// the Unix epoch and `DateTime` is normalized RFC 3339 text.
+pub fn parse_time_text(name: &str, text: &str) -> Result<Value, String> {
+    match name {
+        "Instant" => rt_time::parse_instant(text).map(Value::Int),
+        "Date" => rt_time::parse_date(text).map(Value::Int),
+        "Duration" => rt_time::parse_duration(text).map(Value::Int),
+        "DateTime" => rt_time::parse_datetime(text)
+            .map(|(unix_ms, offset)| Value::String(rt_time::format_datetime(unix_ms, offset))),
+        _ => Err(format!("{name} is not a time type")),
+    }
+}
+
+/// Renders a runtime value of a builtin time type as boundary text.
+pub fn format_time_value(name: &str, value: &Value) -> Option<String> {
+    match (name, value.unboxed()) {
+        ("Instant", Value::Int(ms)) => Some(rt_time::format_instant(ms)),
+        ("Date", Value::Int(days)) => Some(rt_time::format_date(days)),
+        ("Duration", Value::Int(ms)) => Some(rt_time::format_duration(ms)),
+        ("DateTime", Value::String(text)) => Some(text),
+        _ => None,
+    }
+}
+
+/// Checks a `"min".."max"` refinement on a time type. Returns the violation
+/// message when the value falls outside the range.
+pub(crate) fn check_time_range(
+    value: &Value,
+    base: &str,
+    min: &str,
+    max: &str,
+) -> Result<Option<String>, String> {
+    let (lo, hi) = crate::refinement::time_range_bounds(base, min, max)?;
+    let at = match value.unboxed() {
+        Value::Int(v) => v,
+        Value::String(text) if base == "DateTime" => rt_time::parse_instant(&text)?,
+        other => {
+            return Err(format!(
+                "refined {base} expects a {base}, got {}",
+                value_type_name(&other)
+            ));
+        }
+    };
+    if at >= lo && at <= hi {
+        return Ok(None);
+    }
+    let shown = format_time_value(base, value).unwrap_or_else(|| at.to_string());
+    Ok(Some(format!("value {shown} out of range {min}..{max}")))
+}
+
+/// Type-directed counterpart of [`value_to_json`] for response boundaries,
+/// where Int-backed time values are written as RFC 3339 / ISO 8601 text.
+pub(crate) fn encode_json_value<H: RuntimeTypeHost>(
+    host: &H,
+    value: &Value,
+    ty: &TypeRef,
+) -> rt_json::JsonValue {
+    let value = value.unboxed();
+    match &ty.kind {
+        TypeRefKind::Optional(inner) => match value {
+            Value::Null => rt_json::JsonValue::Null,
+            other => encode_json_value(host, &other, inner),
+        },
+        TypeRefKind::Result { ok, err } => encode_json_result(host, &value, ok, err.as_deref()),
+        TypeRefKind::Refined { base, .. } => encode_named_json(host, &value, &base.name),
+        TypeRefKind::Simple(ident) => encode_named_json(host, &value, &ident.name),
+        TypeRefKind::Tuple(items) => match value {
+            Value::List(values) if values.len() == items.len() => rt_json::JsonValue::Array(
+                values
+                    .iter()
+                    .zip(items)
+                    .map(|(item, item_ty)| encode_json_value(host, item, item_ty))
+                    .collect(),
+            ),
+            other => value_to_json(&other),
+        },
+        TypeRefKind::Generic { base, args } => match (base.name.as_str(), args.as_slice(), value) {
+            ("Option", [_], Value::Null) => rt_json::JsonValue::Null,
+            ("Option", [inner], other) => encode_json_value(host, &other, inner),
+            ("Result", [ok, err], other) => encode_json_result(host, &other, ok, Some(err)),
+            ("List", [inner], Value::List(items)) => rt_json::JsonValue::Array(
+                items
+                    .iter()
+                    .map(|item| encode_json_value(host, item, inner))
+                    .collect(),
+            ),
+            ("Map", [_, inner], Value::Map(items)) => rt_json::JsonValue::Object(
+                items
+                    .iter()
+                    .map(|(key, item)| (key.clone(), encode_json_value(host, item, inner)))
+                    .collect(),
+            ),
+            (_, _, other) => value_to_json(&other),
+        },
+    }
+}
+
+fn encode_json_result<H: RuntimeTypeHost>(
+    host: &H,
+    value: &Value,
+    ok: &TypeRef,
+    err: Option<&TypeRef>,
+) -> rt_json::JsonValue {
+    match value {
+        Value::ResultOk(inner) => encode_json_value(host, inner, ok),
+        Value::ResultErr(inner) => match err {
+            Some(err) => encode_json_value(host, inner, err),
+            None => value_to_json(inner),
+        },
+        other => encode_json_value(host, other, ok),
+    }
+}
+
+fn encode_named_json<H: RuntimeTypeHost>(
+    host: &H,
+    value: &Value,
+    name: &str,
+) -> rt_json::JsonValue {
+    let (module, simple_name) = split_type_name(name);
+    if module.is_none()
+        && let Some(text) = format_time_value(simple_name, value)
+    {
+        return rt_json::JsonValue::String(text);
+    }
+    match value {
+        Value::Struct { name, fields } => {
+            let Some(field_types) = host.struct_field_types(name) else {
+                return value_to_json(value);
+            };
+            let mut out = BTreeMap::new();
+            for (key, field) in fields {
+                let json = match field_types.iter().find(|(field_name, _)| field_name == key) {
+                    Some((_, ty)) => encode_json_value(host, field, ty),
+                    None => value_to_json(field),
+                };
+                out.insert(key.clone(), json);
+            }
+            rt_json::JsonValue::Object(out)
+        }
+        Value::Enum {
+            name,
+            variant,
+            payload,
+        } if !payload.is_empty() => {
+            let Some(payload_types) = host.enum_payload_types(name, variant) else {
+                return value_to_json(value);
+            };
+            let mut items: Vec<_> = payload
+                .iter()
+                .zip(&payload_types)
+                .map(|(item, ty)| encode_json_value(host, item, ty))
+                .collect();
+            let mut out = BTreeMap::new();
+            out.insert(
+                "type".to_string(),
+                rt_json::JsonValue::String(variant.clone()),
+            );
+            let data = if items.len() == 1 {
+                items.remove(0)
+            } else {
+                rt_json::JsonValue::Array(items)
+            };
+            out.insert("data".to_string(), data);
+            rt_json::JsonValue::Object(out)
+        }
+        other => value_to_json(other),
+    }
+}
+
 pub fn value_to_json(value: &Value) -> rt_json::JsonValue {
     match value.unboxed() {
         Value::Unit => rt_json::JsonValue::Null,
         Value::Int(v) => rt_json::JsonValue::Number(v as f64),
         Value::Float(v) => rt_json::JsonValue::Number(v),
+        Value::Decimal(v) => rt_json::JsonValue::String(v.to_string()),
         Value::Bool(v) => rt_json::JsonValue::Bool(v),
         Value::String(v) => rt_json::JsonValue::String(v.clone()),
         Value::Bytes(v) => rt_json::JsonValue::String(rt_bytes::encode_base64(&v)),
@@ -721,6 +1037,10 @@ pub fn value_to_json(value: &Value) -> rt_json::JsonValue {
         Value::Boxed(_) => rt_json::JsonValue::String("<box>".to_string()),
         Value::Query(_) => rt_json::JsonValue::String("<query>".to_string()),
         Value::Task(_) => rt_json::JsonValue::String("<task>".to_string()),
+        Value::Channel(_) => rt_json::JsonValue::String("<channel>".to_string()),
+        Value::Range(range) => {
+            rt_json::JsonValue::Array(range.iter().map(|item| value_to_json(&item)).collect())
+        }
         Value::Iterator(_) => rt_json::JsonValue::String("<iterator>".to_string()),
         Value::Struct { fields, .. } => {
             let mut out = BTreeMap::new();
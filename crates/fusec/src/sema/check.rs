@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::PathBuf;
 
 use crate::ast::{
@@ -11,11 +11,12 @@ use crate::frontend::html_tag_builtin::should_use_html_tag_builtin;
 use crate::html_tags::{self, HtmlTagKind};
 use crate::loader::{ImportedAsset, ImportedAssetKind, ModuleId, ModuleLink, ModuleMap};
 use crate::refinement::{
-    NumberLiteral, RefinementConstraint, base_is_string_like, parse_constraints,
+    NumberLiteral, RefinementConstraint, base_is_string_like, parse_constraints, time_range_bounds,
 };
 use crate::span::Span;
 
-use super::symbols::ModuleSymbols;
+use super::sql::{self, SqlColumn, SqlSchema};
+use super::symbols::{FieldInfo, ModuleSymbols};
 use super::types::{FnSig, ParamSig, Ty, TypeParamSig};
 
 const TYPED_QUERY_CALL_DIAG_CODE: &str = "FUSE_TYPED_QUERY_CALL";
@@ -27,12 +28,16 @@ const FUSE_IMPL_INCOMPLETE: &str = "FUSE_IMPL_INCOMPLETE";
 const FUSE_IMPL_SIGNATURE_MISMATCH: &str = "FUSE_IMPL_SIGNATURE_MISMATCH";
 const FUSE_IMPL_ORPHAN: &str = "FUSE_IMPL_ORPHAN";
 const FUSE_INTERFACE_NOT_A_TYPE: &str = "FUSE_INTERFACE_NOT_A_TYPE";
+const FUSE_VISIBILITY_PRIVATE: &str = "FUSE_VISIBILITY_PRIVATE";
 const FUSE_INTERFACE_DYNAMIC_USE: &str = "FUSE_INTERFACE_DYNAMIC_USE";
 const FUSE_GENERIC_DUPLICATE_TYPE_PARAM: &str = "FUSE_GENERIC_DUPLICATE_TYPE_PARAM";
 const FUSE_GENERIC_CALL_TYPE_ARG: &str = "FUSE_GENERIC_CALL_TYPE_ARG";
 const FUSE_GENERIC_INFERENCE: &str = "FUSE_GENERIC_INFERENCE";
+const FUSE_GENERIC_TYPE_ARG: &str = "FUSE_GENERIC_TYPE_ARG";
 const FUSE_WHERE_UNKNOWN_INTERFACE: &str = "FUSE_WHERE_UNKNOWN_INTERFACE";
 const FUSE_WHERE_MULTI_CONSTRAINT: &str = "FUSE_WHERE_MULTI_CONSTRAINT";
+/// Builtin interface behind `for` over user types; see [`iterator_next_item`].
+const ITERATOR_INTERFACE: &str = "Iterator";
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 enum TypedQuerySelectError {
@@ -72,7 +77,10 @@ pub struct Checker<'a> {
     declared_capabilities: HashSet<Capability>,
     used_capabilities: HashSet<Capability>,
     current_self_type: Option<Ty>,
-    type_param_scopes: Vec<HashMap<String, Option<String>>>,
+    type_param_scopes: Vec<HashMap<String, Vec<String>>>,
+    in_generic_type_decl: bool,
+    binding_mutable: bool,
+    sql_schema: Option<&'a SqlSchema>,
 }
 
 impl<'a> Checker<'a> {
@@ -156,6 +164,42 @@ impl<'a> Checker<'a> {
             }),
         );
         env.insert_builtin("assert");
+        let item = Ty::TypeParam("T".to_string());
+        for (name, ret) in [
+            ("await_all", Ty::List(Box::new(item.clone()))),
+            ("await_any", item.clone()),
+        ] {
+            env.insert_builtin_with_ty(
+                name,
+                Ty::Fn(FnSig {
+                    type_params: vec![TypeParamSig {
+                        name: "T".to_string(),
+                        interface_bounds: Vec::new(),
+                    }],
+                    params: vec![ParamSig {
+                        name: "tasks".to_string(),
+                        ty: Ty::List(Box::new(Ty::Task(Box::new(item.clone())))),
+                        has_default: false,
+                    }],
+                    ret: Box::new(ret),
+                }),
+            );
+        }
+        env.insert_builtin_with_ty(
+            "channel",
+            Ty::Fn(FnSig {
+                type_params: vec![TypeParamSig {
+                    name: "T".to_string(),
+                    interface_bounds: Vec::new(),
+                }],
+                params: vec![ParamSig {
+                    name: "capacity".to_string(),
+                    ty: Ty::Int,
+                    has_default: false,
+                }],
+                ret: Box::new(Ty::Channel(Box::new(item.clone()))),
+            }),
+        );
         env.insert_builtin_with_ty(
             "asset",
             Ty::Fn(FnSig {
@@ -170,6 +214,7 @@ impl<'a> Checker<'a> {
         );
         env.insert_builtin("serve");
         env.insert_builtin_with_ty("crypto", Ty::External("crypto".to_string()));
+        env.insert_builtin_with_ty("decimal", Ty::External("decimal".to_string()));
         env.insert_builtin_with_ty("task", Ty::External("task".to_string()));
         env.insert_builtin_with_ty("html", Ty::External("html".to_string()));
         env.insert_builtin_with_ty("svg", Ty::External("svg".to_string()));
@@ -202,15 +247,24 @@ impl<'a> Checker<'a> {
             used_capabilities: HashSet::new(),
             current_self_type: None,
             type_param_scopes: vec![HashMap::new()],
+            in_generic_type_decl: false,
+            binding_mutable: false,
+            sql_schema: None,
         }
     }
 
+    /// Enables compile-time SQL checks against a migration-derived schema.
+    pub fn set_sql_schema(&mut self, schema: Option<&'a SqlSchema>) {
+        self.sql_schema = schema;
+    }
+
     pub fn check_program(&mut self, program: &Program) {
         for item in &program.items {
             match item {
                 Item::Fn(decl) => self.check_fn_decl(decl),
                 Item::Component(decl) => self.check_component_decl(decl),
                 Item::Config(decl) => self.check_config_decl(decl),
+                Item::Const(decl) => self.check_const_decl(decl),
                 Item::Service(decl) => self.check_service_decl(decl),
                 Item::App(decl) => {
                     self.env.push();
@@ -222,12 +276,29 @@ impl<'a> Checker<'a> {
                     let _ = self.check_block(&decl.body);
                     self.env.pop();
                 }
+                Item::Seed(decl) => {
+                    self.env.push();
+                    let _ = self.check_block(&decl.body);
+                    self.env.pop();
+                }
                 Item::Migration(decl) => {
+                    // Migration SQL is checked while the schema is replayed,
+                    // against the tables that exist at that point.
+                    let sql_schema = self.sql_schema.take();
                     self.env.push();
                     let _ = self.check_block(&decl.body);
                     self.env.pop();
+                    if let Some(down) = &decl.down {
+                        self.env.push();
+                        let _ = self.check_block(down);
+                        self.env.pop();
+                    }
+                    self.sql_schema = sql_schema;
                 }
                 Item::Type(decl) => self.check_type_decl(decl),
+                Item::TypeAlias(decl) => {
+                    let _ = self.resolve_type_ref(&decl.target);
+                }
                 Item::Enum(decl) => self.check_enum_decl(decl),
                 Item::Interface(decl) => self.check_interface_decl(decl),
                 Item::Impl(decl) => self.check_impl_decl(decl),
@@ -250,7 +321,7 @@ impl<'a> Checker<'a> {
         }
     }
 
-    fn lookup_type_param_bound(&self, name: &str) -> Option<Option<String>> {
+    fn lookup_type_param_bound(&self, name: &str) -> Option<Vec<String>> {
         for scope in self.type_param_scopes.iter().rev() {
             if let Some(bound) = scope.get(name) {
                 return Some(bound.clone());
@@ -317,10 +388,10 @@ impl<'a> Checker<'a> {
             self.type_param_scopes
                 .last_mut()
                 .expect("type param scope should exist")
-                .insert(name.clone(), None);
+                .insert(name.clone(), Vec::new());
             resolved.push(TypeParamSig {
                 name,
-                interface_bound: None,
+                interface_bounds: Vec::new(),
             });
         }
         for constraint in where_clause {
@@ -330,22 +401,28 @@ impl<'a> Checker<'a> {
                 .last()
                 .and_then(|scope| scope.get(&name))
                 .cloned();
-            let Some(bound) = existing else {
+            let Some(bounds) = existing else {
                 self.diags.error(
                     constraint.type_param.span,
                     format!("unknown type parameter {} in where clause", name),
                 );
                 continue;
             };
-            if bound.is_some() {
+            if bounds.contains(&constraint.interface.name) {
                 self.diags.error_with_code(
                     constraint.span,
                     FUSE_WHERE_MULTI_CONSTRAINT,
-                    format!("type parameter {} already has an interface constraint", name),
+                    format!(
+                        "type parameter {} is already constrained by {}",
+                        name, constraint.interface.name
+                    ),
                 );
                 continue;
             }
-            if self.interface_info_in_scope(&constraint.interface.name).is_none() {
+            if self
+                .interface_info_in_scope(&constraint.interface.name)
+                .is_none()
+            {
                 self.diags.error_with_code(
                     constraint.interface.span,
                     FUSE_WHERE_UNKNOWN_INTERFACE,
@@ -353,15 +430,17 @@ impl<'a> Checker<'a> {
                 );
                 continue;
             }
-            if let Some(bound) = self
+            if let Some(bounds) = self
                 .type_param_scopes
                 .last_mut()
                 .and_then(|scope| scope.get_mut(&name))
             {
-                *bound = Some(constraint.interface.name.clone());
+                bounds.push(constraint.interface.name.clone());
             }
             if let Some(idx) = index.get(&name) {
-                resolved[*idx].interface_bound = Some(constraint.interface.name.clone());
+                resolved[*idx]
+                    .interface_bounds
+                    .push(constraint.interface.name.clone());
             }
         }
         resolved
@@ -392,10 +471,10 @@ impl<'a> Checker<'a> {
             self.type_param_scopes
                 .last_mut()
                 .expect("type param scope should exist")
-                .insert(param.name.clone(), None);
+                .insert(param.name.clone(), Vec::new());
             resolved.push(TypeParamSig {
                 name: param.name.clone(),
-                interface_bound: None,
+                interface_bounds: Vec::new(),
             });
         }
         for constraint in where_clause {
@@ -404,25 +483,31 @@ impl<'a> Checker<'a> {
                 .last()
                 .and_then(|scope| scope.get(&constraint.type_param))
                 .cloned();
-            let Some(bound) = existing else {
+            let Some(bounds) = existing else {
                 self.diags.error(
                     constraint.span,
-                    format!("unknown type parameter {} in where clause", constraint.type_param),
+                    format!(
+                        "unknown type parameter {} in where clause",
+                        constraint.type_param
+                    ),
                 );
                 continue;
             };
-            if bound.is_some() {
+            if bounds.contains(&constraint.interface) {
                 self.diags.error_with_code(
                     constraint.span,
                     FUSE_WHERE_MULTI_CONSTRAINT,
                     format!(
-                        "type parameter {} already has an interface constraint",
-                        constraint.type_param
+                        "type parameter {} is already constrained by {}",
+                        constraint.type_param, constraint.interface
                     ),
                 );
                 continue;
             }
-            if self.interface_info_in(module_id, &constraint.interface).is_none() {
+            if self
+                .interface_info_in(module_id, &constraint.interface)
+                .is_none()
+            {
                 self.diags.error_with_code(
                     constraint.span,
                     FUSE_WHERE_UNKNOWN_INTERFACE,
@@ -430,21 +515,33 @@ impl<'a> Checker<'a> {
                 );
                 continue;
             }
-            if let Some(bound) = self
+            if let Some(bounds) = self
                 .type_param_scopes
                 .last_mut()
                 .and_then(|scope| scope.get_mut(&constraint.type_param))
             {
-                *bound = Some(constraint.interface.clone());
+                bounds.push(constraint.interface.clone());
             }
             if let Some(idx) = index.get(&constraint.type_param) {
-                resolved[*idx].interface_bound = Some(constraint.interface.clone());
+                resolved[*idx]
+                    .interface_bounds
+                    .push(constraint.interface.clone());
             }
         }
         resolved
     }
 
     fn check_type_decl(&mut self, decl: &crate::ast::TypeDecl) {
+        if !decl.type_params.is_empty() {
+            // Field defaults are checked on each instance, where the
+            // parameters are concrete.
+            self.with_generic_type_decl(&decl.type_params, |this| {
+                for field in &decl.fields {
+                    let _ = this.resolve_type_ref(&field.ty);
+                }
+            });
+            return;
+        }
         for field in &decl.fields {
             let field_ty = self.resolve_type_ref(&field.ty);
             if let Some(default) = &field.default {
@@ -457,14 +554,42 @@ impl<'a> Checker<'a> {
     }
 
     fn check_enum_decl(&mut self, decl: &crate::ast::EnumDecl) {
-        for variant in &decl.variants {
-            for ty in &variant.payload {
-                let _ = self.resolve_type_ref(ty);
+        self.with_generic_type_decl(&decl.type_params, |this| {
+            for variant in &decl.variants {
+                for ty in &variant.payload {
+                    let _ = this.resolve_type_ref(ty);
+                }
             }
-        }
+        });
+    }
+
+    fn with_generic_type_decl<F>(&mut self, type_params: &[crate::ast::TypeParam], f: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        self.with_ast_type_params(type_params, &[], |this, _| {
+            let prev = std::mem::replace(&mut this.in_generic_type_decl, !type_params.is_empty());
+            f(this);
+            this.in_generic_type_decl = prev;
+        });
     }
 
     fn check_interface_decl(&mut self, decl: &crate::ast::InterfaceDecl) {
+        for parent in &decl.extends {
+            if self.interface_info_in_scope(&parent.name).is_none() {
+                self.diags
+                    .error(parent.span, format!("unknown interface {}", parent.name));
+            } else if self
+                .interface_ancestry(self.module_id, &parent.name)
+                .iter()
+                .any(|(owner, info)| *owner == self.module_id && info.name == decl.name.name)
+            {
+                self.diags.error(
+                    parent.span,
+                    format!("interface {} cannot extend itself", decl.name.name),
+                );
+            }
+        }
         let prev_self = self.current_self_type.clone();
         self.current_self_type = Some(Ty::SelfType);
         for member in &decl.members {
@@ -489,11 +614,61 @@ impl<'a> Checker<'a> {
             });
         }
         self.current_self_type = prev_self;
+        self.check_interface_default_bodies(decl);
+    }
+
+    /// Default bodies are checked once, generically: `Self` is a type
+    /// parameter bounded by the interface, so they may only rely on its
+    /// members.
+    fn check_interface_default_bodies(&mut self, decl: &crate::ast::InterfaceDecl) {
+        let prev_self = self.current_self_type.clone();
+        self.current_self_type = Some(Ty::TypeParam("Self".to_string()));
+        self.push_type_param_scope();
+        if let Some(scope) = self.type_param_scopes.last_mut() {
+            scope.insert("Self".to_string(), vec![decl.name.name.clone()]);
+        }
+        for member in &decl.members {
+            let Some(body) = &member.body else {
+                continue;
+            };
+            let uses_self = super::symbols::interface_member_uses_self(member);
+            self.with_ast_type_params(&member.type_params, &member.where_clause, |this, _| {
+                let params: Vec<(String, Ty)> = member
+                    .params
+                    .iter()
+                    .map(|param| (param.name.name.clone(), this.resolve_type_ref(&param.ty)))
+                    .collect();
+                let ret = member
+                    .ret
+                    .as_ref()
+                    .map(|ty| this.resolve_type_ref(ty))
+                    .unwrap_or(Ty::Unit);
+                let prev_return = this.current_return.replace(ret);
+                this.env.push();
+                if uses_self {
+                    this.insert_var(
+                        "self",
+                        Ty::TypeParam("Self".to_string()),
+                        false,
+                        member.span,
+                    );
+                }
+                for (name, ty) in params {
+                    this.insert_var(&name, ty, false, member.span);
+                }
+                let _ = this.check_block(body);
+                this.env.pop();
+                this.current_return = prev_return;
+            });
+        }
+        self.pop_type_param_scope();
+        self.current_self_type = prev_self;
     }
 
     fn check_impl_decl(&mut self, decl: &crate::ast::ImplDecl) {
-        let interface_info = self.interface_info_in_scope(&decl.interface.name).cloned();
-        if interface_info.is_none() {
+        let interface_known = self.interface_info_in_scope(&decl.interface.name).is_some();
+        let builtin_iterator = !interface_known && decl.interface.name == ITERATOR_INTERFACE;
+        if !interface_known && !builtin_iterator {
             self.diags.error(
                 decl.interface.span,
                 format!("unknown interface {}", decl.interface.name),
@@ -520,19 +695,28 @@ impl<'a> Checker<'a> {
                 );
             }
             let uses_self = self
-                .impl_method_uses_self(self.module_id, &decl.interface.name, &decl.target.name, &method.name.name)
+                .impl_method_uses_self(
+                    self.module_id,
+                    &decl.interface.name,
+                    &decl.target.name,
+                    &method.name.name,
+                )
                 .unwrap_or(false);
             self.check_impl_method(method, target_ty.clone(), uses_self);
         }
 
-        let Some(interface_info) = interface_info else {
+        if builtin_iterator {
+            self.check_iterator_impl(decl, &target_ty, &methods_by_name);
+        }
+        if !interface_known {
             return;
-        };
-        let interface_owner = self
-            .interface_owner_in_scope(&decl.interface.name)
-            .unwrap_or(self.module_id);
-        for member in &interface_info.members {
+        }
+        let members = self.interface_members_with_parents(self.module_id, &decl.interface.name);
+        for (interface_owner, member) in &members {
             let Some(method) = methods_by_name.get(&member.name) else {
+                if member.has_default {
+                    continue;
+                }
                 self.diags.error_with_code(
                     decl.span,
                     FUSE_IMPL_INCOMPLETE,
@@ -544,7 +728,7 @@ impl<'a> Checker<'a> {
                 continue;
             };
             let expected =
-                self.resolve_interface_member_sig_for_target(interface_owner, member, &target_ty);
+                self.resolve_interface_member_sig_for_target(*interface_owner, member, &target_ty);
             let actual = self.resolve_impl_method_sig(method, &target_ty);
             if !self.fn_sig_matches(&expected, &actual) {
                 self.diags.error_with_code(
@@ -559,40 +743,105 @@ impl<'a> Checker<'a> {
         }
     }
 
-    fn check_impl_method(
+    fn check_iterator_impl(
         &mut self,
-        decl: &crate::ast::FnDecl,
-        target_ty: Ty,
-        uses_self: bool,
+        decl: &crate::ast::ImplDecl,
+        target_ty: &Ty,
+        methods_by_name: &HashMap<String, &crate::ast::FnDecl>,
     ) {
+        let Some(method) = methods_by_name.get("next") else {
+            self.diags.error_with_code(
+                decl.span,
+                FUSE_IMPL_INCOMPLETE,
+                format!(
+                    "impl {} for {} is missing method next",
+                    ITERATOR_INTERFACE, decl.target.name
+                ),
+            );
+            return;
+        };
+        let uses_self = self
+            .impl_method_uses_self(
+                self.module_id,
+                ITERATOR_INTERFACE,
+                &decl.target.name,
+                "next",
+            )
+            .unwrap_or(false);
+        let sig = self.resolve_impl_method_sig(method, target_ty);
+        if !uses_self || iterator_next_item(&sig, target_ty).is_none() {
+            self.diags.error_with_code(
+                method.span,
+                FUSE_IMPL_SIGNATURE_MISMATCH,
+                format!(
+                    "impl method next does not match interface {}; expected fn next() -> (T, {})? using self",
+                    ITERATOR_INTERFACE, decl.target.name
+                ),
+            );
+        }
+    }
+
+    /// Item type of a `for` over a struct or enum with an `impl Iterator`.
+    fn iterator_item_ty(&mut self, ty: &Ty, span: Span) -> Option<Ty> {
+        let (Ty::Struct(name) | Ty::Enum(name)) = ty else {
+            return None;
+        };
+        let implements = self.module_symbols.values().any(|symbols| {
+            symbols
+                .impls
+                .iter()
+                .any(|info| info.interface == ITERATOR_INTERFACE && info.target == *name)
+        });
+        if !implements {
+            return None;
+        }
+        let next = crate::ast::Ident {
+            name: "next".to_string(),
+            span,
+        };
+        let resolved = self.resolve_impl_method(name, &next, InterfaceMethodKind::Instance)?;
+        iterator_next_item(&resolved.sig, ty)
+    }
+
+    fn check_impl_method(&mut self, decl: &crate::ast::FnDecl, target_ty: Ty, uses_self: bool) {
         let prev_self = self.current_self_type.clone();
         self.current_self_type = Some(target_ty.clone());
-        self.with_ast_type_params(&decl.type_params, &decl.where_clause, |this, type_params| {
-            let sig = this.resolve_fn_sig_scoped(decl, type_params);
-            if let Some(ret) = &decl.ret {
-                this.validate_return_error_domains(ret.span, sig.ret.as_ref(), "impl method return type");
-            }
-            let prev_return = this.current_return.replace(*sig.ret.clone());
-            this.env.push();
-            if uses_self {
-                this.insert_var("self", target_ty.clone(), false, decl.span);
-            }
-            for param in &sig.params {
-                this.insert_var(&param.name, param.ty.clone(), false, decl.span);
-            }
-            let _ = this.check_block(&decl.body);
-            this.env.pop();
-            this.current_return = prev_return;
-        });
+        self.with_ast_type_params(
+            &decl.type_params,
+            &decl.where_clause,
+            |this, type_params| {
+                let sig = this.resolve_fn_sig_scoped(decl, type_params);
+                if let Some(ret) = &decl.ret {
+                    this.validate_return_error_domains(
+                        ret.span,
+                        sig.ret.as_ref(),
+                        "impl method return type",
+                    );
+                }
+                let prev_return = this.current_return.replace(*sig.ret.clone());
+                this.env.push();
+                if uses_self {
+                    this.insert_var("self", target_ty.clone(), false, decl.span);
+                }
+                for param in &sig.params {
+                    this.insert_var(&param.name, param.ty.clone(), false, decl.span);
+                }
+                let _ = this.check_block(&decl.body);
+                this.env.pop();
+                this.current_return = prev_return;
+            },
+        );
         self.current_self_type = prev_self;
     }
 
     fn resolve_impl_method_sig(&mut self, decl: &crate::ast::FnDecl, target_ty: &Ty) -> FnSig {
         let prev_self = self.current_self_type.clone();
         self.current_self_type = Some(target_ty.clone());
-        let sig = self.with_ast_type_params(&decl.type_params, &decl.where_clause, |this, type_params| {
-            this.resolve_fn_sig_scoped(decl, type_params)
-        });
+        let sig = self.with_ast_type_params(
+            &decl.type_params,
+            &decl.where_clause,
+            |this, type_params| this.resolve_fn_sig_scoped(decl, type_params),
+        );
         self.current_self_type = prev_self;
         sig
     }
@@ -605,27 +854,32 @@ impl<'a> Checker<'a> {
     ) -> FnSig {
         let prev_self = self.current_self_type.clone();
         self.current_self_type = Some(target_ty.clone());
-        let sig = self.with_ref_type_params(module_id, &member.type_params, &member.where_clause, |this, type_params| {
-            let params = member
-                .params
-                .iter()
-                .map(|param| ParamSig {
-                    name: param.name.clone(),
-                    ty: this.resolve_type_ref_in(module_id, &param.ty),
-                    has_default: param.has_default,
-                })
-                .collect();
-            let ret = member
-                .ret
-                .as_ref()
-                .map(|ty| this.resolve_type_ref_in(module_id, ty))
-                .unwrap_or(Ty::Unit);
-            FnSig {
-                type_params,
-                params,
-                ret: Box::new(ret),
-            }
-        });
+        let sig = self.with_ref_type_params(
+            module_id,
+            &member.type_params,
+            &member.where_clause,
+            |this, type_params| {
+                let params = member
+                    .params
+                    .iter()
+                    .map(|param| ParamSig {
+                        name: param.name.clone(),
+                        ty: this.resolve_type_ref_in(module_id, &param.ty),
+                        has_default: param.has_default,
+                    })
+                    .collect();
+                let ret = member
+                    .ret
+                    .as_ref()
+                    .map(|ty| this.resolve_type_ref_in(module_id, ty))
+                    .unwrap_or(Ty::Unit);
+                FnSig {
+                    type_params,
+                    params,
+                    ret: Box::new(ret),
+                }
+            },
+        );
         self.current_self_type = prev_self;
         sig
     }
@@ -638,7 +892,7 @@ impl<'a> Checker<'a> {
             .type_params
             .iter()
             .zip(&actual.type_params)
-            .all(|(left, right)| left.interface_bound == right.interface_bound)
+            .all(|(left, right)| left.interface_bounds == right.interface_bounds)
         {
             return false;
         }
@@ -648,11 +902,15 @@ impl<'a> Checker<'a> {
         if expected.ret != actual.ret {
             return false;
         }
-        expected.params.iter().zip(&actual.params).all(|(left, right)| {
-            left.name == right.name
-                && left.ty == right.ty
-                && left.has_default == right.has_default
-        })
+        expected
+            .params
+            .iter()
+            .zip(&actual.params)
+            .all(|(left, right)| {
+                left.name == right.name
+                    && left.ty == right.ty
+                    && left.has_default == right.has_default
+            })
     }
 
     fn check_config_decl(&mut self, decl: &crate::ast::ConfigDecl) {
@@ -665,6 +923,24 @@ impl<'a> Checker<'a> {
         }
     }
 
+    fn check_const_decl(&mut self, decl: &crate::ast::ConstDecl) {
+        let declared = self.resolve_type_ref(&decl.ty);
+        let value_ty = self.check_expr(&decl.value);
+        // Canonicalization replaces every foldable value with its literal
+        // form, so anything else could not be evaluated at compile time.
+        if !is_folded_const(&decl.value) {
+            self.diags.error(
+                decl.value.span,
+                format!(
+                    "const {} must be a compile-time constant expression",
+                    decl.name.name
+                ),
+            );
+        } else if !self.is_assignable_with_refined(&value_ty, &declared) {
+            self.type_mismatch(decl.value.span, &declared, &value_ty);
+        }
+    }
+
     fn check_service_decl(&mut self, decl: &crate::ast::ServiceDecl) {
         for route in &decl.routes {
             self.env.push();
@@ -690,20 +966,28 @@ impl<'a> Checker<'a> {
     }
 
     fn check_fn_decl(&mut self, decl: &crate::ast::FnDecl) {
-        self.with_ast_type_params(&decl.type_params, &decl.where_clause, |this, type_params| {
-            let sig = this.resolve_fn_sig_scoped(decl, type_params);
-            if let Some(ret) = &decl.ret {
-                this.validate_return_error_domains(ret.span, sig.ret.as_ref(), "function return type");
-            }
-            this.current_return = Some(*sig.ret.clone());
-            this.env.push();
-            for param in &sig.params {
-                this.insert_var(&param.name, param.ty.clone(), false, decl.span);
-            }
-            let _ = this.check_block(&decl.body);
-            this.env.pop();
-            this.current_return = None;
-        });
+        self.with_ast_type_params(
+            &decl.type_params,
+            &decl.where_clause,
+            |this, type_params| {
+                let sig = this.resolve_fn_sig_scoped(decl, type_params);
+                if let Some(ret) = &decl.ret {
+                    this.validate_return_error_domains(
+                        ret.span,
+                        sig.ret.as_ref(),
+                        "function return type",
+                    );
+                }
+                this.current_return = Some(*sig.ret.clone());
+                this.env.push();
+                for param in &sig.params {
+                    this.insert_var(&param.name, param.ty.clone(), false, decl.span);
+                }
+                let _ = this.check_block(&decl.body);
+                this.env.pop();
+                this.current_return = None;
+            },
+        );
     }
 
     fn check_component_decl(&mut self, decl: &crate::ast::ComponentDecl) {
@@ -772,27 +1056,32 @@ impl<'a> Checker<'a> {
         }
         let symbols = self.module_symbols.get(&module_id)?;
         let sig_ref = symbols.functions.get(name)?;
-        let sig = self.with_ref_type_params(module_id, &sig_ref.type_params, &sig_ref.where_clause, |this, type_params| {
-            let params = sig_ref
-                .params
-                .iter()
-                .map(|param| ParamSig {
-                    name: param.name.clone(),
-                    ty: this.resolve_type_ref_in(module_id, &param.ty),
-                    has_default: param.has_default,
-                })
-                .collect();
-            let ret = sig_ref
-                .ret
-                .as_ref()
-                .map(|ty| this.resolve_type_ref_in(module_id, ty))
-                .unwrap_or(Ty::Unit);
-            FnSig {
-                type_params,
-                params,
-                ret: Box::new(ret),
-            }
-        });
+        let sig = self.with_ref_type_params(
+            module_id,
+            &sig_ref.type_params,
+            &sig_ref.where_clause,
+            |this, type_params| {
+                let params = sig_ref
+                    .params
+                    .iter()
+                    .map(|param| ParamSig {
+                        name: param.name.clone(),
+                        ty: this.resolve_type_ref_in(module_id, &param.ty),
+                        has_default: param.has_default,
+                    })
+                    .collect();
+                let ret = sig_ref
+                    .ret
+                    .as_ref()
+                    .map(|ty| this.resolve_type_ref_in(module_id, ty))
+                    .unwrap_or(Ty::Unit);
+                FnSig {
+                    type_params,
+                    params,
+                    ret: Box::new(ret),
+                }
+            },
+        );
         self.fn_cache
             .insert((module_id, name.to_string()), sig.clone());
         Some(sig)
@@ -878,7 +1167,11 @@ impl<'a> Checker<'a> {
                     return;
                 };
                 match base_ident.name.as_str() {
-                    "db" if matches!(name.name.as_str(), "exec" | "query" | "one" | "from") => {
+                    "db" if matches!(
+                        name.name.as_str(),
+                        "exec" | "execute" | "query" | "one" | "from" | "rollback"
+                    ) =>
+                    {
                         self.require_capability(span, Capability::Db, "db call")
                     }
                     "http" => self.require_capability(
@@ -970,6 +1263,31 @@ impl<'a> Checker<'a> {
                 }
                 Ty::Unit
             }
+            StmtKind::LetPattern {
+                pat,
+                ty,
+                expr,
+                mutable,
+            } => {
+                let value_ty = self.check_expr(expr);
+                let final_ty = if let Some(ty_ref) = ty {
+                    let ann_ty = self.resolve_type_ref(ty_ref);
+                    if !self.is_assignable(&value_ty, &ann_ty) {
+                        self.type_mismatch(expr.span, &ann_ty, &value_ty);
+                    }
+                    ann_ty
+                } else {
+                    value_ty
+                };
+                if let Some(span) = refutable_pattern_span(pat) {
+                    self.diags
+                        .error(span, "let patterns must be irrefutable; use match instead");
+                }
+                let prev = std::mem::replace(&mut self.binding_mutable, *mutable);
+                self.bind_pattern(pat, &final_ty);
+                self.binding_mutable = prev;
+                Ty::Unit
+            }
             StmtKind::Assign { target, expr } => {
                 if self.in_spawn_scope() {
                     if let Some(root) = lvalue_root_name(target) {
@@ -1046,10 +1364,14 @@ impl<'a> Checker<'a> {
             }
             StmtKind::Match { expr, cases } => {
                 let expr_ty = self.check_expr(expr);
-                for (pat, block) in cases {
+                for case in cases {
                     self.env.push();
-                    self.bind_pattern(pat, &expr_ty);
-                    let _ = self.check_block(block);
+                    self.bind_pattern(&case.pat, &expr_ty);
+                    if let Some(guard) = &case.guard {
+                        let guard_ty = self.check_expr(guard);
+                        self.expect_bool(guard.span, &guard_ty);
+                    }
+                    let _ = self.check_block(&case.block);
                     self.env.pop();
                 }
                 Ty::Unit
@@ -1057,9 +1379,11 @@ impl<'a> Checker<'a> {
             StmtKind::For { pat, iter, block } => {
                 let iter_ty = self.check_expr(iter);
                 let item_ty = match iter_ty {
-                    Ty::List(inner) => *inner,
+                    Ty::List(inner) | Ty::Range(inner) => *inner,
+                    Ty::Map(key, value) if pat.is_pair() => Ty::Tuple(vec![*key, *value]),
                     Ty::Map(_, value) => *value,
                     Ty::Unknown => Ty::Unknown,
+                    other if let Some(item) = self.iterator_item_ty(&other, iter.span) => item,
                     other => {
                         self.diags
                             .error(iter.span, format!("cannot iterate over type {}", other));
@@ -1140,6 +1464,10 @@ impl<'a> Checker<'a> {
                     crate::ast::UnaryOp::Neg => {
                         if self.is_numeric(&inner_ty) {
                             inner_ty
+                        } else if self.time_base(&inner_ty) == Some(Ty::Duration) {
+                            Ty::Duration
+                        } else if self.is_decimal(&inner_ty) {
+                            Ty::Decimal
                         } else {
                             self.diags
                                 .error(expr.span, "unary '-' requires numeric type");
@@ -1183,11 +1511,28 @@ impl<'a> Checker<'a> {
                     }
                 }
                 self.enforce_call_capabilities(callee, expr.span);
+                if let ExprKind::Ident(ident) = &callee.kind
+                    && matches!(ident.name.as_str(), "await_all" | "await_any")
+                {
+                    if self.transaction_scope_depth > 0 {
+                        self.diags
+                            .error(expr.span, "transaction blocks cannot await tasks");
+                    }
+                    if let Some(ExprKind::ListLit(items)) = args.first().map(|arg| &arg.value.kind)
+                    {
+                        for item in items {
+                            if let ExprKind::Ident(task) = &item.kind {
+                                self.env.clear_pending_spawn(&task.name);
+                            }
+                        }
+                    }
+                }
                 if !type_args.is_empty()
                     && matches!(
                         &callee.kind,
-                        ExprKind::Member { name, .. }
+                        ExprKind::Member { base, name }
                             if matches!(name.name.as_str(), "one" | "all")
+                                && !matches!(&base.kind, ExprKind::Ident(ident) if ident.name == "db")
                     )
                 {
                     return self.check_typed_query_call(expr.span, callee, args, type_args);
@@ -1195,7 +1540,7 @@ impl<'a> Checker<'a> {
                 if let ExprKind::Member { base, name } = &callee.kind {
                     if let ExprKind::Ident(ident) = &base.kind {
                         if ident.name == "db"
-                            && matches!(name.name.as_str(), "exec" | "query" | "one")
+                            && matches!(name.name.as_str(), "exec" | "execute" | "query" | "one")
                         {
                             if uses_html_block {
                                 self.diags.error(
@@ -1206,6 +1551,24 @@ impl<'a> Checker<'a> {
                             if args.len() < 1 || args.len() > 2 {
                                 self.diags.error(expr.span, "db.* expects 1 or 2 arguments");
                             }
+                            let row_ty =
+                                if type_args.is_empty() {
+                                    None
+                                } else {
+                                    Some(self.check_typed_raw_query(
+                                        expr.span, &name.name, args, type_args,
+                                    ))
+                                };
+                            if let Some(problem) = self
+                                .sql_schema
+                                .and_then(|schema| schema.check_raw_call(args))
+                            {
+                                self.diags.error_with_code(
+                                    problem.span,
+                                    problem.code,
+                                    problem.message,
+                                );
+                            }
                             if let Some(first) = args.get(0) {
                                 let arg_ty = self.check_expr(&first.value);
                                 if !self.is_assignable(&arg_ty, &Ty::String) {
@@ -1224,8 +1587,16 @@ impl<'a> Checker<'a> {
                                     }
                                 }
                             }
+                            if let Some(row_ty) = row_ty {
+                                return match name.name.as_str() {
+                                    "query" => Ty::List(Box::new(row_ty)),
+                                    "one" => Ty::Option(Box::new(row_ty)),
+                                    _ => Ty::Unknown,
+                                };
+                            }
                             return match name.name.as_str() {
                                 "exec" => Ty::Unit,
+                                "execute" => Ty::External("db.result".to_string()),
                                 "query" => Ty::List(Box::new(Ty::Map(
                                     Box::new(Ty::String),
                                     Box::new(Ty::Unknown),
@@ -1239,10 +1610,19 @@ impl<'a> Checker<'a> {
                         }
                     }
                 }
+                if let ExprKind::Member { name, .. } = &callee.kind
+                    && matches!(
+                        name.name.as_str(),
+                        "one" | "all" | "count" | "exec" | "execute" | "sql" | "params"
+                    )
+                {
+                    self.check_query_chain_sql(expr);
+                }
                 let callee_ty = self.check_expr(callee);
                 match callee_ty {
                     Ty::Fn(raw_sig) => {
-                        let sig = self.instantiate_function_call_sig(expr.span, &raw_sig, args, type_args);
+                        let sig = self
+                            .instantiate_function_call_sig(expr.span, &raw_sig, args, type_args);
                         if uses_html_block && !matches!(sig.ret.as_ref(), Ty::Html | Ty::Unknown) {
                             self.diags.error(
                                 expr.span,
@@ -1299,6 +1679,7 @@ impl<'a> Checker<'a> {
             ExprKind::Index { base, index } => self.check_index(base, index, false),
             ExprKind::OptionalIndex { base, index } => self.check_index(base, index, true),
             ExprKind::StructLit { name, fields } => self.check_struct_lit(name, fields, expr.span),
+            ExprKind::RecordUpdate { base, fields } => self.check_record_update(base, fields),
             ExprKind::ListLit(items) => {
                 let mut elem_ty = Ty::Unknown;
                 for item in items {
@@ -1307,6 +1688,9 @@ impl<'a> Checker<'a> {
                 }
                 Ty::List(Box::new(elem_ty))
             }
+            ExprKind::TupleLit(items) => {
+                Ty::Tuple(items.iter().map(|item| self.check_expr(item)).collect())
+            }
             ExprKind::MapLit(pairs) => {
                 let mut key_ty = Ty::Unknown;
                 let mut val_ty = Ty::Unknown;
@@ -1407,7 +1791,7 @@ impl<'a> Checker<'a> {
             } => {
                 let iter_ty = self.check_expr(iter);
                 let item_ty = match iter_ty {
-                    Ty::List(inner) => *inner,
+                    Ty::List(inner) | Ty::Range(inner) => *inner,
                     Ty::Map(_, value) => *value,
                     Ty::Unknown => Ty::Unknown,
                     other => {
@@ -1422,13 +1806,16 @@ impl<'a> Checker<'a> {
                 self.env.pop();
                 Ty::List(Box::new(Ty::Html))
             }
-            ExprKind::Await { expr: inner } => {
+            ExprKind::Await {
+                expr: inner,
+                timeout,
+            } => {
                 if self.transaction_scope_depth > 0 {
                     self.diags
                         .error(expr.span, "transaction blocks cannot await tasks");
                 }
                 let inner_ty = self.check_expr(inner);
-                match inner_ty {
+                let value_ty = match inner_ty {
                     Ty::Task(task_inner) => {
                         if let ExprKind::Ident(ident) = &inner.kind {
                             self.env.clear_pending_spawn(&ident.name);
@@ -1441,7 +1828,15 @@ impl<'a> Checker<'a> {
                             .error(expr.span, format!("await expects Task, got {}", other));
                         Ty::Unknown
                     }
+                };
+                let Some(timeout) = timeout else {
+                    return value_ty;
+                };
+                let timeout_ty = self.check_expr(timeout);
+                if !self.is_assignable(&timeout_ty, &Ty::Int) {
+                    self.type_mismatch(timeout.span, &Ty::Int, &timeout_ty);
                 }
+                Ty::Result(Box::new(value_ty), Box::new(Ty::Error))
             }
             ExprKind::Box { expr: inner } => {
                 if self.in_spawn_scope() {
@@ -1463,6 +1858,20 @@ impl<'a> Checker<'a> {
         span: Span,
     ) -> Ty {
         if let Some(info) = self.type_info(&name.name) {
+            if !info.type_params.is_empty() {
+                self.diags.error_with_code(
+                    name.span,
+                    FUSE_GENERIC_TYPE_ARG,
+                    format!(
+                        "generic type {0} needs explicit type arguments: {0}<...>(...)",
+                        name.name
+                    ),
+                );
+                for field in fields {
+                    let _ = self.check_expr(&field.value);
+                }
+                return Ty::Unknown;
+            }
             let field_defs = info.fields.clone();
             let mut seen = HashSet::new();
             for field in fields {
@@ -1528,14 +1937,59 @@ impl<'a> Checker<'a> {
         }
     }
 
-    fn check_member(&mut self, base: &Expr, name: &crate::ast::Ident, is_optional: bool) -> Ty {
-        let (base_ty, associated_receiver) = match &base.kind {
-            ExprKind::Ident(ident) => {
-                if let Some(var) = self.env.lookup(&ident.name) {
-                    (var.ty.clone(), false)
-                } else if ident.name == "Self" {
-                    if let Some(self_ty) = &self.current_self_type {
-                        (self_ty.clone(), true)
+    fn check_record_update(&mut self, base: &Expr, fields: &[crate::ast::StructField]) -> Ty {
+        let base_ty = self.check_expr(base);
+        let type_name = match &base_ty {
+            Ty::Struct(name) => name.clone(),
+            Ty::Unknown => {
+                for field in fields {
+                    let _ = self.check_expr(&field.value);
+                }
+                return Ty::Unknown;
+            }
+            other => {
+                self.diags.error(
+                    base.span,
+                    format!("record update requires a struct value, found {}", other),
+                );
+                for field in fields {
+                    let _ = self.check_expr(&field.value);
+                }
+                return Ty::Unknown;
+            }
+        };
+        let mut seen = HashSet::new();
+        for field in fields {
+            let value_ty = self.check_expr(&field.value);
+            if !seen.insert(field.name.name.clone()) {
+                self.diags
+                    .error(field.span, "duplicate field in record update");
+                continue;
+            }
+            match self.lookup_field_opt(&type_name, &field.name.name) {
+                Some(field_ty) => {
+                    let field_ty = self.resolve_type_ref(&field_ty);
+                    if !self.is_assignable_with_refined(&value_ty, &field_ty) {
+                        self.type_mismatch(field.span, &field_ty, &value_ty);
+                    }
+                }
+                None => self.diags.error(
+                    field.name.span,
+                    format!("unknown field {} on {}", field.name.name, type_name),
+                ),
+            }
+        }
+        base_ty
+    }
+
+    fn check_member(&mut self, base: &Expr, name: &crate::ast::Ident, is_optional: bool) -> Ty {
+        let (base_ty, associated_receiver) = match &base.kind {
+            ExprKind::Ident(ident) => {
+                if let Some(var) = self.env.lookup(&ident.name) {
+                    (var.ty.clone(), false)
+                } else if ident.name == "Self" {
+                    if let Some(self_ty) = &self.current_self_type {
+                        (self_ty.clone(), true)
                     } else {
                         (self.check_expr(base), false)
                     }
@@ -1543,7 +1997,9 @@ impl<'a> Checker<'a> {
                     (Ty::TypeParam(ident.name.clone()), true)
                 } else if self.modules.contains(&ident.name) {
                     (Ty::Module(ident.name.clone()), false)
-                } else if let Some((ty, _)) = self.lookup_nominal_type_in_scope_silent(self.module_id, &ident.name) {
+                } else if let Some((ty, _)) =
+                    self.lookup_nominal_type_in_scope_silent(self.module_id, &ident.name)
+                {
                     (ty, true)
                 } else {
                     (self.check_expr(base), false)
@@ -1555,7 +2011,9 @@ impl<'a> Checker<'a> {
             } => {
                 if let ExprKind::Ident(module_ident) = &inner_base.kind {
                     let qualified = format!("{}.{}", module_ident.name, inner_name.name);
-                    if let Some((ty, _)) = self.lookup_nominal_type_in_scope_silent(self.module_id, &qualified) {
+                    if let Some((ty, _)) =
+                        self.lookup_nominal_type_in_scope_silent(self.module_id, &qualified)
+                    {
                         (ty, true)
                     } else {
                         (self.check_expr(base), false)
@@ -1601,8 +2059,10 @@ impl<'a> Checker<'a> {
                 {
                     Ty::Fn(resolved.sig)
                 } else {
-                    self.diags
-                        .error(name.span, format!("unknown field {} on {}", name.name, name_ty));
+                    self.diags.error(
+                        name.span,
+                        format!("unknown field {} on {}", name.name, name_ty),
+                    );
                     Ty::Unknown
                 }
             }
@@ -1659,6 +2119,8 @@ impl<'a> Checker<'a> {
             }
             Ty::Module(ref module_name) => self.lookup_module_member(module_name, name),
             Ty::External(ref external) => self.lookup_external_member(external, name),
+            Ty::Channel(ref inner) => self.lookup_channel_member(inner, name),
+            Ty::Range(ref inner) => self.lookup_range_member(inner, name),
             Ty::Unknown => Ty::Unknown,
             other => {
                 self.diags.error(
@@ -1717,9 +2179,7 @@ impl<'a> Checker<'a> {
             let mut missing = Vec::new();
             for param in &sig.type_params {
                 if !bindings.contains_key(&param.name)
-                    || bindings
-                        .get(&param.name)
-                        .is_some_and(|ty| ty.is_unknown())
+                    || bindings.get(&param.name).is_some_and(|ty| ty.is_unknown())
                 {
                     missing.push(param.name.clone());
                 }
@@ -1728,10 +2188,7 @@ impl<'a> Checker<'a> {
                 self.diags.error_with_code(
                     span,
                     FUSE_GENERIC_INFERENCE,
-                    format!(
-                        "could not infer type arguments for {}",
-                        missing.join(", ")
-                    ),
+                    format!("could not infer type arguments for {}", missing.join(", ")),
                 );
                 for name in missing {
                     bindings.insert(name, Ty::Unknown);
@@ -1740,7 +2197,7 @@ impl<'a> Checker<'a> {
         }
 
         for param in &sig.type_params {
-            if let Some(bound) = &param.interface_bound {
+            for bound in &param.interface_bounds {
                 let ty = bindings.get(&param.name).cloned().unwrap_or(Ty::Unknown);
                 if !self.type_satisfies_interface(&ty, bound) {
                     self.diags.error_with_code(
@@ -1812,6 +2269,11 @@ impl<'a> Checker<'a> {
                     self.collect_type_param_inference(inner, arg_inner, bindings);
                 }
             }
+            Ty::Channel(inner) => {
+                if let Ty::Channel(arg_inner) = arg_ty {
+                    self.collect_type_param_inference(inner, arg_inner, bindings);
+                }
+            }
             Ty::Boxed(inner) => {
                 if let Ty::Boxed(arg_inner) = arg_ty {
                     self.collect_type_param_inference(inner, arg_inner, bindings);
@@ -1841,6 +2303,7 @@ impl<'a> Checker<'a> {
                 Box::new(self.substitute_ty(err, bindings)),
             ),
             Ty::Task(inner) => Ty::Task(Box::new(self.substitute_ty(inner, bindings))),
+            Ty::Channel(inner) => Ty::Channel(Box::new(self.substitute_ty(inner, bindings))),
             Ty::Boxed(inner) => Ty::Boxed(Box::new(self.substitute_ty(inner, bindings))),
             Ty::Range(inner) => Ty::Range(Box::new(self.substitute_ty(inner, bindings))),
             Ty::Refined { base, repr } => Ty::Refined {
@@ -1852,17 +2315,23 @@ impl<'a> Checker<'a> {
     }
 
     fn type_satisfies_interface(&self, ty: &Ty, interface_name: &str) -> bool {
+        let wanted_name = split_qualified_type_name(interface_name)
+            .map_or(interface_name, |(_, item_name)| item_name);
+        let wanted = (self.interface_owner_in_scope(interface_name), wanted_name);
         match ty {
-            Ty::Struct(name) | Ty::Enum(name) => self.module_symbols.values().any(|symbols| {
-                symbols
-                    .impls
+            Ty::Struct(name) | Ty::Enum(name) => {
+                self.module_symbols.iter().any(|(module_id, symbols)| {
+                    symbols.impls.iter().any(|impl_info| {
+                        impl_info.target == *name
+                            && self.interface_extends(*module_id, &impl_info.interface, wanted)
+                    })
+                })
+            }
+            Ty::TypeParam(name) => self.lookup_type_param_bound(name).is_some_and(|bounds| {
+                bounds
                     .iter()
-                    .any(|impl_info| impl_info.interface == interface_name && impl_info.target == *name)
+                    .any(|bound| self.interface_extends(self.module_id, bound, wanted))
             }),
-            Ty::TypeParam(name) => self
-                .lookup_type_param_bound(name)
-                .flatten()
-                .is_some_and(|bound| bound == interface_name),
             Ty::Unknown => true,
             _ => false,
         }
@@ -1945,6 +2414,7 @@ impl<'a> Checker<'a> {
             }
         };
         self.check_typed_query_projection(base, &type_args[0], &struct_name, span);
+        self.check_typed_query_column_types(base, &type_args[0], &struct_name);
         if name.name == "one" {
             Ty::Option(Box::new(target_ty))
         } else {
@@ -1952,6 +2422,65 @@ impl<'a> Checker<'a> {
         }
     }
 
+    /// Checks `db.query<T>(sql, params)` / `db.one<T>(sql, params)` and
+    /// returns the row type. When the SQL is a literal and the migration
+    /// schema is known, the result columns are matched against `T`.
+    fn check_typed_raw_query(
+        &mut self,
+        span: Span,
+        method: &str,
+        args: &[CallArg],
+        type_args: &[crate::ast::TypeRef],
+    ) -> Ty {
+        if !matches!(method, "query" | "one") {
+            self.diags.error_with_code(
+                span,
+                TYPED_QUERY_CALL_DIAG_CODE,
+                "type arguments are only supported on db.query<T>() and db.one<T>()",
+            );
+            return Ty::Unknown;
+        }
+        if type_args.len() != 1 {
+            self.diags.error_with_code(
+                span,
+                TYPED_QUERY_TYPE_ARG_DIAG_CODE,
+                format!(
+                    "typed query methods expect exactly one type argument, found {}",
+                    type_args.len()
+                ),
+            );
+            return Ty::Unknown;
+        }
+        let target_ty = self.resolve_type_ref(&type_args[0]);
+        let struct_name = match &target_ty {
+            Ty::Struct(name) => name.clone(),
+            Ty::Unknown => return Ty::Unknown,
+            other => {
+                self.diags.error_with_code(
+                    type_args[0].span,
+                    TYPED_QUERY_TYPE_ARG_DIAG_CODE,
+                    format!(
+                        "typed query result type must be a declared `type`, found {}",
+                        other
+                    ),
+                );
+                return Ty::Unknown;
+            }
+        };
+        let Some(columns) = self
+            .sql_schema
+            .and_then(|schema| schema.raw_call_columns(args))
+        else {
+            return target_ty;
+        };
+        if let Some(expected_fields) = self.typed_query_target_fields(&type_args[0], &struct_name) {
+            let selected: Vec<String> = columns.iter().map(|column| column.name.clone()).collect();
+            self.report_typed_query_field_mismatch(span, &struct_name, &expected_fields, &selected);
+        }
+        self.check_typed_column_types(&columns, &type_args[0], &struct_name);
+        target_ty
+    }
+
     fn check_typed_query_projection(
         &mut self,
         base: &Expr,
@@ -2001,20 +2530,36 @@ impl<'a> Checker<'a> {
                 return;
             }
         };
-        let selected_fields: HashSet<String> = selected
+        let selected_fields: Vec<String> = selected
             .iter()
-            .map(|name| name.rsplit('.').next().unwrap_or(name.as_str()).to_string())
+            .map(|name| typed_query_column_field(name).to_string())
             .collect();
-        let declared_fields: HashSet<String> = expected_fields.iter().cloned().collect();
-        let mut missing: Vec<String> = expected_fields
+        self.report_typed_query_field_mismatch(
+            span,
+            struct_name,
+            &expected_fields,
+            &selected_fields,
+        );
+    }
+
+    fn report_typed_query_field_mismatch(
+        &mut self,
+        span: Span,
+        struct_name: &str,
+        expected_fields: &[String],
+        selected_fields: &[String],
+    ) {
+        let selected: HashSet<&str> = selected_fields.iter().map(String::as_str).collect();
+        let declared: HashSet<&str> = expected_fields.iter().map(String::as_str).collect();
+        let mut missing: Vec<&str> = expected_fields
             .iter()
-            .filter(|field| !selected_fields.contains(field.as_str()))
-            .cloned()
+            .map(String::as_str)
+            .filter(|field| !selected.contains(field))
             .collect();
-        let mut unexpected: Vec<String> = selected_fields
+        let mut unexpected: Vec<&str> = selected
             .iter()
-            .filter(|field| !declared_fields.contains(field.as_str()))
-            .cloned()
+            .copied()
+            .filter(|field| !declared.contains(field))
             .collect();
         if missing.is_empty() && unexpected.is_empty() {
             return;
@@ -2043,40 +2588,60 @@ impl<'a> Checker<'a> {
         &self,
         expr: &Expr,
     ) -> Result<Vec<String>, TypedQuerySelectError> {
-        let ExprKind::Call { callee, args, .. } = &expr.kind else {
-            return Err(TypedQuerySelectError::MissingSelect);
-        };
-        let ExprKind::Member { base, name } = &callee.kind else {
-            return Err(TypedQuerySelectError::MissingSelect);
-        };
-        if !is_query_method_name(&name.name) {
-            return Err(TypedQuerySelectError::MissingSelect);
-        }
-        match name.name.as_str() {
-            "select" => {
-                if args.len() != 1 {
-                    return Err(TypedQuerySelectError::InvalidColumns);
-                }
-                let ExprKind::ListLit(items) = &args[0].value.kind else {
-                    return Err(TypedQuerySelectError::InvalidColumns);
-                };
-                if items.is_empty() {
-                    return Err(TypedQuerySelectError::EmptySelect);
+        // Walk the builder chain outermost-first: the last `select` (or
+        // `returning` on writes) wins, while every `aggregate` alias
+        // contributes an extra projected column.
+        let mut current = expr;
+        let mut selected: Option<Vec<String>> = None;
+        let mut aggregates = Vec::new();
+        while let ExprKind::Call { callee, args, .. } = &current.kind {
+            let ExprKind::Member { base, name } = &callee.kind else {
+                break;
+            };
+            if !is_query_method_name(&name.name) {
+                break;
+            }
+            match name.name.as_str() {
+                "select" | "returning" if selected.is_none() => {
+                    if args.len() != 1 {
+                        return Err(TypedQuerySelectError::InvalidColumns);
+                    }
+                    let ExprKind::ListLit(items) = &args[0].value.kind else {
+                        return Err(TypedQuerySelectError::InvalidColumns);
+                    };
+                    if items.is_empty() {
+                        return Err(TypedQuerySelectError::EmptySelect);
+                    }
+                    let mut out = Vec::with_capacity(items.len());
+                    for item in items {
+                        let ExprKind::Literal(Literal::String(text)) = &item.kind else {
+                            return Err(TypedQuerySelectError::InvalidColumns);
+                        };
+                        out.push(text.clone());
+                    }
+                    selected = Some(out);
                 }
-                let mut out = Vec::with_capacity(items.len());
-                for item in items {
-                    let ExprKind::Literal(Literal::String(text)) = &item.kind else {
+                "aggregate" => {
+                    let Some(ExprKind::Literal(Literal::String(alias))) =
+                        args.get(2).map(|arg| &arg.value.kind)
+                    else {
                         return Err(TypedQuerySelectError::InvalidColumns);
                     };
-                    out.push(text.clone());
+                    aggregates.push(alias.clone());
                 }
-                Ok(out)
+                "select" | "returning" | "where" | "or_where" | "join" | "left_join"
+                | "order_by" | "limit" | "offset" | "group_by" | "having" | "one" | "all" => {}
+                _ => break,
             }
-            "where" | "order_by" | "limit" | "one" | "all" => {
-                self.extract_query_select_columns(base)
+            current = base;
+        }
+        match selected {
+            Some(mut columns) => {
+                columns.extend(aggregates);
+                Ok(columns)
             }
-            "from" => Err(TypedQuerySelectError::MissingSelect),
-            _ => Err(TypedQuerySelectError::MissingSelect),
+            None if !aggregates.is_empty() => Ok(aggregates),
+            None => Err(TypedQuerySelectError::MissingSelect),
         }
     }
 
@@ -2085,8 +2650,17 @@ impl<'a> Checker<'a> {
         target_type: &crate::ast::TypeRef,
         struct_name: &str,
     ) -> Option<Vec<String>> {
+        let fields = self.typed_query_target_field_infos(target_type, struct_name)?;
+        Some(fields.into_iter().map(|field| field.name).collect())
+    }
+
+    fn typed_query_target_field_infos(
+        &self,
+        target_type: &crate::ast::TypeRef,
+        struct_name: &str,
+    ) -> Option<Vec<FieldInfo>> {
         if let Some(info) = self.type_info(struct_name) {
-            return Some(info.fields.iter().map(|field| field.name.clone()).collect());
+            return Some(info.fields.clone());
         }
         let crate::ast::TypeRefKind::Simple(ident) = &target_type.kind else {
             return None;
@@ -2095,7 +2669,60 @@ impl<'a> Checker<'a> {
         let link = self.modules.get(module_name)?;
         let symbols = self.module_symbols.get(&link.id)?;
         let info = symbols.types.get(item_name)?;
-        Some(info.fields.iter().map(|field| field.name.clone()).collect())
+        Some(info.fields.clone())
+    }
+
+    /// Prepares the SQL of a `db.from(...)` builder chain against the
+    /// migration schema, reporting unknown tables and columns.
+    fn check_query_chain_sql(&mut self, expr: &Expr) -> Option<Vec<SqlColumn>> {
+        let schema = self.sql_schema?;
+        match schema.check_query_chain(expr) {
+            Ok(columns) => columns,
+            Err(problem) => {
+                self.diags
+                    .error_with_code(problem.span, problem.code, problem.message);
+                None
+            }
+        }
+    }
+
+    fn check_typed_query_column_types(
+        &mut self,
+        base: &Expr,
+        target_type: &crate::ast::TypeRef,
+        struct_name: &str,
+    ) {
+        let Some(columns) = self.check_query_chain_sql(base) else {
+            return;
+        };
+        self.check_typed_column_types(&columns, target_type, struct_name);
+    }
+
+    fn check_typed_column_types(
+        &mut self,
+        columns: &[SqlColumn],
+        target_type: &crate::ast::TypeRef,
+        struct_name: &str,
+    ) {
+        let Some(fields) = self.typed_query_target_field_infos(target_type, struct_name) else {
+            return;
+        };
+        for column in columns {
+            let Some(field) = fields.iter().find(|field| field.name == column.name) else {
+                continue;
+            };
+            let field_ty = self.resolve_type_ref(&field.ty);
+            if let Some(decl_type) = sql::column_type_mismatch(column, &field_ty) {
+                self.diags.error_with_code(
+                    target_type.span,
+                    sql::FUSE_SQL_TYPE_MISMATCH,
+                    format!(
+                        "column {} has sql type {} but {}.{} is {}",
+                        column.name, decl_type, struct_name, field.name, field_ty
+                    ),
+                );
+            }
+        }
     }
 
     fn check_index(&mut self, base: &Expr, index: &Expr, is_optional: bool) -> Ty {
@@ -2116,7 +2743,7 @@ impl<'a> Checker<'a> {
         }
         let index_ty = self.check_expr(index);
         let value_ty = match inner {
-            Ty::List(elem_ty) => {
+            Ty::List(elem_ty) | Ty::Range(elem_ty) => {
                 if !matches!(index_ty, Ty::Unknown) && !self.is_int_like(&index_ty) {
                     self.diags.error(index.span, "list index must be Int");
                 }
@@ -2160,6 +2787,7 @@ impl<'a> Checker<'a> {
     fn lookup_external_member(&mut self, external: &str, name: &crate::ast::Ident) -> Ty {
         match external {
             "db" => self.lookup_db_member(name),
+            "db.result" => self.lookup_db_result_member(name),
             "query" => self.lookup_query_member(name),
             "task" => self.lookup_task_member(name),
             "html" => self.lookup_html_member(name),
@@ -2171,6 +2799,14 @@ impl<'a> Checker<'a> {
             "http.error" => self.lookup_http_error_member(name),
             "time" => self.lookup_time_member(name),
             "crypto" => self.lookup_crypto_member(name),
+            "decimal" => match decimal_member_sig(&name.name) {
+                Some(sig) => Ty::Fn(sig),
+                None => {
+                    self.diags
+                        .error(name.span, format!("unknown decimal method {}", name.name));
+                    Ty::Unknown
+                }
+            },
             _ => {
                 self.diags.error(
                     name.span,
@@ -2181,6 +2817,55 @@ impl<'a> Checker<'a> {
         }
     }
 
+    fn lookup_channel_member(&mut self, item: &Ty, name: &crate::ast::Ident) -> Ty {
+        let (params, ret) = match name.name.as_str() {
+            "send" => (
+                vec![ParamSig {
+                    name: "value".to_string(),
+                    ty: item.clone(),
+                    has_default: false,
+                }],
+                Ty::Unit,
+            ),
+            "recv" => (Vec::new(), Ty::Option(Box::new(item.clone()))),
+            "close" => (Vec::new(), Ty::Unit),
+            _ => {
+                self.diags
+                    .error(name.span, format!("unknown channel method {}", name.name));
+                return Ty::Unknown;
+            }
+        };
+        Ty::Fn(FnSig {
+            type_params: Vec::new(),
+            params,
+            ret: Box::new(ret),
+        })
+    }
+
+    fn lookup_range_member(&mut self, item: &Ty, name: &crate::ast::Ident) -> Ty {
+        let (params, ret) = match name.name.as_str() {
+            "step" => (
+                vec![ParamSig {
+                    name: "step".to_string(),
+                    ty: item.clone(),
+                    has_default: false,
+                }],
+                Ty::Range(Box::new(item.clone())),
+            ),
+            "to_list" => (Vec::new(), Ty::List(Box::new(item.clone()))),
+            _ => {
+                self.diags
+                    .error(name.span, format!("unknown range method {}", name.name));
+                return Ty::Unknown;
+            }
+        };
+        Ty::Fn(FnSig {
+            type_params: Vec::new(),
+            params,
+            ret: Box::new(ret),
+        })
+    }
+
     fn lookup_html_member(&mut self, name: &crate::ast::Ident) -> Ty {
         match name.name.as_str() {
             "text" | "raw" => Ty::Fn(FnSig {
@@ -2351,11 +3036,14 @@ impl<'a> Checker<'a> {
                 ],
                 ret: Box::new(Ty::Result(Box::new(Ty::Int), Box::new(Ty::Error))),
             }),
-            _ => {
-                self.diags
-                    .error(name.span, format!("unknown time method {}", name.name));
-                Ty::Unknown
-            }
+            other => match time_value_member_sig(other) {
+                Some(sig) => Ty::Fn(sig),
+                None => {
+                    self.diags
+                        .error(name.span, format!("unknown time method {}", name.name));
+                    Ty::Unknown
+                }
+            },
         }
     }
 
@@ -2456,14 +3144,28 @@ impl<'a> Checker<'a> {
         }
     }
 
+    fn lookup_db_result_member(&mut self, name: &crate::ast::Ident) -> Ty {
+        match name.name.as_str() {
+            "rows_affected" => Ty::Int,
+            "last_insert_id" => Ty::Option(Box::new(Ty::Int)),
+            _ => {
+                self.diags
+                    .error(name.span, format!("unknown db result field {}", name.name));
+                Ty::Unknown
+            }
+        }
+    }
+
     fn lookup_http_response_member(&mut self, name: &crate::ast::Ident) -> Ty {
         match name.name.as_str() {
             "method" | "url" | "body" => Ty::String,
             "status" => Ty::Int,
             "headers" => Ty::Map(Box::new(Ty::String), Box::new(Ty::String)),
             _ => {
-                self.diags
-                    .error(name.span, format!("unknown http response field {}", name.name));
+                self.diags.error(
+                    name.span,
+                    format!("unknown http response field {}", name.name),
+                );
                 Ty::Unknown
             }
         }
@@ -2568,6 +3270,16 @@ impl<'a> Checker<'a> {
                 params: vec![sql_arg.clone()],
                 ret: Box::new(Ty::Unit),
             }),
+            "execute" => Ty::Fn(FnSig {
+                type_params: Vec::new(),
+                params: vec![sql_arg.clone()],
+                ret: Box::new(Ty::External("db.result".to_string())),
+            }),
+            "rollback" => Ty::Fn(FnSig {
+                type_params: Vec::new(),
+                params: Vec::new(),
+                ret: Box::new(Ty::Unit),
+            }),
             "query" => Ty::Fn(FnSig {
                 type_params: Vec::new(),
                 params: vec![sql_arg.clone()],
@@ -2628,6 +3340,48 @@ impl<'a> Checker<'a> {
                 ],
                 ret: Box::new(Ty::External("query".to_string())),
             }),
+            "or_where" => Ty::Fn(FnSig {
+                type_params: Vec::new(),
+                params: vec![
+                    ParamSig {
+                        name: "column".to_string(),
+                        ty: Ty::String,
+                        has_default: false,
+                    },
+                    ParamSig {
+                        name: "op".to_string(),
+                        ty: Ty::String,
+                        has_default: false,
+                    },
+                    ParamSig {
+                        name: "value".to_string(),
+                        ty: Ty::Unknown,
+                        has_default: false,
+                    },
+                ],
+                ret: Box::new(Ty::External("query".to_string())),
+            }),
+            "join" | "left_join" => Ty::Fn(FnSig {
+                type_params: Vec::new(),
+                params: vec![
+                    ParamSig {
+                        name: "table".to_string(),
+                        ty: Ty::String,
+                        has_default: false,
+                    },
+                    ParamSig {
+                        name: "left".to_string(),
+                        ty: Ty::String,
+                        has_default: false,
+                    },
+                    ParamSig {
+                        name: "right".to_string(),
+                        ty: Ty::String,
+                        has_default: false,
+                    },
+                ],
+                ret: Box::new(Ty::External("query".to_string())),
+            }),
             "order_by" => Ty::Fn(FnSig {
                 type_params: Vec::new(),
                 params: vec![
@@ -2653,6 +3407,66 @@ impl<'a> Checker<'a> {
                 }],
                 ret: Box::new(Ty::External("query".to_string())),
             }),
+            "offset" => Ty::Fn(FnSig {
+                type_params: Vec::new(),
+                params: vec![ParamSig {
+                    name: "n".to_string(),
+                    ty: Ty::Int,
+                    has_default: false,
+                }],
+                ret: Box::new(Ty::External("query".to_string())),
+            }),
+            "group_by" => Ty::Fn(FnSig {
+                type_params: Vec::new(),
+                params: vec![ParamSig {
+                    name: "columns".to_string(),
+                    ty: Ty::List(Box::new(Ty::String)),
+                    has_default: false,
+                }],
+                ret: Box::new(Ty::External("query".to_string())),
+            }),
+            "having" => Ty::Fn(FnSig {
+                type_params: Vec::new(),
+                params: vec![
+                    ParamSig {
+                        name: "column".to_string(),
+                        ty: Ty::String,
+                        has_default: false,
+                    },
+                    ParamSig {
+                        name: "op".to_string(),
+                        ty: Ty::String,
+                        has_default: false,
+                    },
+                    ParamSig {
+                        name: "value".to_string(),
+                        ty: Ty::Unknown,
+                        has_default: false,
+                    },
+                ],
+                ret: Box::new(Ty::External("query".to_string())),
+            }),
+            "aggregate" => Ty::Fn(FnSig {
+                type_params: Vec::new(),
+                params: vec![
+                    ParamSig {
+                        name: "func".to_string(),
+                        ty: Ty::String,
+                        has_default: false,
+                    },
+                    ParamSig {
+                        name: "column".to_string(),
+                        ty: Ty::String,
+                        has_default: false,
+                    },
+                    ParamSig {
+                        name: "alias".to_string(),
+                        ty: Ty::String,
+                        has_default: false,
+                    },
+                ],
+                ret: Box::new(Ty::External("query".to_string())),
+            }),
             "insert" => Ty::Fn(FnSig {
                 type_params: Vec::new(),
                 params: vec![ParamSig {
@@ -2662,6 +3476,38 @@ impl<'a> Checker<'a> {
                 }],
                 ret: Box::new(Ty::External("query".to_string())),
             }),
+            "insert_many" => Ty::Fn(FnSig {
+                type_params: Vec::new(),
+                params: vec![ParamSig {
+                    name: "rows".to_string(),
+                    ty: Ty::List(Box::new(Ty::Unknown)),
+                    has_default: false,
+                }],
+                ret: Box::new(Ty::External("query".to_string())),
+            }),
+            "on_conflict" => Ty::Fn(FnSig {
+                type_params: Vec::new(),
+                params: vec![ParamSig {
+                    name: "columns".to_string(),
+                    ty: Ty::List(Box::new(Ty::String)),
+                    has_default: false,
+                }],
+                ret: Box::new(Ty::External("query".to_string())),
+            }),
+            "do_update" => Ty::Fn(FnSig {
+                type_params: Vec::new(),
+                params: vec![ParamSig {
+                    name: "columns".to_string(),
+                    ty: Ty::List(Box::new(Ty::String)),
+                    has_default: true,
+                }],
+                ret: Box::new(Ty::External("query".to_string())),
+            }),
+            "do_nothing" => Ty::Fn(FnSig {
+                type_params: Vec::new(),
+                params: vec![],
+                ret: Box::new(Ty::External("query".to_string())),
+            }),
             "upsert" => Ty::Fn(FnSig {
                 type_params: Vec::new(),
                 params: vec![ParamSig {
@@ -2692,6 +3538,15 @@ impl<'a> Checker<'a> {
                 params: vec![],
                 ret: Box::new(Ty::External("query".to_string())),
             }),
+            "returning" => Ty::Fn(FnSig {
+                type_params: Vec::new(),
+                params: vec![ParamSig {
+                    name: "columns".to_string(),
+                    ty: Ty::List(Box::new(Ty::String)),
+                    has_default: false,
+                }],
+                ret: Box::new(Ty::External("query".to_string())),
+            }),
             "count" => Ty::Fn(FnSig {
                 type_params: Vec::new(),
                 params: vec![],
@@ -2712,6 +3567,11 @@ impl<'a> Checker<'a> {
                 params: vec![],
                 ret: Box::new(Ty::Unit),
             }),
+            "execute" => Ty::Fn(FnSig {
+                type_params: Vec::new(),
+                params: vec![],
+                ret: Box::new(Ty::External("db.result".to_string())),
+            }),
             "sql" => Ty::Fn(FnSig {
                 type_params: Vec::new(),
                 params: vec![],
@@ -2774,8 +3634,12 @@ impl<'a> Checker<'a> {
         enum_name: &str,
         variant: &str,
     ) -> Option<super::symbols::EnumVariantInfo> {
-        self.enum_info(enum_name)
-            .and_then(|info| info.variants.iter().find(|item| item.name == variant).cloned())
+        self.enum_info(enum_name).and_then(|info| {
+            info.variants
+                .iter()
+                .find(|item| item.name == variant)
+                .cloned()
+        })
     }
 
     fn interface_info_in(
@@ -2811,7 +3675,10 @@ impl<'a> Checker<'a> {
             let module_map = self.module_maps.get(&module_id).unwrap_or(self.modules);
             let link = module_map.get(module_name)?;
             let symbols = self.module_symbols.get(&link.id)?;
-            return symbols.interfaces.contains_key(item_name).then_some(link.id);
+            return symbols
+                .interfaces
+                .contains_key(item_name)
+                .then_some(link.id);
         }
         let symbols = self.module_symbols.get(&module_id).unwrap_or(self.symbols);
         if symbols.interfaces.contains_key(name) {
@@ -2830,6 +3697,72 @@ impl<'a> Checker<'a> {
         self.interface_owner_in(self.module_id, name)
     }
 
+    /// `name` followed by every interface it extends, directly or not, each
+    /// paired with its declaring module. Cycles are cut at the first repeat.
+    fn interface_ancestry(
+        &self,
+        module_id: ModuleId,
+        name: &str,
+    ) -> Vec<(ModuleId, super::symbols::InterfaceInfo)> {
+        let mut out: Vec<(ModuleId, super::symbols::InterfaceInfo)> = Vec::new();
+        let mut pending = vec![(module_id, name.to_string())];
+        while let Some((scope, name)) = pending.pop() {
+            let (Some(owner), Some(info)) = (
+                self.interface_owner_in(scope, &name),
+                self.interface_info_in(scope, &name),
+            ) else {
+                continue;
+            };
+            if out
+                .iter()
+                .any(|(seen_owner, seen)| *seen_owner == owner && seen.name == info.name)
+            {
+                continue;
+            }
+            pending.extend(
+                info.extends
+                    .iter()
+                    .rev()
+                    .map(|parent| (owner, parent.clone())),
+            );
+            out.push((owner, info.clone()));
+        }
+        out
+    }
+
+    /// Members of `name` including inherited ones; a member redeclared by a
+    /// child interface hides the parent's.
+    fn interface_members_with_parents(
+        &self,
+        module_id: ModuleId,
+        name: &str,
+    ) -> Vec<(ModuleId, super::symbols::InterfaceMemberInfo)> {
+        let mut out: Vec<(ModuleId, super::symbols::InterfaceMemberInfo)> = Vec::new();
+        for (owner, info) in self.interface_ancestry(module_id, name) {
+            for member in info.members {
+                if out.iter().all(|(_, seen)| seen.name != member.name) {
+                    out.push((owner, member));
+                }
+            }
+        }
+        out
+    }
+
+    /// Whether interface `name`, resolved from `module_id`, is `wanted` or
+    /// extends it. A `wanted` interface whose module is unknown matches by name.
+    fn interface_extends(
+        &self,
+        module_id: ModuleId,
+        name: &str,
+        wanted: (Option<ModuleId>, &str),
+    ) -> bool {
+        self.interface_ancestry(module_id, name)
+            .iter()
+            .any(|(owner, info)| {
+                info.name == wanted.1 && wanted.0.is_none_or(|wanted_owner| wanted_owner == *owner)
+            })
+    }
+
     fn resolve_impl_target(&mut self, ident: &crate::ast::Ident) -> Option<(Ty, Option<ModuleId>)> {
         self.resolve_nominal_type_in_scope(&ident.name, ident.span)
     }
@@ -2840,15 +3773,24 @@ impl<'a> Checker<'a> {
         span: Span,
     ) -> Option<(Ty, Option<ModuleId>)> {
         if let Some((module_name, item_name)) = split_qualified_type_name(name) {
-            let module_map = self.module_maps.get(&self.module_id).unwrap_or(self.modules);
+            let module_map = self
+                .module_maps
+                .get(&self.module_id)
+                .unwrap_or(self.modules);
             let Some(link) = module_map.get(module_name) else {
-                self.diags.error(span, format!("unknown module {}", module_name));
+                self.diags
+                    .error(span, format!("unknown module {}", module_name));
                 return None;
             };
             let Some(symbols) = self.module_symbols.get(&link.id) else {
-                self.diags.error(span, format!("unknown module {}", module_name));
+                self.diags
+                    .error(span, format!("unknown module {}", module_name));
                 return None;
             };
+            if link.id != self.module_id && link.exports.is_private(item_name) {
+                self.report_private_member(span, module_name, item_name);
+                return None;
+            }
             if symbols.types.contains_key(item_name) {
                 return Some((Ty::Struct(item_name.to_string()), Some(link.id)));
             }
@@ -2900,9 +3842,10 @@ impl<'a> Checker<'a> {
             }
         }
         match name {
-            "Unit" | "Int" | "Float" | "Bool" | "String" | "Bytes" | "Html" | "Id"
-            | "Email" | "Error" => {
-                self.diags.error(span, format!("{} is not a nominal impl target", name));
+            "Unit" | "Int" | "Float" | "Bool" | "String" | "Bytes" | "Html" | "Id" | "Email"
+            | "Instant" | "Date" | "Duration" | "DateTime" | "Decimal" | "Error" => {
+                self.diags
+                    .error(span, format!("{} is not a nominal impl target", name));
             }
             _ => self.diags.error(span, format!("unknown type {}", name)),
         }
@@ -2931,7 +3874,9 @@ impl<'a> Checker<'a> {
             count += symbols
                 .impls
                 .iter()
-                .filter(|item| item.interface == decl.interface.name && item.target == decl.target.name)
+                .filter(|item| {
+                    item.interface == decl.interface.name && item.target == decl.target.name
+                })
                 .count();
         }
         if count > 1 {
@@ -2953,7 +3898,9 @@ impl<'a> Checker<'a> {
         let interface_owner = self.interface_owner_in_scope(&decl.interface.name);
         let interface_pkg = interface_owner.and_then(|id| self.module_package_root(id));
         let target_pkg = target_owner.and_then(|id| self.module_package_root(id));
-        let external_interface = interface_pkg.as_ref().is_some_and(|pkg| *pkg != current_pkg);
+        let external_interface = interface_pkg
+            .as_ref()
+            .is_some_and(|pkg| *pkg != current_pkg);
         let external_target = target_pkg.as_ref().is_some_and(|pkg| *pkg != current_pkg);
         if external_interface && external_target {
             self.diags.error_with_code(
@@ -2979,7 +3926,11 @@ impl<'a> Checker<'a> {
             .impls
             .iter()
             .find(|item| item.interface == interface && item.target == target)
-            .and_then(|item| item.methods.iter().find(|candidate| candidate.name == method))
+            .and_then(|item| {
+                item.methods
+                    .iter()
+                    .find(|candidate| candidate.name == method)
+            })
             .map(|method| method.uses_self)
     }
 
@@ -3028,7 +3979,8 @@ impl<'a> Checker<'a> {
         name: &crate::ast::Ident,
         kind: InterfaceMethodKind,
     ) -> Option<ResolvedImplMethod> {
-        let mut candidates: Vec<(ModuleId, super::symbols::ImplMethodInfo, String)> = Vec::new();
+        // (module declaring the signature, module of the impl, method)
+        let mut candidates: Vec<(ModuleId, ModuleId, super::symbols::ImplMethodInfo)> = Vec::new();
         for (module_id, symbols) in self.module_symbols {
             for impl_info in &symbols.impls {
                 if impl_info.target != target_name {
@@ -3041,7 +3993,38 @@ impl<'a> Checker<'a> {
                         InterfaceMethodKind::Associated
                     };
                     if method.name == name.name && method_kind == kind {
-                        candidates.push((*module_id, method.clone(), impl_info.interface.clone()));
+                        candidates.push((*module_id, *module_id, method.clone()));
+                    }
+                }
+                if impl_info
+                    .methods
+                    .iter()
+                    .any(|method| method.name == name.name)
+                {
+                    continue;
+                }
+                for (owner, member) in
+                    self.interface_members_with_parents(*module_id, &impl_info.interface)
+                {
+                    let member_kind = if member.uses_self {
+                        InterfaceMethodKind::Instance
+                    } else {
+                        InterfaceMethodKind::Associated
+                    };
+                    if member.has_default && member.name == name.name && member_kind == kind {
+                        candidates.push((
+                            owner,
+                            *module_id,
+                            super::symbols::ImplMethodInfo {
+                                name: member.name,
+                                type_params: member.type_params,
+                                params: member.params,
+                                ret: member.ret,
+                                where_clause: member.where_clause,
+                                span: member.span,
+                                uses_self: member.uses_self,
+                            },
+                        ));
                     }
                 }
             }
@@ -3058,31 +4041,36 @@ impl<'a> Checker<'a> {
                 ),
             );
         }
-        let (module_id, method, _interface) = candidates.remove(0);
-        let target_ty = self.resolve_simple_type_name_in(module_id, target_name, method.span);
+        let (module_id, impl_module_id, method) = candidates.remove(0);
+        let target_ty = self.resolve_simple_type_name_in(impl_module_id, target_name, method.span);
         let prev_self = self.current_self_type.clone();
         self.current_self_type = Some(target_ty.clone());
-        let sig = self.with_ref_type_params(module_id, &method.type_params, &method.where_clause, |this, type_params| {
-            let params = method
-                .params
-                .iter()
-                .map(|param| ParamSig {
-                    name: param.name.clone(),
-                    ty: this.resolve_type_ref_in(module_id, &param.ty),
-                    has_default: param.has_default,
-                })
-                .collect();
-            let ret = method
-                .ret
-                .as_ref()
-                .map(|ty| this.resolve_type_ref_in(module_id, ty))
-                .unwrap_or(Ty::Unit);
-            FnSig {
-                type_params,
-                params,
-                ret: Box::new(ret),
-            }
-        });
+        let sig = self.with_ref_type_params(
+            module_id,
+            &method.type_params,
+            &method.where_clause,
+            |this, type_params| {
+                let params = method
+                    .params
+                    .iter()
+                    .map(|param| ParamSig {
+                        name: param.name.clone(),
+                        ty: this.resolve_type_ref_in(module_id, &param.ty),
+                        has_default: param.has_default,
+                    })
+                    .collect();
+                let ret = method
+                    .ret
+                    .as_ref()
+                    .map(|ty| this.resolve_type_ref_in(module_id, ty))
+                    .unwrap_or(Ty::Unit);
+                FnSig {
+                    type_params,
+                    params,
+                    ret: Box::new(ret),
+                }
+            },
+        );
         self.current_self_type = prev_self;
         Some(ResolvedImplMethod { sig })
     }
@@ -3093,16 +4081,12 @@ impl<'a> Checker<'a> {
         name: &crate::ast::Ident,
         _associated_receiver: bool,
     ) -> Option<FnSig> {
-        let interface_name = self.lookup_type_param_bound(type_param)??;
-        let interface_owner = self
-            .interface_owner_in_scope(&interface_name)
-            .unwrap_or(self.module_id);
-        let member = self
-            .interface_info_in_scope(&interface_name)?
-            .members
-            .iter()
-            .find(|member| member.name == name.name)
-            .cloned()?;
+        let bounds = self.lookup_type_param_bound(type_param)?;
+        let (interface_owner, member) = bounds.iter().find_map(|bound| {
+            self.interface_members_with_parents(self.module_id, bound)
+                .into_iter()
+                .find(|(_, member)| member.name == name.name)
+        })?;
         let prev_self = self.current_self_type.clone();
         self.current_self_type = Some(Ty::TypeParam(type_param.to_string()));
         let sig = self.with_ref_type_params(
@@ -3177,6 +4161,10 @@ impl<'a> Checker<'a> {
         if symbols.configs.contains_key(&ident.name) {
             return Ty::Config(ident.name.clone());
         }
+        if let Some(info) = symbols.consts.get(&ident.name) {
+            let ty = info.ty.clone();
+            return self.resolve_type_ref_in(link.id, &ty);
+        }
         if symbols.interfaces.contains_key(&ident.name) {
             self.diags.error_with_code(
                 ident.span,
@@ -3216,6 +4204,17 @@ impl<'a> Checker<'a> {
                 return Ty::Unknown;
             }
         };
+        if !info.type_params.is_empty() {
+            self.diags.error_with_code(
+                name.span,
+                FUSE_GENERIC_TYPE_ARG,
+                format!(
+                    "generic enum {0} needs explicit type arguments: {0}.{1}<...>(...)",
+                    enum_name, name.name
+                ),
+            );
+            return Ty::Unknown;
+        }
         let payload = match info.variants.iter().find(|v| v.name == name.name) {
             Some(variant) => variant.payload.clone(),
             None => {
@@ -3245,6 +4244,14 @@ impl<'a> Checker<'a> {
         })
     }
 
+    fn report_private_member(&mut self, span: Span, module_name: &str, item_name: &str) {
+        self.diags.error_with_code(
+            span,
+            FUSE_VISIBILITY_PRIVATE,
+            format!("{module_name}.{item_name} is private to its module"),
+        );
+    }
+
     fn lookup_module_member(&mut self, module_name: &str, name: &crate::ast::Ident) -> Ty {
         let Some(link) = self.modules.get(module_name) else {
             self.diags
@@ -3258,6 +4265,10 @@ impl<'a> Checker<'a> {
             );
             return Ty::Unknown;
         }
+        if link.id != self.module_id && link.exports.is_private(&name.name) {
+            self.report_private_member(name.span, module_name, &name.name);
+            return Ty::Unknown;
+        }
         let Some(symbols) = self.module_symbols.get(&link.id) else {
             self.diags
                 .error(name.span, format!("unknown module {}", module_name));
@@ -3276,6 +4287,10 @@ impl<'a> Checker<'a> {
         if symbols.configs.contains_key(&name.name) {
             return Ty::Config(name.name.clone());
         }
+        if let Some(info) = symbols.consts.get(&name.name) {
+            let ty = info.ty.clone();
+            return self.resolve_type_ref_in(link.id, &ty);
+        }
         if symbols.enums.contains_key(&name.name) {
             return Ty::Enum(name.name.clone());
         }
@@ -3457,6 +4472,10 @@ impl<'a> Checker<'a> {
         if self.symbols.configs.contains_key(&ident.name) {
             return Ty::Config(ident.name.clone());
         }
+        if let Some(info) = self.symbols.consts.get(&ident.name) {
+            let ty = info.ty.clone();
+            return self.resolve_type_ref(&ty);
+        }
         if self.symbols.interfaces.contains_key(&ident.name) {
             self.diags.error_with_code(
                 ident.span,
@@ -3492,6 +4511,14 @@ impl<'a> Checker<'a> {
         use crate::ast::TypeRefKind;
         match &ty.kind {
             TypeRefKind::Simple(ident) => {
+                if let Some(arity) = self.generic_type_arity_in(module_id, &ident.name) {
+                    self.diags.error_with_code(
+                        ident.span,
+                        FUSE_GENERIC_TYPE_ARG,
+                        type_arg_count_message(&ident.name, arity),
+                    );
+                    return Ty::Unknown;
+                }
                 self.resolve_simple_type_name_in(module_id, &ident.name, ident.span)
             }
             TypeRefKind::Generic { base, args } => {
@@ -3531,9 +4558,51 @@ impl<'a> Checker<'a> {
                         let err = self.resolve_type_ref_in(module_id, &args[1]);
                         Ty::Result(Box::new(ok), Box::new(err))
                     }
+                    "Channel" => {
+                        if args.len() != 1 {
+                            self.diags.error(ty.span, "Channel expects 1 type argument");
+                            return Ty::Unknown;
+                        }
+                        let inner = self.resolve_type_ref_in(module_id, &args[0]);
+                        Ty::Channel(Box::new(inner))
+                    }
+                    "Range" => {
+                        if args.len() != 1 {
+                            self.diags.error(ty.span, "Range expects 1 type argument");
+                            return Ty::Unknown;
+                        }
+                        let inner = self.resolve_type_ref_in(module_id, &args[0]);
+                        Ty::Range(Box::new(inner))
+                    }
                     _ => {
-                        self.diags
-                            .error(base.span, format!("unknown generic type {}", base.name));
+                        // Concrete uses of generic `type`/`enum` declarations
+                        // were instantiated by the frontend; whatever is left
+                        // here could not be.
+                        let Some(arity) = self.generic_type_arity_in(module_id, base_name) else {
+                            self.diags
+                                .error(base.span, format!("unknown generic type {}", base.name));
+                            return Ty::Unknown;
+                        };
+                        if args.len() != arity {
+                            self.diags.error_with_code(
+                                ty.span,
+                                FUSE_GENERIC_TYPE_ARG,
+                                type_arg_count_message(base_name, arity),
+                            );
+                        } else if self.in_generic_type_decl {
+                            for arg in args {
+                                let _ = self.resolve_type_ref_in(module_id, arg);
+                            }
+                        } else {
+                            self.diags.error_with_code(
+                                ty.span,
+                                FUSE_GENERIC_TYPE_ARG,
+                                format!(
+                                    "cannot instantiate {}: type arguments must be concrete types",
+                                    base.name
+                                ),
+                            );
+                        }
                         Ty::Unknown
                     }
                 }
@@ -3565,7 +4634,46 @@ impl<'a> Checker<'a> {
                     repr,
                 }
             }
+            TypeRefKind::Tuple(items) => Ty::Tuple(
+                items
+                    .iter()
+                    .map(|item| self.resolve_type_ref_in(module_id, item))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Number of type parameters when `name` is a generic `type`/`enum`
+    /// declaration visible from `module_id`.
+    fn generic_type_arity_in(&self, module_id: ModuleId, name: &str) -> Option<usize> {
+        if self.lookup_type_param_bound(name).is_some() {
+            return None;
         }
+        let (symbols, item_name) = match split_qualified_type_name(name) {
+            Some((module_name, item_name)) => {
+                let module_map = self.module_maps.get(&module_id).unwrap_or(self.modules);
+                let link = module_map.get(module_name)?;
+                (self.module_symbols.get(&link.id)?, item_name)
+            }
+            None => {
+                let local = self.module_symbols.get(&module_id).unwrap_or(self.symbols);
+                if local.types.contains_key(name) || local.enums.contains_key(name) {
+                    (local, name)
+                } else {
+                    let import_items = self
+                        .module_import_items
+                        .get(&module_id)
+                        .unwrap_or(self.import_items);
+                    let link = import_items.get(name)?;
+                    (self.module_symbols.get(&link.id)?, name)
+                }
+            }
+        };
+        let type_params = match symbols.types.get(item_name) {
+            Some(info) => &info.type_params,
+            None => &symbols.enums.get(item_name)?.type_params,
+        };
+        (!type_params.is_empty()).then_some(type_params.len())
     }
 
     fn resolve_simple_type_name(&mut self, name: &str, span: Span) -> Ty {
@@ -3596,6 +4704,11 @@ impl<'a> Checker<'a> {
                 RefinementConstraint::Range { min, max, span } => {
                     self.validate_refined_range_constraint(&base.name, min, max, span);
                 }
+                RefinementConstraint::TextRange { min, max, span } => {
+                    if let Err(message) = time_range_bounds(&base.name, &min, &max) {
+                        self.diags.error(span, message);
+                    }
+                }
                 RefinementConstraint::Regex { span, .. } => {
                     if !base_is_string_like(&base.name) {
                         self.diags.error(
@@ -3634,7 +4747,7 @@ impl<'a> Checker<'a> {
                         .error(span, "range bounds for Int refinements must be integers");
                 }
             }
-            "Float" => {}
+            "Float" | "Decimal" => {}
             _ => self.diags.error(
                 span,
                 format!(
@@ -3738,6 +4851,10 @@ impl<'a> Checker<'a> {
                         .error(span, format!("unknown module {}", module_name));
                     return Ty::Unknown;
                 };
+                if link.id != module_id && link.exports.is_private(item_name) {
+                    self.report_private_member(span, module_name, item_name);
+                    return Ty::Unknown;
+                }
                 if symbols.types.contains_key(item_name) {
                     return Ty::Struct(item_name.to_string());
                 }
@@ -3783,6 +4900,11 @@ impl<'a> Checker<'a> {
             "Html" => Ty::Html,
             "Id" => Ty::Id,
             "Email" => Ty::Email,
+            "Instant" => Ty::Instant,
+            "Date" => Ty::Date,
+            "Duration" => Ty::Duration,
+            "DateTime" => Ty::DateTime,
+            "Decimal" => Ty::Decimal,
             "Error" => Ty::Error,
             _ => {
                 let symbols = self.module_symbols.get(&module_id).unwrap_or(self.symbols);
@@ -3803,6 +4925,13 @@ impl<'a> Checker<'a> {
                     );
                     return Ty::Unknown;
                 }
+                // Aliases are expanded before sema; one that is still
+                // referenced by name is part of a cycle.
+                if symbols.type_aliases.contains_key(name) {
+                    self.diags
+                        .error(span, format!("type alias {} refers to itself", name));
+                    return Ty::Unknown;
+                }
                 let import_items = self
                     .module_import_items
                     .get(&module_id)
@@ -3841,7 +4970,7 @@ impl<'a> Checker<'a> {
                 if self.is_enum_variant_name(ty, &ident.name) {
                     self.check_enum_variant_pattern(ty, &ident.name, &[], pat.span);
                 } else {
-                    self.insert_var(&ident.name, ty.clone(), false, ident.span);
+                    self.insert_var(&ident.name, ty.clone(), self.binding_mutable, ident.span);
                 }
             }
             PatternKind::Literal(lit) => {
@@ -3856,6 +4985,89 @@ impl<'a> Checker<'a> {
             PatternKind::Struct { name, fields } => {
                 self.check_struct_pattern(ty, name, fields, pat.span);
             }
+            PatternKind::Tuple(items) => match ty {
+                Ty::Tuple(elems) if elems.len() == items.len() => {
+                    for (item, elem) in items.iter().zip(elems) {
+                        self.bind_pattern(item, elem);
+                    }
+                }
+                Ty::Unknown => {
+                    for item in items {
+                        self.bind_pattern(item, &Ty::Unknown);
+                    }
+                }
+                other => {
+                    self.diags.error(
+                        pat.span,
+                        format!(
+                            "tuple pattern with {} elements does not match {}",
+                            items.len(),
+                            other
+                        ),
+                    );
+                    for item in items {
+                        self.bind_pattern(item, &Ty::Unknown);
+                    }
+                }
+            },
+            PatternKind::Or(alts) => {
+                let mut bound: Option<BTreeMap<String, Ty>> = None;
+                for alt in alts {
+                    self.env.push();
+                    self.bind_pattern(alt, ty);
+                    let vars = self.env.pop_vars();
+                    let Some(expected) = &bound else {
+                        bound = Some(vars);
+                        continue;
+                    };
+                    if !vars.keys().eq(expected.keys()) {
+                        self.diags
+                            .error(alt.span, "or-pattern alternatives must bind the same names");
+                        continue;
+                    }
+                    for (name, alt_ty) in &vars {
+                        let expected_ty = &expected[name];
+                        if !self.is_assignable(alt_ty, expected_ty) {
+                            self.diags.error(
+                                alt.span,
+                                format!(
+                                    "or-pattern binding {name} has type {alt_ty} here but {expected_ty} in the first alternative"
+                                ),
+                            );
+                        }
+                    }
+                }
+                for (name, bound_ty) in bound.unwrap_or_default() {
+                    self.insert_var(&name, bound_ty, self.binding_mutable, pat.span);
+                }
+            }
+            PatternKind::Bind { name, pat: inner } => {
+                self.bind_pattern(inner, ty);
+                self.insert_var(&name.name, ty.clone(), self.binding_mutable, name.span);
+            }
+            PatternKind::Range { start, end } => {
+                let start_ty = self.ty_from_literal(start);
+                let end_ty = self.ty_from_literal(end);
+                let in_order = match (start, end) {
+                    (Literal::Int(start), Literal::Int(end)) => Some(start <= end),
+                    (Literal::Float(start), Literal::Float(end)) => Some(start <= end),
+                    _ => None,
+                };
+                match in_order {
+                    None => self.diags.error(
+                        pat.span,
+                        format!("range pattern bounds must both be Int or both be Float, found {start_ty} and {end_ty}"),
+                    ),
+                    Some(false) => self
+                        .diags
+                        .error(pat.span, "range pattern start must not exceed its end"),
+                    Some(true) => {
+                        if !self.is_assignable(&start_ty, ty) {
+                            self.type_mismatch(pat.span, ty, &start_ty);
+                        }
+                    }
+                }
+            }
         }
     }
 
@@ -3881,6 +5093,16 @@ impl<'a> Checker<'a> {
                     } else {
                         Ty::Int
                     }
+                } else if let Some(ty) = self.time_arithmetic(op, &left, &right) {
+                    ty
+                } else if self.decimal_operands(&left, &right) {
+                    Ty::Decimal
+                } else if self.decimal_mixed_with_float(&left, &right) {
+                    self.diags.error(
+                        span,
+                        "Decimal cannot be mixed with Float; convert with decimal.parse or decimal.to_float",
+                    );
+                    Ty::Unknown
                 } else if matches!(*op, Add)
                     && (matches!(left, Ty::String) || matches!(right, Ty::String))
                 {
@@ -3894,7 +5116,10 @@ impl<'a> Checker<'a> {
                 }
             }
             Eq | NotEq => {
-                if !self.is_assignable(&left, &right) && !self.is_assignable(&right, &left) {
+                if !self.is_assignable(&left, &right)
+                    && !self.is_assignable(&right, &left)
+                    && !self.decimal_operands(&left, &right)
+                {
                     self.diags
                         .error(span, "equality comparison on incompatible types");
                 }
@@ -3903,7 +5128,15 @@ impl<'a> Checker<'a> {
             Lt | LtEq | Gt | GtEq => {
                 if self.is_numeric(&left) && self.is_numeric(&right) {
                     Ty::Bool
-                } else if left.is_unknown() || right.is_unknown() {
+                } else if let Some(kind) = self.time_base(&left)
+                    && self.time_base(&right).as_ref() == Some(&kind)
+                    && kind != Ty::DateTime
+                {
+                    Ty::Bool
+                } else if self.decimal_operands(&left, &right)
+                    || left.is_unknown()
+                    || right.is_unknown()
+                {
                     Ty::Bool
                 } else {
                     self.diags.error(span, "comparison requires numeric types");
@@ -3920,12 +5153,12 @@ impl<'a> Checker<'a> {
                     Ty::Unknown
                 }
             }
-            Range => {
+            Range | RangeExclusive => {
                 if self.is_numeric(&left) && self.is_numeric(&right) {
                     let elem_ty = self.range_elem_type(&left, &right);
-                    Ty::List(Box::new(elem_ty))
+                    Ty::Range(Box::new(elem_ty))
                 } else if left.is_unknown() || right.is_unknown() {
-                    Ty::List(Box::new(Ty::Unknown))
+                    Ty::Range(Box::new(Ty::Unknown))
                 } else {
                     self.diags.error(span, "range requires numeric types");
                     Ty::Unknown
@@ -3934,6 +5167,62 @@ impl<'a> Checker<'a> {
         }
     }
 
+    /// Operator table for the builtin time types. Instants, durations and
+    /// dates share the Int representation at runtime, so every accepted
+    /// combination maps onto plain integer arithmetic.
+    fn time_arithmetic(&self, op: &crate::ast::BinaryOp, left: &Ty, right: &Ty) -> Option<Ty> {
+        use crate::ast::BinaryOp::*;
+        let left = self
+            .time_base(left)
+            .or_else(|| self.is_int_like(left).then_some(Ty::Int))?;
+        let right = self
+            .time_base(right)
+            .or_else(|| self.is_int_like(right).then_some(Ty::Int))?;
+        let ty = match (op, &left, &right) {
+            (Add, Ty::Instant, Ty::Duration) | (Add, Ty::Duration, Ty::Instant) => Ty::Instant,
+            (Sub, Ty::Instant, Ty::Duration) => Ty::Instant,
+            (Sub, Ty::Instant, Ty::Instant) => Ty::Duration,
+            (Add | Sub | Mod, Ty::Duration, Ty::Duration) => Ty::Duration,
+            (Mul, Ty::Duration, Ty::Int) | (Mul, Ty::Int, Ty::Duration) => Ty::Duration,
+            (Div, Ty::Duration, Ty::Int) => Ty::Duration,
+            (Div, Ty::Duration, Ty::Duration) => Ty::Int,
+            (Add, Ty::Date, Ty::Int) | (Add, Ty::Int, Ty::Date) => Ty::Date,
+            (Sub, Ty::Date, Ty::Int) => Ty::Date,
+            (Sub, Ty::Date, Ty::Date) => Ty::Int,
+            _ => return None,
+        };
+        Some(ty)
+    }
+
+    /// `Decimal` combines with another `Decimal` or an `Int`; `Int` operands
+    /// are widened exactly, so the result is always a `Decimal`.
+    fn decimal_operands(&self, left: &Ty, right: &Ty) -> bool {
+        (self.is_decimal(left) || self.is_decimal(right))
+            && (self.is_decimal(left) || self.is_int_like(left))
+            && (self.is_decimal(right) || self.is_int_like(right))
+    }
+
+    fn decimal_mixed_with_float(&self, left: &Ty, right: &Ty) -> bool {
+        let is_float = |ty: &Ty| self.numeric_base_type(ty) == Some(Ty::Float);
+        (self.is_decimal(left) && is_float(right)) || (is_float(left) && self.is_decimal(right))
+    }
+
+    fn is_decimal(&self, ty: &Ty) -> bool {
+        match ty {
+            Ty::Decimal => true,
+            Ty::Refined { base, .. } => self.is_decimal(base),
+            _ => false,
+        }
+    }
+
+    fn time_base(&self, ty: &Ty) -> Option<Ty> {
+        match ty {
+            Ty::Instant | Ty::Date | Ty::Duration | Ty::DateTime => Some(ty.clone()),
+            Ty::Refined { base, .. } => self.time_base(base),
+            _ => None,
+        }
+    }
+
     fn unify_types(&mut self, span: Span, left: Ty, right: Ty) -> Ty {
         if left.is_unknown() {
             return right;
@@ -4006,16 +5295,29 @@ impl<'a> Checker<'a> {
             (Ty::Option(value_inner), Ty::Option(target_inner)) => {
                 self.is_assignable(value_inner, target_inner)
             }
-            (Ty::List(value_inner), Ty::List(target_inner)) => {
+            (Ty::List(value_inner), Ty::List(target_inner))
+            | (Ty::Range(value_inner), Ty::Range(target_inner)) => {
                 self.is_assignable(value_inner, target_inner)
             }
             (Ty::Map(value_key, value_val), Ty::Map(target_key, target_val)) => {
                 self.is_assignable(value_key, target_key)
                     && self.is_assignable(value_val, target_val)
             }
+            (Ty::Tuple(value_items), Ty::Tuple(target_items)) => {
+                value_items.len() == target_items.len()
+                    && value_items
+                        .iter()
+                        .zip(target_items)
+                        .all(|(value, target)| self.is_assignable(value, target))
+            }
             (Ty::Task(value_inner), Ty::Task(target_inner)) => {
                 self.is_assignable(value_inner, target_inner)
             }
+            // Channels are read and written, so their element type is invariant.
+            (Ty::Channel(value_inner), Ty::Channel(target_inner)) => {
+                self.is_assignable(value_inner, target_inner)
+                    && self.is_assignable(target_inner, value_inner)
+            }
             (Ty::Option(_), _) => false,
             (_, Ty::Option(inner)) => self.is_assignable(value, inner),
             (Ty::Unknown, _) | (_, Ty::Unknown) => true,
@@ -4029,6 +5331,7 @@ impl<'a> Checker<'a> {
         }
         match target {
             Ty::Refined { base, .. } => self.is_assignable(value, base),
+            Ty::Option(inner) => self.is_assignable_with_refined(value, inner),
             _ => false,
         }
     }
@@ -4502,21 +5805,43 @@ fn force_html_input_tag_call(name: &str, args: &[CallArg]) -> bool {
         )
 }
 
+/// Maps a selected column to the row key SQLite reports for it: the alias for
+/// `col as alias`, otherwise the final segment of a qualified name.
+fn typed_query_column_field(column: &str) -> &str {
+    if let Some((_, alias)) = crate::db::split_select_alias(column) {
+        return alias;
+    }
+    column.rsplit('.').next().unwrap_or(column)
+}
+
 fn is_query_method_name(name: &str) -> bool {
     matches!(
         name,
         "select"
             | "where"
+            | "or_where"
+            | "join"
+            | "left_join"
             | "order_by"
             | "limit"
+            | "offset"
+            | "group_by"
+            | "having"
+            | "aggregate"
             | "insert"
+            | "insert_many"
             | "upsert"
+            | "on_conflict"
+            | "do_update"
+            | "do_nothing"
             | "update"
             | "delete"
+            | "returning"
             | "count"
             | "one"
             | "all"
             | "exec"
+            | "execute"
             | "sql"
             | "params"
     )
@@ -4545,7 +5870,6 @@ fn spawn_forbidden_builtin(callee: &Expr) -> Option<&'static str> {
             _ => None,
         },
         ExprKind::Member { base, name } => match &base.kind {
-            ExprKind::Ident(ident) if ident.name == "db" => Some("db.*"),
             ExprKind::Ident(ident) if ident.name == "response" => match name.name.as_str() {
                 "header" | "cookie" | "delete_cookie" => Some("response.*"),
                 _ => None,
@@ -4562,6 +5886,43 @@ fn spawn_forbidden_builtin(callee: &Expr) -> Option<&'static str> {
     }
 }
 
+fn refutable_pattern_span(pat: &Pattern) -> Option<Span> {
+    match &pat.kind {
+        PatternKind::Wildcard | PatternKind::Ident(_) => None,
+        PatternKind::Literal(_) | PatternKind::EnumVariant { .. } | PatternKind::Range { .. } => {
+            Some(pat.span)
+        }
+        PatternKind::Bind { pat, .. } => refutable_pattern_span(pat),
+        PatternKind::Or(alts) => {
+            if alts.iter().any(|alt| refutable_pattern_span(alt).is_none()) {
+                None
+            } else {
+                Some(pat.span)
+            }
+        }
+        PatternKind::Struct { fields, .. } => fields
+            .iter()
+            .find_map(|field| refutable_pattern_span(&field.pat)),
+        PatternKind::Tuple(items) => items.iter().find_map(refutable_pattern_span),
+    }
+}
+
+fn is_folded_const(expr: &Expr) -> bool {
+    match &expr.kind {
+        ExprKind::Literal(_) => true,
+        ExprKind::ListLit(items) | ExprKind::TupleLit(items) => items.iter().all(is_folded_const),
+        ExprKind::MapLit(pairs) => pairs
+            .iter()
+            .all(|(key, value)| is_folded_const(key) && is_folded_const(value)),
+        _ => false,
+    }
+}
+
+fn type_arg_count_message(name: &str, arity: usize) -> String {
+    let plural = if arity == 1 { "" } else { "s" };
+    format!("{name} expects {arity} type argument{plural}")
+}
+
 fn split_qualified_type_name(name: &str) -> Option<(&str, &str)> {
     let mut parts = name.split('.');
     let module = parts.next()?;
@@ -4632,6 +5993,23 @@ impl TypeEnv {
         Ok(())
     }
 
+    /// Pops the innermost scope and returns the types of the names it bound.
+    fn pop_vars(&mut self) -> BTreeMap<String, Ty> {
+        if self.scopes.len() <= 1 {
+            return BTreeMap::new();
+        }
+        self.scopes
+            .pop()
+            .map(|scope| {
+                scope
+                    .vars
+                    .into_iter()
+                    .map(|(name, info)| (name, info.ty))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     fn lookup(&self, name: &str) -> Option<&VarInfo> {
         for scope in self.scopes.iter().rev() {
             if let Some(var) = scope.vars.get(name) {
@@ -4839,3 +6217,109 @@ fn check_aria_attr(diags: &mut crate::diag::Diagnostics, span: Span, key: &str,
         );
     }
 }
+
+/// Signatures of the `time.*` helpers that work with the builtin `Instant`,
+/// `Date`, `Duration` and `DateTime` types.
+pub fn time_value_member_sig(name: &str) -> Option<FnSig> {
+    let sig = |params: &[(&str, Ty)], ret: Ty| FnSig {
+        type_params: Vec::new(),
+        params: params
+            .iter()
+            .map(|(name, ty)| ParamSig {
+                name: name.to_string(),
+                ty: ty.clone(),
+                has_default: false,
+            })
+            .collect(),
+        ret: Box::new(ret),
+    };
+    let fallible = |ty: Ty| Ty::Result(Box::new(ty), Box::new(Ty::Error));
+    let sig = match name {
+        "instant" => sig(&[], Ty::Instant),
+        "today" => sig(&[], Ty::Date),
+        "from_unix_ms" => sig(&[("ms", Ty::Int)], Ty::Instant),
+        "unix_ms" => sig(&[("moment", Ty::Instant)], Ty::Int),
+        "millis" | "seconds" | "minutes" | "hours" | "days" => {
+            sig(&[("count", Ty::Int)], Ty::Duration)
+        }
+        "to_millis" => sig(&[("span", Ty::Duration)], Ty::Int),
+        "date" => sig(
+            &[("year", Ty::Int), ("month", Ty::Int), ("day", Ty::Int)],
+            fallible(Ty::Date),
+        ),
+        "date_of" => sig(&[("moment", Ty::Instant)], Ty::Date),
+        "start_of" => sig(&[("day", Ty::Date)], Ty::Instant),
+        "in_zone" => sig(
+            &[("moment", Ty::Instant), ("offset", Ty::String)],
+            fallible(Ty::DateTime),
+        ),
+        "instant_of" => sig(&[("value", Ty::DateTime)], Ty::Instant),
+        "offset_of" => sig(&[("value", Ty::DateTime)], Ty::String),
+        "local_date" => sig(&[("value", Ty::DateTime)], Ty::Date),
+        "format_instant" => sig(&[("moment", Ty::Instant)], Ty::String),
+        "format_date" => sig(&[("day", Ty::Date)], Ty::String),
+        "format_duration" => sig(&[("span", Ty::Duration)], Ty::String),
+        "format_datetime" => sig(&[("value", Ty::DateTime)], Ty::String),
+        "parse_instant" => sig(&[("text", Ty::String)], fallible(Ty::Instant)),
+        "parse_date" => sig(&[("text", Ty::String)], fallible(Ty::Date)),
+        "parse_duration" => sig(&[("text", Ty::String)], fallible(Ty::Duration)),
+        "parse_datetime" => sig(&[("text", Ty::String)], fallible(Ty::DateTime)),
+        _ => return None,
+    };
+    Some(sig)
+}
+
+/// Signatures of the `decimal.*` helpers for the builtin `Decimal` type.
+pub fn decimal_member_sig(name: &str) -> Option<FnSig> {
+    let param = |name: &str, ty: Ty, has_default: bool| ParamSig {
+        name: name.to_string(),
+        ty,
+        has_default,
+    };
+    let (params, ret) = match name {
+        "parse" => (
+            vec![param("text", Ty::String, false)],
+            Ty::Result(Box::new(Ty::Decimal), Box::new(Ty::Error)),
+        ),
+        "from_int" => (vec![param("value", Ty::Int, false)], Ty::Decimal),
+        "from_units" => (
+            vec![
+                param("units", Ty::Int, false),
+                param("scale", Ty::Int, false),
+            ],
+            Ty::Decimal,
+        ),
+        "round" => (
+            vec![
+                param("value", Ty::Decimal, false),
+                param("scale", Ty::Int, false),
+                param("mode", Ty::String, true),
+            ],
+            Ty::Decimal,
+        ),
+        "scale" => (vec![param("value", Ty::Decimal, false)], Ty::Int),
+        "to_float" => (vec![param("value", Ty::Decimal, false)], Ty::Float),
+        _ => return None,
+    };
+    Some(FnSig {
+        type_params: Vec::new(),
+        params,
+        ret: Box::new(ret),
+    })
+}
+
+/// The `Iterator` protocol is functional: `next()` takes no arguments and
+/// returns the current item paired with the advanced iterator, or `null` once
+/// exhausted. Returns the item type when `sig` has that shape.
+fn iterator_next_item(sig: &FnSig, self_ty: &Ty) -> Option<Ty> {
+    if !sig.params.is_empty() || !sig.type_params.is_empty() {
+        return None;
+    }
+    let Ty::Option(inner) = sig.ret.as_ref() else {
+        return None;
+    };
+    match inner.as_ref() {
+        Ty::Tuple(items) if items.len() == 2 && items[1] == *self_ty => Some(items[0].clone()),
+        _ => None,
+    }
+}
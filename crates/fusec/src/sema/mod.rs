@@ -1,4 +1,5 @@
 pub mod check;
+pub mod sql;
 pub mod symbols;
 pub mod types;
 
@@ -12,6 +13,8 @@ use crate::diag::{Diag, Diagnostics};
 use crate::loader::{ModuleId, ModuleRegistry};
 use crate::span::Span;
 
+const FUSE_VISIBILITY_IMPLICIT_EXPORTS: &str = "FUSE_VISIBILITY_IMPLICIT_EXPORTS";
+
 pub struct Analysis {
     pub symbols: symbols::ModuleSymbols,
 }
@@ -25,6 +28,7 @@ pub fn analyze_program(program: &Program) -> (Analysis, Vec<Diag>) {
     let mut diags = Diagnostics::default();
     let mut expanded = program.clone();
     expand_type_derivations(&mut expanded, &mut diags);
+    crate::frontend::monomorphize::monomorphize_program_types(&mut expanded);
     crate::frontend::canonicalize::canonicalize_program(&mut expanded);
     let declared_caps = collect_declared_capabilities(&expanded, &mut diags);
     let symbols = symbols::collect(&expanded, &mut diags);
@@ -106,6 +110,8 @@ pub fn analyze_registry_with_options(
         module_maps_by_id.insert(*id, unit.modules.clone());
         module_paths_by_id.insert(*id, unit.path.clone());
     }
+    let (sql_schema, migration_sql_problems) = sql::SqlSchema::from_registry(registry);
+    report_migration_sql_problems(registry, migration_sql_problems, None, &mut diags);
     for (id, unit) in &registry.modules {
         let symbols = match symbols_by_id.get(id) {
             Some(symbols) => symbols,
@@ -124,6 +130,7 @@ pub fn analyze_registry_with_options(
             &module_paths_by_id,
             &mut diags,
         );
+        checker.set_sql_schema(sql_schema.as_ref());
         checker.check_program(&unit.program);
         used_caps_by_id.insert(*id, checker.used_capabilities().clone());
     }
@@ -135,6 +142,7 @@ pub fn analyze_registry_with_options(
             &mut diags,
         );
         validate_strict_cross_layer_import_cycles(registry, &mut diags);
+        validate_strict_explicit_visibility(registry, &mut diags);
         validate_strict_error_domain_isolation(
             registry,
             &module_maps_by_id,
@@ -189,6 +197,13 @@ pub fn analyze_module(registry: &ModuleRegistry, module_id: ModuleId) -> (Analys
         .get(&module_id)
         .cloned()
         .unwrap_or_else(symbols::ModuleSymbols::default);
+    let (sql_schema, migration_sql_problems) = sql::SqlSchema::from_registry(registry);
+    report_migration_sql_problems(
+        registry,
+        migration_sql_problems,
+        Some(module_id),
+        &mut diags,
+    );
     if let Some(unit) = registry.modules.get(&module_id) {
         let mut checker = check::Checker::new(
             module_id,
@@ -203,12 +218,36 @@ pub fn analyze_module(registry: &ModuleRegistry, module_id: ModuleId) -> (Analys
             &module_paths_by_id,
             &mut diags,
         );
+        checker.set_sql_schema(sql_schema.as_ref());
         checker.check_program(&unit.program);
     }
 
     (Analysis { symbols }, diags.into_vec())
 }
 
+fn report_migration_sql_problems(
+    registry: &ModuleRegistry,
+    problems: Vec<sql::MigrationSqlProblem>,
+    only_module: Option<ModuleId>,
+    diags: &mut Diagnostics,
+) {
+    for entry in problems {
+        if only_module.is_some_and(|id| id != entry.module_id) {
+            continue;
+        }
+        let problem = entry.problem;
+        match registry.get(entry.module_id) {
+            Some(unit) => diags.error_at_path_with_code(
+                unit.path.clone(),
+                problem.span,
+                problem.code,
+                problem.message,
+            ),
+            None => diags.error_with_code(problem.span, problem.code, problem.message),
+        }
+    }
+}
+
 fn validate_strict_capability_purity(
     registry: &ModuleRegistry,
     declared_caps_by_id: &HashMap<ModuleId, HashSet<Capability>>,
@@ -270,6 +309,53 @@ fn validate_strict_cross_layer_import_cycles(registry: &ModuleRegistry, diags: &
     }
 }
 
+/// Migration aid: modules imported elsewhere that still export every
+/// declaration because none is marked `pub`.
+fn validate_strict_explicit_visibility(registry: &ModuleRegistry, diags: &mut Diagnostics) {
+    let mut imported: Vec<ModuleId> = registry
+        .modules
+        .values()
+        .flat_map(imported_module_ids)
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    imported.sort_unstable();
+    for module_id in imported {
+        let Some(unit) = registry.get(module_id) else {
+            continue;
+        };
+        if unit.exports.explicit_visibility || crate::loader::is_std_error_virtual_path(&unit.path)
+        {
+            continue;
+        }
+        let Some(span) = unit.program.items.iter().find_map(visibility_item_span) else {
+            continue;
+        };
+        diags.warning_at_path_with_code(
+            unit.path.clone(),
+            span,
+            FUSE_VISIBILITY_IMPLICIT_EXPORTS,
+            format!(
+                "strict architecture: {} exports every declaration implicitly; mark its public API with pub",
+                unit.path.display()
+            ),
+        );
+    }
+}
+
+fn visibility_item_span(item: &Item) -> Option<Span> {
+    match item {
+        Item::Type(decl) => Some(decl.name.span),
+        Item::TypeAlias(decl) => Some(decl.name.span),
+        Item::Enum(decl) => Some(decl.name.span),
+        Item::Interface(decl) => Some(decl.name.span),
+        Item::Fn(decl) => Some(decl.name.span),
+        Item::Config(decl) => Some(decl.name.span),
+        Item::Const(decl) => Some(decl.name.span),
+        _ => None,
+    }
+}
+
 fn validate_strict_error_domain_isolation(
     registry: &ModuleRegistry,
     module_maps_by_id: &HashMap<ModuleId, crate::loader::ModuleMap>,
@@ -595,7 +681,7 @@ fn resolve_derived_fields(
     };
 
     let fields = if let Some(derive) = &decl.derive {
-        resolve_without_fields(program, diags, derive, cache, visiting)
+        resolve_derive(program, diags, derive, cache, visiting)
     } else {
         Some(decl.fields.clone())
     };
@@ -607,7 +693,7 @@ fn resolve_derived_fields(
     fields
 }
 
-fn resolve_without_fields(
+fn resolve_derive(
     program: &Program,
     diags: &mut Diagnostics,
     derive: &TypeDerive,
@@ -623,25 +709,10 @@ fn resolve_without_fields(
         return None;
     }
     let base_fields = resolve_derived_fields(program, diags, base_name, cache, visiting)?;
-
-    let mut removed = HashSet::new();
-    for field in &derive.without {
-        removed.insert(field.name.clone());
-    }
-
-    for field in &derive.without {
-        if !base_fields.iter().any(|f| f.name.name == field.name) {
-            diags.error(
-                field.span,
-                format!("unknown field {} in {}", field.name, derive.base.name),
-            );
-        }
+    let (fields, errors) = crate::loader::apply_derive_ops(base_fields, derive);
+    for (span, message) in errors {
+        diags.error(span, message);
     }
-
-    let fields = base_fields
-        .into_iter()
-        .filter(|field| !removed.contains(&field.name.name))
-        .collect();
     Some(fields)
 }
 
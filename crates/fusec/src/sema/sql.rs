@@ -0,0 +1,563 @@
+//! Compile-time SQL checking.
+//!
+//! Every `migration` block is replayed, in run order, into an in-memory SQLite
+//! database. Literal SQL passed to `db.exec`/`db.query`/`db.one` and
+//! `db.from(...)` builder chains are then prepared against that schema so
+//! unknown tables/columns, placeholder count mismatches and typed projection
+//! mismatches surface as `FUSE_SQL_*` diagnostics instead of runtime errors.
+
+use rusqlite::Connection;
+
+use crate::ast::{Block, CallArg, Expr, ExprKind, InterpPart, Item, Literal, Stmt, StmtKind};
+use crate::db::Query;
+use crate::interp::Value;
+use crate::loader::{ModuleId, ModuleRegistry};
+use crate::span::Span;
+
+use super::types::Ty;
+
+pub const FUSE_SQL_UNKNOWN_TABLE: &str = "FUSE_SQL_UNKNOWN_TABLE";
+pub const FUSE_SQL_UNKNOWN_COLUMN: &str = "FUSE_SQL_UNKNOWN_COLUMN";
+pub const FUSE_SQL_PARAM_COUNT: &str = "FUSE_SQL_PARAM_COUNT";
+pub const FUSE_SQL_TYPE_MISMATCH: &str = "FUSE_SQL_TYPE_MISMATCH";
+pub const FUSE_SQL_SYNTAX: &str = "FUSE_SQL_SYNTAX";
+
+pub struct SqlSchema {
+    conn: Connection,
+}
+
+pub struct SqlProblem {
+    pub span: Span,
+    pub code: &'static str,
+    pub message: String,
+}
+
+/// A problem found while replaying a migration, reported against its module.
+pub struct MigrationSqlProblem {
+    pub module_id: ModuleId,
+    pub problem: SqlProblem,
+}
+
+pub struct SqlColumn {
+    pub name: String,
+    pub decl_type: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum ColumnAffinity {
+    Integer,
+    Real,
+    Text,
+}
+
+impl SqlSchema {
+    /// Builds the schema from every migration in `registry`. Returns `None`
+    /// when there are no migrations, or when the replay cannot account for
+    /// the whole schema: a migration issues non-literal SQL, or its SQL fails
+    /// against the replayed tables (it targets tables that pre-date the
+    /// migration history). Only placeholder count mismatches in migrations
+    /// are reported, since those do not depend on the schema.
+    pub fn from_registry(
+        registry: &ModuleRegistry,
+    ) -> (Option<SqlSchema>, Vec<MigrationSqlProblem>) {
+        let mut migrations = Vec::new();
+        for (id, unit) in &registry.modules {
+            for item in &unit.program.items {
+                if let Item::Migration(decl) = item {
+                    migrations.push((decl.name.as_str(), unit.path.as_path(), *id, &decl.body));
+                }
+            }
+        }
+        if migrations.is_empty() {
+            return (None, Vec::new());
+        }
+        migrations.sort_by(|a, b| a.0.cmp(b.0).then_with(|| a.1.cmp(b.1)));
+        let Ok(conn) = Connection::open_in_memory() else {
+            return (None, Vec::new());
+        };
+        let schema = SqlSchema { conn };
+        let mut problems = Vec::new();
+        let mut complete = true;
+        for (_, _, module_id, body) in migrations {
+            visit_block(body, &mut |expr| {
+                let Some(args) = raw_db_call_args(expr) else {
+                    return;
+                };
+                let Some((sql, _)) = args.first().and_then(|arg| literal_sql(&arg.value)) else {
+                    complete = false;
+                    return;
+                };
+                if !is_checked_statement(sql) {
+                    if schema.conn.execute_batch(sql).is_err() {
+                        complete = false;
+                    }
+                    return;
+                }
+                match schema.check_raw_call(args) {
+                    Some(problem) if problem.code == FUSE_SQL_PARAM_COUNT => {
+                        problems.push(MigrationSqlProblem { module_id, problem });
+                    }
+                    Some(_) => complete = false,
+                    None => {}
+                }
+            });
+        }
+        if !complete {
+            return (None, problems);
+        }
+        // Tables created outside migrations (setup code, tests) are folded in
+        // so queries against them are not reported as unknown.
+        for unit in registry.modules.values() {
+            for item in &unit.program.items {
+                for block in non_migration_blocks(item) {
+                    visit_block(block, &mut |expr| {
+                        let Some(args) = raw_db_call_args(expr) else {
+                            return;
+                        };
+                        if let Some((sql, _)) = args.first().and_then(|arg| literal_sql(&arg.value))
+                            && !is_checked_statement(sql)
+                        {
+                            let _ = schema.conn.execute_batch(sql);
+                        }
+                    });
+                }
+            }
+        }
+        (Some(schema), problems)
+    }
+
+    /// Checks a raw `db.exec`/`db.execute`/`db.query`/`db.one` call whose SQL
+    /// is a string literal. DDL and other non-DML statements are not checked.
+    pub fn check_raw_call(&self, args: &[CallArg]) -> Option<SqlProblem> {
+        let (sql, span) = args.first().and_then(|arg| literal_sql(&arg.value))?;
+        if !is_checked_statement(sql) {
+            return None;
+        }
+        let (param_count, _) = match self.describe(sql) {
+            Ok(described) => described?,
+            Err(err) => return Some(problem_from_error(span, &err)),
+        };
+        let (provided, provided_span) = match args.get(1) {
+            Some(arg) => match &arg.value.kind {
+                ExprKind::ListLit(items) => (items.len(), arg.span),
+                _ => return None,
+            },
+            None => (0, span),
+        };
+        if provided == param_count {
+            return None;
+        }
+        Some(SqlProblem {
+            span: provided_span,
+            code: FUSE_SQL_PARAM_COUNT,
+            message: format!(
+                "sql statement expects {} parameter{}, got {}",
+                param_count,
+                if param_count == 1 { "" } else { "s" },
+                provided
+            ),
+        })
+    }
+
+    /// Returns the result columns of a literal raw SQL call, or `None` when
+    /// the statement cannot be described (problems are reported by
+    /// [`SqlSchema::check_raw_call`]).
+    pub fn raw_call_columns(&self, args: &[CallArg]) -> Option<Vec<SqlColumn>> {
+        let (sql, _) = args.first().and_then(|arg| literal_sql(&arg.value))?;
+        if !is_checked_statement(sql) {
+            return None;
+        }
+        let (_, columns) = self.describe(sql).ok()??;
+        Some(columns)
+    }
+
+    /// Checks a `db.from(...)` builder chain ending at `expr` and returns the
+    /// result columns of the generated statement. Chains that cannot be
+    /// replayed statically (non-literal table/column names, insert/upsert)
+    /// yield `Ok(None)`.
+    pub fn check_query_chain(&self, expr: &Expr) -> Result<Option<Vec<SqlColumn>>, SqlProblem> {
+        let Some((sql, span)) = query_chain_sql(expr) else {
+            return Ok(None);
+        };
+        match self.describe(&sql) {
+            Ok(Some((_, columns))) => Ok(Some(columns)),
+            Ok(None) => Ok(None),
+            Err(err) => Err(problem_from_error(span, &err)),
+        }
+    }
+
+    fn describe(&self, sql: &str) -> Result<Option<(usize, Vec<SqlColumn>)>, rusqlite::Error> {
+        let stmt = match self.conn.prepare(sql) {
+            Ok(stmt) => stmt,
+            // Several statements in one string are executed as a batch at
+            // runtime; only single statements are checked.
+            Err(rusqlite::Error::MultipleStatement) => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        let columns = stmt
+            .columns()
+            .into_iter()
+            .map(|column| SqlColumn {
+                name: column.name().to_string(),
+                decl_type: column.decl_type().map(str::to_string),
+            })
+            .collect();
+        Ok(Some((stmt.parameter_count(), columns)))
+    }
+}
+
+/// Reports a field whose type cannot hold values of the column's declared
+/// type. Columns without a declared type (expressions, aggregates) and
+/// fields of non-scalar types are not compared.
+pub fn column_type_mismatch(column: &SqlColumn, field_ty: &Ty) -> Option<String> {
+    let decl_type = column.decl_type.as_deref()?;
+    let affinity = column_affinity(decl_type)?;
+    let mut ty = field_ty;
+    while let Ty::Option(inner) | Ty::Boxed(inner) = ty {
+        ty = inner;
+    }
+    if let Ty::Refined { base, .. } = ty {
+        ty = base;
+    }
+    let compatible = match ty {
+        Ty::Int | Ty::Instant | Ty::Date | Ty::Duration => affinity == ColumnAffinity::Integer,
+        Ty::Float => matches!(affinity, ColumnAffinity::Integer | ColumnAffinity::Real),
+        Ty::Bool => affinity != ColumnAffinity::Text,
+        Ty::String | Ty::Id | Ty::Email | Ty::DateTime | Ty::Decimal => {
+            affinity == ColumnAffinity::Text
+        }
+        _ => return None,
+    };
+    if compatible {
+        None
+    } else {
+        Some(decl_type.to_string())
+    }
+}
+
+/// SQLite type affinity rules (https://sqlite.org/datatype3.html, 3.1);
+/// NUMERIC and BLOB affinity columns accept anything and are not compared.
+fn column_affinity(decl_type: &str) -> Option<ColumnAffinity> {
+    let upper = decl_type.to_ascii_uppercase();
+    if upper.contains("INT") {
+        Some(ColumnAffinity::Integer)
+    } else if upper.contains("CHAR") || upper.contains("CLOB") || upper.contains("TEXT") {
+        Some(ColumnAffinity::Text)
+    } else if upper.contains("BLOB") || upper.is_empty() {
+        None
+    } else if upper.contains("REAL") || upper.contains("FLOA") || upper.contains("DOUB") {
+        Some(ColumnAffinity::Real)
+    } else {
+        None
+    }
+}
+
+fn problem_from_error(span: Span, err: &rusqlite::Error) -> SqlProblem {
+    let message = match err {
+        rusqlite::Error::SqliteFailure(_, Some(message)) => message.clone(),
+        rusqlite::Error::SqlInputError { msg, .. } => msg.clone(),
+        other => other.to_string(),
+    };
+    let code = if message.starts_with("no such table") {
+        FUSE_SQL_UNKNOWN_TABLE
+    } else if message.starts_with("no such column") || message.contains("has no column named") {
+        FUSE_SQL_UNKNOWN_COLUMN
+    } else {
+        FUSE_SQL_SYNTAX
+    };
+    SqlProblem {
+        span,
+        code,
+        message: format!("sql error: {message}"),
+    }
+}
+
+fn literal_sql(expr: &Expr) -> Option<(&str, Span)> {
+    match &expr.kind {
+        ExprKind::Literal(Literal::String(text)) => Some((text.as_str(), expr.span)),
+        _ => None,
+    }
+}
+
+fn raw_db_call_args(expr: &Expr) -> Option<&[CallArg]> {
+    let ExprKind::Call { callee, args, .. } = &expr.kind else {
+        return None;
+    };
+    let ExprKind::Member { base, name } = &callee.kind else {
+        return None;
+    };
+    match &base.kind {
+        ExprKind::Ident(ident)
+            if ident.name == "db"
+                && matches!(name.name.as_str(), "exec" | "execute" | "query" | "one") =>
+        {
+            Some(args)
+        }
+        _ => None,
+    }
+}
+
+/// Statements that are prepared and checked; everything else (DDL, pragmas,
+/// transaction control) only shapes the schema.
+fn is_checked_statement(sql: &str) -> bool {
+    let mut rest = sql.trim_start();
+    while let Some(comment) = rest.strip_prefix("--") {
+        rest = comment
+            .split_once('\n')
+            .map(|(_, tail)| tail)
+            .unwrap_or("")
+            .trim_start();
+    }
+    let keyword: String = rest
+        .chars()
+        .take_while(|ch| ch.is_ascii_alphabetic())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    matches!(
+        keyword.as_str(),
+        "select" | "insert" | "update" | "delete" | "replace" | "with"
+    )
+}
+
+fn query_chain_sql(expr: &Expr) -> Option<(String, Span)> {
+    let mut calls = Vec::new();
+    let mut current = expr;
+    let (table, span) = loop {
+        let ExprKind::Call { callee, args, .. } = &current.kind else {
+            return None;
+        };
+        let ExprKind::Member { base, name } = &callee.kind else {
+            return None;
+        };
+        if name.name == "from" {
+            match &base.kind {
+                ExprKind::Ident(ident) if ident.name == "db" => {}
+                _ => return None,
+            }
+            let (table, span) = literal_sql(&args.first()?.value)?;
+            break (table, span);
+        }
+        calls.push((name.name.as_str(), args.as_slice()));
+        current = base;
+    };
+    let mut query = Query::new(table.to_string()).ok()?;
+    for (method, args) in calls.into_iter().rev() {
+        let text = |idx: usize| -> Option<String> {
+            literal_sql(&args.get(idx)?.value).map(|(text, _)| text.to_string())
+        };
+        let texts = |idx: usize| -> Option<Vec<String>> {
+            let ExprKind::ListLit(items) = &args.get(idx)?.value.kind else {
+                return None;
+            };
+            items
+                .iter()
+                .map(|item| literal_sql(item).map(|(text, _)| text.to_string()))
+                .collect()
+        };
+        let placeholder = |op: &str| {
+            if op.trim().eq_ignore_ascii_case("in") {
+                Value::List(vec![Value::Null])
+            } else {
+                Value::Null
+            }
+        };
+        query = match method {
+            "select" => query.select(texts(0)?),
+            "where" => {
+                let op = text(1)?;
+                query.where_clause(text(0)?, op.clone(), placeholder(&op))
+            }
+            "or_where" => {
+                let op = text(1)?;
+                query.or_where(text(0)?, op.clone(), placeholder(&op))
+            }
+            "having" => {
+                let op = text(1)?;
+                query.having(text(0)?, op.clone(), placeholder(&op))
+            }
+            "join" => query.join(text(0)?, text(1)?, text(2)?),
+            "left_join" => query.left_join(text(0)?, text(1)?, text(2)?),
+            "order_by" => query.order_by(text(0)?, text(1)?),
+            "limit" => query.limit(1),
+            "offset" => query.offset(1),
+            "group_by" => query.group_by(texts(0)?),
+            "aggregate" => query.aggregate(text(0)?, text(1)?, text(2)?),
+            "update" => query.update_set(text(0)?, Value::Null),
+            "delete" => query.delete_rows(),
+            "returning" => query.returning(texts(0)?),
+            "count" => query.count(),
+            "one" | "all" | "exec" | "execute" | "sql" | "params" => break,
+            _ => return None,
+        }
+        .ok()?;
+    }
+    let (sql, _) = query.build_sql(None).ok()?;
+    Some((sql, span))
+}
+
+fn non_migration_blocks(item: &Item) -> Vec<&Block> {
+    match item {
+        Item::Fn(decl) => vec![&decl.body],
+        Item::Impl(decl) => decl.methods.iter().map(|method| &method.body).collect(),
+        Item::Component(decl) => vec![&decl.body],
+        Item::Service(decl) => decl.routes.iter().map(|route| &route.body).collect(),
+        Item::App(decl) => vec![&decl.body],
+        Item::Seed(decl) => vec![&decl.body],
+        Item::Test(decl) => vec![&decl.body],
+        _ => Vec::new(),
+    }
+}
+
+fn visit_block(block: &Block, f: &mut impl FnMut(&Expr)) {
+    for stmt in &block.stmts {
+        visit_stmt(stmt, f);
+    }
+}
+
+fn visit_stmt(stmt: &Stmt, f: &mut impl FnMut(&Expr)) {
+    match &stmt.kind {
+        StmtKind::Let { expr, .. }
+        | StmtKind::Var { expr, .. }
+        | StmtKind::LetPattern { expr, .. }
+        | StmtKind::Expr(expr) => visit_expr(expr, f),
+        StmtKind::Assign { target, expr } => {
+            visit_expr(target, f);
+            visit_expr(expr, f);
+        }
+        StmtKind::Return { expr } => {
+            if let Some(expr) = expr {
+                visit_expr(expr, f);
+            }
+        }
+        StmtKind::If {
+            cond,
+            then_block,
+            else_if,
+            else_block,
+        } => {
+            visit_expr(cond, f);
+            visit_block(then_block, f);
+            for (cond, block) in else_if {
+                visit_expr(cond, f);
+                visit_block(block, f);
+            }
+            if let Some(block) = else_block {
+                visit_block(block, f);
+            }
+        }
+        StmtKind::Match { expr, cases } => {
+            visit_expr(expr, f);
+            for case in cases {
+                if let Some(guard) = &case.guard {
+                    visit_expr(guard, f);
+                }
+                visit_block(&case.block, f);
+            }
+        }
+        StmtKind::For { iter, block, .. } => {
+            visit_expr(iter, f);
+            visit_block(block, f);
+        }
+        StmtKind::While { cond, block } => {
+            visit_expr(cond, f);
+            visit_block(block, f);
+        }
+        StmtKind::Transaction { block } => visit_block(block, f),
+        StmtKind::Break | StmtKind::Continue => {}
+    }
+}
+
+fn visit_expr(expr: &Expr, f: &mut impl FnMut(&Expr)) {
+    f(expr);
+    match &expr.kind {
+        ExprKind::Literal(_) | ExprKind::Ident(_) => {}
+        ExprKind::Binary { left, right, .. } | ExprKind::Coalesce { left, right } => {
+            visit_expr(left, f);
+            visit_expr(right, f);
+        }
+        ExprKind::Unary { expr, .. }
+        | ExprKind::Box { expr }
+        | ExprKind::Member { base: expr, .. }
+        | ExprKind::OptionalMember { base: expr, .. } => visit_expr(expr, f),
+        ExprKind::Call { callee, args, .. } => {
+            visit_expr(callee, f);
+            for arg in args {
+                visit_expr(&arg.value, f);
+            }
+        }
+        ExprKind::Index { base, index } | ExprKind::OptionalIndex { base, index } => {
+            visit_expr(base, f);
+            visit_expr(index, f);
+        }
+        ExprKind::StructLit { fields, .. } => {
+            for field in fields {
+                visit_expr(&field.value, f);
+            }
+        }
+        ExprKind::RecordUpdate { base, fields } => {
+            visit_expr(base, f);
+            for field in fields {
+                visit_expr(&field.value, f);
+            }
+        }
+        ExprKind::ListLit(items) | ExprKind::TupleLit(items) => {
+            for item in items {
+                visit_expr(item, f);
+            }
+        }
+        ExprKind::MapLit(entries) => {
+            for (key, value) in entries {
+                visit_expr(key, f);
+                visit_expr(value, f);
+            }
+        }
+        ExprKind::InterpString(parts) => {
+            for part in parts {
+                if let InterpPart::Expr(expr) = part {
+                    visit_expr(expr, f);
+                }
+            }
+        }
+        ExprKind::BangChain { expr, error }
+        | ExprKind::Await {
+            expr,
+            timeout: error,
+        } => {
+            visit_expr(expr, f);
+            if let Some(error) = error {
+                visit_expr(error, f);
+            }
+        }
+        ExprKind::Spawn { block } => visit_block(block, f),
+        ExprKind::HtmlIf {
+            cond,
+            then_children,
+            else_if,
+            else_children,
+        } => {
+            visit_expr(cond, f);
+            for child in then_children {
+                visit_expr(child, f);
+            }
+            for (cond, children) in else_if {
+                visit_expr(cond, f);
+                for child in children {
+                    visit_expr(child, f);
+                }
+            }
+            for child in else_children {
+                visit_expr(child, f);
+            }
+        }
+        ExprKind::HtmlFor {
+            iter,
+            body_children,
+            ..
+        } => {
+            visit_expr(iter, f);
+            for child in body_children {
+                visit_expr(child, f);
+            }
+        }
+    }
+}
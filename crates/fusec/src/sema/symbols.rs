@@ -2,8 +2,8 @@ use std::collections::HashMap;
 
 use crate::ast::{
     Block, ComponentDecl, ConfigDecl, EnumDecl, Expr, ExprKind, FnDecl, Ident, ImplDecl,
-    ImportDecl, ImportSpec, InterfaceDecl, InterfaceMember, InterpPart, Item, Pattern,
-    PatternKind, Program, ServiceDecl, Stmt, StmtKind, TypeDecl, TypeRef, TypeRefKind,
+    ImportDecl, ImportSpec, InterfaceDecl, InterfaceMember, InterpPart, Item, Pattern, PatternKind,
+    Program, ServiceDecl, Stmt, StmtKind, TypeDecl, TypeRef, TypeRefKind,
 };
 use crate::diag::Diagnostics;
 use crate::loader::{ImportPathKind, classify_import_path};
@@ -16,6 +16,8 @@ pub struct ModuleSymbols {
     pub interfaces: HashMap<String, InterfaceInfo>,
     pub functions: HashMap<String, FnSigRef>,
     pub configs: HashMap<String, ConfigInfo>,
+    pub consts: HashMap<String, ConstInfo>,
+    pub type_aliases: HashMap<String, TypeAliasInfo>,
     pub services: HashMap<String, ServiceInfo>,
     pub imports: HashMap<String, ImportInfo>,
     pub impls: Vec<ImplInfo>,
@@ -34,6 +36,7 @@ impl ModuleSymbols {
 #[derive(Clone, Debug)]
 pub struct TypeInfo {
     pub name: String,
+    pub type_params: Vec<TypeParamRef>,
     pub fields: Vec<FieldInfo>,
     pub span: Span,
 }
@@ -49,6 +52,7 @@ pub struct FieldInfo {
 #[derive(Clone, Debug)]
 pub struct EnumInfo {
     pub name: String,
+    pub type_params: Vec<TypeParamRef>,
     pub variants: Vec<EnumVariantInfo>,
     pub span: Span,
 }
@@ -63,6 +67,7 @@ pub struct EnumVariantInfo {
 #[derive(Clone, Debug)]
 pub struct InterfaceInfo {
     pub name: String,
+    pub extends: Vec<String>,
     pub members: Vec<InterfaceMemberInfo>,
     pub span: Span,
 }
@@ -87,6 +92,9 @@ pub struct InterfaceMemberInfo {
     pub params: Vec<ParamRef>,
     pub ret: Option<TypeRef>,
     pub where_clause: Vec<WhereConstraintRef>,
+    pub has_default: bool,
+    /// Whether the default body reads `self`; members without a default are `false`.
+    pub uses_self: bool,
     pub span: Span,
 }
 
@@ -134,6 +142,20 @@ pub struct ConfigInfo {
     pub span: Span,
 }
 
+#[derive(Clone, Debug)]
+pub struct ConstInfo {
+    pub name: String,
+    pub ty: TypeRef,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug)]
+pub struct TypeAliasInfo {
+    pub name: String,
+    pub target: TypeRef,
+    pub span: Span,
+}
+
 #[derive(Clone, Debug)]
 pub struct ServiceInfo {
     pub name: String,
@@ -170,6 +192,8 @@ pub fn collect(program: &Program, diags: &mut Diagnostics) -> ModuleSymbols {
     let mut interfaces = HashMap::new();
     let mut functions = HashMap::new();
     let mut configs = HashMap::new();
+    let mut consts = HashMap::new();
+    let mut type_aliases = HashMap::new();
     let mut services = HashMap::new();
     let mut imports = HashMap::new();
     let mut impls = Vec::new();
@@ -184,9 +208,33 @@ pub fn collect(program: &Program, diags: &mut Diagnostics) -> ModuleSymbols {
             Item::Fn(decl) => collect_fn(decl, &mut functions, &mut names, diags),
             Item::Component(decl) => collect_component(decl, &mut functions, &mut names, diags),
             Item::Config(decl) => collect_config(decl, &mut configs, &mut names, diags),
+            Item::Const(decl) => {
+                if register_name(&decl.name.name, decl.name.span, &mut names, diags) {
+                    consts.insert(
+                        decl.name.name.clone(),
+                        ConstInfo {
+                            name: decl.name.name.clone(),
+                            ty: decl.ty.clone(),
+                            span: decl.span,
+                        },
+                    );
+                }
+            }
+            Item::TypeAlias(decl) => {
+                if register_name(&decl.name.name, decl.name.span, &mut names, diags) {
+                    type_aliases.insert(
+                        decl.name.name.clone(),
+                        TypeAliasInfo {
+                            name: decl.name.name.clone(),
+                            target: decl.target.clone(),
+                            span: decl.span,
+                        },
+                    );
+                }
+            }
             Item::Service(decl) => collect_service(decl, &mut services, &mut names, diags),
             Item::Impl(decl) => impls.push(collect_impl(decl)),
-            Item::App(_) | Item::Migration(_) | Item::Test(_) => {}
+            Item::App(_) | Item::Migration(_) | Item::Seed(_) | Item::Test(_) => {}
         }
     }
 
@@ -196,6 +244,8 @@ pub fn collect(program: &Program, diags: &mut Diagnostics) -> ModuleSymbols {
         interfaces,
         functions,
         configs,
+        consts,
+        type_aliases,
         services,
         imports,
         impls,
@@ -321,6 +371,7 @@ fn collect_type(
         decl.name.name.clone(),
         TypeInfo {
             name: decl.name.name.clone(),
+            type_params: type_params_ref(&decl.type_params),
             fields,
             span: decl.span,
         },
@@ -349,6 +400,7 @@ fn collect_enum(
         decl.name.name.clone(),
         EnumInfo {
             name: decl.name.name.clone(),
+            type_params: type_params_ref(&decl.type_params),
             variants,
             span: decl.span,
         },
@@ -369,6 +421,11 @@ fn collect_interface(
         decl.name.name.clone(),
         InterfaceInfo {
             name: decl.name.name.clone(),
+            extends: decl
+                .extends
+                .iter()
+                .map(|parent| parent.name.clone())
+                .collect(),
             members,
             span: decl.span,
         },
@@ -382,10 +439,23 @@ fn interface_member_info(member: &InterfaceMember) -> InterfaceMemberInfo {
         params: params_ref(&member.params),
         ret: member.ret.clone(),
         where_clause: where_constraints_ref(&member.where_clause),
+        has_default: member.body.is_some(),
+        uses_self: interface_member_uses_self(member),
         span: member.span,
     }
 }
 
+pub(crate) fn interface_member_uses_self(member: &InterfaceMember) -> bool {
+    member.body.as_ref().is_some_and(|body| {
+        member
+            .params
+            .iter()
+            .filter_map(|param| param.default.as_ref())
+            .any(|expr| expr_uses_ident(expr, "self"))
+            || block_uses_ident(body, "self")
+    })
+}
+
 fn collect_fn(
     decl: &FnDecl,
     functions: &mut HashMap<String, FnSigRef>,
@@ -481,10 +551,15 @@ fn stmt_uses_ident(stmt: &Stmt, ident: &str) -> bool {
         StmtKind::Let { ty: _, expr, .. } | StmtKind::Var { ty: _, expr, .. } => {
             expr_uses_ident(expr, ident)
         }
+        StmtKind::LetPattern { pat, expr, .. } => {
+            pattern_uses_ident(pat, ident) || expr_uses_ident(expr, ident)
+        }
         StmtKind::Assign { target, expr } => {
             expr_uses_ident(target, ident) || expr_uses_ident(expr, ident)
         }
-        StmtKind::Return { expr } => expr.as_ref().is_some_and(|expr| expr_uses_ident(expr, ident)),
+        StmtKind::Return { expr } => expr
+            .as_ref()
+            .is_some_and(|expr| expr_uses_ident(expr, ident)),
         StmtKind::If {
             cond,
             then_block,
@@ -493,23 +568,32 @@ fn stmt_uses_ident(stmt: &Stmt, ident: &str) -> bool {
         } => {
             expr_uses_ident(cond, ident)
                 || block_uses_ident(then_block, ident)
-                || else_if
-                    .iter()
-                    .any(|(expr, block)| expr_uses_ident(expr, ident) || block_uses_ident(block, ident))
+                || else_if.iter().any(|(expr, block)| {
+                    expr_uses_ident(expr, ident) || block_uses_ident(block, ident)
+                })
                 || else_block
                     .as_ref()
                     .is_some_and(|block| block_uses_ident(block, ident))
         }
         StmtKind::Match { expr, cases } => {
             expr_uses_ident(expr, ident)
-                || cases
-                    .iter()
-                    .any(|(pat, block)| pattern_uses_ident(pat, ident) || block_uses_ident(block, ident))
+                || cases.iter().any(|case| {
+                    pattern_uses_ident(&case.pat, ident)
+                        || case
+                            .guard
+                            .as_ref()
+                            .is_some_and(|guard| expr_uses_ident(guard, ident))
+                        || block_uses_ident(&case.block, ident)
+                })
         }
         StmtKind::For { pat, iter, block } => {
-            pattern_uses_ident(pat, ident) || expr_uses_ident(iter, ident) || block_uses_ident(block, ident)
+            pattern_uses_ident(pat, ident)
+                || expr_uses_ident(iter, ident)
+                || block_uses_ident(block, ident)
+        }
+        StmtKind::While { cond, block } => {
+            expr_uses_ident(cond, ident) || block_uses_ident(block, ident)
         }
-        StmtKind::While { cond, block } => expr_uses_ident(cond, ident) || block_uses_ident(block, ident),
         StmtKind::Transaction { block } => block_uses_ident(block, ident),
         StmtKind::Expr(expr) => expr_uses_ident(expr, ident),
         StmtKind::Break | StmtKind::Continue => false,
@@ -524,12 +608,19 @@ fn expr_uses_ident(expr: &Expr, ident: &str) -> bool {
             expr_uses_ident(left, ident) || expr_uses_ident(right, ident)
         }
         ExprKind::Unary { expr, .. }
-        | ExprKind::Await { expr }
+        | ExprKind::Await {
+            expr,
+            timeout: None,
+        }
         | ExprKind::Box { expr }
         | ExprKind::BangChain { expr, error: None } => expr_uses_ident(expr, ident),
         ExprKind::BangChain {
             expr,
             error: Some(error),
+        }
+        | ExprKind::Await {
+            expr,
+            timeout: Some(error),
         } => expr_uses_ident(expr, ident) || expr_uses_ident(error, ident),
         ExprKind::Call {
             callee,
@@ -549,9 +640,20 @@ fn expr_uses_ident(expr: &Expr, ident: &str) -> bool {
             expr_uses_ident(base, ident) || expr_uses_ident(index, ident)
         }
         ExprKind::StructLit { name, fields } => {
-            name.name == ident || fields.iter().any(|field| expr_uses_ident(&field.value, ident))
+            name.name == ident
+                || fields
+                    .iter()
+                    .any(|field| expr_uses_ident(&field.value, ident))
+        }
+        ExprKind::RecordUpdate { base, fields } => {
+            expr_uses_ident(base, ident)
+                || fields
+                    .iter()
+                    .any(|field| expr_uses_ident(&field.value, ident))
+        }
+        ExprKind::ListLit(items) | ExprKind::TupleLit(items) => {
+            items.iter().any(|item| expr_uses_ident(item, ident))
         }
-        ExprKind::ListLit(items) => items.iter().any(|item| expr_uses_ident(item, ident)),
         ExprKind::MapLit(items) => items
             .iter()
             .any(|(key, value)| expr_uses_ident(key, ident) || expr_uses_ident(value, ident)),
@@ -567,12 +669,16 @@ fn expr_uses_ident(expr: &Expr, ident: &str) -> bool {
             else_children,
         } => {
             expr_uses_ident(cond, ident)
-                || then_children.iter().any(|expr| expr_uses_ident(expr, ident))
+                || then_children
+                    .iter()
+                    .any(|expr| expr_uses_ident(expr, ident))
                 || else_if.iter().any(|(cond, children)| {
                     expr_uses_ident(cond, ident)
                         || children.iter().any(|expr| expr_uses_ident(expr, ident))
                 })
-                || else_children.iter().any(|expr| expr_uses_ident(expr, ident))
+                || else_children
+                    .iter()
+                    .any(|expr| expr_uses_ident(expr, ident))
         }
         ExprKind::HtmlFor {
             pat,
@@ -581,23 +687,28 @@ fn expr_uses_ident(expr: &Expr, ident: &str) -> bool {
         } => {
             pattern_uses_ident(pat, ident)
                 || expr_uses_ident(iter, ident)
-                || body_children.iter().any(|expr| expr_uses_ident(expr, ident))
+                || body_children
+                    .iter()
+                    .any(|expr| expr_uses_ident(expr, ident))
         }
     }
 }
 
 fn pattern_uses_ident(pattern: &Pattern, ident: &str) -> bool {
     match &pattern.kind {
-        PatternKind::Wildcard | PatternKind::Literal(_) => false,
+        PatternKind::Wildcard | PatternKind::Literal(_) | PatternKind::Range { .. } => false,
         PatternKind::Ident(name) => name.name == ident,
+        PatternKind::Bind { name, pat } => name.name == ident || pattern_uses_ident(pat, ident),
+        PatternKind::Or(alts) => alts.iter().any(|alt| pattern_uses_ident(alt, ident)),
         PatternKind::EnumVariant { name, args } => {
             name.name == ident || args.iter().any(|arg| pattern_uses_ident(arg, ident))
         }
+        PatternKind::Tuple(items) => items.iter().any(|item| pattern_uses_ident(item, ident)),
         PatternKind::Struct { name, fields } => {
             name.name == ident
-                || fields.iter().any(|field| {
-                    field.name.name == ident || pattern_uses_ident(&field.pat, ident)
-                })
+                || fields
+                    .iter()
+                    .any(|field| field.name.name == ident || pattern_uses_ident(&field.pat, ident))
         }
     }
 }
@@ -10,7 +10,7 @@ pub struct ParamSig {
 #[derive(Clone, Debug, PartialEq)]
 pub struct TypeParamSig {
     pub name: String,
-    pub interface_bound: Option<String>,
+    pub interface_bounds: Vec<String>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -32,6 +32,11 @@ pub enum Ty {
     Html,
     Id,
     Email,
+    Instant,
+    Date,
+    Duration,
+    DateTime,
+    Decimal,
     Error,
     SelfType,
     TypeParam(String),
@@ -41,10 +46,12 @@ pub enum Ty {
     External(String),
     List(Box<Ty>),
     Map(Box<Ty>, Box<Ty>),
+    Tuple(Vec<Ty>),
     Option(Box<Ty>),
     Result(Box<Ty>, Box<Ty>),
     Fn(FnSig),
     Task(Box<Ty>),
+    Channel(Box<Ty>),
     Boxed(Box<Ty>),
     Range(Box<Ty>),
     Refined { base: Box<Ty>, repr: String },
@@ -81,6 +88,11 @@ impl fmt::Display for Ty {
             Ty::Html => write!(f, "Html"),
             Ty::Id => write!(f, "Id"),
             Ty::Email => write!(f, "Email"),
+            Ty::Instant => write!(f, "Instant"),
+            Ty::Date => write!(f, "Date"),
+            Ty::Duration => write!(f, "Duration"),
+            Ty::DateTime => write!(f, "DateTime"),
+            Ty::Decimal => write!(f, "Decimal"),
             Ty::Error => write!(f, "Error"),
             Ty::SelfType => write!(f, "Self"),
             Ty::TypeParam(name) => write!(f, "{name}"),
@@ -90,6 +102,16 @@ impl fmt::Display for Ty {
             Ty::External(name) => write!(f, "{name}"),
             Ty::List(inner) => write!(f, "List<{}>", inner),
             Ty::Map(key, value) => write!(f, "Map<{}, {}>", key, value),
+            Ty::Tuple(items) => {
+                write!(f, "(")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                write!(f, ")")
+            }
             Ty::Option(inner) => write!(f, "{}?", inner),
             Ty::Result(ok, err) => write!(f, "{}!{}", ok, err),
             Ty::Fn(sig) => {
@@ -114,19 +136,21 @@ impl fmt::Display for Ty {
                 write!(f, ") -> {}", sig.ret)?;
                 let mut first = true;
                 for param in &sig.type_params {
-                    if let Some(bound) = &param.interface_bound {
-                        if first {
-                            write!(f, " where ")?;
-                            first = false;
-                        } else {
-                            write!(f, ", ")?;
-                        }
-                        write!(f, "{}: {}", param.name, bound)?;
+                    if param.interface_bounds.is_empty() {
+                        continue;
+                    }
+                    if first {
+                        write!(f, " where ")?;
+                        first = false;
+                    } else {
+                        write!(f, ", ")?;
                     }
+                    write!(f, "{}: {}", param.name, param.interface_bounds.join(" + "))?;
                 }
                 Ok(())
             }
             Ty::Task(inner) => write!(f, "Task<{}>", inner),
+            Ty::Channel(inner) => write!(f, "Channel<{}>", inner),
             Ty::Boxed(inner) => write!(f, "box {}", inner),
             Ty::Range(inner) => write!(f, "Range<{}>", inner),
             Ty::Refined { repr, .. } => write!(f, "{repr}"),
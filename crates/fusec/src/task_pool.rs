@@ -1,11 +1,20 @@
-use std::sync::OnceLock;
+use std::cell::RefCell;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::mpsc::{self, Sender};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, OnceLock};
 use std::thread;
 use std::time::Instant;
 
 type Job = Box<dyn FnOnce() + Send + 'static>;
 
+/// A worker's job queue. Only the thread currently acting as that worker
+/// receives from it; the mutex lets the role move to a replacement thread.
+type WorkerQueue = Arc<Mutex<Receiver<Job>>>;
+
+thread_local! {
+    static WORKER_QUEUE: RefCell<Option<WorkerQueue>> = const { RefCell::new(None) };
+}
+
 pub(crate) fn submit<F>(job: F)
 where
     F: FnOnce() + Send + 'static,
@@ -13,6 +22,39 @@ where
     TaskPool::global().submit(Box::new(job));
 }
 
+/// Runs `wait`, which may block until another task makes progress.
+///
+/// Jobs already queued behind the current one could be the ones `wait` is
+/// waiting for, so when called from a pool worker the worker role (and its
+/// queue) is handed to a fresh thread first. The current thread exits once
+/// its job returns.
+pub(crate) fn blocking<R>(wait: impl FnOnce() -> R) -> R {
+    if let Some(queue) = WORKER_QUEUE.with(|current| current.borrow_mut().take()) {
+        let name = thread::current().name().unwrap_or("fuse-task").to_string();
+        let _ = thread::Builder::new()
+            .name(name)
+            .spawn(move || run_worker(queue));
+    }
+    wait()
+}
+
+fn run_worker(queue: WorkerQueue) {
+    WORKER_QUEUE.with(|current| *current.borrow_mut() = Some(Arc::clone(&queue)));
+    loop {
+        let job = match queue.lock() {
+            Ok(rx) => rx.recv(),
+            Err(_) => return,
+        };
+        let Ok(job) = job else {
+            return;
+        };
+        job();
+        if WORKER_QUEUE.with(|current| current.borrow().is_none()) {
+            return;
+        }
+    }
+}
+
 /// Per-worker-channel round-robin task pool.
 ///
 /// The previous design used a single `Arc<Mutex<Receiver<Job>>>` shared
@@ -45,13 +87,10 @@ impl TaskPool {
         for idx in 0..workers {
             let (tx, rx) = mpsc::channel::<Job>();
             senders.push(tx);
+            let queue = Arc::new(Mutex::new(rx));
             let _ = thread::Builder::new()
                 .name(format!("fuse-task-{idx}"))
-                .spawn(move || {
-                    for job in rx {
-                        job();
-                    }
-                });
+                .spawn(move || run_worker(queue));
         }
         Self {
             senders,
@@ -81,10 +120,10 @@ impl TaskPool {
 #[cfg(test)]
 mod tests {
     use std::collections::HashSet;
-    use std::sync::{Arc, Barrier, mpsc};
+    use std::sync::{Arc, Barrier, Condvar, Mutex, mpsc};
     use std::time::Duration;
 
-    use super::submit;
+    use super::{blocking, submit};
 
     #[test]
     fn runs_multiple_jobs_concurrently() {
@@ -125,4 +164,34 @@ mod tests {
         }
         assert_eq!(finished.len(), 2, "expected two distinct finished jobs");
     }
+
+    #[test]
+    fn blocked_jobs_hand_off_their_worker_queue() {
+        let gate = Arc::new((Mutex::new(false), Condvar::new()));
+        let (tx, rx) = mpsc::channel::<usize>();
+        let jobs = 8;
+
+        for idx in 0..jobs {
+            let gate = Arc::clone(&gate);
+            let tx = tx.clone();
+            submit(move || {
+                let _ = tx.send(idx);
+                blocking(|| {
+                    let (open, changed) = &*gate;
+                    let mut open = open.lock().expect("gate lock");
+                    while !*open {
+                        open = changed.wait(open).expect("gate wait");
+                    }
+                });
+            });
+        }
+
+        for _ in 0..jobs {
+            rx.recv_timeout(Duration::from_secs(2))
+                .expect("expected every job to start while earlier jobs block");
+        }
+        let (open, changed) = &*gate;
+        *open.lock().expect("gate lock") = true;
+        changed.notify_all();
+    }
 }
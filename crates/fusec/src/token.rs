@@ -200,4 +200,6 @@ pub enum Punct {
     Bang,
     QuestionQuestion,
     QuestionBang,
+    Pipe,
+    At,
 }
@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+
+use fusec::concurrency_metrics;
+use fusec::interp::{Interpreter, Value};
+use fusec::native::{NativeVm, compile_registry};
+
+fn as_string(value: Value) -> String {
+    match value {
+        Value::String(text) => text,
+        Value::ResultOk(inner) => as_string(*inner),
+        other => panic!("expected String, got {other:?}"),
+    }
+}
+
+const SRC: &str = r#"
+fn pipeline() -> String:
+  let numbers = channel<Int>(2)
+  let producer = spawn:
+    for i in 1..4:
+      numbers.send(i * 10)
+    numbers.close()
+    0
+  var total = 0
+  for i in 1..4:
+    total = total + (numbers.recv() ?? 0)
+  let _ = await producer
+  let drained = numbers.recv() ?? -1
+  return "${total}|${drained}"
+
+fn fan_in() -> String:
+  let results: Channel<String> = channel<String>(4)
+  let a = spawn:
+    results.send("a")
+    0
+  let b = spawn:
+    results.send("b")
+    0
+  let c = spawn:
+    results.send("c")
+    0
+  let _ = await_all([a, b, c])
+  results.close()
+  var seen = 0
+  for i in 1..3:
+    if (results.recv() ?? "") != "":
+      seen = seen + 1
+  return "${seen}|${results.recv() ?? "closed"}"
+
+fn buffered() -> Channel<Int>:
+  let ch = channel<Int>(3)
+  ch.send(1)
+  ch.send(2)
+  ch.send(3)
+  return ch
+
+fn closed_send() -> String:
+  let ch = channel<Int>(1)
+  ch.close()
+  let drained = ch.recv()
+  ch.send(1)
+  return "${drained}"
+"#;
+
+fn load() -> fusec::ModuleRegistry {
+    let (registry, diags) =
+        fusec::load_program_with_modules(std::path::Path::new("channels.fuse"), SRC);
+    assert!(diags.is_empty(), "unexpected load diagnostics: {diags:?}");
+    let (_analysis, sema_diags) = fusec::sema::analyze_registry(&registry);
+    assert!(
+        sema_diags.is_empty(),
+        "unexpected sema diagnostics: {sema_diags:?}"
+    );
+    registry
+}
+
+fn run_both(registry: &fusec::ModuleRegistry, name: &str) -> String {
+    let mut interp = Interpreter::with_registry(registry);
+    let ast = as_string(
+        interp
+            .call_function_with_named_args(name, &HashMap::new())
+            .expect("ast call failed"),
+    );
+    let native = compile_registry(registry).expect("native lowering failed");
+    let mut native_vm = NativeVm::new(&native);
+    let native = as_string(
+        native_vm
+            .call_function(name, vec![])
+            .expect("native call failed"),
+    );
+    assert_eq!(native, ast, "backend mismatch for {name}");
+    ast
+}
+
+fn sema_messages(src: &str) -> Vec<String> {
+    let (registry, diags) =
+        fusec::load_program_with_modules(std::path::Path::new("channels_bad.fuse"), src);
+    assert!(diags.is_empty(), "unexpected load diagnostics: {diags:?}");
+    let (_analysis, sema_diags) = fusec::sema::analyze_registry(&registry);
+    sema_diags.into_iter().map(|diag| diag.message).collect()
+}
+
+#[test]
+fn producer_consumer_pipeline_works_across_backends() {
+    let registry = load();
+    assert_eq!(run_both(&registry, "pipeline"), "100|-1");
+}
+
+#[test]
+fn spawned_tasks_fan_in_through_one_channel_across_backends() {
+    let registry = load();
+    assert_eq!(run_both(&registry, "fan_in"), "3|closed");
+}
+
+#[test]
+fn buffered_messages_are_reported_as_channel_queue_depth() {
+    let registry = load();
+    let mut interp = Interpreter::with_registry(&registry);
+    let channel = interp
+        .call_function_with_named_args("buffered", &HashMap::new())
+        .expect("ast call failed");
+    assert!(matches!(channel, Value::Channel(_)), "got {channel:?}");
+    assert!(concurrency_metrics::snapshot().channel_queue_depth >= 3);
+}
+
+#[test]
+fn sending_on_a_closed_channel_fails_across_backends() {
+    let registry = load();
+    let mut interp = Interpreter::with_registry(&registry);
+    let ast = interp
+        .call_function_with_named_args("closed_send", &HashMap::new())
+        .expect_err("ast backend should reject the send");
+    assert!(
+        ast.contains("send on closed channel"),
+        "unexpected ast error: {ast}"
+    );
+    let native = compile_registry(&registry).expect("native lowering failed");
+    let mut native_vm = NativeVm::new(&native);
+    let native = native_vm
+        .call_function("closed_send", vec![])
+        .expect_err("native backend should reject the send");
+    assert!(
+        native.contains("send on closed channel"),
+        "unexpected native error: {native}"
+    );
+}
+
+#[test]
+fn sema_allows_channels_inside_spawn_and_checks_element_types() {
+    let messages = sema_messages(
+        r#"
+fn main() -> Int:
+  let ch = channel<Int>(1)
+  let task = spawn:
+    ch.send(1)
+    0
+  let _ = await task
+  return ch.recv() ?? 0
+"#,
+    );
+    assert!(messages.is_empty(), "unexpected sema errors: {messages:?}");
+
+    let messages = sema_messages(
+        r#"
+fn main() -> Int:
+  let ch = channel<Int>(1)
+  ch.send("one")
+  return 0
+"#,
+    );
+    assert!(
+        messages
+            .iter()
+            .any(|message| message.contains("type mismatch")),
+        "expected a send type error, got {messages:?}"
+    );
+
+    let messages = sema_messages(
+        r#"
+fn main() -> Int:
+  let ch = channel<Int>(1)
+  ch.peek()
+  return 0
+"#,
+    );
+    assert!(
+        messages
+            .iter()
+            .any(|message| message.contains("unknown channel method peek")),
+        "expected an unknown method error, got {messages:?}"
+    );
+}
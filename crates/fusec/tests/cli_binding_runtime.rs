@@ -0,0 +1,235 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn write_program(name: &str, contents: &str) -> PathBuf {
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let dir = std::env::temp_dir().join(format!("fuse_cli_{name}_{stamp}"));
+    fs::create_dir_all(&dir).expect("failed to create temp dir");
+    let path = dir.join(format!("{name}.fuse"));
+    fs::write(&path, contents).expect("failed to write temp file");
+    path
+}
+
+fn run(backend: &str, program: &PathBuf, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_fusec"))
+        .arg("--run")
+        .arg("--backend")
+        .arg(backend)
+        .arg(program)
+        .arg("--")
+        .args(args)
+        .output()
+        .expect("failed to run fusec")
+}
+
+fn run_both(program: &PathBuf, args: &[&str]) -> String {
+    let ast = run("ast", program, args);
+    let native = run("native", program, args);
+    assert!(
+        ast.status.success(),
+        "ast stderr: {}",
+        String::from_utf8_lossy(&ast.stderr)
+    );
+    assert!(
+        native.status.success(),
+        "native stderr: {}",
+        String::from_utf8_lossy(&native.stderr)
+    );
+    let ast = String::from_utf8_lossy(&ast.stdout).to_string();
+    assert_eq!(
+        ast,
+        String::from_utf8_lossy(&native.stdout),
+        "backend mismatch"
+    );
+    ast
+}
+
+fn run_err(program: &PathBuf, args: &[&str]) -> String {
+    let output = run("ast", program, args);
+    assert_eq!(output.status.code(), Some(2), "status: {:?}", output.status);
+    String::from_utf8_lossy(&output.stderr).to_string()
+}
+
+const TODO: &str = r#"
+enum Command:
+  Add(String)
+  Done(Int)
+  List
+
+enum Color:
+  Red
+  DarkBlue
+
+## Manage a small todo list.
+fn main(command: Command, tags: List<String>, priority: Int(1..5) = 3, color: Color = Color.Red, verbose: Bool = false):
+  match command:
+    Add(title):
+      print("add ${title} priority=${priority} tags=${tags}")
+    Done(id):
+      print("done ${id}")
+    List:
+      print("list")
+  match color:
+    Red:
+      print("red verbose=${verbose}")
+    DarkBlue:
+      print("dark-blue verbose=${verbose}")
+"#;
+
+#[test]
+fn positionals_variadics_and_enum_commands_bind_across_backends() {
+    let program = write_program("todo", TODO);
+    assert_eq!(
+        run_both(
+            &program,
+            &[
+                "add",
+                "Buy milk",
+                "home",
+                "--priority",
+                "2",
+                "errand",
+                "--verbose"
+            ],
+        ),
+        "add Buy milk priority=2 tags=[home, errand]\nred verbose=true\n"
+    );
+    assert_eq!(
+        run_both(
+            &program,
+            &["done", "4", "--color=dark-blue", "--", "--not-a-flag"]
+        ),
+        "done 4\ndark-blue verbose=false\n"
+    );
+    assert_eq!(
+        run_both(&program, &["list", "--no-verbose", "--color", "DarkBlue"]),
+        "list\ndark-blue verbose=false\n"
+    );
+}
+
+#[test]
+fn cli_binding_reports_bad_arguments() {
+    let program = write_program("todo", TODO);
+    let stderr = run_err(&program, &["remove"]);
+    assert!(
+        stderr.contains("unknown variant remove; expected one of add, done, list"),
+        "stderr: {stderr}"
+    );
+    let stderr = run_err(&program, &["add"]);
+    assert!(
+        stderr.contains("add expects 1 argument(s)"),
+        "stderr: {stderr}"
+    );
+    let stderr = run_err(&program, &["done", "4", "--bogus", "1"]);
+    assert!(
+        stderr.contains("\"code\":\"unknown_flag\""),
+        "stderr: {stderr}"
+    );
+    assert!(stderr.contains("\"path\":\"bogus\""), "stderr: {stderr}");
+    let stderr = run_err(&program, &["done", "4", "--priority", "9"]);
+    assert!(stderr.contains("out of range 1..5"), "stderr: {stderr}");
+    let stderr = run_err(&program, &["list", "--no-priority"]);
+    assert!(stderr.contains("expected Bool flag"), "stderr: {stderr}");
+
+    let single = write_program("greet", "fn main(name: String):\n  print(name)\n");
+    let stderr = run_err(&single, &["Ada", "Lovelace"]);
+    assert!(
+        stderr.contains("unexpected argument: Lovelace"),
+        "stderr: {stderr}"
+    );
+}
+
+#[test]
+fn help_is_generated_from_the_signature() {
+    let program = write_program("todo", TODO);
+    let output = run("ast", &program, &["--help"]);
+    assert!(output.status.success(), "status: {:?}", output.status);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "Manage a small todo list.
+
+Usage: todo <command> [tags...] [options]
+
+Commands:
+  add <String>
+  done <Int>
+  list
+
+Arguments:
+  [tags...]  List<String>
+
+Options:
+  --priority <Int(1..5)>   (default: 3)
+  --color <red|dark-blue>  Color (default: Color.Red)
+  --verbose, --no-verbose  Bool (default: false)
+  -h, --help               Print this help
+"
+    );
+
+    let optional = write_program("limit", "fn main(limit: Int?):\n  print(limit ?? 10)\n");
+    let output = run("ast", &optional, &["--help"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("  --limit <Int>  (optional)\n"),
+        "stdout: {stdout}"
+    );
+}
+
+#[test]
+fn pub_functions_become_subcommands() {
+    let program = write_program(
+        "tool",
+        r#"
+## Greet someone by name.
+## Prints a single line.
+pub fn greet(name: String, excited: Bool = false):
+  if excited:
+    print("Hello, ${name}!")
+  else:
+    print("Hello, ${name}")
+
+## Echo the given words.
+pub fn echo_all(words: List<String>):
+  print("${words}")
+
+fn helper() -> Int:
+  return 1
+"#,
+    );
+    assert_eq!(
+        run_both(&program, &["greet", "Ada", "--excited"]),
+        "Hello, Ada!\n"
+    );
+    assert_eq!(run_both(&program, &["echo-all", "a", "b"]), "[a, b]\n");
+
+    let output = run("ast", &program, &["--help"]);
+    assert!(output.status.success(), "status: {:?}", output.status);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "Usage: tool <command> [options]
+
+Commands:
+  greet     Greet someone by name.
+  echo-all  Echo the given words.
+
+Run `tool <command> --help` for the options of a command.
+"
+    );
+    let output = run("ast", &program, &["greet", "-h"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Usage: tool greet <name> [options]"),
+        "stdout: {stdout}"
+    );
+
+    let stderr = run_err(&program, &["helper"]);
+    assert!(
+        stderr.contains("\"code\":\"unknown_command\""),
+        "stderr: {stderr}"
+    );
+}
@@ -366,3 +366,96 @@ app "demo":
         );
     }
 }
+
+#[test]
+fn config_time_fields_parse_rfc3339_and_iso8601_across_backends() {
+    let program = r#"
+requires time
+
+config Launch:
+  day: Date = time.date_of(time.from_unix_ms(0))
+  window: Duration = time.minutes(30)
+  opens: Instant = time.from_unix_ms(0)
+
+app "demo":
+  print(time.format_date(Launch.day))
+  print(time.format_duration(Launch.window))
+  print(time.format_instant(Launch.opens + Launch.window))
+"#;
+
+    let program_path = write_temp_file("fuse_config_time_fields", "fuse", program);
+    for backend in ["ast", "native"] {
+        let output = run_program_backend(
+            backend,
+            &program_path,
+            &[
+                ("LAUNCH_DAY", "2024-05-01"),
+                ("LAUNCH_WINDOW", "PT90M"),
+                ("LAUNCH_OPENS", "2024-05-01T09:00:00+02:00"),
+            ],
+        );
+        assert!(
+            output.status.success(),
+            "backend={backend} stderr: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(
+            lines,
+            vec!["2024-05-01", "PT1H30M", "2024-05-01T08:30:00Z"],
+            "backend={backend}"
+        );
+
+        let bad = run_program_backend(
+            backend,
+            &program_path,
+            &[
+                ("LAUNCH_DAY", "2024-05-01"),
+                ("LAUNCH_WINDOW", "P1M"),
+                ("LAUNCH_OPENS", "2024-05-01T09:00:00Z"),
+            ],
+        );
+        assert!(!bad.status.success(), "backend={backend} expected failure");
+        let stderr = String::from_utf8_lossy(&bad.stderr);
+        assert!(
+            stderr.contains("invalid Duration"),
+            "backend={backend} stderr: {stderr}"
+        );
+    }
+}
+
+#[test]
+fn config_decimal_fields_parse_exact_text_across_backends() {
+    let program = r#"
+config Billing:
+  fee: Decimal = decimal.from_units(250, 2)
+
+app "demo":
+  print(Billing.fee * 3)
+"#;
+
+    let program_path = write_temp_file("fuse_config_decimal_fields", "fuse", program);
+    for backend in ["ast", "native"] {
+        let output = run_program_backend(backend, &program_path, &[("BILLING_FEE", "0.10")]);
+        assert!(
+            output.status.success(),
+            "backend={backend} stderr: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert_eq!(stdout.trim(), "0.30", "backend={backend}");
+
+        let exponent = run_program_backend(backend, &program_path, &[("BILLING_FEE", "1e-1")]);
+        let stdout = String::from_utf8_lossy(&exponent.stdout);
+        assert_eq!(stdout.trim(), "0.3", "backend={backend}");
+
+        let bad = run_program_backend(backend, &program_path, &[("BILLING_FEE", "1,5")]);
+        assert!(!bad.status.success(), "backend={backend} expected failure");
+        let stderr = String::from_utf8_lossy(&bad.stderr);
+        assert!(
+            stderr.contains("invalid Decimal"),
+            "backend={backend} stderr: {stderr}"
+        );
+    }
+}
@@ -0,0 +1,208 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use fuse_rt::json::{self, JsonValue, decode};
+use fusec::interp::{Interpreter, Value};
+use fusec::native::{NativeVm, compile_registry};
+
+#[path = "support/lsp.rs"]
+mod lsp;
+use lsp::{LspClient, path_to_uri};
+
+fn temp_project_dir(tag: &str) -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    dir.push(format!("fuse_const_alias_{tag}_{nanos}"));
+    dir
+}
+
+fn write_file(path: &Path, contents: &str) {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).expect("create parent directories");
+    }
+    fs::write(path, contents).expect("write source file");
+}
+
+fn as_string(value: Value) -> String {
+    match value {
+        Value::String(text) => text,
+        other => panic!("expected String, got {other:?}"),
+    }
+}
+
+fn get_object<'a>(value: &'a JsonValue, path: &str) -> &'a BTreeMap<String, JsonValue> {
+    let JsonValue::Object(map) = value else {
+        panic!("{path}: expected object, got {value:?}");
+    };
+    map
+}
+
+const LIMITS_SRC: &str = r#"
+const MAX_NAME: Int = 8 * 10
+const PREFIX: String = "id-"
+
+type Name = String(1..MAX_NAME)
+"#;
+
+// ---------------------------------------------------------------------------
+// Test 1: local, imported and qualified constants plus aliases
+// ---------------------------------------------------------------------------
+
+#[test]
+fn constants_and_aliases_run_in_ast_and_native_backends() {
+    let dir = temp_project_dir("runtime");
+    let main_src = r#"
+import Limits from "./limits"
+import { PREFIX } from "./limits"
+
+const SCALE: Int = -(1 + 2)
+const GREETING: String = "hi ${PREFIX}"
+const LABELS: List<String> = ["a", "b"]
+
+type Count = Int
+type Label = String
+type Labels = List<Label>
+
+fn tag(name: Label) -> String:
+  return "${PREFIX}${name}"
+
+fn main() -> String:
+  let total: Count = Limits.MAX_NAME + SCALE
+  let names: Labels = ["ada"]
+  var joined = ""
+  for label in LABELS:
+    joined = joined + label
+  let tagged = tag(names[0])
+  let SCALE = 100
+  return "${GREETING}|${tagged}|${total}|${SCALE}|${joined}"
+"#;
+    let main_path = dir.join("main.fuse");
+    write_file(&dir.join("limits.fuse"), LIMITS_SRC);
+    write_file(&main_path, main_src);
+
+    let (registry, diags) = fusec::load_program_with_modules(&main_path, main_src);
+    assert!(diags.is_empty(), "unexpected load diagnostics: {diags:?}");
+    let (_analysis, sema_diags) = fusec::sema::analyze_registry(&registry);
+    assert!(
+        sema_diags.is_empty(),
+        "unexpected sema diagnostics: {sema_diags:?}"
+    );
+
+    let mut interp = Interpreter::with_registry(&registry);
+    let ast = as_string(
+        interp
+            .call_function_with_named_args("main", &HashMap::new())
+            .expect("ast call failed"),
+    );
+    let native = compile_registry(&registry).expect("native lowering failed");
+    let mut native_vm = NativeVm::new(&native);
+    let native = as_string(
+        native_vm
+            .call_function("main", vec![])
+            .expect("native call failed"),
+    );
+
+    let _ = fs::remove_dir_all(&dir);
+    assert_eq!(ast, "hi id-|id-ada|77|100|ab");
+    assert_eq!(native, ast);
+}
+
+// ---------------------------------------------------------------------------
+// Test 2: aliases of refined types keep their constraints in OpenAPI
+// ---------------------------------------------------------------------------
+
+#[test]
+fn openapi_alias_schema_carries_refined_constraints() {
+    let dir = temp_project_dir("openapi");
+    let main_src = r#"
+import { Name } from "./limits"
+
+type Signup:
+  name: Name
+
+service Api at "":
+  post "/signup" body Signup -> String:
+    return "ok"
+"#;
+    let main_path = dir.join("main.fuse");
+    write_file(&dir.join("limits.fuse"), LIMITS_SRC);
+    write_file(&main_path, main_src);
+
+    let (registry, diags) = fusec::load_program_with_modules(&main_path, main_src);
+    assert!(diags.is_empty(), "unexpected diagnostics: {diags:?}");
+    let openapi_json =
+        fusec::openapi::generate_openapi(&registry).expect("openapi generation failed");
+    let _ = fs::remove_dir_all(&dir);
+
+    let doc = decode(&openapi_json).expect("failed to decode openapi json");
+    let root = get_object(&doc, "root");
+    let components = get_object(root.get("components").expect("components"), "components");
+    let schemas = get_object(components.get("schemas").expect("schemas"), "schemas");
+    let signup = schemas
+        .iter()
+        .find(|(key, _)| key.ends_with("Signup"))
+        .map(|(_, schema)| get_object(schema, "Signup"))
+        .expect("Signup schema");
+    let props = get_object(signup.get("properties").expect("properties"), "properties");
+    let name = get_object(props.get("name").expect("name"), "name");
+    assert_eq!(name.get("type"), Some(&JsonValue::String("string".into())));
+    assert_eq!(name.get("minLength"), Some(&JsonValue::Number(1.0)));
+    assert_eq!(name.get("maxLength"), Some(&JsonValue::Number(80.0)));
+}
+
+// ---------------------------------------------------------------------------
+// Test 3: hover shows const references and alias declarations
+// ---------------------------------------------------------------------------
+
+#[test]
+fn lsp_hover_shows_const_and_alias_declarations() {
+    let dir = temp_project_dir("lsp");
+    write_file(
+        &dir.join("fuse.toml"),
+        "[package]\nentry = \"main.fuse\"\napp = \"Demo\"\n",
+    );
+    let src = r#"const MAX: Int = 10
+type Title = String(1..MAX)
+
+fn clamp(title: Title) -> Int:
+  return MAX
+"#;
+    let main_path = dir.join("main.fuse");
+    let main_uri = path_to_uri(&main_path);
+    write_file(&main_path, src);
+
+    let mut lsp = LspClient::spawn_with_root(&path_to_uri(&dir));
+    lsp.open_document(&main_uri, src, 1);
+    let _ = lsp.wait_diagnostics(&main_uri);
+
+    let hover_at = |lsp: &mut LspClient, line: usize, character: usize| {
+        let mut text_doc = BTreeMap::new();
+        text_doc.insert("uri".to_string(), JsonValue::String(main_uri.clone()));
+        let mut pos = BTreeMap::new();
+        pos.insert("line".to_string(), JsonValue::Number(line as f64));
+        pos.insert("character".to_string(), JsonValue::Number(character as f64));
+        let mut params = BTreeMap::new();
+        params.insert("textDocument".to_string(), JsonValue::Object(text_doc));
+        params.insert("position".to_string(), JsonValue::Object(pos));
+        json::encode(&lsp.request("textDocument/hover", JsonValue::Object(params)))
+    };
+
+    let const_hover = hover_at(&mut lsp, 4, 10);
+    assert!(
+        const_hover.contains("const MAX: Int = 10"),
+        "hover on MAX should show the const declaration: {const_hover}"
+    );
+    let alias_hover = hover_at(&mut lsp, 1, 6);
+    assert!(
+        alias_hover.contains("type Title = String(1..MAX)"),
+        "hover on Title should show the alias target: {alias_hover}"
+    );
+
+    lsp.shutdown();
+    let _ = fs::remove_dir_all(dir);
+}
@@ -0,0 +1,131 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn write_temp_program(name: &str, contents: &str) -> PathBuf {
+    let mut path = std::env::temp_dir();
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    path.push(format!("{name}_{stamp}.fuse"));
+    fs::write(&path, contents).expect("failed to write temp program");
+    path
+}
+
+fn temp_db_url() -> String {
+    let mut path = std::env::temp_dir();
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    path.push(format!("fuse_db_observability_runtime_{stamp}.sqlite"));
+    format!("sqlite://{}", path.display())
+}
+
+fn run_program(backend: &str, path: &PathBuf, envs: &[(&str, &str)]) -> Output {
+    let exe = env!("CARGO_BIN_EXE_fusec");
+    let mut cmd = Command::new(exe);
+    cmd.arg("--run")
+        .arg("--backend")
+        .arg(backend)
+        .arg(path)
+        .env("FUSE_DB_URL", temp_db_url())
+        .env_remove("FUSE_REQUEST_LOG")
+        .env_remove("FUSE_METRICS_HOOK")
+        .env_remove("FUSE_DB_SLOW_QUERY_MS");
+    for (key, value) in envs {
+        cmd.env(key, value);
+    }
+    cmd.output().expect("failed to run fusec --run")
+}
+
+const PROGRAM: &str = r#"
+requires db
+
+fn main():
+  db.exec("create table if not exists notes (id integer primary key, title text)")
+  transaction:
+    db.exec("insert into notes (title) values (?)", ["a"])
+  let rows = db.query("select id from notes where title = 'a'")
+  print(rows)
+
+app "demo":
+  main()
+"#;
+
+#[test]
+fn db_query_events_are_opt_in_in_ast_and_native_backends() {
+    let path = write_temp_program("fuse_db_observability_events", PROGRAM);
+    for backend in ["ast", "native"] {
+        let quiet = run_program(backend, &path, &[]);
+        let stderr = String::from_utf8_lossy(&quiet.stderr);
+        assert!(quiet.status.success(), "backend={backend} stderr={stderr}");
+        assert!(
+            !stderr.contains("db.query"),
+            "backend={backend} stderr={stderr}"
+        );
+
+        let logged = run_program(backend, &path, &[("FUSE_REQUEST_LOG", "structured")]);
+        let stderr = String::from_utf8_lossy(&logged.stderr);
+        assert!(logged.status.success(), "backend={backend} stderr={stderr}");
+        let events: Vec<&str> = stderr
+            .lines()
+            .filter(|line| line.contains("\"event\":\"db.query\""))
+            .collect();
+        assert_eq!(events.len(), 3, "backend={backend} stderr={stderr}");
+        assert!(
+            events[1].contains("\"fingerprint\":\"insert into notes (title) values (?)\"")
+                && events[1].contains("\"params\":1")
+                && events[1].contains("\"rows\":1")
+                && events[1].contains("\"transaction\":true")
+                && events[1].contains("\"request_id\":null"),
+            "backend={backend} event={}",
+            events[1]
+        );
+        assert!(
+            events[2].contains("\"fingerprint\":\"select id from notes where title = ?\"")
+                && events[2].contains("\"transaction\":false"),
+            "backend={backend} event={}",
+            events[2]
+        );
+    }
+}
+
+#[test]
+fn invalid_slow_query_threshold_fails_in_ast_and_native_backends() {
+    let path = write_temp_program("fuse_db_observability_invalid_slow", PROGRAM);
+    for backend in ["ast", "native"] {
+        let output = run_program(backend, &path, &[("FUSE_DB_SLOW_QUERY_MS", "abc")]);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            !output.status.success(),
+            "backend={backend} stderr={stderr}"
+        );
+        assert!(
+            stderr.contains("invalid FUSE_DB_SLOW_QUERY_MS: expected integer milliseconds >= 0"),
+            "backend={backend} stderr={stderr}"
+        );
+    }
+}
+
+#[test]
+fn slow_queries_log_at_warn_in_ast_and_native_backends() {
+    let path = write_temp_program("fuse_db_observability_slow", PROGRAM);
+    for backend in ["ast", "native"] {
+        let output = run_program(backend, &path, &[("FUSE_DB_SLOW_QUERY_MS", "0")]);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(output.status.success(), "backend={backend} stderr={stderr}");
+        let warnings = stderr
+            .lines()
+            .filter(|line| {
+                line.contains("\"level\":\"warn\"")
+                    && line.contains("\"message\":\"slow db query\"")
+                    && line.contains("\"threshold_ms\":0")
+            })
+            .count();
+        assert_eq!(warnings, 3, "backend={backend} stderr={stderr}");
+        assert!(!stderr.contains("\"event\":\"db.query\""));
+    }
+}
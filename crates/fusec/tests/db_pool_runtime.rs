@@ -127,3 +127,86 @@ app "demo":
         );
     }
 }
+
+#[test]
+fn spawned_tasks_share_the_db_pool_all_backends() {
+    let program = r#"
+requires db
+
+fn main():
+  db.exec("create table if not exists items (id integer)")
+  let first = spawn:
+    db.execute("insert into items (id) values (?)", [1]).rows_affected
+  let second = spawn:
+    transaction:
+      db.exec("insert into items (id) values (?)", [2])
+    db.from("items").where("id", "=", 2).count()
+  let inserted = await first
+  let committed = await second
+  let total = db.from("items").count()
+  print("${inserted} ${committed} ${total}")
+
+app "demo":
+  main()
+"#;
+    let path = write_temp_program("fuse_db_pool_spawn", program);
+    for backend in ["ast", "native"] {
+        let exe = env!("CARGO_BIN_EXE_fusec");
+        let output = Command::new(exe)
+            .arg("--run")
+            .arg("--backend")
+            .arg(backend)
+            .arg(&path)
+            .env("FUSE_DB_URL", temp_db_url())
+            .env("FUSE_CONFIG", empty_config_path())
+            .env("FUSE_DB_POOL_SIZE", "2")
+            .env("FUSE_DB_WAL", "true")
+            .env("FUSE_METRICS_HOOK", "stderr")
+            .output()
+            .expect("failed to run fusec");
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(output.status.success(), "backend={backend} stderr={stderr}");
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout).trim(),
+            "1 1 2",
+            "backend={backend}"
+        );
+        assert!(
+            stderr.contains("\"metric\":\"db.pool.snapshot\""),
+            "backend={backend} stderr={stderr}"
+        );
+    }
+}
+
+#[test]
+fn db_busy_timeout_env_rejects_invalid_values_all_backends() {
+    let program = r#"
+requires db
+
+fn main():
+  db.exec("create table if not exists items (id integer)")
+
+app "demo":
+  main()
+"#;
+    let path = write_temp_program("fuse_db_busy_timeout_invalid", program);
+    for backend in ["ast", "native"] {
+        let exe = env!("CARGO_BIN_EXE_fusec");
+        let output = Command::new(exe)
+            .arg("--run")
+            .arg("--backend")
+            .arg(backend)
+            .arg(&path)
+            .env("FUSE_DB_URL", temp_db_url())
+            .env("FUSE_CONFIG", empty_config_path())
+            .env("FUSE_DB_BUSY_TIMEOUT_MS", "-5")
+            .output()
+            .expect("failed to run fusec");
+        assert!(!output.status.success(), "backend={backend}");
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            stderr.contains("invalid FUSE_DB_BUSY_TIMEOUT_MS"),
+            "backend={backend} stderr={stderr}"
+        );
+    }
+}
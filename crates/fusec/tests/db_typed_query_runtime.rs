@@ -261,3 +261,284 @@ fn all_users() -> List<String>:
         "typed query result type must be a declared `type`, found String"
     );
 }
+
+fn join_program_source() -> &'static str {
+    r#"
+requires db
+
+type User:
+  id: Int
+  name: String
+
+type Post:
+  id: Int
+  user_id: Int
+  title: String
+
+type AuthoredPost:
+  title: String
+  author: String
+
+type AuthorPostCount:
+  author: String
+  posts: Int
+
+fn seed():
+  db.exec("create table if not exists users (id integer primary key, name text not null)")
+  db.exec("create table if not exists posts (id integer primary key, user_id integer not null, title text not null)")
+  db.exec("delete from users")
+  db.exec("delete from posts")
+  db.from("users").insert(User(id=1, name="Ada")).exec()
+  db.from("users").insert(User(id=2, name="Bob")).exec()
+  db.from("users").insert(User(id=3, name="Cy")).exec()
+  db.from("posts").insert(Post(id=1, user_id=1, title="a1")).exec()
+  db.from("posts").insert(Post(id=2, user_id=1, title="a2")).exec()
+  db.from("posts").insert(Post(id=3, user_id=2, title="b1")).exec()
+  db.from("posts").insert(Post(id=4, user_id=3, title="c1")).exec()
+
+fn authored_page() -> List<AuthoredPost>:
+  return db.from("posts")
+    .join("users", "posts.user_id", "users.id")
+    .select(["posts.title", "users.name as author"])
+    .where("users.name", "=", "Ada")
+    .or_where("users.name", "=", "Cy")
+    .order_by("users.name", "asc")
+    .order_by("posts.id", "desc")
+    .limit(2)
+    .offset(1)
+    .all<AuthoredPost>()
+
+fn prolific_authors() -> List<AuthorPostCount>:
+  return db.from("posts")
+    .join("users", "posts.user_id", "users.id")
+    .select(["users.name as author"])
+    .aggregate("count", "*", "posts")
+    .group_by(["users.name"])
+    .having("posts", ">", 1)
+    .all<AuthorPostCount>()
+"#
+}
+
+fn expect_string_field(value: &Value, field: &str, expected: &str) {
+    let Value::Struct { fields, .. } = value else {
+        panic!("expected struct, got {value:?}");
+    };
+    match fields.get(field) {
+        Some(Value::String(v)) => assert_eq!(v, expected),
+        other => panic!("expected {field} String({expected}), got {other:?}"),
+    }
+}
+
+fn expect_join_results(page: Value, prolific: Value) {
+    let Value::List(items) = page else {
+        panic!("expected authored page list, got {page:?}");
+    };
+    assert_eq!(items.len(), 2);
+    expect_string_field(&items[0], "title", "a1");
+    expect_string_field(&items[0], "author", "Ada");
+    expect_string_field(&items[1], "title", "c1");
+    expect_string_field(&items[1], "author", "Cy");
+
+    let Value::List(items) = prolific else {
+        panic!("expected prolific author list, got {prolific:?}");
+    };
+    assert_eq!(items.len(), 1);
+    expect_string_field(&items[0], "author", "Ada");
+    let Value::Struct { fields, .. } = &items[0] else {
+        panic!("expected struct");
+    };
+    assert!(matches!(fields.get("posts"), Some(Value::Int(2))));
+}
+
+#[test]
+fn joined_and_grouped_typed_queries_in_ast_and_native_backends() {
+    let _env_guard = ENV_LOCK.lock().expect("lock env guard");
+    let dir = temp_project_dir("join");
+    fs::create_dir_all(&dir).expect("create temp dir");
+    let main_path = dir.join("main.fuse");
+    write_file(&main_path, join_program_source());
+    let registry = load_registry(&main_path);
+
+    unsafe {
+        std::env::set_var("FUSE_DB_URL", temp_db_url("join_ast"));
+    }
+    let mut interp = Interpreter::with_registry(&registry);
+    interp
+        .call_function_with_named_args("seed", &HashMap::new())
+        .expect("seed failed");
+    let page = interp
+        .call_function_with_named_args("authored_page", &HashMap::new())
+        .expect("authored_page failed");
+    let prolific = interp
+        .call_function_with_named_args("prolific_authors", &HashMap::new())
+        .expect("prolific_authors failed");
+    expect_join_results(page, prolific);
+
+    unsafe {
+        std::env::set_var("FUSE_DB_URL", temp_db_url("join_native"));
+    }
+    let native = compile_registry(&registry).expect("native lowering failed");
+    let mut vm = NativeVm::new(&native);
+    vm.call_function("seed", vec![]).expect("seed failed");
+    let page = vm
+        .call_function("authored_page", vec![])
+        .expect("authored_page failed");
+    let prolific = vm
+        .call_function("prolific_authors", vec![])
+        .expect("prolific_authors failed");
+    expect_join_results(page, prolific);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn typed_query_alias_and_aggregate_columns_are_validated() {
+    let diags = analyze_registry_diags(
+        r#"
+requires db
+
+type AuthorPostCount:
+  author: String
+  posts: Int
+
+fn counts() -> List<AuthorPostCount>:
+  return db.from("posts")
+    .join("users", "posts.user_id", "users.id")
+    .select(["users.name"])
+    .aggregate("count", "*", "total")
+    .group_by(["users.name"])
+    .all<AuthorPostCount>()
+"#,
+        "alias_mismatch_diag",
+    );
+    assert_eq!(diags.len(), 1, "unexpected diagnostics: {diags:?}");
+    assert_eq!(
+        diags[0].code.as_deref(),
+        Some("FUSE_TYPED_QUERY_FIELD_MISMATCH")
+    );
+    assert_eq!(
+        diags[0].message,
+        "typed query projection for AuthorPostCount does not match selected columns: missing [author, posts]; unexpected [name, total]; expected fields [author, posts]"
+    );
+}
+
+fn raw_program_source() -> &'static str {
+    r#"
+requires db
+
+type User:
+  id: Int
+  name: String
+
+fn seed():
+  db.exec("create table if not exists users (id integer primary key, name text not null)")
+  db.exec("delete from users")
+  db.exec("insert into users (id, name) values (1, 'Ada'), (2, 'Bob')")
+
+fn raw_users() -> List<User>:
+  return db.query<User>("select id, name from users order by id")
+
+fn raw_user(id: Int) -> User?:
+  return db.one<User>("select id, name from users where id = ?", [id])
+"#
+}
+
+fn expect_raw_results(all: Value, one: Value, missing: Value) {
+    match all {
+        Value::List(items) => {
+            assert_eq!(items.len(), 2);
+            expect_user_struct(&items[0], 1, "Ada");
+            expect_user_struct(&items[1], 2, "Bob");
+        }
+        other => panic!("expected typed user list, got {other:?}"),
+    }
+    expect_user_struct(&one, 2, "Bob");
+    assert!(matches!(missing, Value::Null));
+}
+
+#[test]
+fn typed_raw_sql_results_in_ast_and_native_backends() {
+    let _env_guard = ENV_LOCK.lock().expect("lock env guard");
+    let dir = temp_project_dir("raw");
+    fs::create_dir_all(&dir).expect("create temp dir");
+    let main_path = dir.join("main.fuse");
+    write_file(&main_path, raw_program_source());
+    let registry = load_registry(&main_path);
+
+    unsafe {
+        std::env::set_var("FUSE_DB_URL", temp_db_url("raw_ast"));
+    }
+    let mut interp = Interpreter::with_registry(&registry);
+    interp
+        .call_function_with_named_args("seed", &HashMap::new())
+        .expect("seed failed");
+    let all = interp
+        .call_function_with_named_args("raw_users", &HashMap::new())
+        .expect("raw_users failed");
+    let mut args = HashMap::new();
+    args.insert("id".to_string(), Value::Int(2));
+    let one = interp
+        .call_function_with_named_args("raw_user", &args)
+        .expect("raw_user failed");
+    args.insert("id".to_string(), Value::Int(999));
+    let missing = interp
+        .call_function_with_named_args("raw_user", &args)
+        .expect("raw_user missing failed");
+    expect_raw_results(all, one, missing);
+
+    unsafe {
+        std::env::set_var("FUSE_DB_URL", temp_db_url("raw_native"));
+    }
+    let native = compile_registry(&registry).expect("native lowering failed");
+    let mut vm = NativeVm::new(&native);
+    vm.call_function("seed", vec![]).expect("seed failed");
+    let all = vm
+        .call_function("raw_users", vec![])
+        .expect("raw_users failed");
+    let one = vm
+        .call_function("raw_user", vec![Value::Int(2)])
+        .expect("raw_user failed");
+    let missing = vm
+        .call_function("raw_user", vec![Value::Int(999)])
+        .expect("raw_user missing failed");
+    expect_raw_results(all, one, missing);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn typed_raw_sql_columns_are_checked_against_migration_schema() {
+    let diags = analyze_registry_diags(
+        r#"
+requires db
+
+type User:
+  id: Int
+  name: Int
+
+migration "001_users":
+  db.exec("create table users (id integer primary key, name text not null, email text)")
+
+fn by_email(email: String) -> User?:
+  return db.one<User>("select id, email from users where email = ?", [email])
+
+fn everyone() -> List<User>:
+  return db.query<User>("select id, name from users")
+"#,
+        "raw_columns_diag",
+    );
+    assert_eq!(diags.len(), 2, "unexpected diagnostics: {diags:?}");
+    assert_eq!(
+        diags[0].code.as_deref(),
+        Some("FUSE_TYPED_QUERY_FIELD_MISMATCH")
+    );
+    assert_eq!(
+        diags[0].message,
+        "typed query projection for User does not match selected columns: missing [name]; unexpected [email]; expected fields [id, name]"
+    );
+    assert_eq!(diags[1].code.as_deref(), Some("FUSE_SQL_TYPE_MISMATCH"));
+    assert_eq!(
+        diags[1].message,
+        "column name has sql type TEXT but User.name is Int"
+    );
+}
@@ -98,3 +98,100 @@ app "demo":
         assert_eq!(scalar_string(&row, "name"), "Ava", "backend={backend}");
     }
 }
+
+#[test]
+fn insert_many_and_on_conflict_have_ast_native_parity() {
+    let program = r#"
+requires db
+
+type Tag:
+  slug: String
+  label: String
+  uses: Int
+
+fn main():
+  db.exec("create table if not exists tags (id integer primary key, slug text not null unique, label text not null, uses integer not null)")
+  db.exec("delete from tags")
+  let seed = [Tag(slug="rust", label="Rust", uses=1), Tag(slug="sql", label="SQL", uses=1)]
+  let inserted = db.from("tags").insert_many(seed).execute()
+  print(inserted.rows_affected)
+  let incoming = [Tag(slug="rust", label="Rust lang", uses=5), Tag(slug="go", label="Go", uses=2)]
+  db.from("tags").insert_many(incoming).on_conflict(["slug"]).do_update(["label"]).exec()
+  db.from("tags").insert(Tag(slug="sql", label="Ignored", uses=9)).on_conflict(["slug"]).do_nothing().exec()
+  db.from("tags").insert(Tag(slug="go", label="Golang", uses=3)).on_conflict(["slug"]).do_update().exec()
+
+app "demo":
+  main()
+"#;
+    let path = write_temp_program("fuse_db_insert_many_runtime", program);
+
+    for backend in ["ast", "native"] {
+        let db_url = temp_db_url();
+        let output = run_program(backend, &path, &db_url);
+        assert!(
+            output.status.success(),
+            "backend={backend} expected success, stdout={} stderr={}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout).trim(),
+            "2",
+            "backend={backend}"
+        );
+
+        let verify_db = Db::open_with_pool(&db_url, 1).expect("open verify db");
+        let rows = verify_db
+            .query("select group_concat(slug || ':' || label || ':' || uses || ':' || id, ',') as tags from (select * from tags order by slug)")
+            .expect("query tags");
+        assert_eq!(
+            scalar_string(&rows, "tags"),
+            "go:Golang:3:3,rust:Rust lang:1:1,sql:SQL:1:2",
+            "backend={backend}"
+        );
+    }
+}
+
+#[test]
+fn insert_many_past_the_parameter_limit_has_ast_native_parity() {
+    let program = r#"
+requires db
+
+type Event:
+  id: Int
+  a: Int
+  b: Int
+  c: Int
+
+fn main():
+  db.exec("create table if not exists events (id integer, a integer, b integer, c integer)")
+  db.exec("delete from events")
+  var rows: List<Event> = [Event(id=1, a=2, b=3, c=4)]
+  for i in 0..12:
+    rows = rows + rows
+  let res = db.from("events").insert_many(rows).execute()
+  print(res.rows_affected)
+  let ids = db.from("events").insert_many(rows).returning(["id"]).all()
+  print(db.from("events").count())
+
+app "demo":
+  main()
+"#;
+    let path = write_temp_program("fuse_db_insert_many_chunks", program);
+
+    for backend in ["ast", "native"] {
+        let db_url = temp_db_url();
+        let output = run_program(backend, &path, &db_url);
+        assert!(
+            output.status.success(),
+            "backend={backend} expected success, stdout={} stderr={}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout).trim(),
+            "8192\n16384",
+            "backend={backend}"
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn write_temp_program(name: &str, contents: &str) -> PathBuf {
+    let mut path = std::env::temp_dir();
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    path.push(format!("{name}_{stamp}.fuse"));
+    fs::write(&path, contents).expect("failed to write temp program");
+    path
+}
+
+fn temp_db_url() -> String {
+    let mut path = std::env::temp_dir();
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    path.push(format!("fuse_db_write_result_runtime_{stamp}.sqlite"));
+    format!("sqlite://{}", path.display())
+}
+
+fn run_program(backend: &str, path: &PathBuf, db_url: &str) -> Output {
+    let exe = env!("CARGO_BIN_EXE_fusec");
+    Command::new(exe)
+        .arg("--run")
+        .arg("--backend")
+        .arg(backend)
+        .arg(path)
+        .env("FUSE_DB_URL", db_url)
+        .output()
+        .expect("failed to run fusec --run")
+}
+
+#[test]
+fn write_results_and_returning_have_ast_native_parity() {
+    let program = r#"
+requires db
+
+type Note:
+  title: String
+
+type SavedNote:
+  id: Int
+  title: String
+
+fn main():
+  db.exec("create table notes (id integer primary key, title text not null)")
+  let first = db.execute("insert into notes (title) values (?)", ["Ada"])
+  print("insert ${first.rows_affected} ${first.last_insert_id ?? 0}")
+  let missed = db.from("notes").update("title", "Bea").where("id", "=", 42).execute()
+  print("update ${missed.rows_affected} ${missed.last_insert_id ?? 0}")
+  let saved = db.from("notes").insert(Note(title="Cy")).returning(["id", "title"]).one<SavedNote>()
+  print("returning ${saved?.id ?? 0} ${saved?.title ?? "none"}")
+  let removed = db.from("notes").delete().where("id", "<=", 2).execute()
+  print("delete ${removed.rows_affected}")
+
+app "demo":
+  main()
+"#;
+    let path = write_temp_program("fuse_db_write_result_runtime", program);
+
+    for backend in ["ast", "native"] {
+        let db_url = temp_db_url();
+        let output = run_program(backend, &path, &db_url);
+        assert!(
+            output.status.success(),
+            "backend={backend} expected success, stdout={} stderr={}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert_eq!(
+            stdout.lines().collect::<Vec<_>>(),
+            vec!["insert 1 1", "update 0 0", "returning 2 Cy", "delete 2"],
+            "backend={backend}"
+        );
+    }
+}
@@ -0,0 +1,334 @@
+use std::collections::{BTreeMap, HashMap};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use fuse_rt::json::{JsonValue, decode};
+use fusec::interp::{Interpreter, Value};
+use fusec::native::{NativeVm, compile_registry};
+
+mod support;
+use support::http::send_http_request_status_body_with_retry;
+use support::net::{find_free_port, skip_if_loopback_unavailable};
+
+static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+fn as_string(value: Value) -> String {
+    match value {
+        Value::String(text) => text,
+        Value::ResultOk(inner) => as_string(*inner),
+        other => panic!("expected String, got {other:?}"),
+    }
+}
+
+const SRC: &str = r#"
+requires db
+
+type PriceError:
+  message: String
+
+type Product:
+  id: Int
+  price: Decimal(0..10000)
+
+fn totals() -> String!PriceError:
+  let price = decimal.parse("19.99") ?! PriceError(message="bad price")
+  let rate = decimal.parse("0.0825") ?! PriceError(message="bad rate")
+  let subtotal = price * 3
+  let tax = decimal.round(subtotal * rate, 2)
+  let total = subtotal + tax
+  let tenth = decimal.from_units(1, 1)
+  let sum = tenth + tenth + tenth
+  var cheaper = "no"
+  if decimal.from_units(1999, 2) < price + 1:
+    cheaper = "yes"
+  return "${subtotal}|${tax}|${total}|${total / 4}|${-total}|${sum}|${sum == decimal.from_units(3, 1)}|${cheaper}|${decimal.scale(total)}|${total % 10}"
+
+fn rounding() -> String!PriceError:
+  let value = decimal.parse("2.345") ?! PriceError(message="bad value")
+  return "${decimal.round(value, 2)}|${decimal.round(value, 2, "half_up")}|${decimal.round(value, 2, "down")}|${decimal.round(-value, 2, "floor")}|${decimal.round(value, 0, "ceiling")}|${decimal.round(decimal.from_int(5), 2)}"
+
+fn bad_parse() -> String:
+  match decimal.parse("12.3.4"):
+    Ok(value):
+      return "${value}"
+    Err(_):
+      return "invalid"
+
+fn product_ok() -> String:
+  let product = Product(id=1, price=decimal.from_units(1999, 2))
+  return "${product.price}"
+
+fn product_out_of_range() -> String:
+  let product = Product(id=1, price=decimal.from_int(20000))
+  return "${product.price}"
+
+fn stored() -> String:
+  db.exec("create table if not exists products (id integer primary key, price text not null)")
+  db.exec("delete from products")
+  db.from("products").insert(Product(id=1, price=decimal.from_units(1999, 2))).exec()
+  let fallback = Product(id=0, price=decimal.from_int(0))
+  let product = db.from("products").select(["id", "price"]).one<Product>() ?? fallback
+  return "${product.price + 1}"
+"#;
+
+fn load() -> fusec::ModuleRegistry {
+    let (registry, diags) =
+        fusec::load_program_with_modules(std::path::Path::new("decimal.fuse"), SRC);
+    assert!(diags.is_empty(), "unexpected load diagnostics: {diags:?}");
+    let (_analysis, sema_diags) = fusec::sema::analyze_registry(&registry);
+    assert!(
+        sema_diags.is_empty(),
+        "unexpected sema diagnostics: {sema_diags:?}"
+    );
+    registry
+}
+
+fn run_both(registry: &fusec::ModuleRegistry, name: &str) -> String {
+    let mut interp = Interpreter::with_registry(registry);
+    let ast = as_string(
+        interp
+            .call_function_with_named_args(name, &HashMap::new())
+            .expect("ast call failed"),
+    );
+    let native = compile_registry(registry).expect("native lowering failed");
+    let mut native_vm = NativeVm::new(&native);
+    let native = as_string(
+        native_vm
+            .call_function(name, vec![])
+            .expect("native call failed"),
+    );
+    assert_eq!(native, ast, "backend mismatch for {name}");
+    ast
+}
+
+#[test]
+fn decimal_arithmetic_is_exact_across_backends() {
+    let registry = load();
+    assert_eq!(
+        run_both(&registry, "totals"),
+        "59.97|4.95|64.92|16.23|-64.92|0.3|true|yes|2|4.92"
+    );
+    assert_eq!(run_both(&registry, "bad_parse"), "invalid");
+}
+
+#[test]
+fn decimal_rounding_modes_match_across_backends() {
+    let registry = load();
+    assert_eq!(
+        run_both(&registry, "rounding"),
+        "2.34|2.35|2.34|-2.35|3|5.00"
+    );
+}
+
+#[test]
+fn decimal_refinement_ranges_are_enforced() {
+    let registry = load();
+    assert_eq!(run_both(&registry, "product_ok"), "19.99");
+    let mut interp = Interpreter::with_registry(&registry);
+    assert!(
+        interp
+            .call_function_with_named_args("product_out_of_range", &HashMap::new())
+            .is_err(),
+        "ast backend should reject a price outside the range"
+    );
+    let native = compile_registry(&registry).expect("native lowering failed");
+    let mut native_vm = NativeVm::new(&native);
+    assert!(
+        native_vm
+            .call_function("product_out_of_range", vec![])
+            .is_err(),
+        "native backend should reject a price outside the range"
+    );
+}
+
+#[test]
+fn decimal_columns_round_trip_through_sqlite_text() {
+    let _env_guard = ENV_LOCK.lock().expect("lock env guard");
+    let registry = load();
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let db_path = std::env::temp_dir().join(format!("fuse_decimal_{stamp}.sqlite"));
+    unsafe {
+        std::env::set_var("FUSE_DB_URL", format!("sqlite://{}", db_path.display()));
+    }
+    assert_eq!(run_both(&registry, "stored"), "20.99");
+    let _ = std::fs::remove_file(&db_path);
+}
+
+#[test]
+fn sema_rejects_mixing_decimal_and_float() {
+    let src = r#"
+fn main(price: Decimal, rate: Float) -> Decimal:
+  return price * rate
+"#;
+    let (registry, diags) =
+        fusec::load_program_with_modules(std::path::Path::new("decimal_bad.fuse"), src);
+    assert!(diags.is_empty(), "unexpected load diagnostics: {diags:?}");
+    let (_analysis, sema_diags) = fusec::sema::analyze_registry(&registry);
+    assert!(
+        sema_diags
+            .iter()
+            .any(|diag| diag.message.contains("Decimal cannot be mixed with Float")),
+        "expected a Decimal/Float error, got {sema_diags:?}"
+    );
+}
+
+const SERVICE_SRC: &str = r#"
+requires network
+
+config App:
+  port: Int = env_int("PORT") ?? 3000
+
+type Quote:
+  price: Decimal(0..10000)
+  qty: Int
+
+type Total:
+  total: Decimal
+
+service Quotes at "/api":
+  post "/total" body Quote -> Total:
+    return Total(total=body.price * body.qty)
+
+app "quotes":
+  serve(App.port)
+"#;
+
+fn get_object<'a>(value: &'a JsonValue, path: &str) -> &'a BTreeMap<String, JsonValue> {
+    let JsonValue::Object(map) = value else {
+        panic!("{path}: expected object, got {value:?}");
+    };
+    map
+}
+
+#[test]
+fn openapi_describes_decimal_as_formatted_string() {
+    let (registry, diags) =
+        fusec::load_program_with_modules(std::path::Path::new("quotes.fuse"), SERVICE_SRC);
+    assert!(diags.is_empty(), "unexpected diagnostics: {diags:?}");
+    let openapi_json =
+        fusec::openapi::generate_openapi(&registry).expect("openapi generation failed");
+    let doc = decode(&openapi_json).expect("failed to decode openapi json");
+    let root = get_object(&doc, "root");
+    let components = get_object(root.get("components").expect("components"), "components");
+    let schemas = get_object(components.get("schemas").expect("schemas"), "schemas");
+    let quote = schemas
+        .iter()
+        .find(|(key, _)| key.ends_with("Quote"))
+        .map(|(_, schema)| get_object(schema, "Quote"))
+        .expect("Quote schema");
+    let props = get_object(quote.get("properties").expect("properties"), "properties");
+    let price = get_object(props.get("price").expect("price"), "price");
+    assert_eq!(price.get("type"), Some(&JsonValue::String("string".into())));
+    assert_eq!(
+        price.get("format"),
+        Some(&JsonValue::String("decimal".into()))
+    );
+    assert_eq!(price.get("x-minimum"), Some(&JsonValue::String("0".into())));
+    assert_eq!(
+        price.get("x-maximum"),
+        Some(&JsonValue::String("10000".into()))
+    );
+    assert_eq!(price.get("minimum"), None);
+    assert_eq!(price.get("maximum"), None);
+}
+
+#[test]
+fn openapi_keeps_fractional_decimal_bounds_exact() {
+    let src = r#"
+requires network
+
+type Rate:
+  value: Decimal(0.05..99.95)
+
+service Rates at "/api":
+  post "/rate" body Rate -> Rate:
+    return body
+
+app "rates":
+  serve(3000)
+"#;
+    let (registry, diags) =
+        fusec::load_program_with_modules(std::path::Path::new("rates.fuse"), src);
+    assert!(diags.is_empty(), "unexpected diagnostics: {diags:?}");
+    let openapi_json =
+        fusec::openapi::generate_openapi(&registry).expect("openapi generation failed");
+    let doc = decode(&openapi_json).expect("failed to decode openapi json");
+    let root = get_object(&doc, "root");
+    let components = get_object(root.get("components").expect("components"), "components");
+    let schemas = get_object(components.get("schemas").expect("schemas"), "schemas");
+    let rate = schemas
+        .iter()
+        .find(|(key, _)| key.ends_with("Rate"))
+        .map(|(_, schema)| get_object(schema, "Rate"))
+        .expect("Rate schema");
+    let props = get_object(rate.get("properties").expect("properties"), "properties");
+    let value = get_object(props.get("value").expect("value"), "value");
+    assert_eq!(
+        value.get("x-minimum"),
+        Some(&JsonValue::String("0.05".into()))
+    );
+    assert_eq!(
+        value.get("x-maximum"),
+        Some(&JsonValue::String("99.95".into()))
+    );
+}
+
+#[test]
+fn http_bodies_accept_decimal_strings_and_numbers_across_backends() {
+    if skip_if_loopback_unavailable(
+        "http_bodies_accept_decimal_strings_and_numbers_across_backends",
+    ) {
+        return;
+    }
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let path = std::env::temp_dir().join(format!("fuse_decimal_http_{stamp}.fuse"));
+    std::fs::write(&path, SERVICE_SRC).expect("failed to write program");
+    let cases = [
+        (r#"{"price":"19.99","qty":3}"#, 200, r#"{"total":"59.97"}"#),
+        (r#"{"price":0.1,"qty":3}"#, 200, r#"{"total":"0.3"}"#),
+        (r#"{"price":".5","qty":3}"#, 200, r#"{"total":"1.5"}"#),
+        (
+            r#"{"price":"1234.567890123456789","qty":1}"#,
+            200,
+            r#"{"total":"1234.567890123456789"}"#,
+        ),
+        (
+            r#"{"price":1234.567890123456789,"qty":1}"#,
+            400,
+            r#"{"error":{"code":"validation_error","fields":[{"code":"invalid_value","message":"invalid Decimal: JSON number has more than 15 significant digits; send the decimal as a string","path":"body.price"}],"message":"validation failed"}}"#,
+        ),
+    ];
+    for backend in ["ast", "native"] {
+        for (body, expected_status, expected) in cases {
+            let port = find_free_port();
+            let mut child = Command::new(env!("CARGO_BIN_EXE_fusec"))
+                .arg("--run")
+                .arg("--backend")
+                .arg(backend)
+                .arg(&path)
+                .env("PORT", port.to_string())
+                .env("FUSE_MAX_REQUESTS", "1")
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .expect("failed to start server");
+            let request = format!(
+                "POST /api/total HTTP/1.1\r\nHost: 127.0.0.1:{port}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            let (status, response) = send_http_request_status_body_with_retry(port, &request);
+            let _ = child.wait();
+            assert_eq!(status, expected_status, "backend={backend} body={response}");
+            assert_eq!(response, expected, "backend={backend}");
+        }
+    }
+    let _ = std::fs::remove_file(&path);
+}
@@ -1,11 +1,17 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use fuse_rt::json::{JsonValue, decode};
 use fusec::interp::{Interpreter, Value};
 use fusec::native::{NativeVm, compile_registry};
 
+mod support;
+use support::http::send_http_request_status_body_with_retry;
+use support::net::{find_free_port, skip_if_loopback_unavailable};
+
 fn temp_project_dir(tag: &str) -> PathBuf {
     let mut dir = std::env::temp_dir();
     let nanos = SystemTime::now()
@@ -23,6 +29,22 @@ fn write_file(path: &Path, contents: &str) {
     fs::write(path, contents).expect("write source file");
 }
 
+fn run_program(backend: &str, path: &Path, db_url: Option<&str>) -> Output {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_fusec"));
+    command.arg("--run").arg("--backend").arg(backend).arg(path);
+    if let Some(db_url) = db_url {
+        command.env("FUSE_DB_URL", db_url);
+    }
+    command.output().expect("failed to run fusec")
+}
+
+fn get_object<'a>(value: &'a JsonValue, path: &str) -> &'a BTreeMap<String, JsonValue> {
+    let JsonValue::Object(map) = value else {
+        panic!("{path}: expected object, got {value:?}");
+    };
+    map
+}
+
 fn as_string(value: Value) -> String {
     match value {
         Value::String(text) => text,
@@ -231,3 +253,482 @@ fn main() -> String:
 
     let _ = fs::remove_dir_all(&dir);
 }
+
+// ---------------------------------------------------------------------------
+// Test 5: generic type and enum declarations, including an imported one
+// ---------------------------------------------------------------------------
+
+#[test]
+fn generic_type_and_enum_instances_run_in_ast_and_native_backends() {
+    let dir = temp_project_dir("types");
+    let main_path = dir.join("main.fuse");
+
+    write_file(
+        &dir.join("paging.fuse"),
+        r#"
+type Meta:
+  count: Int
+
+type Page<T>:
+  items: List<T>
+  meta: Meta
+"#,
+    );
+
+    write_file(
+        &main_path,
+        r#"
+import Paging from "./paging"
+import { Meta } from "./paging"
+
+type Note:
+  title: String
+
+enum Outcome<T, E>:
+  Success(T)
+  Failure(E)
+
+fn first(page: Paging.Page<Note>) -> Outcome<Note, String>:
+  if page.meta.count == 0:
+    return Outcome.Failure<Note, String>("empty")
+  return Outcome.Success<Note, String>(page.items[0])
+
+fn describe(result: Outcome<Note, String>) -> String:
+  match result:
+    Success(note):
+      return "ok:" + note.title
+    Failure(message):
+      return "error:" + message
+  return ""
+
+fn main() -> String:
+  let empty = Paging.Page<Note>(items=[], meta=Meta(count=0))
+  let full = Paging.Page<Note>(items=[Note(title="a")], meta=Meta(count=1))
+  return describe(first(empty)) + "|" + describe(first(full))
+"#,
+    );
+
+    let src = fs::read_to_string(&main_path).expect("read source");
+    let (registry, diags) = fusec::load_program_with_modules(&main_path, &src);
+    assert!(diags.is_empty(), "unexpected load diagnostics: {diags:?}");
+    let (_analysis, sema_diags) = fusec::sema::analyze_registry(&registry);
+    assert!(
+        sema_diags.is_empty(),
+        "unexpected sema diagnostics: {sema_diags:?}"
+    );
+
+    let mut interp = Interpreter::with_registry(&registry);
+    let ast_value = interp
+        .call_function_with_named_args("main", &HashMap::new())
+        .expect("ast call failed");
+
+    let native = compile_registry(&registry).expect("native lowering failed");
+    let mut native_vm = NativeVm::new(&native);
+    let native_value = native_vm
+        .call_function("main", vec![])
+        .expect("native call failed");
+
+    assert_eq!(as_string(ast_value), "error:empty|ok:a");
+    assert_eq!(as_string(native_value), "error:empty|ok:a");
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn generic_constructors_take_type_args_from_the_expected_type() {
+    let src = r#"
+type Page<T>:
+  items: List<T>
+  total: Int
+
+enum Outcome<T, E>:
+  Success(T)
+  Failure(E)
+  Empty
+
+fn first(page: Page<Int>) -> Outcome<Int, String>:
+  if page.total == 0:
+    return Outcome.Empty
+  if page.total > 5:
+    return Outcome.Failure("too many")
+  return Outcome.Success(page.items[0])
+
+fn describe(result: Outcome<Int, String>) -> String:
+  match result:
+    Success(value):
+      return "ok:${value}"
+    Failure(message):
+      return "error:" + message
+    Empty:
+      return "empty"
+  return ""
+
+fn main() -> String:
+  let page: Page<Int> = Page(items=[7, 8], total=2)
+  let empty: Page<Int> = Page(items=[], total=0)
+  var fallback: Outcome<Int, String>? = Outcome.Failure("unset")
+  return describe(first(page)) + "|" + describe(first(empty)) + "|" + describe(fallback ?? first(page))
+"#;
+    let (registry, diags) = fusec::load_program_with_modules(Path::new("expected.fuse"), src);
+    assert!(diags.is_empty(), "unexpected load diagnostics: {diags:?}");
+    let (_analysis, sema_diags) = fusec::sema::analyze_registry(&registry);
+    assert!(
+        sema_diags.is_empty(),
+        "unexpected sema diagnostics: {sema_diags:?}"
+    );
+
+    let mut interp = Interpreter::with_registry(&registry);
+    let ast_value = interp
+        .call_function_with_named_args("main", &HashMap::new())
+        .expect("ast call failed");
+    let native = compile_registry(&registry).expect("native lowering failed");
+    let mut native_vm = NativeVm::new(&native);
+    let native_value = native_vm
+        .call_function("main", vec![])
+        .expect("native call failed");
+
+    assert_eq!(as_string(ast_value), "ok:7|empty|error:unset");
+    assert_eq!(as_string(native_value), "ok:7|empty|error:unset");
+}
+
+// ---------------------------------------------------------------------------
+// Test 6: default members, interface inheritance and multiple bounds
+// ---------------------------------------------------------------------------
+
+#[test]
+fn interface_defaults_and_inheritance_run_in_ast_and_native_backends() {
+    let dir = temp_project_dir("defaults");
+    let main_path = dir.join("main.fuse");
+
+    write_file(
+        &dir.join("codec.fuse"),
+        r#"
+interface Encode:
+  fn encode() -> String
+
+  fn quoted() -> String:
+    return "'" + self.encode() + "'"
+
+interface Codec extends Encode:
+  fn decode(text: String) -> Self
+
+  fn twin() -> String:
+    return Self.decode(self.encode() + self.encode()).encode()
+"#,
+    );
+
+    write_file(
+        &main_path,
+        r##"
+import { Codec, Encode } from "./codec"
+
+interface Tagged:
+  fn tag() -> String
+
+type Note:
+  text: String
+
+type Label:
+  name: String
+
+impl Codec for Note:
+  fn encode() -> String:
+    return self.text
+
+  fn decode(text: String) -> Self:
+    return Note(text=text)
+
+impl Codec for Label:
+  fn encode() -> String:
+    return "#" + self.name
+
+  fn decode(text: String) -> Self:
+    return Label(name=text)
+
+  fn quoted() -> String:
+    return "[" + self.name + "]"
+
+impl Tagged for Note:
+  fn tag() -> String:
+    return "note " + self.text
+
+fn show<T>(value: T) -> String where T: Encode + Tagged:
+  return value.tag() + ":" + value.quoted()
+
+fn main() -> String:
+  let note = Note(text="ab")
+  let label = Label(name="x")
+  return show<Note>(note) + "|" + note.twin() + "|" + label.quoted() + "|" + label.twin()
+"##,
+    );
+
+    let src = fs::read_to_string(&main_path).expect("read source");
+    let (registry, diags) = fusec::load_program_with_modules(&main_path, &src);
+    assert!(diags.is_empty(), "unexpected load diagnostics: {diags:?}");
+    let (_analysis, sema_diags) = fusec::sema::analyze_registry(&registry);
+    assert!(
+        sema_diags.is_empty(),
+        "unexpected sema diagnostics: {sema_diags:?}"
+    );
+
+    let mut interp = Interpreter::with_registry(&registry);
+    let ast_value = interp
+        .call_function_with_named_args("main", &HashMap::new())
+        .expect("ast call failed");
+
+    let native = compile_registry(&registry).expect("native lowering failed");
+    let mut native_vm = NativeVm::new(&native);
+    let native_value = native_vm
+        .call_function("main", vec![])
+        .expect("native call failed");
+
+    let expected = "note ab:'ab'|abab|[x]|##x#x";
+    assert_eq!(as_string(ast_value), expected);
+    assert_eq!(as_string(native_value), expected);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+// ---------------------------------------------------------------------------
+// Test 7: instances are keyed by their full type arguments
+// ---------------------------------------------------------------------------
+
+#[test]
+fn generic_instances_with_similar_arguments_stay_distinct() {
+    let dir = temp_project_dir("distinct");
+    let main_path = dir.join("main.fuse");
+    write_file(
+        &main_path,
+        r#"
+type A_B:
+  v: Int
+
+type A:
+  v: Int
+
+type B_C:
+  v: Int
+
+type C:
+  v: Int
+
+type Pair<L, R>:
+  left: L
+  right: R
+
+type Box<T>:
+  value: T
+
+fn pairs() -> String:
+  let a = Pair<A_B, C>(left=A_B(v=1), right=C(v=2))
+  let b = Pair<A, B_C>(left=A(v=3), right=B_C(v=4))
+  return "${a.left.v}${a.right.v}${b.left.v}${b.right.v}"
+
+fn main():
+  print(pairs())
+  let loose = Box<String>(value="too long")
+  print(loose.value)
+  let short = Box<String(1..5)>(value="too long")
+  print(short.value)
+
+app "distinct":
+  main()
+"#,
+    );
+
+    for backend in ["ast", "native"] {
+        let output = run_program(backend, &main_path, None);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert_eq!(
+            stdout, "1234\ntoo long\n",
+            "backend={backend} stderr={stderr}"
+        );
+        assert!(!output.status.success(), "backend={backend}");
+        assert!(
+            stderr.contains("length 8 out of range 1..5"),
+            "backend={backend} stderr={stderr}"
+        );
+    }
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+// ---------------------------------------------------------------------------
+// Test 8: generic instances in typed queries and json.encode
+// ---------------------------------------------------------------------------
+
+#[test]
+fn generic_instances_decode_typed_query_rows_and_encode_json() {
+    let dir = temp_project_dir("typed_query");
+    let main_path = dir.join("main.fuse");
+    write_file(
+        &main_path,
+        r#"
+requires db
+
+type Note:
+  title: String
+
+type Row<T>:
+  id: Int
+  value: T
+
+type Page<T>:
+  items: List<T>
+  total: Int
+
+fn main():
+  db.exec("create table if not exists items (id integer primary key, value text not null)")
+  db.exec("delete from items")
+  db.exec("insert into items (id, value) values (1, 'a'), (2, 'b')")
+  let rows = db.query<Row<String>>("select id, value from items order by id")
+  let last = db.from("items").select(["id", "value"]).order_by("id", "desc").one<Row<String>>()
+  match last:
+    Some(row):
+      print("${rows[0].value}${rows[1].value}|${row.id}")
+    None:
+      print("none")
+  print(json.encode(Page<Note>(items=[Note(title="x")], total=1)))
+
+app "typed_query":
+  main()
+"#,
+    );
+
+    for backend in ["ast", "native"] {
+        let db_path = dir.join(format!("{backend}.sqlite"));
+        let db_url = format!("sqlite://{}", db_path.display());
+        let output = run_program(backend, &main_path, Some(&db_url));
+        assert!(
+            output.status.success(),
+            "backend={backend} stderr={}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "ab|2\n{\"items\":[{\"title\":\"x\"}],\"total\":1}\n",
+            "backend={backend}"
+        );
+    }
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+// ---------------------------------------------------------------------------
+// Test 9: generic instances as HTTP bodies and OpenAPI schemas
+// ---------------------------------------------------------------------------
+
+const PAGE_SERVICE: &str = r#"
+requires network
+
+config App:
+  port: Int = env_int("PORT") ?? 3000
+
+type Note:
+  title: String
+
+type User:
+  name: String
+
+type Page<T>:
+  items: List<T>
+  total: Int
+
+type Box<T>:
+  value: T
+
+service Api at "/api":
+  post "/notes" body Page<Note> -> Page<Note>:
+    return Page<Note>(items=body.items, total=body.total + 1)
+  get "/users" -> Page<User>:
+    return Page<User>(items=[User(name="ada")], total=1)
+  post "/short" body Box<String(1..5)> -> String:
+    return body.value
+
+app "pages":
+  serve(App.port)
+"#;
+
+#[test]
+fn openapi_emits_one_schema_per_generic_instance() {
+    let (registry, diags) = fusec::load_program_with_modules(Path::new("pages.fuse"), PAGE_SERVICE);
+    assert!(diags.is_empty(), "unexpected diagnostics: {diags:?}");
+    let openapi_json =
+        fusec::openapi::generate_openapi(&registry).expect("openapi generation failed");
+    let doc = decode(&openapi_json).expect("failed to decode openapi json");
+    let root = get_object(&doc, "root");
+    let components = get_object(root.get("components").expect("components"), "components");
+    let schemas = get_object(components.get("schemas").expect("schemas"), "schemas");
+    let items_ref = |instance: &str| {
+        let schema = schemas
+            .iter()
+            .find(|(key, _)| key.ends_with(instance))
+            .map(|(_, schema)| get_object(schema, instance))
+            .unwrap_or_else(|| panic!("{instance} schema in {openapi_json}"));
+        let props = get_object(schema.get("properties").expect("properties"), "properties");
+        let items = get_object(props.get("items").expect("items"), "items");
+        let inner = get_object(items.get("items").expect("items.items"), "items.items");
+        match inner.get("$ref") {
+            Some(JsonValue::String(reference)) => reference.clone(),
+            other => panic!("{instance}: expected $ref, got {other:?}"),
+        }
+    };
+    assert!(items_ref("Page_Note").ends_with("Note"));
+    assert!(items_ref("Page_User").ends_with("User"));
+    assert!(
+        !schemas.keys().any(|key| key.ends_with("Page")),
+        "the generic template has no schema: {openapi_json}"
+    );
+}
+
+#[test]
+fn generic_instance_http_bodies_round_trip_across_backends() {
+    if skip_if_loopback_unavailable("generic_instance_http_bodies_round_trip_across_backends") {
+        return;
+    }
+    let dir = temp_project_dir("http");
+    let main_path = dir.join("pages.fuse");
+    write_file(&main_path, PAGE_SERVICE);
+    let cases = [
+        (
+            "/api/notes",
+            r#"{"items":[{"title":"a"}],"total":1}"#,
+            200,
+            r#"{"items":[{"title":"a"}],"total":2}"#,
+        ),
+        ("/api/short", r#"{"value":"abc"}"#, 200, r#""abc""#),
+        (
+            "/api/short",
+            r#"{"value":"too long"}"#,
+            400,
+            r#"{"error":{"code":"validation_error","fields":[{"code":"invalid_value","message":"length 8 out of range 1..5","path":"body.value"}],"message":"validation failed"}}"#,
+        ),
+    ];
+    for backend in ["ast", "native"] {
+        for (route, body, expected_status, expected) in cases {
+            let port = find_free_port();
+            let mut child = Command::new(env!("CARGO_BIN_EXE_fusec"))
+                .arg("--run")
+                .arg("--backend")
+                .arg(backend)
+                .arg(&main_path)
+                .env("PORT", port.to_string())
+                .env("FUSE_MAX_REQUESTS", "1")
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .expect("failed to start server");
+            let request = format!(
+                "POST {route} HTTP/1.1\r\nHost: 127.0.0.1:{port}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            let (status, response) = send_http_request_status_body_with_retry(port, &request);
+            let _ = child.wait();
+            assert_eq!(status, expected_status, "backend={backend} body={response}");
+            assert_eq!(response, expected, "backend={backend}");
+        }
+    }
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -107,24 +107,193 @@ fn process<T>(x: String) -> String where T: DoesNotExist:
 }
 
 // ---------------------------------------------------------------------------
-// FUSE_WHERE_MULTI_CONSTRAINT: multiple constraints on one type param
+// FUSE_WHERE_MULTI_CONSTRAINT: the same interface twice on one type param
 // ---------------------------------------------------------------------------
 
 #[test]
-fn rejects_multi_constraint_on_same_type_param() {
+fn accepts_multiple_interface_constraints_on_one_type_param() {
     let src = r#"
 interface Encodable:
   fn encode() -> String
 
 interface Printable:
-  fn print() -> String
+  fn render() -> String
 
-fn process<T>(x: String) -> String where T: Encodable, T: Printable:
+type User:
+  name: String
+
+impl Encodable for User:
+  fn encode() -> String:
+    return self.name
+
+impl Printable for User:
+  fn render() -> String:
+    return "<" + self.name + ">"
+
+fn process<T>(x: T) -> String where T: Encodable + Printable:
+  return x.encode() + x.render()
+
+fn legacy<T>(x: T) -> String where T: Encodable, T: Printable:
+  return process<T>(x)
+
+fn main() -> String:
+  return legacy<User>(User(name="ada"))
+"#;
+    assert_no_diags(src);
+}
+
+#[test]
+fn rejects_repeated_constraint_on_same_type_param() {
+    let src = r#"
+interface Encodable:
+  fn encode() -> String
+
+fn process<T>(x: String) -> String where T: Encodable + Encodable:
   return x
 "#;
     assert_codes_include(src, &["FUSE_WHERE_MULTI_CONSTRAINT"]);
 }
 
+#[test]
+fn rejects_type_missing_one_of_several_constraints() {
+    let src = r#"
+interface Encodable:
+  fn encode() -> String
+
+interface Printable:
+  fn render() -> String
+
+type User:
+  name: String
+
+impl Encodable for User:
+  fn encode() -> String:
+    return self.name
+
+fn process<T>(x: T) -> String where T: Encodable + Printable:
+  return x.encode()
+
+fn main() -> String:
+  return process<User>(User(name="ada"))
+"#;
+    let messages: Vec<String> = analyze_raw(src).into_iter().map(|d| d.message).collect();
+    assert!(
+        messages
+            .iter()
+            .any(|m| m == "type User does not satisfy Printable"),
+        "got {messages:?}"
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Default members and interface inheritance
+// ---------------------------------------------------------------------------
+
+#[test]
+fn default_and_inherited_members_satisfy_impls_and_bounds() {
+    let src = r#"
+interface Encodable:
+  fn encode() -> String
+
+  fn twice() -> String:
+    return self.encode() + self.encode()
+
+interface Codec extends Encodable:
+  fn decode(text: String) -> Self
+
+  fn copy() -> Self:
+    return Self.decode(self.encode())
+
+type User:
+  name: String
+
+impl Codec for User:
+  fn encode() -> String:
+    return self.name
+
+  fn decode(text: String) -> Self:
+    return User(name=text)
+
+fn shout<T>(x: T) -> String where T: Encodable:
+  return x.twice()
+
+fn main() -> String:
+  let user = User(name="ada")
+  return user.copy().twice() + shout<User>(user)
+"#;
+    assert_no_diags(src);
+}
+
+#[test]
+fn rejects_impl_missing_inherited_member() {
+    let src = r#"
+interface Encodable:
+  fn encode() -> String
+
+interface Codec extends Encodable:
+  fn label() -> String:
+    return "codec"
+
+type User:
+  name: String
+
+impl Codec for User:
+  fn label() -> String:
+    return self.name
+"#;
+    let diags = analyze_raw(src);
+    assert!(
+        diags
+            .iter()
+            .any(|d| d.code.as_deref() == Some("FUSE_IMPL_INCOMPLETE")
+                && d.message == "impl Codec for User is missing method encode"),
+        "got {diags:?}"
+    );
+}
+
+#[test]
+fn default_bodies_only_see_interface_members() {
+    let src = r#"
+interface Encodable:
+  fn encode() -> String
+
+  fn twice() -> String:
+    return self.name + self.encode()
+"#;
+    let messages: Vec<String> = analyze_raw(src).into_iter().map(|d| d.message).collect();
+    assert!(
+        messages
+            .iter()
+            .any(|m| m == "type parameter Self has no member name"),
+        "got {messages:?}"
+    );
+}
+
+#[test]
+fn rejects_unknown_and_cyclic_parent_interfaces() {
+    let src = r#"
+interface A extends B:
+  fn a() -> String
+
+interface B extends A:
+  fn b() -> String
+
+interface C extends Missing:
+  fn c() -> String
+"#;
+    let messages: Vec<String> = analyze_raw(src).into_iter().map(|d| d.message).collect();
+    assert!(
+        messages
+            .iter()
+            .any(|m| m == "interface A cannot extend itself"),
+        "got {messages:?}"
+    );
+    assert!(
+        messages.iter().any(|m| m == "unknown interface Missing"),
+        "got {messages:?}"
+    );
+}
+
 // ---------------------------------------------------------------------------
 // FUSE_GENERIC_DUPLICATE_TYPE_PARAM: duplicate type param names
 // ---------------------------------------------------------------------------
@@ -153,3 +322,74 @@ fn main() -> Int:
 "#;
     assert_codes_include(src, &["FUSE_GENERIC_INFERENCE"]);
 }
+
+// ---------------------------------------------------------------------------
+// Generic type and enum declarations
+// ---------------------------------------------------------------------------
+
+#[test]
+fn generic_type_and_enum_instances_type_check() {
+    let src = r#"
+type Note:
+  title: String
+
+type Page<T>:
+  items: List<T>
+  total: Int
+
+enum Outcome<T, E>:
+  Success(T)
+  Failure(E)
+
+fn first(page: Page<Note>) -> Outcome<Note, String>:
+  if page.total == 0:
+    return Outcome.Failure<Note, String>("empty")
+  return Outcome.Success<Note, String>(page.items[0])
+
+fn main() -> String:
+  let page = Page<Note>(items=[Note(title="a")], total=1)
+  match first(page):
+    Success(note):
+      return note.title
+    Failure(message):
+      return message
+  return ""
+"#;
+    assert_no_diags(src);
+}
+
+#[test]
+fn rejects_wrong_type_arg_count_on_generic_type() {
+    let src = r#"
+type Page<T>:
+  items: List<T>
+
+fn size(page: Page<Int, String>) -> Int:
+  return 0
+"#;
+    assert_codes_include(src, &["FUSE_GENERIC_TYPE_ARG"]);
+}
+
+#[test]
+fn rejects_bare_generic_type_name() {
+    let src = r#"
+type Page<T>:
+  items: List<T>
+
+fn size(page: Page) -> Int:
+  return 0
+"#;
+    assert_codes_include(src, &["FUSE_GENERIC_TYPE_ARG"]);
+}
+
+#[test]
+fn rejects_generic_type_instantiated_with_type_param() {
+    let src = r#"
+type Page<T>:
+  items: List<T>
+
+fn wrap<T>(items: List<T>) -> Page<T>:
+  return Page<T>(items=items)
+"#;
+    assert_codes_include(src, &["FUSE_GENERIC_TYPE_ARG"]);
+}
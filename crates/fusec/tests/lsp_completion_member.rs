@@ -138,3 +138,58 @@ fn main():
     lsp.shutdown();
     let _ = fs::remove_dir_all(dir);
 }
+
+#[test]
+fn lsp_member_completion_hides_private_alias_members() {
+    let dir = temp_project_dir("fuse_lsp_completion_private");
+    fs::create_dir_all(&dir).expect("create temp dir");
+    write_project_file(
+        &dir.join("fuse.toml"),
+        "[package]\nentry = \"main.fuse\"\napp = \"Demo\"\n",
+    );
+
+    let util_src = r#"pub fn greet() -> String:
+  return grumble()
+
+fn grumble() -> String:
+  return "hi"
+"#;
+    let main_src = r#"import util from "./util"
+
+fn main():
+  let _alias = util.gr
+  print("ok")
+"#;
+    let util_path = dir.join("util.fuse");
+    let main_path = dir.join("main.fuse");
+    write_project_file(&util_path, util_src);
+    write_project_file(&main_path, main_src);
+
+    let root_uri = path_to_uri(&dir);
+    let util_uri = path_to_uri(&util_path);
+    let main_uri = path_to_uri(&main_path);
+
+    let mut lsp = LspClient::spawn_with_root(&root_uri);
+    lsp.open_document(&util_uri, util_src, 1);
+    lsp.open_document(&main_uri, main_src, 1);
+    assert!(lsp.wait_diagnostics(&util_uri).is_empty());
+    let _ = lsp.wait_diagnostics(&main_uri);
+
+    let (line, col) = line_col_of(main_src, "util.gr");
+    let completion = lsp.request(
+        "textDocument/completion",
+        completion_params(&main_uri, line, col + "util.gr".len()),
+    );
+    let text = json::encode(&completion);
+    assert!(
+        text.contains("\"label\":\"greet\""),
+        "expected pub member greet, got: {text}"
+    );
+    assert!(
+        !text.contains("\"label\":\"grumble\""),
+        "private member grumble should not be offered, got: {text}"
+    );
+
+    lsp.shutdown();
+    let _ = fs::remove_dir_all(dir);
+}
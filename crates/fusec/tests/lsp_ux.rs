@@ -851,6 +851,163 @@ app "Demo":
     let _ = fs::remove_dir_all(&dir);
 }
 
+#[test]
+fn lsp_diagnostics_report_sql_schema_codes() {
+    let dir = temp_project_dir();
+    fs::create_dir_all(&dir).expect("create temp dir");
+    fs::write(
+        dir.join("fuse.toml"),
+        "[package]\nentry = \"main.fuse\"\napp = \"Demo\"\n",
+    )
+    .expect("write fuse.toml");
+
+    let main_src = r#"requires db
+
+migration "001_users":
+  db.exec("create table users (id integer primary key, name text)")
+
+fn count_names():
+  let rows = db.query("select nme from users")
+  print(rows)
+
+app "Demo":
+  count_names()
+"#;
+    let main_path = dir.join("main.fuse");
+    fs::write(&main_path, main_src).expect("write main.fuse");
+
+    let root_uri = path_to_uri(&dir);
+    let main_uri = path_to_uri(&main_path);
+
+    let (mut child, mut stdin, mut stdout) = spawn_lsp();
+
+    let mut init_params = BTreeMap::new();
+    init_params.insert("rootUri".to_string(), JsonValue::String(root_uri));
+    send_request(&mut stdin, 1, "initialize", JsonValue::Object(init_params));
+    let _ = wait_response(&mut stdout, 1);
+    send_notification(
+        &mut stdin,
+        "initialized",
+        JsonValue::Object(BTreeMap::new()),
+    );
+
+    let mut main_doc = BTreeMap::new();
+    main_doc.insert("uri".to_string(), JsonValue::String(main_uri.clone()));
+    main_doc.insert(
+        "languageId".to_string(),
+        JsonValue::String("fuse".to_string()),
+    );
+    main_doc.insert("version".to_string(), JsonValue::Number(1.0));
+    main_doc.insert("text".to_string(), JsonValue::String(main_src.to_string()));
+    let mut main_open_params = BTreeMap::new();
+    main_open_params.insert("textDocument".to_string(), JsonValue::Object(main_doc));
+    send_notification(
+        &mut stdin,
+        "textDocument/didOpen",
+        JsonValue::Object(main_open_params),
+    );
+
+    let main_diags = wait_diagnostics(&mut stdout, &main_uri);
+    let diags_text = json::encode(&JsonValue::Array(main_diags.clone()));
+    assert_eq!(main_diags.len(), 1, "unexpected diagnostics: {diags_text}");
+    assert!(
+        diags_text.contains("\"code\":\"FUSE_SQL_UNKNOWN_COLUMN\"")
+            && diags_text.contains("no such column: nme"),
+        "expected FUSE_SQL_UNKNOWN_COLUMN diagnostic, got {diags_text}"
+    );
+
+    send_request(
+        &mut stdin,
+        2,
+        "shutdown",
+        JsonValue::Object(BTreeMap::new()),
+    );
+    let _ = wait_response(&mut stdout, 2);
+    send_notification(&mut stdin, "exit", JsonValue::Object(BTreeMap::new()));
+    let status = child.wait().expect("wait lsp");
+    assert!(status.success(), "fuse-lsp exited with {status}");
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn lsp_diagnostics_report_private_import_code() {
+    let dir = temp_project_dir();
+    fs::create_dir_all(&dir).expect("create temp dir");
+    fs::write(
+        dir.join("fuse.toml"),
+        "[package]\nentry = \"main.fuse\"\napp = \"Demo\"\n",
+    )
+    .expect("write fuse.toml");
+
+    let users_src = r#"pub fn greet() -> String:
+  return helper()
+
+fn helper() -> String:
+  return "hi"
+"#;
+    fs::write(dir.join("users.fuse"), users_src).expect("write users.fuse");
+    let main_src = r#"import { helper } from "./users"
+
+app "Demo":
+  print(helper())
+"#;
+    let main_path = dir.join("main.fuse");
+    fs::write(&main_path, main_src).expect("write main.fuse");
+
+    let root_uri = path_to_uri(&dir);
+    let main_uri = path_to_uri(&main_path);
+
+    let (mut child, mut stdin, mut stdout) = spawn_lsp();
+
+    let mut init_params = BTreeMap::new();
+    init_params.insert("rootUri".to_string(), JsonValue::String(root_uri));
+    send_request(&mut stdin, 1, "initialize", JsonValue::Object(init_params));
+    let _ = wait_response(&mut stdout, 1);
+    send_notification(
+        &mut stdin,
+        "initialized",
+        JsonValue::Object(BTreeMap::new()),
+    );
+
+    let mut main_doc = BTreeMap::new();
+    main_doc.insert("uri".to_string(), JsonValue::String(main_uri.clone()));
+    main_doc.insert(
+        "languageId".to_string(),
+        JsonValue::String("fuse".to_string()),
+    );
+    main_doc.insert("version".to_string(), JsonValue::Number(1.0));
+    main_doc.insert("text".to_string(), JsonValue::String(main_src.to_string()));
+    let mut main_open_params = BTreeMap::new();
+    main_open_params.insert("textDocument".to_string(), JsonValue::Object(main_doc));
+    send_notification(
+        &mut stdin,
+        "textDocument/didOpen",
+        JsonValue::Object(main_open_params),
+    );
+
+    let main_diags = wait_diagnostics(&mut stdout, &main_uri);
+    let diags_text = json::encode(&JsonValue::Array(main_diags.clone()));
+    assert!(
+        diags_text.contains("\"code\":\"FUSE_IMPORT_PRIVATE\"")
+            && diags_text.contains("helper is private to ./users; mark it pub to import it"),
+        "expected FUSE_IMPORT_PRIVATE diagnostic, got {diags_text}"
+    );
+
+    send_request(
+        &mut stdin,
+        2,
+        "shutdown",
+        JsonValue::Object(BTreeMap::new()),
+    );
+    let _ = wait_response(&mut stdout, 2);
+    send_notification(&mut stdin, "exit", JsonValue::Object(BTreeMap::new()));
+    let status = child.wait().expect("wait lsp");
+    assert!(status.success(), "fuse-lsp exited with {status}");
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
 #[test]
 fn lsp_multi_package_definition_and_references_smoke() {
     let dir = temp_project_dir();
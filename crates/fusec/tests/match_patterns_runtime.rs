@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use fusec::interp::{Interpreter, Value};
+use fusec::native::{NativeVm, compile_registry};
+
+fn temp_project_dir(tag: &str) -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    dir.push(format!("fuse_match_patterns_runtime_{tag}_{nanos}"));
+    dir
+}
+
+fn write_file(path: &Path, contents: &str) {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).expect("create parent directories");
+    }
+    fs::write(path, contents).expect("write source file");
+}
+
+fn as_string(value: Value) -> String {
+    match value {
+        Value::String(text) => text,
+        other => panic!("expected String, got {other:?}"),
+    }
+}
+
+/// Runs `main` in both backends and returns the (ast, native) results.
+fn run_main(tag: &str, src: &str) -> (String, String) {
+    let dir = temp_project_dir(tag);
+    let main_path = dir.join("main.fuse");
+    write_file(&main_path, src);
+
+    let (registry, diags) = fusec::load_program_with_modules(&main_path, src);
+    assert!(diags.is_empty(), "unexpected load diagnostics: {diags:?}");
+    let (_analysis, sema_diags) = fusec::sema::analyze_registry(&registry);
+    assert!(
+        sema_diags.is_empty(),
+        "unexpected sema diagnostics: {sema_diags:?}"
+    );
+
+    let mut interp = Interpreter::with_registry(&registry);
+    let ast_value = interp
+        .call_function_with_named_args("main", &HashMap::new())
+        .expect("ast call failed");
+
+    let native = compile_registry(&registry).expect("native lowering failed");
+    let mut native_vm = NativeVm::new(&native);
+    let native_value = native_vm
+        .call_function("main", vec![])
+        .expect("native call failed");
+
+    let _ = fs::remove_dir_all(&dir);
+    (as_string(ast_value), as_string(native_value))
+}
+
+// ---------------------------------------------------------------------------
+// Test 1: guards, or-patterns and binding patterns
+// ---------------------------------------------------------------------------
+
+#[test]
+fn match_guards_or_and_bind_patterns_run_in_ast_and_native_backends() {
+    let (ast, native) = run_main(
+        "guards",
+        r#"
+enum Shape:
+  Circle(Int)
+  Square(Int)
+  Dot
+
+fn scope(name: String) -> String:
+  match name:
+    "notes:read" | "notes:write" -> "notes"
+    other if other == "admin" -> "all"
+    _ -> "none"
+
+fn size(shape: Shape, limit: Int) -> String:
+  match shape:
+    Circle(r) | Square(r) if limit > 10 -> "big ${r}"
+    whole @ Circle(r) -> "circle ${r}"
+    Square(_) | Dot -> "small"
+
+fn main() -> String:
+  let a = scope("notes:write")
+  let b = scope("admin")
+  let c = scope("guest")
+  let d = size(Shape.Square(12), 20)
+  let e = size(Shape.Circle(3), 5)
+  let f = size(Shape.Dot, 20)
+  return "${a}|${b}|${c}|${d}|${e}|${f}"
+"#,
+    );
+    assert_eq!(ast, "notes|all|none|big 12|circle 3|small");
+    assert_eq!(native, ast);
+}
+
+// ---------------------------------------------------------------------------
+// Test 2: numeric range patterns
+// ---------------------------------------------------------------------------
+
+#[test]
+fn match_range_patterns_run_in_ast_and_native_backends() {
+    let (ast, native) = run_main(
+        "ranges",
+        r#"
+fn grade(score: Int) -> String:
+  match score:
+    -10..-1 -> "negative"
+    n if n < -10 -> "too low"
+    0 -> "zero"
+    1..49 -> "fail"
+    n @ 50..100 -> "pass ${n}"
+    _ -> "invalid"
+
+fn band(temp: Float) -> String:
+  match temp:
+    0.0..15.5 -> "cold"
+    _ -> "warm"
+
+fn main() -> String:
+  let a = grade(-3)
+  let low = grade(-11)
+  let b = grade(0)
+  let c = grade(49)
+  let d = grade(50)
+  let e = grade(101)
+  let f = band(15.5)
+  let g = band(20.0)
+  return "${a}|${low}|${b}|${c}|${d}|${e}|${f}|${g}"
+"#,
+    );
+    assert_eq!(ast, "negative|too low|zero|fail|pass 50|invalid|cold|warm");
+    assert_eq!(native, ast);
+}
@@ -51,6 +51,28 @@ fn run_migrate(program_path: &PathBuf, db_url: &str, pool_size: usize) -> Output
         .expect("failed to run fusec --migrate")
 }
 
+fn run_fusec(args: &[&str], program_path: &PathBuf, db_url: &str) -> Output {
+    let exe = env!("CARGO_BIN_EXE_fusec");
+    Command::new(exe)
+        .args(args)
+        .arg(program_path)
+        .env("FUSE_DB_URL", db_url)
+        .env_remove("FUSE_DB_POOL_SIZE")
+        .env_remove("APP_DB_POOL_SIZE")
+        .output()
+        .expect("failed to run fusec")
+}
+
+fn table_exists(db: &Db, table: &str) -> bool {
+    let rows = db
+        .query_params(
+            "select count(*) as c from sqlite_master where type = 'table' and name = ?",
+            &[Value::String(table.to_string())],
+        )
+        .expect("query sqlite_master");
+    scalar_i64(&rows, "c") == 1
+}
+
 fn scalar_i64(rows: &[HashMap<String, Value>], key: &str) -> i64 {
     let value = rows.first().and_then(|row| row.get(key));
     match value {
@@ -274,3 +296,295 @@ migration "001_seed":
 
     let _ = fs::remove_dir_all(dir);
 }
+
+const REVERSIBLE_MIGRATIONS: &str = r#"
+requires db
+
+migration "001_users":
+  db.exec("create table users (id integer primary key, name text)")
+down:
+  db.exec("drop table users")
+
+migration "002_posts":
+  db.exec("create table posts (id integer primary key, title text)")
+down:
+  db.exec("drop table posts")
+
+migration "003_tags":
+  db.exec("create table tags (id integer primary key)")
+down:
+  db.exec("drop table tags")
+"#;
+
+#[test]
+fn migrate_down_reverts_migrations_after_target_and_reports_status() {
+    let db_url = temp_db_url();
+    let path = write_temp_program("fuse_migration_down", REVERSIBLE_MIGRATIONS);
+
+    let output = run_fusec(&["--migrate"], &path, &db_url);
+    assert!(
+        output.status.success(),
+        "stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = run_fusec(&["--migrate-down", "--to", "001_users"], &path, &db_url);
+    assert!(
+        output.status.success(),
+        "stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout, "rolled back 003_tags\nrolled back 002_posts\n");
+
+    let verify_db = Db::open_with_pool(&db_url, 1).expect("open verify db");
+    assert!(table_exists(&verify_db, "users"));
+    assert!(!table_exists(&verify_db, "posts"));
+    assert!(!table_exists(&verify_db, "tags"));
+    let history = verify_db
+        .query("select count(*) as c from __fuse_migrations")
+        .expect("query migration history");
+    assert_eq!(scalar_i64(&history, "c"), 1);
+
+    let output = run_fusec(&["--migrate-status"], &path, &db_url);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "package <default>\n  applied   001_users\n  pending   002_posts\n  pending   003_tags\n"
+    );
+
+    let output = run_fusec(&["--migrate-down"], &path, &db_url);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "rolled back 001_users\n"
+    );
+    assert!(!table_exists(&verify_db, "users"));
+}
+
+#[test]
+fn migrate_down_requires_down_blocks_before_reverting_anything() {
+    let db_url = temp_db_url();
+    let program = r#"
+requires db
+
+migration "001_users":
+  db.exec("create table users (id integer primary key)")
+down:
+  db.exec("drop table users")
+
+migration "002_posts":
+  db.exec("create table posts (id integer primary key)")
+"#;
+    let path = write_temp_program("fuse_migration_no_down", program);
+    let output = run_fusec(&["--migrate"], &path, &db_url);
+    assert!(output.status.success());
+
+    let output = run_fusec(&["--migrate-down", "--to", "000_none"], &path, &db_url);
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("unknown migration 000_none"),
+        "stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = run_fusec(&["--migrate-down", "--to", "001_users"], &path, &db_url);
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("migration 002_posts has no down block"),
+        "stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let verify_db = Db::open_with_pool(&db_url, 1).expect("open verify db");
+    assert!(table_exists(&verify_db, "users"));
+    assert!(table_exists(&verify_db, "posts"));
+}
+
+#[test]
+fn migrate_dry_run_prints_statements_without_applying_them() {
+    let db_url = temp_db_url();
+    let program = r#"
+requires db
+
+migration "001_items":
+  db.exec("create table items (id integer, rank integer)")
+  db.exec("insert into items (id, rank) values (?, ?)", [1, 2])
+"#;
+    let path = write_temp_program("fuse_migration_dry_run", program);
+
+    let output = run_fusec(&["--migrate", "--dry-run"], &path, &db_url);
+    assert!(
+        output.status.success(),
+        "stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.starts_with("-- up 001_items (package <default>)\n"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("create table items (id integer, rank integer);\n"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("insert into items (id, rank) values (?, ?);\n"),
+        "{stdout}"
+    );
+
+    let verify_db = Db::open_with_pool(&db_url, 1).expect("open verify db");
+    assert!(!table_exists(&verify_db, "items"));
+    assert!(!table_exists(&verify_db, "__fuse_migrations"));
+
+    let output = run_fusec(&["--migrate"], &path, &db_url);
+    assert!(output.status.success());
+    let output = run_fusec(&["--migrate", "--dry-run"], &path, &db_url);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "no pending migrations\n"
+    );
+}
+
+#[test]
+fn modified_applied_migration_is_rejected_by_checksum() {
+    let db_url = temp_db_url();
+    let path = write_temp_program("fuse_migration_checksum", REVERSIBLE_MIGRATIONS);
+    let output = run_fusec(&["--migrate"], &path, &db_url);
+    assert!(output.status.success());
+
+    // Whitespace-only edits keep the checksum stable.
+    let reformatted = REVERSIBLE_MIGRATIONS.replace(
+        "db.exec(\"create table users (id integer primary key, name text)\")",
+        "db.exec(\"create table users (id integer primary key, name text)\")   \n",
+    );
+    fs::write(&path, reformatted).expect("rewrite program");
+    let output = run_fusec(&["--migrate"], &path, &db_url);
+    assert!(
+        output.status.success(),
+        "stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let edited = REVERSIBLE_MIGRATIONS.replace("name text", "name text, email text");
+    fs::write(&path, edited).expect("rewrite program");
+    let output = run_fusec(&["--migrate"], &path, &db_url);
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains(
+            "migration 001_users in package <default> was modified after it was applied (checksum mismatch)"
+        ),
+        "stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = run_fusec(&["--migrate-status"], &path, &db_url);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("  modified  001_users\n"));
+
+    // Edits to the down block of an applied migration are detected too.
+    let edited = REVERSIBLE_MIGRATIONS.replace("drop table posts", "drop table if exists posts");
+    fs::write(&path, edited).expect("rewrite program");
+    let output = run_fusec(&["--migrate-down"], &path, &db_url);
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains(
+            "migration 002_posts in package <default> was modified after it was applied (checksum mismatch)"
+        ),
+        "stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn migrate_down_to_only_reverts_the_target_package() {
+    let db_url = temp_db_url();
+    let dir = temp_workspace_dir("fuse_migration_down_packages");
+    let dep_dir = dir.join("deps").join("auth");
+    fs::create_dir_all(&dep_dir).expect("create dep dir");
+    fs::write(dir.join("fuse.toml"), "[package]\nentry = \"main.fuse\"\n")
+        .expect("write root manifest");
+    fs::write(
+        dep_dir.join("fuse.toml"),
+        "[package]\nname = \"auth\"\nentry = \"main.fuse\"\n",
+    )
+    .expect("write dep manifest");
+    fs::write(
+        dir.join("main.fuse"),
+        r#"requires db
+import auth from "./deps/auth/main"
+
+migration "001_users":
+  db.exec("create table users (id integer primary key)")
+down:
+  db.exec("drop table users")
+
+migration "002_posts":
+  db.exec("create table posts (id integer primary key)")
+down:
+  db.exec("drop table posts")
+"#,
+    )
+    .expect("write root entry");
+    fs::write(
+        dep_dir.join("main.fuse"),
+        r#"requires db
+
+migration "001_sessions":
+  db.exec("create table sessions (id integer primary key)")
+down:
+  db.exec("drop table sessions")
+
+migration "003_tokens":
+  db.exec("create table tokens (id integer primary key)")
+down:
+  db.exec("drop table tokens")
+"#,
+    )
+    .expect("write dep entry");
+    let entry = dir.join("main.fuse");
+
+    let output = run_fusec(&["--migrate"], &entry, &db_url);
+    assert!(
+        output.status.success(),
+        "stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = run_fusec(&["--migrate-down", "--to", "001_users"], &entry, &db_url);
+    assert!(
+        output.status.success(),
+        "stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "rolled back 002_posts\n"
+    );
+    let verify_db = Db::open_with_pool(&db_url, 1).expect("open verify db");
+    assert!(table_exists(&verify_db, "users"));
+    assert!(!table_exists(&verify_db, "posts"));
+    assert!(table_exists(&verify_db, "sessions"));
+    assert!(table_exists(&verify_db, "tokens"));
+
+    let output = run_fusec(
+        &["--migrate-down", "--to", "auth:001_sessions"],
+        &entry,
+        &db_url,
+    );
+    assert!(
+        output.status.success(),
+        "stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "rolled back 003_tokens\n"
+    );
+    assert!(table_exists(&verify_db, "users"));
+    assert!(table_exists(&verify_db, "sessions"));
+    assert!(!table_exists(&verify_db, "tokens"));
+
+    let _ = fs::remove_dir_all(dir);
+}
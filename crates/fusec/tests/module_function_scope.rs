@@ -597,3 +597,150 @@ fn main() -> Int!AuthError!DbError:
 
     let _ = fs::remove_dir_all(&dir);
 }
+
+#[test]
+fn private_module_members_are_rejected_outside_their_module() {
+    let dir = temp_project_dir("visibility");
+    fs::create_dir_all(&dir).expect("create temp dir");
+    let main_path = dir.join("main.fuse");
+    write_file(
+        &dir.join("users.fuse"),
+        r#"
+type Secret:
+  token: String
+
+pub type User:
+  name: String
+
+fn helper() -> String:
+  return "hidden"
+
+pub fn greet(name: String) -> String:
+  return helper() + ":" + name
+"#,
+    );
+    write_file(
+        &dir.join("legacy.fuse"),
+        r#"
+fn helper() -> String:
+  return "legacy"
+"#,
+    );
+    write_file(
+        &main_path,
+        r#"
+import Users from "./users"
+import Legacy from "./legacy"
+import { greet, User } from "./users"
+
+fn main() -> String:
+  let user = User(name="ada")
+  return greet(user.name) + Legacy.helper()
+"#,
+    );
+
+    let src = fs::read_to_string(&main_path).expect("read root source");
+    let (registry, diags) = fusec::load_program_with_modules(&main_path, &src);
+    assert!(diags.is_empty(), "unexpected diagnostics: {diags:?}");
+    let (_analysis, sema_diags) = fusec::sema::analyze_registry(&registry);
+    assert!(
+        sema_diags.is_empty(),
+        "unexpected sema diagnostics: {sema_diags:?}"
+    );
+    let mut interp = Interpreter::with_registry(&registry);
+    let ast = interp
+        .call_function_with_named_args("main", &HashMap::new())
+        .expect("ast call failed");
+    assert_eq!(as_string(ast), "hidden:adalegacy");
+
+    write_file(
+        &main_path,
+        r#"
+import Users from "./users"
+
+fn main() -> String:
+  let secret: Users.Secret = Users.Secret(token="x")
+  return Users.helper()
+"#,
+    );
+    let src = fs::read_to_string(&main_path).expect("read root source");
+    let (registry, diags) = fusec::load_program_with_modules(&main_path, &src);
+    assert!(diags.is_empty(), "unexpected diagnostics: {diags:?}");
+    let (_analysis, sema_diags) = fusec::sema::analyze_registry(&registry);
+    let messages: Vec<String> = sema_diags.into_iter().map(|diag| diag.message).collect();
+    assert!(
+        messages
+            .iter()
+            .any(|msg| msg.contains("Users.helper is private to its module")),
+        "missing private function diagnostic: {messages:?}"
+    );
+    assert!(
+        messages
+            .iter()
+            .any(|msg| msg.contains("Users.Secret is private to its module")),
+        "missing private type diagnostic: {messages:?}"
+    );
+
+    write_file(
+        &main_path,
+        r#"
+import { helper } from "./users"
+
+fn main() -> String:
+  return helper()
+"#,
+    );
+    let src = fs::read_to_string(&main_path).expect("read root source");
+    let (_registry, diags) = fusec::load_program_with_modules(&main_path, &src);
+    let private_import = diags
+        .iter()
+        .find(|diag| diag.code.as_deref() == Some("FUSE_IMPORT_PRIVATE"))
+        .expect("missing private import diagnostic");
+    assert_eq!(
+        private_import.message,
+        "helper is private to ./users; mark it pub to import it"
+    );
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn strict_architecture_warns_on_implicit_module_exports() {
+    let dir = temp_project_dir("strict_visibility");
+    fs::create_dir_all(&dir).expect("create temp dir");
+    let main_path = dir.join("main.fuse");
+    write_file(
+        &dir.join("util.fuse"),
+        r#"
+fn value() -> Int:
+  return 1
+"#,
+    );
+    write_file(
+        &main_path,
+        r#"
+import Util from "./util"
+
+fn main() -> Int:
+  return Util.value()
+"#,
+    );
+
+    let src = fs::read_to_string(&main_path).expect("read root source");
+    let (registry, diags) = fusec::load_program_with_modules(&main_path, &src);
+    assert!(diags.is_empty(), "unexpected loader diagnostics: {diags:?}");
+    let (_analysis, sema_diags) = fusec::sema::analyze_registry_with_options(
+        &registry,
+        fusec::sema::AnalyzeOptions {
+            strict_architecture: true,
+        },
+    );
+    let messages: Vec<String> = sema_diags.into_iter().map(|diag| diag.message).collect();
+    let expected = "exports every declaration implicitly; mark its public API with pub";
+    assert!(
+        messages.iter().any(|msg| msg.contains(expected)),
+        "missing strict implicit export warning: {messages:?}"
+    );
+
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -778,6 +778,47 @@ app "demo":
     assert_eq!(String::from_utf8_lossy(&ast.stdout), "ok\n");
 }
 
+#[test]
+fn parity_break_and_continue_ending_if_bodies() {
+    let program = r#"
+fn scan(limit: Int) -> String:
+  var out = ""
+  for i in [1, 2, 3, 4, 5]:
+    if i == 2:
+      continue
+    if i > limit:
+      break
+    out = out + "${i},"
+  var n = 0
+  while true:
+    n = n + 1
+    if n >= 3:
+      break
+  return "${out}${n}"
+
+app "demo":
+  print(scan(4))
+"#;
+    let ast = run_temp_program("ast", program, &[]);
+    let native = run_temp_program("native", program, &[]);
+
+    assert!(
+        ast.status.success(),
+        "ast stderr: {}",
+        String::from_utf8_lossy(&ast.stderr)
+    );
+    assert!(
+        native.status.success(),
+        "native stderr: {}",
+        String::from_utf8_lossy(&native.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&ast.stdout),
+        String::from_utf8_lossy(&native.stdout)
+    );
+    assert_eq!(String::from_utf8_lossy(&ast.stdout), "1,3,4,3\n");
+}
+
 #[test]
 fn parity_crypto_hash_invalid_algorithm_error() {
     let program = r#"
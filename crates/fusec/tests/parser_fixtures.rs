@@ -1,4 +1,6 @@
-use fusec::ast::{Capability, ExprKind, Item, Literal, StmtKind, TypeRefKind};
+use fusec::ast::{
+    Capability, DeriveOp, ExprKind, Item, Literal, PatternKind, StmtKind, TypeRefKind,
+};
 use fusec::parse_source;
 
 fn assert_parse_ok(src: &str) {
@@ -139,6 +141,24 @@ type PublicUser = User without email, id
     assert_parse_ok(src);
 }
 
+#[test]
+fn parses_seed_declarations_without_reserving_seed() {
+    let src = r#"
+seed "001_users":
+  db.exec("insert into users (id) values ('u1')")
+
+fn seed():
+  let seed = 1
+  print(seed)
+"#;
+    let program = parse_ok(src);
+    assert!(matches!(
+        &program.items[0],
+        Item::Seed(decl) if decl.name.value == "001_users" && decl.body.stmts.len() == 1
+    ));
+    assert!(matches!(&program.items[1], Item::Fn(decl) if decl.name.name == "seed"));
+}
+
 #[test]
 fn parses_interpolated_strings() {
     let src = r#"
@@ -824,6 +844,37 @@ impl Factory for User:
     assert_eq!(ret_ident.name, "Self");
 }
 
+#[test]
+fn parses_interface_defaults_inheritance_and_multiple_bounds() {
+    let src = r#"
+interface Codec extends Encode, Decode:
+  fn encode() -> String
+
+  fn twice() -> String:
+    return self.encode() + self.encode()
+
+fn show<T, U>(value: T, other: U) -> String where T: Codec + Debug, U: Codec:
+  return value.twice()
+"#;
+    let program = parse_ok(src);
+    let Some(Item::Interface(decl)) = program.items.first() else {
+        panic!("expected interface declaration");
+    };
+    let parents: Vec<_> = decl.extends.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(parents, ["Encode", "Decode"]);
+    assert!(decl.members[0].body.is_none());
+    assert!(decl.members[1].body.is_some());
+    let Some(Item::Fn(func)) = program.items.get(1) else {
+        panic!("expected function");
+    };
+    let bounds: Vec<_> = func
+        .where_clause
+        .iter()
+        .map(|c| (c.type_param.name.as_str(), c.interface.name.as_str()))
+        .collect();
+    assert_eq!(bounds, [("T", "Codec"), ("T", "Debug"), ("U", "Codec")]);
+}
+
 #[test]
 fn parser_reserves_interface_and_impl_keywords_in_identifier_positions() {
     let src = r#"
@@ -832,3 +883,171 @@ fn interface(impl: Int) -> Int:
 "#;
     assert_parse_err_code(src, "FUSE_RESERVED_KEYWORD");
 }
+
+#[test]
+fn parses_tuple_types_literals_and_patterns() {
+    let src = r#"
+fn pair() -> (String, Int):
+  let (name, count) = ("ada", 1)
+  var (lo, hi) = (1, 2,)
+  for key, value in {"a": 1}:
+    lo = lo + value
+  match (name, count):
+    (_, 0) -> "none"
+    (label, _):
+      return (label, hi)
+"#;
+    let program = parse_ok(src);
+    let Some(Item::Fn(decl)) = program.items.first() else {
+        panic!("expected fn declaration");
+    };
+    let Some(ret) = &decl.ret else {
+        panic!("expected return type");
+    };
+    let TypeRefKind::Tuple(items) = &ret.kind else {
+        panic!("expected tuple return type");
+    };
+    assert_eq!(items.len(), 2);
+    let StmtKind::LetPattern { pat, mutable, .. } = &decl.body.stmts[1].kind else {
+        panic!("expected var pattern");
+    };
+    assert!(*mutable);
+    assert!(pat.is_pair());
+    let StmtKind::For { pat, .. } = &decl.body.stmts[2].kind else {
+        panic!("expected for loop");
+    };
+    assert!(pat.is_pair());
+}
+
+#[test]
+fn tuple_types_need_two_elements() {
+    assert_parse_err_contains(
+        "fn one(value: (Int)) -> Int:\n  return 1\n",
+        "tuple types need at least two elements",
+    );
+}
+
+#[test]
+fn parses_match_guards_or_bind_and_range_patterns() {
+    let src = r#"
+fn grade(score: Int) -> String:
+  match score:
+    0 | 100 -> "edge"
+    n @ -10..-1 if n != -5 -> "negative"
+    _ -> "other"
+"#;
+    let program = parse_ok(src);
+    let Some(Item::Fn(decl)) = program.items.first() else {
+        panic!("expected fn declaration");
+    };
+    let StmtKind::Match { cases, .. } = &decl.body.stmts[0].kind else {
+        panic!("expected match statement");
+    };
+    assert!(matches!(&cases[0].pat.kind, PatternKind::Or(alts) if alts.len() == 2));
+    assert!(cases[0].guard.is_none());
+    let PatternKind::Bind { name, pat } = &cases[1].pat.kind else {
+        panic!("expected binding pattern");
+    };
+    assert_eq!(name.name, "n");
+    assert!(matches!(
+        &pat.kind,
+        PatternKind::Range {
+            start: Literal::Int(-10),
+            end: Literal::Int(-1),
+        }
+    ));
+    assert!(cases[1].guard.is_some());
+}
+
+#[test]
+fn parses_consts_and_type_aliases() {
+    let src = r#"
+const MAX_TITLE: Int = 80
+type Title = String(1..MAX_TITLE)
+type Tags = List<Title>
+"#;
+    let program = parse_ok(src);
+    let Some(Item::Const(decl)) = program.items.first() else {
+        panic!("expected const declaration");
+    };
+    assert_eq!(decl.name.name, "MAX_TITLE");
+    assert!(matches!(&decl.ty.kind, TypeRefKind::Simple(ident) if ident.name == "Int"));
+    assert!(matches!(
+        &decl.value.kind,
+        ExprKind::Literal(Literal::Int(80))
+    ));
+    let Some(Item::TypeAlias(alias)) = program.items.get(1) else {
+        panic!("expected type alias");
+    };
+    assert_eq!(alias.name.name, "Title");
+    assert!(matches!(&alias.target.kind, TypeRefKind::Refined { .. }));
+    assert!(matches!(
+        program.items.get(2),
+        Some(Item::TypeAlias(alias)) if matches!(alias.target.kind, TypeRefKind::Generic { .. })
+    ));
+}
+
+#[test]
+fn parses_type_derivations_and_record_updates() {
+    let src = r#"
+type NotePatch = Note without id pick title, text partial with tags: List<String> = []
+
+fn rename(note: Note) -> Note:
+  return note with title = "x", id = 2
+"#;
+    let program = parse_ok(src);
+    let Some(Item::Type(decl)) = program.items.first() else {
+        panic!("expected type declaration");
+    };
+    let derive = decl.derive.as_ref().expect("expected derivation");
+    assert_eq!(derive.base.name, "Note");
+    assert!(matches!(
+        derive.ops.as_slice(),
+        [
+            DeriveOp::Without(without),
+            DeriveOp::Pick(picked),
+            DeriveOp::Partial,
+            DeriveOp::With(added),
+        ] if without.len() == 1 && picked.len() == 2 && added.len() == 1
+    ));
+    let Some(Item::Fn(func)) = program.items.get(1) else {
+        panic!("expected function");
+    };
+    let Some(StmtKind::Return { expr: Some(expr) }) = func.body.stmts.first().map(|s| &s.kind)
+    else {
+        panic!("expected return statement");
+    };
+    let ExprKind::RecordUpdate { base, fields } = &expr.kind else {
+        panic!("expected record update");
+    };
+    assert!(matches!(&base.kind, ExprKind::Ident(ident) if ident.name == "note"));
+    let names: Vec<_> = fields.iter().map(|f| f.name.name.as_str()).collect();
+    assert_eq!(names, ["title", "id"]);
+}
+
+#[test]
+fn parses_pub_visibility_markers() {
+    let src = r#"
+pub type User:
+  name: String
+
+pub fn greet() -> String:
+  return "hi"
+
+fn helper() -> Int:
+  return 1
+"#;
+    let program = parse_ok(src);
+    assert!(matches!(program.items.first(), Some(Item::Type(decl)) if decl.is_pub));
+    assert!(matches!(program.items.get(1), Some(Item::Fn(func)) if func.is_pub));
+    assert!(matches!(program.items.get(2), Some(Item::Fn(func)) if !func.is_pub));
+
+    assert_parse_err_contains(
+        r#"
+pub service Api at "/api":
+  get "/" -> String:
+    return "ok"
+"#,
+        "pub is only allowed on fn, type, enum, interface, config and const declarations",
+    );
+}
@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+
+use fusec::interp::{Interpreter, Value};
+use fusec::native::{NativeVm, compile_registry};
+
+fn as_string(value: Value) -> String {
+    match value {
+        Value::String(text) => text,
+        Value::ResultOk(inner) => as_string(*inner),
+        other => panic!("expected String, got {other:?}"),
+    }
+}
+
+const SRC: &str = r#"
+type Countdown:
+  n: Int
+
+impl Iterator for Countdown:
+  fn next() -> (Int, Countdown)?:
+    if self.n <= 0:
+      return null
+    return (self.n, Countdown(n=self.n - 1))
+
+type Cursor:
+  offset: Int
+  limit: Int
+
+impl Iterator for Cursor:
+  fn next() -> ((Int, String), Cursor)?:
+    if self.offset >= self.limit:
+      return null
+    let row = (self.offset, "row${self.offset}")
+    return (row, Cursor(offset=self.offset + 1, limit=self.limit))
+
+fn sum(values: Range<Int>) -> Int:
+  var total = 0
+  for value in values:
+    total = total + value
+  return total
+
+fn ranges() -> String:
+  let exclusive = 0..<5
+  let stepped = (0..10).step(3)
+  return "${exclusive}|${sum(exclusive)}|${sum(1..10)}|${stepped}|${sum(stepped)}|${sum(3..<3)}"
+
+fn lazy() -> String:
+  var seen = 0
+  for i in 0..1000000000000:
+    if i >= 3:
+      break
+    seen = seen + 1
+  let big = 0..<1000000000000
+  return "${seen}|${big[999999999999]}|${(1..3).to_list()}|${(0.0..1.0).step(0.25).to_list()}"
+
+fn custom() -> String:
+  var out = ""
+  for n in Countdown(n=4):
+    if n == 2:
+      continue
+    out = out + "${n},"
+  for (id, name) in Cursor(offset=0, limit=3):
+    out = out + "${id}=${name};"
+    if id == 1:
+      break
+  return out
+
+fn display() -> String:
+  let inclusive = 1..3
+  return "${inclusive}|${1..4}|${inclusive.to_list()}"
+
+fn zero_step() -> String:
+  let r = (0..4).step(0)
+  return "${r}"
+"#;
+
+fn load() -> fusec::ModuleRegistry {
+    let (registry, diags) =
+        fusec::load_program_with_modules(std::path::Path::new("ranges.fuse"), SRC);
+    assert!(diags.is_empty(), "unexpected load diagnostics: {diags:?}");
+    let (_analysis, sema_diags) = fusec::sema::analyze_registry(&registry);
+    assert!(
+        sema_diags.is_empty(),
+        "unexpected sema diagnostics: {sema_diags:?}"
+    );
+    registry
+}
+
+fn run_both(registry: &fusec::ModuleRegistry, name: &str) -> String {
+    let mut interp = Interpreter::with_registry(registry);
+    let ast = as_string(
+        interp
+            .call_function_with_named_args(name, &HashMap::new())
+            .expect("ast call failed"),
+    );
+    let native = compile_registry(registry).expect("native lowering failed");
+    let mut native_vm = NativeVm::new(&native);
+    let native = as_string(
+        native_vm
+            .call_function(name, vec![])
+            .expect("native call failed"),
+    );
+    assert_eq!(native, ast, "backend mismatch for {name}");
+    ast
+}
+
+fn sema_messages(src: &str) -> Vec<String> {
+    let (registry, diags) =
+        fusec::load_program_with_modules(std::path::Path::new("ranges_bad.fuse"), src);
+    assert!(diags.is_empty(), "unexpected load diagnostics: {diags:?}");
+    let (_analysis, sema_diags) = fusec::sema::analyze_registry(&registry);
+    sema_diags.into_iter().map(|diag| diag.message).collect()
+}
+
+#[test]
+fn exclusive_and_stepped_ranges_work_across_backends() {
+    let registry = load();
+    assert_eq!(
+        run_both(&registry, "ranges"),
+        "0..<5|10|55|(0..10).step(3)|18|0"
+    );
+}
+
+#[test]
+fn ranges_display_in_source_form() {
+    let registry = load();
+    assert_eq!(run_both(&registry, "display"), "1..3|1..4|[1, 2, 3]");
+}
+
+#[test]
+fn huge_ranges_are_not_materialized() {
+    let registry = load();
+    assert_eq!(
+        run_both(&registry, "lazy"),
+        "3|999999999999|[1, 2, 3]|[0, 0.25, 0.5, 0.75, 1]"
+    );
+}
+
+#[test]
+fn for_loops_walk_user_iterators_across_backends() {
+    let registry = load();
+    assert_eq!(run_both(&registry, "custom"), "4,3,1,0=row0;1=row1;");
+}
+
+#[test]
+fn zero_step_fails_across_backends() {
+    let registry = load();
+    let mut interp = Interpreter::with_registry(&registry);
+    let ast = interp
+        .call_function_with_named_args("zero_step", &HashMap::new())
+        .expect_err("ast backend should reject the step");
+    assert!(
+        ast.contains("range step must be > 0"),
+        "unexpected ast error: {ast}"
+    );
+    let native = compile_registry(&registry).expect("native lowering failed");
+    let mut native_vm = NativeVm::new(&native);
+    let native = native_vm
+        .call_function("zero_step", vec![])
+        .expect_err("native backend should reject the step");
+    assert!(
+        native.contains("range step must be > 0"),
+        "unexpected native error: {native}"
+    );
+}
+
+#[test]
+fn sema_types_ranges_and_checks_iterator_impls() {
+    let messages = sema_messages(
+        r#"
+fn main() -> Int:
+  let values: List<Int> = 1..3
+  return 0
+"#,
+    );
+    assert!(
+        messages
+            .iter()
+            .any(|message| message.contains("type mismatch")),
+        "expected a Range/List mismatch, got {messages:?}"
+    );
+
+    let messages = sema_messages(
+        r#"
+type Bad:
+  n: Int
+
+impl Iterator for Bad:
+  fn next() -> Int?:
+    return self.n
+
+fn main() -> Int:
+  return 0
+"#,
+    );
+    assert!(
+        messages
+            .iter()
+            .any(|message| message.contains("impl method next does not match interface Iterator")),
+        "expected an iterator shape error, got {messages:?}"
+    );
+
+    let messages = sema_messages(
+        r#"
+type Plain:
+  n: Int
+
+fn main() -> Int:
+  for item in Plain(n=1):
+    print(item)
+  return 0
+"#,
+    );
+    assert!(
+        messages
+            .iter()
+            .any(|message| message.contains("cannot iterate over type Plain")),
+        "expected a non-iterable error, got {messages:?}"
+    );
+}
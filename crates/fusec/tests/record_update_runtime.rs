@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+use fusec::interp::{Interpreter, Value};
+use fusec::native::{NativeVm, compile_registry};
+
+fn as_string(value: Value) -> String {
+    match value {
+        Value::String(text) => text,
+        other => panic!("expected String, got {other:?}"),
+    }
+}
+
+const SRC: &str = r#"
+type Note:
+  id: Int
+  title: String(1..20)
+  text: String = ""
+
+type NoteCreate = Note without id
+type NoteSummary = Note pick id, title
+type NotePatch = Note without id partial
+type TaggedNote = Note with pinned: Bool = false
+
+fn describe(changes: NotePatch) -> String:
+  let title = changes.title ?? "-"
+  let text = changes.text ?? "-"
+  return "${title}/${text}"
+
+fn main() -> String:
+  let note = Note(id=1, title="draft", text="b")
+  let renamed = note with title = "final", text = "c"
+  let summary = NoteSummary(id=renamed.id, title=renamed.title)
+  let changes = NotePatch(title="x", text=null)
+  let tagged = TaggedNote(id=2, title="t", text="", pinned=true)
+  let created = NoteCreate(title="n", text="d")
+  return "${note.title}|${renamed.title}|${renamed.text}|${summary.title}|${describe(changes)}|${tagged.pinned}|${created.text}"
+
+fn omitted_fields() -> String:
+  let changes = NotePatch(text="only")
+  let tagged = TaggedNote(id=2, title="t")
+  return "${describe(changes)}|${tagged.pinned}|${tagged.text}"
+
+fn invalid_update() -> String:
+  let note = Note(id=1, title="draft")
+  let next = note with title = ""
+  return next.title
+"#;
+
+fn load() -> fusec::ModuleRegistry {
+    let (registry, diags) =
+        fusec::load_program_with_modules(std::path::Path::new("record_update.fuse"), SRC);
+    assert!(diags.is_empty(), "unexpected load diagnostics: {diags:?}");
+    let (_analysis, sema_diags) = fusec::sema::analyze_registry(&registry);
+    assert!(
+        sema_diags.is_empty(),
+        "unexpected sema diagnostics: {sema_diags:?}"
+    );
+    registry
+}
+
+#[test]
+fn derived_types_and_record_updates_run_in_ast_and_native_backends() {
+    let registry = load();
+    let mut interp = Interpreter::with_registry(&registry);
+    let ast = as_string(
+        interp
+            .call_function_with_named_args("main", &HashMap::new())
+            .expect("ast call failed"),
+    );
+    let native = compile_registry(&registry).expect("native lowering failed");
+    let mut native_vm = NativeVm::new(&native);
+    let native = as_string(
+        native_vm
+            .call_function("main", vec![])
+            .expect("native call failed"),
+    );
+
+    assert_eq!(ast, "draft|final|c|final|x/-|true|d");
+    assert_eq!(native, ast);
+}
+
+#[test]
+fn partial_fields_default_to_null_and_added_fields_keep_defaults() {
+    let registry = load();
+    let mut interp = Interpreter::with_registry(&registry);
+    let ast = as_string(
+        interp
+            .call_function_with_named_args("omitted_fields", &HashMap::new())
+            .expect("ast call failed"),
+    );
+    assert_eq!(ast, "-/only|false|");
+}
+
+#[test]
+fn record_update_revalidates_refined_fields() {
+    let registry = load();
+    let mut interp = Interpreter::with_registry(&registry);
+    assert!(
+        interp
+            .call_function_with_named_args("invalid_update", &HashMap::new())
+            .is_err(),
+        "ast backend should reject an empty title"
+    );
+    let native = compile_registry(&registry).expect("native lowering failed");
+    let mut native_vm = NativeVm::new(&native);
+    assert!(
+        native_vm.call_function("invalid_update", vec![]).is_err(),
+        "native backend should reject an empty title"
+    );
+}
@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use fusec::db::Db;
+use fusec::interp::Value;
+
+fn temp_workspace_dir(name: &str) -> PathBuf {
+    let mut path = std::env::temp_dir();
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    path.push(format!("{name}_{stamp}"));
+    fs::create_dir_all(&path).expect("create workspace dir");
+    path
+}
+
+fn db_url(dir: &Path) -> String {
+    format!("sqlite://{}", dir.join("dev.sqlite").display())
+}
+
+fn run_fusec(args: &[&str], program_path: &Path, db_url: &str) -> Output {
+    let exe = env!("CARGO_BIN_EXE_fusec");
+    Command::new(exe)
+        .args(args)
+        .arg(program_path)
+        .env("FUSE_DB_URL", db_url)
+        .env_remove("FUSE_DB_POOL_SIZE")
+        .output()
+        .expect("failed to run fusec")
+}
+
+fn scalar_i64(rows: &[HashMap<String, Value>], key: &str) -> i64 {
+    let value = rows.first().and_then(|row| row.get(key));
+    match value {
+        Some(Value::Int(v)) => *v,
+        _ => panic!("expected Int scalar for key {key}, got {value:?}"),
+    }
+}
+
+const PROGRAM: &str = r#"requires db
+
+import Users from "./users.json"
+
+type User:
+  id: String
+  email: String(3..80)
+
+migration "001_users":
+  db.exec("create table users (id text primary key, email text not null)")
+
+seed "001_users":
+  for row in Users:
+    db.from("users").insert(User(id=row["id"], email=row["email"])).exec()
+
+fn user_count() -> Int:
+  let rows = db.query("select count(*) as c from users")
+  return rows[0]["c"]
+
+test "seeded users are present":
+  assert(user_count() == 2)
+
+test "writes are visible within a test":
+  db.from("users").insert(User(id="u3", email="cy@example.com")).exec()
+  assert(user_count() == 3)
+
+test "writes do not leak into later tests":
+  assert(user_count() == 2)
+"#;
+
+fn write_project(dir: &Path, program: &str) -> PathBuf {
+    fs::write(
+        dir.join("users.json"),
+        r#"[{"id": "u1", "email": "ada@example.com"}, {"id": "u2", "email": "bob@example.com"}]"#,
+    )
+    .expect("write users.json");
+    let path = dir.join("main.fuse");
+    fs::write(&path, program).expect("write main.fuse");
+    path
+}
+
+#[test]
+fn seed_applies_once_and_rejects_modified_seeds() {
+    let dir = temp_workspace_dir("fuse_seed_once");
+    let program = write_project(&dir, PROGRAM);
+    let url = db_url(&dir);
+
+    let migrate = run_fusec(&["--migrate"], &program, &url);
+    assert!(
+        migrate.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&migrate.stderr)
+    );
+    let first = run_fusec(&["--seed"], &program, &url);
+    assert!(
+        first.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&first.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&first.stdout), "seeded 001_users\n");
+    let second = run_fusec(&["--seed"], &program, &url);
+    assert!(second.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&second.stdout),
+        "no pending seeds\n"
+    );
+
+    let db = Db::open(&url).expect("open db");
+    let rows = db
+        .query("select count(*) as c from users")
+        .expect("count users");
+    assert_eq!(scalar_i64(&rows, "c"), 2);
+    let rows = db
+        .query("select count(*) as c from __fuse_seeds where name = '001_users'")
+        .expect("count seeds");
+    assert_eq!(scalar_i64(&rows, "c"), 1);
+
+    let edited = PROGRAM.replace(
+        "  for row in Users:\n",
+        "  for row in Users:\n    print(row[\"id\"])\n",
+    );
+    fs::write(&program, edited).expect("rewrite program");
+    let modified = run_fusec(&["--seed"], &program, &url);
+    assert!(!modified.status.success());
+    let stderr = String::from_utf8_lossy(&modified.stderr);
+    assert!(
+        stderr.contains("seed 001_users in package <default> was modified after it was applied"),
+        "stderr: {stderr}"
+    );
+}
+
+#[test]
+fn seed_validation_failure_rolls_back_the_seed() {
+    let dir = temp_workspace_dir("fuse_seed_invalid");
+    let program = PROGRAM.replace(
+        "seed \"001_users\":",
+        "seed \"002_bad_user\":\n  db.from(\"users\").insert(User(id=\"u9\", email=\"x\")).exec()\n\nseed \"001_users\":",
+    );
+    let program = write_project(&dir, &program);
+    let url = db_url(&dir);
+
+    assert!(run_fusec(&["--migrate"], &program, &url).status.success());
+    let output = run_fusec(&["--seed"], &program, &url);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("seed 002_bad_user"), "stderr: {stderr}");
+
+    let db = Db::open(&url).expect("open db");
+    let rows = db
+        .query("select count(*) as c from users where id = 'u9'")
+        .expect("count users");
+    assert_eq!(scalar_i64(&rows, "c"), 0);
+    let rows = db
+        .query("select name from __fuse_seeds order by name")
+        .expect("list seeds");
+    let names: Vec<_> = rows
+        .iter()
+        .filter_map(|row| match row.get("name") {
+            Some(Value::String(name)) => Some(name.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(names, vec!["001_users"]);
+}
+
+#[test]
+fn tests_start_from_migrated_and_seeded_state() {
+    let dir = temp_workspace_dir("fuse_seed_tests");
+    let program = write_project(&dir, PROGRAM);
+    // The configured database is never touched by seeded test runs.
+    let url = db_url(&dir);
+
+    let output = run_fusec(&["--test"], &program, &url);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "stdout: {stdout}\nstderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("ok (3 tests)"), "stdout: {stdout}");
+    assert!(!dir.join("dev.sqlite").exists());
+}
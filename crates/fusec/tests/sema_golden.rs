@@ -1034,3 +1034,133 @@ type Input:
         &["Error: regex() constraint is only supported for string-like refined bases, found Int"],
     );
 }
+
+#[test]
+fn tuple_destructuring_type_checks() {
+    let src = r#"
+type Point:
+  x: Int
+  y: Int
+
+fn split(name: String) -> (String, Int):
+  return (name, 1)
+
+fn main():
+  let (name, count) = split("ada")
+  let Point(x=px, y=_) = Point(x=1, y=2)
+  let total: Int = count + px
+  for key, value in {"a": 1}:
+    let label: String = key
+    let next: Int = value + total
+"#;
+    assert_diags(src, &[]);
+}
+
+#[test]
+fn tuple_pattern_arity_must_match() {
+    let src = r#"
+fn main():
+  let (a, b, c) = (1, "two")
+"#;
+    assert_diags(
+        src,
+        &["Error: tuple pattern with 3 elements does not match (Int, String)"],
+    );
+}
+
+#[test]
+fn let_patterns_must_be_irrefutable() {
+    let src = r#"
+fn main():
+  let (0, name) = (1, "two")
+  let (count, label): (Int, Int) = (1, "two")
+"#;
+    assert_diags(
+        src,
+        &[
+            "Error: let patterns must be irrefutable; use match instead",
+            "Error: type mismatch: expected (Int, Int), found (Int, String)",
+        ],
+    );
+}
+
+#[test]
+fn match_guards_and_or_patterns_are_checked() {
+    let src = r#"
+fn label(value: Int, name: String) -> String:
+  match value:
+    n if n -> "truthy"
+    1 | n -> "one"
+    1..1.5 -> "mixed"
+    5..2 -> "backwards"
+    _ -> "other"
+"#;
+    assert_diags(
+        src,
+        &[
+            "Error: expected Bool condition",
+            "Error: or-pattern alternatives must bind the same names",
+            "Error: range pattern bounds must both be Int or both be Float, found Int and Float",
+            "Error: range pattern start must not exceed its end",
+        ],
+    );
+}
+
+#[test]
+fn consts_and_type_aliases_are_checked() {
+    let src = r#"
+const LIMIT: Int = 3 + 4
+const NAME: String = LIMIT
+const NOW: Int = clock()
+const LOOP: Int = LOOP + 1
+
+type Title = String(1..LIMIT)
+type Cycle = Cycle
+
+fn clock() -> Int:
+  return 1
+
+fn show(title: Title) -> Int:
+  return LIMIT
+"#;
+    assert_diags(
+        src,
+        &[
+            "Error: type mismatch: expected String, found Int",
+            "Error: const NOW must be a compile-time constant expression",
+            "Error: const LOOP must be a compile-time constant expression",
+            "Error: type alias Cycle refers to itself",
+        ],
+    );
+}
+
+#[test]
+fn derivations_and_record_updates_are_checked() {
+    let src = r#"
+type Note:
+  id: Int
+  title: String
+
+type Summary = Note pick id, slug
+type Tagged = Note with title: String
+
+fn rename(note: Note) -> Note:
+  let a = note with title = 1
+  let b = note with slug = "x"
+  let c = note with id = 1, id = 2
+  let n = 3
+  let d = n with id = 1
+  return note
+"#;
+    assert_diags(
+        src,
+        &[
+            "Error: unknown field slug in Note",
+            "Error: field title already exists in Note",
+            "Error: type mismatch: expected String, found Int",
+            "Error: unknown field slug on Note",
+            "Error: duplicate field in record update",
+            "Error: record update requires a struct value, found Int",
+        ],
+    );
+}
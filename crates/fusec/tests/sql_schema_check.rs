@@ -0,0 +1,186 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use fusec::diag::Diag;
+
+fn temp_project_dir(tag: &str) -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    dir.push(format!("fuse_sql_schema_{tag}_{nanos}"));
+    dir
+}
+
+fn analyze(src: &str, tag: &str) -> Vec<Diag> {
+    let dir = temp_project_dir(tag);
+    fs::create_dir_all(&dir).expect("create temp dir");
+    let main_path = dir.join("main.fuse");
+    fs::write(&main_path, src).expect("write main.fuse");
+    let (registry, load_diags) = fusec::load_program_with_modules(&main_path, src);
+    assert!(
+        load_diags.is_empty(),
+        "unexpected loader diagnostics: {load_diags:?}"
+    );
+    let (_analysis, diags) = fusec::sema::analyze_registry(&registry);
+    let _ = fs::remove_dir_all(&dir);
+    diags
+}
+
+fn codes(diags: &[Diag]) -> Vec<(&str, &str)> {
+    diags
+        .iter()
+        .map(|diag| (diag.code.as_deref().unwrap_or(""), diag.message.as_str()))
+        .collect()
+}
+
+const SCHEMA: &str = r#"
+requires db
+
+migration "001_users":
+  db.exec("create table users (id integer primary key, name text not null, age integer)")
+
+migration "002_posts":
+  db.exec("create table posts (id integer primary key, user_id integer, title text)")
+  db.exec("alter table users add column email text")
+"#;
+
+#[test]
+fn raw_sql_is_checked_against_migration_schema() {
+    let src = format!(
+        r#"{SCHEMA}
+fn main():
+  db.exec("insert into users (name, email) values (?, ?)", ["a", "a@example.com"])
+  let missing = db.query("select * from comments")
+  let typo = db.one("select nme from users where id = ?", [1])
+  let short = db.query("select id from users where id = ? and name = ?", [1])
+  db.exec("create table scratch (id integer)")
+  let scratch = db.query("select id from scratch")
+"#
+    );
+    let diags = analyze(&src, "raw");
+    assert_eq!(
+        codes(&diags),
+        vec![
+            (
+                "FUSE_SQL_UNKNOWN_TABLE",
+                "sql error: no such table: comments"
+            ),
+            ("FUSE_SQL_UNKNOWN_COLUMN", "sql error: no such column: nme"),
+            (
+                "FUSE_SQL_PARAM_COUNT",
+                "sql statement expects 2 parameters, got 1"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn query_builder_chains_are_checked_against_migration_schema() {
+    let src = format!(
+        r#"{SCHEMA}
+type UserRow:
+  id: Int
+  name: String
+  age: String
+
+fn main():
+  let ok = db.from("posts").join("users", "posts.user_id", "users.id").select(["posts.title", "users.email"]).all()
+  let bad_table = db.from("userz").count()
+  let bad_column = db.from("users").where("nam", "=", "x").all()
+  let typed = db.from("users").select(["id", "name", "age"]).one<UserRow>()
+"#
+    );
+    let diags = analyze(&src, "builder");
+    assert_eq!(
+        codes(&diags),
+        vec![
+            ("FUSE_SQL_UNKNOWN_TABLE", "sql error: no such table: userz"),
+            ("FUSE_SQL_UNKNOWN_COLUMN", "sql error: no such column: nam"),
+            (
+                "FUSE_SQL_TYPE_MISMATCH",
+                "column age has sql type INTEGER but UserRow.age is String"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn migration_param_count_mismatches_are_reported() {
+    let src = r#"
+requires db
+
+migration "001_seed":
+  db.exec("create table items (id integer, label text)")
+  db.exec("insert into items (id, label) values (?, ?)", [1])
+"#;
+    let diags = analyze(src, "migration_params");
+    assert_eq!(
+        codes(&diags),
+        vec![(
+            "FUSE_SQL_PARAM_COUNT",
+            "sql statement expects 2 parameters, got 1"
+        )]
+    );
+}
+
+#[test]
+fn migration_ddl_through_db_execute_shapes_the_schema() {
+    let src = r#"
+requires db
+
+migration "001_notes":
+  db.execute("create table notes (id integer primary key, title text)")
+
+fn main():
+  let rows = db.query("select title from notes")
+  let typo = db.query("select titel from notes")
+"#;
+    let diags = analyze(src, "migration_execute");
+    assert_eq!(
+        codes(&diags),
+        vec![(
+            "FUSE_SQL_UNKNOWN_COLUMN",
+            "sql error: no such column: titel"
+        )]
+    );
+}
+
+#[test]
+fn sql_checks_are_skipped_without_a_complete_migration_schema() {
+    // No migrations: the schema lives outside the program.
+    let no_migrations = r#"
+requires db
+
+fn main():
+  let rows = db.query("select * from anything")
+"#;
+    assert!(analyze(no_migrations, "no_migrations").is_empty());
+
+    // A migration touching a table it did not create (pre-existing database).
+    let external_table = r#"
+requires db
+
+migration "001_backfill":
+  db.exec("update legacy_items set flag = 1")
+
+fn main():
+  let rows = db.query("select * from anything")
+"#;
+    assert!(analyze(external_table, "external_table").is_empty());
+
+    // Non-literal migration SQL cannot be replayed.
+    let dynamic = r#"
+requires db
+
+migration "001_dynamic":
+  let name = "items"
+  db.exec("create table ${name} (id integer)")
+
+fn main():
+  let rows = db.query("select * from anything")
+"#;
+    assert!(analyze(dynamic, "dynamic").is_empty());
+}
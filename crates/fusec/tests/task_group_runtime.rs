@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use fusec::interp::{Interpreter, Value};
+use fusec::native::{NativeVm, compile_registry};
+
+static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+fn as_string(value: Value) -> String {
+    match value {
+        Value::String(text) => text,
+        Value::ResultOk(inner) => as_string(*inner),
+        other => panic!("expected String, got {other:?}"),
+    }
+}
+
+const SRC: &str = r#"
+requires db
+requires time
+
+type Late:
+  message: String
+
+fn busy(n: Int) -> Int:
+  var total = 0
+  for i in 0..n:
+    for j in 0..999:
+      total = total + 1
+  return total
+
+fn group() -> String:
+  let a = spawn:
+    busy(2)
+  let b = spawn:
+    busy(3)
+  let both = await_all([a, b])
+  return "${both[0]}|${both[1]}"
+
+fn race() -> String:
+  let slow = spawn:
+    busy(100000)
+  let fast = spawn:
+    7
+  let first = await_any([slow, fast])
+  return "${first}"
+
+fn timed_out() -> String:
+  let slow = spawn:
+    busy(100000)
+  match await slow within 30:
+    Ok(value):
+      return "done ${value}"
+    Err(err):
+      return "${err}"
+
+fn in_time() -> String!Late:
+  let task = spawn:
+    5
+  let value = await task within 5000 ?! Late(message="late")
+  return "${value}"
+
+fn too_late() -> String!Late:
+  let task = spawn:
+    busy(100000)
+  let value = await task within 20 ?! Late(message="late")
+  return "${value}"
+
+fn cancelled_stops() -> String:
+  db.exec("create table if not exists ticks (id integer)")
+  db.exec("delete from ticks")
+  let slow = spawn:
+    for i in 0..100000:
+      db.exec("insert into ticks (id) values (1)")
+    0
+  let _ = await slow within 30
+  time.sleep(50)
+  let first = db.query("select count(*) as n from ticks")
+  time.sleep(100)
+  let second = db.query("select count(*) as n from ticks")
+  return "${first[0]["n"] == second[0]["n"]}"
+"#;
+
+fn load() -> fusec::ModuleRegistry {
+    let (registry, diags) =
+        fusec::load_program_with_modules(std::path::Path::new("tasks.fuse"), SRC);
+    assert!(diags.is_empty(), "unexpected load diagnostics: {diags:?}");
+    let (_analysis, sema_diags) = fusec::sema::analyze_registry(&registry);
+    assert!(
+        sema_diags.is_empty(),
+        "unexpected sema diagnostics: {sema_diags:?}"
+    );
+    registry
+}
+
+fn run_both(registry: &fusec::ModuleRegistry, name: &str) -> String {
+    let mut interp = Interpreter::with_registry(registry);
+    let ast = as_string(
+        interp
+            .call_function_with_named_args(name, &HashMap::new())
+            .expect("ast call failed"),
+    );
+    let native = compile_registry(registry).expect("native lowering failed");
+    let mut native_vm = NativeVm::new(&native);
+    let native = as_string(
+        native_vm
+            .call_function(name, vec![])
+            .expect("native call failed"),
+    );
+    assert_eq!(native, ast, "backend mismatch for {name}");
+    ast
+}
+
+fn sema_messages(src: &str) -> Vec<String> {
+    let (registry, diags) =
+        fusec::load_program_with_modules(std::path::Path::new("tasks_bad.fuse"), src);
+    assert!(diags.is_empty(), "unexpected load diagnostics: {diags:?}");
+    let (_analysis, sema_diags) = fusec::sema::analyze_registry(&registry);
+    sema_diags.into_iter().map(|diag| diag.message).collect()
+}
+
+#[test]
+fn await_all_and_await_any_work_across_backends() {
+    let registry = load();
+    assert_eq!(run_both(&registry, "group"), "3000|4000");
+    assert_eq!(run_both(&registry, "race"), "7");
+}
+
+#[test]
+fn await_within_returns_typed_timeout_errors_across_backends() {
+    let registry = load();
+    assert_eq!(
+        run_both(&registry, "timed_out"),
+        "std.Error(task timed out after 30ms)"
+    );
+    assert_eq!(run_both(&registry, "in_time"), "5");
+    let mut interp = Interpreter::with_registry(&registry);
+    let ast = interp
+        .call_function_with_named_args("too_late", &HashMap::new())
+        .expect_err("ast backend should fail the late await");
+    let native = compile_registry(&registry).expect("native lowering failed");
+    let mut native_vm = NativeVm::new(&native);
+    let native = native_vm.call_function("too_late", vec![]);
+    assert!(ast.contains("late"), "unexpected ast error: {ast}");
+    assert!(
+        matches!(&native, Ok(Value::ResultErr(_))),
+        "native backend should fail the late await, got {native:?}"
+    );
+}
+
+#[test]
+fn timed_out_tasks_are_cancelled_across_backends() {
+    let _env_guard = ENV_LOCK.lock().expect("lock env guard");
+    let registry = load();
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let db_path = std::env::temp_dir().join(format!("fuse_tasks_{stamp}.sqlite"));
+    unsafe {
+        std::env::set_var("FUSE_DB_URL", format!("sqlite://{}", db_path.display()));
+    }
+    assert_eq!(run_both(&registry, "cancelled_stops"), "true");
+    let _ = std::fs::remove_file(&db_path);
+}
+
+#[test]
+fn task_groups_count_as_awaiting_their_tasks() {
+    let messages = sema_messages(
+        r#"
+fn main() -> Int:
+  let a = spawn:
+    1
+  let b = spawn:
+    2
+  let first = await_any([a, b])
+  return first
+"#,
+    );
+    assert!(messages.is_empty(), "unexpected sema errors: {messages:?}");
+
+    let messages = sema_messages(
+        r#"
+fn main() -> Int:
+  let a = spawn:
+    1
+  let b = spawn:
+    2
+  let first = await_any([a])
+  return first
+"#,
+    );
+    assert!(
+        messages
+            .iter()
+            .any(|message| message.contains("spawned task `b` is not awaited")),
+        "expected an unawaited task error, got {messages:?}"
+    );
+}
+
+#[test]
+fn sema_rejects_non_int_timeouts() {
+    let messages = sema_messages(
+        r#"
+fn main() -> Int:
+  let task = spawn:
+    1
+  match await task within "soon":
+    Ok(value):
+      return value
+    Err(_):
+      return 0
+"#,
+    );
+    assert!(
+        messages.iter().any(|message| message.contains("Int")),
+        "expected a timeout type error, got {messages:?}"
+    );
+}
@@ -0,0 +1,265 @@
+use std::collections::{BTreeMap, HashMap};
+use std::process::{Command, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use fuse_rt::json::{JsonValue, decode};
+use fusec::interp::{Interpreter, Value};
+use fusec::native::{NativeVm, compile_registry};
+
+mod support;
+use support::http::send_http_request_status_body_with_retry;
+use support::net::{find_free_port, skip_if_loopback_unavailable};
+
+fn as_string(value: Value) -> String {
+    match value {
+        Value::String(text) => text,
+        Value::ResultOk(inner) => as_string(*inner),
+        other => panic!("expected String, got {other:?}"),
+    }
+}
+
+const SRC: &str = r#"
+requires time
+
+type TimeError:
+  message: String
+
+type Booking:
+  day: Date("2020-01-01".."2030-12-31")
+  length: Duration("PT15M".."PT8H")
+
+fn ends_at(start: Instant, length: Duration) -> Instant:
+  return start + length
+
+fn main() -> String!TimeError:
+  let start = time.parse_instant("2024-02-28T23:30:00Z") ?! TimeError(message="bad time")
+  let length = time.hours(1) + time.minutes(15)
+  let end = ends_at(start, length)
+  let gap = end - start
+  let day = time.date_of(end)
+  let later = day + 2
+  let zoned = time.in_zone(end, "+05:30") ?! TimeError(message="bad time")
+  var order = "after"
+  if start < end:
+    order = "before"
+  return "${time.format_instant(end)}|${time.format_duration(gap)}|${time.format_date(later)}|${later - day}|${time.format_datetime(zoned)}|${time.format_date(time.local_date(zoned))}|${order}|${gap / time.minutes(5)}"
+
+fn calendar() -> String!TimeError:
+  let day = time.date(2024, 2, 29) ?! TimeError(message="bad time")
+  let start = time.start_of(day)
+  let span = time.parse_duration("P1W2DT3.5S") ?! TimeError(message="bad time")
+  return "${time.unix_ms(start)}|${time.to_millis(span)}|${time.format_instant(start - time.days(1))}"
+
+fn bad_date() -> String:
+  match time.date(2023, 2, 29):
+    Ok(day):
+      return time.format_date(day)
+    Err(_):
+      return "invalid"
+
+fn booking_ok() -> String:
+  let day = time.date_of(time.from_unix_ms(1714521600000))
+  let booking = Booking(day=day, length=time.minutes(30))
+  return time.format_date(booking.day)
+
+fn booking_out_of_range() -> String:
+  let day = time.date_of(time.from_unix_ms(1924992000000))
+  let booking = Booking(day=day, length=time.minutes(30))
+  return time.format_date(booking.day)
+"#;
+
+fn load() -> fusec::ModuleRegistry {
+    let (registry, diags) =
+        fusec::load_program_with_modules(std::path::Path::new("time.fuse"), SRC);
+    assert!(diags.is_empty(), "unexpected load diagnostics: {diags:?}");
+    let (_analysis, sema_diags) = fusec::sema::analyze_registry(&registry);
+    assert!(
+        sema_diags.is_empty(),
+        "unexpected sema diagnostics: {sema_diags:?}"
+    );
+    registry
+}
+
+fn run_both(registry: &fusec::ModuleRegistry, name: &str) -> String {
+    let mut interp = Interpreter::with_registry(registry);
+    let ast = as_string(
+        interp
+            .call_function_with_named_args(name, &HashMap::new())
+            .expect("ast call failed"),
+    );
+    let native = compile_registry(registry).expect("native lowering failed");
+    let mut native_vm = NativeVm::new(&native);
+    let native = as_string(
+        native_vm
+            .call_function(name, vec![])
+            .expect("native call failed"),
+    );
+    assert_eq!(native, ast, "backend mismatch for {name}");
+    ast
+}
+
+#[test]
+fn time_arithmetic_and_formatting_match_across_backends() {
+    let registry = load();
+    assert_eq!(
+        run_both(&registry, "main"),
+        "2024-02-29T00:45:00Z|PT1H15M|2024-03-02|2|2024-02-29T06:15:00+05:30|2024-02-29|before|15"
+    );
+    assert_eq!(
+        run_both(&registry, "calendar"),
+        "1709164800000|777603500|2024-02-28T00:00:00Z"
+    );
+}
+
+#[test]
+fn fallible_time_helpers_return_errors() {
+    let registry = load();
+    assert_eq!(run_both(&registry, "bad_date"), "invalid");
+}
+
+#[test]
+fn time_refinement_ranges_are_enforced() {
+    let registry = load();
+    assert_eq!(run_both(&registry, "booking_ok"), "2024-05-01");
+    let mut interp = Interpreter::with_registry(&registry);
+    assert!(
+        interp
+            .call_function_with_named_args("booking_out_of_range", &HashMap::new())
+            .is_err(),
+        "ast backend should reject a day outside the range"
+    );
+    let native = compile_registry(&registry).expect("native lowering failed");
+    let mut native_vm = NativeVm::new(&native);
+    assert!(
+        native_vm
+            .call_function("booking_out_of_range", vec![])
+            .is_err(),
+        "native backend should reject a day outside the range"
+    );
+}
+
+#[test]
+fn sema_rejects_mismatched_time_operands() {
+    let src = r#"
+requires time
+
+fn main(a: Instant, b: Instant, d: Date) -> Int:
+  let sum = a + b
+  let mixed = d - a
+  return time.unix_ms(sum)
+"#;
+    let (registry, diags) =
+        fusec::load_program_with_modules(std::path::Path::new("time_bad.fuse"), src);
+    assert!(diags.is_empty(), "unexpected load diagnostics: {diags:?}");
+    let (_analysis, sema_diags) = fusec::sema::analyze_registry(&registry);
+    assert!(
+        sema_diags.len() >= 2,
+        "expected operand errors, got {sema_diags:?}"
+    );
+}
+
+const SERVICE_SRC: &str = r#"
+requires network
+requires time
+
+config App:
+  port: Int = env_int("PORT") ?? 3000
+
+type Slot:
+  day: Date
+  starts: Instant
+  length: Duration
+  local: DateTime
+
+service Slots at "/api":
+  post "/slots" body Slot -> Slot:
+    return body with length = body.length + time.minutes(15)
+
+app "slots":
+  serve(App.port)
+"#;
+
+fn get_object<'a>(value: &'a JsonValue, path: &str) -> &'a BTreeMap<String, JsonValue> {
+    let JsonValue::Object(map) = value else {
+        panic!("{path}: expected object, got {value:?}");
+    };
+    map
+}
+
+#[test]
+fn openapi_describes_time_types_as_formatted_strings() {
+    let (registry, diags) =
+        fusec::load_program_with_modules(std::path::Path::new("slots.fuse"), SERVICE_SRC);
+    assert!(diags.is_empty(), "unexpected diagnostics: {diags:?}");
+    let openapi_json =
+        fusec::openapi::generate_openapi(&registry).expect("openapi generation failed");
+    let doc = decode(&openapi_json).expect("failed to decode openapi json");
+    let root = get_object(&doc, "root");
+    let components = get_object(root.get("components").expect("components"), "components");
+    let schemas = get_object(components.get("schemas").expect("schemas"), "schemas");
+    let slot = schemas
+        .iter()
+        .find(|(key, _)| key.ends_with("Slot"))
+        .map(|(_, schema)| get_object(schema, "Slot"))
+        .expect("Slot schema");
+    let props = get_object(slot.get("properties").expect("properties"), "properties");
+    for (field, format) in [
+        ("day", "date"),
+        ("starts", "date-time"),
+        ("length", "duration"),
+        ("local", "date-time"),
+    ] {
+        let schema = get_object(props.get(field).expect(field), field);
+        assert_eq!(
+            schema.get("type"),
+            Some(&JsonValue::String("string".into()))
+        );
+        assert_eq!(
+            schema.get("format"),
+            Some(&JsonValue::String(format.into())),
+            "{field}"
+        );
+    }
+}
+
+#[test]
+fn http_bodies_use_rfc3339_and_iso8601_text_across_backends() {
+    if skip_if_loopback_unavailable("http_bodies_use_rfc3339_and_iso8601_text_across_backends") {
+        return;
+    }
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let path = std::env::temp_dir().join(format!("fuse_time_http_{stamp}.fuse"));
+    std::fs::write(&path, SERVICE_SRC).expect("failed to write program");
+    let body = r#"{"day":"2024-05-01","starts":"2024-05-01T09:00:00+02:00","length":"PT45M","local":"2024-05-01T09:00:00+02:00"}"#;
+    for backend in ["ast", "native"] {
+        let port = find_free_port();
+        let mut child = Command::new(env!("CARGO_BIN_EXE_fusec"))
+            .arg("--run")
+            .arg("--backend")
+            .arg(backend)
+            .arg(&path)
+            .env("PORT", port.to_string())
+            .env("FUSE_MAX_REQUESTS", "1")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("failed to start server");
+        let request = format!(
+            "POST /api/slots HTTP/1.1\r\nHost: 127.0.0.1:{port}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        let (status, response) = send_http_request_status_body_with_retry(port, &request);
+        let _ = child.wait();
+        assert_eq!(status, 200, "backend={backend} body={response}");
+        assert_eq!(
+            response,
+            r#"{"day":"2024-05-01","length":"PT1H","local":"2024-05-01T09:00:00+02:00","starts":"2024-05-01T07:00:00Z"}"#,
+            "backend={backend}"
+        );
+    }
+    let _ = std::fs::remove_file(&path);
+}
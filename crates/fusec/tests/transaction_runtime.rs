@@ -137,3 +137,98 @@ fn transaction_commits_and_rolls_back_in_native_backend() {
     assert_eq!(row_count(&db_url), 1);
     let _ = fs::remove_dir_all(&dir);
 }
+
+fn item_ids(db_url: &str) -> Vec<i64> {
+    let db = Db::open_with_pool(db_url, 1).expect("open db");
+    let rows = db
+        .query("select id from items order by id")
+        .expect("query item ids");
+    rows.iter()
+        .map(|row| match row.get("id") {
+            Some(fusec::interp::Value::Int(v)) => *v,
+            other => panic!("expected Int id, got {other:?}"),
+        })
+        .collect()
+}
+
+fn nested_program_source() -> &'static str {
+    r#"
+requires db
+
+fn reset():
+  db.exec("create table if not exists items (id integer)")
+  db.exec("delete from items")
+
+fn add_item(id: Int):
+  transaction:
+    db.exec("insert into items (id) values (?)", [id])
+
+fn tx_nested():
+  transaction:
+    add_item(10)
+    transaction:
+      db.exec("insert into items (id) values (11)")
+      db.rollback()
+    add_item(12)
+
+fn tx_discard():
+  transaction:
+    add_item(20)
+    db.rollback()
+  add_item(21)
+
+fn tx_nested_failure():
+  transaction:
+    add_item(30)
+    transaction:
+      db.exec("insert into items (id) values (31)")
+      assert(false, "boom")
+"#
+}
+
+#[test]
+fn nested_transactions_use_savepoints_in_ast_and_native_backends() {
+    let _env_guard = ENV_LOCK.lock().expect("lock env guard");
+    let dir = temp_project_dir("nested");
+    fs::create_dir_all(&dir).expect("create temp dir");
+    let main_path = dir.join("main.fuse");
+    write_file(&main_path, nested_program_source());
+    let src = fs::read_to_string(&main_path).expect("read root source");
+    let (registry, diags) = fusec::load_program_with_modules(&main_path, &src);
+    assert!(diags.is_empty(), "unexpected diagnostics: {diags:?}");
+    let (_analysis, sema_diags) = fusec::sema::analyze_registry(&registry);
+    assert!(
+        sema_diags.is_empty(),
+        "unexpected sema diagnostics: {sema_diags:?}"
+    );
+
+    let db_url = temp_db_url("nested_ast");
+    unsafe {
+        std::env::set_var("FUSE_DB_URL", &db_url);
+    }
+    let mut interp = Interpreter::with_registry(&registry);
+    for name in ["reset", "tx_nested", "tx_discard"] {
+        interp
+            .call_function_with_named_args(name, &HashMap::new())
+            .unwrap_or_else(|err| panic!("{name} failed: {err}"));
+    }
+    let err = interp.call_function_with_named_args("tx_nested_failure", &HashMap::new());
+    assert!(err.is_err(), "expected tx_nested_failure to fail");
+    assert_eq!(item_ids(&db_url), vec![10, 12, 21]);
+
+    let db_url = temp_db_url("nested_native");
+    unsafe {
+        std::env::set_var("FUSE_DB_URL", &db_url);
+    }
+    let native = compile_registry(&registry).expect("native lowering failed");
+    let mut vm = NativeVm::new(&native);
+    for name in ["reset", "tx_nested", "tx_discard"] {
+        vm.call_function(name, vec![])
+            .unwrap_or_else(|err| panic!("{name} failed: {err}"));
+    }
+    let err = vm.call_function("tx_nested_failure", vec![]);
+    assert!(err.is_err(), "expected tx_nested_failure to fail");
+    assert_eq!(item_ids(&db_url), vec![10, 12, 21]);
+
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use fusec::interp::{Interpreter, Value};
+use fusec::native::{NativeVm, compile_registry};
+
+fn temp_project_dir(tag: &str) -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    dir.push(format!("fuse_tuple_runtime_{tag}_{nanos}"));
+    dir
+}
+
+fn write_file(path: &Path, contents: &str) {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).expect("create parent directories");
+    }
+    fs::write(path, contents).expect("write source file");
+}
+
+fn as_string(value: Value) -> String {
+    match value {
+        Value::String(text) => text,
+        other => panic!("expected String, got {other:?}"),
+    }
+}
+
+/// Runs `main` in both backends and returns the (ast, native) results.
+fn run_main(tag: &str, src: &str) -> (String, String) {
+    let dir = temp_project_dir(tag);
+    let main_path = dir.join("main.fuse");
+    write_file(&main_path, src);
+
+    let (registry, diags) = fusec::load_program_with_modules(&main_path, src);
+    assert!(diags.is_empty(), "unexpected load diagnostics: {diags:?}");
+    let (_analysis, sema_diags) = fusec::sema::analyze_registry(&registry);
+    assert!(
+        sema_diags.is_empty(),
+        "unexpected sema diagnostics: {sema_diags:?}"
+    );
+
+    let mut interp = Interpreter::with_registry(&registry);
+    let ast_value = interp
+        .call_function_with_named_args("main", &HashMap::new())
+        .expect("ast call failed");
+
+    let native = compile_registry(&registry).expect("native lowering failed");
+    let mut native_vm = NativeVm::new(&native);
+    let native_value = native_vm
+        .call_function("main", vec![])
+        .expect("native call failed");
+
+    let _ = fs::remove_dir_all(&dir);
+    (as_string(ast_value), as_string(native_value))
+}
+
+// ---------------------------------------------------------------------------
+// Test 1: tuple returns and let/var destructuring
+// ---------------------------------------------------------------------------
+
+#[test]
+fn tuple_let_destructuring_runs_in_ast_and_native_backends() {
+    let (ast, native) = run_main(
+        "let",
+        r#"
+type Point:
+  x: Int
+  y: Int
+
+fn split(name: String) -> (String, Int):
+  return (name, 2)
+
+fn main() -> String:
+  let (label, count): (String, Int) = split("ada")
+  var (lo, hi) = (1, 10)
+  lo = lo + count
+  let Point(x=px, y=_) = Point(x=7, y=8)
+  let (_, (inner, _)) = (0, ("deep", 1))
+  return "${label}:${lo}:${hi}:${px}:${inner}"
+"#,
+    );
+    assert_eq!(ast, "ada:3:10:7:deep");
+    assert_eq!(native, ast);
+}
+
+// ---------------------------------------------------------------------------
+// Test 2: destructuring in for loops, including map entries
+// ---------------------------------------------------------------------------
+
+#[test]
+fn tuple_for_destructuring_runs_in_ast_and_native_backends() {
+    let (ast, native) = run_main(
+        "for",
+        r#"
+fn main() -> String:
+  var names = ""
+  for (name, age) in [("ada", 36), ("alan", 41)]:
+    names = names + "${name}=${age};"
+  let ages = {"ada": 36, "alan": 41}
+  var total = 0
+  var keys = 0
+  for key, value in ages:
+    total = total + value
+    keys = keys + 1
+  return "${names}${total}:${keys}"
+"#,
+    );
+    assert_eq!(ast, "ada=36;alan=41;77:2");
+    assert_eq!(native, ast);
+}
+
+// ---------------------------------------------------------------------------
+// Test 3: tuple and nested patterns in match
+// ---------------------------------------------------------------------------
+
+#[test]
+fn tuple_match_patterns_run_in_ast_and_native_backends() {
+    let (ast, native) = run_main(
+        "match",
+        r#"
+fn describe(pair: (Int, Option<String>)) -> String:
+  match pair:
+    (0, _) -> "zero"
+    (n, Some(name)) -> "${name}#${n}"
+    (n, None) -> "anon#${n}"
+
+fn main() -> String:
+  let zero = describe((0, "x"))
+  let named = describe((2, "ada"))
+  let other = describe((3, null))
+  return "${zero}|${named}|${other}"
+"#,
+    );
+    assert_eq!(ast, "zero|ada#2|anon#3");
+    assert_eq!(native, ast);
+}